@@ -0,0 +1,81 @@
+//! Intersecting tags when one is rare: every set read vs smallest first
+//!
+//! Run with `cargo bench --bench all_tags`.
+
+use std::collections::HashSet;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tagr::db::Database;
+
+const FILES: usize = 10_000;
+const ITERATIONS: u32 = 200;
+
+fn time(mut find: impl FnMut() -> usize) -> Duration {
+    let started = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(find());
+    }
+    started.elapsed() / ITERATIONS
+}
+
+/// Read every tag's files, then intersect them in the order given
+fn intersect_all(db: &Database, tags: &[String]) -> usize {
+    let sets: Vec<HashSet<PathBuf>> = tags
+        .iter()
+        .map(|tag| {
+            db.find_by_tag(tag)
+                .expect("find files")
+                .into_iter()
+                .collect()
+        })
+        .collect();
+    sets[0]
+        .iter()
+        .filter(|file| sets[1..].iter().all(|set| set.contains(*file)))
+        .count()
+}
+
+fn main() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let files = dir.path().join("files");
+    std::fs::create_dir(&files).expect("create files dir");
+    let db = Database::open(dir.path().join("db")).expect("open database");
+
+    for i in 0..FILES {
+        let file = files.join(format!("file_{i:05}.txt"));
+        std::fs::write(&file, "").expect("write file");
+        let mut tags = vec!["common".to_string(), "shared".to_string()];
+        if i % 1_000 == 0 {
+            tags.push("rare".into());
+        }
+        db.insert(&file, tags).expect("insert file");
+    }
+
+    let searches: [(&str, Vec<String>); 2] = [
+        (
+            "rare tag",
+            vec!["common".into(), "shared".into(), "rare".into()],
+        ),
+        (
+            "unused tag",
+            vec!["common".into(), "shared".into(), "unused".into()],
+        ),
+    ];
+    println!("{FILES} files, mean of {ITERATIONS} runs:");
+    for (name, tags) in &searches {
+        let naive = time(|| intersect_all(&db, tags));
+        let ordered = time(|| db.find_by_all_tags(tags).expect("find files").len());
+        assert_eq!(
+            intersect_all(&db, tags),
+            db.find_by_all_tags(tags).unwrap().len()
+        );
+        println!("  {name}:");
+        println!("    every set, in order given {naive:?}");
+        println!("    find_by_all_tags()        {ordered:?}");
+        println!(
+            "    speedup                   {:.0}x",
+            naive.as_secs_f64() / ordered.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Counting a tag's files: full index decode vs length prefix only
+//!
+//! Run with `cargo bench --bench tag_count`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use tagr::db::Database;
+
+const FILES: usize = 10_000;
+const ITERATIONS: u32 = 1_000;
+
+fn time(mut count: impl FnMut() -> usize) -> Duration {
+    let started = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(count());
+    }
+    started.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let files = dir.path().join("files");
+    std::fs::create_dir(&files).expect("create files dir");
+    let db = Database::open(dir.path().join("db")).expect("open database");
+
+    for i in 0..FILES {
+        let file = files.join(format!("file_{i:05}.txt"));
+        std::fs::write(&file, "").expect("write file");
+        db.insert(&file, vec!["shared".into()])
+            .expect("insert file");
+    }
+
+    let full = time(|| db.find_by_tag("shared").expect("find files").len());
+    let fast = time(|| db.count_files_per_tag_fast("shared").expect("count files"));
+
+    println!("{FILES} files under one tag, mean of {ITERATIONS} runs:");
+    println!("  find_by_tag().len()        {full:?}");
+    println!("  count_files_per_tag_fast() {fast:?}");
+    println!(
+        "  speedup                    {:.0}x",
+        full.as_secs_f64() / fast.as_secs_f64()
+    );
+}
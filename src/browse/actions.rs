@@ -325,16 +325,34 @@ pub fn execute_copy_path(files: &[PathBuf]) -> Result<ActionOutcome, String> {
         .collect::<Vec<_>>()
         .join("\n");
 
-    match arboard::Clipboard::new() {
-        Ok(mut clipboard) => match clipboard.set_text(&paths_text) {
-            Ok(()) => Ok(ActionOutcome::Success {
-                affected_count: files.len(),
-                details: "Copied paths to clipboard".to_string(),
-            }),
-            Err(e) => Err(format!("Clipboard error: {e}")),
-        },
-        Err(e) => Err(format!("Clipboard unavailable: {e}")),
-    }
+    copy_to_clipboard(&paths_text)?;
+    Ok(ActionOutcome::Success {
+        affected_count: files.len(),
+        details: "Copied paths to clipboard".to_string(),
+    })
+}
+
+/// Copy text to the system clipboard
+///
+/// # Errors
+/// Returns an error string if no clipboard is available (e.g. over SSH
+/// without X forwarding) or tagr was built without the `clipboard` feature.
+#[cfg(feature = "clipboard")]
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| format!("Clipboard unavailable: {e}"))?;
+    clipboard
+        .set_text(text)
+        .map_err(|e| format!("Clipboard error: {e}"))
+}
+
+/// Copy text to the system clipboard
+///
+/// # Errors
+/// Always fails: tagr was built without the `clipboard` feature.
+#[cfg(not(feature = "clipboard"))]
+pub fn copy_to_clipboard(_text: &str) -> Result<(), String> {
+    Err("Clipboard unavailable: built without the `clipboard` feature".to_string())
 }
 
 /// Execute file copying to directory (pure business logic)
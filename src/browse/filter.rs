@@ -29,11 +29,14 @@ impl ActiveFilter {
                 file_patterns: Vec::new(),
                 file_mode: FileMode::Any,
                 excludes: Vec::new(),
+                exclude_file_patterns: vec![],
                 regex_tag: false,
                 regex_file: false,
                 glob_files: false,
                 virtual_tags: Vec::new(),
                 virtual_mode: TagMode::All,
+                exclude_virtual_tags: Vec::new(),
+                expression: None,
             },
         }
     }
@@ -138,9 +141,11 @@ impl ActiveFilter {
     #[must_use]
     pub const fn is_empty(&self) -> bool {
         self.criteria.tags.is_empty()
+            && self.criteria.expression.is_none()
             && self.criteria.excludes.is_empty()
             && self.criteria.file_patterns.is_empty()
             && self.criteria.virtual_tags.is_empty()
+            && self.criteria.exclude_virtual_tags.is_empty()
     }
 
     /// Add a file pattern
@@ -199,14 +204,18 @@ impl fmt::Display for ActiveFilter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "tagr search")?;
 
-        // Include tags
-        for tag in &self.criteria.tags {
-            write!(f, " -t ")?;
-            // Quote tags with spaces or special chars
-            if needs_quoting(tag) {
-                write!(f, "\"{tag}\"")?;
-            } else {
-                write!(f, "{tag}")?;
+        // Include tags, or the expression that supersedes them
+        if let Some(expr) = &self.criteria.expression {
+            write!(f, " --expr \"{expr}\"")?;
+        } else {
+            for tag in &self.criteria.tags {
+                write!(f, " -t ")?;
+                // Quote tags with spaces or special chars
+                if needs_quoting(tag) {
+                    write!(f, "\"{tag}\"")?;
+                } else {
+                    write!(f, "{tag}")?;
+                }
             }
         }
 
@@ -241,7 +250,7 @@ impl fmt::Display for ActiveFilter {
         }
 
         // Tag mode (only show if multiple include tags)
-        if self.criteria.tags.len() > 1 {
+        if self.criteria.tags.len() > 1 && self.criteria.expression.is_none() {
             match self.criteria.tag_mode {
                 TagMode::Any => write!(f, " --any-tag")?,
                 TagMode::All => write!(f, " --all-tags")?,
@@ -112,8 +112,8 @@ pub mod session;
 pub mod ui;
 
 pub use actions::{
-    execute_add_tag, execute_copy_files, execute_copy_path, execute_delete_from_db,
-    execute_open_in_default, execute_open_in_editor, execute_remove_tag,
+    copy_to_clipboard, execute_add_tag, execute_copy_files, execute_copy_path,
+    execute_delete_from_db, execute_open_in_default, execute_open_in_editor, execute_remove_tag,
 };
 pub use filter::ActiveFilter;
 pub use models::{
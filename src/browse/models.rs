@@ -241,6 +241,8 @@ pub struct SearchCriteriaData {
     pub file_patterns: Vec<String>,
     /// Current virtual tags
     pub virtual_tags: Vec<String>,
+    /// Current grouped expression, in query syntax
+    pub expression: Option<String>,
 }
 
 // ============================================================================
@@ -154,6 +154,7 @@ pub fn get_available_tags(db: &Database) -> Result<Vec<TagrItem>, DbError> {
 /// # Arguments
 /// * `db` - Database to query
 /// * `params` - Search parameters specifying filters
+/// * `exclude_dirs` - Directory patterns whose files are left out
 ///
 /// # Returns
 /// Vector of `TagrItem` instances representing files, with tags and metadata
@@ -168,13 +169,19 @@ pub fn get_available_tags(db: &Database) -> Result<Vec<TagrItem>, DbError> {
 ///     tag_mode: SearchMode::Any,
 ///     ..Default::default()
 /// };
-/// let files = get_matching_files(&db, &params)?;
+/// let files = get_matching_files(&db, &params, &[])?;
 /// ```
-pub fn get_matching_files(db: &Database, params: &SearchParams) -> Result<Vec<TagrItem>, DbError> {
+pub fn get_matching_files(
+    db: &Database,
+    params: &SearchParams,
+    exclude_dirs: &[String],
+) -> Result<Vec<TagrItem>, DbError> {
     let file_paths = crate::db::query::apply_search_params(db, params)?;
+    let dir_patterns = crate::search::filter::dir_patterns(exclude_dirs)?;
 
     let items: Result<Vec<TagrItem>, DbError> = file_paths
         .into_iter()
+        .filter(|path| !crate::search::filter::in_excluded_dir(path, &dir_patterns))
         .map(|path| {
             let tags = db.get_tags(&path)?.unwrap_or_default();
             let pair = crate::Pair { file: path, tags };
@@ -215,17 +222,22 @@ pub fn get_files_by_tags(
         tags: tags.to_vec(),
         tag_mode: mode.into(),
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: crate::cli::SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: crate::cli::SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
 
-    get_matching_files(db, &params)
+    get_matching_files(db, &params, &[])
 }
 
 /// Filter an existing collection of items in-memory using search parameters
@@ -366,17 +378,22 @@ mod tests {
             tags: vec!["rust".to_string()],
             tag_mode: crate::cli::SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: crate::cli::SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: crate::cli::SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
-        let files = get_matching_files(db, &params).unwrap();
+        let files = get_matching_files(db, &params, &[]).unwrap();
         assert_eq!(files.len(), 2);
 
         for item in &files {
@@ -479,17 +496,22 @@ mod tests {
             tags: vec!["rust".to_string()],
             tag_mode: crate::cli::SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: crate::cli::SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: crate::cli::SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
-        let files = get_matching_files(db, &params).unwrap();
+        let files = get_matching_files(db, &params, &[]).unwrap();
         assert_eq!(files.len(), 0);
     }
 
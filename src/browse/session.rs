@@ -31,7 +31,7 @@
 use crate::browse::models::{ActionOutcome, SearchMode, TagrItem};
 use crate::browse::{actions, query};
 use crate::cli::SearchParams;
-use crate::config::PreviewConfig;
+use crate::config::{PreviewConfig, TagNormalization};
 use crate::db::Database;
 use crate::keybinds::actions::BrowseAction;
 use crate::keybinds::config::KeybindConfig;
@@ -91,6 +91,24 @@ pub struct BrowseConfig {
 
     /// File selection phase settings
     pub file_phase_settings: PhaseSettings,
+
+    /// File used to persist TUI state between sessions (None = disabled)
+    pub tui_state_file: Option<PathBuf>,
+
+    /// Restore saved TUI state on startup
+    pub restore_tui_state: bool,
+
+    /// Directory patterns excluded from file results
+    pub exclude_dirs: Vec<String>,
+
+    /// Tags pre-selected in the tag tree when the browser first opens
+    pub start_tags: Vec<String>,
+
+    /// Cleanup applied to tags entered in the add-tag action
+    pub tag_normalization: TagNormalization,
+
+    /// Most files loaded into the file pane at once (0 = no limit)
+    pub max_items: usize,
 }
 
 /// Path display format options
@@ -104,6 +122,9 @@ pub enum PathFormat {
 
     /// Just the filename
     Basename,
+
+    /// The last N path components
+    LastN(usize),
 }
 
 /// Configuration for a specific browser phase
@@ -169,7 +190,7 @@ impl<'a> BrowseSession<'a> {
     /// Returns error if database queries fail
     pub fn new(db: &'a Database, config: BrowseConfig) -> Result<Self> {
         let current_phase = if let Some(ref search_params) = config.initial_search {
-            let items = query::get_matching_files(db, search_params)?;
+            let items = query::get_matching_files(db, search_params, &config.exclude_dirs)?;
 
             BrowserPhase {
                 phase_type: PhaseType::FileSelection {
@@ -389,6 +410,7 @@ impl<'a> BrowseSession<'a> {
                         exclude_tags: vec![],
                         file_patterns: vec![],
                         virtual_tags: vec![],
+                        expression: None,
                     }
                 } else {
                     crate::browse::models::SearchCriteriaData {
@@ -396,6 +418,7 @@ impl<'a> BrowseSession<'a> {
                         exclude_tags: vec![],
                         file_patterns: vec![],
                         virtual_tags: vec![],
+                        expression: None,
                     }
                 }
             },
@@ -404,6 +427,7 @@ impl<'a> BrowseSession<'a> {
                 exclude_tags: params.exclude_tags.clone(),
                 file_patterns: params.file_patterns.clone(),
                 virtual_tags: params.virtual_tags.clone(),
+                expression: params.expression.as_ref().map(ToString::to_string),
             },
         )
     }
@@ -455,7 +479,7 @@ impl<'a> BrowseSession<'a> {
         // Decision: in-memory vs DB query
         if filters_relaxed || self.base_items.is_none() {
             // Re-query database (filter relaxation or first refinement)
-            let items = query::get_matching_files(self.db, &new_params)?;
+            let items = query::get_matching_files(self.db, &new_params, &self.config.exclude_dirs)?;
 
             // Cache for in-memory filtering if small enough
             if items.len() < HYBRID_FILTER_THRESHOLD {
@@ -485,7 +509,7 @@ impl<'a> BrowseSession<'a> {
             };
         } else {
             // Fallback: re-query (should not happen, but defensive)
-            let items = query::get_matching_files(self.db, &new_params)?;
+            let items = query::get_matching_files(self.db, &new_params, &self.config.exclude_dirs)?;
 
             self.current_phase = BrowserPhase {
                 phase_type: PhaseType::FileSelection {
@@ -588,6 +612,12 @@ impl Default for BrowseConfig {
             path_format: PathFormat::Absolute,
             tag_phase_settings: PhaseSettings::default_for_tags(),
             file_phase_settings: PhaseSettings::default_for_files(),
+            tui_state_file: None,
+            restore_tui_state: false,
+            exclude_dirs: Vec::new(),
+            start_tags: Vec::new(),
+            tag_normalization: TagNormalization::default(),
+            max_items: 0,
         }
     }
 }
@@ -644,7 +674,8 @@ impl PhaseSettings {
 /// - Reducing number of include tags in ALL mode
 /// - Removing file patterns
 /// - Removing virtual tag constraints
-const fn is_filter_relaxation(old: &SearchParams, new: &SearchParams) -> bool {
+/// - Changing the grouped expression
+fn is_filter_relaxation(old: &SearchParams, new: &SearchParams) -> bool {
     // Exclude tags reduced
     if new.exclude_tags.len() < old.exclude_tags.len() {
         return true;
@@ -665,6 +696,11 @@ const fn is_filter_relaxation(old: &SearchParams, new: &SearchParams) -> bool {
         return true;
     }
 
+    // Expression changed (may match files the cached set excluded)
+    if old.expression != new.expression {
+        return true;
+    }
+
     // Mode changed from ALL to ANY (less restrictive)
     if matches!(old.tag_mode, crate::cli::SearchMode::All)
         && matches!(new.tag_mode, crate::cli::SearchMode::Any)
@@ -702,14 +738,19 @@ mod tests {
                 tags: vec!["rust".to_string()],
                 tag_mode: crate::cli::SearchMode::Any,
                 file_patterns: vec![],
+                file_only_patterns: vec![],
                 file_mode: crate::cli::SearchMode::All,
                 exclude_tags: vec![],
+                exclude_file_patterns: vec![],
                 regex_tag: false,
                 regex_file: false,
                 glob_files: false,
                 virtual_tags: vec![],
                 virtual_mode: crate::cli::SearchMode::All,
+                exclude_virtual_tags: vec![],
                 no_hierarchy: false,
+                expression: None,
+                no_index: false,
             }),
             ..Default::default()
         };
@@ -773,14 +814,19 @@ mod tests {
                 tags: vec!["rust".to_string()],
                 tag_mode: crate::cli::SearchMode::Any,
                 file_patterns: vec![],
+                file_only_patterns: vec![],
                 file_mode: crate::cli::SearchMode::All,
                 exclude_tags: vec![],
+                exclude_file_patterns: vec![],
                 regex_tag: false,
                 regex_file: false,
                 glob_files: false,
                 virtual_tags: vec![],
                 virtual_mode: crate::cli::SearchMode::All,
+                exclude_virtual_tags: vec![],
                 no_hierarchy: false,
+                expression: None,
+                no_index: false,
             }),
             ..Default::default()
         };
@@ -794,14 +840,19 @@ mod tests {
             tags: vec!["rust".to_string()],
             tag_mode: crate::cli::SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: crate::cli::SearchMode::All,
             exclude_tags: vec!["docs".to_string()],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: crate::cli::SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         session.update_search_params(new_params).unwrap();
@@ -828,14 +879,19 @@ mod tests {
                 tags: vec!["test".to_string()],
                 tag_mode: crate::cli::SearchMode::Any,
                 file_patterns: vec![],
+                file_only_patterns: vec![],
                 file_mode: crate::cli::SearchMode::All,
                 exclude_tags: vec![],
+                exclude_file_patterns: vec![],
                 regex_tag: false,
                 regex_file: false,
                 glob_files: false,
                 virtual_tags: vec![],
                 virtual_mode: crate::cli::SearchMode::All,
+                exclude_virtual_tags: vec![],
                 no_hierarchy: false,
+                expression: None,
+                no_index: false,
             }),
             ..Default::default()
         };
@@ -870,14 +926,19 @@ mod tests {
             tags: vec!["rust".into()],
             tag_mode: crate::cli::SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: crate::cli::SearchMode::All,
             exclude_tags: vec!["python".into(), "js".into()],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: crate::cli::SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let new = SearchParams {
@@ -895,14 +956,19 @@ mod tests {
             tags: vec!["rust".into(), "web".into()],
             tag_mode: crate::cli::SearchMode::All,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: crate::cli::SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: crate::cli::SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let new = SearchParams {
@@ -920,14 +986,19 @@ mod tests {
             tags: vec!["rust".into(), "web".into(), "backend".into()],
             tag_mode: crate::cli::SearchMode::All,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: crate::cli::SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: crate::cli::SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let new = SearchParams {
@@ -945,14 +1016,19 @@ mod tests {
             tags: vec!["rust".into()],
             tag_mode: crate::cli::SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: crate::cli::SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: crate::cli::SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let new = SearchParams {
@@ -990,14 +1066,19 @@ mod tests {
                 tags: vec!["rust".to_string()],
                 tag_mode: crate::cli::SearchMode::Any,
                 file_patterns: vec![],
+                file_only_patterns: vec![],
                 file_mode: crate::cli::SearchMode::All,
                 exclude_tags: vec![],
+                exclude_file_patterns: vec![],
                 regex_tag: false,
                 regex_file: false,
                 glob_files: false,
                 virtual_tags: vec![],
                 virtual_mode: crate::cli::SearchMode::All,
+                exclude_virtual_tags: vec![],
                 no_hierarchy: false,
+                expression: None,
+                no_index: false,
             }),
             ..Default::default()
         };
@@ -1014,14 +1095,19 @@ mod tests {
             tags: vec!["rust".to_string()],
             tag_mode: crate::cli::SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: crate::cli::SearchMode::All,
             exclude_tags: vec!["tag1".to_string()],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: crate::cli::SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         session.update_search_params(new_params).unwrap();
@@ -1062,14 +1148,19 @@ mod tests {
                 tags: vec!["rust".to_string()],
                 tag_mode: crate::cli::SearchMode::Any,
                 file_patterns: vec![],
+                file_only_patterns: vec![],
                 file_mode: crate::cli::SearchMode::All,
                 exclude_tags: vec!["cli".to_string()],
+                exclude_file_patterns: vec![],
                 regex_tag: false,
                 regex_file: false,
                 glob_files: false,
                 virtual_tags: vec![],
                 virtual_mode: crate::cli::SearchMode::All,
+                exclude_virtual_tags: vec![],
                 no_hierarchy: false,
+                expression: None,
+                no_index: false,
             }),
             ..Default::default()
         };
@@ -1083,14 +1174,19 @@ mod tests {
             tags: vec!["rust".to_string()],
             tag_mode: crate::cli::SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: crate::cli::SearchMode::All,
             exclude_tags: vec![], // Removed exclude
             regex_tag: false,
+            exclude_file_patterns: vec![],
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: crate::cli::SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         session.update_search_params(new_params).unwrap();
@@ -1118,14 +1214,19 @@ mod tests {
                 tags: vec!["rust".to_string()],
                 tag_mode: crate::cli::SearchMode::Any,
                 file_patterns: vec![],
+                file_only_patterns: vec![],
                 file_mode: crate::cli::SearchMode::All,
                 exclude_tags: vec![],
+                exclude_file_patterns: vec![],
                 regex_tag: false,
                 regex_file: false,
                 glob_files: false,
                 virtual_tags: vec![],
                 virtual_mode: crate::cli::SearchMode::All,
+                exclude_virtual_tags: vec![],
                 no_hierarchy: false,
+                expression: None,
+                no_index: false,
             }),
             ..Default::default()
         };
@@ -73,6 +73,10 @@ impl<'a, F: FuzzyFinder> BrowseController<'a, F> {
     /// Returns error if database operations or action execution fails
     #[allow(clippy::too_many_lines)]
     pub fn run(mut self) -> Result<Option<BrowseResult>, BrowseError> {
+        // Start tags only apply to the first browser run; later runs (after
+        // actions) keep whatever the user selected since
+        let mut start_tags = self.session.config().start_tags.clone();
+
         loop {
             let phase = self.session.current_phase();
 
@@ -90,7 +94,7 @@ impl<'a, F: FuzzyFinder> BrowseController<'a, F> {
             }
 
             // Run unified browser loop for current phase
-            let browser_result = self.run_browser_phase()?;
+            let browser_result = self.run_browser_phase(std::mem::take(&mut start_tags))?;
 
             match browser_result {
                 BrowserResult::Accept(selected_ids) => {
@@ -159,6 +163,7 @@ impl<'a, F: FuzzyFinder> BrowseController<'a, F> {
                     exclude_tags,
                     file_patterns,
                     virtual_tags,
+                    expression,
                 } => {
                     // User completed refine search overlay - apply the new criteria
                     use crate::cli::SearchParams;
@@ -177,14 +182,19 @@ impl<'a, F: FuzzyFinder> BrowseController<'a, F> {
                                         tags: selected_tags.clone(),
                                         tag_mode: crate::cli::SearchMode::Any,
                                         file_patterns: vec![],
+                                        file_only_patterns: vec![],
                                         file_mode: crate::cli::SearchMode::All,
                                         exclude_tags: vec![],
+                                        exclude_file_patterns: vec![],
                                         regex_tag: false,
                                         regex_file: false,
                                         glob_files: false,
                                         virtual_tags: vec![],
                                         virtual_mode: crate::cli::SearchMode::All,
+                                        exclude_virtual_tags: vec![],
                                         no_hierarchy: false,
+                                        expression: None,
+                                        no_index: false,
                                     }
                                 } else {
                                     SearchParams {
@@ -192,14 +202,19 @@ impl<'a, F: FuzzyFinder> BrowseController<'a, F> {
                                         tags: vec![],
                                         tag_mode: crate::cli::SearchMode::Any,
                                         file_patterns: vec![],
+                                        file_only_patterns: vec![],
                                         file_mode: crate::cli::SearchMode::All,
                                         exclude_tags: vec![],
+                                        exclude_file_patterns: vec![],
                                         regex_tag: false,
                                         regex_file: false,
                                         glob_files: false,
                                         virtual_tags: vec![],
                                         virtual_mode: crate::cli::SearchMode::All,
+                                        exclude_virtual_tags: vec![],
                                         no_hierarchy: false,
+                                        expression: None,
+                                        no_index: false,
                                     }
                                 }
                             });
@@ -209,14 +224,20 @@ impl<'a, F: FuzzyFinder> BrowseController<'a, F> {
                         tags: include_tags,
                         tag_mode: current.tag_mode,
                         file_patterns,
+                        file_only_patterns: current.file_only_patterns.clone(),
                         file_mode: current.file_mode,
                         exclude_tags,
+                        exclude_file_patterns: vec![],
                         regex_tag: current.regex_tag,
                         regex_file: current.regex_file,
                         glob_files: current.glob_files,
                         virtual_tags,
                         virtual_mode: current.virtual_mode,
+                        exclude_virtual_tags: current.exclude_virtual_tags.clone(),
                         no_hierarchy: current.no_hierarchy,
+                        expression: expression
+                            .and_then(|e| crate::filters::FilterExpression::parse(&e).ok()),
+                        no_index: current.no_index,
                     };
 
                     self.session.update_search_params(new_params)?;
@@ -297,7 +318,7 @@ impl<'a, F: FuzzyFinder> BrowseController<'a, F> {
     /// # Errors
     ///
     /// Returns error if finder invocation fails
-    fn run_browser_phase(&self) -> Result<BrowserResult, BrowseError> {
+    fn run_browser_phase(&self, start_tags: Vec<String>) -> Result<BrowserResult, BrowseError> {
         let phase = self.session.current_phase();
 
         let display_items: Vec<DisplayItem> = phase
@@ -338,9 +359,17 @@ impl<'a, F: FuzzyFinder> BrowseController<'a, F> {
                 search_criteria.exclude_tags,
                 search_criteria.file_patterns,
                 search_criteria.virtual_tags,
+                search_criteria.expression,
             ))
             .with_schema(tag_schema)
-            .with_database(database);
+            .with_database(database)
+            .with_state_file(
+                self.session.config().tui_state_file.clone(),
+                self.session.config().restore_tui_state,
+            )
+            .with_exclude_dirs(self.session.config().exclude_dirs.clone())
+            .with_start_tags(start_tags)
+            .with_max_items(self.session.config().max_items);
 
         let config = if let Some(preview_cfg) = phase.settings.preview_config.clone() {
             config.with_preview(preview_cfg.into())
@@ -360,6 +389,7 @@ impl<'a, F: FuzzyFinder> BrowseController<'a, F> {
                 exclude_tags: criteria.exclude_tags.clone(),
                 file_patterns: criteria.file_patterns.clone(),
                 virtual_tags: criteria.virtual_tags.clone(),
+                expression: criteria.expression.clone(),
             });
         }
 
@@ -507,6 +537,9 @@ impl<'a, F: FuzzyFinder> BrowseController<'a, F> {
                 .and_then(|n| n.to_str())
                 .unwrap_or("")
                 .to_string(),
+            PathFormat::LastN(n) => {
+                crate::output::format_path(path, crate::config::PathFormat::LastN(*n))
+            }
         }
     }
 
@@ -600,6 +633,7 @@ impl<'a, F: FuzzyFinder> BrowseController<'a, F> {
         match action_id {
             "add_tag" => {
                 let tags: Vec<String> = input.split_whitespace().map(ToString::to_string).collect();
+                let tags = self.session.config().tag_normalization.apply_all(&tags);
 
                 if tags.is_empty() {
                     return Ok(ActionOutcome::Failed("No tags specified".to_string()));
@@ -694,6 +728,8 @@ enum BrowserResult {
         exclude_tags: Vec<String>,
         file_patterns: Vec<String>,
         virtual_tags: Vec<String>,
+        /// Grouped expression in query syntax (already validated by the overlay)
+        expression: Option<String>,
     },
 
     /// User cancelled (ESC)
@@ -723,61 +759,16 @@ pub enum BrowseError {
 mod tests {
     use super::*;
     use crate::browse::session::BrowseConfig;
-    use crate::testing::TestDb;
+    use crate::testing::{MockFinder, TestDb};
     use crate::ui::FinderResult;
 
-    /// Mock finder for testing
-    struct MockFinder {
-        results: Vec<FinderResult>,
-        call_count: std::cell::RefCell<usize>,
-    }
-
-    impl MockFinder {
-        fn new(results: Vec<FinderResult>) -> Self {
-            Self {
-                results,
-                call_count: std::cell::RefCell::new(0),
-            }
-        }
-    }
-
-    impl FuzzyFinder for MockFinder {
-        fn run(&self, _config: FinderConfig) -> crate::ui::Result<FinderResult> {
-            let mut count = self.call_count.borrow_mut();
-            let result = self
-                .results
-                .get(*count)
-                .ok_or_else(|| crate::ui::UiError::BuildError("No more mock results".into()))?;
-            *count += 1;
-
-            // Clone manually since FinderResult doesn't derive Clone
-            Ok(FinderResult {
-                selected: result.selected.clone(),
-                aborted: result.aborted,
-                final_key: result.final_key.clone(),
-                refine_search: result.refine_search.clone(),
-                input_action: result.input_action.clone(),
-                direct_file_selection: result.direct_file_selection,
-                selected_tags: result.selected_tags.clone(),
-            })
-        }
-    }
-
     #[test]
     fn test_controller_cancels_on_empty_tag_selection() {
         let db = TestDb::new("test_controller_cancel");
         let config = BrowseConfig::default();
         let session = BrowseSession::new(db.db(), config).unwrap();
 
-        let mock_finder = MockFinder::new(vec![FinderResult {
-            selected: vec![],
-            aborted: true,
-            final_key: None,
-            refine_search: None,
-            input_action: None,
-            direct_file_selection: false,
-            selected_tags: vec![],
-        }]);
+        let mock_finder = MockFinder::scripted([FinderResult::aborted()]);
 
         let controller = BrowseController::new(session, mock_finder);
         let result = controller.run().unwrap();
@@ -40,8 +40,10 @@
 //! }
 //! ```
 
+use crate::vtags::VirtualTag;
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Path display format
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -50,6 +52,8 @@ pub enum PathFormat {
     Absolute,
     /// Display relative paths (relative to current directory)
     Relative,
+    /// Display the last N path components
+    LastN(usize),
 }
 
 /// List variant for the list command
@@ -61,6 +65,77 @@ pub enum ListVariant {
     Tags,
 }
 
+/// Sort order for `tagr list files` and `tagr search`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListSort {
+    /// Most recently modified first
+    #[value(alias = "mtime")]
+    Modified,
+    /// Alphabetically by path
+    Name,
+    /// Largest files first
+    Size,
+    /// Most recently tagged first
+    Tagged,
+}
+
+/// What `tagr dedup` compares files by
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DedupBy {
+    /// Files with exactly the same set of tags
+    #[default]
+    Tags,
+    /// Files with identical contents
+    Content,
+}
+
+/// Output format for `tagr config get`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfigFormat {
+    /// Human-readable text (default)
+    #[default]
+    Text,
+    /// A JSON object keyed by field name
+    Json,
+}
+
+/// Output format for `tagr list` and `tagr search`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListFormat {
+    /// Human-readable lines (default)
+    #[default]
+    Text,
+    /// JSON array for scripting
+    Json,
+    /// One compact JSON object per line, written as results are produced
+    Jsonl,
+    /// Markdown list of file links with their tags, for documentation
+    Markdown,
+    /// Tab-separated columns with a header row, for `column -t` or `cut`
+    Tsv,
+}
+
+/// Output format for tables such as `tagr db list` and `tagr filter list`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TableFormat {
+    /// Aligned columns (default)
+    #[default]
+    Text,
+    /// Tab-separated columns with a header row, for `column -t` or `cut`
+    Tsv,
+}
+
+/// Shell targeted by `tagr completions shell-hook`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookShell {
+    /// Bash (via `PROMPT_COMMAND`)
+    Bash,
+    /// Zsh (via `chpwd` hook)
+    Zsh,
+    /// Fish (via `--on-variable PWD`)
+    Fish,
+}
+
 /// Search mode for combining multiple criteria
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SearchMode {
@@ -83,10 +158,14 @@ pub struct SearchParams {
     pub tag_mode: SearchMode,
     /// File patterns to filter by
     pub file_patterns: Vec<String>,
+    /// Glob patterns matched without any tag requirement (`search --files`)
+    pub file_only_patterns: Vec<String>,
     /// How to combine multiple file patterns (AND/OR)
     pub file_mode: SearchMode,
     /// Tags to exclude
     pub exclude_tags: Vec<String>,
+    /// File patterns to exclude (glob, or regex with `regex_file`)
+    pub exclude_file_patterns: Vec<String>,
     /// Use regex for tag matching
     pub regex_tag: bool,
     /// Use regex for file pattern matching
@@ -97,8 +176,14 @@ pub struct SearchParams {
     pub virtual_tags: Vec<String>,
     /// How to combine multiple virtual tags (AND/OR)
     pub virtual_mode: SearchMode,
+    /// Virtual tags whose matching files are dropped
+    pub exclude_virtual_tags: Vec<String>,
     /// Skip hierarchy expansion (don't search parent tags)
     pub no_hierarchy: bool,
+    /// Grouped tag and file expression every result must also satisfy
+    pub expression: Option<crate::filters::FilterExpression>,
+    /// Match tags by scanning every entry instead of using the reverse index
+    pub no_index: bool,
 }
 
 /// Preview configuration overrides from CLI
@@ -127,6 +212,26 @@ pub struct TagContext {
     pub tags: Vec<String>,
     /// Skip tag canonicalization
     pub no_canonicalize: bool,
+    /// Derive additional tags from the filename
+    pub from_filename: bool,
+    /// Characters that separate filename tokens
+    pub separators: String,
+    /// Drop tokens that consist only of digits
+    pub skip_numbers: bool,
+    /// Minimum length of a derived token
+    pub min_len: usize,
+    /// Tag every file under `file`, which is a directory
+    pub recursive: bool,
+    /// Globs selecting files to tag with `recursive`
+    pub include: Vec<String>,
+    /// Globs of files and directories to skip with `recursive`
+    pub exclude: Vec<String>,
+    /// Honor `.gitignore` with `recursive`
+    pub gitignore: bool,
+    /// Walk into symlinked directories with `recursive`
+    pub follow_symlinks: bool,
+    /// Show the tags that would be applied without writing them
+    pub dry_run: bool,
 }
 
 /// Context for untag command execution
@@ -138,6 +243,8 @@ pub struct UntagContext {
     pub tags: Vec<String>,
     /// Remove all tags from file
     pub all: bool,
+    /// Show the resulting tags without writing them
+    pub dry_run: bool,
 }
 
 /// Context for browse command execution
@@ -147,11 +254,62 @@ pub struct BrowseContext {
     pub search_params: Option<SearchParams>,
     /// Command to execute on selected files
     pub execute_cmd: Option<String>,
+    /// Whether `execute_cmd` runs once per file or once for all files
+    pub execute_mode: ExecuteMode,
     /// Preview configuration overrides
     pub preview_overrides: PreviewOverrides,
+    /// Skip restoring saved TUI state
+    pub fresh: bool,
+    /// Tags pre-selected in the tag tree on launch
+    pub start_tags: Vec<String>,
 }
 
 impl SearchParams {
+    /// Build params from CLI criteria, combining tags with `default_tag_mode`
+    /// unless `--any-tag` or `--all-tags` is given
+    #[must_use]
+    pub fn from_criteria(criteria: &SearchCriteriaArgs, default_tag_mode: SearchMode) -> Self {
+        Self {
+            query: None,
+            tags: criteria.tags.clone(),
+            tag_mode: criteria.tag_mode_or(default_tag_mode),
+            file_patterns: criteria.file_patterns.clone(),
+            file_only_patterns: Vec::new(),
+            file_mode: if criteria.any_file {
+                SearchMode::Any
+            } else {
+                SearchMode::All
+            },
+            exclude_tags: criteria.excludes.clone(),
+            exclude_file_patterns: criteria.exclude_file_patterns.clone(),
+            regex_tag: criteria.regex_tag,
+            regex_file: criteria.regex_file,
+            glob_files: criteria.glob_files,
+            virtual_tags: criteria.virtual_tags.clone(),
+            virtual_mode: if criteria.any_virtual {
+                SearchMode::Any
+            } else {
+                SearchMode::All
+            },
+            exclude_virtual_tags: criteria.exclude_virtual_tags.clone(),
+            no_hierarchy: false, // Default to false, set explicitly from command
+            expression: None,
+            no_index: false,
+        }
+    }
+
+    /// Whether any query, tag, file pattern, virtual tag or expression is set
+    #[must_use]
+    pub fn has_criteria(&self) -> bool {
+        self.expression.is_some()
+            || self.query.is_some()
+            || !self.tags.is_empty()
+            || !self.file_patterns.is_empty()
+            || !self.file_only_patterns.is_empty()
+            || !self.virtual_tags.is_empty()
+            || !self.exclude_virtual_tags.is_empty()
+    }
+
     /// Merge with another `SearchParams` to create combined criteria
     ///
     /// This adds criteria from `other` on top of self:
@@ -177,6 +335,12 @@ impl SearchParams {
             }
         }
 
+        for pattern in &other.file_only_patterns {
+            if !self.file_only_patterns.contains(pattern) {
+                self.file_only_patterns.push(pattern.clone());
+            }
+        }
+
         // Merge exclusions
         for exclude in &other.exclude_tags {
             if !self.exclude_tags.contains(exclude) {
@@ -184,6 +348,12 @@ impl SearchParams {
             }
         }
 
+        for pattern in &other.exclude_file_patterns {
+            if !self.exclude_file_patterns.contains(pattern) {
+                self.exclude_file_patterns.push(pattern.clone());
+            }
+        }
+
         // Merge virtual tags
         for vtag in &other.virtual_tags {
             if !self.virtual_tags.contains(vtag) {
@@ -191,12 +361,22 @@ impl SearchParams {
             }
         }
 
+        for vtag in &other.exclude_virtual_tags {
+            if !self.exclude_virtual_tags.contains(vtag) {
+                self.exclude_virtual_tags.push(vtag.clone());
+            }
+        }
+
         // OR the boolean flags
         self.regex_tag = self.regex_tag || other.regex_tag;
         self.regex_file = self.regex_file || other.regex_file;
         self.glob_files = self.glob_files || other.glob_files;
         self.no_hierarchy = self.no_hierarchy || other.no_hierarchy;
 
+        if other.expression.is_some() {
+            self.expression.clone_from(&other.expression);
+        }
+
         // Modes from other always override (caller handles preservation if needed)
         self.tag_mode = other.tag_mode;
         self.file_mode = other.file_mode;
@@ -210,90 +390,83 @@ impl From<SearchParams> for crate::filters::FilterCriteria {
     /// Note: The general query is not preserved in `FilterCriteria` since
     /// filters use explicit tags and file patterns only.
     fn from(params: SearchParams) -> Self {
+        // `--files` globs are stored as ordinary glob file patterns
+        let glob_files = !params.file_only_patterns.is_empty();
+        let mut file_patterns = params.file_patterns;
+        file_patterns.extend(params.file_only_patterns);
         Self {
             tags: params.tags,
             tag_mode: params.tag_mode.into(),
-            file_patterns: params.file_patterns,
+            expression: params.expression,
+            file_patterns,
             file_mode: params.file_mode.into(),
             excludes: params.exclude_tags,
+            exclude_file_patterns: params.exclude_file_patterns,
             regex_tag: params.regex_tag,
             regex_file: params.regex_file,
-            glob_files: false,
+            glob_files,
             virtual_tags: params.virtual_tags,
             virtual_mode: params.virtual_mode.into(),
+            exclude_virtual_tags: params.exclude_virtual_tags,
         }
     }
 }
 
 impl From<&SearchParams> for crate::filters::FilterCriteria {
     fn from(params: &SearchParams) -> Self {
-        Self {
-            tags: params.tags.clone(),
-            tag_mode: params.tag_mode.into(),
-            file_patterns: params.file_patterns.clone(),
-            file_mode: params.file_mode.into(),
-            excludes: params.exclude_tags.clone(),
-            regex_tag: params.regex_tag,
-            regex_file: params.regex_file,
-            glob_files: false,
-            virtual_tags: params.virtual_tags.clone(),
-            virtual_mode: params.virtual_mode.into(),
-        }
+        Self::from(params.clone())
     }
 }
 
 impl From<&crate::filters::FilterCriteria> for SearchParams {
+    /// An expression supersedes the criteria's flat tags and tag mode
     fn from(criteria: &crate::filters::FilterCriteria) -> Self {
+        let (tags, tag_mode) = if criteria.expression.is_some() {
+            (Vec::new(), SearchMode::default())
+        } else {
+            (criteria.tags.clone(), criteria.tag_mode.into())
+        };
         Self {
             query: None,
-            tags: criteria.tags.clone(),
-            tag_mode: criteria.tag_mode.into(),
+            tags,
+            tag_mode,
             file_patterns: criteria.file_patterns.clone(),
+            file_only_patterns: Vec::new(),
             file_mode: criteria.file_mode.into(),
             exclude_tags: criteria.excludes.clone(),
+            exclude_file_patterns: criteria.exclude_file_patterns.clone(),
             regex_tag: criteria.regex_tag,
             regex_file: criteria.regex_file,
             glob_files: criteria.glob_files,
             virtual_tags: criteria.virtual_tags.clone(),
             virtual_mode: criteria.virtual_mode.into(),
+            exclude_virtual_tags: criteria.exclude_virtual_tags.clone(),
             no_hierarchy: false, // Filters don't store hierarchy preference
+            expression: criteria.expression.clone(),
+            no_index: false,
         }
     }
 }
 
 impl From<&SearchCriteriaArgs> for SearchParams {
+    /// Multiple tags default to AND; see [`SearchParams::from_criteria`]
     fn from(criteria: &SearchCriteriaArgs) -> Self {
-        Self {
-            query: None,
-            tags: criteria.tags.clone(),
-            tag_mode: if criteria.any_tag {
-                SearchMode::Any
-            } else {
-                SearchMode::All
-            },
-            file_patterns: criteria.file_patterns.clone(),
-            file_mode: if criteria.any_file {
-                SearchMode::Any
-            } else {
-                SearchMode::All
-            },
-            exclude_tags: criteria.excludes.clone(),
-            regex_tag: criteria.regex_tag,
-            regex_file: criteria.regex_file,
-            glob_files: criteria.glob_files,
-            virtual_tags: criteria.virtual_tags.clone(),
-            virtual_mode: if criteria.any_virtual {
-                SearchMode::Any
-            } else {
-                SearchMode::All
-            },
-            no_hierarchy: false, // Default to false, set explicitly from command
-        }
+        Self::from_criteria(criteria, SearchMode::All)
     }
 }
 
 /// Execute a command template for each file in the list
 ///
+/// How a browse `{}` command template is applied to the selected files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecuteMode {
+    /// Run the command once per file (`--exec`)
+    #[default]
+    PerFile,
+    /// Run the command once with every file substituted (`--exec-multi`)
+    AllFiles,
+}
+
 /// Runs a shell command for each file, replacing the `{}` placeholder in the
 /// command template with the file path.
 ///
@@ -313,18 +486,14 @@ impl From<&SearchCriteriaArgs> for SearchParams {
 /// # Examples
 ///
 /// ```no_run
-/// use tagr::cli::execute_command_on_files;
+/// use tagr::cli::execute_per_file;
 /// use std::path::PathBuf;
 ///
 /// let files = vec![PathBuf::from("file1.txt"), PathBuf::from("file2.txt")];
-/// let count = execute_command_on_files(&files, "cat {}", false);
+/// let count = execute_per_file(&files, "cat {}", false);
 /// println!("Successfully executed command on {} files", count);
 /// ```
-pub fn execute_command_on_files<P: AsRef<Path>>(
-    files: &[P],
-    cmd_template: &str,
-    quiet: bool,
-) -> usize {
+pub fn execute_per_file<P: AsRef<Path>>(files: &[P], cmd_template: &str, quiet: bool) -> usize {
     let mut success_count = 0;
 
     for file in files {
@@ -355,6 +524,93 @@ pub fn execute_command_on_files<P: AsRef<Path>>(
     success_count
 }
 
+/// Runs a shell command once for all files
+///
+/// Every `{}` in the command template is replaced with the shell-quoted file
+/// paths separated by spaces, so `vim {}` opens all files in one editor.
+/// Nothing is run when `files` is empty.
+///
+/// # Errors
+/// Returns an error if a path cannot be shell-quoted (it contains a NUL
+/// byte), the shell cannot be spawned, or the command exits unsuccessfully.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tagr::cli::execute_on_all_files;
+/// use std::path::PathBuf;
+///
+/// let files = vec![PathBuf::from("a file.txt"), PathBuf::from("b.txt")];
+/// // Runs: git add 'a file.txt' b.txt
+/// execute_on_all_files(&files, "git add {}", false).unwrap();
+/// ```
+pub fn execute_on_all_files(
+    files: &[PathBuf],
+    cmd_template: &str,
+    quiet: bool,
+) -> Result<(), crate::TagrError> {
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let cmd = cmd_template.replace("{}", &quote_paths(files)?);
+    if !quiet {
+        println!("Running: {cmd}");
+    }
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(&cmd)
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(crate::TagrError::InvalidInput(format!(
+            "Command failed with status: {status}"
+        )))
+    }
+}
+
+/// Parse a `--path-context` component count, which must be at least 1
+fn parse_path_context(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        Ok(n) => Ok(n),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Parse a byte size such as `100KB` or `1.5MiB`
+fn parse_byte_size(value: &str) -> Result<u64, String> {
+    byte_unit::Byte::parse_str(value, true)
+        .map(byte_unit::Byte::as_u64)
+        .map_err(|e| e.to_string())
+}
+
+/// Parse a virtual tag expression such as `modified:today`
+fn parse_virtual_tag(value: &str) -> Result<VirtualTag, String> {
+    VirtualTag::parse_with_config(value, &crate::vtags::VirtualTagConfig::default())
+        .map_err(|e| e.to_string())
+}
+
+/// Shell-quote paths and join them with spaces
+fn quote_paths(files: &[PathBuf]) -> Result<String, crate::TagrError> {
+    files
+        .iter()
+        .map(|file| {
+            let path = file.to_string_lossy();
+            shlex::try_quote(&path)
+                .map(std::borrow::Cow::into_owned)
+                .map_err(|e| {
+                    crate::TagrError::InvalidInput(format!(
+                        "Cannot quote path {}: {e}",
+                        file.display()
+                    ))
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|quoted| quoted.join(" "))
+}
+
 /// Conditional operation flags (reusable across bulk operations)
 #[derive(Args, Debug, Clone, Default)]
 pub struct ConditionalArgs {
@@ -369,6 +625,30 @@ pub struct ConditionalArgs {
     /// Only process files that are missing ANY of these tags
     #[arg(long = "if-missing-tag", value_name = "TAG")]
     pub if_missing_tag: Vec<String>,
+
+    /// Only process files last modified longer ago than this (e.g., 30d)
+    #[arg(long = "if-older-than", value_name = "DURATION", value_parser = humantime::parse_duration)]
+    pub if_older_than: Option<Duration>,
+
+    /// Only process files modified within this duration (e.g., 7d)
+    #[arg(long = "if-newer-than", value_name = "DURATION", value_parser = humantime::parse_duration)]
+    pub if_newer_than: Option<Duration>,
+
+    /// Only process files larger than this size (e.g., 1MB)
+    #[arg(long = "if-size-gt", value_name = "SIZE", value_parser = parse_byte_size)]
+    pub if_size_gt: Option<u64>,
+
+    /// Only process files smaller than this size (e.g., 100KB)
+    #[arg(long = "if-size-lt", value_name = "SIZE", value_parser = parse_byte_size)]
+    pub if_size_lt: Option<u64>,
+
+    /// Only process files with this extension (without the dot)
+    #[arg(long = "if-extension", value_name = "EXT")]
+    pub if_extension: Option<String>,
+
+    /// Only process files matching ALL of these virtual tags (e.g., size:>1MB)
+    #[arg(long = "if-matches-vtag", value_name = "VTAG_EXPR", value_parser = parse_virtual_tag)]
+    pub if_matches_vtag: Vec<VirtualTag>,
 }
 
 /// Configuration management subcommands
@@ -381,11 +661,47 @@ pub enum ConfigCommands {
         setting: String,
     },
 
-    /// Get a configuration value
+    /// Get a configuration value, or `all` for every value with its default and source
     Get {
-        /// Configuration key to retrieve (e.g., quiet)
+        /// Configuration key to retrieve (e.g., quiet), or `all` / `*`
         #[arg(value_name = "KEY")]
         key: String,
+
+        /// Output format (json requires `all`)
+        #[arg(long = "format", value_name = "FORMAT", default_value = "text")]
+        format: ConfigFormat,
+    },
+
+    /// List every configuration value with its default and source
+    List {
+        /// Print a JSON array of `{key, value, default, source}` objects
+        #[arg(long = "json")]
+        json: bool,
+    },
+
+    /// Check the config file for syntax errors and invalid values
+    Validate,
+}
+
+/// Shell integration subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum CompletionsCommands {
+    /// Install completions to the shell's conventional location
+    Install {
+        /// Shell to install for (detected from $SHELL if omitted)
+        #[arg(value_enum)]
+        shell: Option<clap_complete::Shell>,
+
+        /// Overwrite an existing completion file
+        #[arg(short = 'f', long = "force")]
+        force: bool,
+    },
+
+    /// Print a hook that auto-tags directories containing a `.tagr-rules` file on `cd`
+    ShellHook {
+        /// Shell to generate the hook for
+        #[arg(long = "shell", value_enum)]
+        shell: HookShell,
     },
 }
 
@@ -394,9 +710,28 @@ pub enum ConfigCommands {
 pub enum TagsCommands {
     /// List all tags in the database
     List {
-        /// Display tags in tree format showing hierarchical relationships
-        #[arg(long = "tree")]
-        tree: bool,
+        /// Display tags as a tree grouped by hierarchy prefix (e.g. lang:rust)
+        #[arg(long = "hierarchy", visible_alias = "tree")]
+        hierarchy: bool,
+
+        /// Show at most N levels of the tree
+        #[arg(long = "depth", value_name = "N", requires = "hierarchy")]
+        depth: Option<usize>,
+
+        /// Draw the tree with ASCII characters instead of box-drawing characters
+        #[arg(long = "no-unicode", requires = "hierarchy")]
+        no_unicode: bool,
+
+        /// Fold aliases into their canonical tag, counting each file once
+        #[arg(long = "canonical", conflicts_with = "hierarchy")]
+        canonical: bool,
+    },
+
+    /// Show tag usage as a bar chart, most used first
+    Cloud {
+        /// Show only the N most used tags
+        #[arg(long = "limit", value_name = "N")]
+        limit: Option<usize>,
     },
 
     /// Remove a tag from all files (cleans up files with no remaining tags)
@@ -405,6 +740,180 @@ pub enum TagsCommands {
         /// Tag to remove from all files
         tag: String,
     },
+
+    /// Rename a tag on every file, keeping its position in each file's tag list
+    Rename {
+        /// Current tag name
+        old_tag: String,
+
+        /// New tag name
+        new_tag: String,
+
+        /// Also rewrite saved filters and schema aliases that reference the old tag
+        #[arg(long = "update-refs")]
+        update_refs: bool,
+
+        /// Allow renaming onto a tag that already exists, merging the two
+        #[arg(long = "merge")]
+        merge: bool,
+
+        /// Preview changes without applying them
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
+
+    /// Pick tags in the fuzzy finder and merge them into a target tag
+    #[command(name = "merge-interactive")]
+    MergeInteractive {
+        /// Preview changes without applying them
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
+
+    /// Find near-duplicate tags (js/JS, color/colour) and merge each group into one
+    ///
+    /// Each group is merged into its most used tag unless --interactive is given.
+    #[command(name = "merge-similar")]
+    MergeSimilar {
+        /// Minimum similarity (0.0-1.0) for two tags to count as variants
+        #[arg(long = "threshold", value_name = "SIMILARITY", default_value_t = 0.8)]
+        threshold: f64,
+
+        /// Pick the canonical form of each group, or skip it
+        #[arg(short = 'i', long = "interactive")]
+        interactive: bool,
+
+        /// Show each group and its proposed canonical form without merging
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
+
+    /// Move every tag under one hierarchy prefix to another (e.g. proj → archive:proj)
+    #[command(name = "rename-prefix")]
+    RenamePrefix {
+        /// Prefix to replace (matches the tag itself and all tags below it)
+        #[arg(value_name = "OLD_PREFIX")]
+        old_prefix: String,
+
+        /// Replacement prefix
+        #[arg(value_name = "NEW_PREFIX")]
+        new_prefix: String,
+
+        /// Preview changes without applying them
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
+}
+
+/// Export subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum ExportCommands {
+    /// Export file-tag pairs as CSV
+    Csv {
+        /// Output file path (prints to stdout if not specified)
+        #[arg(short = 'o', long = "output")]
+        output: Option<PathBuf>,
+
+        /// One row per file and tag: `file,tag` (default)
+        #[arg(long = "long", conflicts_with = "wide")]
+        long: bool,
+
+        /// One row per file with tags joined by ';': `file,tags`
+        #[arg(long = "wide", conflicts_with = "long")]
+        wide: bool,
+
+        /// Omit the header row
+        #[arg(long = "no-header")]
+        no_header: bool,
+    },
+
+    /// Write each file's tags to its `user.xdg.tags` extended attribute
+    #[cfg(feature = "xattr")]
+    Xattr {
+        /// Replace attribute tags with the database's, dropping tags not in it
+        #[arg(long = "sync")]
+        sync: bool,
+    },
+
+    /// Export file-tag pairs to an SQLite database with `files` and `tags` tables
+    #[cfg(feature = "sqlite")]
+    Sqlite {
+        /// SQLite file to write (existing `files` and `tags` tables are replaced)
+        #[arg(short = 'o', long = "output")]
+        output: PathBuf,
+    },
+}
+
+/// Import subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum ImportCommands {
+    /// Import file-tag pairs from CSV (as written by `export csv`)
+    Csv {
+        /// CSV file to read
+        file: PathBuf,
+
+        /// One row per file and tag: `file,tag` (default)
+        #[arg(long = "long", conflicts_with = "wide")]
+        long: bool,
+
+        /// One row per file with tags joined by ';': `file,tags`
+        #[arg(long = "wide", conflicts_with = "long")]
+        wide: bool,
+
+        /// Add imported tags to existing ones instead of replacing them
+        #[arg(long = "merge")]
+        merge: bool,
+
+        /// Treat the first row as a header (detected automatically otherwise)
+        #[arg(long = "has-header")]
+        has_header: bool,
+
+        /// Preview changes without applying them
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
+
+    /// Import tags other taggers stored in extended attributes (`user.xdg.tags`)
+    #[cfg(feature = "xattr")]
+    Xattr {
+        /// Directory to scan recursively
+        dir: PathBuf,
+
+        /// Preview changes without applying them
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
+    },
+
+    /// Import file-tag pairs from an SQLite database written by `export sqlite`
+    #[cfg(feature = "sqlite")]
+    Sqlite {
+        /// SQLite file to read
+        file: PathBuf,
+
+        /// What to do with files that already have tags
+        #[arg(long = "on-conflict", value_enum, default_value_t)]
+        on_conflict: crate::db::ImportConflict,
+    },
 }
 
 /// Database management subcommands
@@ -420,7 +929,14 @@ pub enum DbCommands {
     },
 
     /// List all databases
-    List,
+    List {
+        /// Print a JSON array of `{name, path, default}` objects
+        #[arg(long = "json", conflicts_with = "format")]
+        json: bool,
+
+        #[command(flatten)]
+        table: TableArgs,
+    },
 
     /// Remove a database from configuration
     #[command(visible_alias = "rm")]
@@ -439,6 +955,17 @@ pub enum DbCommands {
         /// Name of the database to set as default
         name: String,
     },
+    /// Rewrite a database to reclaim disk space left by deletions
+    Compact {
+        /// Name of the database to compact
+        name: String,
+    },
+
+    /// Show file and tag counts against the configured limits, cache size and disk usage
+    Info {
+        /// Name of the database (defaults to the default database)
+        name: Option<String>,
+    },
 }
 
 /// Bulk operation subcommands
@@ -456,6 +983,10 @@ pub enum BulkCommands {
         #[command(flatten)]
         conditions: ConditionalArgs,
 
+        /// Check conditions in parallel and write the tags in one batch
+        #[arg(long = "parallel")]
+        parallel: bool,
+
         /// Preview changes without applying them
         #[arg(short = 'n', long = "dry-run")]
         dry_run: bool,
@@ -481,6 +1012,10 @@ pub enum BulkCommands {
         #[command(flatten)]
         conditions: ConditionalArgs,
 
+        /// Check conditions in parallel and remove the tags in one batch
+        #[arg(long = "parallel")]
+        parallel: bool,
+
         /// Preview changes without applying them
         #[arg(short = 'n', long = "dry-run")]
         dry_run: bool,
@@ -499,6 +1034,14 @@ pub enum BulkCommands {
         /// New tag name
         new_tag: String,
 
+        /// Also rewrite saved filters and schema aliases that reference the old tag
+        #[arg(long = "update-refs")]
+        update_refs: bool,
+
+        /// Allow renaming onto a tag that already exists, merging the two
+        #[arg(long = "merge")]
+        merge: bool,
+
         /// Preview changes without applying them
         #[arg(short = 'n', long = "dry-run")]
         dry_run: bool,
@@ -532,8 +1075,21 @@ pub enum BulkCommands {
     #[command(visible_alias = "copy")]
     CopyTags {
         /// Source file to copy tags from
-        #[arg(value_name = "SOURCE_FILE")]
-        source: PathBuf,
+        #[arg(value_name = "SOURCE_FILE", required_unless_present = "smart")]
+        source: Option<PathBuf>,
+
+        /// Copy to each target from the database file with the most similar name
+        #[arg(long = "smart", conflicts_with = "source")]
+        smart: bool,
+
+        /// Minimum filename similarity (0.0-1.0) for --smart matches
+        #[arg(
+            long = "threshold",
+            value_name = "SIMILARITY",
+            default_value_t = 0.8,
+            requires = "smart"
+        )]
+        threshold: f64,
 
         #[command(flatten)]
         criteria: SearchCriteriaArgs,
@@ -670,20 +1226,111 @@ pub enum BulkCommands {
         yes: bool,
     },
 
+    /// Auto-tag files with tokens from their filenames (like `tag --from-filename`)
+    #[command(name = "propagate-by-filename", visible_alias = "prop-name")]
+    PropagateByFilename {
+        /// Root directory to process (defaults to all files)
+        #[arg(value_name = "ROOT")]
+        root: Option<PathBuf>,
+
+        /// Characters that separate filename tokens
+        #[arg(long = "sep", value_name = "CHARS", default_value = "-_. ")]
+        separators: String,
+
+        /// Skip tokens that are pure numbers
+        #[arg(long = "skip-numbers")]
+        skip_numbers: bool,
+
+        /// Skip tokens shorter than N characters
+        #[arg(long = "min-len", value_name = "N", default_value_t = 2)]
+        min_len: usize,
+
+        /// Preview changes without applying them
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
+
+    /// Walk a directory and tag each file by the first matching rule in a TOML file
+    ///
+    /// Rules are `[[rule]]` tables with a `glob` (relative to --dir) or an
+    /// `extension`, and `tags`. Files that already have their rule's tags are
+    /// skipped.
+    #[command(name = "propagate", visible_alias = "prop")]
+    Propagate {
+        /// Directory to walk
+        #[arg(long = "dir", value_name = "DIR")]
+        dir: PathBuf,
+
+        /// TOML file with the rules
+        #[arg(long = "rules", value_name = "FILE")]
+        rules: PathBuf,
+
+        /// Descend at most N levels (1 = only the directory's own files)
+        #[arg(long = "max-depth", value_name = "N")]
+        max_depth: Option<usize>,
+
+        /// Follow symlinked files and directories
+        #[arg(long = "follow-symlinks")]
+        follow_symlinks: bool,
+
+        /// Preview changes without applying them
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
+
+    /// Tag a project's files from its Cargo.toml, package.json or pyproject.toml
+    #[command(name = "propagate-from-manifest", visible_alias = "prop-manifest")]
+    PropagateFromManifest {
+        /// Manifest to read project metadata from
+        #[arg(value_name = "MANIFEST")]
+        manifest: PathBuf,
+
+        /// Tag files under this directory instead of the manifest's
+        #[arg(long = "target-dir", value_name = "DIR")]
+        target_dir: Option<PathBuf>,
+
+        /// Preview changes without applying them
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
+
     /// Transform tags across the database (case, format, prefix/suffix, regex)
     #[command(name = "transform")]
     Transform {
-        /// Transformation type
-        #[arg(value_enum)]
-        transformation: TransformationType,
+        /// Transformation type (optional when using --add-prefix, --lowercase, etc.)
+        #[arg(
+            value_enum,
+            value_name = "TRANSFORMATION",
+            required_unless_present_any([
+                "add_prefix",
+                "strip_prefix",
+                "add_suffix",
+                "strip_suffix",
+                "lowercase",
+                "uppercase",
+            ])
+        )]
+        kind: Option<TransformationType>,
 
         /// Parameter for transformation (prefix, suffix, or regex pattern)
         #[arg(short = 'p', long = "param", required_if_eq_any([
-            ("transformation", "add-prefix"),
-            ("transformation", "add-suffix"),
-            ("transformation", "remove-prefix"),
-            ("transformation", "remove-suffix"),
-            ("transformation", "regex-replace"),
+            ("kind", "add-prefix"),
+            ("kind", "add-suffix"),
+            ("kind", "remove-prefix"),
+            ("kind", "remove-suffix"),
+            ("kind", "regex-replace"),
         ]))]
         param: Option<String>,
 
@@ -691,14 +1338,21 @@ pub enum BulkCommands {
         #[arg(
             short = 'r',
             long = "replacement",
-            required_if_eq("transformation", "regex-replace")
+            required_if_eq("kind", "regex-replace")
         )]
         replacement: Option<String>,
 
+        #[command(flatten)]
+        transformation: TransformationArgs,
+
         /// Only transform specific tags (omit to transform all)
         #[arg(short = 't', long = "tags", value_name = "TAG")]
         filter: Vec<String>,
 
+        /// Only transform tags matching this glob pattern
+        #[arg(long = "tag-filter", value_name = "GLOB")]
+        tag_filter: Option<String>,
+
         /// Preview changes without applying them
         #[arg(short = 'n', long = "dry-run")]
         dry_run: bool,
@@ -709,6 +1363,74 @@ pub enum BulkCommands {
     },
 }
 
+/// Composable prefix/suffix/case transformation flags for `bulk transform`
+///
+/// When several flags are given they are applied in a fixed order:
+/// strip prefix, strip suffix, change case, add prefix, add suffix.
+#[derive(Args, Debug, Clone, Default)]
+pub struct TransformationArgs {
+    /// Remove PREFIX from the start of matching tags
+    #[arg(long = "strip-prefix", value_name = "PREFIX")]
+    pub strip_prefix: Option<String>,
+
+    /// Remove SUFFIX from the end of matching tags
+    #[arg(long = "strip-suffix", value_name = "SUFFIX")]
+    pub strip_suffix: Option<String>,
+
+    /// Convert tags to lowercase
+    #[arg(long = "lowercase", conflicts_with = "uppercase")]
+    pub lowercase: bool,
+
+    /// Convert tags to uppercase
+    #[arg(long = "uppercase", conflicts_with = "lowercase")]
+    pub uppercase: bool,
+
+    /// Prepend PREFIX to tags
+    #[arg(long = "add-prefix", value_name = "PREFIX")]
+    pub add_prefix: Option<String>,
+
+    /// Append SUFFIX to tags
+    #[arg(long = "add-suffix", value_name = "SUFFIX")]
+    pub add_suffix: Option<String>,
+}
+
+impl TransformationArgs {
+    /// Build the ordered transformation chain described by these flags
+    #[must_use]
+    pub fn transformations(&self) -> Vec<crate::commands::bulk::TagTransformation> {
+        use crate::commands::bulk::TagTransformation;
+
+        let mut chain = Vec::new();
+        if let Some(prefix) = &self.strip_prefix {
+            chain.push(TagTransformation::StripPrefix {
+                prefix: prefix.clone(),
+            });
+        }
+        if let Some(suffix) = &self.strip_suffix {
+            chain.push(TagTransformation::StripSuffix {
+                suffix: suffix.clone(),
+            });
+        }
+        if self.lowercase {
+            chain.push(TagTransformation::Lowercase);
+        }
+        if self.uppercase {
+            chain.push(TagTransformation::Uppercase);
+        }
+        if let Some(prefix) = &self.add_prefix {
+            chain.push(TagTransformation::AddPrefix {
+                prefix: prefix.clone(),
+            });
+        }
+        if let Some(suffix) = &self.add_suffix {
+            chain.push(TagTransformation::AddSuffix {
+                suffix: suffix.clone(),
+            });
+        }
+        chain
+    }
+}
+
 /// Transformation type for tags
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TransformationType {
@@ -755,6 +1477,16 @@ pub enum AliasCommands {
         alias: String,
     },
 
+    /// Rename an alias, keeping the tag it points to
+    #[command(visible_alias = "mv")]
+    Rename {
+        /// Current alias name
+        old: String,
+
+        /// New alias name
+        new: String,
+    },
+
     /// List all aliases
     #[command(visible_alias = "ls")]
     List,
@@ -789,7 +1521,10 @@ pub enum AliasCommands {
 pub enum FilterCommands {
     /// List all saved filters
     #[command(visible_alias = "ls")]
-    List,
+    List {
+        #[command(flatten)]
+        table: TableArgs,
+    },
 
     /// Show detailed information about a filter
     Show {
@@ -867,6 +1602,34 @@ pub struct DbArgs {
     pub db: Option<String>,
 }
 
+/// Arguments for plain tag listings (`tagr tags`, `tagr tags list`)
+#[derive(Parser, Debug, Clone, Default)]
+pub struct TagsQueryArgs {
+    /// List only the tags of FILE, in the order they were added
+    #[arg(long = "for", value_name = "FILE")]
+    pub file: Option<PathBuf>,
+
+    /// List only tags starting with PREFIX (e.g. lang:)
+    #[arg(long = "prefix", value_name = "PREFIX", global = true)]
+    pub prefix: Option<String>,
+
+    /// Print bare tag names terminated by NUL instead of newline (for xargs -0)
+    #[arg(short = '0', long = "print0", global = true)]
+    pub print0: bool,
+}
+
+/// Shared arguments for commands that print a table
+#[derive(Parser, Debug, Clone, Copy, Default)]
+pub struct TableArgs {
+    /// Truncate cells wider than N characters with `…`
+    #[arg(long = "max-width", value_name = "N")]
+    pub max_width: Option<usize>,
+
+    /// Output format
+    #[arg(long = "format", value_name = "FORMAT", default_value = "text")]
+    pub format: TableFormat,
+}
+
 /// Shared search criteria arguments (tags, file patterns, virtual tags)
 #[derive(Parser, Debug, Clone)]
 #[allow(clippy::struct_excessive_bools)]
@@ -875,7 +1638,8 @@ pub struct SearchCriteriaArgs {
     #[arg(short = 't', long = "tag", value_name = "TAG", num_args = 0..)]
     pub tags: Vec<String>,
 
-    /// Match files with ANY of the specified tags (OR logic, default is AND)
+    /// Match files with ANY of the specified tags (OR logic; default is AND
+    /// unless `default_tag_mode` is set in the config)
     #[arg(long = "any-tag", conflicts_with = "all_tags")]
     pub any_tag: bool,
 
@@ -899,6 +1663,10 @@ pub struct SearchCriteriaArgs {
     #[arg(short = 'e', long = "exclude", value_name = "TAG", num_args = 0..)]
     pub excludes: Vec<String>,
 
+    /// Exclude files matching these patterns (glob, or regex with --regex-file)
+    #[arg(long = "exclude-file", value_name = "PATTERN", num_args = 0..)]
+    pub exclude_file_patterns: Vec<String>,
+
     /// Use regex matching for tags (alias: --regex-tags)
     #[arg(short = 'r', long = "regex-tag", visible_alias = "regex-tags")]
     pub regex_tag: bool,
@@ -930,6 +1698,45 @@ pub struct SearchCriteriaArgs {
     /// Match files with ALL of the virtual tags (AND logic, explicit)
     #[arg(long = "all-virtual", conflicts_with = "any_virtual")]
     pub all_virtual: bool,
+
+    /// Drop files matching these virtual tags (same as -v '!VTAG')
+    #[arg(long = "exclude-virtual", value_name = "VTAG", num_args = 1..)]
+    pub exclude_virtual_tags: Vec<String>,
+
+    /// Hide files inside directories matching these globs (e.g. target, .git)
+    #[arg(long = "exclude-dir", value_name = "PATTERN", num_args = 0..)]
+    pub exclude_dirs: Vec<String>,
+
+    /// Don't apply the `default_exclude_dirs` from the config
+    #[arg(long = "include-all-dirs")]
+    pub include_all_dirs: bool,
+}
+
+impl SearchCriteriaArgs {
+    /// Tag mode from `--any-tag`/`--all-tags`, or `default` when neither is given
+    #[must_use]
+    pub const fn tag_mode_or(&self, default: SearchMode) -> SearchMode {
+        if self.any_tag {
+            SearchMode::Any
+        } else if self.all_tags {
+            SearchMode::All
+        } else {
+            default
+        }
+    }
+
+    /// Directory exclusions to apply: `defaults` (unless `--include-all-dirs`)
+    /// followed by any `--exclude-dir` patterns
+    #[must_use]
+    pub fn exclude_dirs(&self, defaults: &[String]) -> Vec<String> {
+        let mut dirs = if self.include_all_dirs {
+            Vec::new()
+        } else {
+            defaults.to_vec()
+        };
+        dirs.extend(self.exclude_dirs.iter().cloned());
+        dirs
+    }
 }
 
 /// Shared arguments for filter operations
@@ -960,6 +1767,14 @@ pub struct Cli {
     /// Suppress informational output (only print results)
     #[arg(short = 'q', long = "quiet", global = true)]
     pub quiet: bool,
+
+    /// Fail instead of creating the database if it does not exist yet
+    #[arg(long = "no-create", global = true)]
+    pub no_create: bool,
+
+    /// Show what mutating commands would change without writing anything
+    #[arg(long = "dry-run", global = true)]
+    pub dry_run: bool,
 }
 
 /// Available CLI commands
@@ -987,6 +1802,21 @@ pub enum Commands {
         #[arg(short = 'x', long = "exec", value_name = "COMMAND")]
         execute: Option<String>,
 
+        /// Execute command once with all selected files ({} becomes the quoted paths)
+        #[arg(
+            long = "exec-multi",
+            value_name = "COMMAND",
+            conflicts_with = "execute"
+        )]
+        execute_multi: Option<String>,
+
+        /// Pre-select a tag in the tag tree on launch (can be repeated)
+        ///
+        /// Unlike -t, the file list is not narrowed; the tag is only selected
+        /// in the tag tree and the file pane is focused.
+        #[arg(long = "start-tag", value_name = "TAG")]
+        start_tags: Vec<String>,
+
         /// Disable preview pane
         #[arg(long = "no-preview")]
         no_preview: bool,
@@ -1003,6 +1833,10 @@ pub enum Commands {
         #[arg(long = "preview-width", value_name = "PERCENT")]
         preview_width: Option<u8>,
 
+        /// Start with a fresh view instead of restoring the previous session
+        #[arg(long = "fresh")]
+        fresh: bool,
+
         /// Display absolute paths (overrides config)
         #[arg(long = "absolute", conflicts_with = "relative")]
         absolute: bool,
@@ -1024,6 +1858,28 @@ pub enum Commands {
         command: ConfigCommands,
     },
 
+    /// Print or install shell completions, or print shell hooks
+    #[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
+    Completions {
+        /// Shell to print completions for
+        #[arg(value_enum)]
+        shell: Option<clap_complete::Shell>,
+
+        #[command(subcommand)]
+        command: Option<CompletionsCommands>,
+    },
+
+    /// Print a file preview as shown in the browser
+    Preview {
+        /// File to preview
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Strip colors and other ANSI escapes (default when stdout is not a terminal)
+        #[arg(long)]
+        plain: bool,
+    },
+
     /// Manage databases
     Db {
         #[command(subcommand)]
@@ -1053,29 +1909,100 @@ pub enum Commands {
         #[arg(short = 't', long = "tags", value_name = "TAG", num_args = 0..)]
         tags_flag: Vec<String>,
 
-        /// File to tag (positional)
-        #[arg(value_name = "FILE", conflicts_with = "file_flag")]
-        file_pos: Option<PathBuf>,
+        /// File to tag (positional)
+        #[arg(value_name = "FILE", conflicts_with = "file_flag")]
+        file_pos: Option<PathBuf>,
+
+        /// Tags to apply (positional; prompted interactively if omitted)
+        #[arg(value_name = "TAGS", conflicts_with = "tags_flag")]
+        tags_pos: Vec<String>,
+
+        /// Skip tag canonicalization (use tags as-is, don't resolve aliases)
+        #[arg(long = "no-canonicalize")]
+        no_canonicalize: bool,
+
+        /// Derive tags from the filename by splitting its stem on separators
+        #[arg(long = "from-filename")]
+        from_filename: bool,
+
+        /// Characters that separate filename tokens (with --from-filename)
+        #[arg(
+            long = "sep",
+            value_name = "CHARS",
+            default_value = "-_. ",
+            requires = "from_filename"
+        )]
+        separators: String,
+
+        /// Skip tokens that are pure numbers (with --from-filename)
+        #[arg(long = "skip-numbers", requires = "from_filename")]
+        skip_numbers: bool,
+
+        /// Skip tokens shorter than N characters (with --from-filename)
+        #[arg(
+            long = "min-len",
+            value_name = "N",
+            default_value_t = 2,
+            requires = "from_filename"
+        )]
+        min_len: usize,
+
+        /// Tag every file under FILE, which must be a directory
+        #[arg(short = 'R', long = "recursive")]
+        recursive: bool,
+
+        /// Only tag files matching this gitignore-style glob (with -R; repeatable)
+        #[arg(long = "include", value_name = "GLOB", requires = "recursive")]
+        include: Vec<String>,
+
+        /// Skip files and directories matching this glob (with -R; repeatable)
+        #[arg(long = "exclude", value_name = "GLOB", requires = "recursive")]
+        exclude: Vec<String>,
+
+        /// Skip files ignored by .gitignore (with -R)
+        #[arg(long = "gitignore", requires = "recursive")]
+        gitignore: bool,
 
-        /// Tags to apply (positional)
-        #[arg(value_name = "TAGS", conflicts_with = "tags_flag")]
-        tags_pos: Vec<String>,
+        /// Walk into symlinked directories (with -R)
+        #[arg(long = "follow-symlinks", requires = "recursive")]
+        follow_symlinks: bool,
 
-        /// Skip tag canonicalization (use tags as-is, don't resolve aliases)
-        #[arg(long = "no-canonicalize")]
-        no_canonicalize: bool,
+        /// Show the file's tags before and after without writing them
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
 
         #[command(flatten)]
         db_args: DbArgs,
     },
 
     /// Search files by tag
-    #[command(visible_alias = "s")]
+    #[command(
+        visible_alias = "s",
+        after_help = "Examples:\n  tagr search -t project --top 10 --format json   # most-tagged project files\n  tagr search -t inbox --bottom 20                # files that need more tags"
+    )]
     Search {
         /// General query (searches both filenames and tags when -t/-f not specified)
         #[arg(value_name = "QUERY")]
         query: Option<String>,
 
+        /// Boolean tag expression, e.g. 'rust AND (test OR bench) AND -deprecated'
+        ///
+        /// Supports AND, OR, NOT, parentheses, -tag exclusion and file:GLOB
+        /// path terms. Can be combined with -f/-v, which further narrow the
+        /// results.
+        #[arg(
+            long = "expr",
+            value_name = "EXPR",
+            conflicts_with_all = ["query", "tags", "excludes", "any_tag", "all_tags"]
+        )]
+        expr: Option<String>,
+
+        /// Search tagged files by glob only, without requiring any tag
+        ///
+        /// Accepts several globs; --any-file/--all-files choose how they combine.
+        #[arg(long = "files", value_name = "GLOB", num_args = 1.., conflicts_with = "query")]
+        files: Vec<String>,
+
         #[command(flatten)]
         criteria: SearchCriteriaArgs,
 
@@ -1087,14 +2014,88 @@ pub enum Commands {
         #[arg(long = "no-hierarchy")]
         no_hierarchy: bool,
 
+        /// Match tags by scanning every file instead of using the tag index
+        ///
+        /// Slower, but gives a correct result even if the reverse index has
+        /// drifted. Compare against a normal search to debug divergence.
+        #[arg(long = "no-index")]
+        no_index: bool,
+
+        /// Show results as an aligned table (falls back to lines when piped)
+        #[arg(long = "table")]
+        table: bool,
+
+        /// Show only the N results with the most tags
+        #[arg(long = "top", value_name = "N", conflicts_with = "bottom")]
+        top: Option<usize>,
+
+        /// Show only the N results with the fewest tags
+        #[arg(long = "bottom", value_name = "N", conflicts_with = "top")]
+        bottom: Option<usize>,
+
+        /// Output format
+        #[arg(long = "format", value_name = "FORMAT", default_value = "text")]
+        format: ListFormat,
+
+        /// Print results grouped under each -t tag instead of a flat list
+        ///
+        /// Implies --any-tag; a file matching several tags is listed under each.
+        /// Cannot be combined with --all-tags.
+        #[arg(long = "group-by-tag", conflicts_with = "all_tags")]
+        group_by_tag: bool,
+
+        /// Sort results by modification time, name, size or tagging time (default: path)
+        #[arg(long = "sort", value_name = "KEY", conflicts_with_all = ["top", "bottom"])]
+        sort: Option<ListSort>,
+
+        /// Show at most N results (after sorting)
+        #[arg(long = "limit", value_name = "N")]
+        limit: Option<usize>,
+
+        /// Skip the first N results
+        #[arg(long = "offset", value_name = "N", default_value_t = 0)]
+        offset: usize,
+
+        /// Print how the search was executed (steps, candidate counts, timing) to stderr
+        #[arg(long = "explain")]
+        explain: bool,
+
+        /// Print only the number of matching files (per tag with --group-by-tag)
+        #[arg(
+            short = 'c',
+            long = "count",
+            visible_alias = "print-count",
+            conflicts_with_all = ["table", "top", "bottom", "sort", "limit", "offset"]
+        )]
+        count: bool,
+
+        /// Reuse results cached within the last SECS seconds (overrides config; 0 disables)
+        ///
+        /// Any change to the database's files or tags invalidates the cache.
+        #[arg(long = "cache-ttl", value_name = "SECS")]
+        cache_ttl: Option<u64>,
+
+        /// Delete all cached search results (runs the search too, if one is given)
+        #[arg(long = "clear-cache")]
+        clear_cache: bool,
+
         /// Display absolute paths (overrides config)
-        #[arg(long = "absolute", conflicts_with = "relative")]
+        #[arg(long = "absolute", conflicts_with_all = ["relative", "path_context"])]
         absolute: bool,
 
         /// Display relative paths (overrides config)
-        #[arg(long = "relative", conflicts_with = "absolute")]
+        #[arg(long = "relative", conflicts_with_all = ["absolute", "path_context"])]
         relative: bool,
 
+        /// Display only the last N path components, adding more where paths would look the same
+        #[arg(
+            long = "path-context",
+            value_name = "N",
+            value_parser = parse_path_context,
+            conflicts_with_all = ["absolute", "relative"]
+        )]
+        path_context: Option<usize>,
+
         #[command(flatten)]
         db_args: DbArgs,
 
@@ -1129,14 +2130,40 @@ pub enum Commands {
         #[arg(value_name = "TAGS", conflicts_with = "tags_flag")]
         tags_pos: Vec<String>,
 
+        /// Show the tags the file would be left with without writing them
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
+
+        #[command(flatten)]
+        db_args: DbArgs,
+    },
+
+    /// Move a file's tags and note to its new path after it was moved or renamed
+    #[command(visible_alias = "mv")]
+    MoveFile {
+        /// Path the file was tagged under
+        #[arg(value_name = "FROM")]
+        from: PathBuf,
+
+        /// Path the file lives at now
+        #[arg(value_name = "TO")]
+        to: PathBuf,
+
+        /// Replace the tags of TO if it is already tagged (default: merge)
+        #[arg(long = "replace")]
+        replace: bool,
+
         #[command(flatten)]
         db_args: DbArgs,
     },
 
-    /// Manage tags globally
+    /// Manage tags globally (lists tags when no subcommand is given)
     Tags {
         #[command(subcommand)]
-        command: TagsCommands,
+        command: Option<TagsCommands>,
+
+        #[command(flatten)]
+        query: TagsQueryArgs,
 
         #[command(flatten)]
         db_args: DbArgs,
@@ -1154,6 +2181,22 @@ pub enum Commands {
     /// Clean up database by removing missing files and files with no tags
     #[command(visible_alias = "c")]
     Cleanup {
+        /// Skip and report entries that cannot be decoded instead of aborting
+        #[arg(long = "skip-corrupt")]
+        skip_corrupt: bool,
+
+        /// Check at most N files, saving where the run stopped
+        #[arg(long = "incremental", value_name = "N")]
+        incremental: Option<usize>,
+
+        /// Continue after the file where the last incremental run stopped
+        #[arg(long = "resume")]
+        resume: bool,
+
+        /// Skip files verified to exist within the last SECS seconds
+        #[arg(long = "max-age-check", value_name = "SECS")]
+        max_age_check: Option<u64>,
+
         #[command(flatten)]
         db_args: DbArgs,
     },
@@ -1164,6 +2207,183 @@ pub enum Commands {
         /// What to list (files or tags)
         variant: ListVariant,
 
+        /// Show only the N files with the most tags
+        #[arg(
+            long = "most-tagged",
+            visible_alias = "top",
+            value_name = "N",
+            conflicts_with = "least_tagged"
+        )]
+        most_tagged: Option<usize>,
+
+        /// Show only the N files with the fewest tags
+        #[arg(
+            long = "least-tagged",
+            visible_alias = "bottom",
+            value_name = "N",
+            conflicts_with = "most_tagged"
+        )]
+        least_tagged: Option<usize>,
+
+        /// Show files as an aligned table (falls back to lines when piped)
+        #[arg(long = "table")]
+        table: bool,
+
+        /// Sort files by modification time, name, size or tagging time
+        #[arg(
+            long = "sort",
+            value_name = "KEY",
+            conflicts_with_all = ["most_tagged", "least_tagged"]
+        )]
+        sort: Option<ListSort>,
+
+        /// Reverse the sort order
+        #[arg(long = "reverse")]
+        reverse: bool,
+
+        /// Show at most N files (after sorting)
+        #[arg(long = "limit", value_name = "N")]
+        limit: Option<usize>,
+
+        /// Skip the first N files (after sorting)
+        #[arg(long = "offset", value_name = "N", default_value_t = 0)]
+        offset: usize,
+
+        /// Group files under a heading for each of their tags
+        #[arg(
+            long = "by-tag",
+            conflicts_with_all = ["once_per_file", "most_tagged", "least_tagged", "table", "sort", "reverse", "limit", "offset"]
+        )]
+        by_tag: bool,
+
+        /// With --by-tag, only show sections for these tags
+        #[arg(long = "only-tags", value_name = "TAG", num_args = 1.., requires = "by_tag")]
+        only_tags: Vec<String>,
+
+        /// Group files by tag, showing each file once under its first tag
+        #[arg(
+            long = "once-per-file",
+            conflicts_with_all = ["by_tag", "most_tagged", "least_tagged", "table", "sort", "reverse", "limit", "offset"]
+        )]
+        once_per_file: bool,
+
+        /// Output format
+        #[arg(long = "format", value_name = "FORMAT", default_value = "text")]
+        format: ListFormat,
+
+        /// Truncate table cells wider than N characters with `…` (tags only)
+        #[arg(long = "max-width", value_name = "N")]
+        max_width: Option<usize>,
+
+        /// Skip and report entries that cannot be decoded instead of aborting
+        #[arg(long = "skip-corrupt")]
+        skip_corrupt: bool,
+
+        /// Display absolute paths (overrides config)
+        #[arg(long = "absolute", conflicts_with = "relative")]
+        absolute: bool,
+
+        /// Display relative paths (overrides config)
+        #[arg(long = "relative", conflicts_with = "absolute")]
+        relative: bool,
+
+        #[command(flatten)]
+        db_args: DbArgs,
+    },
+
+    /// Re-apply tagging operations recorded in a TOML journal, in order
+    Replay {
+        /// Journal file with `[[ops]]` entries
+        #[arg(value_name = "JOURNAL")]
+        journal: PathBuf,
+
+        /// Report failing operations and carry on instead of stopping
+        #[arg(long = "continue-on-error")]
+        continue_on_error: bool,
+
+        /// List the operations without applying them
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
+
+        #[command(flatten)]
+        db_args: DbArgs,
+    },
+
+    /// Serve a local HTTP/JSON API over the database
+    #[cfg(feature = "server")]
+    Serve {
+        /// Port to listen on
+        #[arg(long = "port", default_value_t = crate::commands::serve::DEFAULT_PORT)]
+        port: u16,
+
+        /// Address to bind; anything other than localhost exposes the database
+        #[arg(
+            long = "bind",
+            visible_alias = "host",
+            value_name = "ADDR",
+            default_value = "127.0.0.1"
+        )]
+        bind: String,
+
+        /// Also answer requests addressed to this host name or IP (repeatable);
+        /// remote clients need one when binding to 0.0.0.0 or ::
+        #[arg(long = "allowed-host", value_name = "HOST")]
+        allowed_hosts: Vec<String>,
+
+        /// Accept `POST /file` to change tags (the API is read-only otherwise)
+        #[arg(long = "allow-write")]
+        allow_write: bool,
+
+        #[command(flatten)]
+        db_args: DbArgs,
+    },
+
+    /// Keep the database open and answer searches over a Unix socket
+    ///
+    /// While it runs, plain tag searches (`tagr search -t ...`) are answered
+    /// by the daemon. Other commands ask it to close the database while they
+    /// run, then it opens the database again.
+    #[cfg(unix)]
+    Daemon {
+        /// Stop the running daemon
+        #[arg(long = "stop")]
+        stop: bool,
+
+        #[command(flatten)]
+        db_args: DbArgs,
+    },
+
+    /// Find files with identical tag sets or identical content
+    Dedup {
+        /// Compare files by tag set or by content
+        #[arg(long = "by", value_name = "KEY", default_value = "tags")]
+        by: DedupBy,
+
+        /// Run a command on each duplicate, keeping the first file of each group
+        #[arg(short = 'x', long = "exec", value_name = "COMMAND")]
+        execute: Option<String>,
+
+        /// Display absolute paths (overrides config)
+        #[arg(long = "absolute", conflicts_with = "relative")]
+        absolute: bool,
+
+        /// Display relative paths (overrides config)
+        #[arg(long = "relative", conflicts_with = "absolute")]
+        relative: bool,
+
+        #[command(flatten)]
+        db_args: DbArgs,
+    },
+
+    /// Show a file's tags, note and the saved filters that match it
+    Get {
+        /// File to look up
+        file: PathBuf,
+
+        /// Print the record as JSON
+        #[arg(long = "json")]
+        json: bool,
+
         /// Display absolute paths (overrides config)
         #[arg(long = "absolute", conflicts_with = "relative")]
         absolute: bool,
@@ -1176,6 +2396,52 @@ pub enum Commands {
         db_args: DbArgs,
     },
 
+    /// Exit 0 if a file has the tags, 1 if not and 2 on errors
+    ///
+    /// Prints nothing unless --verbose, so it can be used in scripts:
+    /// `tagr has notes.md draft && echo "still a draft"`.
+    Has {
+        /// File to check
+        file: PathBuf,
+
+        /// Tags to look for
+        #[arg(value_name = "TAG", required = true)]
+        tags: Vec<String>,
+
+        /// Require every tag (default)
+        #[arg(long = "all", conflicts_with = "any")]
+        all: bool,
+
+        /// Require at least one of the tags
+        #[arg(long = "any")]
+        any: bool,
+
+        /// Print which tags the file has or lacks
+        #[arg(short = 'v', long = "verbose")]
+        verbose: bool,
+
+        #[command(flatten)]
+        db_args: DbArgs,
+    },
+
+    /// Export the database to other formats
+    Export {
+        #[command(subcommand)]
+        command: ExportCommands,
+
+        #[command(flatten)]
+        db_args: DbArgs,
+    },
+
+    /// Import file-tag pairs from other formats
+    Import {
+        #[command(subcommand)]
+        command: ImportCommands,
+
+        #[command(flatten)]
+        db_args: DbArgs,
+    },
+
     /// Manage file notes and descriptions
     #[command(visible_alias = "n")]
     Note {
@@ -1206,6 +2472,16 @@ impl Commands {
                 tags_flag,
                 tags_pos,
                 no_canonicalize,
+                from_filename,
+                separators,
+                skip_numbers,
+                min_len,
+                recursive,
+                include,
+                exclude,
+                gitignore,
+                follow_symlinks,
+                dry_run,
                 ..
             } => {
                 let file = file_flag.clone().or_else(|| file_pos.clone());
@@ -1218,6 +2494,16 @@ impl Commands {
                     file,
                     tags,
                     no_canonicalize: *no_canonicalize,
+                    from_filename: *from_filename,
+                    separators: separators.clone(),
+                    skip_numbers: *skip_numbers,
+                    min_len: *min_len,
+                    recursive: *recursive,
+                    include: include.clone(),
+                    exclude: exclude.clone(),
+                    gitignore: *gitignore,
+                    follow_symlinks: *follow_symlinks,
+                    dry_run: *dry_run,
                 })
             }
             _ => None,
@@ -1225,29 +2511,32 @@ impl Commands {
     }
 
     /// Helper method to get search parameters from search command
+    ///
+    /// Multiple tags are combined with `default_tag_mode` unless `--any-tag`
+    /// or `--all-tags` is given.
     #[must_use]
-    pub fn get_search_params(&self) -> Option<SearchParams> {
+    pub fn get_search_params(&self, default_tag_mode: SearchMode) -> Option<SearchParams> {
         match self {
             Self::Search {
                 query,
                 criteria,
                 no_hierarchy,
+                no_index,
+                files,
                 ..
             } => Some(SearchParams {
                 query: query.clone(),
                 tags: criteria.tags.clone(),
-                tag_mode: if criteria.any_tag {
-                    SearchMode::Any
-                } else {
-                    SearchMode::All
-                },
+                tag_mode: criteria.tag_mode_or(default_tag_mode),
                 file_patterns: criteria.file_patterns.clone(),
+                file_only_patterns: files.clone(),
                 file_mode: if criteria.any_file {
                     SearchMode::Any
                 } else {
                     SearchMode::All
                 },
                 exclude_tags: criteria.excludes.clone(),
+                exclude_file_patterns: criteria.exclude_file_patterns.clone(),
                 regex_tag: criteria.regex_tag,
                 regex_file: criteria.regex_file,
                 glob_files: criteria.glob_files,
@@ -1257,46 +2546,62 @@ impl Commands {
                 } else {
                     SearchMode::All
                 },
+                exclude_virtual_tags: criteria.exclude_virtual_tags.clone(),
                 no_hierarchy: *no_hierarchy,
+                expression: None,
+                no_index: *no_index,
             }),
             _ => None,
         }
     }
 
     /// Get browse command context
+    ///
+    /// Tags given on the command line are combined with `default_tag_mode`
+    /// unless `--any-tag` or `--all-tags` is given.
     #[must_use]
-    pub fn get_browse_context(&self) -> Option<BrowseContext> {
+    pub fn get_browse_context(&self, default_tag_mode: SearchMode) -> Option<BrowseContext> {
         match self {
             Self::Browse {
                 query,
                 criteria,
                 no_hierarchy,
                 execute,
+                execute_multi,
+                start_tags,
                 no_preview,
                 preview_lines,
                 preview_position,
                 preview_width,
+                fresh,
                 ..
             } => {
                 let search_params = if query.is_some()
                     || !criteria.tags.is_empty()
                     || !criteria.file_patterns.is_empty()
                     || !criteria.excludes.is_empty()
+                    || !criteria.exclude_file_patterns.is_empty()
                     || !criteria.virtual_tags.is_empty()
+                    || !criteria.exclude_virtual_tags.is_empty()
                 {
                     Some(SearchParams {
                         query: query.clone(),
                         tags: criteria.tags.clone(),
-                        tag_mode: SearchMode::Any,
+                        tag_mode: criteria.tag_mode_or(default_tag_mode),
                         file_patterns: criteria.file_patterns.clone(),
+                        file_only_patterns: Vec::new(),
                         file_mode: SearchMode::Any,
                         exclude_tags: criteria.excludes.clone(),
+                        exclude_file_patterns: criteria.exclude_file_patterns.clone(),
                         regex_tag: false,
                         regex_file: false,
                         glob_files: false,
                         virtual_tags: criteria.virtual_tags.clone(),
                         virtual_mode: SearchMode::Any,
+                        exclude_virtual_tags: criteria.exclude_virtual_tags.clone(),
                         no_hierarchy: *no_hierarchy,
+                        expression: None,
+                        no_index: false,
                     })
                 } else {
                     None
@@ -1304,13 +2609,20 @@ impl Commands {
 
                 Some(BrowseContext {
                     search_params,
-                    execute_cmd: execute.clone(),
+                    execute_cmd: execute.clone().or_else(|| execute_multi.clone()),
+                    execute_mode: if execute_multi.is_some() {
+                        ExecuteMode::AllFiles
+                    } else {
+                        ExecuteMode::PerFile
+                    },
                     preview_overrides: PreviewOverrides {
                         no_preview: *no_preview,
                         preview_lines: *preview_lines,
                         preview_position: preview_position.clone(),
                         preview_width: *preview_width,
                     },
+                    fresh: *fresh,
+                    start_tags: start_tags.clone(),
                 })
             }
             _ => None,
@@ -1327,6 +2639,7 @@ impl Commands {
                 tags_flag,
                 tags_pos,
                 all,
+                dry_run,
                 ..
             } => {
                 let file = file_flag.clone().or_else(|| file_pos.clone());
@@ -1339,6 +2652,7 @@ impl Commands {
                     file,
                     tags,
                     all: *all,
+                    dry_run: *dry_run,
                 })
             }
             _ => None,
@@ -1353,10 +2667,21 @@ impl Commands {
             | Self::Tag { db_args, .. }
             | Self::Search { db_args, .. }
             | Self::Untag { db_args, .. }
+            | Self::MoveFile { db_args, .. }
             | Self::Tags { db_args, .. }
             | Self::Bulk { db_args, .. }
-            | Self::Cleanup { db_args }
-            | Self::List { db_args, .. } => db_args.db.clone(),
+            | Self::Cleanup { db_args, .. }
+            | Self::List { db_args, .. }
+            | Self::Dedup { db_args, .. }
+            | Self::Get { db_args, .. }
+            | Self::Has { db_args, .. }
+            | Self::Replay { db_args, .. }
+            | Self::Export { db_args, .. }
+            | Self::Import { db_args, .. } => db_args.db.clone(),
+            #[cfg(feature = "server")]
+            Self::Serve { db_args, .. } => db_args.db.clone(),
+            #[cfg(unix)]
+            Self::Daemon { db_args, .. } => db_args.db.clone(),
             _ => None,
         }
     }
@@ -1376,6 +2701,9 @@ impl Commands {
                 | BulkCommands::DeleteFiles { dry_run, yes, .. }
                 | BulkCommands::PropagateByDir { dry_run, yes, .. }
                 | BulkCommands::PropagateByExt { dry_run, yes, .. }
+                | BulkCommands::PropagateByFilename { dry_run, yes, .. }
+                | BulkCommands::PropagateFromManifest { dry_run, yes, .. }
+                | BulkCommands::Propagate { dry_run, yes, .. }
                 | BulkCommands::Transform { dry_run, yes, .. } => (*dry_run, *yes),
             };
             Some((command, dry_run, yes))
@@ -1383,6 +2711,59 @@ impl Commands {
             None
         }
     }
+
+    /// Name of the command if it writes but cannot preview its changes
+    ///
+    /// These commands reject `--dry-run` instead of ignoring it. Read-only
+    /// commands and those that preview their changes return `None`.
+    #[must_use]
+    pub const fn dry_run_unsupported(&self) -> Option<&'static str> {
+        use crate::commands::note::NoteSubcommand;
+
+        match self {
+            Self::Browse { .. } => Some("browse"),
+            #[cfg(feature = "server")]
+            Self::Serve { .. } => Some("serve"),
+            Self::Note { command, .. } => match command {
+                NoteSubcommand::Edit(_) => Some("note edit"),
+                NoteSubcommand::Add(_) => Some("note add"),
+                _ => None,
+            },
+            #[cfg(feature = "sqlite")]
+            Self::Import {
+                command: ImportCommands::Sqlite { .. },
+                ..
+            } => Some("import sqlite"),
+            Self::Db { command } => match command {
+                DbCommands::Add { .. } => Some("db add"),
+                DbCommands::Remove { .. } => Some("db remove"),
+                DbCommands::SetDefault { .. } => Some("db set-default"),
+                DbCommands::Compact { .. } => Some("db compact"),
+                DbCommands::List { .. } | DbCommands::Info { .. } => None,
+            },
+            Self::Config {
+                command: ConfigCommands::Set { .. },
+            } => Some("config set"),
+            Self::Filter { command } => match command {
+                FilterCommands::Create { .. } => Some("filter create"),
+                FilterCommands::Delete { .. } => Some("filter delete"),
+                FilterCommands::Rename { .. } => Some("filter rename"),
+                FilterCommands::Import { .. } => Some("filter import"),
+                _ => None,
+            },
+            Self::Alias { command } => match command {
+                AliasCommands::Add { .. } => Some("alias add"),
+                AliasCommands::Remove { .. } => Some("alias remove"),
+                AliasCommands::Rename { .. } => Some("alias rename"),
+                _ => None,
+            },
+            Self::Completions {
+                command: Some(CompletionsCommands::Install { .. }),
+                ..
+            } => Some("completions install"),
+            _ => None,
+        }
+    }
 }
 
 impl Cli {
@@ -1405,19 +2786,26 @@ impl Cli {
                 any_file: false,
                 all_files: false,
                 excludes: Vec::new(),
+                exclude_file_patterns: vec![],
                 regex_tag: false,
                 regex_file: false,
                 glob_files: false,
                 virtual_tags: Vec::new(),
                 any_virtual: false,
                 all_virtual: false,
+                exclude_virtual_tags: Vec::new(),
+                exclude_dirs: Vec::new(),
+                include_all_dirs: false,
             },
             no_hierarchy: false,
             execute: None,
+            execute_multi: None,
+            start_tags: Vec::new(),
             no_preview: false,
             preview_lines: None,
             preview_position: None,
             preview_width: None,
+            fresh: false,
             absolute: false,
             relative: false,
             db_args: DbArgs { db: None },
@@ -1443,6 +2831,10 @@ impl Cli {
         };
 
         match &self.command {
+            Some(Commands::Search {
+                path_context: Some(n),
+                ..
+            }) => Some(PathFormat::LastN(*n)),
             Some(
                 Commands::Browse {
                     absolute, relative, ..
@@ -1453,6 +2845,12 @@ impl Cli {
                 | Commands::List {
                     absolute, relative, ..
                 }
+                | Commands::Dedup {
+                    absolute, relative, ..
+                }
+                | Commands::Get {
+                    absolute, relative, ..
+                }
                 | Commands::Note {
                     absolute, relative, ..
                 },
@@ -1478,6 +2876,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_global_dry_run() {
+        let cli = Cli::parse_from(["tagr", "--dry-run", "cleanup"]);
+        assert!(cli.dry_run);
+
+        let cli = Cli::parse_from(["tagr", "tags", "remove", "old", "--dry-run"]);
+        assert!(cli.dry_run);
+
+        // Commands with their own --dry-run see the global flag and vice versa
+        let cli = Cli::parse_from(["tagr", "--dry-run", "tag", "a.txt", "rust"]);
+        let ctx = cli.command.as_ref().unwrap().get_tag_context().unwrap();
+        assert!(ctx.dry_run);
+
+        let cli = Cli::parse_from(["tagr", "bulk", "tag", "-n", "a", "b"]);
+        assert!(cli.dry_run);
+    }
+
     #[test]
     fn test_parse_tag_with_positional() {
         let cli = Cli::parse_from(["tagr", "tag", "test.txt", "tag1", "tag2"]);
@@ -1494,7 +2909,12 @@ mod tests {
     fn test_parse_search_with_single_tag() {
         let cli = Cli::parse_from(["tagr", "search", "-t", "mytag"]);
         if let Some(Commands::Search { .. }) = cli.command {
-            let params = cli.command.as_ref().unwrap().get_search_params().unwrap();
+            let params = cli
+                .command
+                .as_ref()
+                .unwrap()
+                .get_search_params(SearchMode::All)
+                .unwrap();
             assert_eq!(params.tags, vec!["mytag".to_string()]);
             assert_eq!(params.tag_mode, SearchMode::All);
         } else {
@@ -1506,7 +2926,12 @@ mod tests {
     fn test_parse_search_with_multiple_tags() {
         let cli = Cli::parse_from(["tagr", "search", "-t", "tag1", "-t", "tag2", "--any-tag"]);
         if let Some(Commands::Search { .. }) = cli.command {
-            let params = cli.command.as_ref().unwrap().get_search_params().unwrap();
+            let params = cli
+                .command
+                .as_ref()
+                .unwrap()
+                .get_search_params(SearchMode::All)
+                .unwrap();
             assert_eq!(params.tags, vec!["tag1".to_string(), "tag2".to_string()]);
             assert_eq!(params.tag_mode, SearchMode::Any);
         } else {
@@ -1528,7 +2953,12 @@ mod tests {
             "--any-file",
         ]);
         if let Some(Commands::Search { .. }) = cli.command {
-            let params = cli.command.as_ref().unwrap().get_search_params().unwrap();
+            let params = cli
+                .command
+                .as_ref()
+                .unwrap()
+                .get_search_params(SearchMode::All)
+                .unwrap();
             assert_eq!(params.tags, vec!["rust".to_string()]);
             assert_eq!(
                 params.file_patterns,
@@ -1553,7 +2983,12 @@ mod tests {
             "old",
         ]);
         if let Some(Commands::Search { .. }) = cli.command {
-            let params = cli.command.as_ref().unwrap().get_search_params().unwrap();
+            let params = cli
+                .command
+                .as_ref()
+                .unwrap()
+                .get_search_params(SearchMode::All)
+                .unwrap();
             assert_eq!(
                 params.exclude_tags,
                 vec!["deprecated".to_string(), "old".to_string()]
@@ -1581,18 +3016,158 @@ mod tests {
     fn test_browse_with_exec() {
         let cli = Cli::parse_from(["tagr", "browse", "-x", "cat {}"]);
         if let Some(Commands::Browse { .. }) = cli.command {
-            let ctx = cli.command.as_ref().unwrap().get_browse_context().unwrap();
+            let ctx = cli
+                .command
+                .as_ref()
+                .unwrap()
+                .get_browse_context(SearchMode::Any)
+                .unwrap();
             assert_eq!(ctx.execute_cmd, Some("cat {}".to_string()));
         } else {
             panic!("Expected Browse command");
         }
     }
 
+    #[test]
+    fn test_browse_with_exec_multi() {
+        let cli = Cli::parse_from(["tagr", "browse", "--exec-multi", "vim {}"]);
+        let ctx = cli
+            .command
+            .as_ref()
+            .unwrap()
+            .get_browse_context(SearchMode::Any)
+            .unwrap();
+        assert_eq!(ctx.execute_cmd, Some("vim {}".to_string()));
+        assert_eq!(ctx.execute_mode, ExecuteMode::AllFiles);
+
+        let cli = Cli::parse_from(["tagr", "browse", "-x", "cat {}"]);
+        let ctx = cli
+            .command
+            .as_ref()
+            .unwrap()
+            .get_browse_context(SearchMode::Any)
+            .unwrap();
+        assert_eq!(ctx.execute_mode, ExecuteMode::PerFile);
+
+        assert!(
+            Cli::try_parse_from(["tagr", "browse", "-x", "a {}", "--exec-multi", "b {}"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_quote_paths_handles_spaces_and_quotes() {
+        let files = vec![
+            PathBuf::from("/tmp/plain.txt"),
+            PathBuf::from("/tmp/with space.txt"),
+            PathBuf::from("/tmp/it's.txt"),
+        ];
+        assert_eq!(
+            quote_paths(&files).unwrap(),
+            r#"/tmp/plain.txt '/tmp/with space.txt' "/tmp/it's.txt""#
+        );
+    }
+
+    #[test]
+    fn test_browse_with_start_tags() {
+        let cli = Cli::parse_from([
+            "tagr",
+            "browse",
+            "--start-tag",
+            "rust",
+            "--start-tag",
+            "notes",
+        ]);
+        let ctx = cli
+            .command
+            .as_ref()
+            .unwrap()
+            .get_browse_context(SearchMode::Any)
+            .unwrap();
+        assert_eq!(
+            ctx.start_tags,
+            vec!["rust".to_string(), "notes".to_string()]
+        );
+        assert!(ctx.search_params.is_none());
+    }
+
+    #[test]
+    fn test_list_grouping_flags() {
+        let cli = Cli::parse_from([
+            "tagr",
+            "list",
+            "files",
+            "--by-tag",
+            "--only-tags",
+            "rust",
+            "python",
+        ]);
+        let Some(Commands::List {
+            by_tag, only_tags, ..
+        }) = cli.command
+        else {
+            panic!("expected list command");
+        };
+        assert!(by_tag);
+        assert_eq!(only_tags, vec!["rust".to_string(), "python".to_string()]);
+
+        assert!(
+            Cli::try_parse_from(["tagr", "list", "files", "--by-tag", "--once-per-file"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["tagr", "list", "files", "--only-tags", "rust"]).is_err());
+    }
+
+    #[test]
+    fn test_search_count_flag() {
+        for flag in ["-c", "--count", "--print-count"] {
+            let cli = Cli::parse_from(["tagr", "search", "-t", "rust", flag, "--group-by-tag"]);
+            let Some(Commands::Search {
+                count,
+                group_by_tag,
+                ..
+            }) = cli.command
+            else {
+                panic!("expected search command");
+            };
+            assert!(count && group_by_tag);
+        }
+
+        assert!(
+            Cli::try_parse_from(["tagr", "search", "-t", "rust", "-c", "--limit", "5"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["tagr", "search", "-t", "rust", "-c", "--top", "5"]).is_err());
+    }
+
+    #[test]
+    fn test_tags_query_flags() {
+        let cli = Cli::parse_from(["tagr", "tags", "--for", "a.rs", "--prefix", "lang:", "-0"]);
+        let Some(Commands::Tags { command, query, .. }) = cli.command else {
+            panic!("expected tags command");
+        };
+        assert!(command.is_none());
+        assert_eq!(query.file, Some(PathBuf::from("a.rs")));
+        assert_eq!(query.prefix.as_deref(), Some("lang:"));
+        assert!(query.print0);
+
+        // --prefix and --print0 also follow the list subcommand
+        let cli = Cli::parse_from(["tagr", "tags", "list", "--prefix", "lang:", "--print0"]);
+        let Some(Commands::Tags { command, query, .. }) = cli.command else {
+            panic!("expected tags command");
+        };
+        assert!(matches!(command, Some(TagsCommands::List { .. })));
+        assert_eq!(query.prefix.as_deref(), Some("lang:"));
+        assert!(query.print0);
+    }
+
     #[test]
     fn test_browse_with_query() {
         let cli = Cli::parse_from(["tagr", "browse", "documents"]);
         if let Some(Commands::Browse { .. }) = cli.command {
-            let ctx = cli.command.as_ref().unwrap().get_browse_context().unwrap();
+            let ctx = cli
+                .command
+                .as_ref()
+                .unwrap()
+                .get_browse_context(SearchMode::Any)
+                .unwrap();
             assert!(ctx.search_params.is_some());
             let params = ctx.search_params.unwrap();
             assert_eq!(params.query, Some("documents".to_string()));
@@ -1614,7 +3189,12 @@ mod tests {
             "*.md",
         ]);
         if let Some(Commands::Browse { .. }) = cli.command {
-            let ctx = cli.command.as_ref().unwrap().get_browse_context().unwrap();
+            let ctx = cli
+                .command
+                .as_ref()
+                .unwrap()
+                .get_browse_context(SearchMode::Any)
+                .unwrap();
             assert!(ctx.search_params.is_some());
             let params = ctx.search_params.unwrap();
             assert_eq!(params.tags, vec!["documents".to_string()]);
@@ -1625,11 +3205,129 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_default_tag_mode_applies_without_flags() {
+        let cli = Cli::parse_from(["tagr", "search", "-t", "a", "-t", "b"]);
+        let command = cli.command.as_ref().unwrap();
+        let params = command.get_search_params(SearchMode::Any).unwrap();
+        assert_eq!(params.tag_mode, SearchMode::Any);
+        let params = command.get_search_params(SearchMode::All).unwrap();
+        assert_eq!(params.tag_mode, SearchMode::All);
+
+        let cli = Cli::parse_from(["tagr", "browse", "-t", "a", "-t", "b"]);
+        let ctx = cli
+            .command
+            .as_ref()
+            .unwrap()
+            .get_browse_context(SearchMode::All)
+            .unwrap();
+        assert_eq!(ctx.search_params.unwrap().tag_mode, SearchMode::All);
+
+        let cli = Cli::parse_from(["tagr", "bulk", "tag", "c", "-t", "a", "-t", "b"]);
+        if let Some(Commands::Bulk {
+            command: BulkCommands::Tag { criteria, .. },
+            ..
+        }) = cli.command
+        {
+            let params = SearchParams::from_criteria(&criteria, SearchMode::Any);
+            assert_eq!(params.tag_mode, SearchMode::Any);
+        } else {
+            panic!("Expected bulk tag command");
+        }
+    }
+
+    #[test]
+    fn test_tag_mode_flags_override_default() {
+        let cli = Cli::parse_from(["tagr", "search", "-t", "a", "-t", "b", "--all-tags"]);
+        let params = cli
+            .command
+            .as_ref()
+            .unwrap()
+            .get_search_params(SearchMode::Any)
+            .unwrap();
+        assert_eq!(params.tag_mode, SearchMode::All);
+
+        let cli = Cli::parse_from(["tagr", "search", "-t", "a", "-t", "b", "--any-tag"]);
+        let params = cli
+            .command
+            .as_ref()
+            .unwrap()
+            .get_search_params(SearchMode::All)
+            .unwrap();
+        assert_eq!(params.tag_mode, SearchMode::Any);
+
+        let cli = Cli::parse_from(["tagr", "browse", "-t", "a", "--all-tags"]);
+        let ctx = cli
+            .command
+            .as_ref()
+            .unwrap()
+            .get_browse_context(SearchMode::Any)
+            .unwrap();
+        assert_eq!(ctx.search_params.unwrap().tag_mode, SearchMode::All);
+    }
+
+    #[test]
+    fn test_parse_bulk_transform_flags() {
+        use crate::commands::bulk::TagTransformation;
+
+        let cli = Cli::parse_from([
+            "tagr",
+            "bulk",
+            "transform",
+            "--add-prefix",
+            "feat:",
+            "--strip-prefix",
+            "feature-",
+            "--lowercase",
+            "--tag-filter",
+            "feature-*",
+        ]);
+        let Some(Commands::Bulk {
+            command:
+                BulkCommands::Transform {
+                    kind,
+                    transformation,
+                    tag_filter,
+                    ..
+                },
+            ..
+        }) = cli.command
+        else {
+            panic!("Expected Bulk Transform command");
+        };
+
+        assert!(kind.is_none());
+        assert_eq!(tag_filter.as_deref(), Some("feature-*"));
+        assert_eq!(
+            transformation.transformations(),
+            vec![
+                TagTransformation::StripPrefix {
+                    prefix: "feature-".into()
+                },
+                TagTransformation::Lowercase,
+                TagTransformation::AddPrefix {
+                    prefix: "feat:".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_bulk_transform_requires_transformation() {
+        let result = Cli::try_parse_from(["tagr", "bulk", "transform"]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_parse_search_with_general_query() {
         let cli = Cli::parse_from(["tagr", "search", "document"]);
         if let Some(Commands::Search { .. }) = cli.command {
-            let params = cli.command.as_ref().unwrap().get_search_params().unwrap();
+            let params = cli
+                .command
+                .as_ref()
+                .unwrap()
+                .get_search_params(SearchMode::All)
+                .unwrap();
             assert_eq!(params.query, Some("document".to_string()));
             assert!(params.tags.is_empty());
             assert!(params.file_patterns.is_empty());
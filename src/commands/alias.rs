@@ -22,6 +22,10 @@ pub fn execute_alias_command(
             remove_alias(alias)?;
             Ok(())
         }
+        AliasCommands::Rename { old, new } => {
+            rename_alias(old, new)?;
+            Ok(())
+        }
         AliasCommands::List => {
             list_aliases()?;
             Ok(())
@@ -64,7 +68,10 @@ fn remove_alias(alias: &str) -> Result<(), SchemaError> {
     // Get the canonical before removing (for display)
     let canonical = schema.canonicalize(alias);
 
-    schema.remove_alias(alias)?;
+    if !schema.remove_alias(alias)? {
+        println!("{} No alias named {}", "ℹ".blue().bold(), alias.cyan());
+        return Ok(());
+    }
     schema.save()?;
 
     println!(
@@ -78,6 +85,26 @@ fn remove_alias(alias: &str) -> Result<(), SchemaError> {
     Ok(())
 }
 
+/// Rename an alias
+fn rename_alias(old: &str, new: &str) -> Result<(), SchemaError> {
+    let mut schema = load_default_schema()?;
+
+    schema.rename_alias(old, new)?;
+    schema.save()?;
+
+    println!(
+        "{} Renamed alias: {} {} {} {} {}",
+        "✓".green().bold(),
+        old.cyan(),
+        "→".dimmed(),
+        new.cyan(),
+        "→".dimmed(),
+        schema.canonicalize(new).yellow()
+    );
+
+    Ok(())
+}
+
 /// List all aliases
 fn list_aliases() -> Result<(), SchemaError> {
     let schema = load_default_schema()?;
@@ -364,6 +391,23 @@ mod tests {
         assert_eq!(schema.canonicalize("js"), "js");
     }
 
+    #[test]
+    fn test_rename_and_remove_persist() {
+        let (mut schema, path, _dir) = create_test_schema();
+        schema.add_alias("js", "javascript").unwrap();
+        schema.add_alias("py", "python").unwrap();
+        schema.rename_alias("js", "ecmascript").unwrap();
+        assert!(schema.remove_alias("py").unwrap());
+        schema.save().unwrap();
+
+        let reloaded = TagSchema::load(&path).unwrap();
+        assert_eq!(
+            reloaded.list_aliases(),
+            [("ecmascript".to_string(), "javascript".to_string())]
+        );
+        assert_eq!(reloaded.get_aliases("javascript"), ["ecmascript"]);
+    }
+
     #[test]
     fn test_list_empty_aliases() {
         let (schema, _path, _dir) = create_test_schema();
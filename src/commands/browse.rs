@@ -6,7 +6,7 @@ use crate::{
         session::{BrowseConfig, BrowseSession, HelpText, PhaseSettings},
         ui::BrowseController,
     },
-    cli::{PreviewOverrides, SearchParams},
+    cli::{ExecuteMode, PreviewOverrides, SearchParams},
     config::{self, PreviewConfig},
     db::Database,
     filters::{FilterCriteria, FilterManager},
@@ -15,6 +15,8 @@ use crate::{
     ui::ratatui_adapter::RatatuiFinder,
 };
 
+use std::path::PathBuf;
+
 type Result<T> = std::result::Result<T, TagrError>;
 
 impl From<config::PathFormat> for crate::browse::session::PathFormat {
@@ -22,12 +24,25 @@ impl From<config::PathFormat> for crate::browse::session::PathFormat {
         match format {
             config::PathFormat::Absolute => Self::Absolute,
             config::PathFormat::Relative => Self::Relative,
+            config::PathFormat::LastN(n) => Self::LastN(n),
         }
     }
 }
 
 /// Execute the browse command
 ///
+/// `start_tags` are pre-selected in the tag tree on launch. Tags entered in the
+/// add-tag action are cleaned up with `tag_normalization`. `execute_cmd` runs
+/// on the selected files once per file or once for all of them, depending on
+/// `execute_mode`.
+///
+/// Images are drawn inline in the preview pane when `image_preview` is true
+/// and the terminal supports a graphics protocol. At most `max_preview_bytes`
+/// of a file are read for its preview.
+///
+/// When `tui_state_file` is set, the TUI state is saved there on exit and, if
+/// `restore_tui_state` is true, restored from it on startup.
+///
 /// # Errors
 /// Returns an error if database operations fail or if the browse operation encounters issues
 #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
@@ -37,7 +52,16 @@ pub fn execute(
     filter_name: Option<&str>,
     save_filter: Option<(&str, Option<&str>)>,
     execute_cmd: Option<String>,
+    execute_mode: ExecuteMode,
+    exclude_dirs: Vec<String>,
     preview_overrides: Option<&PreviewOverrides>,
+    start_tags: Vec<String>,
+    tag_normalization: config::TagNormalization,
+    image_preview: bool,
+    max_preview_bytes: u64,
+    max_items: usize,
+    tui_state_file: Option<PathBuf>,
+    restore_tui_state: bool,
     path_format: config::PathFormat,
     quiet: bool,
 ) -> Result<()> {
@@ -64,7 +88,10 @@ pub fn execute(
     let preview_config = if preview_overrides.as_ref().is_some_and(|o| o.no_preview) {
         None
     } else {
-        let mut config = PreviewConfig::default();
+        let mut config = PreviewConfig {
+            max_preview_bytes,
+            ..PreviewConfig::default()
+        };
         if let Some(overrides) = &preview_overrides
             && let Some(lines) = overrides.preview_lines
         {
@@ -99,6 +126,7 @@ pub fn execute(
             ("ctrl+d".to_string(), "Delete from database".to_string()),
             ("ctrl+o".to_string(), "Open file".to_string()),
             ("ctrl+y".to_string(), "Copy path".to_string()),
+            ("alt+y".to_string(), "Copy CLI command".to_string()),
             ("ESC".to_string(), "Cancel".to_string()),
         ]),
     };
@@ -108,12 +136,20 @@ pub fn execute(
         path_format: path_format.into(),
         tag_phase_settings,
         file_phase_settings,
+        tui_state_file,
+        restore_tui_state,
+        exclude_dirs,
+        start_tags,
+        tag_normalization,
+        max_items,
     };
 
     let session =
         BrowseSession::new(db, config).map_err(|e| TagrError::BrowseError(e.to_string()))?;
 
-    let finder = RatatuiFinder::with_styled_preview(100); // Max 100 lines of syntax-highlighted preview
+    // Max 100 lines of syntax-highlighted preview
+    let finder = RatatuiFinder::with_styled_preview(100, max_preview_bytes)
+        .with_image_preview(image_preview);
 
     let controller = BrowseController::new(session, finder);
 
@@ -141,7 +177,16 @@ pub fn execute(
                 if !quiet {
                     println!("\n=== Executing Command ===");
                 }
-                crate::cli::execute_command_on_files(&result.selected_files, &cmd_template, quiet);
+                match execute_mode {
+                    ExecuteMode::PerFile => {
+                        crate::cli::execute_per_file(&result.selected_files, &cmd_template, quiet);
+                    }
+                    ExecuteMode::AllFiles => crate::cli::execute_on_all_files(
+                        &result.selected_files,
+                        &cmd_template,
+                        quiet,
+                    )?,
+                }
             }
 
             if let Some((name, desc)) = save_filter {
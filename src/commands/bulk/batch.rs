@@ -143,10 +143,10 @@ pub fn batch_from_file(
                 }
             }
             Err(e) => {
-                summary.add_error(format!("{}: {}", entry.file.display(), e));
                 if !quiet {
                     eprintln!("✗ Failed to tag {}: {}", entry.file.display(), e);
                 }
+                summary.add_failure(e.with_context(&entry.file, "batch_from_file"));
             }
         }
     }
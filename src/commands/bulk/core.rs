@@ -57,6 +57,8 @@ pub struct BulkOpSummary {
     pub skipped_condition: usize,
     pub errors: usize,
     pub error_messages: Vec<String>,
+    /// Errors recorded with [`BulkOpSummary::add_failure`], with their context
+    pub failures: Vec<TagrError>,
 }
 
 impl BulkOpSummary {
@@ -77,6 +79,14 @@ impl BulkOpSummary {
         self.errors += 1;
         self.error_messages.push(msg);
     }
+    /// Record a failure, keeping the error itself alongside its message
+    ///
+    /// Bulk operations pass errors built with [`TagrError::with_context`],
+    /// so the message names the operation and the file.
+    pub fn add_failure(&mut self, error: TagrError) {
+        self.add_error(error.to_string());
+        self.failures.push(error);
+    }
     pub fn print(&self, operation: &str) {
         println!("\n{}", format!("=== {operation} Summary ===").bold());
         println!("  {} {}", "✓ Success:".green(), self.success);
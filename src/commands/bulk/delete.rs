@@ -78,10 +78,10 @@ pub fn bulk_delete_files(
                 }
             }
             Err(e) => {
-                summary.add_error(format!("{}: {}", file.display(), e));
                 if !quiet {
                     eprintln!("✗ Failed to delete {}: {}", file.display(), e);
                 }
+                summary.add_failure(e.with_context(&file, "bulk_delete_files"));
             }
         }
     }
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::path::Path;
 
 use colored::Colorize;
@@ -5,7 +6,7 @@ use dialoguer::Confirm;
 
 use super::batch::{BatchFormat, format_mismatch_hint_parsed};
 use super::core::{BulkOpSummary, SkipReason};
-use crate::{Pair, TagrError, db::Database};
+use crate::{Pair, TagrError, db::Database, schema::HIERARCHY_DELIMITER};
 
 type Result<T> = std::result::Result<T, TagrError>;
 
@@ -71,88 +72,217 @@ pub fn bulk_map_tags(
         }
     }
     let mut summary = BulkOpSummary::new();
-    for mapping in mappings {
-        if mapping.from == mapping.to {
+    for mapping in &mappings {
+        apply_mapping(db, mapping, &mut summary, quiet)?;
+    }
+    if !quiet {
+        summary.print("Map Tags");
+    }
+    Ok(())
+}
+
+/// Rename every tag under `old_prefix` to sit under `new_prefix` instead.
+///
+/// Prefixes are whole hierarchy segments: `proj` and `proj:` both match the
+/// tag `proj` itself and anything below it (`proj:web`), but never `project`.
+/// Each affected tag goes through the same mapping logic as
+/// [`bulk_map_tags`], so collisions with existing tags are merged.
+///
+/// # Errors
+/// Returns `TagrError::InvalidInput` for empty or identical prefixes, and
+/// database errors during lookups and updates.
+pub fn rename_tag_prefix(
+    db: &Database,
+    old_prefix: &str,
+    new_prefix: &str,
+    dry_run: bool,
+    yes: bool,
+    quiet: bool,
+) -> Result<()> {
+    let old_prefix = old_prefix.trim_end_matches(HIERARCHY_DELIMITER);
+    let new_prefix = new_prefix.trim_end_matches(HIERARCHY_DELIMITER);
+    if old_prefix.is_empty() || new_prefix.is_empty() {
+        return Err(TagrError::InvalidInput(
+            "Tag prefixes cannot be empty".into(),
+        ));
+    }
+    if old_prefix == new_prefix {
+        return Err(TagrError::InvalidInput(
+            "Old and new prefixes are identical".into(),
+        ));
+    }
+    if replace_tag_prefix(new_prefix, old_prefix, "").is_some() {
+        return Err(TagrError::InvalidInput(format!(
+            "New prefix '{new_prefix}' cannot be nested under '{old_prefix}'"
+        )));
+    }
+
+    let mappings: Vec<TagMapping> = db
+        .list_all_tags()?
+        .into_iter()
+        .filter_map(|tag| {
+            let to = replace_tag_prefix(&tag, old_prefix, new_prefix)?;
+            Some(TagMapping { from: tag, to })
+        })
+        .collect();
+    if mappings.is_empty() {
+        if !quiet {
+            println!("No tags with prefix '{old_prefix}' found in database.");
+        }
+        return Ok(());
+    }
+
+    let mut affected = HashSet::new();
+    for mapping in &mappings {
+        affected.extend(db.find_by_tag(&mapping.from)?);
+    }
+
+    if dry_run {
+        println!("{}", "=== Dry Run Mode ===".yellow().bold());
+        println!(
+            "Would rename {} tag(s) in {} file(s):",
+            mappings.len(),
+            affected.len()
+        );
+        for (i, m) in mappings.iter().enumerate().take(15) {
+            println!("  {}. '{}' → '{}'", i + 1, m.from.cyan(), m.to.green());
+        }
+        if mappings.len() > 15 {
+            println!("  ... and {} more", mappings.len() - 15);
+        }
+        println!("\n{}", "Run without --dry-run to apply changes.".yellow());
+        return Ok(());
+    }
+    if !yes {
+        let prompt = format!(
+            "Rename {} tag(s) from '{}' to '{}' in {} file(s)?",
+            mappings.len(),
+            old_prefix.cyan(),
+            new_prefix.green(),
+            affected.len()
+        );
+        let confirmed = Confirm::new()
+            .with_prompt(prompt)
+            .interact()
+            .map_err(|e| TagrError::InvalidInput(format!("Failed to get confirmation: {e}")))?;
+        if !confirmed {
+            println!("Operation cancelled.");
+            return Ok(());
+        }
+    }
+
+    let mut summary = BulkOpSummary::new();
+    for mapping in &mappings {
+        apply_mapping(db, mapping, &mut summary, quiet)?;
+    }
+    if !quiet {
+        summary.print("Rename Prefix");
+        println!(
+            "Renamed {} tag(s) across {} file(s).",
+            mappings.len(),
+            affected.len()
+        );
+    }
+    Ok(())
+}
+
+/// Substitute `new_prefix` for `old_prefix` if `tag` is at or below it
+fn replace_tag_prefix(tag: &str, old_prefix: &str, new_prefix: &str) -> Option<String> {
+    let rest = tag.strip_prefix(old_prefix)?;
+    if rest.is_empty() || rest.starts_with(HIERARCHY_DELIMITER) {
+        Some(format!("{new_prefix}{rest}"))
+    } else {
+        None
+    }
+}
+
+/// Apply one tag mapping to every file carrying `mapping.from`
+///
+/// If a file already has `mapping.to`, the old tag is dropped instead of
+/// producing a duplicate.
+fn apply_mapping(
+    db: &Database,
+    mapping: &TagMapping,
+    summary: &mut BulkOpSummary,
+    quiet: bool,
+) -> Result<()> {
+    if mapping.from == mapping.to {
+        summary.add_skip();
+        if !quiet {
+            println!("⊘ Skipped (identical): '{}'", mapping.from);
+        }
+        return Ok(());
+    }
+    let files = db.find_by_tag(&mapping.from)?;
+    if files.is_empty() {
+        summary.add_skip();
+        if !quiet {
+            println!("⊘ Skipped (not found): '{}'", mapping.from);
+        }
+        return Ok(());
+    }
+    for file in files {
+        let Some(mut tags) = db.get_tags(&file)? else {
+            let _ = SkipReason::Other;
             summary.add_skip();
-            if !quiet {
-                println!("⊘ Skipped (identical): '{}'", mapping.from);
-            }
             continue;
-        }
-        let files = db.find_by_tag(&mapping.from)?;
-        if files.is_empty() {
+        };
+        if !tags.iter().any(|t| t == &mapping.from) {
             summary.add_skip();
-            if !quiet {
-                println!("⊘ Skipped (not found): '{}'", mapping.from);
-            }
             continue;
         }
-        for file in files {
-            let Some(mut tags) = db.get_tags(&file)? else {
-                let _ = SkipReason::Other;
-                summary.add_skip();
-                continue;
-            };
-            if !tags.iter().any(|t| t == &mapping.from) {
-                summary.add_skip();
-                continue;
-            }
-            let target_exists = tags.iter().any(|t| t == &mapping.to);
-            let mut changed = false;
-            for t in &mut tags {
-                if t == &mapping.from {
-                    if target_exists {
-                        *t = String::new();
-                    } else {
-                        t.clone_from(&mapping.to);
-                    }
-                    changed = true;
+        let target_exists = tags.iter().any(|t| t == &mapping.to);
+        let mut changed = false;
+        for t in &mut tags {
+            if t == &mapping.from {
+                if target_exists {
+                    *t = String::new();
+                } else {
+                    t.clone_from(&mapping.to);
                 }
+                changed = true;
             }
-            if !changed {
-                summary.add_skip();
-                continue;
-            }
-            let new_tags: Vec<String> = tags
-                .into_iter()
-                .filter(|t| !t.is_empty())
-                .collect::<std::collections::HashSet<_>>()
-                .into_iter()
-                .collect();
-            let pair = Pair {
-                file: file.clone(),
-                tags: new_tags,
-            };
-            match db.insert_pair(&pair) {
-                Ok(()) => {
-                    summary.add_success();
-                    if !quiet {
-                        println!(
-                            "✓ '{}' → '{}' in {}",
-                            mapping.from,
-                            mapping.to,
-                            file.display()
-                        );
-                    }
+        }
+        if !changed {
+            summary.add_skip();
+            continue;
+        }
+        let new_tags: Vec<String> = tags
+            .into_iter()
+            .filter(|t| !t.is_empty())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let pair = Pair {
+            file: file.clone(),
+            tags: new_tags,
+        };
+        match db.insert_pair(&pair) {
+            Ok(()) => {
+                summary.add_success();
+                if !quiet {
+                    println!(
+                        "✓ '{}' → '{}' in {}",
+                        mapping.from,
+                        mapping.to,
+                        file.display()
+                    );
                 }
-                Err(e) => {
-                    summary.add_error(format!("{}: {}", file.display(), e));
-                    if !quiet {
-                        eprintln!(
-                            "✗ Failed '{}' → '{}' in {}: {}",
-                            mapping.from,
-                            mapping.to,
-                            file.display(),
-                            e
-                        );
-                    }
+            }
+            Err(e) => {
+                if !quiet {
+                    eprintln!(
+                        "✗ Failed '{}' → '{}' in {}: {}",
+                        mapping.from,
+                        mapping.to,
+                        file.display(),
+                        e
+                    );
                 }
+                summary.add_failure(e.with_context(&file, "bulk_map_tags"));
             }
         }
     }
-    if !quiet {
-        summary.print("Map Tags");
-    }
     Ok(())
 }
 
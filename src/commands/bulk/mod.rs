@@ -6,7 +6,7 @@
 //! - `batch`: apply tags from batch specification files
 //! - `mapping`: rename many tags via mapping files
 //! - `delete`: bulk delete files from the database
-//! - `propagate`: auto-tag files by directory or extension
+//! - `propagate`: auto-tag files by directory, extension, filename, manifest or rules file
 //! - `transform`: apply transformations to existing tags
 //!
 //! Public functions and enums are re-exported to preserve the original API
@@ -21,11 +21,19 @@ mod tag_ops;
 mod transform;
 
 pub use batch::{BatchFormat, batch_from_file};
+pub(crate) use core::print_dry_run_preview;
 pub use core::{BulkAction, BulkOpSummary};
 pub use delete::bulk_delete_files;
-pub use mapping::bulk_map_tags;
-pub use propagate::{propagate_by_directory, propagate_by_extension};
-pub use tag_ops::{CopyTagsConfig, bulk_tag, bulk_untag, copy_tags, merge_tags, rename_tag};
+pub use mapping::{bulk_map_tags, rename_tag_prefix};
+pub use propagate::{
+    CargoManifest, ManifestParser, PackageJson, PropagateRule, PropagateRules, PyProject,
+    extract_manifest_tags, propagate_by_directory, propagate_by_extension, propagate_by_filename,
+    propagate_by_rules, propagate_from_manifest,
+};
+pub use tag_ops::{
+    CopyTagsConfig, CopyTagsSource, TagRefs, bulk_tag, bulk_untag, copy_tags, merge_tags,
+    rename_tag,
+};
 pub use transform::{TagTransformation, transform_tags};
 
 // Re-export used parsing types for external callers that may switch on format.
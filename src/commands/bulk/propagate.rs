@@ -1,11 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use colored::Colorize;
 use dialoguer::Confirm;
+use serde::Deserialize;
 
 use super::core::BulkOpSummary;
 use crate::TagrError;
+use crate::commands::tag::{FilenameTagOptions, tokenize_filename};
 use crate::db::Database;
 
 type Result<T> = std::result::Result<T, TagrError>;
@@ -224,10 +226,10 @@ pub fn propagate_by_directory(
                 }
             }
             Err(e) => {
-                summary.add_error(format!("{}: {}", file.display(), e));
                 if !quiet {
                     eprintln!("✗ Failed to tag {}: {}", file.display(), e);
                 }
+                summary.add_failure(e.with_context(file, "propagate_by_directory"));
             }
         }
     }
@@ -356,10 +358,10 @@ pub fn propagate_by_extension(
                 }
             }
             Err(e) => {
-                summary.add_error(format!("{}: {}", file.display(), e));
                 if !quiet {
                     eprintln!("✗ Failed to tag {}: {}", file.display(), e);
                 }
+                summary.add_failure(e.with_context(file, "propagate_by_extension"));
             }
         }
     }
@@ -370,3 +372,654 @@ pub fn propagate_by_extension(
 
     Ok(())
 }
+
+/// Auto-tag files with tokens taken from their filenames.
+///
+/// Each file's stem is split with [`tokenize_filename`], so
+/// `2023_vacation_beach.jpg` yields `vacation` and `beach` (plus `2023`
+/// unless `options.skip_numbers` is set). The extension is never a token.
+///
+/// # Arguments
+/// * `db` - Database instance
+/// * `root` - Optional root directory to filter files (None = all files)
+/// * `options` - Separators and token filters
+/// * `dry_run` - Preview changes without applying
+/// * `yes` - Skip confirmation prompt
+/// * `quiet` - Suppress output
+///
+/// # Errors
+/// Returns database errors during file queries and updates.
+pub fn propagate_by_filename(
+    db: &Database,
+    root: Option<&Path>,
+    options: &FilenameTagOptions,
+    dry_run: bool,
+    yes: bool,
+    quiet: bool,
+) -> Result<()> {
+    let file_tags: Vec<(PathBuf, Vec<String>)> = db
+        .list_all()?
+        .into_iter()
+        .filter(|pair| root.is_none_or(|root_path| pair.file.starts_with(root_path)))
+        .map(|pair| {
+            let tokens = tokenize_filename(&pair.file, options);
+            (pair.file, tokens)
+        })
+        .filter(|(_, tags)| !tags.is_empty())
+        .collect();
+
+    if file_tags.is_empty() {
+        if !quiet {
+            println!("No tags to apply.");
+        }
+        return Ok(());
+    }
+
+    if dry_run {
+        println!("{}", "=== Dry Run Mode ===".yellow().bold());
+        println!(
+            "Would apply filename-based tags to {} file(s)",
+            file_tags.len()
+        );
+        println!("\n{}", "Sample changes (up to 10):".bold());
+        for (i, (file, tags)) in file_tags.iter().enumerate().take(10) {
+            println!(
+                "  {}. {} → [{}]",
+                i + 1,
+                file.display(),
+                tags.join(", ").cyan()
+            );
+        }
+        if file_tags.len() > 10 {
+            println!("  ... and {} more", file_tags.len() - 10);
+        }
+        println!("\n{}", "Run without --dry-run to apply changes.".yellow());
+        return Ok(());
+    }
+
+    if !yes {
+        let prompt = format!("Apply filename-based tags to {} file(s)?", file_tags.len());
+        let confirmed = Confirm::new()
+            .with_prompt(prompt)
+            .interact()
+            .map_err(|e| TagrError::InvalidInput(format!("Failed to get confirmation: {e}")))?;
+        if !confirmed {
+            println!("Operation cancelled.");
+            return Ok(());
+        }
+    }
+
+    let mut summary = BulkOpSummary::new();
+
+    for (file, tags) in &file_tags {
+        match db.add_tags(file, tags.clone()) {
+            Ok(()) => {
+                summary.add_success();
+                if !quiet {
+                    println!("✓ Tagged {}: [{}]", file.display(), tags.join(", "));
+                }
+            }
+            Err(e) => {
+                if !quiet {
+                    eprintln!("✗ Failed to tag {}: {}", file.display(), e);
+                }
+                summary.add_failure(e.with_context(file, "propagate_by_filename"));
+            }
+        }
+    }
+
+    if !quiet {
+        summary.print("Propagate by Filename");
+    }
+
+    Ok(())
+}
+
+/// One `[[rule]]` of a rules file: files matching `glob` or `extension` get `tags`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PropagateRule {
+    /// Glob matched against the path relative to the walked directory
+    pub glob: Option<String>,
+    /// Extension to match, without the dot (case-insensitive)
+    pub extension: Option<String>,
+    /// Tags added to matching files
+    pub tags: Vec<String>,
+}
+
+/// Rules for [`propagate_by_rules`], read from a TOML file
+///
+/// ```toml
+/// [[rule]]
+/// glob = "tests/**"
+/// tags = ["test"]
+///
+/// [[rule]]
+/// extension = "rs"
+/// tags = ["rust"]
+/// ```
+///
+/// Each file gets the tags of the first rule it matches.
+#[derive(Debug, Clone)]
+pub struct PropagateRules {
+    rules: Vec<(RuleMatcher, Vec<String>)>,
+}
+
+#[derive(Debug, Clone)]
+enum RuleMatcher {
+    Glob(glob::Pattern),
+    Extension(String),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    rule: Vec<PropagateRule>,
+}
+
+impl PropagateRules {
+    /// Read rules from a TOML file
+    ///
+    /// # Errors
+    /// Returns `TagrError::InvalidInput` if the file cannot be read or parsed,
+    /// or a rule is invalid.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            TagrError::InvalidInput(format!("Failed to read rules {}: {e}", path.display()))
+        })?;
+        let file: RulesFile = toml::from_str(&content).map_err(|e| {
+            TagrError::InvalidInput(format!("Failed to parse rules {}: {e}", path.display()))
+        })?;
+        Self::new(file.rule)
+    }
+
+    /// Compile `rules`, each of which needs exactly one of `glob` and
+    /// `extension` and at least one tag
+    ///
+    /// # Errors
+    /// Returns `TagrError::InvalidInput` for invalid rules or glob patterns.
+    pub fn new(rules: Vec<PropagateRule>) -> Result<Self> {
+        if rules.is_empty() {
+            return Err(TagrError::InvalidInput("No rules defined".into()));
+        }
+        let rules = rules
+            .into_iter()
+            .enumerate()
+            .map(|(i, rule)| {
+                let matcher = match (rule.glob, rule.extension) {
+                    (Some(pattern), None) => glob::Pattern::new(&pattern)
+                        .map(RuleMatcher::Glob)
+                        .map_err(|e| {
+                            TagrError::InvalidInput(format!(
+                                "Rule {}: invalid glob '{pattern}': {e}",
+                                i + 1
+                            ))
+                        })?,
+                    (None, Some(ext)) => {
+                        RuleMatcher::Extension(ext.trim_start_matches('.').to_lowercase())
+                    }
+                    _ => {
+                        return Err(TagrError::InvalidInput(format!(
+                            "Rule {} needs exactly one of 'glob' and 'extension'",
+                            i + 1
+                        )));
+                    }
+                };
+                if rule.tags.is_empty() {
+                    return Err(TagrError::InvalidInput(format!(
+                        "Rule {} has no tags",
+                        i + 1
+                    )));
+                }
+                Ok((matcher, rule.tags))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Tags of the first rule matching `relative`, a path below the walked
+    /// directory
+    #[must_use]
+    pub fn tags_for(&self, relative: &Path) -> Option<&[String]> {
+        self.rules
+            .iter()
+            .find(|(matcher, _)| match matcher {
+                RuleMatcher::Glob(pattern) => pattern.matches_path(relative),
+                RuleMatcher::Extension(ext) => relative
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| e.eq_ignore_ascii_case(ext)),
+            })
+            .map(|(_, tags)| tags.as_slice())
+    }
+}
+
+/// Regular files under `dir`, at most `max_depth` levels down (1 = only
+/// `dir`'s own files)
+///
+/// Symlinks are skipped unless `follow_symlinks`; directories reached twice
+/// through links are walked once.
+fn walk_dir(
+    dir: &Path,
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+) -> std::io::Result<Vec<PathBuf>> {
+    fn walk(
+        dir: &Path,
+        depth: usize,
+        max_depth: Option<usize>,
+        follow_symlinks: bool,
+        visited: &mut HashSet<PathBuf>,
+        files: &mut Vec<PathBuf>,
+    ) -> std::io::Result<()> {
+        if !visited.insert(dir.canonicalize()?) {
+            return Ok(());
+        }
+        let mut entries = std::fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort_by_key(std::fs::DirEntry::file_name);
+        for entry in entries {
+            let mut file_type = entry.file_type()?;
+            if file_type.is_symlink() {
+                if !follow_symlinks {
+                    continue;
+                }
+                match entry.path().metadata() {
+                    Ok(target) => file_type = target.file_type(),
+                    // Broken link
+                    Err(_) => continue,
+                }
+            }
+            if file_type.is_file() {
+                files.push(entry.path());
+            } else if file_type.is_dir() && max_depth.is_none_or(|max| depth < max) {
+                walk(
+                    &entry.path(),
+                    depth + 1,
+                    max_depth,
+                    follow_symlinks,
+                    visited,
+                    files,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    walk(
+        dir,
+        1,
+        max_depth,
+        follow_symlinks,
+        &mut HashSet::new(),
+        &mut files,
+    )?;
+    Ok(files)
+}
+
+/// Tag the files under a directory by the first matching rule.
+///
+/// Walks `dir` instead of the database, so files that aren't tagged yet are
+/// picked up. Files that already have all of their rule's tags are skipped,
+/// which makes re-running after adding files cheap.
+///
+/// # Arguments
+/// * `db` - Database instance
+/// * `dir` - Directory to walk
+/// * `rules` - Rules mapping globs and extensions to tags
+/// * `max_depth` - Deepest level to walk (1 = only `dir`'s own files)
+/// * `follow_symlinks` - Walk into symlinked directories and tag symlinked files
+/// * `dry_run` - Preview changes without applying
+/// * `yes` - Skip confirmation prompt
+/// * `quiet` - Suppress output
+///
+/// # Errors
+/// Returns `TagrError::InvalidInput` if `max_depth` is 0 or `dir` cannot be
+/// read, and database errors during queries.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::fn_params_excessive_bools)]
+pub fn propagate_by_rules(
+    db: &Database,
+    dir: &Path,
+    rules: &PropagateRules,
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+    dry_run: bool,
+    yes: bool,
+    quiet: bool,
+) -> Result<()> {
+    if max_depth == Some(0) {
+        return Err(TagrError::InvalidInput(
+            "--max-depth must be at least 1".into(),
+        ));
+    }
+    let dir = dir.canonicalize().map_err(|e| {
+        TagrError::InvalidInput(format!("Cannot access directory {}: {e}", dir.display()))
+    })?;
+    let walked = walk_dir(&dir, max_depth, follow_symlinks)
+        .map_err(|e| TagrError::InvalidInput(format!("Failed to walk {}: {e}", dir.display())))?;
+
+    let mut summary = BulkOpSummary::new();
+    let mut file_tags: Vec<(PathBuf, Vec<String>)> = Vec::new();
+    for file in walked {
+        let Some(tags) = file
+            .strip_prefix(&dir)
+            .ok()
+            .and_then(|relative| rules.tags_for(relative))
+        else {
+            continue;
+        };
+        let existing = db.get_tags(&file)?.unwrap_or_default();
+        if tags.iter().all(|tag| existing.contains(tag)) {
+            summary.add_skip();
+        } else {
+            file_tags.push((file, tags.to_vec()));
+        }
+    }
+
+    if file_tags.is_empty() {
+        if !quiet {
+            if summary.skipped > 0 {
+                println!(
+                    "All {} matching file(s) already have their tags.",
+                    summary.skipped
+                );
+            } else {
+                println!("No files match any rule.");
+            }
+        }
+        return Ok(());
+    }
+
+    if dry_run {
+        println!("{}", "=== Dry Run Mode ===".yellow().bold());
+        println!("Would apply rule-based tags to {} file(s)", file_tags.len());
+        if summary.skipped > 0 {
+            println!(
+                "Skipping {} file(s) that already have them",
+                summary.skipped
+            );
+        }
+        println!("\n{}", "Sample changes (up to 10):".bold());
+        for (i, (file, tags)) in file_tags.iter().enumerate().take(10) {
+            println!(
+                "  {}. {} → [{}]",
+                i + 1,
+                file.display(),
+                tags.join(", ").cyan()
+            );
+        }
+        if file_tags.len() > 10 {
+            println!("  ... and {} more", file_tags.len() - 10);
+        }
+        println!("\n{}", "Run without --dry-run to apply changes.".yellow());
+        return Ok(());
+    }
+
+    if !yes {
+        let prompt = format!("Apply rule-based tags to {} file(s)?", file_tags.len());
+        let confirmed = Confirm::new()
+            .with_prompt(prompt)
+            .interact()
+            .map_err(|e| TagrError::InvalidInput(format!("Failed to get confirmation: {e}")))?;
+        if !confirmed {
+            println!("Operation cancelled.");
+            return Ok(());
+        }
+    }
+
+    for (file, tags) in &file_tags {
+        match db.add_tags(file, tags.clone()) {
+            Ok(()) => {
+                summary.add_success();
+                if !quiet {
+                    println!("✓ Tagged {}: [{}]", file.display(), tags.join(", "));
+                }
+            }
+            Err(e) => {
+                if !quiet {
+                    eprintln!("✗ Failed to tag {}: {}", file.display(), e);
+                }
+                summary.add_failure(e.with_context(file, "propagate_by_rules"));
+            }
+        }
+    }
+
+    if !quiet {
+        summary.print("Propagate by Rules");
+    }
+
+    Ok(())
+}
+
+/// Extracts tags describing a project from its manifest file
+pub trait ManifestParser {
+    /// Read `manifest` and return the tags for the project it describes
+    ///
+    /// # Errors
+    /// Returns an error if the manifest cannot be read or parsed, or lacks
+    /// the package name.
+    fn extract_tags(manifest: &Path) -> Result<Vec<String>>;
+}
+
+/// `Cargo.toml`: `rust`, `crate:<name>` and `edition:<edition>`
+pub struct CargoManifest;
+
+/// `package.json`: `javascript`, `npm` and `package:<name>`
+pub struct PackageJson;
+
+/// `pyproject.toml`: `python` and `package:<name>`
+pub struct PyProject;
+
+fn read_manifest(manifest: &Path) -> Result<String> {
+    std::fs::read_to_string(manifest).map_err(|e| {
+        TagrError::InvalidInput(format!(
+            "Failed to read manifest {}: {e}",
+            manifest.display()
+        ))
+    })
+}
+
+fn parse_toml_manifest(manifest: &Path) -> Result<toml::Table> {
+    read_manifest(manifest)?.parse().map_err(|e| {
+        TagrError::InvalidInput(format!(
+            "Failed to parse manifest {}: {e}",
+            manifest.display()
+        ))
+    })
+}
+
+fn missing_name(manifest: &Path) -> TagrError {
+    TagrError::InvalidInput(format!(
+        "Manifest {} has no package name",
+        manifest.display()
+    ))
+}
+
+impl ManifestParser for CargoManifest {
+    fn extract_tags(manifest: &Path) -> Result<Vec<String>> {
+        let table = parse_toml_manifest(manifest)?;
+        let package = table.get("package").and_then(toml::Value::as_table);
+        let name = package
+            .and_then(|package| package.get("name"))
+            .and_then(toml::Value::as_str)
+            .ok_or_else(|| missing_name(manifest))?;
+
+        let mut tags = vec!["rust".to_string(), format!("crate:{name}")];
+        // `edition.workspace = true` is a table, not an edition
+        if let Some(edition) = package
+            .and_then(|package| package.get("edition"))
+            .and_then(toml::Value::as_str)
+        {
+            tags.push(format!("edition:{edition}"));
+        }
+        Ok(tags)
+    }
+}
+
+impl ManifestParser for PackageJson {
+    fn extract_tags(manifest: &Path) -> Result<Vec<String>> {
+        let json: serde_json::Value =
+            serde_json::from_str(&read_manifest(manifest)?).map_err(|e| {
+                TagrError::InvalidInput(format!(
+                    "Failed to parse manifest {}: {e}",
+                    manifest.display()
+                ))
+            })?;
+        let name = json
+            .get("name")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| missing_name(manifest))?;
+        Ok(vec![
+            "javascript".to_string(),
+            "npm".to_string(),
+            format!("package:{name}"),
+        ])
+    }
+}
+
+impl ManifestParser for PyProject {
+    fn extract_tags(manifest: &Path) -> Result<Vec<String>> {
+        let table = parse_toml_manifest(manifest)?;
+        // PEP 621 `[project]`, falling back to Poetry's `[tool.poetry]`
+        let name = table
+            .get("project")
+            .and_then(|project| project.get("name"))
+            .or_else(|| {
+                table
+                    .get("tool")
+                    .and_then(|tool| tool.get("poetry"))
+                    .and_then(|poetry| poetry.get("name"))
+            })
+            .and_then(toml::Value::as_str)
+            .ok_or_else(|| missing_name(manifest))?;
+        Ok(vec!["python".to_string(), format!("package:{name}")])
+    }
+}
+
+/// Extract tags from a manifest, choosing the parser by its filename
+///
+/// # Errors
+/// Returns `TagrError::InvalidInput` for unsupported manifest names and any
+/// error from the parser.
+pub fn extract_manifest_tags(manifest: &Path) -> Result<Vec<String>> {
+    match manifest.file_name().and_then(|name| name.to_str()) {
+        Some("Cargo.toml") => CargoManifest::extract_tags(manifest),
+        Some("package.json") => PackageJson::extract_tags(manifest),
+        Some("pyproject.toml") => PyProject::extract_tags(manifest),
+        _ => Err(TagrError::InvalidInput(format!(
+            "Unsupported manifest '{}'. Expected Cargo.toml, package.json or pyproject.toml",
+            manifest.display()
+        ))),
+    }
+}
+
+/// Tag files with project metadata read from a manifest.
+///
+/// The tags from [`extract_manifest_tags`] are added to every database file
+/// under the manifest's directory, or under `target_dir` when given.
+///
+/// # Arguments
+/// * `db` - Database instance
+/// * `manifest` - Path to a `Cargo.toml`, `package.json` or `pyproject.toml`
+/// * `target_dir` - Directory whose files are tagged (defaults to the manifest's)
+/// * `dry_run` - Preview changes without applying
+/// * `yes` - Skip confirmation prompt
+/// * `quiet` - Suppress output
+///
+/// # Errors
+/// Returns `TagrError::InvalidInput` for unreadable or unsupported manifests
+/// and missing directories, and database errors during queries and updates.
+#[allow(clippy::fn_params_excessive_bools)]
+pub fn propagate_from_manifest(
+    db: &Database,
+    manifest: &Path,
+    target_dir: Option<&Path>,
+    dry_run: bool,
+    yes: bool,
+    quiet: bool,
+) -> Result<()> {
+    let tags = extract_manifest_tags(manifest)?;
+
+    let dir = match target_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => manifest
+            .canonicalize()?
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    let dir = dir.canonicalize().map_err(|e| {
+        TagrError::InvalidInput(format!("Cannot access directory {}: {e}", dir.display()))
+    })?;
+
+    let files: Vec<PathBuf> = db
+        .list_all()?
+        .into_iter()
+        .map(|pair| pair.file)
+        .filter(|file| file.starts_with(&dir))
+        .collect();
+
+    if files.is_empty() {
+        if !quiet {
+            println!("No files found in {}.", dir.display());
+        }
+        return Ok(());
+    }
+
+    if dry_run {
+        println!("{}", "=== Dry Run Mode ===".yellow().bold());
+        println!(
+            "Would apply [{}] to {} file(s)",
+            tags.join(", ").cyan(),
+            files.len()
+        );
+        println!("\n{}", "Sample files (up to 10):".bold());
+        for (i, file) in files.iter().enumerate().take(10) {
+            println!("  {}. {}", i + 1, file.display());
+        }
+        if files.len() > 10 {
+            println!("  ... and {} more", files.len() - 10);
+        }
+        println!("\n{}", "Run without --dry-run to apply changes.".yellow());
+        return Ok(());
+    }
+
+    if !yes {
+        let prompt = format!("Apply [{}] to {} file(s)?", tags.join(", "), files.len());
+        let confirmed = Confirm::new()
+            .with_prompt(prompt)
+            .interact()
+            .map_err(|e| TagrError::InvalidInput(format!("Failed to get confirmation: {e}")))?;
+        if !confirmed {
+            println!("Operation cancelled.");
+            return Ok(());
+        }
+    }
+
+    let mut summary = BulkOpSummary::new();
+
+    for file in &files {
+        match db.add_tags(file, tags.clone()) {
+            Ok(()) => {
+                summary.add_success();
+                if !quiet {
+                    println!("✓ Tagged {}: [{}]", file.display(), tags.join(", "));
+                }
+            }
+            Err(e) => {
+                if !quiet {
+                    eprintln!("✗ Failed to tag {}: {}", file.display(), e);
+                }
+                summary.add_failure(e.with_context(file, "propagate_from_manifest"));
+            }
+        }
+    }
+
+    if !quiet {
+        summary.print("Propagate from Manifest");
+    }
+
+    Ok(())
+}
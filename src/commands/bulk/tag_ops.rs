@@ -1,11 +1,16 @@
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use colored::Colorize;
 
 use crate::cli::{ConditionalArgs, SearchParams};
+use crate::config::TagNormalization;
 use crate::db::Database;
+use crate::filters::FilterManager;
 use crate::patterns::{PatternBuilder, PatternContext};
+use crate::schema::TagSchema;
+use crate::vtags::{VirtualTagConfig, VirtualTagEvaluator};
 use crate::{Pair, TagrError};
 
 use super::core::{
@@ -71,24 +76,105 @@ fn check_conditions(
     {
         return Ok(false);
     }
+    check_metadata_conditions(file, conditions)
+}
+
+/// Check the file-metadata conditions, reading metadata only when one is set
+fn check_metadata_conditions(file: &Path, conditions: &ConditionalArgs) -> Result<bool> {
+    if let Some(ext) = &conditions.if_extension {
+        let ext = ext.trim_start_matches('.');
+        if !file
+            .extension()
+            .is_some_and(|e| e.to_string_lossy().eq_ignore_ascii_case(ext))
+        {
+            return Ok(false);
+        }
+    }
+    if conditions.if_older_than.is_some()
+        || conditions.if_newer_than.is_some()
+        || conditions.if_size_gt.is_some()
+        || conditions.if_size_lt.is_some()
+    {
+        let metadata = std::fs::metadata(file)?;
+        if conditions.if_older_than.is_some() || conditions.if_newer_than.is_some() {
+            // A modification time in the future counts as age zero
+            let age = SystemTime::now()
+                .duration_since(metadata.modified()?)
+                .unwrap_or_default();
+            if conditions.if_older_than.is_some_and(|d| age <= d)
+                || conditions.if_newer_than.is_some_and(|d| age >= d)
+            {
+                return Ok(false);
+            }
+        }
+        if conditions.if_size_gt.is_some_and(|n| metadata.len() <= n)
+            || conditions.if_size_lt.is_some_and(|n| metadata.len() >= n)
+        {
+            return Ok(false);
+        }
+    }
+    if !conditions.if_matches_vtag.is_empty() {
+        let config = VirtualTagConfig::default();
+        let cache_ttl = Duration::from_secs(config.cache_ttl_seconds);
+        let mut evaluator = VirtualTagEvaluator::new(cache_ttl, config);
+        for vtag in &conditions.if_matches_vtag {
+            if !evaluator.matches(file, vtag)? {
+                return Ok(false);
+            }
+        }
+    }
     Ok(true)
 }
 
+/// Check conditions for every file, in input order
+///
+/// With `parallel`, files are checked on rayon's thread pool; the results
+/// still line up with `files`.
+fn check_all_conditions(
+    files: &[PathBuf],
+    db: &Database,
+    conditions: &ConditionalArgs,
+    tags: &[String],
+    parallel: bool,
+) -> Vec<Result<bool>> {
+    use rayon::prelude::*;
+
+    if parallel {
+        files
+            .par_iter()
+            .map(|file| check_conditions(file, db, conditions, tags))
+            .collect()
+    } else {
+        files
+            .iter()
+            .map(|file| check_conditions(file, db, conditions, tags))
+            .collect()
+    }
+}
+
 /// Add tags in bulk to files matching the search parameters.
 ///
+/// Tags are cleaned up with `normalization` before anything is matched or written.
+/// With `parallel`, conditions are checked in parallel and the tags are
+/// written in a single batch; output and summary are the same either way.
+///
 /// # Errors
 /// Returns database errors from query and tag operations, and `TagrError::InvalidInput`
 /// for invalid arguments (e.g., empty tag list).
 #[allow(clippy::too_many_arguments)]
+#[allow(clippy::fn_params_excessive_bools)]
 pub fn bulk_tag(
     db: &Database,
     mut params: SearchParams,
     tags: &[String],
+    normalization: TagNormalization,
     conditions: &ConditionalArgs,
+    parallel: bool,
     dry_run: bool,
     yes: bool,
     quiet: bool,
 ) -> Result<()> {
+    let tags = &normalization.apply_all(tags);
     if tags.is_empty() {
         return Err(TagrError::InvalidInput("No tags provided".into()));
     }
@@ -108,10 +194,29 @@ pub fn bulk_tag(
         println!("Operation cancelled.");
         return Ok(());
     }
+    let checks = check_all_conditions(&files, db, conditions, tags, parallel);
+    let changes: Vec<(PathBuf, Vec<String>)> = files
+        .iter()
+        .zip(&checks)
+        .filter(|(_, check)| matches!(check, Ok(true)))
+        .map(|(file, _)| (file.clone(), tags.to_vec()))
+        .collect();
+    let writes = if parallel {
+        db.add_tags_many(&changes)?
+    } else {
+        changes
+            .iter()
+            .map(|(file, tags)| db.add_tags(file, tags.clone()))
+            .collect()
+    };
+    let mut writes = writes.into_iter();
     let mut summary = BulkOpSummary::new();
-    for file in &files {
-        match check_conditions(file, db, conditions, tags) {
-            Ok(true) => match db.add_tags(file, tags.to_vec()) {
+    for (file, check) in files.iter().zip(checks) {
+        match check {
+            Ok(true) => match writes
+                .next()
+                .expect("one write per file that met conditions")
+            {
                 Ok(()) => {
                     summary.add_success();
                     if !quiet {
@@ -119,10 +224,10 @@ pub fn bulk_tag(
                     }
                 }
                 Err(e) => {
-                    summary.add_error(format!("{}: {}", file.display(), e));
                     if !quiet {
                         eprintln!("✗ Failed to tag {}: {}", file.display(), e);
                     }
+                    summary.add_failure(e.with_context(file, "bulk_tag"));
                 }
             },
             Ok(false) => {
@@ -133,10 +238,10 @@ pub fn bulk_tag(
                 }
             }
             Err(e) => {
-                summary.add_error(format!("{}: {}", file.display(), e));
                 if !quiet {
                     eprintln!("✗ Failed to check conditions for {}: {}", file.display(), e);
                 }
+                summary.add_failure(e.with_context(file, "bulk_tag"));
             }
         }
     }
@@ -148,6 +253,9 @@ pub fn bulk_tag(
 
 /// Remove tags in bulk, optionally removing all tags from matched files.
 ///
+/// With `parallel`, conditions are checked in parallel and the tags are
+/// removed in a single batch; output and summary are the same either way.
+///
 /// # Errors
 /// Returns database errors from query and tag operations, and `TagrError::InvalidInput`
 /// for invalid arguments (e.g., missing tags without `--all`).
@@ -159,6 +267,7 @@ pub fn bulk_untag(
     tags: &[String],
     remove_all: bool,
     conditions: &ConditionalArgs,
+    parallel: bool,
     dry_run: bool,
     yes: bool,
     quiet: bool,
@@ -197,30 +306,47 @@ pub fn bulk_untag(
         println!("Operation cancelled.");
         return Ok(());
     }
+    let checks = check_all_conditions(&files, db, conditions, tags, parallel);
+    let targets: Vec<PathBuf> = files
+        .iter()
+        .zip(&checks)
+        .filter(|(_, check)| matches!(check, Ok(true)))
+        .map(|(file, _)| file.clone())
+        .collect();
+    let writes = if parallel {
+        db.remove_tags_many(&targets, (!remove_all).then_some(tags))?
+    } else if remove_all {
+        targets
+            .iter()
+            .map(|file| db.remove(file).map(|_| ()))
+            .collect()
+    } else {
+        targets
+            .iter()
+            .map(|file| db.remove_tags(file, tags))
+            .collect()
+    };
+    let mut writes = writes.into_iter();
     let mut summary = BulkOpSummary::new();
-    for file in &files {
-        match check_conditions(file, db, conditions, tags) {
-            Ok(true) => {
-                let result = if remove_all {
-                    db.remove(file).map(|_| ())
-                } else {
-                    db.remove_tags(file, tags)
-                };
-                match result {
-                    Ok(()) => {
-                        summary.add_success();
-                        if !quiet {
-                            println!("✓ Untagged: {}", file.display());
-                        }
+    for (file, check) in files.iter().zip(checks) {
+        match check {
+            Ok(true) => match writes
+                .next()
+                .expect("one write per file that met conditions")
+            {
+                Ok(()) => {
+                    summary.add_success();
+                    if !quiet {
+                        println!("✓ Untagged: {}", file.display());
                     }
-                    Err(e) => {
-                        summary.add_error(format!("{}: {}", file.display(), e));
-                        if !quiet {
-                            eprintln!("✗ Failed to untag {}: {}", file.display(), e);
-                        }
+                }
+                Err(e) => {
+                    if !quiet {
+                        eprintln!("✗ Failed to untag {}: {}", file.display(), e);
                     }
+                    summary.add_failure(e.with_context(file, "bulk_untag"));
                 }
-            }
+            },
             Ok(false) => {
                 let _ = SkipReason::ConditionNotMet;
                 summary.add_skip_condition();
@@ -229,10 +355,10 @@ pub fn bulk_untag(
                 }
             }
             Err(e) => {
-                summary.add_error(format!("{}: {}", file.display(), e));
                 if !quiet {
                     eprintln!("✗ Failed to check conditions for {}: {}", file.display(), e);
                 }
+                summary.add_failure(e.with_context(file, "bulk_untag"));
             }
         }
     }
@@ -242,15 +368,85 @@ pub fn bulk_untag(
     Ok(())
 }
 
+/// Replace tags matching `is_old` with `new_tag` in place
+///
+/// Duplicates created by the replacement are dropped, keeping the first
+/// occurrence, so `[a, new, old]` becomes `[a, new]`.
+fn replace_in_order(
+    tags: Vec<String>,
+    is_old: impl Fn(&str) -> bool,
+    new_tag: &str,
+) -> Vec<String> {
+    let mut replaced: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = if is_old(&tag) {
+            new_tag.to_string()
+        } else {
+            tag
+        };
+        if !replaced.contains(&tag) {
+            replaced.push(tag);
+        }
+    }
+    replaced
+}
+
+/// Saved filters and schema aliases that `rename_tag` keeps in sync
+pub struct TagRefs {
+    /// Saved filters whose criteria may reference the tag
+    pub filters: FilterManager,
+    /// Tag schema whose aliases may target the tag
+    pub schema: TagSchema,
+}
+
+impl TagRefs {
+    /// Load the default filter storage and tag schema
+    ///
+    /// # Errors
+    /// Returns an error if the filter path cannot be resolved or the schema
+    /// file cannot be parsed.
+    pub fn load_default() -> Result<Self> {
+        Ok(Self {
+            filters: FilterManager::new(crate::filters::get_filter_path()?),
+            schema: crate::schema::load_default_schema()?,
+        })
+    }
+
+    /// Names of saved filters and aliases that reference `tag`
+    fn referencing(&self, tag: &str) -> Result<(Vec<String>, Vec<String>)> {
+        let filters = self
+            .filters
+            .list()?
+            .into_iter()
+            .filter(|f| f.criteria.references_tag(tag))
+            .map(|f| f.name)
+            .collect();
+        let mut aliases = self.schema.get_aliases(tag);
+        aliases.sort();
+        Ok((filters, aliases))
+    }
+}
+
 /// Rename a tag across all files where it appears.
 ///
+/// With `refs`, saved filter criteria and schema aliases that reference
+/// `old_tag` are rewritten to `new_tag` after the database update.
+///
+/// If `new_tag` is already in use, renaming merges `old_tag` into it; this
+/// requires `merge` so the intent is explicit.
+///
 /// # Errors
-/// Returns database errors during lookups and updates, and `TagrError::InvalidInput`
-/// for invalid arguments (e.g., identical old/new names).
+/// Returns database errors during lookups and updates, filter or schema errors
+/// when updating references, and `TagrError::InvalidInput` for invalid
+/// arguments (e.g., identical old/new names, or an existing `new_tag`
+/// without `merge`).
+#[allow(clippy::too_many_arguments)]
 pub fn rename_tag(
     db: &Database,
     old_tag: &str,
     new_tag: &str,
+    refs: Option<&mut TagRefs>,
+    merge: bool,
     dry_run: bool,
     yes: bool,
     quiet: bool,
@@ -261,12 +457,27 @@ pub fn rename_tag(
         ));
     }
     let files = db.find_by_tag(old_tag)?;
-    if files.is_empty() {
+    let (ref_filters, ref_aliases) = match refs.as_deref() {
+        Some(refs) => refs.referencing(old_tag)?,
+        None => (Vec::new(), Vec::new()),
+    };
+    if files.is_empty() && ref_filters.is_empty() && ref_aliases.is_empty() {
         if !quiet {
             println!("Tag '{old_tag}' not found in database.");
         }
         return Ok(());
     }
+    let existing = db.tag_file_count(new_tag)?;
+    let collision = if existing > 0 && !files.is_empty() {
+        let both = db
+            .find_by_all_tags(&[old_tag.to_string(), new_tag.to_string()])?
+            .len();
+        Some(format!(
+            "Tag '{new_tag}' already exists on {existing} file(s); renaming would merge '{old_tag}' into it ({both} file(s) have both)"
+        ))
+    } else {
+        None
+    };
     if dry_run {
         println!("{}", "=== Dry Run Mode ===".yellow().bold());
         println!(
@@ -275,23 +486,50 @@ pub fn rename_tag(
             new_tag.green(),
             files.len()
         );
-        println!("\n{}", "Affected files:".bold());
-        for (i, file) in files.iter().enumerate().take(10) {
-            println!("  {}. {}", i + 1, file.display());
+        if let Some(collision) = &collision {
+            let note = if merge { "" } else { " — requires --merge" };
+            println!("{}", format!("{collision}{note}").yellow());
         }
-        if files.len() > 10 {
-            println!("  ... and {} more", files.len() - 10);
+        if !files.is_empty() {
+            println!("\n{}", "Affected files:".bold());
+            for (i, file) in files.iter().enumerate().take(10) {
+                println!("  {}. {}", i + 1, file.display());
+            }
+            if files.len() > 10 {
+                println!("  ... and {} more", files.len() - 10);
+            }
+        }
+        if !ref_filters.is_empty() {
+            println!("\nWould update filter(s): {}", ref_filters.join(", "));
+        }
+        if !ref_aliases.is_empty() {
+            println!("Would retarget alias(es): {}", ref_aliases.join(", "));
         }
         println!("\n{}", "Run without --dry-run to apply changes.".yellow());
         return Ok(());
     }
+    if let Some(collision) = collision
+        && !merge
+    {
+        return Err(TagrError::InvalidInput(format!(
+            "{collision}. Use --merge to proceed."
+        )));
+    }
     if !yes {
-        let prompt = format!(
-            "Rename tag '{}' to '{}' in {} file(s)?",
+        let mut prompt = format!(
+            "Rename tag '{}' to '{}' in {} file(s)",
             old_tag.cyan(),
             new_tag.green(),
             files.len()
         );
+        if refs.is_some() {
+            prompt.push_str(&format!(
+                ", {} filter(s) and {} alias(es)",
+                ref_filters.len(),
+                ref_aliases.len()
+            ));
+        }
+        prompt.push('?');
         let confirmed = dialoguer::Confirm::new()
             .with_prompt(prompt)
             .interact()
@@ -307,12 +545,7 @@ pub fn rename_tag(
             summary.add_skip();
             continue;
         };
-        let new_tags: Vec<String> = current_tags
-            .into_iter()
-            .map(|t| if t == old_tag { new_tag.to_string() } else { t })
-            .collect::<HashSet<_>>()
-            .into_iter()
-            .collect();
+        let new_tags = replace_in_order(current_tags, |t| t == old_tag, new_tag);
         let pair = Pair {
             file: file.clone(),
             tags: new_tags,
@@ -325,10 +558,10 @@ pub fn rename_tag(
                 }
             }
             Err(e) => {
-                summary.add_error(format!("{}: {}", file.display(), e));
                 if !quiet {
                     eprintln!("✗ Failed to rename in {}: {}", file.display(), e);
                 }
+                summary.add_failure(e.with_context(file, "rename_tag"));
             }
         }
     }
@@ -344,6 +577,26 @@ pub fn rename_tag(
             summary.print("Rename Tag");
         }
     }
+    if let Some(refs) = refs {
+        let filters = refs.filters.rename_tag(old_tag, new_tag)?;
+        let aliases = refs.schema.retarget_aliases(old_tag, new_tag);
+        if !aliases.is_empty() {
+            refs.schema.save()?;
+        }
+        if !quiet {
+            if !filters.is_empty() {
+                println!("{} Updated filter(s): {}", "✓".green(), filters.join(", "));
+            }
+            if !aliases.is_empty() {
+                println!(
+                    "{} Retargeted alias(es) to '{}': {}",
+                    "✓".green(),
+                    new_tag,
+                    aliases.join(", ")
+                );
+            }
+        }
+    }
     Ok(())
 }
 
@@ -358,14 +611,19 @@ mod tests {
             tags: vec![],
             tag_mode: crate::cli::SearchMode::All,
             file_patterns: vec!["**/*.rs".to_string(), "src/?ain.rs".to_string()],
+            file_only_patterns: vec![],
             file_mode: crate::cli::SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: crate::cli::SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         normalize_bulk_params(&mut params).expect("normalize should succeed");
@@ -382,14 +640,19 @@ mod tests {
             tags: vec![],
             tag_mode: crate::cli::SearchMode::All,
             file_patterns: vec![".*\\.md".to_string()],
+            file_only_patterns: vec![],
             file_mode: crate::cli::SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: true,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: crate::cli::SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         normalize_bulk_params(&mut params).expect("normalize should succeed");
@@ -407,14 +670,19 @@ mod tests {
             tags: vec!["feature/*".to_string()],
             tag_mode: crate::cli::SearchMode::All,
             file_patterns: vec!["src".to_string()],
+            file_only_patterns: vec![],
             file_mode: crate::cli::SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: crate::cli::SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let err = normalize_bulk_params(&mut params).expect_err("should error");
@@ -434,12 +702,56 @@ pub struct CopyTagsConfig<'a> {
     pub quiet: bool,
 }
 
-/// Copy tags from a source file to a set of target files.
+impl CopyTagsConfig<'_> {
+    /// Keep only the tags selected by `--copy-tags` and not `--exclude-tags`
+    fn filter(&self, tags: Vec<String>) -> Vec<String> {
+        tags.into_iter()
+            .filter(|tag| {
+                if let Some(specific) = self.specific_tags
+                    && !specific.contains(tag)
+                {
+                    return false;
+                }
+                !self.exclude_tags.contains(tag)
+            })
+            .collect()
+    }
+}
+
+/// Where `copy_tags` takes the tags to copy from
+#[derive(Debug, Clone, PartialEq)]
+pub enum CopyTagsSource {
+    /// Copy from one file to every target
+    Explicit(PathBuf),
+    /// Copy to each target from the database file whose name is most similar
+    ///
+    /// Similarity is the normalized Levenshtein similarity of the file names
+    /// (0.0 to 1.0); matches below `similarity_threshold` are ignored.
+    Smart { similarity_threshold: f64 },
+}
+
+/// Copy tags to a set of target files.
 ///
 /// # Errors
 /// Returns database errors during lookups and updates, and `TagrError::InvalidInput`
-/// when the source file is missing or after filtering no tags are available.
+/// when the source file is missing or the similarity threshold is outside `0.0..=1.0`.
 pub fn copy_tags(
+    db: &Database,
+    source: &CopyTagsSource,
+    params: SearchParams,
+    config: CopyTagsConfig,
+) -> Result<()> {
+    match source {
+        CopyTagsSource::Explicit(source_file) => {
+            copy_tags_from_file(db, source_file, params, config)
+        }
+        CopyTagsSource::Smart {
+            similarity_threshold,
+        } => copy_tags_from_similar(db, *similarity_threshold, params, config),
+    }
+}
+
+fn copy_tags_from_file(
     db: &Database,
     source_file: &Path,
     mut params: SearchParams,
@@ -451,17 +763,7 @@ pub fn copy_tags(
             source_file.display()
         ))
     })?;
-    let tags_to_copy: Vec<String> = source_tags
-        .into_iter()
-        .filter(|tag| {
-            if let Some(specific) = config.specific_tags
-                && !specific.contains(tag)
-            {
-                return false;
-            }
-            !config.exclude_tags.contains(tag)
-        })
-        .collect();
+    let tags_to_copy = config.filter(source_tags);
     if tags_to_copy.is_empty() {
         if !config.quiet {
             println!("No tags to copy after filtering.");
@@ -530,10 +832,170 @@ pub fn copy_tags(
                 }
             }
             Err(e) => {
-                summary.add_error(format!("{}: {}", file.display(), e));
                 if !config.quiet {
                     eprintln!("✗ Failed to copy tags to {}: {}", file.display(), e);
                 }
+                summary.add_failure(e.with_context(file, "copy_tags"));
+            }
+        }
+    }
+    if !config.quiet {
+        summary.print("Copy Tags");
+    }
+    Ok(())
+}
+
+/// A target file paired with the most similarly named file it copies from
+struct SimilarMatch {
+    target: PathBuf,
+    source: PathBuf,
+    similarity: f64,
+    tags: Vec<String>,
+}
+
+/// Similarity of two paths' file names, from 0.0 (unrelated) to 1.0 (equal)
+fn filename_similarity(a: &Path, b: &Path) -> f64 {
+    let name = |path: &Path| {
+        path.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    strsim::normalized_levenshtein(&name(a), &name(b))
+}
+
+fn copy_tags_from_similar(
+    db: &Database,
+    threshold: f64,
+    mut params: SearchParams,
+    config: CopyTagsConfig,
+) -> Result<()> {
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(TagrError::InvalidInput(format!(
+            "Similarity threshold must be between 0.0 and 1.0, got {threshold}"
+        )));
+    }
+    normalize_bulk_params(&mut params)?;
+    let target_files = crate::db::query::apply_search_params(db, &params)?;
+    if target_files.is_empty() {
+        if !config.quiet {
+            println!("No target files match the specified criteria.");
+        }
+        return Ok(());
+    }
+
+    // Candidates are chosen from tags as they are before any copying, so the
+    // result does not depend on the order targets are processed in
+    let mut candidates: Vec<(PathBuf, Vec<String>)> = Vec::new();
+    for file in db.list_all_files()? {
+        let tags = config.filter(db.get_tags(&file)?.unwrap_or_default());
+        if !tags.is_empty() {
+            candidates.push((file, tags));
+        }
+    }
+
+    let mut matches = Vec::new();
+    let mut unmatched = Vec::new();
+    for target in target_files {
+        let best = candidates
+            .iter()
+            .filter(|(file, _)| *file != target)
+            .map(|(file, tags)| (file, tags, filename_similarity(&target, file)))
+            .filter(|(_, _, similarity)| *similarity >= threshold)
+            .max_by(|a, b| a.2.total_cmp(&b.2).then_with(|| b.0.cmp(a.0)));
+        match best {
+            Some((source, tags, similarity)) => matches.push(SimilarMatch {
+                target,
+                source: source.clone(),
+                similarity,
+                tags: tags.clone(),
+            }),
+            None => unmatched.push(target),
+        }
+    }
+
+    if config.dry_run {
+        println!("{}", "=== Dry Run Mode ===".yellow().bold());
+        println!(
+            "Would copy tags to {} file(s) from similarly named files (threshold {threshold:.2})",
+            matches.len()
+        );
+        println!(
+            "
+{}",
+            "Target files:".bold()
+        );
+        for (i, m) in matches.iter().enumerate().take(10) {
+            println!("  {}. {}", i + 1, m.target.display());
+            println!(
+                "     Would copy from similar file: {} (similarity: {:.2}) [{}]",
+                m.source.file_name().map_or_else(
+                    || m.source.display().to_string(),
+                    |n| n.to_string_lossy().into_owned()
+                ),
+                m.similarity,
+                m.tags.join(", ").cyan()
+            );
+        }
+        if matches.len() > 10 {
+            println!("  ... and {} more", matches.len() - 10);
+        }
+        if !unmatched.is_empty() {
+            println!(
+                "
+{} file(s) have no similar file above the threshold",
+                unmatched.len()
+            );
+        }
+        println!(
+            "
+{}",
+            "Run without --dry-run to apply changes.".yellow()
+        );
+        return Ok(());
+    }
+
+    if matches.is_empty() {
+        if !config.quiet {
+            println!("No similar files found above the threshold.");
+        }
+        return Ok(());
+    }
+    if !config.yes {
+        let prompt = format!(
+            "Copy tags to {} file(s) from similarly named files?",
+            matches.len()
+        );
+        let confirmed = dialoguer::Confirm::new()
+            .with_prompt(prompt)
+            .interact()
+            .map_err(|e| TagrError::InvalidInput(format!("Failed to get confirmation: {e}")))?;
+        if !confirmed {
+            println!("Operation cancelled.");
+            return Ok(());
+        }
+    }
+
+    let mut summary = BulkOpSummary::new();
+    for _ in &unmatched {
+        summary.add_skip();
+    }
+    for m in &matches {
+        match db.add_tags(&m.target, m.tags.clone()) {
+            Ok(()) => {
+                summary.add_success();
+                if !config.quiet {
+                    println!(
+                        "✓ Copied tags to: {} (from {})",
+                        m.target.display(),
+                        m.source.display()
+                    );
+                }
+            }
+            Err(e) => {
+                if !config.quiet {
+                    eprintln!("✗ Failed to copy tags to {}: {}", m.target.display(), e);
+                }
+                summary.add_failure(e.with_context(&m.target, "copy_tags"));
             }
         }
     }
@@ -620,18 +1082,11 @@ pub fn merge_tags(
             summary.add_skip();
             continue;
         };
-        let new_tags: Vec<String> = current_tags
-            .into_iter()
-            .map(|t| {
-                if source_tags.contains(&t) {
-                    target_tag.to_string()
-                } else {
-                    t
-                }
-            })
-            .collect::<HashSet<_>>()
-            .into_iter()
-            .collect();
+        let new_tags = replace_in_order(
+            current_tags,
+            |t| source_tags.iter().any(|s| s == t),
+            target_tag,
+        );
         let pair = Pair {
             file: file.clone(),
             tags: new_tags,
@@ -644,10 +1099,10 @@ pub fn merge_tags(
                 }
             }
             Err(e) => {
-                summary.add_error(format!("{}: {}", file.display(), e));
                 if !quiet {
                     eprintln!("✗ Failed to merge in {}: {}", file.display(), e);
                 }
+                summary.add_failure(e.with_context(file, "merge_tags"));
             }
         }
     }
@@ -1,13 +1,43 @@
 use std::path::PathBuf;
 
+use crate::TagrError;
 use crate::cli::{ConditionalArgs, SearchMode, SearchParams};
 use crate::testing::{TempFile, TestDb};
 
 use super::batch::{parse_csv, parse_json, parse_plaintext};
 use super::{
-    BatchFormat, CopyTagsConfig, bulk_delete_files, bulk_map_tags, bulk_tag, bulk_untag, copy_tags,
-    merge_tags, rename_tag,
+    BatchFormat, CopyTagsConfig, CopyTagsSource, PropagateRule, PropagateRules, TagRefs,
+    TagTransformation, bulk_delete_files, bulk_map_tags, bulk_tag, bulk_untag, copy_tags,
+    extract_manifest_tags, merge_tags, propagate_by_filename, propagate_by_rules,
+    propagate_from_manifest, rename_tag, rename_tag_prefix, transform_tags,
 };
+use crate::commands::tag::FilenameTagOptions;
+
+#[test]
+fn test_summary_records_failure_context() {
+    let file = PathBuf::from("/data/report.txt");
+    let mut summary = super::BulkOpSummary::new();
+    summary.add_failure(
+        crate::db::DbError::FileNotFound(file.display().to_string())
+            .with_context(&file, "bulk_tag"),
+    );
+
+    assert_eq!(summary.errors, 1);
+    assert_eq!(
+        summary.error_messages,
+        ["bulk_tag on '/data/report.txt': Database error: File not found: /data/report.txt"]
+    );
+    let TagrError::WithContext {
+        source,
+        file: Some(failed),
+        operation,
+    } = &summary.failures[0]
+    else {
+        panic!("expected context, got {:?}", summary.failures[0]);
+    };
+    assert_eq!((failed, operation.as_str()), (&file, "bulk_tag"));
+    assert!(matches!(**source, TagrError::DbError(_)));
+}
 
 #[test]
 fn test_parse_plaintext_ok() {
@@ -85,21 +115,28 @@ fn test_bulk_tag_basic() {
         tags: vec!["initial".into()],
         tag_mode: SearchMode::Any,
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
     bulk_tag(
         db,
         params,
         &["bulk".into(), "added".into()],
+        crate::config::TagNormalization::default(),
         &ConditionalArgs::default(),
         false,
+        false,
         true,
         true,
     )
@@ -126,14 +163,19 @@ fn test_bulk_untag_specific_tags() {
         tags: vec!["tag1".into()],
         tag_mode: SearchMode::Any,
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
     bulk_untag(
         db,
@@ -142,6 +184,7 @@ fn test_bulk_untag_specific_tags() {
         false,
         &ConditionalArgs::default(),
         false,
+        false,
         true,
         true,
     )
@@ -161,11 +204,106 @@ fn test_rename_tag_basic() {
     db.add_tags(f1.path(), vec!["oldname".into(), "other".into()])
         .unwrap();
     db.add_tags(f2.path(), vec!["oldname".into()]).unwrap();
-    rename_tag(db, "oldname", "newname", false, true, true).unwrap();
+    rename_tag(db, "oldname", "newname", None, false, false, true, true).unwrap();
     let tags1 = db.get_tags(f1.path()).unwrap().unwrap();
     assert!(tags1.contains(&"newname".into()));
 }
 
+#[test]
+fn test_rename_tag_onto_existing_tag_requires_merge() {
+    let test_db = TestDb::new("test_rename_tag_collision");
+    let db = test_db.db();
+    let f1 = TempFile::create("file1.txt").unwrap();
+    let f2 = TempFile::create("file2.txt").unwrap();
+    db.add_tags(f1.path(), vec!["oldname".into(), "newname".into()])
+        .unwrap();
+    db.add_tags(f2.path(), vec!["oldname".into()]).unwrap();
+
+    let err = rename_tag(db, "oldname", "newname", None, false, false, true, true).unwrap_err();
+    assert!(
+        matches!(&err, TagrError::InvalidInput(msg) if msg.contains("1 file(s)") && msg.contains("--merge"))
+    );
+    assert_eq!(db.find_by_tag("oldname").unwrap().len(), 2);
+
+    rename_tag(db, "oldname", "newname", None, true, false, true, true).unwrap();
+    assert!(db.find_by_tag("oldname").unwrap().is_empty());
+    assert_eq!(db.find_by_tag("newname").unwrap().len(), 2);
+    assert_eq!(
+        db.get_tags(f1.path()).unwrap().unwrap(),
+        vec!["newname".to_string()]
+    );
+}
+
+#[test]
+fn test_rename_tag_update_refs() {
+    use crate::filters::{FilterCriteria, FilterManager};
+    use crate::schema::TagSchema;
+
+    let test_db = TestDb::new("test_rename_tag_update_refs");
+    let db = test_db.db();
+    let f1 = TempFile::create("file1.txt").unwrap();
+    db.add_tags(f1.path(), vec!["oldname".into()]).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let filters = FilterManager::without_backup(dir.path().join("filters.toml"));
+    filters
+        .create(
+            "uses-old",
+            "references the renamed tag".into(),
+            FilterCriteria::builder()
+                .tags(vec!["oldname".into(), "keep".into()])
+                .exclude("oldname".into())
+                .build(),
+        )
+        .unwrap();
+    filters
+        .create(
+            "unrelated",
+            String::new(),
+            FilterCriteria::builder().tag("keep".into()).build(),
+        )
+        .unwrap();
+
+    let schema_path = dir.path().join("tag_schema.toml");
+    let mut schema = TagSchema::load(&schema_path).unwrap();
+    schema.add_alias("old", "oldname").unwrap();
+    schema.add_alias("k", "keep").unwrap();
+    schema.save().unwrap();
+
+    let mut refs = TagRefs {
+        filters: FilterManager::without_backup(dir.path().join("filters.toml")),
+        schema: TagSchema::load(&schema_path).unwrap(),
+    };
+    rename_tag(
+        db,
+        "oldname",
+        "newname",
+        Some(&mut refs),
+        false,
+        false,
+        true,
+        true,
+    )
+    .unwrap();
+
+    assert_eq!(
+        db.get_tags(f1.path()).unwrap().unwrap(),
+        vec!["newname".to_string()]
+    );
+
+    let criteria = filters.get("uses-old").unwrap().criteria;
+    assert_eq!(criteria.tags, vec!["newname", "keep"]);
+    assert_eq!(criteria.excludes, vec!["newname"]);
+    assert_eq!(
+        filters.get("unrelated").unwrap().criteria.tags,
+        vec!["keep"]
+    );
+
+    let schema = TagSchema::load(&schema_path).unwrap();
+    assert_eq!(schema.canonicalize("old"), "newname");
+    assert_eq!(schema.canonicalize("k"), "keep");
+}
+
 #[test]
 fn test_merge_tags_basic() {
     let test_db = TestDb::new("test_merge_tags");
@@ -213,18 +351,23 @@ fn test_copy_tags_all() {
         tags: vec!["initial".into()],
         tag_mode: SearchMode::Any,
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
     copy_tags(
         db,
-        source.path(),
+        &CopyTagsSource::Explicit(source.path().to_path_buf()),
         params,
         CopyTagsConfig {
             specific_tags: None,
@@ -239,6 +382,48 @@ fn test_copy_tags_all() {
     assert!(tags1.contains(&"tag1".into()));
 }
 
+#[test]
+fn test_copy_tags_smart_uses_most_similar_filename() {
+    let test_db = TestDb::new("test_copy_tags_smart");
+    let db = test_db.db();
+    let v1 = TempFile::create("report_v1.pdf").unwrap();
+    let v2 = TempFile::create("report_v2.pdf").unwrap();
+    let notes = TempFile::create("notes.txt").unwrap();
+    let unrelated = TempFile::create("holiday.jpg").unwrap();
+    db.add_tags(v1.path(), vec!["finance".into(), "q3".into()])
+        .unwrap();
+    db.add_tags(notes.path(), vec!["misc".into()]).unwrap();
+    db.add_tags(v2.path(), vec!["new".into()]).unwrap();
+    db.add_tags(unrelated.path(), vec!["new".into()]).unwrap();
+    let params = SearchParams {
+        tags: vec!["new".into()],
+        no_hierarchy: true,
+        ..Default::default()
+    };
+    let config = CopyTagsConfig {
+        specific_tags: None,
+        exclude_tags: &["q3".into()],
+        dry_run: false,
+        yes: true,
+        quiet: true,
+    };
+
+    let invalid = CopyTagsSource::Smart {
+        similarity_threshold: 1.5,
+    };
+    assert!(copy_tags(db, &invalid, params.clone(), config).is_err());
+
+    let smart = CopyTagsSource::Smart {
+        similarity_threshold: 0.8,
+    };
+    copy_tags(db, &smart, params, config).unwrap();
+
+    let mut tags = db.get_tags(v2.path()).unwrap().unwrap();
+    tags.sort();
+    assert_eq!(tags, vec!["finance", "new"]);
+    assert_eq!(db.get_tags(unrelated.path()).unwrap().unwrap(), vec!["new"]);
+}
+
 #[test]
 fn test_bulk_map_tags_basic() {
     let test_db = TestDb::new("test_bulk_map_tags_basic");
@@ -262,6 +447,52 @@ fn test_bulk_map_tags_basic() {
     assert!(!tags.contains(&"old".into()));
 }
 
+#[test]
+fn test_rename_tag_prefix_segments() {
+    let test_db = TestDb::new("test_rename_tag_prefix_segments");
+    let db = test_db.db();
+    db.clear().unwrap();
+    let f1 = TempFile::create("f1.txt").unwrap();
+    let f2 = TempFile::create("f2.txt").unwrap();
+    db.add_tags(f1.path(), vec!["proj".into(), "proj:web".into()])
+        .unwrap();
+    db.add_tags(f2.path(), vec!["project".into(), "proj:cli".into()])
+        .unwrap();
+
+    // Trailing delimiter is optional and "project" is not under "proj"
+    rename_tag_prefix(db, "proj:", "archive:proj", false, true, true).unwrap();
+
+    let mut tags1 = db.get_tags(f1.path()).unwrap().unwrap();
+    tags1.sort();
+    assert_eq!(tags1, vec!["archive:proj", "archive:proj:web"]);
+    let mut tags2 = db.get_tags(f2.path()).unwrap().unwrap();
+    tags2.sort();
+    assert_eq!(tags2, vec!["archive:proj:cli", "project"]);
+}
+
+#[test]
+fn test_rename_tag_prefix_merges_collisions() {
+    let test_db = TestDb::new("test_rename_tag_prefix_collisions");
+    let db = test_db.db();
+    db.clear().unwrap();
+    let f = TempFile::create("file.txt").unwrap();
+    db.add_tags(f.path(), vec!["proj:web".into(), "archive:proj:web".into()])
+        .unwrap();
+
+    rename_tag_prefix(db, "proj", "archive:proj", false, true, true).unwrap();
+
+    let tags = db.get_tags(f.path()).unwrap().unwrap();
+    assert_eq!(tags, vec!["archive:proj:web"]);
+}
+
+#[test]
+fn test_rename_tag_prefix_rejects_identical() {
+    let test_db = TestDb::new("test_rename_tag_prefix_identical");
+    let db = test_db.db();
+    assert!(rename_tag_prefix(db, "proj", "proj:", false, true, true).is_err());
+    assert!(rename_tag_prefix(db, "proj", "proj:old", false, true, true).is_err());
+}
+
 #[test]
 fn test_bulk_delete_files_basic() {
     let test_db = TestDb::new("test_bulk_delete_files_basic");
@@ -301,26 +532,34 @@ fn test_bulk_tag_if_not_exists() {
         tags: vec!["old".into()],
         tag_mode: SearchMode::Any,
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
     let conditions = ConditionalArgs {
         if_not_exists: true,
         if_has_tag: vec![],
         if_missing_tag: vec![],
+        ..Default::default()
     };
     bulk_tag(
         db,
         params,
         &["existing".into(), "new".into()],
+        crate::config::TagNormalization::default(),
         &conditions,
         false,
+        false,
         true,
         true,
     )
@@ -360,26 +599,34 @@ fn test_bulk_tag_if_has_tag() {
         tags: vec!["search".into()],
         tag_mode: SearchMode::Any,
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
     let conditions = ConditionalArgs {
         if_not_exists: false,
         if_has_tag: vec!["required1".into(), "required2".into()],
         if_missing_tag: vec![],
+        ..Default::default()
     };
     bulk_tag(
         db,
         params,
         &["conditional".into()],
+        crate::config::TagNormalization::default(),
         &conditions,
         false,
+        false,
         true,
         true,
     )
@@ -425,26 +672,34 @@ fn test_bulk_tag_if_missing_tag() {
         tags: vec!["search".into()],
         tag_mode: SearchMode::Any,
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
     let conditions = ConditionalArgs {
         if_not_exists: false,
         if_has_tag: vec![],
         if_missing_tag: vec!["complete".into(), "wip".into()],
+        ..Default::default()
     };
     bulk_tag(
         db,
         params,
         &["needs-review".into()],
+        crate::config::TagNormalization::default(),
         &conditions,
         false,
+        false,
         true,
         true,
     )
@@ -462,3 +717,426 @@ fn test_bulk_tag_if_missing_tag() {
         "f3 missing both tags"
     );
 }
+
+#[test]
+fn test_rename_and_merge_preserve_tag_order() {
+    let test_db = TestDb::new("test_rename_and_merge_preserve_tag_order");
+    let db = test_db.db();
+    let plain = TempFile::create("plain.txt").unwrap();
+    let collision = TempFile::create("collision.txt").unwrap();
+    db.insert(plain.path(), vec!["a".into(), "old".into(), "b".into()])
+        .unwrap();
+    db.insert(
+        collision.path(),
+        vec!["a".into(), "new".into(), "old".into()],
+    )
+    .unwrap();
+
+    rename_tag(db, "old", "new", None, true, false, true, true).unwrap();
+    assert_eq!(
+        db.get_tags(plain.path()).unwrap().unwrap(),
+        vec!["a", "new", "b"]
+    );
+    assert_eq!(
+        db.get_tags(collision.path()).unwrap().unwrap(),
+        vec!["a", "new"]
+    );
+
+    db.insert(
+        plain.path(),
+        vec!["x".into(), "b".into(), "y".into(), "a".into()],
+    )
+    .unwrap();
+    merge_tags(db, &["a".into(), "b".into()], "ab", false, true, true).unwrap();
+    assert_eq!(
+        db.get_tags(plain.path()).unwrap().unwrap(),
+        vec!["x", "ab", "y"]
+    );
+    assert_eq!(
+        db.get_tags(collision.path()).unwrap().unwrap(),
+        vec!["ab", "new"]
+    );
+}
+
+#[test]
+fn test_bulk_tag_parallel_matches_serial() {
+    let files: Vec<TempFile> = (0..40)
+        .map(|i| TempFile::create(format!("parallel{i}.txt")).unwrap())
+        .collect();
+    let run = |name: &str, parallel: bool| {
+        let test_db = TestDb::new(name);
+        let db = test_db.db();
+        for (i, file) in files.iter().enumerate() {
+            let mut tags = vec!["batch".to_string()];
+            if i % 3 == 0 {
+                tags.push("done".into());
+            }
+            db.add_tags(file.path(), tags).unwrap();
+        }
+        let params = SearchParams {
+            tags: vec!["batch".into()],
+            ..Default::default()
+        };
+        let conditions = ConditionalArgs {
+            if_missing_tag: vec!["done".into()],
+            ..Default::default()
+        };
+        bulk_tag(
+            db,
+            params.clone(),
+            &["todo".into()],
+            crate::config::TagNormalization::default(),
+            &conditions,
+            parallel,
+            false,
+            true,
+            true,
+        )
+        .unwrap();
+        let tagged = db.find_by_tag("todo").unwrap();
+        let order: Vec<_> = files
+            .iter()
+            .map(|file| db.get_tags(file.path()).unwrap())
+            .collect();
+        bulk_untag(
+            db,
+            params,
+            &["batch".into()],
+            false,
+            &conditions,
+            parallel,
+            false,
+            true,
+            true,
+        )
+        .unwrap();
+        (tagged, order, db.find_by_tag("batch").unwrap())
+    };
+
+    let serial = run("bulk_tag_serial", false);
+    let parallel = run("bulk_tag_parallel", true);
+    assert_eq!(serial.0.len(), 26);
+    assert_eq!(
+        serial.1[1].as_deref(),
+        Some(&["batch".to_string(), "todo".to_string()][..])
+    );
+    assert_eq!(serial.2.len(), 14, "files with 'done' keep 'batch'");
+    assert_eq!(parallel, serial);
+}
+
+#[test]
+fn test_bulk_tag_metadata_conditions() {
+    let test_db = TestDb::new("test_bulk_tag_metadata_conditions");
+    let db = test_db.db();
+    db.clear().unwrap();
+    let big_rs = TempFile::create_with_content("big.rs", &[b'x'; 2048]).unwrap();
+    let small_rs = TempFile::create("small.rs").unwrap();
+    let big_txt = TempFile::create_with_content("big.txt", &[b'x'; 2048]).unwrap();
+    for f in [&big_rs, &small_rs, &big_txt] {
+        db.add_tags(f.path(), vec!["search".into()]).unwrap();
+    }
+    let params = SearchParams {
+        tags: vec!["search".into()],
+        ..Default::default()
+    };
+    let conditions = ConditionalArgs {
+        if_size_gt: Some(1024),
+        if_extension: Some("rs".into()),
+        if_newer_than: Some(std::time::Duration::from_secs(3600)),
+        ..Default::default()
+    };
+    bulk_tag(
+        db,
+        params.clone(),
+        &["large-source".into()],
+        crate::config::TagNormalization::default(),
+        &conditions,
+        false,
+        false,
+        true,
+        true,
+    )
+    .unwrap();
+    let tagged = |f: &TempFile, tag: &str| {
+        db.get_tags(f.path())
+            .unwrap()
+            .unwrap()
+            .contains(&tag.into())
+    };
+    assert!(tagged(&big_rs, "large-source"));
+    assert!(!tagged(&small_rs, "large-source"), "too small");
+    assert!(!tagged(&big_txt, "large-source"), "wrong extension");
+
+    // Freshly created files are not older than 30 days
+    let conditions = ConditionalArgs {
+        if_older_than: Some(std::time::Duration::from_secs(30 * 86_400)),
+        ..Default::default()
+    };
+    bulk_tag(
+        db,
+        params.clone(),
+        &["stale".into()],
+        crate::config::TagNormalization::default(),
+        &conditions,
+        false,
+        false,
+        true,
+        true,
+    )
+    .unwrap();
+    assert!(!tagged(&big_rs, "stale"));
+
+    // Virtual tag conditions compose with tag conditions using AND
+    let conditions = ConditionalArgs {
+        if_missing_tag: vec!["large-source".into()],
+        if_matches_vtag: vec![crate::vtags::VirtualTag::try_from("size:>1KB").unwrap()],
+        ..Default::default()
+    };
+    bulk_tag(
+        db,
+        params,
+        &["large".into()],
+        crate::config::TagNormalization::default(),
+        &conditions,
+        false,
+        false,
+        true,
+        true,
+    )
+    .unwrap();
+    assert!(!tagged(&big_rs, "large"), "already has large-source");
+    assert!(!tagged(&small_rs, "large"), "too small");
+    assert!(tagged(&big_txt, "large"));
+}
+
+#[test]
+fn test_transform_tags_composes_prefix_changes() {
+    let test_db = TestDb::new("bulk_transform_compose");
+    let db = test_db.db();
+    let file = TempFile::create("transform.txt").unwrap();
+    db.insert(
+        file.path(),
+        vec!["feature-Login".into(), "feature-api".into(), "docs".into()],
+    )
+    .unwrap();
+
+    let chain = [
+        TagTransformation::StripPrefix {
+            prefix: "feature-".into(),
+        },
+        TagTransformation::Lowercase,
+        TagTransformation::AddPrefix {
+            prefix: "feat:".into(),
+        },
+    ];
+    transform_tags(db, &chain, None, Some("feature-*"), false, true, true).unwrap();
+
+    let mut tags = db.get_tags(file.path()).unwrap().unwrap();
+    tags.sort();
+    assert_eq!(tags, vec!["docs", "feat:api", "feat:login"]);
+}
+
+#[test]
+fn test_transform_tags_suffix_and_dry_run() {
+    let test_db = TestDb::new("bulk_transform_suffix");
+    let db = test_db.db();
+    let file = TempFile::create("suffix.txt").unwrap();
+    db.insert(file.path(), vec!["notes.old".into()]).unwrap();
+
+    let strip = [TagTransformation::StripSuffix {
+        suffix: ".old".into(),
+    }];
+    transform_tags(db, &strip, None, None, true, true, true).unwrap();
+    assert_eq!(
+        db.get_tags(file.path()).unwrap().unwrap(),
+        vec!["notes.old"]
+    );
+
+    transform_tags(db, &strip, None, None, false, true, true).unwrap();
+    let add = [TagTransformation::AddSuffix {
+        suffix: "-v2".into(),
+    }];
+    transform_tags(db, &add, None, None, false, true, true).unwrap();
+    assert_eq!(db.get_tags(file.path()).unwrap().unwrap(), vec!["notes-v2"]);
+}
+
+#[test]
+fn test_transform_tags_rejects_invalid_glob() {
+    let test_db = TestDb::new("bulk_transform_bad_glob");
+    let db = test_db.db();
+
+    let err = transform_tags(
+        db,
+        &[TagTransformation::Uppercase],
+        None,
+        Some("[unclosed"),
+        false,
+        true,
+        true,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("Invalid tag filter pattern"));
+}
+
+#[test]
+fn test_propagate_by_filename() {
+    let test_db = TestDb::new("test_propagate_by_filename");
+    let db = test_db.db();
+    let photo = TempFile::create("2023_vacation_beach.jpg").unwrap();
+    let numbers = TempFile::create("2024-01-01.txt").unwrap();
+    db.insert(photo.path(), vec!["photo".into()]).unwrap();
+    db.insert(numbers.path(), vec!["log".into()]).unwrap();
+    let options = FilenameTagOptions {
+        skip_numbers: true,
+        ..Default::default()
+    };
+
+    propagate_by_filename(db, None, &options, true, true, true).unwrap();
+    assert_eq!(db.get_tags(photo.path()).unwrap().unwrap(), vec!["photo"]);
+
+    propagate_by_filename(db, None, &options, false, true, true).unwrap();
+    let mut tags = db.get_tags(photo.path()).unwrap().unwrap();
+    tags.sort();
+    assert_eq!(tags, vec!["beach", "photo", "vacation"]);
+    assert_eq!(db.get_tags(numbers.path()).unwrap().unwrap(), vec!["log"]);
+}
+
+#[test]
+fn test_extract_manifest_tags() {
+    let cargo = TempFile::create_with_content(
+        "Cargo.toml",
+        b"[package]\nname = \"tagr\"\nedition = \"2024\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+        extract_manifest_tags(cargo.path()).unwrap(),
+        vec!["rust", "crate:tagr", "edition:2024"]
+    );
+
+    let npm = TempFile::create_with_content("package.json", br#"{"name": "left-pad"}"#).unwrap();
+    assert_eq!(
+        extract_manifest_tags(npm.path()).unwrap(),
+        vec!["javascript", "npm", "package:left-pad"]
+    );
+
+    let poetry =
+        TempFile::create_with_content("pyproject.toml", b"[tool.poetry]\nname = \"requests\"\n")
+            .unwrap();
+    assert_eq!(
+        extract_manifest_tags(poetry.path()).unwrap(),
+        vec!["python", "package:requests"]
+    );
+
+    let nameless = TempFile::create_with_content("Cargo.toml", b"[workspace]\n").unwrap();
+    assert!(matches!(
+        extract_manifest_tags(nameless.path()),
+        Err(TagrError::InvalidInput(_))
+    ));
+    let other = TempFile::create("setup.py").unwrap();
+    assert!(matches!(
+        extract_manifest_tags(other.path()),
+        Err(TagrError::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn test_propagate_from_manifest() {
+    let test_db = TestDb::new("test_propagate_from_manifest");
+    let db = test_db.db();
+    let manifest =
+        TempFile::create_with_content("pyproject.toml", b"[project]\nname = \"demo\"\n").unwrap();
+    let dir = manifest.path().parent().unwrap().canonicalize().unwrap();
+    let source = dir.join("main.py");
+    std::fs::write(&source, "print()").unwrap();
+    let outside = TempFile::create("other.py").unwrap();
+    db.insert(&source, vec!["code".into()]).unwrap();
+    db.insert(outside.path(), vec!["code".into()]).unwrap();
+
+    propagate_from_manifest(db, manifest.path(), None, true, true, true).unwrap();
+    assert_eq!(db.get_tags(&source).unwrap().unwrap(), vec!["code"]);
+
+    propagate_from_manifest(db, manifest.path(), None, false, true, true).unwrap();
+    let mut tags = db.get_tags(&source).unwrap().unwrap();
+    tags.sort();
+    assert_eq!(tags, vec!["code", "package:demo", "python"]);
+    assert_eq!(db.get_tags(outside.path()).unwrap().unwrap(), vec!["code"]);
+}
+
+#[test]
+fn test_propagate_by_rules() {
+    let test_db = TestDb::new("test_propagate_by_rules");
+    let db = test_db.db();
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    for file in [
+        "src/main.rs",
+        "src/lib.rs",
+        "tests/it.rs",
+        "README.md",
+        "notes.txt",
+        "deep/a/b/c.rs",
+    ] {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "").unwrap();
+    }
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(root.join("src/main.rs"), root.join("link.rs")).unwrap();
+    db.insert(root.join("src/lib.rs"), vec!["rust".into()])
+        .unwrap();
+
+    let rules_file = root.join("rules.toml");
+    std::fs::write(
+        &rules_file,
+        "[[rule]]\nglob = \"tests/**\"\ntags = [\"test\"]\n\n\
+         [[rule]]\nextension = \"rs\"\ntags = [\"rust\"]\n\n\
+         [[rule]]\nextension = \".MD\"\ntags = [\"docs\"]\n",
+    )
+    .unwrap();
+    let rules = PropagateRules::load(&rules_file).unwrap();
+
+    propagate_by_rules(db, &root, &rules, Some(2), false, true, true, true).unwrap();
+    assert_eq!(db.get_tags(root.join("src/main.rs")).unwrap(), None);
+
+    propagate_by_rules(db, &root, &rules, Some(2), false, false, true, true).unwrap();
+    let tags = |file: &str| db.get_tags(root.join(file)).unwrap();
+    assert_eq!(tags("src/main.rs"), Some(vec!["rust".into()]));
+    assert_eq!(tags("src/lib.rs"), Some(vec!["rust".into()]));
+    assert_eq!(tags("tests/it.rs"), Some(vec!["test".into()]));
+    assert_eq!(tags("README.md"), Some(vec!["docs".into()]));
+    assert_eq!(tags("notes.txt"), None);
+    assert_eq!(tags("deep/a/b/c.rs"), None);
+    assert_eq!(tags("link.rs"), None);
+
+    assert!(propagate_by_rules(db, &root, &rules, Some(0), false, false, true, true).is_err());
+}
+
+#[test]
+fn test_propagate_rules_validation() {
+    let rule = |glob: Option<&str>, extension: Option<&str>, tags: &[&str]| PropagateRule {
+        glob: glob.map(Into::into),
+        extension: extension.map(Into::into),
+        tags: tags.iter().map(ToString::to_string).collect(),
+    };
+
+    assert!(PropagateRules::new(vec![]).is_err());
+    assert!(PropagateRules::new(vec![rule(None, None, &["a"])]).is_err());
+    assert!(PropagateRules::new(vec![rule(Some("*.rs"), Some("rs"), &["a"])]).is_err());
+    assert!(PropagateRules::new(vec![rule(Some("*.rs"), None, &[])]).is_err());
+    assert!(PropagateRules::new(vec![rule(Some("[a"), None, &["a"])]).is_err());
+
+    let rules = PropagateRules::new(vec![
+        rule(Some("docs/*"), None, &["docs"]),
+        rule(None, Some("md"), &["markdown"]),
+    ])
+    .unwrap();
+    assert_eq!(
+        rules.tags_for(std::path::Path::new("docs/guide.md")),
+        Some(&["docs".to_string()][..])
+    );
+    assert_eq!(
+        rules.tags_for(std::path::Path::new("src/NOTES.MD")),
+        Some(&["markdown".to_string()][..])
+    );
+    assert_eq!(rules.tags_for(std::path::Path::new("src/main.rs")), None);
+}
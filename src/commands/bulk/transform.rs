@@ -13,7 +13,7 @@ use crate::{Pair, TagrError};
 type Result<T> = std::result::Result<T, TagrError>;
 
 /// Tag transformation type
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TagTransformation {
     Lowercase,
     Uppercase,
@@ -21,10 +21,18 @@ pub enum TagTransformation {
     SnakeCase,
     CamelCase,
     PascalCase,
-    AddPrefix(String),
-    AddSuffix(String),
-    RemovePrefix(String),
-    RemoveSuffix(String),
+    AddPrefix {
+        prefix: String,
+    },
+    AddSuffix {
+        suffix: String,
+    },
+    StripPrefix {
+        prefix: String,
+    },
+    StripSuffix {
+        suffix: String,
+    },
     RegexReplace {
         pattern: String,
         replacement: String,
@@ -41,10 +49,10 @@ impl TagTransformation {
             Self::SnakeCase => tag.to_snake_case(),
             Self::CamelCase => tag.to_lower_camel_case(),
             Self::PascalCase => tag.to_pascal_case(),
-            Self::AddPrefix(prefix) => format!("{prefix}{tag}"),
-            Self::AddSuffix(suffix) => format!("{tag}{suffix}"),
-            Self::RemovePrefix(prefix) => tag.strip_prefix(prefix).unwrap_or(tag).to_string(),
-            Self::RemoveSuffix(suffix) => tag.strip_suffix(suffix).unwrap_or(tag).to_string(),
+            Self::AddPrefix { prefix } => format!("{prefix}{tag}"),
+            Self::AddSuffix { suffix } => format!("{tag}{suffix}"),
+            Self::StripPrefix { prefix } => tag.strip_prefix(prefix).unwrap_or(tag).to_string(),
+            Self::StripSuffix { suffix } => tag.strip_suffix(suffix).unwrap_or(tag).to_string(),
             Self::RegexReplace {
                 pattern,
                 replacement,
@@ -56,31 +64,54 @@ impl TagTransformation {
             }
         })
     }
+
+    /// Apply a chain of transformations to a tag, in order
+    fn apply_all(transformations: &[Self], tag: &str) -> Result<String> {
+        transformations
+            .iter()
+            .try_fold(tag.to_string(), |current, t| t.apply(&current))
+    }
 }
 
 /// Transform tags across all files in the database.
 ///
 /// # Arguments
 /// * `db` - Database instance
-/// * `transformation` - Transformation to apply
+/// * `transformations` - Transformations to apply, composed in order
 /// * `filter_tags` - Only transform specific tags (None = all tags)
+/// * `tag_filter` - Only transform tags matching this glob pattern
 /// * `dry_run` - Preview changes without applying
 /// * `yes` - Skip confirmation prompt
 /// * `quiet` - Suppress output
 ///
 /// # Errors
 /// Returns database errors during file queries and updates, and `TagrError::InvalidInput`
-/// for invalid regex patterns.
+/// for invalid regex or glob patterns.
 #[allow(clippy::too_many_lines)]
 #[allow(clippy::missing_panics_doc)]
 pub fn transform_tags(
     db: &Database,
-    transformation: &TagTransformation,
+    transformations: &[TagTransformation],
     filter_tags: Option<&[String]>,
+    tag_filter: Option<&str>,
     dry_run: bool,
     yes: bool,
     quiet: bool,
 ) -> Result<()> {
+    if transformations.is_empty() {
+        return Err(TagrError::InvalidInput(
+            "No transformation specified".into(),
+        ));
+    }
+
+    let tag_glob = tag_filter
+        .map(|p| {
+            glob::Pattern::new(p).map_err(|e| {
+                TagrError::InvalidInput(format!("Invalid tag filter pattern '{p}': {e}"))
+            })
+        })
+        .transpose()?;
+
     // Collect all unique tags from database
     let all_pairs = db.list_all()?;
     let mut all_tags: HashSet<String> = HashSet::new();
@@ -89,14 +120,11 @@ pub fn transform_tags(
     }
 
     // Filter tags if specified
-    let tags_to_transform: Vec<String> = if let Some(filter) = filter_tags {
-        all_tags
-            .into_iter()
-            .filter(|t| filter.contains(t))
-            .collect()
-    } else {
-        all_tags.into_iter().collect()
-    };
+    let tags_to_transform: Vec<String> = all_tags
+        .into_iter()
+        .filter(|t| filter_tags.is_none_or(|filter| filter.contains(t)))
+        .filter(|t| tag_glob.as_ref().is_none_or(|g| g.matches(t)))
+        .collect();
 
     if tags_to_transform.is_empty() {
         if !quiet {
@@ -110,7 +138,7 @@ pub fn transform_tags(
     let mut conflicts: HashMap<String, Vec<String>> = HashMap::new();
 
     for old_tag in &tags_to_transform {
-        let new_tag = transformation.apply(old_tag)?;
+        let new_tag = TagTransformation::apply_all(transformations, old_tag)?;
 
         // Check for collisions
         if new_tag != *old_tag {
@@ -166,12 +194,9 @@ pub fn transform_tags(
         println!("\n{}", "Tag transformations:".bold());
         let mut mappings: Vec<_> = tag_mapping.iter().collect();
         mappings.sort_by_key(|(old, _)| old.as_str());
-        for (i, (old_tag, new_tag)) in mappings.iter().enumerate().take(20) {
+        for (i, (old_tag, new_tag)) in mappings.iter().enumerate() {
             println!("  {}. {} → {}", i + 1, old_tag, new_tag.cyan());
         }
-        if tag_mapping.len() > 20 {
-            println!("  ... and {} more", tag_mapping.len() - 20);
-        }
         println!("\n{}", "Run without --dry-run to apply changes.".yellow());
         return Ok(());
     }
@@ -221,10 +246,10 @@ pub fn transform_tags(
                 }
             }
             Err(e) => {
-                summary.add_error(format!("{}: {}", pair.file.display(), e));
                 if !quiet {
                     eprintln!("✗ Failed to transform {}: {}", pair.file.display(), e);
                 }
+                summary.add_failure(e.with_context(&pair.file, "transform_tags"));
             }
         }
     }
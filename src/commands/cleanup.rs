@@ -1,41 +1,181 @@
 //! Cleanup command - remove missing files and files with no tags
 
-use crate::{TagrError, config, db::Database, output};
+use crate::{
+    TagrError,
+    config::{self, SymlinkMode},
+    db::Database,
+    output,
+};
 use dialoguer::Select;
 use std::path::PathBuf;
 
 type Result<T> = std::result::Result<T, TagrError>;
 
-/// Execute the cleanup command
-///
-/// # Errors
-/// Returns an error if database operations fail or if user interaction fails
-pub fn execute(db: &Database, path_format: config::PathFormat, quiet: bool) -> Result<()> {
-    if !quiet {
-        println!("Scanning database for issues...");
+/// How much of the database one cleanup run checks
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScanLimits {
+    /// Check at most this many files, saving where the scan stopped
+    pub incremental: Option<usize>,
+    /// Start after the file where the last incremental run stopped
+    pub resume: bool,
+    /// Skip files verified to exist within this many seconds
+    pub max_age_check: Option<u64>,
+}
+
+impl ScanLimits {
+    /// Whether the run reads or saves the cleanup cursor
+    const fn uses_cursor(self) -> bool {
+        self.incremental.is_some() || self.resume
     }
+}
 
-    let all_pairs = db.list_all()?;
-    let mut missing_files = Vec::new();
-    let mut untagged_no_notes = Vec::new();
-    let mut notes_only_files = Vec::new();
+/// Files found by a cleanup scan
+#[derive(Debug, Default)]
+struct Scan {
+    missing: Vec<PathBuf>,
+    untagged_no_notes: Vec<PathBuf>,
+    notes_only: Vec<PathBuf>,
+    /// Files whose existence was checked
+    checked: usize,
+    /// Files skipped because they were verified within `max_age_check`
+    recently_checked: usize,
+    /// Whether the scan reached the end of the database
+    finished: bool,
+}
+
+/// Check the files selected by `limits`, recording when each was verified
+///
+/// With a cursor in use, it is saved after the last file visited, or cleared
+/// once the scan reaches the end so the next run starts over. Without
+/// `record`, neither the check times nor the cursor are written.
+fn scan(
+    db: &Database,
+    symlinks: SymlinkMode,
+    skip_corrupt: bool,
+    limits: ScanLimits,
+    now: i64,
+    record: bool,
+) -> Result<Scan> {
+    let start = if limits.resume {
+        db.cleanup_cursor()?
+    } else {
+        None
+    };
+    let max_age = limits
+        .max_age_check
+        .map(|secs| i64::try_from(secs).unwrap_or(i64::MAX));
+
+    let mut scan = Scan {
+        finished: true,
+        ..Scan::default()
+    };
+    let mut corrupt = Vec::new();
+    let mut last = None;
+
+    for result in db.iter_pairs_after(start.as_deref())? {
+        if limits.incremental.is_some_and(|n| scan.checked >= n) {
+            scan.finished = false;
+            break;
+        }
+        let pair = match result {
+            Ok(pair) => pair,
+            Err(e) if skip_corrupt => {
+                corrupt.push(e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        last = Some(pair.file.clone());
 
-    for pair in all_pairs {
-        if !pair.file.exists() {
-            missing_files.push(pair.file);
-        } else if pair.tags.is_empty() {
+        if let Some(max_age) = max_age
+            && let Some(at) = db.last_checked(&pair.file)?
+            && now.saturating_sub(at) < max_age
+        {
+            scan.recently_checked += 1;
+            continue;
+        }
+        scan.checked += 1;
+
+        if !symlinks.exists(&pair.file) {
+            scan.missing.push(pair.file);
+            continue;
+        }
+        if record {
+            db.set_last_checked(&pair.file, now)?;
+        }
+        if pair.tags.is_empty() {
             // File has no tags - check if it has a note
             let has_note = db.get_note(&pair.file)?.is_some();
             if has_note {
-                notes_only_files.push(pair.file);
+                scan.notes_only.push(pair.file);
             } else {
                 // No tags and no note - this shouldn't happen with equality model
                 // but handle it gracefully
-                untagged_no_notes.push(pair.file);
+                scan.untagged_no_notes.push(pair.file);
             }
         }
     }
 
+    output::warn_corrupt_entries(&corrupt);
+    if record && limits.uses_cursor() {
+        let cursor = if scan.finished { None } else { last.or(start) };
+        db.set_cleanup_cursor(cursor.as_deref())?;
+    }
+    Ok(scan)
+}
+
+/// Execute the cleanup command
+///
+/// A stored path counts as missing according to `symlinks`: broken symlinks are
+/// missing unless the mode is [`SymlinkMode::StoreLink`]. With `skip_corrupt`,
+/// entries that cannot be decoded are reported and left untouched. `limits`
+/// restricts the run to part of the database; see [`ScanLimits`]. With
+/// `dry_run`, the files that would be deleted are listed and nothing is
+/// written, not even the scan's progress.
+///
+/// # Errors
+/// Returns an error if database operations fail or if user interaction fails
+pub fn execute(
+    db: &Database,
+    symlinks: SymlinkMode,
+    skip_corrupt: bool,
+    limits: ScanLimits,
+    path_format: config::PathFormat,
+    dry_run: bool,
+    quiet: bool,
+) -> Result<()> {
+    if !quiet {
+        println!("Scanning database for issues...");
+    }
+
+    let Scan {
+        missing: missing_files,
+        untagged_no_notes,
+        notes_only: notes_only_files,
+        checked,
+        recently_checked,
+        finished,
+    } = scan(
+        db,
+        symlinks,
+        skip_corrupt,
+        limits,
+        chrono::Utc::now().timestamp(),
+        !dry_run,
+    )?;
+
+    if !quiet && (limits.uses_cursor() || limits.max_age_check.is_some()) {
+        print!("Checked {checked} file(s)");
+        if recently_checked > 0 {
+            print!(", skipped {recently_checked} checked recently");
+        }
+        if finished {
+            println!("; reached the end of the database.");
+        } else {
+            println!("; run with --resume to continue.");
+        }
+    }
+
     let total_issues = missing_files.len() + untagged_no_notes.len();
 
     if total_issues == 0 && notes_only_files.is_empty() {
@@ -45,6 +185,25 @@ pub fn execute(db: &Database, path_format: config::PathFormat, quiet: bool) -> R
         return Ok(());
     }
 
+    if dry_run {
+        if !quiet {
+            for (heading, files) in [
+                ("Missing files", &missing_files),
+                ("Files with no tags or notes", &untagged_no_notes),
+            ] {
+                if files.is_empty() {
+                    continue;
+                }
+                println!("\n{heading}: {}", files.len());
+                for file in files {
+                    println!("  - {}", output::format_path(file, path_format));
+                }
+            }
+            println!("\nWould delete {total_issues} file(s) from database");
+        }
+        return Ok(());
+    }
+
     let mut deleted_count = 0;
     let mut skipped_count = 0;
 
@@ -195,3 +354,90 @@ fn process_cleanup_files(
 
     Ok((deleted_count, skipped_count))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::NoteRecord;
+    use crate::testing::{TempFile, TestDb};
+
+    #[test]
+    fn test_incremental_scan_resumes_and_skips_recent_checks() {
+        let test_db = TestDb::new("test_cleanup_incremental");
+        let db = test_db.db();
+        let files: Vec<TempFile> = (0..5)
+            .map(|i| TempFile::create(format!("cleanup_inc_{i}.txt")).unwrap())
+            .collect();
+        for file in &files {
+            db.insert(file.path(), vec!["tag".into()]).unwrap();
+        }
+        let gone = TempFile::create("cleanup_inc_missing.txt").unwrap();
+        let missing = gone.path().to_path_buf();
+        db.insert(&missing, vec!["tag".into()]).unwrap();
+        std::fs::remove_file(&missing).unwrap();
+
+        let limits = ScanLimits {
+            incremental: Some(4),
+            resume: true,
+            max_age_check: None,
+        };
+        let first = scan(db, SymlinkMode::default(), false, limits, 1_000, true).unwrap();
+        assert_eq!(first.checked, 4);
+        assert!(!first.finished);
+        assert!(db.cleanup_cursor().unwrap().is_some());
+
+        let second = scan(db, SymlinkMode::default(), false, limits, 1_000, true).unwrap();
+        assert_eq!(second.checked, 2);
+        assert!(second.finished);
+        assert!(db.cleanup_cursor().unwrap().is_none());
+        let mut found: Vec<_> = first.missing.into_iter().chain(second.missing).collect();
+        found.sort();
+        assert_eq!(found, vec![missing.clone()]);
+
+        // Existing files were verified at 1000; only the missing one is due
+        let recent = ScanLimits {
+            max_age_check: Some(60),
+            ..ScanLimits::default()
+        };
+        let third = scan(db, SymlinkMode::default(), false, recent, 1_030, true).unwrap();
+        assert_eq!((third.checked, third.recently_checked), (1, 5));
+        assert_eq!(third.missing, vec![missing]);
+
+        let fourth = scan(db, SymlinkMode::default(), false, recent, 1_100, true).unwrap();
+        assert_eq!((fourth.checked, fourth.recently_checked), (6, 0));
+    }
+
+    #[test]
+    fn test_dry_run_leaves_database_unchanged() {
+        let test_db = TestDb::new("test_cleanup_dry_run");
+        let db = test_db.db();
+        let kept = TempFile::create("cleanup_dry_run_kept.txt").unwrap();
+        db.insert(kept.path(), vec!["tag".into()]).unwrap();
+        let gone = TempFile::create("cleanup_dry_run_missing.txt").unwrap();
+        let missing = gone.path().to_path_buf();
+        db.insert(&missing, vec!["tag".into()]).unwrap();
+        db.set_note(&missing, NoteRecord::new("still here".into()))
+            .unwrap();
+        std::fs::remove_file(&missing).unwrap();
+
+        let limits = ScanLimits {
+            incremental: Some(1),
+            ..ScanLimits::default()
+        };
+        execute(
+            db,
+            SymlinkMode::default(),
+            false,
+            limits,
+            config::PathFormat::Absolute,
+            true,
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(db.get_tags(&missing).unwrap(), Some(vec!["tag".into()]));
+        assert!(db.get_note(&missing).unwrap().is_some());
+        assert_eq!(db.last_checked(kept.path()).unwrap(), None);
+        assert!(db.cleanup_cursor().unwrap().is_none());
+    }
+}
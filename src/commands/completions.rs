@@ -0,0 +1,225 @@
+//! Completions command - shell completions and integration snippets
+
+use crate::TagrError;
+use crate::cli::{Cli, CompletionsCommands, HookShell};
+use clap::CommandFactory;
+use clap_complete::Shell;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, TagrError>;
+
+/// Marker file that enables auto-tagging for a directory
+pub const RULES_FILE: &str = ".tagr-rules";
+
+/// Execute the completions command
+///
+/// With a bare `shell`, prints its completion script to stdout.
+///
+/// # Errors
+/// Returns an error if the shell cannot be detected, the completion file
+/// already exists without `--force`, or writing fails
+pub fn execute(
+    shell: Option<Shell>,
+    command: Option<&CompletionsCommands>,
+    quiet: bool,
+) -> Result<()> {
+    match command {
+        Some(CompletionsCommands::Install { shell, force }) => {
+            let shell = match shell {
+                Some(shell) => *shell,
+                None => Shell::from_env().ok_or_else(|| {
+                    TagrError::InvalidInput(
+                        "Could not detect shell from $SHELL; pass it explicitly".into(),
+                    )
+                })?,
+            };
+            let path = install(shell, *force)?;
+            if !quiet {
+                println!("Installed {shell} completions to {}", path.display());
+                println!("{}", reload_hint(shell));
+            }
+        }
+        Some(CompletionsCommands::ShellHook { shell }) => print!("{}", shell_hook(*shell)),
+        None => {
+            if let Some(shell) = shell {
+                let mut script = Vec::new();
+                generate_static(shell, &mut script);
+                io::stdout().write_all(&script)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write the completion script for `shell` to `buf`
+pub fn generate_static(shell: Shell, buf: &mut dyn Write) {
+    clap_complete::generate(shell, &mut Cli::command(), "tagr", buf);
+}
+
+/// Conventional per-user completion file for `shell`
+///
+/// - bash: `~/.local/share/bash-completion/completions/tagr`
+/// - zsh: `~/.zfunc/_tagr` (must be on `$fpath`)
+/// - fish: `~/.config/fish/completions/tagr.fish`
+///
+/// Returns `None` for shells without a per-user completion directory.
+#[must_use]
+pub fn install_path(shell: Shell) -> Option<PathBuf> {
+    match shell {
+        Shell::Bash => {
+            dirs::data_dir().map(|dir| dir.join("bash-completion").join("completions").join("tagr"))
+        }
+        Shell::Zsh => dirs::home_dir().map(|dir| dir.join(".zfunc").join("_tagr")),
+        Shell::Fish => {
+            dirs::config_dir().map(|dir| dir.join("fish").join("completions").join("tagr.fish"))
+        }
+        _ => None,
+    }
+}
+
+/// Install the completion script for `shell`, returning the written path
+///
+/// # Errors
+/// Returns an error if the shell has no known install location, the file
+/// exists and `force` is false, or the file cannot be written
+pub fn install(shell: Shell, force: bool) -> Result<PathBuf> {
+    let path = install_path(shell).ok_or_else(|| {
+        TagrError::InvalidInput(format!(
+            "No standard completion directory for {shell}; use 'tagr completions {shell}' and redirect the output"
+        ))
+    })?;
+    write_completions(shell, &path, force)?;
+    Ok(path)
+}
+
+fn write_completions(shell: Shell, path: &Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        return Err(TagrError::InvalidInput(format!(
+            "{} already exists; use --force to overwrite",
+            path.display()
+        )));
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut script = Vec::new();
+    generate_static(shell, &mut script);
+    std::fs::write(path, script)?;
+    Ok(())
+}
+
+fn reload_hint(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Zsh => {
+            "Add 'fpath+=~/.zfunc' before 'compinit' in ~/.zshrc, then restart your shell."
+        }
+        Shell::Fish => "Completions load automatically in new fish sessions.",
+        _ => "Restart your shell to load the completions.",
+    }
+}
+
+/// Generate a shell hook that auto-tags on directory change
+///
+/// When the shell enters a directory containing a [`RULES_FILE`], the hook runs
+/// `tagr bulk propagate-by-dir` on it. The file check happens in the shell, so
+/// `tagr` is only spawned for directories that opted in.
+///
+/// # Examples
+///
+/// ```
+/// use tagr::cli::HookShell;
+/// use tagr::commands::completions::shell_hook;
+///
+/// let hook = shell_hook(HookShell::Zsh);
+/// assert!(hook.contains("add-zsh-hook chpwd"));
+/// ```
+#[must_use]
+pub fn shell_hook(shell: HookShell) -> String {
+    let run = "tagr bulk propagate-by-dir \"$PWD\" --yes --quiet";
+
+    match shell {
+        HookShell::Bash => format!(
+            r#"# tagr auto-tagging hook for bash
+# Add to ~/.bashrc: eval "$(tagr completions shell-hook --shell bash)"
+__tagr_hook() {{
+    local status=$?
+    if [ "$PWD" != "$__TAGR_LAST_DIR" ]; then
+        __TAGR_LAST_DIR="$PWD"
+        [ -f {RULES_FILE} ] && {run}
+    fi
+    return $status
+}}
+case ";$PROMPT_COMMAND;" in
+    *";__tagr_hook;"*) ;;
+    *) PROMPT_COMMAND="__tagr_hook${{PROMPT_COMMAND:+;$PROMPT_COMMAND}}" ;;
+esac
+"#
+        ),
+        HookShell::Zsh => format!(
+            r#"# tagr auto-tagging hook for zsh
+# Add to ~/.zshrc: eval "$(tagr completions shell-hook --shell zsh)"
+__tagr_hook() {{
+    [[ -f {RULES_FILE} ]] && {run}
+}}
+autoload -Uz add-zsh-hook
+add-zsh-hook chpwd __tagr_hook
+"#
+        ),
+        HookShell::Fish => format!(
+            r#"# tagr auto-tagging hook for fish
+# Add to ~/.config/fish/config.fish: tagr completions shell-hook --shell fish | source
+function __tagr_hook --on-variable PWD
+    test -f {RULES_FILE}; and {run}
+end
+"#
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_static_bash() {
+        let mut buf = Vec::new();
+        generate_static(Shell::Bash, &mut buf);
+        let script = String::from_utf8(buf).unwrap();
+        assert!(script.contains("_tagr()"));
+        assert!(script.contains("complete"));
+    }
+
+    #[test]
+    fn test_write_completions_refuses_overwrite() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("completions").join("tagr");
+
+        write_completions(Shell::Bash, &path, false).unwrap();
+        assert!(path.exists());
+
+        std::fs::write(&path, "custom").unwrap();
+        assert!(write_completions(Shell::Bash, &path, false).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "custom");
+
+        write_completions(Shell::Bash, &path, true).unwrap();
+        assert_ne!(std::fs::read_to_string(&path).unwrap(), "custom");
+    }
+
+    #[test]
+    fn test_shell_hooks_use_native_mechanism() {
+        assert!(shell_hook(HookShell::Bash).contains("PROMPT_COMMAND="));
+        assert!(shell_hook(HookShell::Zsh).contains("add-zsh-hook chpwd __tagr_hook"));
+        assert!(shell_hook(HookShell::Fish).contains("--on-variable PWD"));
+    }
+
+    #[test]
+    fn test_shell_hooks_check_rules_file_first() {
+        for shell in [HookShell::Bash, HookShell::Zsh, HookShell::Fish] {
+            let hook = shell_hook(shell);
+            let check = hook.find(RULES_FILE).unwrap();
+            let spawn = hook.find("tagr bulk propagate-by-dir").unwrap();
+            assert!(check < spawn, "{shell:?} hook spawns tagr before checking");
+        }
+    }
+}
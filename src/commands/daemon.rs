@@ -0,0 +1,624 @@
+//! Daemon command - answer searches over a Unix socket from an open database
+//!
+//! Opening a large database dominates the cost of a quick `tagr search`.
+//! `tagr daemon` opens it once and serves queries on a socket next to the
+//! database (see [`socket_path`]). While the socket exists, plain tag searches
+//! (`tagr search -t ...` with `--any-tag`/`--all-tags` and `--no-hierarchy`,
+//! printed as text) are forwarded to it.
+//!
+//! sled lets one process hold a database open, so every other command takes a
+//! [`Lease`] before opening the database itself: the daemon closes it until
+//! the lease is dropped, then opens it again. Searches arriving meanwhile are
+//! answered with [`Response::Unavailable`] and the client searches directly.
+//! A lease whose holder died without returning it lapses once the daemon can
+//! open the database again after [`LEASE_GRACE`].
+//!
+//! Connections are served one at a time; one that stays idle for
+//! [`IDLE_TIMEOUT`] is closed so it cannot hold up other clients.
+//!
+//! # Protocol
+//!
+//! Each message is a 4-byte big-endian length followed by that many bytes of
+//! JSON: a [`Request`] from the client, answered by one [`Response`]. A
+//! connection may carry any number of requests.
+
+use crate::{
+    Pair, TagrError,
+    cli::{SearchMode, SearchParams},
+    commands::search,
+    db::{DbError, DbOptions, ReadOnlyDatabase},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::cell::RefCell;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+type Result<T> = std::result::Result<T, TagrError>;
+
+/// Largest accepted message (64 MiB)
+const MAX_MESSAGE_BYTES: u32 = 64 * 1024 * 1024;
+
+/// How long a client waits on the daemon before falling back
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long the daemon waits for the next request on a connection
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a lease keeps the daemon from reopening the database unasked
+pub const LEASE_GRACE: Duration = Duration::from_secs(10);
+
+/// How long to keep retrying an open while the lock is being released
+///
+/// sled releases its lock in the background after the last handle is dropped.
+const LOCK_WAIT: Duration = Duration::from_secs(1);
+
+/// A request sent to the daemon
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    /// Check that the daemon is alive
+    Ping,
+    /// Find files by tags, like `tagr search -t ...`
+    Search {
+        tags: Vec<String>,
+        /// Match any tag instead of all of them
+        #[serde(default)]
+        any: bool,
+        /// Match tags exactly instead of by hierarchy prefix
+        #[serde(default)]
+        no_hierarchy: bool,
+        /// Directory globs whose files are dropped from the results
+        #[serde(default)]
+        exclude_dirs: Vec<String>,
+    },
+    /// Close the database so the client can open it, until `Resume`
+    Release,
+    /// Return a lease taken with `Release`
+    Resume,
+    /// Stop the daemon after replying
+    Shutdown,
+}
+
+impl Request {
+    /// The search parameters a `Search` request stands for
+    #[must_use]
+    pub fn search_params(tags: &[String], any: bool, no_hierarchy: bool) -> SearchParams {
+        SearchParams {
+            tags: tags.to_vec(),
+            tag_mode: if any {
+                SearchMode::Any
+            } else {
+                SearchMode::All
+            },
+            no_hierarchy,
+            ..SearchParams::default()
+        }
+    }
+}
+
+/// The daemon's reply to a [`Request`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    /// Reply to `Ping`
+    Pong,
+    /// Matching files with their tags, in path order
+    Files { pairs: Vec<Pair> },
+    /// The database is leased out; search it directly
+    Unavailable,
+    /// Reply to `Release`, once the daemon has closed the database
+    Released,
+    /// Reply to `Resume`
+    Resumed,
+    /// Reply to `Shutdown`
+    Stopping,
+    /// The request failed
+    Error { message: String },
+}
+
+/// Write one length-prefixed JSON message
+///
+/// # Errors
+/// Returns an error if the message is too large or cannot be written.
+pub fn write_message<W: Write, T: Serialize>(writer: &mut W, message: &T) -> io::Result<()> {
+    let json = serde_json::to_vec(message)?;
+    let len = u32::try_from(json.len())
+        .ok()
+        .filter(|len| *len <= MAX_MESSAGE_BYTES)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Message too large"))?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(&json)?;
+    writer.flush()
+}
+
+/// Read one length-prefixed JSON message
+///
+/// # Errors
+/// Returns `UnexpectedEof` if the stream ends before a message starts, or
+/// `InvalidData` if the message is too large or not valid JSON.
+pub fn read_message<R: Read, T: DeserializeOwned>(reader: &mut R) -> io::Result<T> {
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len);
+    if len > MAX_MESSAGE_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Message of {len} bytes exceeds the {MAX_MESSAGE_BYTES} byte limit"),
+        ));
+    }
+    let mut json = vec![0; len as usize];
+    reader.read_exact(&mut json)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// Socket the daemon for the database at `db_path` listens on
+#[must_use]
+pub fn socket_path(db_path: &Path) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push(".sock");
+    PathBuf::from(path)
+}
+
+/// Retry `open` while the database is locked, for up to [`LOCK_WAIT`]
+///
+/// # Errors
+/// Returns the last error from `open`.
+pub fn retry_while_locked<T>(
+    mut open: impl FnMut() -> std::result::Result<T, DbError>,
+) -> std::result::Result<T, DbError> {
+    let started = Instant::now();
+    loop {
+        match open() {
+            Err(DbError::Locked { .. }) if started.elapsed() < LOCK_WAIT => {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// The daemon's hold on the database
+struct DaemonState {
+    /// The open database, `None` while leased out
+    db: Option<ReadOnlyDatabase>,
+    /// Leases taken and not yet returned
+    leases: usize,
+    /// When the last lease was taken
+    leased_at: Option<Instant>,
+}
+
+/// The query daemon, listening on a Unix socket
+pub struct QueryDaemon {
+    listener: UnixListener,
+    socket: PathBuf,
+    db_path: PathBuf,
+    options: DbOptions,
+    state: RefCell<DaemonState>,
+}
+
+impl QueryDaemon {
+    /// Listen on `socket`, serving `db`
+    ///
+    /// A leftover socket from a daemon that is no longer running is replaced.
+    /// When `db` is leased out, it is reopened from the same path and options.
+    ///
+    /// # Errors
+    /// Returns an error if a daemon is already answering on `socket` or the
+    /// socket cannot be created.
+    pub fn bind(db: ReadOnlyDatabase, socket: &Path) -> Result<Self> {
+        if socket.exists() {
+            if UnixStream::connect(socket).is_ok() {
+                return Err(TagrError::InvalidInput(format!(
+                    "A daemon is already listening on {}",
+                    socket.display()
+                )));
+            }
+            std::fs::remove_file(socket)?;
+        }
+        let listener = UnixListener::bind(socket)?;
+        Ok(Self {
+            listener,
+            socket: socket.to_path_buf(),
+            db_path: db.path().to_path_buf(),
+            options: db.options(),
+            state: RefCell::new(DaemonState {
+                db: Some(db),
+                leases: 0,
+                leased_at: None,
+            }),
+        })
+    }
+
+    /// Handle connections until a client sends `Shutdown`
+    pub fn run(&self) {
+        for stream in self.listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("Warning: failed to accept connection: {e}");
+                    continue;
+                }
+            };
+            match self.serve(stream) {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(e) => eprintln!("Warning: connection failed: {e}"),
+            }
+        }
+    }
+
+    /// Answer requests on one connection; returns whether to shut down
+    fn serve(&self, stream: UnixStream) -> io::Result<bool> {
+        stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+        stream.set_write_timeout(Some(IDLE_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = BufWriter::new(stream);
+        loop {
+            let request = match read_message(&mut reader) {
+                Ok(request) => request,
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+                // An idle client is dropped so others are not kept waiting
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    return Ok(false);
+                }
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                    let message = format!("Invalid request: {e}");
+                    write_message(&mut writer, &Response::Error { message })?;
+                    return Ok(false);
+                }
+                Err(e) => return Err(e),
+            };
+            let stop = request == Request::Shutdown;
+            write_message(&mut writer, &self.handle(&request))?;
+            if stop {
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Answer a single request
+    #[must_use]
+    pub fn handle(&self, request: &Request) -> Response {
+        match request {
+            Request::Ping => Response::Pong,
+            Request::Search {
+                tags,
+                any,
+                no_hierarchy,
+                exclude_dirs,
+            } => {
+                let Some(db) = self.database() else {
+                    return Response::Unavailable;
+                };
+                let params = Request::search_params(tags, *any, *no_hierarchy);
+                match search::find_tagged(&db, &params, exclude_dirs) {
+                    Ok(pairs) => Response::Files { pairs },
+                    Err(e) => Response::Error {
+                        message: e.to_string(),
+                    },
+                }
+            }
+            Request::Release => {
+                let mut state = self.state.borrow_mut();
+                state.db = None;
+                state.leases += 1;
+                state.leased_at = Some(Instant::now());
+                Response::Released
+            }
+            Request::Resume => {
+                let mut state = self.state.borrow_mut();
+                state.leases = state.leases.saturating_sub(1);
+                if state.leases == 0 && state.db.is_none() {
+                    // The client has just closed the database; wait for its lock
+                    state.db = retry_while_locked(|| self.open()).ok();
+                }
+                Response::Resumed
+            }
+            Request::Shutdown => Response::Stopping,
+        }
+    }
+
+    /// The database, reopened if no lease stands in the way
+    ///
+    /// A lease older than [`LEASE_GRACE`] no longer blocks reopening: if the
+    /// database can be opened, its holder is gone.
+    fn database(&self) -> Option<ReadOnlyDatabase> {
+        let mut state = self.state.borrow_mut();
+        if state.db.is_none() {
+            let lapsed = state
+                .leased_at
+                .is_none_or(|leased_at| leased_at.elapsed() >= LEASE_GRACE);
+            if state.leases > 0 && !lapsed {
+                return None;
+            }
+            state.db = Some(self.open().ok()?);
+            state.leases = 0;
+        }
+        state.db.clone()
+    }
+
+    fn open(&self) -> std::result::Result<ReadOnlyDatabase, DbError> {
+        ReadOnlyDatabase::open_with(&self.db_path, self.options)
+    }
+}
+
+impl Drop for QueryDaemon {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+/// A connection to a running daemon
+pub struct Client {
+    stream: UnixStream,
+}
+
+impl Client {
+    /// Connect to the daemon on `socket`
+    ///
+    /// # Errors
+    /// Returns an error if no daemon is listening on `socket`.
+    pub fn connect(socket: &Path) -> io::Result<Self> {
+        let stream = UnixStream::connect(socket)?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+        Ok(Self { stream })
+    }
+
+    /// Send a request and wait for its response
+    ///
+    /// # Errors
+    /// Returns an error if the daemon cannot be reached or replies with an
+    /// invalid message.
+    pub fn request(&mut self, request: &Request) -> io::Result<Response> {
+        write_message(&mut self.stream, request)?;
+        read_message(&mut self.stream)
+    }
+}
+
+/// Keeps a running daemon from holding the database until dropped
+///
+/// Take one before opening the database while a daemon may be running; see
+/// the [module docs](self).
+#[must_use = "the daemon reopens the database when the lease is dropped"]
+pub struct Lease {
+    db_path: PathBuf,
+}
+
+impl Lease {
+    /// Ask the daemon for `db_path` to close the database
+    ///
+    /// Returns `None` if no daemon answers; nothing needs to be returned then.
+    pub fn take(db_path: &Path) -> Option<Self> {
+        match forward(db_path, &Request::Release) {
+            Some(Response::Released) => Some(Self {
+                db_path: db_path.to_path_buf(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        let _ = forward(&self.db_path, &Request::Resume);
+    }
+}
+
+/// Ask the daemon for `db_path` to answer `request`
+///
+/// Returns `None` if no daemon is running or it does not answer, so the
+/// caller can open the database itself.
+#[must_use]
+pub fn forward(db_path: &Path, request: &Request) -> Option<Response> {
+    let socket = socket_path(db_path);
+    if !socket.exists() {
+        return None;
+    }
+    Client::connect(&socket)
+        .and_then(|mut client| client.request(request))
+        .ok()
+}
+
+/// Execute the daemon command
+///
+/// Serves `db_path` until stopped, or with `stop`, asks the running daemon to
+/// exit.
+///
+/// # Errors
+/// Returns an error if the database or socket cannot be opened, or with
+/// `stop`, if no daemon is running.
+pub fn execute(
+    db_path: &Path,
+    db: Option<ReadOnlyDatabase>,
+    stop: bool,
+    quiet: bool,
+) -> Result<()> {
+    let socket = socket_path(db_path);
+    if stop {
+        return match forward(db_path, &Request::Shutdown) {
+            Some(Response::Stopping) => {
+                if !quiet {
+                    println!("Stopped the daemon on {}", socket.display());
+                }
+                Ok(())
+            }
+            _ => Err(TagrError::InvalidInput(format!(
+                "No daemon is running on {}",
+                socket.display()
+            ))),
+        };
+    }
+
+    let db = db.ok_or_else(|| TagrError::InvalidInput("No database to serve".into()))?;
+    let daemon = QueryDaemon::bind(db, &socket)?;
+    if !quiet {
+        println!(
+            "Serving searches on {} (stop with 'tagr daemon --stop')",
+            socket.display()
+        );
+    }
+    daemon.run();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TempFile, TestDb};
+
+    fn round_trip<T: Serialize + DeserializeOwned>(message: &T) -> T {
+        let mut buf = Vec::new();
+        write_message(&mut buf, message).unwrap();
+        let len = u32::from_be_bytes(buf[..4].try_into().unwrap()) as usize;
+        assert_eq!(len, buf.len() - 4);
+        read_message(&mut buf.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn test_messages_round_trip() {
+        let requests = [
+            Request::Ping,
+            Request::Search {
+                tags: vec!["rust".into(), "lang:go".into()],
+                any: true,
+                no_hierarchy: false,
+                exclude_dirs: vec!["**/target".into()],
+            },
+            Request::Release,
+            Request::Resume,
+            Request::Shutdown,
+        ];
+        for request in requests {
+            assert_eq!(round_trip(&request), request);
+        }
+
+        let responses = [
+            Response::Pong,
+            Response::Files {
+                pairs: vec![Pair::new("/tmp/a.rs".into(), vec!["rust".into()])],
+            },
+            Response::Unavailable,
+            Response::Released,
+            Response::Resumed,
+            Response::Stopping,
+            Response::Error {
+                message: "boom".into(),
+            },
+        ];
+        for response in responses {
+            assert_eq!(round_trip(&response), response);
+        }
+    }
+
+    #[test]
+    fn test_search_request_defaults_optional_fields() {
+        let request: Request =
+            serde_json::from_str(r#"{"type":"search","tags":["rust"]}"#).unwrap();
+        assert_eq!(
+            request,
+            Request::Search {
+                tags: vec!["rust".into()],
+                any: false,
+                no_hierarchy: false,
+                exclude_dirs: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_message_rejects_oversized_and_truncated() {
+        let mut oversized = (MAX_MESSAGE_BYTES + 1).to_be_bytes().to_vec();
+        oversized.extend_from_slice(b"{}");
+        let err = read_message::<_, Request>(&mut oversized.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut truncated = 10u32.to_be_bytes().to_vec();
+        truncated.extend_from_slice(b"{\"ty");
+        let err = read_message::<_, Request>(&mut truncated.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_daemon_answers_over_socket() {
+        let db = TestDb::new("test_daemon_answers_over_socket");
+        let rust = TempFile::create("daemon_main.rs").unwrap();
+        let docs = TempFile::create("daemon_README.md").unwrap();
+        db.db().insert(rust.path(), vec!["rust".into()]).unwrap();
+        db.db().insert(docs.path(), vec!["docs".into()]).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("tagr.sock");
+        let daemon = QueryDaemon::bind(ReadOnlyDatabase::from(db.db().clone()), &socket).unwrap();
+        let server = std::thread::spawn(move || daemon.run());
+
+        // A client that connects and goes quiet is dropped after IDLE_TIMEOUT
+        let _idle = UnixStream::connect(&socket).unwrap();
+        let mut client = Client::connect(&socket).unwrap();
+        assert_eq!(client.request(&Request::Ping).unwrap(), Response::Pong);
+        let search = Request::Search {
+            tags: vec!["rust".into()],
+            any: false,
+            no_hierarchy: false,
+            exclude_dirs: Vec::new(),
+        };
+        assert_eq!(
+            client.request(&search).unwrap(),
+            Response::Files {
+                pairs: vec![Pair::new(rust.path().to_path_buf(), vec!["rust".into()])],
+            }
+        );
+        assert_eq!(
+            client.request(&Request::Shutdown).unwrap(),
+            Response::Stopping
+        );
+        server.join().unwrap();
+        assert!(!socket.exists());
+    }
+
+    #[test]
+    fn test_lease_closes_and_reopens_database() {
+        use crate::db::Database;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("leased_db");
+        let first = TempFile::create("lease_first.rs").unwrap();
+        let second = TempFile::create("lease_second.rs").unwrap();
+        let db = Database::open(&db_path).unwrap();
+        db.insert(first.path(), vec!["rust".into()]).unwrap();
+
+        let daemon = QueryDaemon::bind(ReadOnlyDatabase::from(db), &socket_path(&db_path)).unwrap();
+        let server = std::thread::spawn(move || daemon.run());
+        let search = Request::Search {
+            tags: vec!["rust".into()],
+            any: false,
+            no_hierarchy: false,
+            exclude_dirs: Vec::new(),
+        };
+
+        let lease = Lease::take(&db_path).unwrap();
+        assert_eq!(forward(&db_path, &search), Some(Response::Unavailable));
+        {
+            let db = retry_while_locked(|| Database::open(&db_path)).unwrap();
+            db.insert(second.path(), vec!["rust".into()]).unwrap();
+        }
+        drop(lease);
+
+        let Some(Response::Files { pairs }) = forward(&db_path, &search) else {
+            panic!("daemon did not reopen the database");
+        };
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(
+            forward(&db_path, &Request::Shutdown),
+            Some(Response::Stopping)
+        );
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,192 @@
+//! Dedup command - find files with identical tag sets or identical content
+
+use crate::{
+    Pair, TagrError,
+    cli::{DedupBy, execute_per_file},
+    config,
+    db::ReadOnlyDatabase,
+    output,
+};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, TagrError>;
+
+/// Group files that carry exactly the same set of tags
+///
+/// Tag order and repeats are ignored. Files without tags are never grouped,
+/// and only groups of two or more files are returned. Groups are ordered by
+/// their sorted tag set, and the paths in each group are sorted.
+#[must_use]
+pub fn group_by_tags(pairs: &[Pair]) -> Vec<Vec<PathBuf>> {
+    let mut groups: BTreeMap<Vec<&str>, Vec<PathBuf>> = BTreeMap::new();
+    for pair in pairs.iter().filter(|pair| !pair.tags.is_empty()) {
+        let mut key: Vec<&str> = pair.tags.iter().map(String::as_str).collect();
+        key.sort_unstable();
+        key.dedup();
+        groups.entry(key).or_default().push(pair.file.clone());
+    }
+
+    finish_groups(groups.into_values())
+}
+
+/// Group files whose contents are byte-for-byte identical
+///
+/// Files are first grouped by size, and only files sharing a size are
+/// hashed. Missing or unreadable files are skipped.
+#[must_use]
+pub fn group_by_content(files: &[PathBuf]) -> Vec<Vec<PathBuf>> {
+    let mut by_size: BTreeMap<u64, Vec<&PathBuf>> = BTreeMap::new();
+    for file in files {
+        if let Ok(metadata) = std::fs::metadata(file)
+            && metadata.is_file()
+        {
+            by_size.entry(metadata.len()).or_default().push(file);
+        }
+    }
+
+    let mut groups: BTreeMap<[u8; 32], Vec<PathBuf>> = BTreeMap::new();
+    for candidates in by_size.into_values().filter(|files| files.len() > 1) {
+        for file in candidates {
+            if let Ok(hash) = hash_file(file) {
+                groups.entry(hash).or_default().push(file.clone());
+            }
+        }
+    }
+
+    finish_groups(groups.into_values())
+}
+
+/// BLAKE3 hash of a file's contents
+fn hash_file(path: &Path) -> io::Result<[u8; 32]> {
+    let mut hasher = blake3::Hasher::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(*hasher.finalize().as_bytes())
+}
+
+/// Keep groups with duplicates, sorting paths within and across groups
+fn finish_groups(groups: impl Iterator<Item = Vec<PathBuf>>) -> Vec<Vec<PathBuf>> {
+    let mut groups: Vec<_> = groups
+        .filter(|files| files.len() > 1)
+        .map(|mut files| {
+            files.sort();
+            files
+        })
+        .collect();
+    groups.sort();
+    groups
+}
+
+/// Execute the dedup command
+///
+/// Prints each group of duplicates separated by a blank line. With `exec`,
+/// the command runs on every file except the first of each group, so the
+/// first path is the one kept.
+///
+/// # Errors
+/// Returns an error if the database cannot be read.
+pub fn execute(
+    db: &ReadOnlyDatabase,
+    by: DedupBy,
+    exec: Option<&str>,
+    path_format: config::PathFormat,
+    quiet: bool,
+) -> Result<()> {
+    let pairs = db.list_all()?;
+    let groups = match by {
+        DedupBy::Tags => group_by_tags(&pairs),
+        DedupBy::Content => {
+            let files: Vec<PathBuf> = pairs.into_iter().map(|pair| pair.file).collect();
+            group_by_content(&files)
+        }
+    };
+
+    if groups.is_empty() {
+        if !quiet {
+            println!("No duplicates found.");
+        }
+        return Ok(());
+    }
+
+    for (i, group) in groups.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        for file in group {
+            println!("{}", output::format_path(file, path_format));
+        }
+    }
+
+    if !quiet {
+        let files: usize = groups.iter().map(Vec::len).sum();
+        println!("\n{} group(s) of duplicates ({files} files)", groups.len());
+    }
+
+    if let Some(cmd) = exec {
+        let extra: Vec<&PathBuf> = groups.iter().flat_map(|group| &group[1..]).collect();
+        execute_per_file(&extra, cmd, quiet);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempFile;
+
+    fn pair(file: &str, tags: &[&str]) -> Pair {
+        Pair::new(
+            PathBuf::from(file),
+            tags.iter().map(ToString::to_string).collect(),
+        )
+    }
+
+    #[test]
+    fn test_group_by_tags() {
+        let pairs = [
+            pair("c.rs", &["rust", "cli"]),
+            pair("a.rs", &["cli", "rust"]),
+            pair("b.rs", &["rust", "cli", "rust"]),
+            pair("lib.rs", &["rust"]),
+            pair("x.md", &["docs"]),
+            pair("y.md", &["docs"]),
+            pair("note.txt", &[]),
+            pair("other.txt", &[]),
+        ];
+
+        assert_eq!(
+            group_by_tags(&pairs),
+            vec![
+                vec![
+                    PathBuf::from("a.rs"),
+                    PathBuf::from("b.rs"),
+                    PathBuf::from("c.rs")
+                ],
+                vec![PathBuf::from("x.md"), PathBuf::from("y.md")],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_content() {
+        let a = TempFile::create("dedup_a.txt").unwrap();
+        let b = TempFile::create("dedup_b.txt").unwrap();
+        let c = TempFile::create("dedup_c.txt").unwrap();
+        std::fs::write(a.path(), "same").unwrap();
+        std::fs::write(b.path(), "same").unwrap();
+        std::fs::write(c.path(), "diff").unwrap();
+
+        let files = [
+            a.path().to_path_buf(),
+            b.path().to_path_buf(),
+            c.path().to_path_buf(),
+            PathBuf::from("/nonexistent/dedup.txt"),
+        ];
+        let mut expected = vec![a.path().to_path_buf(), b.path().to_path_buf()];
+        expected.sort();
+        assert_eq!(group_by_content(&files), vec![expected]);
+    }
+}
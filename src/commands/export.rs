@@ -0,0 +1,267 @@
+//! Export command - write the database to portable formats
+
+use crate::{Pair, TagrError, cli::ExportCommands, db::ReadOnlyDatabase, output::csv::format_row};
+use std::path::Path;
+
+type Result<T> = std::result::Result<T, TagrError>;
+
+/// Row layout for CSV export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvLayout {
+    /// One `file,tag` row per file-tag pair
+    Long,
+    /// One `file,tags` row per file, tags joined with `;`
+    Wide,
+}
+
+/// Execute an export subcommand
+///
+/// `dry_run` only affects `export xattr`, the one export that changes files
+/// other than its output.
+///
+/// # Errors
+/// Returns an error if reading the database or writing the output fails
+#[cfg_attr(not(feature = "xattr"), allow(unused_variables))]
+pub fn execute(
+    db: &ReadOnlyDatabase,
+    command: &ExportCommands,
+    dry_run: bool,
+    quiet: bool,
+) -> Result<()> {
+    match command {
+        ExportCommands::Csv {
+            output,
+            wide,
+            no_header,
+            ..
+        } => {
+            let layout = if *wide {
+                CsvLayout::Wide
+            } else {
+                CsvLayout::Long
+            };
+            let mut pairs = db.list_all()?;
+            pairs.sort_by(|a, b| a.file.cmp(&b.file));
+            let csv = to_csv(&pairs, layout, !no_header);
+            write_export(&csv, output.as_deref(), pairs.len(), quiet)
+        }
+        #[cfg(feature = "xattr")]
+        ExportCommands::Xattr { sync } => {
+            let mut pairs = db.list_all()?;
+            pairs.sort_by(|a, b| a.file.cmp(&b.file));
+            export_xattr(&pairs, *sync, dry_run, quiet);
+            Ok(())
+        }
+        #[cfg(feature = "sqlite")]
+        ExportCommands::Sqlite { output } => {
+            db.export_to_sqlite(output)?;
+            if !quiet {
+                println!("Exported database to {}", output.display());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Write tags to each file's `user.xdg.tags` attribute as a comma-separated list
+///
+/// Without `sync`, tags already in the attribute are kept and the database's
+/// are added. With `sync`, the attribute holds exactly the database's tags
+/// and is removed from files without tags. Files on filesystems without
+/// extended attributes are warned about and skipped; other failures are
+/// listed in the summary. With `dry_run`, the changes are printed instead of
+/// written.
+#[cfg(feature = "xattr")]
+pub fn export_xattr(pairs: &[Pair], sync: bool, dry_run: bool, quiet: bool) {
+    use super::bulk::BulkOpSummary;
+    use super::import::{XDG_TAGS_XATTR, parse_xattr_tags};
+    use colored::Colorize;
+
+    let mut summary = BulkOpSummary::new();
+    if dry_run {
+        println!("{}", "=== Dry Run Mode ===".yellow().bold());
+    }
+    for pair in pairs {
+        let file = &pair.file;
+        let existing = match xattr::get(file, XDG_TAGS_XATTR) {
+            Ok(value) => value.and_then(|value| parse_xattr_tags(&value)),
+            Err(e) if e.kind() == std::io::ErrorKind::Unsupported => {
+                eprintln!(
+                    "Warning: {} does not support extended attributes, skipping",
+                    file.display()
+                );
+                summary.add_skip();
+                continue;
+            }
+            Err(e) => {
+                summary.add_error(format!("{}: {e}", file.display()));
+                continue;
+            }
+        };
+
+        let mut tags = if sync {
+            Vec::new()
+        } else {
+            existing.clone().unwrap_or_default()
+        };
+        for tag in &pair.tags {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        if existing.as_ref() == Some(&tags) || (existing.is_none() && tags.is_empty()) {
+            summary.add_skip();
+            continue;
+        }
+
+        if dry_run {
+            summary.add_success();
+            if tags.is_empty() {
+                println!("  Would remove {XDG_TAGS_XATTR}: {}", file.display());
+            } else {
+                println!(
+                    "  Would export: {} [{}]",
+                    file.display(),
+                    tags.join(", ").cyan()
+                );
+            }
+            continue;
+        }
+
+        let result = if tags.is_empty() {
+            xattr::remove(file, XDG_TAGS_XATTR)
+        } else {
+            xattr::set(file, XDG_TAGS_XATTR, tags.join(",").as_bytes())
+        };
+        match result {
+            Ok(()) => {
+                summary.add_success();
+                if !quiet {
+                    println!("✓ Exported: {} [{}]", file.display(), tags.join(", "));
+                }
+            }
+            Err(e) => summary.add_error(format!("{}: {e}", file.display())),
+        }
+    }
+
+    if !quiet || summary.errors > 0 {
+        summary.print("Export xattr");
+    }
+    if dry_run {
+        println!("\n{}", "Run without --dry-run to apply changes.".yellow());
+    }
+}
+
+/// Render pairs as CSV in the given layout, optionally with a header row
+#[must_use]
+pub fn to_csv(pairs: &[Pair], layout: CsvLayout, header: bool) -> String {
+    let mut csv = String::new();
+    if header {
+        csv.push_str(&match layout {
+            CsvLayout::Long => format_row(&["file", "tag"]),
+            CsvLayout::Wide => format_row(&["file", "tags"]),
+        });
+    }
+    for pair in pairs {
+        let file = pair.file.to_string_lossy();
+        match layout {
+            CsvLayout::Long => {
+                for tag in &pair.tags {
+                    csv.push_str(&format_row(&[file.as_ref(), tag]));
+                }
+            }
+            CsvLayout::Wide => {
+                csv.push_str(&format_row(&[file.as_ref(), &pair.tags.join(";")]));
+            }
+        }
+    }
+    csv
+}
+
+fn write_export(content: &str, output: Option<&Path>, files: usize, quiet: bool) -> Result<()> {
+    match output {
+        Some(path) => {
+            std::fs::write(path, content)?;
+            if !quiet {
+                println!("Exported {files} file(s) to {}", path.display());
+            }
+        }
+        None => print!("{content}"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn pairs() -> Vec<Pair> {
+        vec![
+            Pair::new(
+                PathBuf::from("/docs/a.txt"),
+                vec!["rust".into(), "notes".into()],
+            ),
+            Pair::new(PathBuf::from("/docs/b, \"c\".txt"), vec!["draft".into()]),
+        ]
+    }
+
+    #[test]
+    fn test_to_csv_long_layout() {
+        let csv = to_csv(&pairs(), CsvLayout::Long, true);
+        assert_eq!(
+            csv,
+            "file,tag\n/docs/a.txt,rust\n/docs/a.txt,notes\n\"/docs/b, \"\"c\"\".txt\",draft\n"
+        );
+    }
+
+    #[cfg(feature = "xattr")]
+    #[test]
+    fn test_export_xattr_round_trips_through_import() {
+        use crate::commands::import::{XDG_TAGS_XATTR, import_xattr};
+        use crate::testing::{TempFile, TestDb};
+
+        let file = TempFile::create("xattr_export.txt").unwrap();
+        if let Err(e) = xattr::set(file.path(), XDG_TAGS_XATTR, b"stale,kept") {
+            eprintln!("skipping: cannot set xattr: {e}");
+            return;
+        }
+        let pairs = vec![Pair::new(
+            file.path().to_path_buf(),
+            vec!["rust".into(), "kept".into()],
+        )];
+
+        // A dry run leaves the attribute alone
+        export_xattr(&pairs, true, true, true);
+        let value = xattr::get(file.path(), XDG_TAGS_XATTR).unwrap().unwrap();
+        assert_eq!(value, b"stale,kept");
+
+        export_xattr(&pairs, false, false, true);
+        let value = xattr::get(file.path(), XDG_TAGS_XATTR).unwrap().unwrap();
+        assert_eq!(value, b"stale,kept,rust");
+
+        export_xattr(&pairs, true, false, true);
+        let test_db = TestDb::new("test_export_xattr_round_trip");
+        import_xattr(test_db.db(), file.path().parent().unwrap(), false, true).unwrap();
+        let mut tags = test_db.db().get_tags(file.path()).unwrap().unwrap();
+        tags.sort();
+        assert_eq!(tags, vec!["kept", "rust"]);
+
+        export_xattr(
+            &[Pair::new(file.path().to_path_buf(), Vec::new())],
+            true,
+            false,
+            true,
+        );
+        assert!(xattr::get(file.path(), XDG_TAGS_XATTR).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_to_csv_wide_layout_without_header() {
+        let csv = to_csv(&pairs(), CsvLayout::Wide, false);
+        assert_eq!(
+            csv,
+            "/docs/a.txt,rust;notes\n\"/docs/b, \"\"c\"\".txt\",draft\n"
+        );
+    }
+}
@@ -11,8 +11,10 @@
 //! - Show filter usage statistics
 
 use crate::TagrError;
-use crate::cli::FilterCommands;
-use crate::filters::{FileMode, FilterCriteria, FilterManager, TagMode};
+use crate::cli::{FilterCommands, TableArgs, TableFormat};
+use crate::filters::{FileMode, Filter, FilterCriteria, FilterManager, ImportResolution, TagMode};
+use crate::output::TableFormatter;
+use crate::ui::{DialoguerInput, UserInput};
 use std::io::Write;
 
 type Result<T> = std::result::Result<T, TagrError>;
@@ -33,8 +35,8 @@ type Result<T> = std::result::Result<T, TagrError>;
 /// - Any filter operation fails
 pub fn execute(command: &FilterCommands, quiet: bool) -> Result<()> {
     match command {
-        FilterCommands::List => {
-            list_filters(quiet)?;
+        FilterCommands::List { table } => {
+            list_filters(*table, quiet)?;
         }
         FilterCommands::Show { name } => {
             show_filter(name, quiet)?;
@@ -68,10 +70,12 @@ pub fn execute(command: &FilterCommands, quiet: bool) -> Result<()> {
                 &criteria.file_patterns,
                 file_mode,
                 &criteria.excludes,
+                &criteria.exclude_file_patterns,
                 criteria.regex_tag,
                 criteria.regex_file,
                 &criteria.virtual_tags,
                 virtual_mode,
+                &criteria.exclude_virtual_tags,
                 quiet,
             )?;
         }
@@ -99,13 +103,13 @@ pub fn execute(command: &FilterCommands, quiet: bool) -> Result<()> {
 }
 
 /// List all saved filters
-fn list_filters(quiet: bool) -> Result<()> {
+fn list_filters(table: TableArgs, quiet: bool) -> Result<()> {
     let filter_path = crate::filters::get_filter_path()?;
     let manager = FilterManager::new(filter_path);
 
     let filters = manager.list()?;
 
-    if filters.is_empty() {
+    if filters.is_empty() && table.format == TableFormat::Text {
         if !quiet {
             println!("No saved filters.");
             println!("Create one with: tagr filter create <name> [options]");
@@ -113,58 +117,44 @@ fn list_filters(quiet: bool) -> Result<()> {
         return Ok(());
     }
 
-    if !quiet {
-        println!("Saved Filters:");
-        println!();
+    if quiet && table.format == TableFormat::Text {
+        for filter in filters {
+            println!("{}", filter.name);
+        }
+        return Ok(());
     }
 
-    let max_name_len = filters
-        .iter()
-        .map(|f| f.name.len())
-        .max()
-        .unwrap_or(0)
-        .max(4);
-
+    let mut rows =
+        TableFormatter::new(&["NAME", "DESCRIPTION", "CRITERIA"]).with_max_width(table.max_width);
     for filter in filters {
         let tags_count = filter.criteria.tags.len();
         let files_count = filter.criteria.file_patterns.len();
 
-        if quiet {
-            println!("{}", filter.name);
+        let desc = if filter.description.is_empty() {
+            "(no description)"
         } else {
-            let desc = if filter.description.is_empty() {
-                String::from("(no description)")
-            } else {
-                filter.description.clone()
-            };
+            filter.description.as_str()
+        };
 
-            println!("  {:<width$}  {}", filter.name, desc, width = max_name_len);
-
-            if tags_count > 0 || files_count > 0 {
-                let mut details = Vec::new();
-                if tags_count > 0 {
-                    details.push(format!(
-                        "{} tag{}",
-                        tags_count,
-                        if tags_count == 1 { "" } else { "s" }
-                    ));
-                }
-                if files_count > 0 {
-                    details.push(format!(
-                        "{} pattern{}",
-                        files_count,
-                        if files_count == 1 { "" } else { "s" }
-                    ));
-                }
-                println!(
-                    "  {:<width$}  ({})",
-                    "",
-                    details.join(", "),
-                    width = max_name_len
-                );
-            }
+        let mut details = Vec::new();
+        if tags_count > 0 {
+            details.push(format!(
+                "{} tag{}",
+                tags_count,
+                if tags_count == 1 { "" } else { "s" }
+            ));
         }
+        if files_count > 0 {
+            details.push(format!(
+                "{} pattern{}",
+                files_count,
+                if files_count == 1 { "" } else { "s" }
+            ));
+        }
+
+        rows.add_row(&[&filter.name, desc, &details.join(", ")]);
     }
+    rows.print(table.format);
 
     Ok(())
 }
@@ -195,10 +185,12 @@ fn create_filter(
     file_patterns: &[String],
     file_mode: FileMode,
     excludes: &[String],
+    exclude_file_patterns: &[String],
     regex_tag: bool,
     regex_file: bool,
     virtual_tags: &[String],
     virtual_mode: TagMode,
+    exclude_virtual_tags: &[String],
     quiet: bool,
 ) -> Result<()> {
     let filter_path = crate::filters::get_filter_path()?;
@@ -210,11 +202,14 @@ fn create_filter(
         file_patterns: file_patterns.to_vec(),
         file_mode,
         excludes: excludes.to_vec(),
+        exclude_file_patterns: exclude_file_patterns.to_vec(),
         regex_tag,
         regex_file,
         glob_files: false,
         virtual_tags: virtual_tags.to_vec(),
         virtual_mode,
+        exclude_virtual_tags: exclude_virtual_tags.to_vec(),
+        expression: None,
     };
 
     let desc = description.unwrap_or("").to_string();
@@ -330,7 +325,15 @@ fn import_filters(
     let filter_path = crate::filters::get_filter_path()?;
     let manager = FilterManager::new(filter_path);
 
-    let (imported, skipped) = manager.import(path, overwrite, skip_existing)?;
+    let input = DialoguerInput::new();
+    let mut prompt = |filter: &Filter| prompt_import_conflict(&input, filter);
+    let resolve: Option<&mut dyn FnMut(&Filter) -> ImportResolution> =
+        if overwrite || skip_existing || quiet {
+            None
+        } else {
+            Some(&mut prompt)
+        };
+    let (imported, skipped) = manager.import(path, overwrite, skip_existing || quiet, resolve)?;
 
     if !quiet {
         println!(
@@ -350,6 +353,29 @@ fn import_filters(
     Ok(())
 }
 
+/// Ask how to resolve an imported filter whose name is already taken
+///
+/// Cancelling either prompt skips the filter.
+fn prompt_import_conflict(input: &dyn UserInput, filter: &Filter) -> ImportResolution {
+    let choices = vec![
+        "Overwrite the existing filter".to_string(),
+        "Skip this filter".to_string(),
+        "Import under a new name".to_string(),
+    ];
+    let prompt = format!("Filter '{}' already exists", filter.name);
+    match input.prompt_select(&prompt, &choices, Some(1)) {
+        Ok(Some(0)) => ImportResolution::Overwrite,
+        Ok(Some(2)) => {
+            let default = format!("{}-imported", filter.name);
+            match input.prompt_text("New filter name", Some(&default), false) {
+                Ok(Some(name)) => ImportResolution::Rename(name.trim().to_string()),
+                _ => ImportResolution::Skip,
+            }
+        }
+        _ => ImportResolution::Skip,
+    }
+}
+
 /// Show filter usage statistics
 ///
 /// NOTE: This is a stub for future implementation of usage statistics.
@@ -0,0 +1,191 @@
+//! Get command - show everything tagr knows about one file
+//!
+//! The inverse of search: instead of listing the files matching criteria, list
+//! the tags, note and saved filters that apply to a file.
+
+use super::search::filter_matches;
+use crate::{
+    TagrError, config,
+    db::{NoteRecord, ReadOnlyDatabase},
+    filters::{Filter, FilterManager},
+    output,
+};
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, TagrError>;
+
+/// A file's tags, note and matching saved filters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileRecord {
+    /// Database key of the file
+    pub file: PathBuf,
+    /// Tags on the file
+    pub tags: Vec<String>,
+    /// The file's note, if it has one
+    pub note: Option<NoteRecord>,
+    /// Names of saved filters whose search would list the file
+    pub filters: Vec<String>,
+}
+
+/// Execute the get command
+///
+/// A file that isn't in the database is reported, not treated as an error.
+///
+/// # Errors
+/// Returns an error if the database or the saved filters cannot be read.
+pub fn execute(
+    db: &ReadOnlyDatabase,
+    file: &Path,
+    json: bool,
+    symlinks: config::SymlinkMode,
+    exclude_dirs: &[String],
+    path_format: config::PathFormat,
+) -> Result<()> {
+    // A stale entry for a deleted file can still be looked up by absolute path
+    let key = symlinks
+        .normalize(file)
+        .or_else(|_| std::path::absolute(file))?;
+    let filters = FilterManager::new(crate::filters::get_filter_path()?).list()?;
+    let record = get_record(db, &key, &filters, exclude_dirs)?;
+    let display = output::format_path(&key, path_format);
+
+    if !json {
+        match record {
+            Some(record) => print_record(&record, &display),
+            None => println!("{display} is not in the database"),
+        }
+        return Ok(());
+    }
+
+    let value = match record {
+        Some(record) => serde_json::json!({
+            "file": display,
+            "tracked": true,
+            "tags": record.tags,
+            "note": record.note.map(|note| note.content),
+            "filters": record.filters,
+        }),
+        None => serde_json::json!({ "file": display, "tracked": false }),
+    };
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| TagrError::InvalidInput(format!("Failed to serialize JSON: {e}")))?;
+    println!("{json}");
+    Ok(())
+}
+
+/// Look up `file`'s record, or `None` if it isn't in the database
+///
+/// Filters whose criteria can't be evaluated (e.g. an invalid regex) are
+/// reported on stderr and left out.
+///
+/// # Errors
+/// Returns an error if a database lookup fails.
+pub fn get_record(
+    db: &ReadOnlyDatabase,
+    file: &Path,
+    filters: &[Filter],
+    exclude_dirs: &[String],
+) -> Result<Option<FileRecord>> {
+    let Some(pair) = db.get_pair(file)? else {
+        return Ok(None);
+    };
+
+    let mut matching = Vec::new();
+    for filter in filters {
+        match filter_matches(db, &filter.criteria, exclude_dirs, file) {
+            Ok(true) => matching.push(filter.name.clone()),
+            Ok(false) => {}
+            Err(e) => eprintln!("Warning: skipping filter '{}': {e}", filter.name),
+        }
+    }
+
+    Ok(Some(FileRecord {
+        file: pair.file,
+        tags: pair.tags,
+        note: db.get_note(file)?,
+        filters: matching,
+    }))
+}
+
+fn print_record(record: &FileRecord, display: &str) {
+    println!("File: {display}");
+    if record.tags.is_empty() {
+        println!("Tags: (none)");
+    } else {
+        println!("Tags: {}", record.tags.join(", "));
+    }
+    if record.filters.is_empty() {
+        println!("Filters: (none)");
+    } else {
+        println!("Filters: {}", record.filters.join(", "));
+    }
+    match &record.note {
+        Some(note) => println!("Note:\n{}", note.content),
+        None => println!("Note: (none)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::FilterCriteria;
+    use crate::testing::{TempFile, TestDb};
+
+    fn filter(name: &str, criteria: FilterCriteria) -> Filter {
+        Filter::new(name.to_string(), String::new(), criteria)
+    }
+
+    #[test]
+    fn test_get_record() {
+        let test_db = TestDb::new("test_get_record");
+        let file = TempFile::create("get_record.rs").unwrap();
+        let other = TempFile::create("get_record_other.md").unwrap();
+        let db = test_db.db();
+        db.insert(file.path(), vec!["rust".into(), "cli".into()])
+            .unwrap();
+        db.insert(other.path(), vec!["docs".into()]).unwrap();
+        db.set_note(file.path(), NoteRecord::new("entry point".into()))
+            .unwrap();
+        let db = ReadOnlyDatabase::from(db.clone());
+
+        let filters = [
+            filter("rust", FilterCriteria::builder().tag("rust".into()).build()),
+            filter(
+                "rust-no-cli",
+                FilterCriteria::builder()
+                    .tag("rust".into())
+                    .exclude("cli".into())
+                    .build(),
+            ),
+            filter(
+                "sources",
+                FilterCriteria::builder()
+                    .virtual_tag("ext:.rs".into())
+                    .build(),
+            ),
+            filter("docs", FilterCriteria::builder().tag("docs".into()).build()),
+        ];
+
+        let record = get_record(&db, file.path(), &filters, &[])
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.tags, ["rust", "cli"]);
+        assert_eq!(record.note.unwrap().content, "entry point");
+        assert_eq!(record.filters, ["rust", "sources"]);
+
+        let record = get_record(&db, other.path(), &filters, &[])
+            .unwrap()
+            .unwrap();
+        assert!(record.note.is_none());
+        assert_eq!(record.filters, ["docs"]);
+    }
+
+    #[test]
+    fn test_get_record_untracked() {
+        let test_db = TestDb::new("test_get_record_untracked");
+        let file = TempFile::create("get_untracked.txt").unwrap();
+        let db = ReadOnlyDatabase::from(test_db.db().clone());
+
+        assert!(get_record(&db, file.path(), &[], &[]).unwrap().is_none());
+    }
+}
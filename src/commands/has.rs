@@ -0,0 +1,124 @@
+//! Has command - check whether a file has tags, for scripts
+//!
+//! The answer is the exit status: `tagr has file.txt rust && echo yes`.
+
+use crate::{TagrError, config, db::ReadOnlyDatabase};
+use std::path::Path;
+
+type Result<T> = std::result::Result<T, TagrError>;
+
+/// Execute the has command
+///
+/// Returns whether `file` has all of `tags`, or any of them with `any`. A
+/// file that isn't in the database has no tags. Nothing is printed unless
+/// `verbose`.
+///
+/// # Errors
+/// Returns an error if no tags are given or the database cannot be read
+pub fn execute(
+    db: &ReadOnlyDatabase,
+    file: &Path,
+    tags: &[String],
+    any: bool,
+    symlinks: config::SymlinkMode,
+    verbose: bool,
+) -> Result<bool> {
+    if tags.is_empty() {
+        return Err(TagrError::InvalidInput(
+            "Specify at least one tag to check".into(),
+        ));
+    }
+
+    // A stale entry for a deleted file can still be looked up by absolute path
+    let key = symlinks
+        .normalize(file)
+        .or_else(|_| std::path::absolute(file))?;
+    let Some(file_tags) = db.get_tags(&key)? else {
+        if verbose {
+            println!("{} is not in the database", file.display());
+        }
+        return Ok(false);
+    };
+
+    let (present, missing): (Vec<&String>, Vec<&String>) =
+        tags.iter().partition(|tag| file_tags.contains(tag));
+    let found = if any {
+        !present.is_empty()
+    } else {
+        missing.is_empty()
+    };
+
+    if verbose {
+        if found {
+            let shown: Vec<&str> = present.iter().map(|tag| tag.as_str()).collect();
+            println!("{} has {}", file.display(), shown.join(", "));
+        } else {
+            let shown: Vec<&str> = missing.iter().map(|tag| tag.as_str()).collect();
+            println!("{} does not have {}", file.display(), shown.join(", "));
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TempFile, TestDb};
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    fn has(test_db: &TestDb, file: &Path, names: &[&str], any: bool) -> Result<bool> {
+        let db = ReadOnlyDatabase::from(test_db.db().clone());
+        execute(
+            &db,
+            file,
+            &tags(names),
+            any,
+            config::SymlinkMode::default(),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_has_all_and_any() {
+        let test_db = TestDb::new("test_has_all_and_any");
+        let file = TempFile::create("has_all_any.txt").unwrap();
+        test_db
+            .db()
+            .insert(file.path(), tags(&["rust", "cli"]))
+            .unwrap();
+
+        assert!(has(&test_db, file.path(), &["rust"], false).unwrap());
+        assert!(has(&test_db, file.path(), &["rust", "cli"], false).unwrap());
+        assert!(!has(&test_db, file.path(), &["rust", "go"], false).unwrap());
+        assert!(has(&test_db, file.path(), &["rust", "go"], true).unwrap());
+        assert!(!has(&test_db, file.path(), &["go", "python"], true).unwrap());
+    }
+
+    #[test]
+    fn test_has_untracked_file_is_false() {
+        let test_db = TestDb::new("test_has_untracked");
+        let file = TempFile::create("has_untracked.txt").unwrap();
+
+        assert!(!has(&test_db, file.path(), &["rust"], false).unwrap());
+        assert!(
+            !has(
+                &test_db,
+                Path::new("/no/such/has_file.txt"),
+                &["rust"],
+                true
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_has_without_tags_is_an_error() {
+        let test_db = TestDb::new("test_has_no_tags");
+        let file = TempFile::create("has_no_tags.txt").unwrap();
+
+        assert!(has(&test_db, file.path(), &[], false).is_err());
+    }
+}
@@ -0,0 +1,546 @@
+//! Import command - read file-tag pairs from portable formats
+//!
+//! The inverse of [`super::export`]: rows are validated one by one and bad
+//! rows are reported with their line number instead of aborting the import.
+
+use colored::Colorize;
+use dialoguer::Confirm;
+
+use super::bulk::BulkOpSummary;
+use super::export::CsvLayout;
+use crate::{TagrError, cli::ImportCommands, db::Database};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, TagrError>;
+
+/// A validated CSV row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportRow {
+    /// 1-based line the record starts on
+    pub line: u64,
+    /// File path (made absolute)
+    pub file: PathBuf,
+    /// Tags for the file from this row
+    pub tags: Vec<String>,
+}
+
+/// Result of parsing a CSV import file
+#[derive(Debug, Default)]
+pub struct CsvImport {
+    /// Rows that passed validation
+    pub rows: Vec<ImportRow>,
+    /// Rows that failed validation, as `line N: reason`
+    pub errors: Vec<String>,
+}
+
+/// Execute an import subcommand
+///
+/// # Errors
+/// Returns an error if the input cannot be read or the confirmation prompt
+/// fails. Invalid rows are reported in the summary, not as errors.
+pub fn execute(db: &Database, command: &ImportCommands, quiet: bool) -> Result<()> {
+    match command {
+        ImportCommands::Csv {
+            file,
+            wide,
+            merge,
+            has_header,
+            dry_run,
+            yes,
+            ..
+        } => {
+            let layout = if *wide {
+                CsvLayout::Wide
+            } else {
+                CsvLayout::Long
+            };
+            let content = std::fs::read_to_string(file).map_err(|e| {
+                TagrError::InvalidInput(format!("Failed to read {}: {}", file.display(), e))
+            })?;
+            let import = parse_csv(&content, layout, *has_header);
+            import_csv(db, file, import, *merge, *dry_run, *yes, quiet)
+        }
+        #[cfg(feature = "xattr")]
+        ImportCommands::Xattr { dir, dry_run } => import_xattr(db, dir, *dry_run, quiet),
+        #[cfg(feature = "sqlite")]
+        ImportCommands::Sqlite { file, on_conflict } => {
+            let report = db.import_from_sqlite(file, *on_conflict)?;
+            for missing in &report.missing {
+                eprintln!("Warning: file not found, skipping: {}", missing.display());
+            }
+            if !quiet {
+                println!(
+                    "Imported {} file(s), skipped {} already tagged, {} missing",
+                    report.imported,
+                    report.skipped,
+                    report.missing.len()
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Parse and validate CSV rows in the given layout
+///
+/// Quoted fields may contain commas, quotes and line breaks. A header row
+/// (`file,tag` or `file,tags`) is skipped when `has_header` is set or when
+/// the first record looks like one. Each remaining row must name an existing
+/// file and carry at least one valid tag; rows that don't are collected in
+/// [`CsvImport::errors`] and parsing continues.
+#[must_use]
+pub fn parse_csv(content: &str, layout: CsvLayout, has_header: bool) -> CsvImport {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let mut import = CsvImport::default();
+
+    for (i, result) in reader.records().enumerate() {
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map_or(0, csv::Position::line);
+                import.errors.push(format!("line {line}: {e}"));
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, csv::Position::line);
+        if i == 0 && (has_header || is_header(&record)) {
+            continue;
+        }
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        match parse_row(&record, layout) {
+            Ok((file, tags)) => import.rows.push(ImportRow { line, file, tags }),
+            Err(reason) => import.errors.push(format!("line {line}: {reason}")),
+        }
+    }
+    import
+}
+
+fn is_header(record: &csv::StringRecord) -> bool {
+    let field = |i| record.get(i).map(|f: &str| f.trim().to_ascii_lowercase());
+    field(0).as_deref() == Some("file") && matches!(field(1).as_deref(), Some("tag" | "tags"))
+}
+
+fn parse_row(
+    record: &csv::StringRecord,
+    layout: CsvLayout,
+) -> std::result::Result<(PathBuf, Vec<String>), String> {
+    let header = match layout {
+        CsvLayout::Long => "file,tag",
+        CsvLayout::Wide => "file,tags",
+    };
+    if record.len() != 2 {
+        return Err(format!(
+            "expected 2 fields ({header}), found {}; quote paths containing commas",
+            record.len()
+        ));
+    }
+
+    let path = record[0].trim();
+    if path.is_empty() {
+        return Err("missing file path".to_string());
+    }
+    let file = std::path::absolute(path).map_err(|e| format!("invalid path '{path}': {e}"))?;
+    if !file.exists() {
+        return Err(format!("file not found: {}", file.display()));
+    }
+
+    let tags: Vec<String> = match layout {
+        CsvLayout::Long => vec![record[1].trim().to_string()],
+        CsvLayout::Wide => record[1]
+            .split(';')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(ToString::to_string)
+            .collect(),
+    };
+    if tags.is_empty() || tags.iter().any(String::is_empty) {
+        return Err(format!("no tag for {}", file.display()));
+    }
+    if let Some(tag) = tags
+        .iter()
+        .find(|tag| tag.chars().any(|c| c.is_whitespace() || c.is_control()))
+    {
+        return Err(format!(
+            "invalid tag '{tag}': tags cannot contain whitespace"
+        ));
+    }
+    Ok((file, tags))
+}
+
+/// Group rows by file, keeping first-seen order and dropping duplicate tags
+fn group_rows(rows: Vec<ImportRow>) -> Vec<(PathBuf, Vec<String>)> {
+    let mut grouped: Vec<(PathBuf, Vec<String>)> = Vec::new();
+    let mut index: HashMap<PathBuf, usize> = HashMap::new();
+    for row in rows {
+        let slot = *index.entry(row.file.clone()).or_insert_with(|| {
+            grouped.push((row.file, Vec::new()));
+            grouped.len() - 1
+        });
+        let tags = &mut grouped[slot].1;
+        for tag in row.tags {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+    grouped
+}
+
+/// Write parsed rows to the database and print a summary
+///
+/// With `merge`, imported tags are added to a file's existing tags;
+/// otherwise they replace them.
+///
+/// # Errors
+/// Returns an error if the confirmation prompt fails.
+pub fn import_csv(
+    db: &Database,
+    input_path: &Path,
+    import: CsvImport,
+    merge: bool,
+    dry_run: bool,
+    yes: bool,
+    quiet: bool,
+) -> Result<()> {
+    let mut summary = BulkOpSummary::new();
+    for error in import.errors {
+        summary.add_error(error);
+    }
+    let files = group_rows(import.rows);
+
+    if dry_run {
+        println!("{}", "=== Dry Run Mode ===".yellow().bold());
+        println!(
+            "Would {} tags for {} file(s)",
+            if merge { "merge" } else { "replace" },
+            files.len()
+        );
+        for (i, (file, tags)) in files.iter().enumerate().take(10) {
+            println!("  {}. {} <- [{}]", i + 1, file.display(), tags.join(", "));
+        }
+        if files.len() > 10 {
+            println!("  ... and {} more", files.len() - 10);
+        }
+        summary.print("Import CSV");
+        println!("\n{}", "Run without --dry-run to apply changes.".yellow());
+        return Ok(());
+    }
+
+    if !yes && !merge && !files.is_empty() {
+        let prompt = format!(
+            "Replace tags for {} file(s) from '{}'?",
+            files.len(),
+            input_path.display()
+        );
+        let confirmed = Confirm::new()
+            .with_prompt(prompt)
+            .interact()
+            .map_err(|e| TagrError::InvalidInput(format!("Failed to get confirmation: {e}")))?;
+        if !confirmed {
+            println!("Operation cancelled.");
+            return Ok(());
+        }
+    }
+
+    for (file, tags) in files {
+        let result = if merge {
+            db.add_tags(&file, tags)
+        } else {
+            db.insert(&file, tags)
+        };
+        match result {
+            Ok(()) => {
+                summary.add_success();
+                if !quiet {
+                    println!("✓ Imported: {}", file.display());
+                }
+            }
+            Err(e) => summary.add_error(format!("{}: {}", file.display(), e)),
+        }
+    }
+    db.flush()?;
+
+    if !quiet || summary.errors > 0 {
+        summary.print("Import CSV");
+    }
+    Ok(())
+}
+
+/// Extended attribute for tags shared between file managers and taggers
+#[cfg(feature = "xattr")]
+pub const XDG_TAGS_XATTR: &str = "user.xdg.tags";
+
+/// Extended attributes holding tags written by other taggers
+#[cfg(feature = "xattr")]
+pub const TAG_XATTRS: &[&str] = &[XDG_TAGS_XATTR, "com.apple.metadata:_kMDItemUserTags"];
+
+/// Split an attribute value into tags at commas and newlines
+///
+/// # Returns
+/// `None` if the value is not UTF-8 text, such as the binary property lists
+/// macOS Finder writes
+#[cfg(feature = "xattr")]
+#[must_use]
+pub fn parse_xattr_tags(value: &[u8]) -> Option<Vec<String>> {
+    let text = std::str::from_utf8(value).ok()?;
+    if text.starts_with("bplist") {
+        return None;
+    }
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split([',', '\n']).map(str::trim) {
+        if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    Some(tags)
+}
+
+/// Every regular file under `dir`, without following symlinks
+#[cfg(feature = "xattr")]
+fn walk_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            walk_files(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Tags from all of [`TAG_XATTRS`] set on `file`
+#[cfg(feature = "xattr")]
+fn read_xattr_tags(file: &Path) -> std::result::Result<Vec<String>, String> {
+    let mut tags: Vec<String> = Vec::new();
+    for name in TAG_XATTRS {
+        let value = match xattr::get(file, name) {
+            Ok(Some(value)) => value,
+            // Linux rejects names outside its namespaces, like the macOS one
+            Ok(None) => continue,
+            Err(e) if e.kind() == std::io::ErrorKind::Unsupported => continue,
+            Err(e) => return Err(format!("cannot read {name}: {e}")),
+        };
+        let parsed =
+            parse_xattr_tags(&value).ok_or_else(|| format!("{name} is not a text tag list"))?;
+        for tag in parsed {
+            if tag.chars().any(|c| c.is_whitespace() || c.is_control()) {
+                return Err(format!(
+                    "invalid tag '{tag}' in {name}: tags cannot contain whitespace"
+                ));
+            }
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+    Ok(tags)
+}
+
+/// Add tags stored in extended attributes of the files under `dir`
+///
+/// Tags are added to any the file already has in the database. Files
+/// without a tag attribute are skipped; unreadable or invalid attributes are
+/// reported in the summary.
+///
+/// # Errors
+/// Returns an error if `dir` cannot be read.
+#[cfg(feature = "xattr")]
+pub fn import_xattr(db: &Database, dir: &Path, dry_run: bool, quiet: bool) -> Result<()> {
+    let dir = dir.canonicalize().map_err(|e| {
+        TagrError::InvalidInput(format!("Cannot access directory {}: {e}", dir.display()))
+    })?;
+    let mut paths = Vec::new();
+    walk_files(&dir, &mut paths)?;
+    paths.sort();
+
+    let mut summary = BulkOpSummary::new();
+    let mut files = Vec::new();
+    for path in paths {
+        match read_xattr_tags(&path) {
+            Ok(tags) if tags.is_empty() => summary.add_skip(),
+            Ok(tags) => files.push((path, tags)),
+            Err(e) => summary.add_error(format!("{}: {e}", path.display())),
+        }
+    }
+
+    if dry_run {
+        println!("{}", "=== Dry Run Mode ===".yellow().bold());
+        println!("Would add tags to {} file(s)", files.len());
+        for (i, (file, tags)) in files.iter().enumerate().take(10) {
+            println!("  {}. {} <- [{}]", i + 1, file.display(), tags.join(", "));
+        }
+        if files.len() > 10 {
+            println!("  ... and {} more", files.len() - 10);
+        }
+        summary.print("Import xattr");
+        println!("\n{}", "Run without --dry-run to apply changes.".yellow());
+        return Ok(());
+    }
+
+    for (file, tags) in files {
+        match db.add_tags(&file, tags) {
+            Ok(()) => {
+                summary.add_success();
+                if !quiet {
+                    println!("✓ Imported: {}", file.display());
+                }
+            }
+            Err(e) => summary.add_error(format!("{}: {}", file.display(), e)),
+        }
+    }
+    db.flush()?;
+
+    if !quiet || summary.errors > 0 {
+        summary.print("Import xattr");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pair;
+    use crate::commands::export::to_csv;
+    use crate::testing::{TempFile, TestDb};
+
+    #[test]
+    fn test_parse_csv_reports_bad_rows_with_line_numbers() {
+        let file = TempFile::create("import, \"quoted\".txt").unwrap();
+        let quoted = format!(
+            "\"{}\"",
+            file.path().display().to_string().replace('"', "\"\"")
+        );
+        let content = format!(
+            "file,tag\n{quoted},rust\n/no/such/file.txt,rust\n{quoted},\n{quoted},bad tag\n{quoted},x,y\n"
+        );
+
+        let import = parse_csv(&content, CsvLayout::Long, false);
+
+        assert_eq!(
+            import.rows,
+            vec![ImportRow {
+                line: 2,
+                file: file.path().to_path_buf(),
+                tags: vec!["rust".into()],
+            }]
+        );
+        assert_eq!(import.errors.len(), 4);
+        assert!(import.errors[0].starts_with("line 3: file not found"));
+        assert!(import.errors[1].starts_with("line 4: no tag"));
+        assert!(import.errors[2].starts_with("line 5: invalid tag 'bad tag'"));
+        assert!(import.errors[3].starts_with("line 6: expected 2 fields"));
+    }
+
+    #[test]
+    fn test_parse_csv_header_detection() {
+        let file = TempFile::create("import_header.txt").unwrap();
+        let path = file.path().display().to_string();
+
+        let detected = parse_csv(&format!("File,Tags\n{path},a;b\n"), CsvLayout::Wide, false);
+        assert!(detected.errors.is_empty());
+        assert_eq!(detected.rows[0].tags, vec!["a", "b"]);
+
+        let forced = parse_csv(&format!("path,labels\n{path},a\n"), CsvLayout::Long, true);
+        assert!(forced.errors.is_empty());
+        assert_eq!(forced.rows.len(), 1);
+
+        let headerless = parse_csv(&format!("{path},a\n"), CsvLayout::Long, false);
+        assert_eq!(headerless.rows.len(), 1);
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let source = TestDb::new("csv_round_trip_source");
+        let target = TestDb::new("csv_round_trip_target");
+        let a = TempFile::create("round, trip \"a\".txt").unwrap();
+        let b = TempFile::create("round_trip_b.txt").unwrap();
+        source
+            .db()
+            .insert_pair(&Pair::new(
+                a.path().to_path_buf(),
+                vec!["rust".into(), "notes".into()],
+            ))
+            .unwrap();
+        source
+            .db()
+            .insert_pair(&Pair::new(b.path().to_path_buf(), vec!["draft".into()]))
+            .unwrap();
+        target
+            .db()
+            .insert_pair(&Pair::new(b.path().to_path_buf(), vec!["old".into()]))
+            .unwrap();
+
+        for layout in [CsvLayout::Long, CsvLayout::Wide] {
+            let csv = to_csv(&source.db().list_all().unwrap(), layout, true);
+            let import = parse_csv(&csv, layout, false);
+            assert!(import.errors.is_empty(), "{:?}", import.errors);
+            import_csv(
+                target.db(),
+                Path::new("-"),
+                import,
+                false,
+                false,
+                true,
+                true,
+            )
+            .unwrap();
+
+            let mut tags = target.db().get_tags(a.path()).unwrap().unwrap();
+            tags.sort();
+            assert_eq!(tags, vec!["notes", "rust"]);
+            assert_eq!(
+                target.db().get_tags(b.path()).unwrap().unwrap(),
+                vec!["draft"]
+            );
+        }
+    }
+
+    #[test]
+    fn test_import_csv_merge_keeps_existing_tags() {
+        let test_db = TestDb::new("csv_import_merge");
+        let db = test_db.db();
+        let file = TempFile::create("import_merge.txt").unwrap();
+        db.insert(file.path(), vec!["old".into()]).unwrap();
+
+        let content = format!("{},new\n", file.path().display());
+        let import = parse_csv(&content, CsvLayout::Long, false);
+        import_csv(db, Path::new("-"), import, true, false, false, true).unwrap();
+
+        let mut tags = db.get_tags(file.path()).unwrap().unwrap();
+        tags.sort();
+        assert_eq!(tags, vec!["new", "old"]);
+    }
+
+    #[cfg(feature = "xattr")]
+    #[test]
+    fn test_import_xattr_reads_xdg_tags() {
+        let test_db = TestDb::new("test_import_xattr");
+        let db = test_db.db();
+        let tagged = TempFile::create("xattr_tagged.txt").unwrap();
+        let dir = tagged.path().parent().unwrap();
+        let plain = dir.join("plain.txt");
+        std::fs::write(&plain, "x").unwrap();
+        if let Err(e) = xattr::set(tagged.path(), "user.xdg.tags", b"rust, cli\nwip,rust") {
+            // Some filesystems (or kernels) have no user xattrs
+            eprintln!("skipping: cannot set xattr: {e}");
+            return;
+        }
+        db.insert(tagged.path(), vec!["existing".into()]).unwrap();
+
+        import_xattr(db, dir, false, true).unwrap();
+
+        let mut tags = db.get_tags(tagged.path()).unwrap().unwrap();
+        tags.sort();
+        assert_eq!(tags, vec!["cli", "existing", "rust", "wip"]);
+        assert!(db.get_tags(&plain).unwrap().is_none());
+        assert_eq!(parse_xattr_tags(b"bplist00\xa1"), None);
+    }
+}
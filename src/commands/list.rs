@@ -1,61 +1,642 @@
 //! List command - list files or tags in the database
 
-use crate::{TagrError, cli::ListVariant, config, db::Database, output};
+use crate::{
+    Pair, TagrError,
+    cli::{ListFormat, ListSort, ListVariant, TableFormat},
+    config,
+    db::{DbError, ReadOnlyDatabase},
+    output::{self, TableFormatter},
+    ui::{OutputWriter, StdoutWriter},
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 type Result<T> = std::result::Result<T, TagrError>;
 
+/// Options controlling which entries the list command shows
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    /// Show only the N files with the most tags
+    pub most_tagged: Option<usize>,
+    /// Show only the N files with the fewest tags
+    pub least_tagged: Option<usize>,
+    /// Print files as an aligned table (when stdout is a terminal)
+    pub table: bool,
+    /// Sort files by this key (database order when `None`)
+    pub sort: Option<ListSort>,
+    /// Reverse the sort order
+    pub reverse: bool,
+    /// Show at most N files after sorting
+    pub limit: Option<usize>,
+    /// Skip the first N files after sorting
+    pub offset: usize,
+    /// Group files under each of their tags
+    pub by_tag: bool,
+    /// Restrict `by_tag` sections to these tags (all tags when empty)
+    pub only_tags: Vec<String>,
+    /// Group files under their first alphabetical tag only
+    pub once_per_file: bool,
+    /// Output format
+    pub format: ListFormat,
+    /// Truncate cells of the tags table wider than N characters
+    pub max_width: Option<usize>,
+    /// Skip and report undecodable entries instead of failing
+    pub skip_corrupt: bool,
+}
+
+impl ListOptions {
+    /// Whether any option that only applies to `list files` is set
+    const fn has_file_options(&self) -> bool {
+        self.most_tagged.is_some()
+            || self.least_tagged.is_some()
+            || self.table
+            || self.sort.is_some()
+            || self.reverse
+            || self.limit.is_some()
+            || self.offset > 0
+            || self.by_tag
+            || !self.only_tags.is_empty()
+            || self.once_per_file
+            || !matches!(self.format, ListFormat::Text | ListFormat::Tsv)
+    }
+}
+
+/// A listed file with the metadata used as its sort key
+#[derive(Debug)]
+struct ListEntry {
+    pair: Pair,
+    modified: Option<SystemTime>,
+    size: Option<u64>,
+    /// When the file was last tagged, in seconds since the Unix epoch
+    tagged: Option<i64>,
+}
+
+impl ListEntry {
+    /// Read the metadata or timestamps needed for `sort`; files without them
+    /// get no key
+    fn new(db: &ReadOnlyDatabase, pair: Pair, sort: Option<ListSort>) -> Self {
+        let metadata = match sort {
+            Some(ListSort::Modified | ListSort::Size) => std::fs::metadata(&pair.file).ok(),
+            Some(ListSort::Name | ListSort::Tagged) | None => None,
+        };
+        let tagged = match sort {
+            Some(ListSort::Tagged) => last_tagged(db, &pair.file),
+            _ => None,
+        };
+        Self {
+            modified: metadata.as_ref().and_then(|m| m.modified().ok()),
+            size: metadata.as_ref().map(std::fs::Metadata::len),
+            tagged,
+            pair,
+        }
+    }
+
+    fn to_json(
+        &self,
+        sort: Option<ListSort>,
+        path_format: config::PathFormat,
+    ) -> serde_json::Value {
+        let mut json = serde_json::json!({
+            "file": output::format_path(&self.pair.file, path_format),
+            "tags": self.pair.tags,
+        });
+        match sort {
+            Some(ListSort::Modified) => {
+                json["modified"] = self.modified.map_or(serde_json::Value::Null, |time| {
+                    DateTime::<Utc>::from(time)
+                        .to_rfc3339_opts(SecondsFormat::Secs, true)
+                        .into()
+                });
+            }
+            Some(ListSort::Size) => json["size"] = self.size.into(),
+            Some(ListSort::Tagged) => {
+                json["tagged"] = self
+                    .tagged
+                    .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+                    .map_or(serde_json::Value::Null, |time| {
+                        time.to_rfc3339_opts(SecondsFormat::Secs, true).into()
+                    });
+            }
+            Some(ListSort::Name) | None => {}
+        }
+        json
+    }
+}
+
 /// Execute the list command
 ///
 /// # Errors
-/// Returns an error if database operations fail
+/// Returns an error if database operations fail or if file-only options are
+/// used when listing tags
 pub fn execute(
-    db: &Database,
+    db: &ReadOnlyDatabase,
     variant: ListVariant,
+    options: &ListOptions,
     path_format: config::PathFormat,
     quiet: bool,
 ) -> Result<()> {
     match variant {
-        ListVariant::Files => list_files(db, path_format, quiet),
-        ListVariant::Tags => list_tags(db, quiet),
+        ListVariant::Files => {
+            if matches!(options.format, ListFormat::Markdown | ListFormat::Tsv)
+                && (options.most_tagged.is_some()
+                    || options.least_tagged.is_some()
+                    || options.by_tag
+                    || options.once_per_file)
+            {
+                return Err(TagrError::InvalidInput(
+                    "--format markdown and tsv cannot be combined with --most-tagged, \
+                     --least-tagged, --by-tag or --once-per-file"
+                        .into(),
+                ));
+            }
+            if options.max_width.is_some() {
+                return Err(TagrError::InvalidInput(
+                    "--max-width only applies to 'tagr list tags'".into(),
+                ));
+            }
+            if let Some(n) = options.most_tagged {
+                let ranked = db.most_tagged_files(n)?;
+                list_ranked_files(db, ranked, "Most", options.format, path_format, quiet)
+            } else if let Some(n) = options.least_tagged {
+                let ranked = db.least_tagged_files(n)?;
+                list_ranked_files(db, ranked, "Least", options.format, path_format, quiet)
+            } else if options.by_tag {
+                let sections = tag_sections(db, &options.only_tags)?;
+                list_tag_sections(&sections, options.format, path_format, quiet)
+            } else if options.once_per_file {
+                let pairs = if options.skip_corrupt {
+                    let (pairs, corrupt) = db.list_all_lenient()?;
+                    output::warn_corrupt_entries(&corrupt);
+                    pairs
+                } else {
+                    db.list_all()?
+                };
+                let sections = first_tag_sections(pairs);
+                list_tag_sections(&sections, options.format, path_format, quiet)
+            } else {
+                list_files(db, options, path_format, quiet)
+            }
+        }
+        ListVariant::Tags => {
+            if options.has_file_options() {
+                return Err(TagrError::InvalidInput(
+                    "--most-tagged, --least-tagged, --table, --sort, --reverse, --limit, \
+                     --offset, --by-tag, --only-tags, --once-per-file and --format only apply \
+                     to 'tagr list files'"
+                        .into(),
+                ));
+            }
+            list_tags(db, options.format, options.max_width, quiet)
+        }
     }
 }
 
-fn list_files(db: &Database, path_format: config::PathFormat, quiet: bool) -> Result<()> {
-    let all_pairs = db.list_all()?;
+fn list_files(
+    db: &ReadOnlyDatabase,
+    options: &ListOptions,
+    path_format: config::PathFormat,
+    quiet: bool,
+) -> Result<()> {
+    if options.format == ListFormat::Jsonl
+        && options.sort.is_none()
+        && !options.reverse
+        && options.offset == 0
+        && options.limit.is_none()
+    {
+        return stream_jsonl(db, options.skip_corrupt, path_format);
+    }
+
+    let pairs = if options.skip_corrupt {
+        let (pairs, corrupt) = db.list_all_lenient()?;
+        output::warn_corrupt_entries(&corrupt);
+        pairs
+    } else {
+        db.list_all()?
+    };
+    let mut entries: Vec<ListEntry> = pairs
+        .into_iter()
+        .map(|pair| ListEntry::new(db, pair, options.sort))
+        .collect();
+
+    match options.sort {
+        Some(sort) => sort_entries(&mut entries, sort, options.reverse),
+        None if options.reverse => entries.reverse(),
+        None => {}
+    }
+    let total = entries.len();
+    let page = output::Page {
+        offset: options.offset,
+        limit: options.limit,
+    };
+    page.apply(&mut entries);
 
-    if all_pairs.is_empty() {
+    if matches!(options.format, ListFormat::Json | ListFormat::Jsonl) {
+        let json: Vec<_> = entries
+            .iter()
+            .map(|entry| entry.to_json(options.sort, path_format))
+            .collect();
+        output::print_json(&json, options.format)?;
+        return Ok(());
+    }
+
+    if options.format == ListFormat::Markdown {
+        let pairs: Vec<Pair> = entries.into_iter().map(|entry| entry.pair).collect();
+        print!("{}", output::format_pairs_as_markdown(&pairs));
+        return Ok(());
+    }
+
+    if options.format == ListFormat::Tsv {
+        let pairs: Vec<Pair> = entries.into_iter().map(|entry| entry.pair).collect();
+        print!("{}", output::pairs_table(&pairs, path_format).render_tsv());
+        return Ok(());
+    }
+
+    if total == 0 {
         if !quiet {
             println!("No files found in database.");
         }
+        return Ok(());
+    }
+
+    if !quiet {
+        println!("Files in database:");
+    }
+    let shown = entries.len();
+    let pairs: Vec<Pair> = entries.into_iter().map(|entry| entry.pair).collect();
+    if options.table
+        && !quiet
+        && let Some(width) = output::terminal_width()
+    {
+        StdoutWriter::new().write(output::format_table(&pairs, path_format, width).trim_end());
     } else {
-        if !quiet {
-            println!("Files in database:");
-        }
-        for pair in all_pairs {
+        for pair in pairs {
             println!(
                 "{}",
                 output::file_with_tags(&pair.file, &pair.tags, path_format, quiet)
             );
         }
     }
+    if !quiet && let Some(footer) = page.footer(shown, total) {
+        println!("{footer}");
+    }
     Ok(())
 }
 
-fn list_tags(db: &Database, quiet: bool) -> Result<()> {
-    let tags = db.list_all_tags()?;
+/// Write every pair as a JSON line straight from the database, without buffering
+fn stream_jsonl(
+    db: &ReadOnlyDatabase,
+    skip_corrupt: bool,
+    path_format: config::PathFormat,
+) -> Result<()> {
+    let mut lines = output::JsonLines::stdout();
+    let mut corrupt = Vec::new();
+    for result in db.iter_pairs() {
+        let pair = match result {
+            Ok(pair) => pair,
+            Err(e @ DbError::CorruptEntry { .. }) if skip_corrupt => {
+                corrupt.push(e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if let Err(e) = lines.write_pair(&pair, path_format) {
+            output::end_on_broken_pipe(e)?;
+            break;
+        }
+    }
+    output::warn_corrupt_entries(&corrupt);
+    Ok(())
+}
+
+/// Sort entries by `sort`; files whose metadata is unavailable always sort last
+fn sort_entries(entries: &mut [ListEntry], sort: ListSort, reverse: bool) {
+    entries.sort_by(|a, b| {
+        let ordering = match sort {
+            ListSort::Name => {
+                let ordering = a.pair.file.cmp(&b.pair.file);
+                if reverse {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+            ListSort::Modified => largest_first(a.modified, b.modified, reverse),
+            ListSort::Size => largest_first(a.size, b.size, reverse),
+            ListSort::Tagged => largest_first(a.tagged, b.tagged, reverse),
+        };
+        ordering.then_with(|| a.pair.file.cmp(&b.pair.file))
+    });
+}
+
+/// When `file` was last tagged, or `None` if it has no timestamps
+pub(super) fn last_tagged(db: &ReadOnlyDatabase, file: &Path) -> Option<i64> {
+    db.get_timestamps(file)
+        .ok()
+        .flatten()
+        .map(|times| times.last_tagged)
+}
 
-    if tags.is_empty() {
+/// Compare optional keys, largest first (smallest first when `reverse`), `None` last
+pub(super) fn largest_first<T: Ord>(a: Option<T>, b: Option<T>, reverse: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if reverse => a.cmp(&b),
+        (Some(a), Some(b)) => b.cmp(&a),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn list_ranked_files(
+    db: &ReadOnlyDatabase,
+    ranked: Vec<(PathBuf, usize)>,
+    label: &str,
+    format: ListFormat,
+    path_format: config::PathFormat,
+    quiet: bool,
+) -> Result<()> {
+    if matches!(format, ListFormat::Json | ListFormat::Jsonl) {
+        let entries = ranked
+            .iter()
+            .map(|(file, count)| {
+                Ok(serde_json::json!({
+                    "file": output::format_path(file, path_format),
+                    "tags": db.get_tags(file)?.unwrap_or_default(),
+                    "tag_count": count,
+                }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        output::print_json(&entries, format)?;
+        return Ok(());
+    }
+
+    if ranked.is_empty() {
         if !quiet {
-            println!("No tags found in database.");
+            println!("No files found in database.");
+        }
+        return Ok(());
+    }
+
+    if !quiet {
+        println!("{label} tagged files:");
+    }
+    for (file, count) in ranked {
+        let path = output::format_path(&file, path_format);
+        if quiet {
+            println!("{path}");
+        } else {
+            println!("  {path} ({count} tag(s))");
+        }
+    }
+    Ok(())
+}
+
+/// Files grouped under a tag heading
+type TagSection = (String, Vec<PathBuf>);
+
+/// Collect every file under each tag, in tag order, limited to `only_tags` if non-empty
+fn tag_sections(db: &ReadOnlyDatabase, only_tags: &[String]) -> Result<Vec<TagSection>> {
+    db.list_all_tags()?
+        .into_iter()
+        .filter(|tag| only_tags.is_empty() || only_tags.contains(tag))
+        .map(|tag| {
+            let files = db.find_by_tag_sorted(&tag)?;
+            Ok((tag, files))
+        })
+        .collect()
+}
+
+/// Group each tagged file under its first alphabetical tag
+fn first_tag_sections(pairs: Vec<Pair>) -> Vec<TagSection> {
+    let mut sections: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for pair in pairs {
+        if let Some(tag) = pair.tags.iter().min() {
+            sections.entry(tag.clone()).or_default().push(pair.file);
+        }
+    }
+    sections
+        .into_iter()
+        .map(|(tag, mut files)| {
+            files.sort();
+            (tag, files)
+        })
+        .collect()
+}
+
+fn list_tag_sections(
+    sections: &[TagSection],
+    format: ListFormat,
+    path_format: config::PathFormat,
+    quiet: bool,
+) -> Result<()> {
+    if matches!(format, ListFormat::Json | ListFormat::Jsonl) {
+        let json: Vec<_> = sections
+            .iter()
+            .map(|(tag, files)| {
+                serde_json::json!({
+                    "tag": tag,
+                    "files": files
+                        .iter()
+                        .map(|file| output::format_path(file, path_format))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        output::print_json(&json, format)?;
+        return Ok(());
+    }
+
+    if sections.is_empty() {
+        if !quiet {
+            println!("No files found in database.");
+        }
+        return Ok(());
+    }
+
+    for (tag, files) in sections {
+        println!("[{tag}]");
+        for file in files {
+            println!("  {}", output::format_path(file, path_format));
         }
+    }
+    Ok(())
+}
+
+fn list_tags(
+    db: &ReadOnlyDatabase,
+    format: ListFormat,
+    max_width: Option<usize>,
+    quiet: bool,
+) -> Result<()> {
+    let tags = db.list_all_tags()?;
+    let table_format = if format == ListFormat::Tsv {
+        TableFormat::Tsv
     } else {
+        TableFormat::Text
+    };
+
+    if tags.is_empty() && table_format == TableFormat::Text {
         if !quiet {
-            println!("Tags in database:");
+            println!("No tags found in database.");
+        }
+    } else if quiet && table_format == TableFormat::Text {
+        for tag in tags {
+            println!("{tag}");
         }
+    } else {
+        let mut table = TableFormatter::new(&["TAG", "FILES"]).with_max_width(max_width);
         for tag in tags {
-            let count = db.find_by_tag(&tag)?.len();
-            println!("{}", output::tag_with_count(&tag, count, quiet));
+            let count = db.count_files_per_tag_fast(&tag)?.to_string();
+            table.add_row(&[&tag, &count]);
         }
+        table.print(table_format);
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn entry(file: &str, modified_secs: Option<u64>, size: Option<u64>) -> ListEntry {
+        ListEntry {
+            pair: Pair::new(PathBuf::from(file), vec![]),
+            modified: modified_secs.map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs)),
+            size,
+            tagged: None,
+        }
+    }
+
+    fn files(entries: &[ListEntry]) -> Vec<&str> {
+        entries
+            .iter()
+            .map(|e| e.pair.file.to_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_modified_newest_first_missing_last() {
+        let mut entries = vec![
+            entry("/old", Some(100), None),
+            entry("/missing", None, None),
+            entry("/new", Some(300), None),
+        ];
+
+        sort_entries(&mut entries, ListSort::Modified, false);
+        assert_eq!(files(&entries), vec!["/new", "/old", "/missing"]);
+
+        sort_entries(&mut entries, ListSort::Modified, true);
+        assert_eq!(files(&entries), vec!["/old", "/new", "/missing"]);
+    }
+
+    #[test]
+    fn test_sort_by_name_and_size() {
+        let mut entries = vec![
+            entry("/b", None, Some(10)),
+            entry("/a", None, Some(5)),
+            entry("/c", None, Some(20)),
+        ];
+
+        sort_entries(&mut entries, ListSort::Name, false);
+        assert_eq!(files(&entries), vec!["/a", "/b", "/c"]);
+
+        sort_entries(&mut entries, ListSort::Size, false);
+        assert_eq!(files(&entries), vec!["/c", "/b", "/a"]);
+    }
+
+    #[test]
+    fn test_sort_tagged_newest_first_untimed_last() {
+        use crate::testing::{TempFile, TestDb};
+
+        let test_db = TestDb::new("test_list_sort_tagged");
+        let file = TempFile::create("list_sort_tagged.rs").unwrap();
+        test_db
+            .db()
+            .insert(file.path(), vec!["rust".into()])
+            .unwrap();
+        let db = ReadOnlyDatabase::from(test_db.db().clone());
+        let timed = ListEntry::new(
+            &db,
+            Pair::new(file.path().to_path_buf(), vec![]),
+            Some(ListSort::Tagged),
+        );
+        assert!(timed.tagged.is_some());
+        let untimed = ListEntry::new(
+            &db,
+            Pair::new(PathBuf::from("/untimed"), vec![]),
+            Some(ListSort::Tagged),
+        );
+        assert!(untimed.tagged.is_none());
+
+        let tagged = |file: &str, secs: Option<i64>| ListEntry {
+            tagged: secs,
+            ..entry(file, None, None)
+        };
+        let mut entries = vec![
+            tagged("/old", Some(100)),
+            untimed,
+            tagged("/new", Some(300)),
+        ];
+        sort_entries(&mut entries, ListSort::Tagged, false);
+        assert_eq!(files(&entries), vec!["/new", "/old", "/untimed"]);
+        sort_entries(&mut entries, ListSort::Tagged, true);
+        assert_eq!(files(&entries), vec!["/old", "/new", "/untimed"]);
+
+        let json = tagged("/a", Some(1_710_498_600))
+            .to_json(Some(ListSort::Tagged), config::PathFormat::Absolute);
+        assert_eq!(json["tagged"], "2024-03-15T10:30:00Z");
+        let json = entries[2].to_json(Some(ListSort::Tagged), config::PathFormat::Absolute);
+        assert!(json["tagged"].is_null());
+    }
+
+    #[test]
+    fn test_tag_sections_group_files_under_each_tag() {
+        use crate::testing::{TempFile, TestDb};
+
+        let test_db = TestDb::new("test_list_tag_sections");
+        let db = test_db.db();
+        let a = TempFile::create("list_sections_a.rs").unwrap();
+        let b = TempFile::create("list_sections_b.py").unwrap();
+        db.insert(a.path(), vec!["rust".into(), "code".into()])
+            .unwrap();
+        db.insert(b.path(), vec!["python".into(), "code".into()])
+            .unwrap();
+        let db = ReadOnlyDatabase::from(db.clone());
+
+        let sections = tag_sections(&db, &[]).unwrap();
+        let tags: Vec<&str> = sections.iter().map(|(tag, _)| tag.as_str()).collect();
+        assert_eq!(tags, vec!["code", "python", "rust"]);
+        assert_eq!(sections[0].1.len(), 2);
+
+        let only = tag_sections(&db, &["rust".to_string()]).unwrap();
+        assert_eq!(
+            only,
+            vec![("rust".to_string(), vec![a.path().to_path_buf()])]
+        );
+
+        let once = first_tag_sections(db.list_all().unwrap());
+        assert_eq!(once.len(), 1);
+        assert_eq!(once[0].0, "code");
+        assert_eq!(once[0].1.len(), 2);
+    }
+
+    #[test]
+    fn test_entry_json_includes_sort_key() {
+        let modified = entry("/a", Some(1_710_498_600), Some(42));
+
+        let json = modified.to_json(Some(ListSort::Modified), config::PathFormat::Absolute);
+        assert_eq!(json["file"], "/a");
+        assert_eq!(json["modified"], "2024-03-15T10:30:00Z");
+
+        let json = modified.to_json(Some(ListSort::Size), config::PathFormat::Absolute);
+        assert_eq!(json["size"], 42);
+
+        let missing = entry("/gone", None, None);
+        let json = missing.to_json(Some(ListSort::Modified), config::PathFormat::Absolute);
+        assert!(json["modified"].is_null());
+    }
+}
@@ -7,10 +7,22 @@ pub mod alias;
 pub mod browse;
 pub mod bulk;
 pub mod cleanup;
+pub mod completions;
+#[cfg(unix)]
+pub mod daemon;
+pub mod dedup;
+pub mod export;
 pub mod filter;
+pub mod get;
+pub mod has;
+pub mod import;
 pub mod list;
 pub mod note;
+pub mod preview;
+pub mod replay;
 pub mod search;
+#[cfg(feature = "server")]
+pub mod serve;
 pub mod tag;
 pub mod tags;
 
@@ -18,8 +30,18 @@ pub mod tags;
 pub use alias::execute_alias_command as alias;
 pub use browse::execute as browse;
 pub use cleanup::execute as cleanup;
+#[cfg(unix)]
+pub use daemon::execute as daemon;
+pub use dedup::execute as dedup;
+pub use export::execute as export;
 pub use filter::execute as filter;
+pub use get::execute as get;
+pub use has::execute as has;
+pub use import::execute as import;
 pub use list::execute as list;
+pub use replay::execute as replay;
 pub use search::execute as search;
+#[cfg(feature = "server")]
+pub use serve::execute as serve;
 pub use tag::execute as tag;
 pub use tags::execute as tags;
@@ -141,9 +141,9 @@ impl NoteSubcommand {
     ) -> Result<(), NoteError> {
         match self {
             Self::Edit(args) => execute_edit(args, db, config),
-            Self::Add(args) => execute_add(args, db, path_format),
-            Self::Show(args) => execute_show(args, db, path_format),
-            Self::Delete(args) => execute_delete(args, db, path_format),
+            Self::Add(args) => execute_add(args, db, config.symlinks, path_format),
+            Self::Show(args) => execute_show(args, db, config.symlinks, path_format),
+            Self::Delete(args) => execute_delete(args, db, config.symlinks, path_format),
             Self::List(args) => execute_list(args, db, path_format),
             Self::Search(args) => execute_search(args, db, path_format),
         }
@@ -158,7 +158,7 @@ fn execute_edit(args: &EditArgs, db: &Database, config: &TagrConfig) -> Result<(
         .unwrap_or_else(|| config.notes.get_editor());
 
     for file in &args.files {
-        let canonical_path = file.canonicalize().map_err(|e| {
+        let canonical_path = config.symlinks.normalize(file).map_err(|e| {
             NoteError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 format!("Cannot access path '{}': {}", file.display(), e),
@@ -223,9 +223,10 @@ fn execute_edit(args: &EditArgs, db: &Database, config: &TagrConfig) -> Result<(
 fn execute_add(
     args: &AddArgs,
     db: &Database,
+    symlinks: config::SymlinkMode,
     path_format: config::PathFormat,
 ) -> Result<(), NoteError> {
-    let canonical_path = args.file.canonicalize().map_err(|e| {
+    let canonical_path = symlinks.normalize(&args.file).map_err(|e| {
         NoteError::Io(std::io::Error::new(
             std::io::ErrorKind::NotFound,
             format!("Cannot access path '{}': {}", args.file.display(), e),
@@ -262,10 +263,11 @@ fn execute_add(
 fn execute_show(
     args: &ShowArgs,
     db: &Database,
+    symlinks: config::SymlinkMode,
     path_format: config::PathFormat,
 ) -> Result<(), NoteError> {
     for file in &args.files {
-        let canonical_path = file.canonicalize().map_err(|e| {
+        let canonical_path = symlinks.normalize(file).map_err(|e| {
             NoteError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 format!("Cannot access path '{}': {}", file.display(), e),
@@ -319,13 +321,14 @@ fn execute_show(
 fn execute_delete(
     args: &DeleteArgs,
     db: &Database,
+    symlinks: config::SymlinkMode,
     path_format: config::PathFormat,
 ) -> Result<(), NoteError> {
     let mut files_to_delete = Vec::new();
 
     // Check which files have notes
     for file in &args.files {
-        let canonical_path = file.canonicalize().map_err(|e| {
+        let canonical_path = symlinks.normalize(file).map_err(|e| {
             NoteError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 format!("Cannot access path '{}': {}", file.display(), e),
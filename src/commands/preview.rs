@@ -0,0 +1,33 @@
+//! Preview command - print a file preview outside the browser
+
+use crate::{
+    TagrError, config,
+    preview::{PreviewContent, PreviewGenerator},
+};
+use std::io::IsTerminal;
+use std::path::Path;
+
+type Result<T> = std::result::Result<T, TagrError>;
+
+/// Execute the preview command
+///
+/// Output is plain text when `plain` is set or stdout is not a terminal, so
+/// piping to a file never captures ANSI escapes.
+///
+/// # Errors
+/// Returns an error if the file does not exist or the preview cannot be generated
+pub fn execute(file: &Path, preview_config: &config::PreviewConfig, plain: bool) -> Result<()> {
+    let generator = PreviewGenerator::new(preview_config.into());
+    let content = generator.generate(file)?;
+
+    if let PreviewContent::Error(msg) = content {
+        return Err(TagrError::InvalidInput(msg));
+    }
+
+    if plain || !std::io::stdout().is_terminal() {
+        println!("{}", content.to_plain_string());
+    } else {
+        println!("{content}");
+    }
+    Ok(())
+}
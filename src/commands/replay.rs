@@ -0,0 +1,265 @@
+//! Replay command - re-apply recorded tagging operations from a file
+//!
+//! A journal is a TOML file with one `[[ops]]` table per operation, applied
+//! in order:
+//!
+//! ```toml
+//! [[ops]]
+//! op = "tag_added"
+//! file = "/projects/app/main.rs"
+//! tags = ["rust", "cli"]
+//!
+//! [[ops]]
+//! op = "renamed"
+//! from = "/projects/app/main.rs"
+//! to = "/projects/app/app.rs"
+//!
+//! [[ops]]
+//! op = "merged"
+//! sources = ["js"]
+//! target = "javascript"
+//! ```
+//!
+//! An operation whose effect is already present is skipped, e.g. adding a
+//! tag the file already has or renaming a file that was already moved.
+
+use crate::{TagrError, commands::bulk::BulkOpSummary, db::Database};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, TagrError>;
+
+/// A recorded tagging operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JournalOp {
+    /// Tags were added to a file
+    TagAdded { file: PathBuf, tags: Vec<String> },
+    /// Tags were removed from a file
+    TagRemoved { file: PathBuf, tags: Vec<String> },
+    /// A file's tags moved to a new path
+    Renamed { from: PathBuf, to: PathBuf },
+    /// Source tags were merged into a target tag on every file
+    Merged {
+        sources: Vec<String>,
+        target: String,
+    },
+}
+
+impl fmt::Display for JournalOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TagAdded { file, tags } => {
+                write!(f, "tag_added {} [{}]", file.display(), tags.join(", "))
+            }
+            Self::TagRemoved { file, tags } => {
+                write!(f, "tag_removed {} [{}]", file.display(), tags.join(", "))
+            }
+            Self::Renamed { from, to } => {
+                write!(f, "renamed {} → {}", from.display(), to.display())
+            }
+            Self::Merged { sources, target } => {
+                write!(f, "merged [{}] → {target}", sources.join(", "))
+            }
+        }
+    }
+}
+
+/// Whether applying an operation changed the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The operation was applied
+    Applied,
+    /// The database already reflected the operation
+    Unchanged,
+}
+
+impl JournalOp {
+    /// Apply the operation to `db`
+    ///
+    /// # Errors
+    /// Returns an error if the operation cannot be applied, e.g. a file no
+    /// longer exists or merge sources include the target.
+    pub fn apply(&self, db: &Database) -> Result<Outcome> {
+        match self {
+            Self::TagAdded { file, tags } => {
+                let existing = db.get_tags(file)?.unwrap_or_default();
+                let missing: Vec<String> = tags
+                    .iter()
+                    .filter(|tag| !existing.contains(tag))
+                    .cloned()
+                    .collect();
+                if missing.is_empty() {
+                    return Ok(Outcome::Unchanged);
+                }
+                db.add_tags(file, missing)?;
+            }
+            Self::TagRemoved { file, tags } => {
+                let existing = db.get_tags(file)?.unwrap_or_default();
+                if !tags.iter().any(|tag| existing.contains(tag)) {
+                    return Ok(Outcome::Unchanged);
+                }
+                db.remove_tags(file, tags)?;
+            }
+            Self::Renamed { from, to } => {
+                if !db.contains(from)? && db.contains(to)? {
+                    return Ok(Outcome::Unchanged);
+                }
+                db.rename_file(from, to)?;
+            }
+            Self::Merged { sources, target } => {
+                if sources
+                    .iter()
+                    .map(|tag| db.count_files_per_tag_fast(tag))
+                    .sum::<std::result::Result<usize, _>>()?
+                    == 0
+                {
+                    return Ok(Outcome::Unchanged);
+                }
+                crate::commands::bulk::merge_tags(db, sources, target, false, true, true)?;
+            }
+        }
+        Ok(Outcome::Applied)
+    }
+}
+
+/// A file of operations to replay in order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Journal {
+    /// Operations, oldest first
+    #[serde(default)]
+    pub ops: Vec<JournalOp>,
+}
+
+impl Journal {
+    /// Read a journal from a TOML file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a valid journal.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| {
+            TagrError::InvalidInput(format!("Invalid journal {}: {e}", path.display()))
+        })
+    }
+}
+
+/// Execute the replay command
+///
+/// Operations run in order. Without `continue_on_error` the first failure
+/// stops the replay; operations before it stay applied.
+///
+/// # Errors
+/// Returns an error if the journal cannot be loaded, or if an operation fails
+/// and `continue_on_error` is not set.
+pub fn execute(
+    db: &Database,
+    journal: &Path,
+    continue_on_error: bool,
+    dry_run: bool,
+    quiet: bool,
+) -> Result<()> {
+    let journal = Journal::load(journal)?;
+    if journal.ops.is_empty() {
+        if !quiet {
+            println!("Journal has no operations.");
+        }
+        return Ok(());
+    }
+
+    if dry_run {
+        println!("{}", "=== Dry Run Mode ===".yellow().bold());
+        println!("Would replay {} operation(s):", journal.ops.len());
+        for (i, op) in journal.ops.iter().enumerate() {
+            println!("  {}. {op}", i + 1);
+        }
+        println!("\n{}", "Run without --dry-run to apply changes.".yellow());
+        return Ok(());
+    }
+
+    let mut summary = BulkOpSummary::new();
+    for (i, op) in journal.ops.iter().enumerate() {
+        let number = i + 1;
+        match op.apply(db) {
+            Ok(Outcome::Applied) => {
+                summary.add_success();
+                if !quiet {
+                    println!("✓ {number}. {op}");
+                }
+            }
+            Ok(Outcome::Unchanged) => {
+                summary.add_skip();
+                if !quiet {
+                    println!("⊘ {number}. {op} (already applied)");
+                }
+            }
+            Err(e) if continue_on_error => {
+                summary.add_error(format!("{number}. {op}: {e}"));
+                if !quiet {
+                    eprintln!("✗ {number}. {op}: {e}");
+                }
+            }
+            Err(e) => {
+                return Err(TagrError::InvalidInput(format!(
+                    "Operation {number} ({op}) failed: {e}. Use --continue-on-error to skip failing operations."
+                )));
+            }
+        }
+    }
+
+    if !quiet {
+        summary.print("Replay");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TempFile, TestDb};
+
+    #[test]
+    fn test_journal_parses_and_replays_idempotently() {
+        let test_db = TestDb::new("test_replay_journal");
+        let db = test_db.db();
+        let old = TempFile::create("replay_old.rs").unwrap();
+        let new = TempFile::create("replay_new.rs").unwrap();
+        let journal: Journal = toml::from_str(&format!(
+            r#"
+            [[ops]]
+            op = "tag_added"
+            file = "{old}"
+            tags = ["rust", "js", "wip"]
+
+            [[ops]]
+            op = "tag_removed"
+            file = "{old}"
+            tags = ["wip"]
+
+            [[ops]]
+            op = "merged"
+            sources = ["js"]
+            target = "javascript"
+
+            [[ops]]
+            op = "renamed"
+            from = "{old}"
+            to = "{new}"
+            "#,
+            old = old.path().display(),
+            new = new.path().display(),
+        ))
+        .unwrap();
+
+        for op in &journal.ops {
+            assert_eq!(op.apply(db).unwrap(), Outcome::Applied, "{op}");
+            assert_eq!(op.apply(db).unwrap(), Outcome::Unchanged, "{op}");
+        }
+        let mut tags = db.get_tags(new.path()).unwrap().unwrap();
+        tags.sort();
+        assert_eq!(tags, vec!["javascript", "rust"]);
+        assert!(!db.contains(old.path()).unwrap());
+    }
+}
@@ -1,15 +1,21 @@
 //! Search command - find files by tags and patterns
 
 use crate::{
-    TagrError,
-    cli::{SearchMode, SearchParams},
+    Pair, TagrError,
+    cli::{ListFormat, ListSort, SearchMode, SearchParams},
     config,
-    db::{Database, query},
-    filters::{FilterCriteria, FilterManager},
+    db::{
+        ReadOnlyDatabase,
+        query::{self, PlanOp, QueryPlan},
+    },
+    filters::{FilterCriteria, FilterExpression, FilterManager, types::FILE_PATTERN_PREFIX},
     output,
     patterns::{PatternBuilder, PatternContext},
+    search::{cache::SearchCache, filter::PathFilterExt, query_lang::Expr},
+    ui::{OutputWriter, StdoutWriter},
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 type Result<T> = std::result::Result<T, TagrError>;
 
@@ -23,7 +29,36 @@ pub struct ExplicitFlags {
 #[derive(Clone, Copy)]
 pub struct OutputConfig {
     pub format: config::PathFormat,
+    /// Most components `last-N` paths grow to when telling files apart
+    pub path_context_max: usize,
     pub quiet: bool,
+    /// Print results as an aligned table (when stdout is a terminal)
+    pub table: bool,
+    /// Keep only the most or least tagged results
+    pub rank: Option<TagRank>,
+    /// Output format
+    pub output: ListFormat,
+    /// Group results under each searched tag
+    pub group_by_tag: bool,
+    /// Result order (path order when `None`); ignored when ranking
+    pub sort: Option<ListSort>,
+    /// Slice of the sorted results to show
+    pub page: output::Page,
+    /// Print the executed query plan to stderr
+    pub explain: bool,
+    /// Print only the number of results instead of listing them
+    pub count: bool,
+    /// Reuse matches cached within this many seconds (0 disables the cache)
+    pub cache_ttl: u64,
+}
+
+/// Selects the N results with the most or fewest tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagRank {
+    /// The N files with the most tags
+    Top(usize),
+    /// The N files with the fewest tags
+    Bottom(usize),
 }
 
 #[derive(Clone, Copy)]
@@ -35,6 +70,9 @@ pub struct FilterConfig<'a> {
 /// Execute the search command
 ///
 /// # Arguments
+/// * `expr` - Boolean tag expression from `--expr`; flat expressions are merged
+///   into `params`, nested ones are evaluated against each file's tags
+/// * `exclude_dirs` - Glob patterns for directories whose files are dropped from the results
 /// * `filter_config` - Configuration for applying/saving filters
 /// * `explicit_flags` - Flags indicating if user explicitly provided tag/file/virtual modes
 /// * `output_config` - Configuration for output formatting and verbosity
@@ -42,8 +80,10 @@ pub struct FilterConfig<'a> {
 /// # Errors
 /// Returns an error if database operations fail or search parameters are invalid
 pub fn execute(
-    db: &Database,
+    db: &ReadOnlyDatabase,
     mut params: SearchParams,
+    expr: Option<&Expr>,
+    exclude_dirs: &[String],
     filter_config: FilterConfig,
     explicit_flags: ExplicitFlags,
     output_config: OutputConfig,
@@ -89,66 +129,243 @@ pub fn execute(
         }
     }
 
+    // Expressions with a flag equivalent take the regular path (aliases, hierarchy rules);
+    // grouped ones and those with `file:` terms are evaluated per file, combined with
+    // any expression from the filter
+    if let Some(expr) = expr {
+        let flat = expr.to_search_params().filter(|flat| {
+            !flat
+                .tags
+                .iter()
+                .chain(&flat.exclude_tags)
+                .any(|tag| tag.starts_with(FILE_PATTERN_PREFIX))
+        });
+        if let Some(flat) = flat {
+            params.tags.extend(flat.tags);
+            params.tag_mode = flat.tag_mode;
+            params.exclude_tags.extend(flat.exclude_tags);
+        } else {
+            let expr = FilterExpression::from(expr.clone());
+            params.expression = Some(match params.expression.take() {
+                Some(existing) => FilterExpression::And(vec![existing, expr]),
+                None => expr,
+            });
+        }
+    }
+
     if params.query.is_some() && (!params.tags.is_empty() || !params.file_patterns.is_empty()) {
         return Err(TagrError::InvalidInput(
             "Cannot use general query with -t or -f flags. Use either 'tagr search <query>' or 'tagr search -t <tag> -f <pattern>'.".into()
         ));
     }
 
-    if params.query.is_none()
-        && params.tags.is_empty()
-        && params.file_patterns.is_empty()
-        && params.virtual_tags.is_empty()
-    {
-        return Err(TagrError::InvalidInput("No search criteria provided. Use -t for tags, -f for file patterns, or -v for virtual tags.".into()));
+    if !params.has_criteria() {
+        return Err(TagrError::InvalidInput("No search criteria provided. Use -t for tags, -f for file patterns, --files for globs, or -v for virtual tags.".into()));
     }
 
-    // Strict mode: require explicit --glob-files or --regex-file for non-bulk search
-    if !params.file_patterns.is_empty() {
-        let has_glob_like = params
-            .file_patterns
-            .iter()
-            .any(|p| p.contains('*') || p.contains('?') || p.contains('['));
-        if has_glob_like && !params.glob_files && !params.regex_file {
+    if output_config.group_by_tag {
+        if params.tags.is_empty() {
             return Err(TagrError::InvalidInput(
-                "Glob-like file pattern detected without --glob-files. Use --glob-files for globs or --regex-file for regex patterns.".into(),
+                "--group-by-tag requires tags to group by. Use -t <tag>.".into(),
             ));
         }
+        if params.tag_mode == SearchMode::All && params.tags.len() > 1 {
+            if explicit_flags.tag_mode {
+                return Err(TagrError::InvalidInput(
+                    "--group-by-tag cannot be used with --all-tags: every result would appear under every tag.".into(),
+                ));
+            }
+            params.tag_mode = SearchMode::Any;
+        }
     }
 
-    // Validate tag/file separation using PatternBuilder in SearchFiles context.
-    // This does not alter params; it ensures glob-like tags are rejected and
-    // patterns are consistent with flags.
-    let mut builder = PatternBuilder::new(PatternContext::SearchFiles)
-        .regex_tags(params.regex_tag)
-        .regex_files(params.regex_file)
-        .glob_files_flag(params.glob_files);
-    for t in &params.tags {
-        builder.add_tag_token(t);
+    check_glob_flags(&params)?;
+    validate_patterns(&params)?;
+
+    let mut plan = output_config.explain.then(QueryPlan::default);
+    let mut files = find_matches_cached(
+        db,
+        &params,
+        exclude_dirs,
+        plan.as_mut(),
+        output_config.cache_ttl,
+    )?;
+    if let Some(rank) = output_config.rank {
+        files = rank_by_tag_count(db, files, rank);
+    } else {
+        // Sort before paging so --offset/--limit select the same files every run
+        sort_files(db, &mut files, output_config.sort);
     }
-    for f in &params.file_patterns {
-        builder.add_file_token(f);
+    let total = files.len();
+    if output_config.count {
+        print_count(db, &params, &files, output_config)?;
+        return finish(plan.as_ref(), filter_config, params, output_config.quiet);
     }
-    let _ = builder.build(params.tag_mode, params.file_mode)?;
-
-    let files = query::apply_search_params(db, &params)?;
+    output_config.page.apply(&mut files);
 
-    if let Some(query) = &params.query {
-        print_results(db, &files, query, output_config.format, output_config.quiet);
-    } else if files.is_empty() {
+    if output_config.group_by_tag {
+        let groups = query::group_by_tag(db.inner(), &params, &files)?;
+        print_groups(&groups, output_config)?;
+    } else if output_config.output == ListFormat::Markdown {
+        let pairs: Vec<Pair> = files
+            .iter()
+            .map(|file| {
+                let tags = db.get_tags(file).ok().flatten().unwrap_or_default();
+                Pair::new(file.clone(), tags)
+            })
+            .collect();
+        print!("{}", output::format_pairs_as_markdown(&pairs));
+    } else if output_config.output == ListFormat::Tsv {
+        let pairs: Vec<Pair> = files
+            .iter()
+            .map(|file| {
+                let tags = db.get_tags(file).ok().flatten().unwrap_or_default();
+                Pair::new(file.clone(), tags)
+            })
+            .collect();
+        print!(
+            "{}",
+            output::pairs_table(&pairs, output_config.format).render_tsv()
+        );
+    } else if output_config.output == ListFormat::Jsonl {
+        write_jsonl(db, &files, output_config, &mut output::JsonLines::stdout())?;
+    } else if output_config.output == ListFormat::Json {
+        print_json(db, &files, output_config)?;
+    } else if let Some(query) = &params.query {
+        print_results(db, &files, total, query, output_config);
+    } else if total == 0 {
         if !output_config.quiet {
-            let criteria = build_criteria_description(&params);
+            let criteria = params.expression.as_ref().map_or_else(
+                || build_criteria_description(&params),
+                |expr| format!("expression '{expr}'"),
+            );
             println!("No files found matching {criteria}");
         }
     } else {
         if !output_config.quiet {
-            let description = build_search_description(&params);
-            println!("Found {} file(s) matching {}:", files.len(), description);
+            let description = params.expression.as_ref().map_or_else(
+                || build_search_description(&params),
+                |expr| format!("expression '{expr}'"),
+            );
+            println!("Found {total} file(s) matching {description}:");
         }
 
-        for file in files {
-            print_file_with_tags(db, &file, output_config.format, output_config.quiet);
+        print_files(db, &files, output_config);
+    }
+    if output_config.output == ListFormat::Text
+        && !output_config.quiet
+        && total > 0
+        && let Some(footer) = output_config.page.footer(files.len(), total)
+    {
+        println!("{footer}");
+    }
+
+    finish(plan.as_ref(), filter_config, params, output_config.quiet)
+}
+
+/// Files matching validated `params`, outside `exclude_dirs`, in database order
+fn find_matches(
+    db: &ReadOnlyDatabase,
+    params: &SearchParams,
+    exclude_dirs: &[String],
+    mut plan: Option<&mut QueryPlan>,
+) -> Result<Vec<PathBuf>> {
+    let match_all_files = params.file_mode == SearchMode::All;
+    let started = Instant::now();
+    let matched = if params.expression.is_none() && is_file_only(params) {
+        // Nothing tag-related to resolve: skip the tag index entirely
+        let matched = db.find_by_file_glob(&params.file_only_patterns, match_all_files)?;
+        if let Some(plan) = plan.as_deref_mut() {
+            let detail = format!("files matching {}", params.file_only_patterns.join(" "));
+            plan.record(PlanOp::Scan, detail, matched.len(), started);
         }
+        matched
+    } else {
+        let matched = query::apply_search_params_traced(db.inner(), params, plan.as_deref_mut())?;
+        if params.file_only_patterns.is_empty() {
+            matched
+        } else {
+            let started = Instant::now();
+            let matched = matched.into_iter().filter_patterns(
+                &params.file_only_patterns,
+                false,
+                match_all_files,
+            )?;
+            if let Some(plan) = plan.as_deref_mut() {
+                let detail = format!("glob {}", params.file_only_patterns.join(" "));
+                plan.record(PlanOp::FileFilter, detail, matched.len(), started);
+            }
+            matched
+        }
+    };
+    let started = Instant::now();
+    let files = matched.into_iter().exclude_dirs(exclude_dirs)?;
+    if let Some(plan) = plan
+        && !exclude_dirs.is_empty()
+    {
+        let detail = format!("directories {}", exclude_dirs.join(" "));
+        plan.record(PlanOp::ExcludeFiles, detail, files.len(), started);
+    }
+    Ok(files)
+}
+
+/// [`find_matches`], answered from the search cache when `cache_ttl` is set
+///
+/// `--explain` reports how the search ran, so it never reads the cache.
+fn find_matches_cached(
+    db: &ReadOnlyDatabase,
+    params: &SearchParams,
+    exclude_dirs: &[String],
+    plan: Option<&mut QueryPlan>,
+    cache_ttl: u64,
+) -> Result<Vec<PathBuf>> {
+    let cache = SearchCache::default_path()
+        .filter(|_| cache_ttl > 0 && plan.is_none())
+        .map(|path| SearchCache::new(path, cache_ttl));
+    let Some(cache) = cache else {
+        return find_matches(db, params, exclude_dirs, plan);
+    };
+
+    let key = SearchCache::key(params, exclude_dirs, db.path(), db.last_mutation()?);
+    if let Some(files) = cache.get(&key) {
+        return Ok(files);
+    }
+    let files = find_matches(db, params, exclude_dirs, None)?;
+    if let Err(e) = cache.put(&key, &files) {
+        eprintln!("Warning: could not write search cache: {e}");
+    }
+    Ok(files)
+}
+
+/// Whether `tagr search --filter <name>` would list `file`
+///
+/// Runs the filter's criteria through the same query as [`execute`], so the
+/// answer cannot drift from what a search returns.
+///
+/// # Errors
+/// Returns an error if the filter's patterns are invalid or the query fails.
+pub fn filter_matches(
+    db: &ReadOnlyDatabase,
+    criteria: &FilterCriteria,
+    exclude_dirs: &[String],
+    file: &Path,
+) -> Result<bool> {
+    let params = SearchParams::from(criteria);
+    validate_patterns(&params)?;
+    Ok(find_matches(db, &params, exclude_dirs, None)?
+        .iter()
+        .any(|matched| matched == file))
+}
+
+/// Print the query plan and save the search as a filter, if requested
+fn finish(
+    plan: Option<&QueryPlan>,
+    filter_config: FilterConfig,
+    params: SearchParams,
+    quiet: bool,
+) -> Result<()> {
+    if let Some(plan) = plan {
+        eprintln!("\nQuery plan:\n{plan}");
     }
 
     if let Some((name, desc)) = filter_config.save {
@@ -159,7 +376,7 @@ pub fn execute(
 
         manager.create(name, description.to_string(), criteria)?;
 
-        if !output_config.quiet {
+        if !quiet {
             println!("\nSaved filter '{name}'");
         }
     }
@@ -167,43 +384,386 @@ pub fn execute(
     Ok(())
 }
 
-fn print_results(
-    db: &Database,
+/// Print the number of matching files, or of files per tag with `--group-by-tag`
+///
+/// Text output is "N file(s) found", or the bare number with `--quiet`;
+/// JSON output is `{"count": N}`.
+fn print_count(
+    db: &ReadOnlyDatabase,
+    params: &SearchParams,
     files: &[PathBuf],
-    query: &str,
+    output_config: OutputConfig,
+) -> Result<()> {
+    let json = matches!(output_config.output, ListFormat::Json | ListFormat::Jsonl);
+    if output_config.group_by_tag {
+        let groups = query::group_by_tag(db.inner(), params, files)?;
+        if json {
+            let entries: Vec<serde_json::Value> = groups
+                .iter()
+                .map(|(tag, pairs)| serde_json::json!({ "tag": tag, "count": pairs.len() }))
+                .collect();
+            output::print_json(&entries, output_config.output)?;
+        } else {
+            for (tag, pairs) in &groups {
+                println!("{tag}: {}", pairs.len());
+            }
+        }
+    } else if json {
+        println!("{}", serde_json::json!({ "count": files.len() }));
+    } else if output_config.quiet {
+        println!("{}", files.len());
+    } else {
+        println!("{} file(s) found", files.len());
+    }
+    Ok(())
+}
+
+/// Strict mode: require explicit --glob-files or --regex-file for non-bulk search
+fn check_glob_flags(params: &SearchParams) -> Result<()> {
+    let has_glob_like = params
+        .file_patterns
+        .iter()
+        .any(|p| p.contains('*') || p.contains('?') || p.contains('['));
+    if has_glob_like && !params.glob_files && !params.regex_file {
+        return Err(TagrError::InvalidInput(
+            "Glob-like file pattern detected without --glob-files. Use --glob-files for globs or --regex-file for regex patterns.".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Validate tag/file separation using `PatternBuilder` in `SearchFiles` context
+///
+/// This does not alter params; it ensures glob-like tags are rejected and
+/// patterns are consistent with flags.
+fn validate_patterns(params: &SearchParams) -> Result<()> {
+    let mut builder = PatternBuilder::new(PatternContext::SearchFiles)
+        .regex_tags(params.regex_tag)
+        .regex_files(params.regex_file)
+        .glob_files_flag(params.glob_files);
+    for t in &params.tags {
+        builder.add_tag_token(t);
+    }
+    for f in &params.file_patterns {
+        builder.add_file_token(f);
+    }
+    let _ = builder.build(params.tag_mode, params.file_mode)?;
+    Ok(())
+}
+
+/// Files matching `params`, outside `exclude_dirs`, in path order
+///
+/// Validates and runs `params` like [`execute`] does, for callers that need
+/// the results rather than printed output.
+///
+/// # Errors
+/// Returns an error if no criteria are given, the patterns are invalid, or the
+/// query fails.
+pub fn find_files(
+    db: &ReadOnlyDatabase,
+    params: &SearchParams,
+    exclude_dirs: &[String],
+) -> Result<Vec<PathBuf>> {
+    if !params.has_criteria() {
+        return Err(TagrError::InvalidInput(
+            "No search criteria provided".into(),
+        ));
+    }
+    check_glob_flags(params)?;
+    validate_patterns(params)?;
+
+    let mut files = find_matches(db, params, exclude_dirs, None)?;
+    sort_files(db, &mut files, None);
+    Ok(files)
+}
+
+/// Files matching a search by `-t` tags only, with their tags, in path order
+///
+/// Answers the subset of searches the query daemon serves; [`execute`] gives
+/// the same files for the same `params` and `exclude_dirs`.
+///
+/// # Errors
+/// Returns an error if no tags are given, a tag is invalid, or the lookup fails.
+pub fn find_tagged(
+    db: &ReadOnlyDatabase,
+    params: &SearchParams,
+    exclude_dirs: &[String],
+) -> Result<Vec<Pair>> {
+    if params.tags.is_empty() {
+        return Err(TagrError::InvalidInput("No tags to search for".into()));
+    }
+    validate_patterns(params)?;
+
+    let matched = query::apply_search_params(db.inner(), params)?;
+    let mut files = matched.into_iter().exclude_dirs(exclude_dirs)?;
+    sort_files(db, &mut files, None);
+    Ok(files
+        .into_iter()
+        .map(|file| {
+            let tags = db.get_tags(&file).ok().flatten().unwrap_or_default();
+            Pair::new(file, tags)
+        })
+        .collect())
+}
+
+/// Print the result of [`find_tagged`] like a text-format search
+pub fn print_tagged(
+    params: &SearchParams,
+    pairs: &[Pair],
     path_format: config::PathFormat,
     quiet: bool,
 ) {
-    if files.is_empty() {
-        if !quiet {
-            println!("No files found matching query '{query}' (searched tags and filenames)");
-        }
-    } else {
+    if pairs.is_empty() {
         if !quiet {
             println!(
-                "Found {} file(s) matching query '{}' (tags or filenames):",
-                files.len(),
-                query
+                "No files found matching {}",
+                build_criteria_description(params)
             );
         }
+        return;
+    }
 
-        for file in files {
-            print_file_with_tags(db, file, path_format, quiet);
+    if !quiet {
+        println!(
+            "Found {} file(s) matching {}:",
+            pairs.len(),
+            build_search_description(params)
+        );
+    }
+    for pair in pairs {
+        println!(
+            "{}",
+            output::file_with_tags(&pair.file, &pair.tags, path_format, quiet)
+        );
+    }
+}
+
+fn print_results(
+    db: &ReadOnlyDatabase,
+    files: &[PathBuf],
+    total: usize,
+    query: &str,
+    output_config: OutputConfig,
+) {
+    if total == 0 {
+        if !output_config.quiet {
+            println!("No files found matching query '{query}' (searched tags and filenames)");
+        }
+    } else {
+        if !output_config.quiet {
+            println!("Found {total} file(s) matching query '{query}' (tags or filenames):");
         }
+
+        print_files(db, files, output_config);
     }
 }
 
-fn print_file_with_tags(
-    db: &Database,
-    file: &PathBuf,
-    path_format: config::PathFormat,
-    quiet: bool,
+/// Sort files by tag count and keep the first N
+///
+/// Ties are broken by path so results are stable.
+/// Sort results by `sort`; ties and files without metadata fall back to path order
+fn sort_files(db: &ReadOnlyDatabase, files: &mut Vec<PathBuf>, sort: Option<ListSort>) {
+    match sort {
+        Some(ListSort::Modified) => sort_by_metadata(files, |m| m.modified().ok()),
+        Some(ListSort::Size) => sort_by_metadata(files, |m| Some(m.len())),
+        Some(ListSort::Tagged) => sort_by_key(files, |file| super::list::last_tagged(db, file)),
+        Some(ListSort::Name) | None => files.sort(),
+    }
+}
+
+/// Sort files by a metadata key, largest first, with unreadable files last
+fn sort_by_metadata<K: Ord>(
+    files: &mut Vec<PathBuf>,
+    key: impl Fn(&std::fs::Metadata) -> Option<K>,
 ) {
+    sort_by_key(files, |file| {
+        std::fs::metadata(file).ok().and_then(|m| key(&m))
+    });
+}
+
+/// Sort files by `read_key`, largest first, with files without a key last
+fn sort_by_key<K: Ord>(files: &mut Vec<PathBuf>, read_key: impl Fn(&Path) -> Option<K>) {
+    let mut keyed: Vec<(Option<K>, PathBuf)> = files
+        .drain(..)
+        .map(|file| (read_key(&file), file))
+        .collect();
+    keyed.sort_by(|a, b| {
+        super::list::largest_first(a.0.as_ref(), b.0.as_ref(), false).then_with(|| a.1.cmp(&b.1))
+    });
+    files.extend(keyed.into_iter().map(|(_, file)| file));
+}
+
+fn rank_by_tag_count(db: &ReadOnlyDatabase, files: Vec<PathBuf>, rank: TagRank) -> Vec<PathBuf> {
+    let mut counted: Vec<(PathBuf, usize)> = files
+        .into_iter()
+        .map(|file| {
+            let count = db
+                .get_tags(&file)
+                .ok()
+                .flatten()
+                .map_or(0, |tags| tags.len());
+            (file, count)
+        })
+        .collect();
+
+    let n = match rank {
+        TagRank::Top(n) => {
+            counted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            n
+        }
+        TagRank::Bottom(n) => {
+            counted.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+            n
+        }
+    };
+    counted.truncate(n);
+    counted.into_iter().map(|(file, _)| file).collect()
+}
+
+/// JSON object for a matched file, with its tag count when ranking
+fn file_json(db: &ReadOnlyDatabase, file: &Path, output_config: OutputConfig) -> serde_json::Value {
+    let tags = db.get_tags(file).ok().flatten().unwrap_or_default();
+    let mut json = serde_json::json!({
+        "file": output::format_path(file, output_config.format),
+        "tags": tags,
+    });
+    if output_config.rank.is_some() {
+        json["tag_count"] = tags.len().into();
+    }
+    json
+}
+
+/// Write matched files as JSON lines, each flushed once its tags are read
+fn write_jsonl<W: std::io::Write>(
+    db: &ReadOnlyDatabase,
+    files: &[PathBuf],
+    output_config: OutputConfig,
+    lines: &mut output::JsonLines<W>,
+) -> Result<()> {
+    for file in files {
+        if let Err(e) = lines.write(&file_json(db, file, output_config)) {
+            return Ok(output::end_on_broken_pipe(e)?);
+        }
+    }
+    Ok(())
+}
+
+/// Print matched files as a JSON array, with tag counts when ranking
+fn print_json(db: &ReadOnlyDatabase, files: &[PathBuf], output_config: OutputConfig) -> Result<()> {
+    let entries: Vec<serde_json::Value> = files
+        .iter()
+        .map(|file| file_json(db, file, output_config))
+        .collect();
+    let json = serde_json::to_string_pretty(&entries)
+        .map_err(|e| TagrError::InvalidInput(format!("Failed to serialize JSON: {e}")))?;
+    println!("{json}");
+    Ok(())
+}
+
+/// Print files under a heading per tag, noting files listed under several tags
+fn print_groups(groups: &[(String, Vec<Pair>)], output_config: OutputConfig) -> Result<()> {
+    if matches!(output_config.output, ListFormat::Json | ListFormat::Jsonl) {
+        let entries: Vec<serde_json::Value> = groups
+            .iter()
+            .map(|(tag, pairs)| {
+                let files: Vec<serde_json::Value> = pairs
+                    .iter()
+                    .map(|pair| {
+                        serde_json::json!({
+                            "file": output::format_path(&pair.file, output_config.format),
+                            "tags": pair.tags,
+                        })
+                    })
+                    .collect();
+                serde_json::json!({ "tag": tag, "files": files })
+            })
+            .collect();
+        output::print_json(&entries, output_config.output)?;
+        return Ok(());
+    }
+    if output_config.output == ListFormat::Markdown {
+        print!("{}", output::format_groups_as_markdown(groups));
+        return Ok(());
+    }
+    if output_config.output == ListFormat::Tsv {
+        let mut table = output::TableFormatter::new(&["GROUP", "PATH", "TAGS"]);
+        for (tag, pairs) in groups {
+            for pair in pairs {
+                table.add_row(&[
+                    tag,
+                    &output::format_path(&pair.file, output_config.format),
+                    &pair.tags.join(", "),
+                ]);
+            }
+        }
+        print!("{}", table.render_tsv());
+        return Ok(());
+    }
+
+    for (i, (tag, pairs)) in groups.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        if output_config.quiet {
+            println!("{tag}:");
+        } else {
+            println!("{tag} ({} file(s)):", pairs.len());
+        }
+        for pair in pairs {
+            let line = output::file_with_tags(
+                &pair.file,
+                &pair.tags,
+                output_config.format,
+                output_config.quiet,
+            );
+            let others: Vec<&str> = groups
+                .iter()
+                .filter(|(other, other_pairs)| {
+                    other != tag && other_pairs.iter().any(|p| p.file == pair.file)
+                })
+                .map(|(other, _)| other.as_str())
+                .collect();
+            if others.is_empty() || output_config.quiet {
+                println!("{line}");
+            } else {
+                println!("{line} (also under: {})", others.join(", "));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print matched files, as a table when requested and stdout is a terminal
+fn print_files(db: &ReadOnlyDatabase, files: &[PathBuf], output_config: OutputConfig) {
+    if output_config.table
+        && !output_config.quiet
+        && let Some(width) = output::terminal_width()
+    {
+        let pairs: Vec<Pair> = files
+            .iter()
+            .map(|file| {
+                let tags = db.get_tags(file).ok().flatten().unwrap_or_default();
+                Pair::new(file.clone(), tags)
+            })
+            .collect();
+        let labels =
+            output::format_paths(files, output_config.format, output_config.path_context_max);
+        StdoutWriter::new().write(output::format_labeled_table(&pairs, &labels, width).trim_end());
+        return;
+    }
+
+    let labels = output::format_paths(files, output_config.format, output_config.path_context_max);
+    for (file, label) in files.iter().zip(&labels) {
+        print_file_with_tags(db, file, label, output_config.quiet);
+    }
+}
+
+fn print_file_with_tags(db: &ReadOnlyDatabase, file: &PathBuf, label: &str, quiet: bool) {
     if let Ok(Some(tags)) = db.get_tags(file) {
-        let formatted = output::file_with_tags(file, &tags, path_format, quiet);
+        let formatted = output::labeled_file_with_tags(label, &tags, quiet);
         println!("{formatted}");
     } else {
-        let formatted = output::format_path(file, path_format);
+        let formatted = label;
         if quiet {
             println!("{formatted}");
         } else {
@@ -212,8 +772,22 @@ fn print_file_with_tags(
     }
 }
 
+/// Whether `--files` globs are the only search criteria
+fn is_file_only(params: &SearchParams) -> bool {
+    !params.file_only_patterns.is_empty()
+        && params.query.is_none()
+        && params.tags.is_empty()
+        && params.file_patterns.is_empty()
+        && params.exclude_tags.is_empty()
+        && params.exclude_file_patterns.is_empty()
+        && params.virtual_tags.is_empty()
+        && params.exclude_virtual_tags.is_empty()
+}
+
 fn build_criteria_description(params: &SearchParams) -> String {
-    if params.tags.is_empty() {
+    if params.tags.is_empty() && params.file_patterns.is_empty() {
+        format!("globs: {}", params.file_only_patterns.join(", "))
+    } else if params.tags.is_empty() {
         format!("file patterns: {}", params.file_patterns.join(", "))
     } else {
         format!("tags: {}", params.tags.join(", "))
@@ -237,6 +811,14 @@ fn build_search_description(params: &SearchParams) -> String {
         format!("ANY pattern [{}]", params.file_patterns.join(", "))
     };
 
+    let glob_desc = if params.file_only_patterns.is_empty() {
+        String::new()
+    } else if params.file_mode == SearchMode::All {
+        format!("ALL globs [{}]", params.file_only_patterns.join(", "))
+    } else {
+        format!("ANY glob [{}]", params.file_only_patterns.join(", "))
+    };
+
     let mut parts = Vec::new();
     if !tag_desc.is_empty() {
         parts.push(tag_desc);
@@ -244,6 +826,23 @@ fn build_search_description(params: &SearchParams) -> String {
     if !file_desc.is_empty() {
         parts.push(file_desc);
     }
+    if !glob_desc.is_empty() {
+        parts.push(glob_desc);
+    }
+    let vtags: Vec<String> = params
+        .virtual_tags
+        .iter()
+        .cloned()
+        .chain(
+            params
+                .exclude_virtual_tags
+                .iter()
+                .map(|vtag| format!("!{vtag}")),
+        )
+        .collect();
+    if !vtags.is_empty() {
+        parts.push(format!("virtual tags [{}]", vtags.join(", ")));
+    }
 
     parts.join(" and ")
 }
@@ -251,7 +850,7 @@ fn build_search_description(params: &SearchParams) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::testing::TestDb;
+    use crate::testing::{TempFile, TestDb};
 
     #[test]
     fn test_execute_errors_on_glob_without_flag() {
@@ -262,18 +861,25 @@ mod tests {
             tags: vec![],
             tag_mode: SearchMode::All,
             file_patterns: vec!["*.rs".to_string()],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
         let err = execute(
-            db,
+            &ReadOnlyDatabase::from(db.clone()),
             params,
+            None,
+            &[],
             FilterConfig {
                 apply: None,
                 save: None,
@@ -285,7 +891,17 @@ mod tests {
             },
             OutputConfig {
                 format: config::PathFormat::Absolute,
+                path_context_max: 8,
                 quiet: true,
+                table: false,
+                rank: None,
+                output: ListFormat::Text,
+                group_by_tag: false,
+                sort: None,
+                page: output::Page::default(),
+                explain: false,
+                count: false,
+                cache_ttl: 0,
             },
         )
         .expect_err("should error");
@@ -306,18 +922,25 @@ mod tests {
             tags: vec![],
             tag_mode: SearchMode::All,
             file_patterns: vec!["*.md".to_string()],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: true,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
         let res = execute(
-            db,
+            &ReadOnlyDatabase::from(db.clone()),
             params,
+            None,
+            &[],
             FilterConfig {
                 apply: None,
                 save: None,
@@ -329,7 +952,17 @@ mod tests {
             },
             OutputConfig {
                 format: config::PathFormat::Absolute,
+                path_context_max: 8,
                 quiet: true,
+                table: false,
+                rank: None,
+                output: ListFormat::Text,
+                group_by_tag: false,
+                sort: None,
+                page: output::Page::default(),
+                explain: false,
+                count: false,
+                cache_ttl: 0,
             },
         );
         assert!(res.is_ok());
@@ -344,18 +977,25 @@ mod tests {
             tags: vec!["feature/*".to_string()],
             tag_mode: SearchMode::All,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
         let err = execute(
-            db,
+            &ReadOnlyDatabase::from(db.clone()),
             params,
+            None,
+            &[],
             FilterConfig {
                 apply: None,
                 save: None,
@@ -367,7 +1007,17 @@ mod tests {
             },
             OutputConfig {
                 format: config::PathFormat::Absolute,
+                path_context_max: 8,
                 quiet: true,
+                table: false,
+                rank: None,
+                output: ListFormat::Text,
+                group_by_tag: false,
+                sort: None,
+                page: output::Page::default(),
+                explain: false,
+                count: false,
+                cache_ttl: 0,
             },
         )
         .expect_err("should error");
@@ -376,4 +1026,75 @@ mod tests {
             _ => panic!("Expected PatternError for glob-like tag token"),
         }
     }
+
+    #[test]
+    fn test_rank_by_tag_count() {
+        let test_db = TestDb::new("search_rank_by_tag_count");
+        let db = test_db.db();
+        let a = TempFile::create("rank_a.txt").unwrap();
+        let b = TempFile::create("rank_b.txt").unwrap();
+        let c = TempFile::create("rank_c.txt").unwrap();
+        db.insert(a.path(), vec!["x".into()]).unwrap();
+        db.insert(b.path(), vec!["x".into(), "y".into(), "z".into()])
+            .unwrap();
+        db.insert(c.path(), vec!["x".into(), "y".into()]).unwrap();
+
+        let db = ReadOnlyDatabase::from(db.clone());
+        let files = vec![
+            a.path().to_path_buf(),
+            b.path().to_path_buf(),
+            c.path().to_path_buf(),
+        ];
+
+        let top = rank_by_tag_count(&db, files.clone(), TagRank::Top(2));
+        assert_eq!(top, vec![b.path().to_path_buf(), c.path().to_path_buf()]);
+
+        let bottom = rank_by_tag_count(&db, files, TagRank::Bottom(1));
+        assert_eq!(bottom, vec![a.path().to_path_buf()]);
+    }
+
+    #[test]
+    fn test_repeated_searches_are_identically_ordered() {
+        let test_db = TestDb::new("search_stable_order");
+        let db = test_db.db();
+        let files: Vec<TempFile> = (0..8)
+            .map(|i| TempFile::create(format!("stable_{i}.txt")).unwrap())
+            .collect();
+        for file in &files {
+            db.insert(file.path(), vec!["x".into(), "y".into()])
+                .unwrap();
+        }
+        let tags = vec!["x".to_string(), "y".to_string()];
+
+        let mut expected: Vec<PathBuf> = files.iter().map(|f| f.path().to_path_buf()).collect();
+        expected.sort();
+        for _ in 0..3 {
+            assert_eq!(db.find_by_all_tags(&tags).unwrap(), expected);
+            assert_eq!(db.find_by_any_tag(&tags).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_sort_files_by_size() {
+        let small = TempFile::create_with_content("sort_small.txt", b"a").unwrap();
+        let large = TempFile::create_with_content("sort_large.txt", b"abcdef").unwrap();
+        let missing = PathBuf::from("/nonexistent/sort_missing.txt");
+
+        let mut files = vec![
+            missing.clone(),
+            small.path().to_path_buf(),
+            large.path().to_path_buf(),
+        ];
+        let test_db = TestDb::new("test_sort_files_by_size");
+        let db = ReadOnlyDatabase::from(test_db.db().clone());
+        sort_files(&db, &mut files, Some(ListSort::Size));
+        assert_eq!(
+            files,
+            vec![
+                large.path().to_path_buf(),
+                small.path().to_path_buf(),
+                missing
+            ]
+        );
+    }
 }
@@ -0,0 +1,501 @@
+//! Serve command - a local HTTP/JSON API over the database
+//!
+//! Endpoints, all returning JSON:
+//!
+//! - `GET /files?tag=rust&tag=cli` - paths of files carrying every given tag,
+//!   or of all files when no `tag` is given
+//! - `GET /search?tag=rust&tag=go&tag_mode=any` - `[{"path": ..., "tags": [...]}]`
+//!   for the files `tagr search` would list; see [`search_criteria`] for the
+//!   parameters
+//! - `GET /tags` - `[{"tag": ..., "count": N}]`, most used first
+//! - `GET /file?path=/abs/file` - `{"path": ..., "tags": [...]}`
+//! - `POST /file` with `{"path": ..., "add": [...], "remove": [...]}` - change
+//!   a file's tags and return them like `GET /file`; only with `--allow-write`
+//!
+//! Paths must be absolute. Failures return `{"error": "..."}` with a 4xx or
+//! 5xx status. Requests are handled one at a time, so writes never
+//! interleave.
+//!
+//! Web pages the user visits can reach a local server too, so every request
+//! must name a local `Host` or one allowed with `--allowed-host` (blocking
+//! DNS rebinding), any `Origin` must name one of those hosts too, and `POST`
+//! bodies must be sent as `application/json`, which a page cannot do
+//! cross-origin without a preflight the server never answers.
+
+use crate::{
+    TagrError,
+    cli::SearchParams,
+    config::TagNormalization,
+    db::{Database, DbError, ReadOnlyDatabase},
+    filters::FilterCriteria,
+};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+type Result<T> = std::result::Result<T, TagrError>;
+
+/// Port `tagr serve` listens on by default
+pub const DEFAULT_PORT: u16 = 7890;
+
+/// Largest accepted request body (1 MiB)
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// A file and its tags, as returned by `GET /file` and `POST /file`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileTags {
+    pub path: PathBuf,
+    pub tags: Vec<String>,
+}
+
+/// Body of `POST /file`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TagChange {
+    pub path: PathBuf,
+    /// Tags to add, normalized like `tagr tag`
+    #[serde(default)]
+    pub add: Vec<String>,
+    /// Tags to remove
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+/// A failed request: HTTP status and message
+#[derive(Debug)]
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<DbError> for ApiError {
+    fn from(e: DbError) -> Self {
+        let status = match e {
+            DbError::FileNotFound(_) => 404,
+            DbError::InvalidInput(_) | DbError::PathError(_) => 400,
+            _ => 500,
+        };
+        Self::new(status, e.to_string())
+    }
+}
+
+impl From<TagrError> for ApiError {
+    fn from(e: TagrError) -> Self {
+        match e {
+            TagrError::DbError(e) => e.into(),
+            TagrError::InvalidInput(_) | TagrError::PatternError(_) | TagrError::SearchError(_) => {
+                Self::new(400, e.to_string())
+            }
+            _ => Self::new(500, e.to_string()),
+        }
+    }
+}
+
+type ApiResult = std::result::Result<String, ApiError>;
+
+/// Hosts accepted in `Host` and `Origin` headers besides the bound address
+/// and those added with [`TagServer::with_allowed_hosts`]
+const LOCAL_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "[::1]"];
+
+/// The HTTP server behind `tagr serve`
+pub struct TagServer {
+    server: Server,
+    db: Database,
+    normalization: TagNormalization,
+    allow_write: bool,
+    exclude_dirs: Vec<String>,
+    allowed_hosts: Vec<String>,
+}
+
+impl TagServer {
+    /// Listen on `addr`, serving `db`
+    ///
+    /// # Errors
+    /// Returns an error if the address cannot be bound.
+    pub fn bind(
+        db: Database,
+        addr: impl ToSocketAddrs,
+        normalization: TagNormalization,
+    ) -> Result<Self> {
+        let server = Server::http(addr)
+            .map_err(|e| TagrError::InvalidInput(format!("Cannot start server: {e}")))?;
+        let mut allowed_hosts: Vec<String> = LOCAL_HOSTS.map(String::from).to_vec();
+        if let Some(ip) = server.server_addr().to_ip().map(|addr| addr.ip())
+            && !ip.is_unspecified()
+        {
+            let host = match ip {
+                IpAddr::V4(ip) => ip.to_string(),
+                IpAddr::V6(ip) => format!("[{ip}]"),
+            };
+            if !allowed_hosts.contains(&host) {
+                allowed_hosts.push(host);
+            }
+        }
+        Ok(Self {
+            server,
+            db,
+            normalization,
+            allow_write: false,
+            exclude_dirs: Vec::new(),
+            allowed_hosts,
+        })
+    }
+
+    /// Accept `POST /file`; without this the server is read-only
+    #[must_use]
+    pub const fn with_allow_write(mut self, allow_write: bool) -> Self {
+        self.allow_write = allow_write;
+        self
+    }
+
+    /// Also accept requests whose `Host` or `Origin` names one of `hosts`
+    ///
+    /// Remote clients address the server by a name or IP the bound address
+    /// does not reveal, e.g. when listening on `0.0.0.0`. Bare IPv6
+    /// addresses are bracketed as in a URL.
+    #[must_use]
+    pub fn with_allowed_hosts(mut self, hosts: Vec<String>) -> Self {
+        for host in hosts {
+            let host = match host.parse::<Ipv6Addr>() {
+                Ok(ip) => format!("[{ip}]"),
+                Err(_) => host,
+            };
+            if !self.allowed_hosts.contains(&host) {
+                self.allowed_hosts.push(host);
+            }
+        }
+        self
+    }
+
+    /// Whether the server listens on every interface without naming any
+    /// host remote clients may use
+    fn rejects_remote_clients(&self) -> bool {
+        self.local_addr()
+            .is_some_and(|addr| addr.ip().is_unspecified())
+            && self.allowed_hosts.len() == LOCAL_HOSTS.len()
+    }
+
+    /// Hide files under directories matching these globs from `GET /search`
+    #[must_use]
+    pub fn with_exclude_dirs(mut self, exclude_dirs: Vec<String>) -> Self {
+        self.exclude_dirs = exclude_dirs;
+        self
+    }
+
+    /// The address the server is listening on
+    #[must_use]
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    /// Handle requests until the process exits
+    pub fn run(&self) {
+        for request in self.server.incoming_requests() {
+            self.respond(request);
+        }
+    }
+
+    fn respond(&self, mut request: Request) {
+        let mut body = String::new();
+        let result = match self.check_headers(request.method(), request.headers()) {
+            Err(e) => Err(e),
+            Ok(()) => match request
+                .as_reader()
+                .take(MAX_BODY_BYTES)
+                .read_to_string(&mut body)
+            {
+                Ok(_) => self.handle(request.method(), request.url(), &body),
+                Err(e) => Err(ApiError::new(400, format!("Cannot read request body: {e}"))),
+            },
+        };
+
+        let (status, json) = match result {
+            Ok(json) => (200, json),
+            Err(e) => (
+                e.status,
+                serde_json::json!({ "error": e.message }).to_string(),
+            ),
+        };
+        let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+            .expect("static header is valid");
+        let response = Response::from_string(json)
+            .with_status_code(status)
+            .with_header(content_type);
+        if let Err(e) = request.respond(response) {
+            eprintln!("Warning: failed to send response: {e}");
+        }
+    }
+
+    /// Reject requests a web page could have sent on the user's behalf
+    fn check_headers(
+        &self,
+        method: &Method,
+        headers: &[Header],
+    ) -> std::result::Result<(), ApiError> {
+        let header = |name: &'static str| {
+            headers
+                .iter()
+                .find(|header| header.field.equiv(name))
+                .map(|header| header.value.as_str().trim())
+        };
+
+        let host = header("Host").unwrap_or_default();
+        if !self.is_allowed_host(host) {
+            return Err(ApiError::new(403, format!("Host '{host}' is not allowed")));
+        }
+        if let Some(origin) = header("Origin") {
+            let local = ["http://", "https://"]
+                .iter()
+                .find_map(|scheme| origin.strip_prefix(scheme))
+                .is_some_and(|host| self.is_allowed_host(host));
+            if !local {
+                return Err(ApiError::new(
+                    403,
+                    format!("Origin '{origin}' is not allowed"),
+                ));
+            }
+        }
+        if *method == Method::Post {
+            let json = header("Content-Type")
+                .and_then(|value| value.split(';').next())
+                .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"));
+            if !json {
+                return Err(ApiError::new(
+                    415,
+                    "POST requests must use Content-Type: application/json",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `host` (with an optional `:port`) names this server locally
+    fn is_allowed_host(&self, host: &str) -> bool {
+        let name = match host.find(']') {
+            Some(end) if host.starts_with('[') => &host[..=end],
+            _ => host.split(':').next().unwrap_or_default(),
+        };
+        self.allowed_hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(name))
+    }
+
+    fn handle(&self, method: &Method, url: &str, body: &str) -> ApiResult {
+        let (route, query) = url.split_once('?').unwrap_or((url, ""));
+        let params: Vec<(String, String)> = form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect();
+        let param = |name: &str| -> Vec<String> {
+            params
+                .iter()
+                .filter(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+                .collect()
+        };
+
+        match (method, route) {
+            (Method::Get, "/files") => self.files(&param("tag")),
+            (Method::Get, "/search") => self.search(&search_criteria(&params)?),
+            (Method::Get, "/tags") => self.tags(),
+            (Method::Get, "/file") => {
+                let path = param("path")
+                    .pop()
+                    .ok_or_else(|| ApiError::new(400, "Missing 'path' parameter"))?;
+                self.file(Path::new(&path))
+            }
+            (Method::Post, "/file") if !self.allow_write => Err(ApiError::new(
+                403,
+                "Server is read-only; restart it with --allow-write to change tags",
+            )),
+            (Method::Post, "/file") => {
+                let change: TagChange = serde_json::from_str(body)
+                    .map_err(|e| ApiError::new(400, format!("Invalid request body: {e}")))?;
+                self.change(change)
+            }
+            (_, "/files" | "/file" | "/search" | "/tags") => Err(ApiError::new(
+                405,
+                format!("Method {method} not allowed on {route}"),
+            )),
+            _ => Err(ApiError::new(404, format!("No endpoint {route}"))),
+        }
+    }
+
+    fn files(&self, tags: &[String]) -> ApiResult {
+        let files = if tags.is_empty() {
+            let mut files = self.db.list_all_files()?;
+            files.sort();
+            files
+        } else {
+            self.db.find_by_all_tags(tags)?
+        };
+        to_json(&files)
+    }
+
+    fn search(&self, criteria: &FilterCriteria) -> ApiResult {
+        let params = SearchParams::from(criteria);
+        let db = ReadOnlyDatabase::from(self.db.clone());
+        let files = crate::commands::search::find_files(&db, &params, &self.exclude_dirs)?;
+        let results = self
+            .db
+            .get_tags_many(&files)?
+            .into_iter()
+            .map(|(path, tags)| FileTags {
+                path,
+                tags: tags.unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+        to_json(&results)
+    }
+
+    fn tags(&self) -> ApiResult {
+        let counts: Vec<serde_json::Value> = self
+            .db
+            .tag_counts()?
+            .into_iter()
+            .map(|(tag, count)| serde_json::json!({ "tag": tag, "count": count }))
+            .collect();
+        to_json(&counts)
+    }
+
+    fn file(&self, path: &Path) -> ApiResult {
+        require_absolute(path)?;
+        let tags = self.db.get_tags(path)?.ok_or_else(|| {
+            ApiError::new(404, format!("{} is not in the database", path.display()))
+        })?;
+        to_json(&FileTags {
+            path: path.to_path_buf(),
+            tags,
+        })
+    }
+
+    fn change(&self, change: TagChange) -> ApiResult {
+        require_absolute(&change.path)?;
+        let add = self.normalization.apply_all(&change.add);
+        if add.is_empty() && change.remove.is_empty() {
+            return Err(ApiError::new(400, "Nothing to add or remove"));
+        }
+
+        if !add.is_empty() {
+            self.db.add_tags(&change.path, add)?;
+        }
+        if !change.remove.is_empty() {
+            self.db.remove_tags(&change.path, &change.remove)?;
+        }
+        let tags = self.db.get_tags(&change.path)?.unwrap_or_default();
+        to_json(&FileTags {
+            path: change.path,
+            tags,
+        })
+    }
+}
+
+/// Search criteria from `GET /search` query parameters
+///
+/// Repeatable: `tag`, `file`, `exclude`, `exclude_file`, `virtual` and
+/// `exclude_virtual`, matching `-t`, `-f`, `-e`, `--exclude-file`, `-v` and
+/// `--exclude-virtual`. Modes: `tag_mode`, `file_mode` and `virtual_mode`
+/// (`any` or `all`, default `all`). Flags: `regex_tag`, `regex_file` and
+/// `glob_files` (`true` or `false`).
+///
+/// The parameters fill a [`FilterCriteria`], the same type saved filters use,
+/// so a search here behaves like the equivalent `tagr search`.
+fn search_criteria(params: &[(String, String)]) -> std::result::Result<FilterCriteria, ApiError> {
+    use serde_json::Value;
+
+    let mut fields = serde_json::Map::new();
+    for (key, value) in params {
+        let list = match key.as_str() {
+            "tag" => "tags",
+            "file" => "file_patterns",
+            "exclude" => "excludes",
+            "exclude_file" => "exclude_file_patterns",
+            "virtual" => "virtual_tags",
+            "exclude_virtual" => "exclude_virtual_tags",
+            "tag_mode" | "file_mode" | "virtual_mode" => {
+                fields.insert(key.clone(), Value::String(value.clone()));
+                continue;
+            }
+            "regex_tag" | "regex_file" | "glob_files" => {
+                let flag = value.parse::<bool>().map_err(|_| {
+                    ApiError::new(400, format!("Invalid value for {key}: '{value}'"))
+                })?;
+                fields.insert(key.clone(), Value::Bool(flag));
+                continue;
+            }
+            _ => return Err(ApiError::new(400, format!("Unknown parameter '{key}'"))),
+        };
+        if let Value::Array(values) = fields
+            .entry(list)
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            values.push(Value::String(value.clone()));
+        }
+    }
+    serde_json::from_value(Value::Object(fields))
+        .map_err(|e| ApiError::new(400, format!("Invalid search: {e}")))
+}
+
+fn require_absolute(path: &Path) -> std::result::Result<(), ApiError> {
+    if path.is_absolute() {
+        Ok(())
+    } else {
+        Err(ApiError::new(
+            400,
+            format!("Path must be absolute: {}", path.display()),
+        ))
+    }
+}
+
+fn to_json(value: &impl Serialize) -> ApiResult {
+    serde_json::to_string(value).map_err(|e| ApiError::new(500, e.to_string()))
+}
+
+/// Execute the serve command
+///
+/// # Errors
+/// Returns an error if the server cannot listen on `bind:port`.
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    db: Database,
+    bind: &str,
+    port: u16,
+    allowed_hosts: Vec<String>,
+    normalization: TagNormalization,
+    allow_write: bool,
+    exclude_dirs: Vec<String>,
+    quiet: bool,
+) -> Result<()> {
+    let server = TagServer::bind(db, (bind, port), normalization)?
+        .with_allowed_hosts(allowed_hosts)
+        .with_allow_write(allow_write)
+        .with_exclude_dirs(exclude_dirs);
+    if server.rejects_remote_clients() {
+        eprintln!(
+            "Warning: listening on {bind}, but only requests addressed to localhost are \
+             answered; pass --allowed-host with the name or IP clients connect to"
+        );
+    }
+    if !quiet {
+        let addr = server
+            .local_addr()
+            .map_or_else(|| format!("{bind}:{port}"), |addr| addr.to_string());
+        let mode = if allow_write {
+            "read-write"
+        } else {
+            "read-only"
+        };
+        println!("Serving the tag database ({mode}) on http://{addr} (Ctrl+C to stop)");
+    }
+    server.run();
+    Ok(())
+}
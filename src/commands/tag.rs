@@ -1,29 +1,126 @@
 //! Tag and untag commands
 
-use crate::schema::load_default_schema;
-use crate::{TagrError, db::Database};
-use std::path::PathBuf;
+use crate::commands::bulk::{BulkAction, BulkOpSummary, print_dry_run_preview};
+use crate::schema::{TagSchema, load_default_schema};
+use crate::ui::{DialoguerInput, UserInput};
+use crate::{
+    TagrError,
+    config::{SymlinkMode, TagNormalization},
+    db::Database,
+};
+use ignore::WalkBuilder;
+use ignore::overrides::OverrideBuilder;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
 type Result<T> = std::result::Result<T, TagrError>;
 
+/// Options for deriving tags from a filename (`--from-filename`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilenameTagOptions {
+    /// Characters that separate tokens in the file stem
+    pub separators: String,
+    /// Drop tokens that consist only of ASCII digits
+    pub skip_numbers: bool,
+    /// Minimum token length in characters; shorter tokens are dropped
+    pub min_len: usize,
+}
+
+impl Default for FilenameTagOptions {
+    fn default() -> Self {
+        Self {
+            separators: "-_. ".to_string(),
+            skip_numbers: false,
+            min_len: 2,
+        }
+    }
+}
+
+/// Split a file's stem into lowercase tag candidates
+///
+/// Tokens are returned in the order they appear, without duplicates.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use tagr::commands::tag::{FilenameTagOptions, tokenize_filename};
+///
+/// let tags = tokenize_filename(
+///     Path::new("2024_Tax-Return.final.pdf"),
+///     &FilenameTagOptions::default(),
+/// );
+/// assert_eq!(tags, vec!["2024", "tax", "return", "final"]);
+/// ```
+#[must_use]
+pub fn tokenize_filename(path: &Path, options: &FilenameTagOptions) -> Vec<String> {
+    let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+        return Vec::new();
+    };
+
+    let mut tokens: Vec<String> = Vec::new();
+    for token in stem.split(|c| options.separators.contains(c)) {
+        let token = token.to_lowercase();
+        if token.chars().count() < options.min_len.max(1) {
+            continue;
+        }
+        if options.skip_numbers && token.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
 /// Execute the tag command - add tags to a file
 ///
+/// The file is keyed according to `symlinks` (see [`SymlinkMode::normalize`]).
+/// Tags are cleaned up with `normalization` before canonicalization.
+/// When `from_filename` is set, tags derived from the file's name are added to
+/// the explicit tags. When no tags are given (and not in quiet mode), the user
+/// is prompted for tags interactively with completion against tags already in
+/// the database. With `dry_run`, the file's tags before and after (including
+/// schema canonicalization) are printed and nothing is written.
+///
 /// # Errors
-/// Returns an error if the file cannot be accessed or database operations fail
+/// Returns an error if the file cannot be accessed, no tags are provided,
+/// or database operations fail
+#[allow(clippy::too_many_arguments)]
 pub fn execute(
     db: &Database,
     file: Option<PathBuf>,
+    symlinks: SymlinkMode,
     tags: &[String],
+    normalization: TagNormalization,
     no_canonicalize: bool,
+    from_filename: Option<&FilenameTagOptions>,
+    dry_run: bool,
     quiet: bool,
 ) -> Result<()> {
     let file_path = file.ok_or_else(|| TagrError::InvalidInput("No file provided".into()))?;
 
+    let mut tags = tags.to_vec();
+    if let Some(options) = from_filename {
+        for token in tokenize_filename(&file_path, options) {
+            if !tags.contains(&token) {
+                tags.push(token);
+            }
+        }
+    }
+
+    // Piped and scheduled runs get the "No tags provided" error instead
+    if tags.is_empty() && !quiet && from_filename.is_none() && std::io::stdin().is_terminal() {
+        tags = prompt_for_tags(&DialoguerInput::new(), &db.list_all_tags()?)?;
+    }
+    let tags = normalization.apply_all(&tags);
+
     if tags.is_empty() {
         return Err(TagrError::InvalidInput("No tags provided".into()));
     }
 
-    let fullpath = file_path.canonicalize().map_err(|e| {
+    let fullpath = symlinks.normalize(&file_path).map_err(|e| {
         TagrError::InvalidInput(format!(
             "Cannot access path '{}': {}",
             file_path.display(),
@@ -31,22 +128,29 @@ pub fn execute(
         ))
     })?;
 
-    // Canonicalize tags unless disabled
-    let final_tags = if no_canonicalize {
-        tags.to_vec()
-    } else {
-        // Load schema and canonicalize each tag
-        match load_default_schema() {
-            Ok(schema) => tags.iter().map(|t| schema.canonicalize(t)).collect(),
-            Err(e) => {
-                // If schema can't be loaded, warn but continue with original tags
-                if !quiet {
-                    eprintln!("Warning: Could not load schema ({e}), using tags as-is");
-                }
-                tags.to_vec()
+    let schema = load_alias_schema(no_canonicalize, quiet);
+    let final_tags = canonicalize_tags(&tags, schema.as_ref());
+
+    if dry_run {
+        let before = db.get_tags(&fullpath)?.unwrap_or_default();
+        let mut after = before.clone();
+        for tag in &final_tags {
+            if !after.contains(tag) {
+                after.push(tag.clone());
             }
         }
-    };
+        let change = TagChange {
+            before,
+            after,
+            canonicalized: tags
+                .into_iter()
+                .zip(final_tags)
+                .filter(|(given, canonical)| given != canonical)
+                .collect(),
+        };
+        change.print("tag", &file_path, quiet);
+        return Ok(());
+    }
 
     let success_msg = if quiet {
         None
@@ -67,20 +171,293 @@ pub fn execute(
     Ok(())
 }
 
+/// The schema used to resolve aliases, or `None` with `no_canonicalize`
+///
+/// If the schema can't be loaded, a warning is printed and `None` is
+/// returned, so tags are used as-is.
+fn load_alias_schema(no_canonicalize: bool, quiet: bool) -> Option<TagSchema> {
+    if no_canonicalize {
+        return None;
+    }
+    load_default_schema()
+        .map_err(|e| {
+            if !quiet {
+                eprintln!("Warning: Could not load schema ({e}), using tags as-is");
+            }
+        })
+        .ok()
+}
+
+/// Resolve aliases in `tags` with `schema`, if there is one
+fn canonicalize_tags(tags: &[String], schema: Option<&TagSchema>) -> Vec<String> {
+    match schema {
+        Some(schema) => tags.iter().map(|t| schema.canonicalize(t)).collect(),
+        None => tags.to_vec(),
+    }
+}
+
+/// Which files `tag -R` picks up while walking a directory
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WalkOptions {
+    /// Only tag files matching one of these gitignore-style globs
+    pub include: Vec<String>,
+    /// Skip files and directories matching any of these globs
+    pub exclude: Vec<String>,
+    /// Skip files ignored by `.gitignore`, `.ignore` and `.git/info/exclude`
+    pub gitignore: bool,
+    /// Walk into symlinked directories and tag symlinked files
+    pub follow_symlinks: bool,
+}
+
+/// Regular files under `dir` selected by `options`, sorted by path
+///
+/// `.git` directories are never walked. Entries that can't be read, such as
+/// symlink loops, are warned about and skipped.
+///
+/// # Errors
+/// Returns `TagrError::InvalidInput` if an include or exclude glob is invalid
+pub fn walk_files(dir: &Path, options: &WalkOptions, quiet: bool) -> Result<Vec<PathBuf>> {
+    let mut overrides = OverrideBuilder::new(dir);
+    let globs = options
+        .include
+        .iter()
+        .cloned()
+        .chain(options.exclude.iter().map(|glob| format!("!{glob}")));
+    for glob in globs {
+        overrides
+            .add(&glob)
+            .map_err(|e| TagrError::InvalidInput(format!("Invalid pattern '{glob}': {e}")))?;
+    }
+    let overrides = overrides
+        .build()
+        .map_err(|e| TagrError::InvalidInput(e.to_string()))?;
+
+    let walker = WalkBuilder::new(dir)
+        .standard_filters(false)
+        .git_ignore(options.gitignore)
+        .git_exclude(options.gitignore)
+        .ignore(options.gitignore)
+        .require_git(false)
+        .follow_links(options.follow_symlinks)
+        .overrides(overrides)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .sort_by_file_name(std::cmp::Ord::cmp)
+        .build();
+
+    let mut files = Vec::new();
+    for entry in walker {
+        match entry {
+            Ok(entry) if entry.file_type().is_some_and(|t| t.is_file()) => {
+                files.push(entry.into_path());
+            }
+            Ok(_) => {}
+            Err(e) => {
+                if !quiet {
+                    eprintln!("Warning: {e}");
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Tag every file under `dir` picked by `options` (`tag -R`)
+///
+/// Each file gets `tags` plus, with `from_filename`, tags derived from its
+/// name. Files that already have all of their tags are skipped. Results are
+/// reported with a bulk summary; with `dry_run`, the affected files are
+/// listed and nothing is written.
+///
+/// # Errors
+/// Returns `TagrError::InvalidInput` if `dir` is not a directory, no tags are
+/// given, or a glob is invalid, and database errors from tag lookups
+#[allow(clippy::too_many_arguments)]
+pub fn tag_recursive(
+    db: &Database,
+    dir: &Path,
+    symlinks: SymlinkMode,
+    tags: &[String],
+    normalization: TagNormalization,
+    no_canonicalize: bool,
+    from_filename: Option<&FilenameTagOptions>,
+    options: &WalkOptions,
+    dry_run: bool,
+    quiet: bool,
+) -> Result<()> {
+    if !dir.is_dir() {
+        return Err(TagrError::InvalidInput(format!(
+            "'{}' is not a directory",
+            dir.display()
+        )));
+    }
+    if tags.is_empty() && from_filename.is_none() {
+        return Err(TagrError::InvalidInput("No tags provided".into()));
+    }
+    // Loaded once: every walked file may need its filename tags resolved
+    let schema = load_alias_schema(no_canonicalize, quiet);
+    let tags = canonicalize_tags(&normalization.apply_all(tags), schema.as_ref());
+
+    let mut summary = BulkOpSummary::new();
+    let mut pending: Vec<(PathBuf, Vec<String>)> = Vec::new();
+    for file in walk_files(dir, options, quiet)? {
+        let mut file_tags = tags.clone();
+        if let Some(filename_options) = from_filename {
+            let derived = normalization.apply_all(&tokenize_filename(&file, filename_options));
+            for tag in canonicalize_tags(&derived, schema.as_ref()) {
+                if !file_tags.contains(&tag) {
+                    file_tags.push(tag);
+                }
+            }
+        }
+        if file_tags.is_empty() {
+            continue;
+        }
+        let key = match symlinks.normalize(&file) {
+            Ok(key) => key,
+            Err(e) => {
+                summary.add_failure(TagrError::from(e).with_context(&file, "tag"));
+                continue;
+            }
+        };
+        let existing = db.get_tags(&key)?.unwrap_or_default();
+        if file_tags.iter().all(|tag| existing.contains(tag)) {
+            summary.add_skip();
+        } else {
+            pending.push((key, file_tags));
+        }
+    }
+
+    if dry_run {
+        let files: Vec<PathBuf> = pending.into_iter().map(|(file, _)| file).collect();
+        print_dry_run_preview(&files, &tags, BulkAction::Add);
+        return Ok(());
+    }
+
+    for (file, file_tags) in pending {
+        match db.add_tags(&file, file_tags) {
+            Ok(()) => {
+                summary.add_success();
+                if !quiet {
+                    println!("✓ Tagged: {}", file.display());
+                }
+            }
+            Err(e) => {
+                if !quiet {
+                    eprintln!("✗ Failed to tag {}: {}", file.display(), e);
+                }
+                summary.add_failure(e.with_context(&file, "tag"));
+            }
+        }
+    }
+    if !quiet {
+        summary.print("Recursive Tag");
+    }
+    Ok(())
+}
+
+/// A file's tags before and after a tag or untag, as shown by `--dry-run`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagChange {
+    /// Tags currently stored for the file
+    pub before: Vec<String>,
+    /// Tags the file would have afterwards
+    pub after: Vec<String>,
+    /// Tags rewritten by the schema, as (given, canonical)
+    pub canonicalized: Vec<(String, String)>,
+}
+
+impl TagChange {
+    /// Print the change; with `quiet`, only the resulting tags, one per line
+    pub fn print(&self, action: &str, file: &Path, quiet: bool) {
+        if quiet {
+            for tag in &self.after {
+                println!("{tag}");
+            }
+            return;
+        }
+
+        println!("Would {action} {}:", file.display());
+        for (given, canonical) in &self.canonicalized {
+            println!("  {given} → {canonical} (schema alias)");
+        }
+        println!("  before:  {}", Self::list(&self.before));
+        println!("  after:   {}", Self::list(&self.after));
+
+        let added: Vec<String> = self
+            .after
+            .iter()
+            .filter(|tag| !self.before.contains(tag))
+            .cloned()
+            .collect();
+        let removed: Vec<String> = self
+            .before
+            .iter()
+            .filter(|tag| !self.after.contains(tag))
+            .cloned()
+            .collect();
+        if added.is_empty() && removed.is_empty() {
+            println!("  no change");
+        }
+        if !added.is_empty() {
+            println!("  added:   {}", added.join(", "));
+        }
+        if !removed.is_empty() {
+            println!("  removed: {}", removed.join(", "));
+        }
+    }
+
+    fn list(tags: &[String]) -> String {
+        if tags.is_empty() {
+            "(none)".to_string()
+        } else {
+            tags.join(", ")
+        }
+    }
+}
+
+/// Prompt for tags one at a time until an empty line is entered
+///
+/// Each prompt completes against `existing` tags. Duplicate entries are ignored.
+///
+/// # Errors
+/// Returns an error if the input backend fails
+pub fn prompt_for_tags(input: &dyn UserInput, existing: &[String]) -> Result<Vec<String>> {
+    let mut tags: Vec<String> = Vec::new();
+
+    while let Some(line) = input
+        .prompt_text_with_completion("Tag (empty to finish)", existing)
+        .map_err(|e| TagrError::InvalidInput(format!("Failed to read tag: {e}")))?
+    {
+        let tag = line.trim();
+        if tag.is_empty() {
+            break;
+        }
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+
+    Ok(tags)
+}
+
 /// Execute the untag command - remove tags from a file
 ///
+/// With `dry_run`, the tags before and after are printed but nothing is written.
+///
 /// # Errors
 /// Returns an error if the file cannot be accessed or database operations fail
 pub fn untag(
     db: &Database,
     file: Option<PathBuf>,
+    symlinks: SymlinkMode,
     tags: &[String],
     all: bool,
+    dry_run: bool,
     quiet: bool,
 ) -> Result<()> {
     let file_path = file.ok_or_else(|| TagrError::InvalidInput("No file provided".into()))?;
 
-    let fullpath = file_path.canonicalize().map_err(|e| {
+    let fullpath = symlinks.normalize(&file_path).map_err(|e| {
         TagrError::InvalidInput(format!(
             "Cannot access path '{}': {}",
             file_path.display(),
@@ -88,6 +465,32 @@ pub fn untag(
         ))
     })?;
 
+    if !all && tags.is_empty() {
+        return Err(TagrError::InvalidInput(
+            "No tags provided. Use -t to specify tags or --all to remove all tags".into(),
+        ));
+    }
+
+    if dry_run {
+        let before = db.get_tags(&fullpath)?.unwrap_or_default();
+        let after = if all {
+            Vec::new()
+        } else {
+            before
+                .iter()
+                .filter(|tag| !tags.contains(tag))
+                .cloned()
+                .collect()
+        };
+        let change = TagChange {
+            before,
+            after,
+            canonicalized: Vec::new(),
+        };
+        change.print("untag", &file_path, quiet);
+        return Ok(());
+    }
+
     if all {
         db.remove(&fullpath)?;
         if !quiet {
@@ -96,12 +499,6 @@ pub fn untag(
         return Ok(());
     }
 
-    if tags.is_empty() {
-        return Err(TagrError::InvalidInput(
-            "No tags provided. Use -t to specify tags or --all to remove all tags".into(),
-        ));
-    }
-
     db.remove_tags(&fullpath, tags)?;
     if !quiet {
         println!(
@@ -113,3 +510,417 @@ pub fn untag(
 
     Ok(())
 }
+
+/// Execute the move-file command
+///
+/// Moves the tags and note of `from` to `to` after the file was moved or
+/// renamed outside of tagr. `from` usually no longer exists, so it is only
+/// made absolute rather than normalized. With `dry_run`, only prints the
+/// tags that would move.
+///
+/// # Errors
+/// Returns an error if `to` cannot be accessed, `from` is not tagged, or
+/// database operations fail
+pub fn move_file(
+    db: &Database,
+    from: &Path,
+    to: &Path,
+    symlinks: SymlinkMode,
+    replace: bool,
+    dry_run: bool,
+    quiet: bool,
+) -> Result<()> {
+    let new = symlinks.normalize(to).map_err(|e| {
+        TagrError::InvalidInput(format!("Cannot access path '{}': {}", to.display(), e))
+    })?;
+    let old = if from.exists() {
+        symlinks.normalize(from)?
+    } else {
+        std::path::absolute(from)?
+    };
+
+    if dry_run {
+        let tags = db.get_tags(&old)?.ok_or_else(|| {
+            TagrError::InvalidInput(format!("{} is not in the database", old.display()))
+        })?;
+        if !quiet {
+            println!(
+                "Would move tags [{}] from {} to {}",
+                tags.join(", "),
+                from.display(),
+                to.display()
+            );
+        }
+        return Ok(());
+    }
+
+    if replace {
+        db.rename_file_replace(&old, &new)?;
+    } else {
+        db.rename_file(&old, &new)?;
+    }
+
+    if !quiet {
+        println!("Moved tags from {} to {}", from.display(), to.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TempFile, TestDb};
+    use crate::ui::mock::MockInput;
+
+    #[test]
+    fn test_prompt_for_tags_stops_on_empty_line() {
+        let input = MockInput::new(["rust", " notes ", "rust", "", "ignored"]);
+        let existing = vec!["rust".to_string()];
+
+        let tags = prompt_for_tags(&input, &existing).unwrap();
+        assert_eq!(tags, vec!["rust".to_string(), "notes".to_string()]);
+    }
+
+    #[test]
+    fn test_tokenize_filename_min_len_and_numbers() {
+        let options = FilenameTagOptions {
+            skip_numbers: true,
+            min_len: 3,
+            ..Default::default()
+        };
+        let tags = tokenize_filename(Path::new("/tmp/IMG_0042 at Beach-a.JPG"), &options);
+        assert_eq!(tags, vec!["img".to_string(), "beach".to_string()]);
+    }
+
+    #[test]
+    fn test_tokenize_filename_dates_and_extensions() {
+        let default = FilenameTagOptions::default();
+        let skip_numbers = FilenameTagOptions {
+            skip_numbers: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            tokenize_filename(Path::new("2023_vacation_beach.jpg"), &default),
+            vec!["2023", "vacation", "beach"]
+        );
+        assert_eq!(
+            tokenize_filename(Path::new("2023_vacation_beach.jpg"), &skip_numbers),
+            vec!["vacation", "beach"]
+        );
+        assert_eq!(
+            tokenize_filename(Path::new("2023-06-14 Birthday Party.PNG"), &skip_numbers),
+            vec!["birthday", "party"]
+        );
+        // Only the last extension is dropped; the stem's dots are separators
+        assert_eq!(
+            tokenize_filename(Path::new("backup.2024-01-01.tar.gz"), &skip_numbers),
+            vec!["backup", "tar"]
+        );
+        assert!(tokenize_filename(Path::new("2023.jpg"), &skip_numbers).is_empty());
+    }
+
+    #[test]
+    fn test_tokenize_filename_custom_separators() {
+        let options = FilenameTagOptions {
+            separators: "+".to_string(),
+            ..Default::default()
+        };
+        let tags = tokenize_filename(Path::new("rust+Notes+rust+draft_v2.md"), &options);
+        assert_eq!(
+            tags,
+            vec![
+                "rust".to_string(),
+                "notes".to_string(),
+                "draft_v2".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tag_and_untag_dry_run_do_not_write() {
+        let db = TestDb::new("test_tag_untag_dry_run");
+        let file = TempFile::create("dry.txt").unwrap();
+        db.db().insert(file.path(), vec!["rust".into()]).unwrap();
+        let snapshot = |db: &Database| (db.list_all().unwrap(), db.list_all_tags().unwrap());
+        let before = snapshot(db.db());
+
+        let path = Some(file.path().to_path_buf());
+        execute(
+            db.db(),
+            path.clone(),
+            SymlinkMode::default(),
+            &["notes".to_string()],
+            TagNormalization::default(),
+            true,
+            None,
+            true,
+            true,
+        )
+        .unwrap();
+        untag(
+            db.db(),
+            path.clone(),
+            SymlinkMode::default(),
+            &["rust".to_string()],
+            false,
+            true,
+            true,
+        )
+        .unwrap();
+        untag(db.db(), path, SymlinkMode::default(), &[], true, true, true).unwrap();
+
+        assert_eq!(snapshot(db.db()), before);
+        assert!(db.db().find_by_tag("notes").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_execute_normalizes_tags_before_storing() {
+        let db = TestDb::new("test_tag_normalizes");
+        let file = TempFile::create("normalize.txt").unwrap();
+
+        execute(
+            db.db(),
+            Some(file.path().to_path_buf()),
+            SymlinkMode::default(),
+            &[" Rust Lang ".to_string(), "rust-lang".to_string()],
+            TagNormalization::Slugify,
+            true,
+            None,
+            false,
+            true,
+        )
+        .unwrap();
+
+        let fullpath = file.path().canonicalize().unwrap();
+        assert_eq!(
+            db.db().get_tags(&fullpath).unwrap().unwrap(),
+            vec!["rust-lang"]
+        );
+    }
+
+    #[test]
+    fn test_execute_from_filename_dry_run_does_not_write() {
+        let db = TestDb::new("test_tag_from_filename_dry_run");
+        let file = TempFile::create("project-notes.txt").unwrap();
+
+        execute(
+            db.db(),
+            Some(file.path().to_path_buf()),
+            SymlinkMode::default(),
+            &[],
+            TagNormalization::default(),
+            true,
+            Some(&FilenameTagOptions::default()),
+            true,
+            true,
+        )
+        .unwrap();
+        assert!(db.db().list_all_files().unwrap().is_empty());
+
+        execute(
+            db.db(),
+            Some(file.path().to_path_buf()),
+            SymlinkMode::default(),
+            &["extra".to_string()],
+            TagNormalization::default(),
+            true,
+            Some(&FilenameTagOptions::default()),
+            false,
+            true,
+        )
+        .unwrap();
+        let fullpath = file.path().canonicalize().unwrap();
+        let tags = db.db().get_tags(&fullpath).unwrap().unwrap();
+        assert!(tags.contains(&"extra".to_string()));
+        assert!(tags.contains(&"project".to_string()));
+        assert!(tags.contains(&"notes".to_string()));
+    }
+
+    /// A temp tree of files, returned as (root, canonical root)
+    fn temp_tree(files: &[&str]) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        for file in files {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "content").unwrap();
+        }
+        let root = dir.path().canonicalize().unwrap();
+        (dir, root)
+    }
+
+    /// Tag `root` recursively with `rust` and return the tagged files, relative
+    /// to `root` where they are inside it
+    fn tag_tree(name: &str, root: &Path, options: &WalkOptions) -> Vec<String> {
+        let db = TestDb::new(name);
+        tag_recursive(
+            db.db(),
+            root,
+            SymlinkMode::default(),
+            &["rust".to_string()],
+            TagNormalization::default(),
+            true,
+            None,
+            options,
+            false,
+            true,
+        )
+        .unwrap();
+        let mut tagged: Vec<String> = db
+            .db()
+            .find_by_tag("rust")
+            .unwrap()
+            .iter()
+            .map(|f| f.strip_prefix(root).unwrap_or(f).display().to_string())
+            .collect();
+        tagged.sort();
+        tagged
+    }
+
+    #[test]
+    fn test_canonicalize_tags_with_loaded_schema() {
+        let mut schema = TagSchema::new();
+        schema.add_alias("js", "javascript").unwrap();
+        let tags = vec!["js".to_string(), "rust".to_string()];
+
+        assert_eq!(
+            canonicalize_tags(&tags, Some(&schema)),
+            ["javascript", "rust"]
+        );
+        assert_eq!(canonicalize_tags(&tags, None), tags);
+        assert!(load_alias_schema(true, true).is_none());
+    }
+
+    #[test]
+    fn test_tag_recursive_include_exclude() {
+        let (_dir, root) = temp_tree(&[
+            "main.rs",
+            "README.md",
+            "src/lib.rs",
+            "src/util/mod.rs",
+            "target/debug/build.rs",
+            ".hidden.rs",
+        ]);
+
+        assert_eq!(
+            tag_tree("tag_recursive_all", &root, &WalkOptions::default()),
+            [
+                ".hidden.rs",
+                "README.md",
+                "main.rs",
+                "src/lib.rs",
+                "src/util/mod.rs",
+                "target/debug/build.rs"
+            ]
+        );
+
+        let options = WalkOptions {
+            include: vec!["*.rs".into()],
+            exclude: vec!["target/".into(), ".*".into()],
+            ..WalkOptions::default()
+        };
+        assert_eq!(
+            tag_tree("tag_recursive_filtered", &root, &options),
+            ["main.rs", "src/lib.rs", "src/util/mod.rs"]
+        );
+
+        let options = WalkOptions {
+            include: vec!["src/**".into()],
+            exclude: vec!["mod.rs".into()],
+            ..WalkOptions::default()
+        };
+        assert_eq!(
+            tag_tree("tag_recursive_anchored", &root, &options),
+            ["src/lib.rs"]
+        );
+
+        let invalid = WalkOptions {
+            include: vec!["[".into()],
+            ..WalkOptions::default()
+        };
+        assert!(walk_files(&root, &invalid, true).is_err());
+    }
+
+    #[test]
+    fn test_tag_recursive_gitignore_and_symlinks() {
+        let (_dir, root) = temp_tree(&[
+            ".gitignore",
+            "keep.txt",
+            "debug.log",
+            "build/out.txt",
+            ".git/config",
+        ]);
+        std::fs::write(root.join(".gitignore"), "*.log\nbuild/\n").unwrap();
+        let (_outside, outside) = temp_tree(&["linked.txt"]);
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+        assert_eq!(
+            tag_tree("tag_recursive_plain", &root, &WalkOptions::default()),
+            [".gitignore", "build/out.txt", "debug.log", "keep.txt"]
+        );
+
+        let options = WalkOptions {
+            gitignore: true,
+            follow_symlinks: true,
+            ..WalkOptions::default()
+        };
+        assert_eq!(
+            tag_tree("tag_recursive_gitignore", &root, &options),
+            [
+                ".gitignore".to_string(),
+                outside.join("linked.txt").display().to_string(),
+                "keep.txt".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tag_recursive_dry_run_and_skips() {
+        let db = TestDb::new("tag_recursive_dry_run");
+        let (_dir, root) = temp_tree(&["a.txt", "b.txt"]);
+        let run = |dry_run| {
+            tag_recursive(
+                db.db(),
+                &root,
+                SymlinkMode::default(),
+                &["notes".to_string()],
+                TagNormalization::default(),
+                true,
+                None,
+                &WalkOptions::default(),
+                dry_run,
+                true,
+            )
+        };
+
+        run(true).unwrap();
+        assert!(db.db().list_all_files().unwrap().is_empty());
+
+        run(false).unwrap();
+        assert_eq!(db.db().find_by_tag("notes").unwrap().len(), 2);
+
+        let file = TempFile::create("not-a-dir.txt").unwrap();
+        assert!(
+            tag_recursive(
+                db.db(),
+                file.path(),
+                SymlinkMode::default(),
+                &["notes".to_string()],
+                TagNormalization::default(),
+                true,
+                None,
+                &WalkOptions::default(),
+                false,
+                true,
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_prompt_for_tags_cancelled() {
+        let input = MockInput::new(Vec::<String>::new());
+        let tags = prompt_for_tags(&input, &[]).unwrap();
+        assert!(tags.is_empty());
+    }
+}
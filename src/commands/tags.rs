@@ -1,25 +1,387 @@
 //! Tags command - global tag management
 
-use crate::{TagrError, cli::TagsCommands, db::Database, output};
+use crate::{
+    TagrError,
+    cli::{TagsCommands, TagsQueryArgs},
+    config::SymlinkMode,
+    db::Database,
+    output,
+    schema::{TagSchema, load_default_schema},
+    ui::{DialoguerInput, DisplayItem, FinderConfig, FuzzyFinder, RatatuiFinder, UserInput},
+};
 use dialoguer::Confirm;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 type Result<T> = std::result::Result<T, TagrError>;
 
 /// Execute the tags management command
 ///
+/// Without a subcommand, lists all tags, or those of `query.file`. The file is
+/// keyed according to `symlinks` (see [`SymlinkMode::normalize`]).
+///
 /// # Errors
-/// Returns an error if database operations fail or user interaction fails
-pub fn execute(db: &Database, command: &TagsCommands, quiet: bool) -> Result<()> {
+/// Returns an error if database operations fail, user interaction fails, or
+/// the listing options are given to a subcommand that does not list tags
+pub fn execute(
+    db: &Database,
+    command: Option<&TagsCommands>,
+    query: &TagsQueryArgs,
+    symlinks: SymlinkMode,
+    dry_run: bool,
+    quiet: bool,
+) -> Result<()> {
+    let prefix = query.prefix.as_deref();
+    let Some(command) = command else {
+        return match &query.file {
+            Some(file) => list_file_tags(db, file, symlinks, prefix, query.print0, quiet),
+            None => list_all_tags(db, None, prefix, query.print0, quiet),
+        };
+    };
+
+    if query.file.is_some() {
+        return Err(TagrError::InvalidInput(
+            "--for cannot be combined with a subcommand".into(),
+        ));
+    }
+    if !matches!(command, TagsCommands::List { .. }) && (prefix.is_some() || query.print0) {
+        return Err(TagrError::InvalidInput(
+            "--prefix and --print0 only apply to listing tags".into(),
+        ));
+    }
+
     match command {
-        TagsCommands::List { tree } => list_all_tags(db, *tree, quiet),
-        TagsCommands::Remove { tag } => remove_tag_globally(db, tag, quiet),
+        TagsCommands::List {
+            hierarchy,
+            depth,
+            no_unicode,
+            canonical,
+        } => {
+            if query.print0 && (*hierarchy || *canonical) {
+                return Err(TagrError::InvalidInput(
+                    "--print0 cannot be combined with --hierarchy or --canonical".into(),
+                ));
+            }
+            if *canonical {
+                return list_canonical_tags(db, &load_default_schema()?, prefix, quiet);
+            }
+            let tree = hierarchy.then_some(TreeOptions {
+                ascii: *no_unicode,
+                depth: *depth,
+            });
+            list_all_tags(db, tree, prefix, query.print0, quiet)
+        }
+        TagsCommands::Cloud { limit } => show_tag_cloud(db, *limit, quiet),
+        TagsCommands::Remove { tag } => remove_tag_globally(db, tag, dry_run, quiet),
+        TagsCommands::Rename {
+            old_tag,
+            new_tag,
+            update_refs,
+            merge,
+            dry_run,
+            yes,
+        } => {
+            let mut refs = if *update_refs {
+                Some(crate::commands::bulk::TagRefs::load_default()?)
+            } else {
+                None
+            };
+            crate::commands::bulk::rename_tag(
+                db,
+                old_tag,
+                new_tag,
+                refs.as_mut(),
+                *merge,
+                *dry_run,
+                *yes,
+                quiet,
+            )
+        }
+        TagsCommands::MergeInteractive { dry_run, yes } => merge_interactive(
+            db,
+            &RatatuiFinder::new(),
+            &DialoguerInput::new(),
+            *dry_run,
+            *yes,
+            quiet,
+        ),
+        TagsCommands::MergeSimilar {
+            threshold,
+            interactive,
+            dry_run,
+            yes,
+        } => merge_similar(
+            db,
+            &DialoguerInput::new(),
+            *threshold,
+            *interactive,
+            *dry_run,
+            *yes,
+            quiet,
+        ),
+        TagsCommands::RenamePrefix {
+            old_prefix,
+            new_prefix,
+            dry_run,
+            yes,
+        } => crate::commands::bulk::rename_tag_prefix(
+            db, old_prefix, new_prefix, *dry_run, *yes, quiet,
+        ),
     }
 }
 
-fn list_all_tags(db: &Database, tree: bool, quiet: bool) -> Result<()> {
+/// Pick source tags in `finder`, prompt for a target with `input`, and merge
+///
+/// Cancelling the finder, the target prompt or the confirmation returns
+/// without touching the database.
+///
+/// # Errors
+/// Returns an error if the finder or prompts fail, the target is one of the
+/// sources, or database operations fail
+pub fn merge_interactive(
+    db: &Database,
+    finder: &dyn FuzzyFinder,
+    input: &dyn UserInput,
+    dry_run: bool,
+    yes: bool,
+    quiet: bool,
+) -> Result<()> {
     let tags = db.list_all_tags()?;
+    if tags.is_empty() {
+        if !quiet {
+            println!("No tags found in database.");
+        }
+        return Ok(());
+    }
+
+    let items = tags
+        .iter()
+        .map(|tag| DisplayItem::new(tag.clone(), tag.clone(), tag.clone()))
+        .collect();
+    let config = FinderConfig::new(items, "Tags to merge> ".to_string())
+        .with_multi_select(true)
+        .with_database(Some(Arc::new(db.clone())));
+    let result = finder
+        .run(config)
+        .map_err(|e| TagrError::BrowseError(e.to_string()))?;
+    if result.aborted || result.selected.is_empty() {
+        if !quiet {
+            println!("Merge cancelled.");
+        }
+        return Ok(());
+    }
+    let sources = result.selected;
+
+    let prompt = format!("Merge [{}] into tag", sources.join(", "));
+    let target = input
+        .prompt_text(&prompt, None, false)
+        .map_err(|e| TagrError::InvalidInput(e.to_string()))?
+        .map(|target| target.trim().to_string())
+        .filter(|target| !target.is_empty());
+    let Some(target) = target else {
+        if !quiet {
+            println!("Merge cancelled.");
+        }
+        return Ok(());
+    };
+
+    if !dry_run && !yes {
+        let prompt = format!("Merge [{}] into '{target}'?", sources.join(", "));
+        let confirmed = input
+            .prompt_confirm(&prompt, true)
+            .map_err(|e| TagrError::InvalidInput(e.to_string()))?;
+        if confirmed != Some(true) {
+            if !quiet {
+                println!("Merge cancelled.");
+            }
+            return Ok(());
+        }
+    }
+
+    crate::commands::bulk::merge_tags(db, &sources, &target, dry_run, true, quiet)
+}
+
+/// Merge groups of near-duplicate tags, each into one canonical tag
+///
+/// Groups come from [`Database::detect_duplicate_tags`]. The most used tag in
+/// a group is its canonical form; with `interactive`, `input` picks it instead
+/// or skips the group. `dry_run` prints the groups and proposed canonical forms.
+///
+/// # Errors
+/// Returns an error if `threshold` is outside `0.0..=1.0`, a prompt fails, or
+/// database operations fail
+pub fn merge_similar(
+    db: &Database,
+    input: &dyn UserInput,
+    threshold: f64,
+    interactive: bool,
+    dry_run: bool,
+    yes: bool,
+    quiet: bool,
+) -> Result<()> {
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(TagrError::InvalidInput(format!(
+            "Similarity threshold must be between 0.0 and 1.0, got {threshold}"
+        )));
+    }
+
+    let groups = db.detect_duplicate_tags(threshold)?;
+    if groups.is_empty() {
+        if !quiet {
+            println!("No similar tags found.");
+        }
+        return Ok(());
+    }
+
+    let mut merges = Vec::new();
+    for group in groups {
+        let tags: Vec<String> = group.iter().map(|(tag, _)| tag.clone()).collect();
+        let canonical = if interactive {
+            let prompt = format!("Select canonical form: [{}]", tags.join(", "));
+            let mut items = tags.clone();
+            items.push("(skip this group)".to_string());
+            match input
+                .prompt_select(&prompt, &items, Some(0))
+                .map_err(|e| TagrError::InvalidInput(e.to_string()))?
+            {
+                Some(index) if index < tags.len() => index,
+                _ => continue,
+            }
+        } else {
+            0
+        };
+
+        if dry_run || !quiet {
+            let counts: Vec<String> = group
+                .iter()
+                .map(|(tag, count)| format!("{tag} ({count})"))
+                .collect();
+            println!("{} → '{}'", counts.join(", "), tags[canonical]);
+        }
+        let mut sources = tags;
+        let target = sources.remove(canonical);
+        merges.push((sources, target));
+    }
+
+    if merges.is_empty() {
+        if !quiet {
+            println!("Merge cancelled.");
+        }
+        return Ok(());
+    }
+    if dry_run {
+        println!(
+            "\nWould merge {} group(s). Run without --dry-run to apply.",
+            merges.len()
+        );
+        return Ok(());
+    }
+    if !yes {
+        let prompt = format!("Merge {} group(s)?", merges.len());
+        let confirmed = input
+            .prompt_confirm(&prompt, true)
+            .map_err(|e| TagrError::InvalidInput(e.to_string()))?;
+        if confirmed != Some(true) {
+            if !quiet {
+                println!("Merge cancelled.");
+            }
+            return Ok(());
+        }
+    }
+
+    for (sources, target) in &merges {
+        crate::commands::bulk::merge_tags(db, sources, target, false, true, quiet)?;
+    }
+    Ok(())
+}
+
+/// Rendering options for [`format_as_tree`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TreeOptions {
+    /// Draw branches with `+--`/`\--` instead of box-drawing characters
+    pub ascii: bool,
+    /// Maximum number of levels to show (None = unlimited)
+    pub depth: Option<usize>,
+}
+
+/// Keep only the tags starting with `prefix` (all of them when `None`)
+#[must_use]
+pub fn filter_by_prefix(tags: Vec<String>, prefix: Option<&str>) -> Vec<String> {
+    match prefix {
+        Some(prefix) => tags
+            .into_iter()
+            .filter(|tag| tag.starts_with(prefix))
+            .collect(),
+        None => tags,
+    }
+}
+
+/// Write each tag followed by a NUL byte, so tags may contain spaces or newlines
+///
+/// # Errors
+/// Returns an error if writing to `out` fails
+pub fn write_null_separated<W: Write>(out: &mut W, tags: &[String]) -> io::Result<()> {
+    for tag in tags {
+        out.write_all(tag.as_bytes())?;
+        out.write_all(b"\0")?;
+    }
+    out.flush()
+}
+
+fn print_null_separated(tags: &[String]) -> Result<()> {
+    write_null_separated(&mut io::stdout().lock(), tags)?;
+    Ok(())
+}
+
+fn list_file_tags(
+    db: &Database,
+    file: &Path,
+    symlinks: SymlinkMode,
+    prefix: Option<&str>,
+    print0: bool,
+    quiet: bool,
+) -> Result<()> {
+    let fullpath = symlinks.normalize(file).map_err(|e| {
+        TagrError::InvalidInput(format!("Cannot access path '{}': {}", file.display(), e))
+    })?;
+    let tags = filter_by_prefix(db.get_tags(&fullpath)?.unwrap_or_default(), prefix);
+
+    if print0 {
+        return print_null_separated(&tags);
+    }
+    if tags.is_empty() {
+        if !quiet {
+            println!("No tags found for {}", fullpath.display());
+        }
+        return Ok(());
+    }
+    if !quiet {
+        println!("Tags for {}:", fullpath.display());
+    }
+    for tag in &tags {
+        if quiet {
+            println!("{tag}");
+        } else {
+            println!("  {tag}");
+        }
+    }
+    Ok(())
+}
+
+fn list_all_tags(
+    db: &Database,
+    tree: Option<TreeOptions>,
+    prefix: Option<&str>,
+    print0: bool,
+    quiet: bool,
+) -> Result<()> {
+    let tags = filter_by_prefix(db.list_all_tags()?, prefix);
 
+    if print0 {
+        return print_null_separated(&tags);
+    }
     if tags.is_empty() {
         if !quiet {
             println!("No tags found in database.");
@@ -27,8 +389,8 @@ fn list_all_tags(db: &Database, tree: bool, quiet: bool) -> Result<()> {
         return Ok(());
     }
 
-    if tree {
-        display_tree_view(db, &tags, quiet)
+    if let Some(options) = tree {
+        display_tree_view(db, &tags, options, quiet)
     } else {
         display_flat_list(db, &tags, quiet)
     }
@@ -39,105 +401,233 @@ fn display_flat_list(db: &Database, tags: &[String], quiet: bool) -> Result<()>
         println!("Tags in database:");
     }
     for tag in tags {
-        let count = db.find_by_tag(tag)?.len();
+        let count = db.count_files_per_tag_fast(tag)?;
         println!("{}", output::tag_with_count(tag, count, quiet));
     }
     Ok(())
 }
 
-fn display_tree_view(db: &Database, tags: &[String], quiet: bool) -> Result<()> {
-    use crate::schema::HIERARCHY_DELIMITER;
-
-    // Separate hierarchical tags from flat tags
-    let mut hierarchy: HashMap<String, Vec<String>> = HashMap::new();
-    let mut root_tags: HashSet<String> = HashSet::new();
+/// Print tags as bars proportional to how many files carry them
+fn show_tag_cloud(db: &Database, limit: Option<usize>, quiet: bool) -> Result<()> {
+    const MIN_BAR: usize = 10;
+    const MAX_BAR: usize = 50;
 
-    for tag in tags {
-        if tag.contains(HIERARCHY_DELIMITER) {
-            // Extract parent from hierarchical tag (e.g., "lang:rust" -> "lang")
-            if let Some(parent) = tag
-                .rsplit_once(HIERARCHY_DELIMITER)
-                .map(|(p, _)| p.to_string())
-            {
-                hierarchy
-                    .entry(parent.clone())
-                    .or_default()
-                    .push(tag.clone());
-                root_tags.insert(extract_root(tag));
-            }
-        } else {
-            // Flat tag
-            root_tags.insert(tag.clone());
+    let mut counts = db.tag_counts()?;
+    if counts.is_empty() {
+        if !quiet {
+            println!("No tags found in database.");
         }
+        return Ok(());
+    }
+    if let Some(limit) = limit {
+        counts.truncate(limit);
     }
 
+    let max = counts.first().map_or(0, |(_, count)| *count);
+    let tag_width = counts
+        .iter()
+        .map(|(tag, _)| unicode_width::UnicodeWidthStr::width(tag.as_str()))
+        .max()
+        .unwrap_or(0);
+    let label_width = tag_width + 2 + 1 + max.to_string().len();
+    let bar = output::terminal_width()
+        .unwrap_or(80)
+        .saturating_sub(label_width)
+        .clamp(MIN_BAR, MAX_BAR);
+
     if !quiet {
-        println!("Tags in database (tree view):");
+        println!("Tag usage:");
     }
+    print!("{}", output::format_tag_bars(&counts, max, bar));
+    Ok(())
+}
 
-    // Sort root tags for consistent output
-    let mut sorted_roots: Vec<_> = root_tags.into_iter().collect();
-    sorted_roots.sort();
+/// A canonical tag with the files of every alias folded into it
+#[derive(Debug, Default)]
+struct CanonicalTag {
+    files: HashSet<PathBuf>,
+    /// Aliases found in the database that were folded in
+    aliases: BTreeSet<String>,
+}
 
-    for root in sorted_roots {
-        let count = db.find_by_tag(&root)?.len();
-        println!("{}", output::tag_with_count(&root, count, quiet));
-        print_children(db, &root, &hierarchy, quiet)?;
+/// Group tags by their canonical form, unioning file sets so a file tagged
+/// with both an alias and its canonical tag is counted once
+fn fold_aliases(
+    db: &Database,
+    tags: &[String],
+    schema: &TagSchema,
+) -> Result<BTreeMap<String, CanonicalTag>> {
+    let mut folded: BTreeMap<String, CanonicalTag> = BTreeMap::new();
+    for tag in tags {
+        let canonical = schema.canonicalize(tag);
+        let entry = folded.entry(canonical.clone()).or_default();
+        entry.files.extend(db.find_by_tag(tag)?);
+        if *tag != canonical {
+            entry.aliases.insert(tag.clone());
+        }
     }
-
-    Ok(())
+    Ok(folded)
 }
 
-fn print_children(
+fn list_canonical_tags(
     db: &Database,
-    parent: &str,
-    hierarchy: &HashMap<String, Vec<String>>,
+    schema: &TagSchema,
+    prefix: Option<&str>,
     quiet: bool,
 ) -> Result<()> {
-    use crate::schema::HIERARCHY_DELIMITER;
+    let tags = db.list_all_tags()?;
+    if tags.is_empty() {
+        if !quiet {
+            println!("No tags found in database.");
+        }
+        return Ok(());
+    }
 
-    // Find all direct children of this parent using the pre-computed hierarchy map
-    // This is O(1) lookup effectively, avoiding O(N) scan of all tags
-    if let Some(children) = hierarchy.get(parent) {
-        let mut sorted_children = children.clone();
-        sorted_children.sort();
+    if !quiet {
+        println!("Tags in database (aliases folded):");
+    }
+    for (tag, folded) in fold_aliases(db, &tags, schema)? {
+        if prefix.is_some_and(|prefix| !tag.starts_with(prefix)) {
+            continue;
+        }
+        if quiet {
+            println!("{tag}");
+        } else if folded.aliases.is_empty() {
+            println!("  {tag} ({})", folded.files.len());
+        } else {
+            let aliases: Vec<_> = folded.aliases.into_iter().collect();
+            println!(
+                "  {tag} ({}) [aliases: {}]",
+                folded.files.len(),
+                aliases.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
 
-        for (idx, child) in sorted_children.iter().enumerate() {
-            let is_last = idx == sorted_children.len() - 1;
-            let count = db.find_by_tag(child)?.len();
+fn display_tree_view(
+    db: &Database,
+    tags: &[String],
+    options: TreeOptions,
+    quiet: bool,
+) -> Result<()> {
+    let mut counts = HashMap::new();
+    if !quiet {
+        println!("Tags in database (tree view):");
+        for tag in tags {
+            counts.insert(tag.clone(), db.count_files_per_tag_fast(tag)?);
+        }
+    }
 
-            // Calculate depth by counting delimiters
-            // root (0 delimiters) -> depth 0 (but printed at top level)
-            // root:child (1 delimiter) -> depth 1
-            let depth = child.matches(HIERARCHY_DELIMITER).count();
+    print!(
+        "{}",
+        format_as_tree(tags, crate::schema::HIERARCHY_DELIMITER, &counts, options)
+    );
+    Ok(())
+}
 
-            // Box drawing characters for tree visualization
-            let prefix_str = if is_last { "└── " } else { "├── " };
-            let indent = "    ".repeat(depth.saturating_sub(1));
+/// Node in the tag tree built by [`format_as_tree`]
+#[derive(Default)]
+struct TreeNode {
+    /// Whether the full path up to this node is itself a tag
+    is_tag: bool,
+    /// Full tag path, used to look up the file count
+    path: String,
+    children: BTreeMap<String, TreeNode>,
+}
 
-            if quiet {
-                println!("{indent}{prefix_str}{child}");
-            } else {
-                println!("  {indent}{prefix_str}{child}  ({count} file(s))");
+/// Render tags as an indented tree, splitting each tag on `separator`
+///
+/// Tags sharing a prefix are grouped under it, e.g. `lang:rust` and `lang:go`
+/// both appear below `lang:`. Prefixes that are not tags themselves end with
+/// the separator; tags present in `counts` are followed by their file count.
+#[must_use]
+pub fn format_as_tree(
+    tags: &[String],
+    separator: char,
+    counts: &HashMap<String, usize>,
+    options: TreeOptions,
+) -> String {
+    let mut root = TreeNode::default();
+    for tag in tags {
+        let mut node = &mut root;
+        let mut path = String::new();
+        for segment in tag.split(separator) {
+            if !path.is_empty() {
+                path.push(separator);
             }
-
-            // Recursively print children of this child
-            print_children(db, child, hierarchy, quiet)?;
+            path.push_str(segment);
+            node = node.children.entry(segment.to_string()).or_default();
+            node.path.clone_from(&path);
         }
+        node.is_tag = true;
     }
 
-    Ok(())
+    let mut out = String::new();
+    for (name, node) in &root.children {
+        render_label(&mut out, name, node, separator, counts);
+        render_children(&mut out, node, "  ", 1, separator, counts, options);
+    }
+    out
+}
+
+fn render_children(
+    out: &mut String,
+    node: &TreeNode,
+    indent: &str,
+    level: usize,
+    separator: char,
+    counts: &HashMap<String, usize>,
+    options: TreeOptions,
+) {
+    if options.depth.is_some_and(|depth| level >= depth) {
+        return;
+    }
+    let (branch, last, pipe) = if options.ascii {
+        ("+-- ", "\\-- ", "|   ")
+    } else {
+        ("├── ", "└── ", "│   ")
+    };
+
+    let count = node.children.len();
+    for (idx, (name, child)) in node.children.iter().enumerate() {
+        let is_last = idx + 1 == count;
+        out.push_str(indent);
+        out.push_str(if is_last { last } else { branch });
+        render_label(out, name, child, separator, counts);
+
+        let child_indent = format!("{indent}{}", if is_last { "    " } else { pipe });
+        render_children(
+            out,
+            child,
+            &child_indent,
+            level + 1,
+            separator,
+            counts,
+            options,
+        );
+    }
 }
 
-fn extract_root(tag: &str) -> String {
-    use crate::schema::HIERARCHY_DELIMITER;
-    tag.split(HIERARCHY_DELIMITER)
-        .next()
-        .expect("split always returns at least one element")
-        .to_string()
+fn render_label(
+    out: &mut String,
+    name: &str,
+    node: &TreeNode,
+    separator: char,
+    counts: &HashMap<String, usize>,
+) {
+    out.push_str(name);
+    if !node.is_tag {
+        out.push(separator);
+    } else if let Some(count) = counts.get(&node.path) {
+        let noun = if *count == 1 { "file" } else { "files" };
+        out.push_str(&format!(" ({count} {noun})"));
+    }
+    out.push('\n');
 }
 
-fn remove_tag_globally(db: &Database, tag: &str, quiet: bool) -> Result<()> {
+fn remove_tag_globally(db: &Database, tag: &str, dry_run: bool, quiet: bool) -> Result<()> {
     let files_before = db.find_by_tag(tag)?;
 
     if files_before.is_empty() {
@@ -155,6 +645,16 @@ fn remove_tag_globally(db: &Database, tag: &str, quiet: bool) -> Result<()> {
         println!();
     }
 
+    if dry_run {
+        if !quiet {
+            println!(
+                "Would remove tag '{tag}' from {} file(s)",
+                files_before.len()
+            );
+        }
+        return Ok(());
+    }
+
     if !confirm("Remove tag from all files?", quiet)? {
         if !quiet {
             println!("Cancelled.");
@@ -184,3 +684,167 @@ fn confirm(prompt: &str, quiet: bool) -> Result<bool> {
         .interact()
         .map_err(|e| TagrError::InvalidInput(format!("Confirmation failed: {e}")))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockFinder, MockInput, TempFile, TestDb};
+
+    fn tags(list: &[&str]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_format_as_tree_groups_by_prefix() {
+        let tags = tags(&["lang:rust", "lang:go", "lang:rust:async", "todo"]);
+        let counts = HashMap::from([
+            ("lang:rust".to_string(), 35),
+            ("lang:go".to_string(), 8),
+            ("lang:rust:async".to_string(), 1),
+            ("todo".to_string(), 2),
+        ]);
+
+        let tree = format_as_tree(&tags, ':', &counts, TreeOptions::default());
+        assert_eq!(
+            tree,
+            "lang:\n  ├── go (8 files)\n  └── rust (35 files)\n      └── async (1 file)\ntodo (2 files)\n"
+        );
+    }
+
+    #[test]
+    fn test_format_as_tree_ascii_and_depth() {
+        let tags = tags(&["a:b:c", "a:d"]);
+        let options = TreeOptions {
+            ascii: true,
+            depth: Some(2),
+        };
+
+        let tree = format_as_tree(&tags, ':', &HashMap::new(), options);
+        assert_eq!(tree, "a:\n  +-- b:\n  \\-- d\n");
+    }
+
+    #[test]
+    fn test_filter_by_prefix() {
+        let all = tags(&["lang:go", "lang:rust", "language", "todo"]);
+        assert_eq!(
+            filter_by_prefix(all.clone(), Some("lang:")),
+            ["lang:go", "lang:rust"]
+        );
+        assert_eq!(filter_by_prefix(all.clone(), Some("lang")).len(), 3);
+        assert!(filter_by_prefix(all.clone(), Some("x")).is_empty());
+        assert_eq!(filter_by_prefix(all.clone(), None), all);
+    }
+
+    #[test]
+    fn test_write_null_separated() {
+        let mut out = Vec::new();
+        write_null_separated(&mut out, &tags(&["two words", "lang:rust"])).unwrap();
+        assert_eq!(out, b"two words\0lang:rust\0");
+
+        out.clear();
+        write_null_separated(&mut out, &[]).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_listing_flags_rejected_by_other_subcommands() {
+        let test_db = TestDb::new("test_listing_flags_rejected");
+        let db = test_db.db();
+        let file = TempFile::create("listing_flags.rs").unwrap();
+        db.insert(file.path(), tags(&["lang:rust"])).unwrap();
+
+        let query = TagsQueryArgs {
+            print0: true,
+            ..Default::default()
+        };
+        let remove = TagsCommands::Remove {
+            tag: "lang:rust".into(),
+        };
+        let result = execute(
+            db,
+            Some(&remove),
+            &query,
+            SymlinkMode::default(),
+            false,
+            true,
+        );
+        assert!(result.is_err());
+        assert_eq!(db.list_all_tags().unwrap(), ["lang:rust"]);
+    }
+
+    #[test]
+    fn test_merge_interactive() {
+        let test_db = TestDb::new("test_merge_interactive");
+        let db = test_db.db();
+        let a = TempFile::create("merge_interactive_a.js").unwrap();
+        let b = TempFile::create("merge_interactive_b.js").unwrap();
+        db.insert(a.path(), tags(&["js", "web"])).unwrap();
+        db.insert(b.path(), tags(&["JS"])).unwrap();
+
+        // Cancelling the finder or the target prompt changes nothing
+        let no_target: [&str; 0] = [];
+        for (finder, input) in [
+            (MockFinder::aborted(), MockInput::new(["javascript"])),
+            (MockFinder::new(tags(&["js"])), MockInput::new(no_target)),
+        ] {
+            merge_interactive(db, &finder, &input, false, false, true).unwrap();
+            assert_eq!(db.list_all_tags().unwrap(), ["JS", "js", "web"]);
+        }
+
+        let finder = MockFinder::new(tags(&["js", "JS"]));
+        let input = MockInput::new(["javascript"]);
+        merge_interactive(db, &finder, &input, false, false, true).unwrap();
+        assert_eq!(db.list_all_tags().unwrap(), ["javascript", "web"]);
+        assert_eq!(db.find_by_tag("javascript").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_merge_similar() {
+        let test_db = TestDb::new("test_merge_similar");
+        let db = test_db.db();
+        let a = TempFile::create("merge_similar_a.js").unwrap();
+        let b = TempFile::create("merge_similar_b.js").unwrap();
+        db.insert(a.path(), tags(&["javascript", "web"])).unwrap();
+        db.insert(b.path(), tags(&["JavaScript", "javascript"]))
+            .unwrap();
+        let c = TempFile::create("merge_similar_c.js").unwrap();
+        db.insert(c.path(), tags(&["Javascript"])).unwrap();
+
+        assert!(merge_similar(db, &MockInput::default(), 1.5, false, false, true, true).is_err());
+
+        // Dry run leaves the tags alone
+        merge_similar(db, &MockInput::default(), 0.8, false, true, true, true).unwrap();
+        assert_eq!(
+            db.list_all_tags().unwrap(),
+            ["JavaScript", "Javascript", "javascript", "web"]
+        );
+
+        // The most used variant becomes canonical
+        merge_similar(db, &MockInput::default(), 0.8, true, false, true, true).unwrap();
+        assert_eq!(db.list_all_tags().unwrap(), ["javascript", "web"]);
+        assert_eq!(db.find_by_tag("javascript").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_fold_aliases_unions_files() {
+        let test_db = TestDb::new("test_fold_aliases_unions_files");
+        let db = test_db.db();
+        let both = TempFile::create("fold_both.js").unwrap();
+        let alias_only = TempFile::create("fold_alias.js").unwrap();
+        let other = TempFile::create("fold_other.rs").unwrap();
+        db.insert(both.path(), tags(&["js", "javascript"])).unwrap();
+        db.insert(alias_only.path(), tags(&["JS"])).unwrap();
+        db.insert(other.path(), tags(&["rust"])).unwrap();
+
+        let mut schema = TagSchema::new();
+        schema.add_alias("js", "javascript").unwrap();
+        schema.add_alias("JS", "javascript").unwrap();
+
+        let folded = fold_aliases(db, &db.list_all_tags().unwrap(), &schema).unwrap();
+        assert_eq!(folded.keys().collect::<Vec<_>>(), ["javascript", "rust"]);
+        let javascript = &folded["javascript"];
+        assert_eq!(javascript.files.len(), 2);
+        assert_eq!(javascript.aliases.iter().collect::<Vec<_>>(), ["JS", "js"]);
+        assert_eq!(folded["rust"].files.len(), 1);
+    }
+}
@@ -0,0 +1,191 @@
+//! Effective configuration listing
+//!
+//! Flattens every configuration field to a dotted name with its current
+//! value, default value and where the value came from. Used by
+//! `tagr config get all`.
+
+use super::TagrConfig;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// One configuration field, as listed by `tagr config list`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigEntry {
+    /// Dotted field name, e.g. `preview.max_lines`
+    pub key: String,
+    pub value: String,
+    pub default: String,
+    /// `toml`, `env` or `default`
+    pub source: String,
+}
+
+/// Shown instead of the value of sensitive fields
+const MASK: &str = "********";
+
+/// Name fragments marking a field as sensitive
+const SENSITIVE: &[&str] = &["token", "password", "secret", "api_key"];
+
+/// Whether a field's value must not be printed
+fn is_sensitive(field: &str) -> bool {
+    let name = field.rsplit('.').next().unwrap_or(field);
+    SENSITIVE.iter().any(|fragment| name.contains(fragment))
+}
+
+/// Flatten nested objects into dotted keys; arrays and scalars are leaves
+fn flatten(prefix: &str, value: Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&key, value, out);
+            }
+        }
+        leaf => {
+            out.insert(prefix.to_string(), leaf);
+        }
+    }
+}
+
+fn flattened(config: &TagrConfig) -> BTreeMap<String, Value> {
+    let mut fields = BTreeMap::new();
+    if let Ok(value) = serde_json::to_value(config) {
+        flatten("", value, &mut fields);
+    }
+    fields
+}
+
+fn render(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => "none".to_string(),
+        Some(Value::String(s)) if s.is_empty() => "\"\"".to_string(),
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| render(Some(item)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Whether the dotted `field` is set in the parsed config file
+fn in_file(file: &toml::Table, field: &str) -> bool {
+    let mut parts = field.split('.');
+    let Some(first) = parts.next() else {
+        return false;
+    };
+    let mut current = file.get(first);
+    for part in parts {
+        current = current.and_then(|value| value.get(part));
+    }
+    current.is_some()
+}
+
+impl TagrConfig {
+    /// Every configuration field with its current and default value and source
+    ///
+    /// Fields are dotted names (`preview.max_lines`) in alphabetical order.
+    /// The source is `toml` when the config file sets the field, `env` when
+    /// an environment variable supplies it and `default` otherwise. Values of
+    /// sensitive fields (tokens, passwords) are masked.
+    #[must_use]
+    pub fn to_display_table(&self) -> Vec<ConfigEntry> {
+        let file = Self::config_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| content.parse::<toml::Table>().ok())
+            .unwrap_or_default();
+        self.display_table_with(&file)
+    }
+
+    /// Like [`TagrConfig::to_display_table`], taking sources from `file`
+    #[must_use]
+    pub fn display_table_with(&self, file: &toml::Table) -> Vec<ConfigEntry> {
+        let current = flattened(self);
+        let defaults = flattened(&Self::default());
+        let mut names: Vec<&String> = current.keys().chain(defaults.keys()).collect();
+        names.sort();
+        names.dedup();
+
+        names
+            .into_iter()
+            .map(|field| {
+                let mut value = render(current.get(field));
+                let mut default = render(defaults.get(field));
+                let mut source = if in_file(file, field) {
+                    "toml"
+                } else {
+                    "default"
+                };
+
+                // The notes editor falls back to $EDITOR, then vim
+                if field == "notes.editor" && source == "default" {
+                    default = "vim".to_string();
+                    value = self.notes.get_editor();
+                    if std::env::var_os("EDITOR").is_some() {
+                        source = "env";
+                    }
+                }
+
+                if is_sensitive(field) {
+                    for shown in [&mut value, &mut default] {
+                        if shown != "none" {
+                            *shown = MASK.to_string();
+                        }
+                    }
+                }
+                ConfigEntry {
+                    key: field.clone(),
+                    value,
+                    default,
+                    source: source.to_string(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_table_reports_values_defaults_and_sources() {
+        let file: toml::Table = "quiet = true\n[preview]\nmax_lines = 10\n".parse().unwrap();
+        let config = TagrConfig {
+            quiet: true,
+            ..TagrConfig::default()
+        };
+
+        let table = config.display_table_with(&file);
+        let row = |field: &str| {
+            table
+                .iter()
+                .find(|entry| entry.key == field)
+                .unwrap_or_else(|| panic!("missing {field}"))
+                .clone()
+        };
+
+        assert_eq!(
+            row("quiet"),
+            ConfigEntry {
+                key: "quiet".into(),
+                value: "true".into(),
+                default: "false".into(),
+                source: "toml".into(),
+            }
+        );
+        assert_eq!(row("db_cache_mb").source, "default");
+        assert_eq!(row("max_files").value, "none");
+        assert_eq!(row("preview.max_lines").source, "toml");
+        assert!(table.windows(2).all(|w| w[0].key < w[1].key));
+
+        assert!(is_sensitive("sync.api_token"));
+        assert!(!is_sensitive("preview.max_lines"));
+    }
+}
@@ -3,21 +3,28 @@
 //! Manages application configuration including database paths.
 //! Configuration is stored in the user's config directory.
 
+mod display;
 mod setup;
+mod validate;
 
+pub use display::ConfigEntry;
 pub use setup::first_time_setup;
+pub use validate::{CheckStatus, ConfigCheckResult, validate_file, validate_schema_file};
 
 use config::{Config, ConfigError, File, FileFormat};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::db::{DEFAULT_CACHE_MB, DbOptions};
 use crate::ui::PreviewPosition;
 
 /// Path display format
+///
+/// Written in the config file as `absolute`, `relative` or `last-N`.
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
+#[serde(into = "String", try_from = "String")]
 #[derive(Default)]
 pub enum PathFormat {
     /// Display absolute paths
@@ -25,6 +32,163 @@ pub enum PathFormat {
     Absolute,
     /// Display relative paths (relative to current directory)
     Relative,
+    /// Display only the last N path components, e.g. `components/Button.tsx`
+    LastN(usize),
+}
+
+impl std::fmt::Display for PathFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Absolute => f.write_str("absolute"),
+            Self::Relative => f.write_str("relative"),
+            Self::LastN(n) => write!(f, "last-{n}"),
+        }
+    }
+}
+
+impl std::str::FromStr for PathFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.to_lowercase();
+        match value.as_str() {
+            "absolute" | "abs" => Ok(Self::Absolute),
+            "relative" | "rel" => Ok(Self::Relative),
+            _ => value
+                .strip_prefix("last-")
+                .and_then(|n| n.parse::<usize>().ok())
+                .filter(|n| *n > 0)
+                .map(Self::LastN)
+                .ok_or_else(|| {
+                    format!("invalid path format '{value}': use 'absolute', 'relative' or 'last-N'")
+                }),
+        }
+    }
+}
+
+impl From<PathFormat> for String {
+    fn from(format: PathFormat) -> Self {
+        format.to_string()
+    }
+}
+
+impl TryFrom<String> for PathFormat {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// How tags entered by the user are cleaned up before they are stored
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum TagNormalization {
+    /// Store tags exactly as entered
+    Off,
+    /// Trim and join internal whitespace runs with `-`
+    #[default]
+    Trim,
+    /// Like `Trim`, and also lowercase
+    Slugify,
+}
+
+impl TagNormalization {
+    /// Normalize a single tag
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tagr::config::TagNormalization;
+    ///
+    /// assert_eq!(TagNormalization::Trim.apply(" Rust  Lang "), "Rust-Lang");
+    /// assert_eq!(TagNormalization::Slugify.apply(" Rust  Lang "), "rust-lang");
+    /// ```
+    #[must_use]
+    pub fn apply(self, tag: &str) -> String {
+        match self {
+            Self::Off => tag.to_string(),
+            Self::Trim => tag.split_whitespace().collect::<Vec<_>>().join("-"),
+            Self::Slugify => Self::Trim.apply(tag).to_lowercase(),
+        }
+    }
+
+    /// Normalize tags, dropping ones that become empty and duplicates
+    ///
+    /// This is the single entry point used by `tag`, `bulk tag` and the
+    /// browse add-tag action.
+    #[must_use]
+    pub fn apply_all(self, tags: &[String]) -> Vec<String> {
+        let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let tag = self.apply(tag);
+            if !tag.is_empty() && !normalized.contains(&tag) {
+                normalized.push(tag);
+            }
+        }
+        normalized
+    }
+}
+
+/// How symlinks are keyed when tagging
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SymlinkMode {
+    /// Store the link path, but treat the link as missing when its target is gone
+    Follow,
+    /// Store the resolved target path, so a link and its target share tags
+    #[default]
+    StoreTarget,
+    /// Store the link path; the link itself is the tagged file, even when broken
+    StoreLink,
+}
+
+impl SymlinkMode {
+    /// Normalize a user-supplied path into a database key
+    ///
+    /// The path is made absolute with its parent directories resolved. Only
+    /// `StoreTarget` resolves the final component when it is a symlink.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path does not exist. A broken symlink is an
+    /// error unless the mode is `StoreLink`.
+    pub fn normalize(self, path: &Path) -> std::io::Result<PathBuf> {
+        match self {
+            Self::StoreTarget => path.canonicalize(),
+            Self::Follow => {
+                path.canonicalize()?;
+                link_path(path)
+            }
+            Self::StoreLink => {
+                path.symlink_metadata()?;
+                link_path(path)
+            }
+        }
+    }
+
+    /// Whether a stored path still refers to something that can be tagged
+    ///
+    /// Used by cleanup: under `StoreLink` a broken symlink still exists.
+    #[must_use]
+    pub fn exists(self, path: &Path) -> bool {
+        match self {
+            Self::StoreLink => path.symlink_metadata().is_ok(),
+            Self::Follow | Self::StoreTarget => path.exists(),
+        }
+    }
+}
+
+/// Absolute path with the parent canonicalized and the final component kept
+fn link_path(path: &Path) -> std::io::Result<PathBuf> {
+    let Some(name) = path.file_name() else {
+        return path.canonicalize();
+    };
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.canonicalize()?,
+        _ => std::env::current_dir()?.canonicalize()?,
+    };
+    Ok(parent.join(name))
 }
 
 /// UI backend selection
@@ -64,6 +228,11 @@ pub struct PreviewConfig {
     #[serde(default = "default_max_file_size")]
     pub max_file_size: u64,
 
+    /// Maximum bytes read from a file for its preview; longer files are
+    /// truncated with a notice
+    #[serde(default = "default_max_preview_bytes")]
+    pub max_preview_bytes: u64,
+
     /// Maximum lines to display
     #[serde(default = "default_max_lines")]
     pub max_lines: usize,
@@ -90,6 +259,7 @@ impl Default for PreviewConfig {
         Self {
             enabled: default_preview_enabled(),
             max_file_size: default_max_file_size(),
+            max_preview_bytes: default_max_preview_bytes(),
             max_lines: default_max_lines(),
             syntax_highlighting: default_syntax_highlighting(),
             show_line_numbers: default_show_line_numbers(),
@@ -107,6 +277,10 @@ const fn default_max_file_size() -> u64 {
     5_242_880 // 5MB
 }
 
+const fn default_max_preview_bytes() -> u64 {
+    crate::preview::DEFAULT_MAX_PREVIEW_BYTES
+}
+
 const fn default_max_lines() -> usize {
     50
 }
@@ -128,6 +302,7 @@ impl From<&PreviewConfig> for crate::ui::PreviewConfig {
         Self {
             enabled: config.enabled,
             max_file_size: config.max_file_size,
+            max_preview_bytes: config.max_preview_bytes,
             max_lines: config.max_lines,
             syntax_highlighting: config.syntax_highlighting,
             show_line_numbers: config.show_line_numbers,
@@ -198,7 +373,7 @@ impl NotesConfig {
 }
 
 /// Application configuration structure
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TagrConfig {
     /// Map of database names to their filesystem paths
     #[serde(default)]
@@ -216,6 +391,10 @@ pub struct TagrConfig {
     #[serde(default)]
     pub path_format: PathFormat,
 
+    /// Most path components `last-N` paths grow to when telling files apart
+    #[serde(default = "default_path_context_max")]
+    pub path_context_max: usize,
+
     /// UI configuration
     #[serde(default)]
     pub ui: UiConfig,
@@ -227,6 +406,139 @@ pub struct TagrConfig {
     /// Notes configuration
     #[serde(default)]
     pub notes: NotesConfig,
+
+    /// Remember browse cursor position, selections and query between sessions
+    #[serde(default)]
+    pub persist_tui_state: bool,
+
+    /// Store tags as typed instead of resolving aliases from the tag schema
+    #[serde(default)]
+    pub no_canonicalize: bool,
+
+    /// How symlinks are keyed when tagging (follow, store-target, store-link)
+    #[serde(default)]
+    pub symlinks: SymlinkMode,
+
+    /// How entered tags are cleaned up before storage (off, trim, slugify)
+    #[serde(default)]
+    pub normalize_tags: TagNormalization,
+
+    /// How multiple `-t` tags combine when neither `--any-tag` nor `--all-tags` is given
+    ///
+    /// When unset, search and bulk commands use `all` and browse uses `any`.
+    #[serde(default)]
+    pub default_tag_mode: Option<crate::filters::TagMode>,
+
+    /// Directories whose files are hidden from search and browse results
+    ///
+    /// Glob patterns matched against each directory name in a file's parent
+    /// path. Bypassed with `--include-all-dirs`.
+    #[serde(default = "default_exclude_dirs")]
+    pub default_exclude_dirs: Vec<String>,
+
+    /// Draw images inline in the browse preview pane
+    ///
+    /// Requires a terminal supporting the Kitty or Sixel graphics protocol;
+    /// other terminals show the image metadata instead.
+    #[serde(default = "default_image_preview")]
+    pub image_preview: bool,
+
+    /// Most files loaded into the browse file pane at once (0 = no limit)
+    ///
+    /// Typing a query still searches every matching file.
+    #[serde(default = "default_browse_max_items")]
+    pub browse_max_items: usize,
+
+    /// Seconds `tagr search` reuses cached results for (0 = no caching)
+    ///
+    /// Any change to the database's files or tags invalidates the cache.
+    #[serde(default)]
+    pub search_cache_ttl: u64,
+
+    /// Maximum number of files a database may hold (unlimited if unset)
+    #[serde(default)]
+    pub max_files: Option<usize>,
+
+    /// Maximum number of tags a single file may carry (unlimited if unset)
+    #[serde(default)]
+    pub max_tags_per_file: Option<usize>,
+
+    /// Size of the database page cache in megabytes
+    #[serde(default = "default_db_cache_mb")]
+    pub db_cache_mb: usize,
+
+    /// Command run after tags are added to a file, e.g. `"mycmd {file} {tags}"`
+    ///
+    /// See [`crate::hooks`] for the placeholders.
+    #[serde(default)]
+    pub on_tag: Option<String>,
+}
+
+/// A configured database, as listed by `tagr db list`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DatabaseEntry {
+    pub name: String,
+    /// Serialized in the platform's native path form
+    pub path: PathBuf,
+    pub default: bool,
+}
+
+impl Default for TagrConfig {
+    fn default() -> Self {
+        Self {
+            databases: HashMap::new(),
+            default_database: None,
+            quiet: false,
+            path_format: PathFormat::default(),
+            path_context_max: default_path_context_max(),
+            ui: UiConfig::default(),
+            preview: PreviewConfig::default(),
+            notes: NotesConfig::default(),
+            persist_tui_state: false,
+            no_canonicalize: false,
+            symlinks: SymlinkMode::default(),
+            normalize_tags: TagNormalization::default(),
+            default_tag_mode: None,
+            default_exclude_dirs: default_exclude_dirs(),
+            image_preview: default_image_preview(),
+            browse_max_items: default_browse_max_items(),
+            search_cache_ttl: 0,
+            max_files: None,
+            max_tags_per_file: None,
+            db_cache_mb: default_db_cache_mb(),
+            on_tag: None,
+        }
+    }
+}
+
+const fn default_path_context_max() -> usize {
+    8
+}
+
+const fn default_browse_max_items() -> usize {
+    5000
+}
+
+const fn default_image_preview() -> bool {
+    true
+}
+
+const fn default_db_cache_mb() -> usize {
+    DEFAULT_CACHE_MB
+}
+
+fn default_exclude_dirs() -> Vec<String> {
+    [
+        ".git",
+        "target",
+        "node_modules",
+        ".tox",
+        "__pycache__",
+        "dist",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
 }
 
 impl TagrConfig {
@@ -258,8 +570,17 @@ impl TagrConfig {
             return Ok(default_config);
         }
 
+        Self::load_from(&config_path)
+    }
+
+    /// Load configuration from a specific file
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError` if the file cannot be read or parsed.
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
         let settings = Config::builder()
-            .add_source(File::from(config_path).format(FileFormat::Toml))
+            .add_source(File::from(path).format(FileFormat::Toml))
             .build()?;
 
         settings.try_deserialize()
@@ -310,6 +631,31 @@ impl TagrConfig {
         Ok(removed)
     }
 
+    /// Cache size and size limits to open databases with
+    #[must_use]
+    pub const fn db_options(&self) -> DbOptions {
+        DbOptions {
+            cache_mb: self.db_cache_mb,
+            max_files: self.max_files,
+            max_tags_per_file: self.max_tags_per_file,
+        }
+    }
+
+    /// Tag mode for commands whose own default is `fallback`
+    ///
+    /// `default_tag_mode` overrides the fallback when set; `--any-tag` and
+    /// `--all-tags` override both.
+    #[must_use]
+    pub fn tag_mode_or(&self, fallback: crate::cli::SearchMode) -> crate::cli::SearchMode {
+        self.default_tag_mode.map_or(fallback, Into::into)
+    }
+
+    /// Hook to run after tagging, if `on_tag` is set
+    #[must_use]
+    pub fn tag_hook(&self) -> Option<crate::hooks::TagHook> {
+        self.on_tag.as_deref().map(crate::hooks::TagHook::new)
+    }
+
     /// Get a database path by name
     #[must_use]
     pub fn get_database(&self, name: &str) -> Option<&PathBuf> {
@@ -322,6 +668,22 @@ impl TagrConfig {
         self.databases.keys().collect()
     }
 
+    /// Every configured database, sorted by name
+    #[must_use]
+    pub fn database_entries(&self) -> Vec<DatabaseEntry> {
+        let mut entries: Vec<DatabaseEntry> = self
+            .databases
+            .iter()
+            .map(|(name, path)| DatabaseEntry {
+                name: name.clone(),
+                path: path.clone(),
+                default: self.default_database.as_ref() == Some(name),
+            })
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries
+    }
+
     /// Set the default database
     ///
     /// # Errors
@@ -484,6 +846,27 @@ mod tests {
         assert!(db_list.contains(&&"gamma".to_string()));
     }
 
+    #[test]
+    fn test_database_entries_serialize_sorted_with_default() {
+        let mut config = TagrConfig::default();
+        config
+            .databases
+            .insert("work".to_string(), PathBuf::from("/tmp/work"));
+        config
+            .databases
+            .insert("home".to_string(), PathBuf::from("/tmp/home"));
+        config.default_database = Some("work".to_string());
+
+        let json = serde_json::to_value(config.database_entries()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                { "name": "home", "path": "/tmp/home", "default": false },
+                { "name": "work", "path": "/tmp/work", "default": true },
+            ])
+        );
+    }
+
     #[test]
     fn test_set_default_database() {
         let mut config = TagrConfig::default();
@@ -566,4 +949,100 @@ mod tests {
         assert_eq!(config.notes.storage, "integrated");
         assert_eq!(config.notes.max_note_size_kb, 100);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_mode_normalize() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().canonicalize().unwrap();
+        let target = dir.join("target.txt");
+        fs::write(&target, "content").unwrap();
+        let link = dir.join("link.txt");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        assert_eq!(SymlinkMode::StoreTarget.normalize(&link).unwrap(), target);
+        assert_eq!(SymlinkMode::Follow.normalize(&link).unwrap(), link);
+        assert_eq!(SymlinkMode::StoreLink.normalize(&link).unwrap(), link);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_mode_broken_link() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().canonicalize().unwrap();
+        let link = dir.join("dangling.txt");
+        std::os::unix::fs::symlink(dir.join("missing.txt"), &link).unwrap();
+
+        assert!(SymlinkMode::StoreTarget.normalize(&link).is_err());
+        assert!(SymlinkMode::Follow.normalize(&link).is_err());
+        assert_eq!(SymlinkMode::StoreLink.normalize(&link).unwrap(), link);
+
+        // Cleanup treats the broken link as missing unless links are stored as-is
+        assert!(!SymlinkMode::StoreTarget.exists(&link));
+        assert!(!SymlinkMode::Follow.exists(&link));
+        assert!(SymlinkMode::StoreLink.exists(&link));
+    }
+
+    #[test]
+    fn test_symlink_mode_config_values() {
+        let config: TagrConfig = toml::from_str("symlinks = \"store-link\"").unwrap();
+        assert_eq!(config.symlinks, SymlinkMode::StoreLink);
+        assert_eq!(TagrConfig::default().symlinks, SymlinkMode::StoreTarget);
+    }
+
+    #[test]
+    fn test_path_format_config_values() {
+        let config: TagrConfig = toml::from_str("path_format = \"last-2\"").unwrap();
+        assert_eq!(config.path_format, PathFormat::LastN(2));
+        assert!(
+            toml::to_string(&config)
+                .unwrap()
+                .contains("path_format = \"last-2\"")
+        );
+
+        let config: TagrConfig = toml::from_str("path_format = \"relative\"").unwrap();
+        assert_eq!(config.path_format, PathFormat::Relative);
+        assert_eq!("abs".parse(), Ok(PathFormat::Absolute));
+        assert!("last-0".parse::<PathFormat>().is_err());
+        assert!("sideways".parse::<PathFormat>().is_err());
+    }
+
+    #[test]
+    fn test_default_tag_mode_config_values() {
+        use crate::cli::SearchMode;
+
+        let config = TagrConfig::default();
+        assert_eq!(config.default_tag_mode, None);
+        assert_eq!(config.tag_mode_or(SearchMode::All), SearchMode::All);
+        assert_eq!(config.tag_mode_or(SearchMode::Any), SearchMode::Any);
+
+        let config: TagrConfig = toml::from_str("default_tag_mode = \"any\"").unwrap();
+        assert_eq!(config.tag_mode_or(SearchMode::All), SearchMode::Any);
+        let config: TagrConfig = toml::from_str("default_tag_mode = \"all\"").unwrap();
+        assert_eq!(config.tag_mode_or(SearchMode::Any), SearchMode::All);
+    }
+
+    #[test]
+    fn test_tag_normalization_modes() {
+        let tags = vec![
+            " Rust Lang ".to_string(),
+            "Rust\tLang".to_string(),
+            "   ".to_string(),
+            "rust-lang".to_string(),
+        ];
+
+        assert_eq!(
+            TagNormalization::Slugify.apply_all(&tags),
+            vec!["rust-lang"]
+        );
+        assert_eq!(
+            TagNormalization::Trim.apply_all(&tags),
+            vec!["Rust-Lang", "rust-lang"]
+        );
+        assert_eq!(TagNormalization::Off.apply_all(&tags), tags);
+
+        let config: TagrConfig = toml::from_str("normalize_tags = \"slugify\"").unwrap();
+        assert_eq!(config.normalize_tags, TagNormalization::Slugify);
+        assert_eq!(TagrConfig::default().normalize_tags, TagNormalization::Trim);
+    }
 }
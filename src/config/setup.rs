@@ -4,16 +4,117 @@
 //! configuration when tagr is run for the first time.
 
 use super::{PathFormat, TagrConfig};
+use crate::commands::completions;
+use clap_complete::Shell;
 use config::ConfigError;
-use dialoguer::{Input, theme::ColorfulTheme};
-use std::path::PathBuf;
+use dialoguer::{Confirm, Input, Select, theme::ColorfulTheme};
+use std::fs;
+use std::path::{Path, PathBuf};
 
-/// Interactive first-time setup - prompts for database name and location
+/// Commented example written to a new tag schema file
+const SCHEMA_TEMPLATE: &str = r#"# tagr tag schema
+#
+# Aliases map alternative names to a canonical tag. Tags are canonicalized
+# when tagging (unless disabled) and searches match every synonym.
+# Manage aliases with 'tagr alias add <alias> <canonical>'.
+#
+# [aliases]
+# js = "javascript"
+# py = "python"
+"#;
+
+/// Commented example written to a new saved filters file
+const FILTERS_TEMPLATE: &str = r#"# tagr saved filters
+#
+# Save a search with 'tagr search ... --save-filter <name>' and recall it
+# with 'tagr search -F <name>'.
+#
+# [[filter]]
+# name = "rust-src"
+# description = "All Rust source files"
+# created = "2025-01-01T00:00:00Z"
+# last_used = "2025-01-01T00:00:00Z"
+#
+# [filter.criteria]
+# tags = ["rust"]
+# file_patterns = ["*.rs"]
+"#;
+
+/// Starting point for where the first database is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DatabaseTemplate {
+    /// Files across the home directory; database in the user data directory
+    Personal,
+    /// A project tree; database next to it in the current directory
+    Work,
+    /// Removable or network drives; database on the mounted volume
+    Media,
+}
+
+impl DatabaseTemplate {
+    const ALL: [Self; 3] = [Self::Personal, Self::Work, Self::Media];
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Personal => "Personal (home directory)",
+            Self::Work => "Work (current directory)",
+            Self::Media => "Media (mounted paths)",
+        }
+    }
+
+    /// Suggested database location, falling back to the data directory
+    fn default_location(self, data_dir: &Path, db_name: &str) -> PathBuf {
+        let base = match self {
+            Self::Personal => None,
+            Self::Work => std::env::current_dir().ok().map(|dir| dir.join(".tagr")),
+            Self::Media => media_root().map(|dir| dir.join(".tagr")),
+        };
+        base.unwrap_or_else(|| data_dir.to_path_buf()).join(db_name)
+    }
+}
+
+/// First existing mount root for removable media
+fn media_root() -> Option<PathBuf> {
+    let user = std::env::var("USER").unwrap_or_default();
+    [
+        PathBuf::from("/run/media").join(&user),
+        PathBuf::from("/media").join(&user),
+        PathBuf::from("/Volumes"),
+        PathBuf::from("/mnt"),
+    ]
+    .into_iter()
+    .find(|dir| dir.is_dir())
+}
+
+/// Write `contents` to `path` unless the file already exists
+fn write_if_missing(path: &Path, contents: &str) -> std::io::Result<bool> {
+    if path.exists() {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(true)
+}
+
+fn input_error(e: dialoguer::Error) -> ConfigError {
+    ConfigError::Message(format!("Failed to read input: {e}"))
+}
+
+/// Interactive first-time setup - prompts for database and preferences
 ///
 /// Guides the user through creating their first database configuration:
-/// 1. Prompts for a database name (default: "default")
-/// 2. Prompts for database location (default: system data directory)
-/// 3. Creates and saves the configuration
+/// 1. Prompts for a database name (default: "main")
+/// 2. Offers a template (personal, work, media) that suggests a location
+/// 3. Prompts for the database location, prefilled from the template
+/// 4. Asks whether to resolve tag aliases when tagging
+/// 5. Asks whether to install shell completions
+///
+/// Nothing is written until every question is answered, so interrupting the
+/// wizard (Ctrl+C) leaves no partial configuration behind. On completion the
+/// config is saved, and commented example schema and filters files are
+/// created if they do not exist yet.
 ///
 /// # Errors
 ///
@@ -32,44 +133,142 @@ use std::path::PathBuf;
 pub fn first_time_setup() -> Result<TagrConfig, ConfigError> {
     println!("Welcome to tagr! Let's set up your first database.\n");
 
+    let theme = ColorfulTheme::default();
     let default_data_dir = dirs::data_local_dir()
         .ok_or_else(|| ConfigError::Message("Could not determine data directory".to_string()))?
         .join("tagr");
 
-    let db_name: String = Input::with_theme(&ColorfulTheme::default())
+    let db_name: String = Input::with_theme(&theme)
         .with_prompt("Database name")
-        .default("default".to_string())
+        .default("main".to_string())
         .interact_text()
-        .map_err(|e| ConfigError::Message(format!("Failed to read input: {e}")))?;
+        .map_err(input_error)?;
+
+    let labels: Vec<&str> = DatabaseTemplate::ALL.iter().map(|t| t.label()).collect();
+    let template = Select::with_theme(&theme)
+        .with_prompt("What will this database track?")
+        .items(&labels)
+        .default(0)
+        .interact()
+        .map_err(input_error)?;
+    let default_path =
+        DatabaseTemplate::ALL[template].default_location(&default_data_dir, &db_name);
 
-    let default_path = default_data_dir.join(&db_name);
-    let db_path_str: String = Input::with_theme(&ColorfulTheme::default())
+    let db_path_str: String = Input::with_theme(&theme)
         .with_prompt("Database location")
         .default(default_path.to_string_lossy().to_string())
         .interact_text()
-        .map_err(|e| ConfigError::Message(format!("Failed to read input: {e}")))?;
+        .map_err(input_error)?;
 
-    let db_path = PathBuf::from(db_path_str);
+    let canonicalize = Confirm::with_theme(&theme)
+        .with_prompt("Resolve tag aliases to canonical names when tagging (e.g. js -> javascript)?")
+        .default(true)
+        .interact()
+        .map_err(input_error)?;
+
+    let shell = Shell::from_env();
+    let install_completions = match shell {
+        Some(shell) if completions::install_path(shell).is_some() => Confirm::with_theme(&theme)
+            .with_prompt(format!("Install {shell} completions?"))
+            .default(true)
+            .interact()
+            .map_err(input_error)?,
+        _ => false,
+    };
 
     let mut config = TagrConfig::default();
-    config.databases.insert(db_name.clone(), db_path);
+    config
+        .databases
+        .insert(db_name.clone(), PathBuf::from(db_path_str));
     config.default_database = Some(db_name);
     config.quiet = false;
     config.path_format = PathFormat::Absolute;
+    config.no_canonicalize = !canonicalize;
 
     config.save()?;
-
     println!("\nConfiguration saved successfully!");
+
+    let config_dir = TagrConfig::config_path()?
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    for (file, contents) in [
+        ("tag_schema.toml", SCHEMA_TEMPLATE),
+        ("filters.toml", FILTERS_TEMPLATE),
+    ] {
+        let path = config_dir.join(file);
+        match write_if_missing(&path, contents) {
+            Ok(true) => println!("Created {}", path.display()),
+            Ok(false) => {}
+            Err(e) => eprintln!("Warning: Could not create {}: {e}", path.display()),
+        }
+    }
+
+    if install_completions && let Some(shell) = shell {
+        match completions::install(shell, false) {
+            Ok(path) => println!("Installed {shell} completions to {}", path.display()),
+            Err(e) => eprintln!("Warning: Could not install completions: {e}"),
+        }
+    }
+
     Ok(config)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::filters::FilterStorage;
+    use crate::schema::TagSchema;
+
+    fn uncomment(template: &str) -> String {
+        template
+            .lines()
+            .filter_map(|line| line.strip_prefix("# "))
+            .filter(|line| line.starts_with('[') || line.contains(" = "))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 
     #[test]
     fn test_setup_module_compiles() {
         // Ensures the module compiles and the function signature is correct
         let _: fn() -> Result<TagrConfig, ConfigError> = first_time_setup;
     }
+
+    #[test]
+    fn test_templates_parse_commented_and_uncommented() {
+        let schema: TagSchema = toml::from_str(SCHEMA_TEMPLATE).unwrap();
+        assert!(schema.aliases.is_empty());
+        let schema: TagSchema = toml::from_str(&uncomment(SCHEMA_TEMPLATE)).unwrap();
+        assert_eq!(
+            schema.aliases.get("js").map(String::as_str),
+            Some("javascript")
+        );
+
+        let filters: FilterStorage = toml::from_str(FILTERS_TEMPLATE).unwrap();
+        assert!(filters.filters.is_empty());
+        let filters: FilterStorage = toml::from_str(&uncomment(FILTERS_TEMPLATE)).unwrap();
+        assert_eq!(filters.filters[0].name, "rust-src");
+    }
+
+    #[test]
+    fn test_write_if_missing_keeps_existing() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("tagr").join("filters.toml");
+
+        assert!(write_if_missing(&path, "first").unwrap());
+        assert!(!write_if_missing(&path, "second").unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+    }
+
+    #[test]
+    fn test_template_default_locations() {
+        let data_dir = Path::new("/data/tagr");
+        assert_eq!(
+            DatabaseTemplate::Personal.default_location(data_dir, "main"),
+            data_dir.join("main")
+        );
+        let work = DatabaseTemplate::Work.default_location(data_dir, "main");
+        assert!(work.ends_with(".tagr/main"));
+    }
 }
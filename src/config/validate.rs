@@ -0,0 +1,284 @@
+//! Configuration file validation
+//!
+//! Checks a config file for problems that would otherwise only surface as
+//! cryptic errors on the next run (syntax errors, invalid enum values,
+//! dangling database references). Used by `tagr config validate`.
+
+use super::TagrConfig;
+use std::path::Path;
+
+/// Severity of a single validation check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// The field is valid
+    Ok,
+    /// The field is usable but likely not what the user intended
+    Warning,
+    /// The field is invalid and will cause failures
+    Error,
+}
+
+/// Outcome of validating one configuration field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigCheckResult {
+    /// Dotted name of the checked field (e.g., `databases.work`)
+    pub field: String,
+    /// Severity of the result
+    pub status: CheckStatus,
+    /// Human-readable explanation
+    pub message: String,
+}
+
+impl ConfigCheckResult {
+    fn new(field: impl Into<String>, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Validate the config file at `path`
+///
+/// A missing file is reported as a warning (defaults will be used). A file that
+/// cannot be parsed produces a single error result, since no further checks are
+/// possible without a parsed configuration.
+#[must_use]
+pub fn validate_file(path: &Path) -> Vec<ConfigCheckResult> {
+    if !path.exists() {
+        return vec![ConfigCheckResult::new(
+            "config",
+            CheckStatus::Warning,
+            format!("{} does not exist; defaults will be used", path.display()),
+        )];
+    }
+
+    match TagrConfig::load_from(path) {
+        Ok(config) => {
+            let mut results = vec![ConfigCheckResult::new(
+                "config",
+                CheckStatus::Ok,
+                format!("{} parsed successfully", path.display()),
+            )];
+            results.extend(config.validate());
+            results
+        }
+        Err(e) => vec![ConfigCheckResult::new(
+            "config",
+            CheckStatus::Error,
+            format!("Failed to parse {}: {e}", path.display()),
+        )],
+    }
+}
+
+impl TagrConfig {
+    /// Run semantic checks on an already parsed configuration
+    #[must_use]
+    pub fn validate(&self) -> Vec<ConfigCheckResult> {
+        let mut results = Vec::new();
+
+        if self.databases.is_empty() {
+            results.push(ConfigCheckResult::new(
+                "databases",
+                CheckStatus::Warning,
+                "No databases configured",
+            ));
+        }
+
+        let mut names: Vec<_> = self.databases.keys().collect();
+        names.sort();
+        for name in names {
+            let path = &self.databases[name];
+            let field = format!("databases.{name}");
+            let result = if path.is_dir() {
+                ConfigCheckResult::new(field, CheckStatus::Ok, path.display().to_string())
+            } else if path.exists() {
+                ConfigCheckResult::new(
+                    field,
+                    CheckStatus::Error,
+                    format!("{} exists but is not a directory", path.display()),
+                )
+            } else {
+                ConfigCheckResult::new(
+                    field,
+                    CheckStatus::Warning,
+                    format!(
+                        "{} does not exist (it will be created on first use)",
+                        path.display()
+                    ),
+                )
+            };
+            results.push(result);
+        }
+
+        results.push(match &self.default_database {
+            None => ConfigCheckResult::new(
+                "default_database",
+                CheckStatus::Warning,
+                "No default database set; --db must be passed to every command",
+            ),
+            Some(name) if !self.databases.contains_key(name) => ConfigCheckResult::new(
+                "default_database",
+                CheckStatus::Error,
+                format!("'{name}' is not listed under [databases]"),
+            ),
+            Some(name) => ConfigCheckResult::new("default_database", CheckStatus::Ok, name),
+        });
+
+        results.push(if self.preview.width_percent > 100 {
+            ConfigCheckResult::new(
+                "preview.width_percent",
+                CheckStatus::Error,
+                format!("{} is out of range (0-100)", self.preview.width_percent),
+            )
+        } else {
+            ConfigCheckResult::new(
+                "preview.width_percent",
+                CheckStatus::Ok,
+                self.preview.width_percent.to_string(),
+            )
+        });
+
+        if self.preview.max_lines == 0 {
+            results.push(ConfigCheckResult::new(
+                "preview.max_lines",
+                CheckStatus::Warning,
+                "0 lines means the preview pane will always be empty",
+            ));
+        }
+
+        if self.notes.max_note_size_kb == 0 {
+            results.push(ConfigCheckResult::new(
+                "notes.max_note_size_kb",
+                CheckStatus::Error,
+                "Must be greater than 0",
+            ));
+        }
+
+        results
+    }
+}
+
+/// Validate the tag schema file at `path`, if it exists
+#[must_use]
+pub fn validate_schema_file(path: &Path) -> Option<ConfigCheckResult> {
+    if !path.exists() {
+        return None;
+    }
+
+    Some(match crate::schema::TagSchema::load(path) {
+        Ok(_) => ConfigCheckResult::new("schema", CheckStatus::Ok, path.display().to_string()),
+        Err(e) => ConfigCheckResult::new(
+            "schema",
+            CheckStatus::Error,
+            format!("Failed to parse {}: {e}", path.display()),
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn status_of(results: &[ConfigCheckResult], field: &str) -> Option<CheckStatus> {
+        results.iter().find(|r| r.field == field).map(|r| r.status)
+    }
+
+    #[test]
+    fn test_validate_missing_file_is_warning() {
+        let temp_dir = TempDir::new().unwrap();
+        let results = validate_file(&temp_dir.path().join("missing.toml"));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, CheckStatus::Warning);
+    }
+
+    #[test]
+    fn test_validate_invalid_enum_value_is_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "path_format = \"sideways\"\n").unwrap();
+
+        let results = validate_file(&path);
+        assert_eq!(status_of(&results, "config"), Some(CheckStatus::Error));
+    }
+
+    #[test]
+    fn test_validate_valid_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_dir = temp_dir.path().join("db");
+        fs::create_dir(&db_dir).unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            format!(
+                "default_database = \"main\"\n[databases]\nmain = {:?}\n",
+                db_dir.display().to_string()
+            ),
+        )
+        .unwrap();
+
+        let results = validate_file(&path);
+        assert!(results.iter().all(|r| r.status == CheckStatus::Ok));
+    }
+
+    #[test]
+    fn test_validate_dangling_default_database() {
+        let config = TagrConfig {
+            default_database: Some("missing".to_string()),
+            ..Default::default()
+        };
+
+        let results = config.validate();
+        assert_eq!(
+            status_of(&results, "default_database"),
+            Some(CheckStatus::Error)
+        );
+    }
+
+    #[test]
+    fn test_validate_database_path_not_a_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("not_a_dir");
+        fs::write(&file, "").unwrap();
+
+        let mut config = TagrConfig::default();
+        config.databases.insert("a".to_string(), file);
+        config
+            .databases
+            .insert("b".to_string(), PathBuf::from("/nonexistent/tagr/db"));
+
+        let results = config.validate();
+        assert_eq!(status_of(&results, "databases.a"), Some(CheckStatus::Error));
+        assert_eq!(
+            status_of(&results, "databases.b"),
+            Some(CheckStatus::Warning)
+        );
+    }
+
+    #[test]
+    fn test_validate_preview_width_out_of_range() {
+        let mut config = TagrConfig::default();
+        config.preview.width_percent = 150;
+
+        let results = config.validate();
+        assert_eq!(
+            status_of(&results, "preview.width_percent"),
+            Some(CheckStatus::Error)
+        );
+    }
+
+    #[test]
+    fn test_validate_schema_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tag_schema.toml");
+        assert!(validate_schema_file(&path).is_none());
+
+        fs::write(&path, "not = [valid").unwrap();
+        let result = validate_schema_file(&path).unwrap();
+        assert_eq!(result.status, CheckStatus::Error);
+    }
+}
@@ -9,10 +9,15 @@
 //! - **`DecodeError`**: Failures when deserializing data from the database
 //! - **`EncodeError`**: Failures when serializing data to the database
 //! - **`SerializeError`**: Generic serialization errors (e.g., invalid UTF-8 in paths)
+//! - **`Locked`**: The database is held open by another process
+//! - **`Io`**: Filesystem errors while managing database directories
+//! - **`CorruptEntry`**: A stored entry that cannot be decoded, with its key
+//! - **`Sqlite`**: Errors reading or writing an SQLite export (`sqlite` feature)
 //!
 //! All errors implement `std::error::Error` via the `thiserror` crate and provide
 //! helpful error messages for debugging.
 
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Database-specific errors
@@ -42,9 +47,97 @@ pub enum DbError {
     #[error("Error while reading path{0}")]
     PathError(String),
 
+    /// No existing database at the given path
+    #[error("No database found at {0}")]
+    DatabaseNotFound(String),
+
+    /// The database is already open in another process
+    #[error(
+        "Database at {} is locked: another tagr process is using this database",
+        path.display()
+    )]
+    Locked {
+        /// Path of the locked database
+        path: PathBuf,
+    },
+
     /// Invalid input provided (e.g., invalid regex or glob pattern)
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+
+    /// Filesystem error while managing database directories
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A write would exceed a configured size limit
+    #[error(
+        "Database limit exceeded: {current} of {limit} allowed; run `tagr cleanup` to remove stale entries or raise the limit in the config"
+    )]
+    LimitExceeded {
+        /// The configured limit
+        limit: usize,
+        /// The count the write would have exceeded
+        current: usize,
+    },
+
+    /// A stored entry could not be decoded
+    #[error("Corrupt entry {key}: {source}")]
+    CorruptEntry {
+        /// The entry's file path, or its raw key bytes in hex if undecodable
+        key: String,
+        /// The decoding failure
+        #[source]
+        source: Box<DbError>,
+    },
+
+    /// Error reading or writing an SQLite export
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+impl DbError {
+    /// Convert to a [`TagrError`] annotated with the operation and file
+    #[must_use]
+    pub fn with_context(self, file: &Path, operation: &str) -> crate::TagrError {
+        crate::TagrError::from(self).with_context(file, operation)
+    }
+
+    /// Wrap a decoding failure for the `files` entry stored under `key`
+    pub(crate) fn corrupt_entry(key: &[u8], source: Self) -> Self {
+        let key = bincode::decode_from_slice::<PathBuf, _>(key, bincode::config::standard())
+            .map_or_else(
+                |_| {
+                    format!(
+                        "0x{}",
+                        key.iter().map(|b| format!("{b:02x}")).collect::<String>()
+                    )
+                },
+                |(path, _)| path.display().to_string(),
+            );
+        Self::CorruptEntry {
+            key,
+            source: Box::new(source),
+        }
+    }
+
+    /// Classify an error returned by `sled::open` for the database at `path`
+    ///
+    /// sled only allows one process to hold a database open and reports a
+    /// failed file lock as a generic I/O error, so it is detected by message.
+    pub(crate) fn from_open_error(err: sled::Error, path: &Path) -> Self {
+        match &err {
+            sled::Error::Io(io_err)
+                if io_err.kind() == std::io::ErrorKind::Other
+                    && io_err.to_string().contains("could not acquire lock") =>
+            {
+                Self::Locked {
+                    path: path.to_path_buf(),
+                }
+            }
+            _ => Self::SledError(err),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -20,6 +20,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_database_not_found_error() {
+        let error = DbError::DatabaseNotFound("/data/typo".to_string());
+        assert_eq!(error.to_string(), "No database found at /data/typo");
+    }
+
+    #[test]
+    fn test_locked_error() {
+        let error = DbError::Locked {
+            path: "/data/main".into(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Database at /data/main is locked: another tagr process is using this database"
+        );
+    }
+
     #[test]
     fn test_error_display() {
         let error = DbError::FileNotFound("/path/to/file.txt".to_string());
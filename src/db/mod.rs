@@ -6,35 +6,150 @@
 //! Uses multiple sled trees for efficient indexing:
 //! - `files`: Main tree mapping file paths to tags
 //! - `tags`: Reverse index mapping tags to file paths
+//!
+//! # Concurrency
+//!
+//! [`Database`] is `Send + Sync` and clones share the same underlying trees.
+//! Reads go straight to sled and never block. Each tag's entry in the reverse
+//! index is updated atomically with a compare-and-swap, but a write touches
+//! the `files` and `tags` trees in several steps, so every mutating method
+//! holds a write lock shared by all clones; concurrent writers from different
+//! threads are serialized and cannot corrupt the index. A reader running
+//! alongside a writer may observe the trees between steps.
+//!
+//! Across processes, sled takes an exclusive lock on the database directory:
+//! a second process cannot open it at all and gets [`DbError::Locked`].
 
 use crate::Pair;
+use crate::cli::SearchMode;
+use crate::hooks::TagHook;
 use bincode;
 use regex::Regex;
 use sled::{Db, Tree};
-use std::collections::HashSet;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::ops::Bound;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicUsize};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 pub mod error;
 pub mod query;
+mod read_only;
+#[cfg(feature = "sqlite")]
+mod sqlite;
 pub mod types;
 
 pub use error::DbError;
-pub use types::{NoteMeta, NoteRecord, PathKey, PathString};
+pub use read_only::ReadOnlyDatabase;
+#[cfg(feature = "sqlite")]
+pub use sqlite::{ImportConflict, ImportReport};
+pub use types::{NoteMeta, NoteRecord, PathKey, PathString, TagTimes};
+
+/// Settings applied when opening a [`Database`]
+///
+/// The limits are checked on every write that adds a file or changes its
+/// tags; `None` means unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DbOptions {
+    /// Size of sled's page cache in megabytes
+    pub cache_mb: usize,
+    /// Maximum number of files the database may hold
+    pub max_files: Option<usize>,
+    /// Maximum number of tags a single file may carry
+    pub max_tags_per_file: Option<usize>,
+}
+
+impl Default for DbOptions {
+    fn default() -> Self {
+        Self {
+            cache_mb: DEFAULT_CACHE_MB,
+            max_files: None,
+            max_tags_per_file: None,
+        }
+    }
+}
+
+/// Writes collected by [`Database::add_tags_many`] before they are applied
+#[derive(Default)]
+struct StagedAdds<'a> {
+    /// Each staged file's tags after the change
+    tags: HashMap<&'a Path, Vec<String>>,
+    /// How many staged files are not in the database yet
+    new_files: usize,
+    /// Files to add under each tag in the reverse index
+    index: BTreeMap<&'a str, BTreeSet<String>>,
+    files_batch: sled::Batch,
+    timestamps_batch: sled::Batch,
+}
+
+/// Writes staged by [`Database::remove_tags_many`]
+#[derive(Default)]
+struct StagedRemovals<'a> {
+    /// Each staged file's tags after the change, `None` once it is removed
+    tags: HashMap<&'a Path, Option<Vec<String>>>,
+    /// How many staged files are removed from the database
+    removed_files: usize,
+    /// Files to drop from each tag in the reverse index
+    index: BTreeMap<String, BTreeSet<String>>,
+    files_batch: sled::Batch,
+    timestamps_batch: sled::Batch,
+    last_checked_batch: sled::Batch,
+    notes_batch: sled::Batch,
+}
+
+/// A file with its tags, `None` when the file is not in the database
+pub type FileTags = (PathBuf, Option<Vec<String>>);
+
+/// Default size of sled's page cache in megabytes
+pub const DEFAULT_CACHE_MB: usize = 64;
+
+/// Key of the incremental cleanup cursor in the `cleanup_state` tree
+const CLEANUP_CURSOR_KEY: &[u8] = b"cursor";
+
+/// Key of the mutation counter in the `meta` tree
+const LAST_MUTATION_KEY: &[u8] = b"last_mutation";
+
+/// Key of the `tags` tree layout version in the `meta` tree
+const TAG_INDEX_VERSION_KEY: &[u8] = b"tag_index_version";
+
+/// `tags` tree layout: 2 stores each tag's files as a sorted `BTreeSet`
+const TAG_INDEX_VERSION: u8 = 2;
+
+/// [`Database::file_count`] before the `files` tree has been counted
+const UNCOUNTED: usize = usize::MAX;
 
 /// Database wrapper that encapsulates all database operations
 ///
 /// Uses multiple trees for efficient operations:
 /// - `files` tree: `file_path` -> `Vec<tag>`
-/// - `tags` tree: tag -> `Vec<file_path>` (reverse index)
+/// - `tags` tree: tag -> `BTreeSet<file_path>` (reverse index)
 /// - `notes` tree: `file_path` -> `NoteRecord`
+/// - `timestamps` tree: `file_path` -> `TagTimes`
+/// - `last_checked` tree: `file_path` -> unix timestamp of the last cleanup check
+/// - `cleanup_state` tree: where the last incremental cleanup stopped
+/// - `meta` tree: database-wide state, such as a counter of changes to files or tags
 ///
 /// Clone is cheap - both `Db` and `Tree` are reference-counted internally.
 #[derive(Debug, Clone)]
 pub struct Database {
     db: Db,
+    path: PathBuf,
     files: Tree,
     tags: Tree,
     notes: Tree,
+    timestamps: Tree,
+    last_checked: Tree,
+    cleanup_state: Tree,
+    meta: Tree,
+    /// Serializes writes so `files` and `tags` stay in sync
+    write_lock: Arc<Mutex<()>>,
+    /// Entries in `files`, counted when `max_files` is first checked and then
+    /// kept up to date by writes; [`UNCOUNTED`] until then
+    file_count: Arc<AtomicUsize>,
+    options: DbOptions,
+    /// Run after tags are added to a file
+    tag_hook: Option<TagHook>,
 }
 
 impl Database {
@@ -51,20 +166,151 @@ impl Database {
     ///
     /// # Errors
     ///
-    /// Returns `DbError` if the database cannot be opened or if the internal trees cannot be created.
+    /// Returns `DbError::Locked` if another process has the database open, or
+    /// `DbError` if the database cannot be opened or if the internal trees cannot be created.
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, DbError> {
-        let db = sled::open(path)?;
+        Self::open_with(path, DbOptions::default())
+    }
+
+    /// Opens or creates a database with a custom cache size and limits
+    ///
+    /// # Arguments
+    /// * `path` - Path to the database directory
+    /// * `options` - Cache size and size limits
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use tagr::db::{Database, DbOptions};
+    /// let options = DbOptions {
+    ///     max_files: Some(10_000),
+    ///     ..DbOptions::default()
+    /// };
+    /// let db = Database::open_with("my_db", options).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError::Locked` if another process has the database open, or
+    /// `DbError` if the database cannot be opened or if the internal trees cannot be created.
+    pub fn open_with<P: AsRef<Path>>(path: P, options: DbOptions) -> Result<Self, DbError> {
+        let db = Self::open_trees(path.as_ref(), options)?;
+        // Databases created before the counter existed get one on first open
+        if db.last_mutation()?.is_none() {
+            db.touch_last_mutation()?;
+        }
+        db.migrate_tag_index()?;
+        Ok(db)
+    }
+
+    /// Open the sled database and its trees without writing to them
+    fn open_trees(path: &Path, options: DbOptions) -> Result<Self, DbError> {
+        let db = sled::Config::new()
+            .path(path)
+            .cache_capacity(options.cache_mb as u64 * 1024 * 1024)
+            .open()
+            .map_err(|e| DbError::from_open_error(e, path))?;
         let files = db.open_tree("files")?;
         let tags = db.open_tree("tags")?;
         let notes = db.open_tree("notes")?;
+        let timestamps = db.open_tree("timestamps")?;
+        let last_checked = db.open_tree("last_checked")?;
+        let cleanup_state = db.open_tree("cleanup_state")?;
+        let meta = db.open_tree("meta")?;
         Ok(Self {
             db,
+            path: path.to_path_buf(),
             files,
             tags,
             notes,
+            timestamps,
+            last_checked,
+            cleanup_state,
+            meta,
+            write_lock: Arc::new(Mutex::new(())),
+            file_count: Arc::new(AtomicUsize::new(UNCOUNTED)),
+            options,
+            tag_hook: None,
         })
     }
 
+    /// Opens an existing database, failing instead of creating a new one
+    ///
+    /// Guards against typos in database paths silently producing a fresh, empty
+    /// database.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the database directory
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError::DatabaseNotFound` if `path` does not contain a sled database,
+    /// or `DbError` if the database cannot be opened.
+    pub fn open_existing<P: AsRef<Path>>(path: P) -> Result<Self, DbError> {
+        Self::open_existing_with(path, DbOptions::default())
+    }
+
+    /// Opens an existing database with a custom cache size and limits
+    ///
+    /// See [`Database::open_existing`] and [`Database::open_with`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError::DatabaseNotFound` if `path` does not contain a sled database,
+    /// or `DbError` if the database cannot be opened.
+    pub fn open_existing_with<P: AsRef<Path>>(
+        path: P,
+        options: DbOptions,
+    ) -> Result<Self, DbError> {
+        let path = path.as_ref();
+        ensure_exists(path)?;
+        Self::open_with(path, options)
+    }
+
+    /// Opens an existing database without writing to it, for [`ReadOnlyDatabase`]
+    fn open_existing_read_only(path: &Path, options: DbOptions) -> Result<Self, DbError> {
+        ensure_exists(path)?;
+        Self::open_trees(path, options)
+    }
+
+    /// Path of the database directory
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The cache size and limits this database was opened with
+    #[must_use]
+    pub const fn options(&self) -> DbOptions {
+        self.options
+    }
+
+    /// Run `hook` after every successful [`add_tags`](Self::add_tags)
+    #[must_use]
+    pub fn with_tag_hook(mut self, hook: Option<TagHook>) -> Self {
+        self.tag_hook = hook;
+        self
+    }
+
+    /// Opens an existing database through a handle that only exposes queries
+    ///
+    /// # Arguments
+    /// * `path` - Path to the database directory
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use tagr::db::Database;
+    /// let db = Database::open_read_only("my_db").unwrap();
+    /// let tags = db.list_all_tags().unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError::DatabaseNotFound` if `path` does not contain a sled database,
+    /// or `DbError` if the database cannot be opened.
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<ReadOnlyDatabase, DbError> {
+        ReadOnlyDatabase::open(path)
+    }
+
     /// Insert or update a file-tags pairing
     ///
     /// # Arguments
@@ -83,27 +329,179 @@ impl Database {
     /// # Errors
     ///
     /// Returns `DbError` if the file does not exist, the path contains invalid UTF-8,
-    /// database operations fail, or serialization errors occur.
+    /// database operations fail, or serialization errors occur. Returns
+    /// `DbError::LimitExceeded` if the write would exceed a configured
+    /// [`DbOptions`] limit.
     pub fn insert_pair(&self, pair: &Pair) -> Result<(), DbError> {
+        let _guard = self.write_guard();
+        self.insert_pair_unlocked(pair)
+    }
+
+    /// Acquire the write lock
+    ///
+    /// A panic while holding the lock leaves sled itself consistent, so a
+    /// poisoned lock is recovered rather than propagated.
+    fn write_guard(&self) -> MutexGuard<'_, ()> {
+        self.write_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn insert_pair_unlocked(&self, pair: &Pair) -> Result<(), DbError> {
         if !pair.file.exists() {
             return Err(DbError::FileNotFound(pair.file.display().to_string()));
         }
 
         let file_path = PathString::new(&pair.file)?;
 
-        if let Some(old_tags) = self.get_tags(&pair.file)? {
+        let old_tags = self.get_tags(&pair.file)?;
+        let is_new = old_tags.is_none();
+        self.check_limits(pair, is_new, 0)?;
+        if let Some(old_tags) = old_tags {
             self.remove_from_tag_index(&file_path, &old_tags)?;
         }
 
         let key = bincode::encode_to_vec(&pair.file, bincode::config::standard())?;
-        let value = bincode::encode_to_vec(&pair.tags, bincode::config::standard())?;
-        self.files.insert(key, value)?;
+        let value = types::encode_tags(&pair.tags)?;
+        self.files.insert(key.as_slice(), value)?;
+        if is_new {
+            self.adjust_file_count(1, 0);
+        }
 
         self.add_to_tag_index(&file_path, &pair.tags)?;
+        self.touch_timestamps(&key)?;
+        self.touch_last_mutation()?;
 
         Ok(())
     }
 
+    /// Record that the files or tags changed, invalidating cached search results
+    ///
+    /// Increments the mutation counter. A new counter starts from the
+    /// current time, so a database recreated at the same path does not
+    /// repeat the values of the one it replaced.
+    fn touch_last_mutation(&self) -> Result<(), DbError> {
+        self.meta.update_and_fetch(LAST_MUTATION_KEY, |old| {
+            let next = old.and_then(decode_counter).map_or_else(
+                || u64::try_from(chrono::Utc::now().timestamp_micros()).unwrap_or_default(),
+                |count| count.wrapping_add(1),
+            );
+            Some(next.to_be_bytes().to_vec())
+        })?;
+        Ok(())
+    }
+
+    /// Counter of changes to files or tags
+    ///
+    /// Incremented by every insert, removal and [`clear`](Self::clear), so
+    /// cached search results keyed on an older value are stale. `None` for a
+    /// database never opened writable since the counter was introduced.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if the counter cannot be read.
+    pub fn last_mutation(&self) -> Result<Option<u64>, DbError> {
+        Ok(self
+            .meta
+            .get(LAST_MUTATION_KEY)?
+            .and_then(|value| decode_counter(&value)))
+    }
+
+    /// Entries in the `files` tree
+    ///
+    /// Walks the tree on the first call only; writes keep the count current
+    /// afterwards. Call with the write lock held.
+    fn file_count(&self) -> usize {
+        let count = self.file_count.load(atomic::Ordering::Relaxed);
+        if count != UNCOUNTED {
+            return count;
+        }
+        let count = self.files.len();
+        self.file_count.store(count, atomic::Ordering::Relaxed);
+        count
+    }
+
+    /// Record that `added` entries were added to and `removed` entries
+    /// removed from `files`, if they have been counted
+    fn adjust_file_count(&self, added: usize, removed: usize) {
+        let _ = self.file_count.fetch_update(
+            atomic::Ordering::Relaxed,
+            atomic::Ordering::Relaxed,
+            |count| (count != UNCOUNTED).then(|| (count + added).saturating_sub(removed)),
+        );
+    }
+
+    /// Record a tag change for the file stored under `key`
+    fn touch_timestamps(&self, key: &[u8]) -> Result<(), DbError> {
+        let value = self.touched_timestamps(key)?;
+        self.timestamps.insert(key, value)?;
+        Ok(())
+    }
+
+    /// The encoded timestamps for `key` after a tag change made now
+    fn touched_timestamps(&self, key: &[u8]) -> Result<Vec<u8>, DbError> {
+        let now = chrono::Utc::now().timestamp();
+        let times = match self.timestamps.get(key)? {
+            Some(value) => {
+                let (times, _): (TagTimes, usize) =
+                    bincode::decode_from_slice(&value, bincode::config::standard())?;
+                TagTimes {
+                    last_tagged: now,
+                    ..times
+                }
+            }
+            None => TagTimes {
+                first_tagged: now,
+                last_tagged: now,
+            },
+        };
+        Ok(bincode::encode_to_vec(times, bincode::config::standard())?)
+    }
+
+    /// Get when a file was first and last tagged
+    ///
+    /// # Returns
+    /// `None` if the file is not in the database, or was tagged before
+    /// timestamps were recorded
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database operations fail.
+    pub fn get_timestamps<P: AsRef<Path>>(&self, file: P) -> Result<Option<TagTimes>, DbError> {
+        let key = bincode::encode_to_vec(file.as_ref(), bincode::config::standard())?;
+        match self.timestamps.get(key)? {
+            Some(value) => {
+                let (times, _): (TagTimes, usize) =
+                    bincode::decode_from_slice(&value, bincode::config::standard())?;
+                Ok(Some(times))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Reject a write that would exceed the configured size limits
+    ///
+    /// `pending` counts new files about to be written alongside `pair`
+    fn check_limits(&self, pair: &Pair, is_new: bool, pending: usize) -> Result<(), DbError> {
+        if let Some(limit) = self.options.max_tags_per_file
+            && pair.tags.len() > limit
+        {
+            return Err(DbError::LimitExceeded {
+                limit,
+                current: pair.tags.len(),
+            });
+        }
+        if let Some(limit) = self.options.max_files
+            && is_new
+        {
+            let current = self.file_count() + pending;
+            if current >= limit {
+                return Err(DbError::LimitExceeded { limit, current });
+            }
+        }
+        Ok(())
+    }
+
     /// Insert or update tags for a specific file
     ///
     /// # Arguments
@@ -140,14 +538,36 @@ impl Database {
 
         match self.files.get(key.as_slice())? {
             Some(value) => {
-                let (tags, _): (Vec<String>, usize) =
-                    bincode::decode_from_slice(&value, bincode::config::standard())?;
+                let tags =
+                    types::decode_tags(&value).map_err(|e| DbError::corrupt_entry(&key, e))?;
                 Ok(Some(tags))
             }
             None => Ok(None),
         }
     }
 
+    /// Get tags for several files at once
+    ///
+    /// # Arguments
+    /// * `files` - Paths to look up
+    ///
+    /// # Returns
+    /// One `(file, tags)` entry per input path, in input order; tags are `None`
+    /// for files not in the database, so results stay aligned with `files`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if a lookup fails or an entry cannot be decoded.
+    pub fn get_tags_many<P: AsRef<Path>>(&self, files: &[P]) -> Result<Vec<FileTags>, DbError> {
+        files
+            .iter()
+            .map(|file| {
+                let file = file.as_ref();
+                Ok((file.to_path_buf(), self.get_tags(file)?))
+            })
+            .collect()
+    }
+
     /// Get the complete Pair (file and tags) for a specific file
     ///
     /// # Arguments
@@ -163,8 +583,7 @@ impl Database {
             Some(value) => {
                 let (file_path, _): (PathBuf, usize) =
                     bincode::decode_from_slice(&key, bincode::config::standard())?;
-                let (tags, _): (Vec<String>, usize) =
-                    bincode::decode_from_slice(&value, bincode::config::standard())?;
+                let tags = types::decode_tags(&value)?;
                 Ok(Some(Pair::new(file_path, tags)))
             }
             None => Ok(None),
@@ -181,22 +600,41 @@ impl Database {
     /// Returns `DbError` if the path contains invalid UTF-8, database operations fail,
     /// or tag index cleanup fails.
     pub fn remove<P: AsRef<Path>>(&self, file: P) -> Result<bool, DbError> {
-        let file_path = PathString::new(file.as_ref())?;
+        let _guard = self.write_guard();
+        self.remove_unlocked(file.as_ref())
+    }
+
+    fn remove_unlocked(&self, file: &Path) -> Result<bool, DbError> {
+        let file_path = PathString::new(file)?;
 
-        let key: Vec<u8> = PathKey::new(file.as_ref()).try_into()?;
+        let key: Vec<u8> = PathKey::new(file).try_into()?;
 
-        if let Some(tags) = self.get_tags(file.as_ref())? {
+        if let Some(tags) = self.get_tags(file)? {
             self.remove_from_tag_index(&file_path, &tags)?;
         }
 
         // Also remove associated note if it exists
-        self.delete_note(file.as_ref())?;
-
-        Ok(self.files.remove(key.as_slice())?.is_some())
+        self.delete_note_unlocked(file)?;
+        self.timestamps.remove(key.as_slice())?;
+        self.last_checked.remove(key.as_slice())?;
+
+        let removed = self.files.remove(key.as_slice())?.is_some();
+        if removed {
+            self.adjust_file_count(0, 1);
+            self.touch_last_mutation()?;
+        }
+        Ok(removed)
     }
 
     /// Add tags to an existing file (merges with existing tags)
     ///
+    /// Tags the file does not have yet are appended in the given order, after
+    /// its existing ones.
+    ///
+    /// If a tag hook is set (see [`with_tag_hook`](Self::with_tag_hook)), it
+    /// runs afterwards with the tags that were appended, and not at all when
+    /// the file already had every tag.
+    ///
     /// # Arguments
     /// * `file` - Path to the file
     /// * `new_tags` - Tags to add
@@ -206,12 +644,255 @@ impl Database {
     /// Returns `DbError` if database operations fail or if insertion fails.
     pub fn add_tags<P: AsRef<Path>>(&self, file: P, new_tags: Vec<String>) -> Result<(), DbError> {
         let path = file.as_ref();
-        let existing = self.get_tags(path)?.unwrap_or_default();
+        let mut added = Vec::new();
+        {
+            let _guard = self.write_guard();
+            let mut tags = self.get_tags(path)?.unwrap_or_default();
+            for tag in new_tags {
+                if !tags.contains(&tag) {
+                    tags.push(tag.clone());
+                    added.push(tag);
+                }
+            }
+
+            self.insert_pair_unlocked(&Pair::new(path.to_path_buf(), tags))?;
+        }
+
+        // Run outside the write lock; a failing hook only warns
+        if let Some(hook) = &self.tag_hook
+            && !added.is_empty()
+        {
+            hook.run(path, &added);
+        }
+        Ok(())
+    }
+
+    /// Add tags to many files, writing them together
+    ///
+    /// Like calling [`add_tags`](Self::add_tags) for each `(file, tags)`
+    /// entry, but the write lock is taken once, the `files` and `timestamps`
+    /// trees are each written in a single sled batch, and every tag's index
+    /// entry is updated once. New tags are appended after a file's existing
+    /// ones. The tag hook runs afterwards for each file that gained tags,
+    /// with just those tags.
+    ///
+    /// # Returns
+    /// One result per entry, in input order. An entry fails on its own if
+    /// the file does not exist, its path is not UTF-8, or a size limit would
+    /// be exceeded; the other entries are still written.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if applying the staged writes fails.
+    pub fn add_tags_many(
+        &self,
+        changes: &[(PathBuf, Vec<String>)],
+    ) -> Result<Vec<Result<(), DbError>>, DbError> {
+        let mut results = Vec::with_capacity(changes.len());
+        {
+            let _guard = self.write_guard();
+            let mut staged = StagedAdds::default();
+            for (file, new_tags) in changes {
+                results.push(self.stage_add(file, new_tags, &mut staged));
+            }
+            let StagedAdds {
+                tags: pending,
+                new_files,
+                index,
+                files_batch,
+                timestamps_batch,
+            } = staged;
+
+            self.files.apply_batch(files_batch)?;
+            self.adjust_file_count(new_files, 0);
+            self.timestamps.apply_batch(timestamps_batch)?;
+            for (tag, files) in index {
+                self.update_tag_files(tag, |set| {
+                    let before = set.len();
+                    set.extend(files.iter().cloned());
+                    set.len() != before
+                })?;
+            }
+            if !pending.is_empty() {
+                self.touch_last_mutation()?;
+            }
+        }
+
+        if let Some(hook) = &self.tag_hook {
+            for ((file, _), result) in changes.iter().zip(&results) {
+                if let Ok(added) = result
+                    && !added.is_empty()
+                {
+                    hook.run(file, added);
+                }
+            }
+        }
+        Ok(results.into_iter().map(|result| result.map(drop)).collect())
+    }
 
-        let mut tag_set: HashSet<String> = existing.into_iter().collect();
-        tag_set.extend(new_tags);
+    /// Stage adding `new_tags` to `file` for [`add_tags_many`](Self::add_tags_many)
+    ///
+    /// Returns the tags the file did not have yet.
+    fn stage_add<'a>(
+        &self,
+        file: &'a Path,
+        new_tags: &'a [String],
+        staged: &mut StagedAdds<'a>,
+    ) -> Result<Vec<String>, DbError> {
+        if !file.exists() {
+            return Err(DbError::FileNotFound(file.display().to_string()));
+        }
+        let file_path = PathString::new(file)?;
+        let stored = self.get_tags(file)?;
+        // A file listed twice builds on its first entry
+        let is_new = stored.is_none() && !staged.tags.contains_key(file);
+        let mut tags = match staged.tags.get(file) {
+            Some(tags) => tags.clone(),
+            None => stored.unwrap_or_default(),
+        };
+        let mut added = Vec::new();
+        for tag in new_tags {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+                added.push(tag.clone());
+            }
+        }
+        let pair = Pair::new(file.to_path_buf(), tags);
+        self.check_limits(&pair, is_new, staged.new_files)?;
+
+        let key = bincode::encode_to_vec(file, bincode::config::standard())?;
+        staged
+            .files_batch
+            .insert(key.as_slice(), types::encode_tags(&pair.tags)?);
+        staged
+            .timestamps_batch
+            .insert(key.as_slice(), self.touched_timestamps(&key)?);
+        for tag in new_tags {
+            staged
+                .index
+                .entry(tag.as_str())
+                .or_default()
+                .insert(file_path.as_str().to_string());
+        }
+        staged.tags.insert(file, pair.tags);
+        staged.new_files += usize::from(is_new);
+        Ok(added)
+    }
 
-        self.insert(path, tag_set.into_iter().collect())
+    /// Remove tags from many files, writing them together
+    ///
+    /// With `Some(tags)`, like calling [`remove_tags`](Self::remove_tags) for
+    /// each file; with `None`, like calling [`remove`](Self::remove), dropping
+    /// the whole entry and its note. The write lock is taken once, each tree
+    /// is written in a single sled batch, and every tag's index entry is
+    /// updated once.
+    ///
+    /// # Returns
+    /// One result per file, in input order. A file fails on its own if its
+    /// path is not UTF-8, or it keeps an entry but no longer exists; the other
+    /// files are still written.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if applying the staged writes fails.
+    pub fn remove_tags_many(
+        &self,
+        files: &[PathBuf],
+        tags_to_remove: Option<&[String]>,
+    ) -> Result<Vec<Result<(), DbError>>, DbError> {
+        let _guard = self.write_guard();
+        let mut staged = StagedRemovals::default();
+        let results: Vec<_> = files
+            .iter()
+            .map(|file| self.stage_remove(file, tags_to_remove, &mut staged))
+            .collect();
+        let StagedRemovals {
+            tags: pending,
+            removed_files,
+            index,
+            files_batch,
+            timestamps_batch,
+            last_checked_batch,
+            notes_batch,
+        } = staged;
+
+        self.files.apply_batch(files_batch)?;
+        self.adjust_file_count(0, removed_files);
+        self.timestamps.apply_batch(timestamps_batch)?;
+        self.last_checked.apply_batch(last_checked_batch)?;
+        self.notes.apply_batch(notes_batch)?;
+        for (tag, removed) in index {
+            self.update_tag_files(&tag, |set| {
+                let before = set.len();
+                set.retain(|file| !removed.contains(file));
+                set.len() != before
+            })?;
+        }
+        if !pending.is_empty() {
+            self.touch_last_mutation()?;
+        }
+        Ok(results)
+    }
+
+    /// Stage removing tags from `file` for [`remove_tags_many`](Self::remove_tags_many)
+    fn stage_remove<'a>(
+        &self,
+        file: &'a Path,
+        tags_to_remove: Option<&[String]>,
+        staged: &mut StagedRemovals<'a>,
+    ) -> Result<(), DbError> {
+        let file_path = PathString::new(file)?;
+        let key = bincode::encode_to_vec(file, bincode::config::standard())?;
+        // A file listed twice builds on its first entry
+        let current = match staged.tags.get(file) {
+            Some(tags) => tags.clone(),
+            None => self.get_tags(file)?,
+        };
+
+        let tracked = current.is_some();
+        let (removed, kept) = match (tags_to_remove, current) {
+            (Some(_), None) => return Ok(()),
+            (None, current) => (current.unwrap_or_default(), None),
+            (Some(remove), Some(tags)) => {
+                let (removed, kept): (Vec<_>, Vec<_>) =
+                    tags.into_iter().partition(|tag| remove.contains(tag));
+                // A file with a note stays tracked with no tags
+                let keep = !kept.is_empty() || self.get_note(file)?.is_some();
+                (removed, keep.then_some(kept))
+            }
+        };
+
+        match &kept {
+            Some(tags) => {
+                if !file.exists() {
+                    return Err(DbError::FileNotFound(file.display().to_string()));
+                }
+                staged
+                    .files_batch
+                    .insert(key.as_slice(), types::encode_tags(tags)?);
+                staged
+                    .timestamps_batch
+                    .insert(key.as_slice(), self.touched_timestamps(&key)?);
+            }
+            None => {
+                staged.removed_files += usize::from(tracked);
+                staged.files_batch.remove(key.as_slice());
+                staged.timestamps_batch.remove(key.as_slice());
+                staged.last_checked_batch.remove(key.as_slice());
+                if tags_to_remove.is_none() {
+                    staged.notes_batch.remove(key.as_slice());
+                }
+            }
+        }
+        for tag in removed {
+            staged
+                .index
+                .entry(tag)
+                .or_default()
+                .insert(file_path.as_str().to_string());
+        }
+        staged.tags.insert(file, kept);
+        Ok(())
     }
 
     /// Remove specific tags from a file
@@ -232,6 +913,7 @@ impl Database {
         tags_to_remove: &[String],
     ) -> Result<(), DbError> {
         let path = file.as_ref();
+        let _guard = self.write_guard();
         if let Some(mut tags) = self.get_tags(path)? {
             tags.retain(|tag| !tags_to_remove.contains(tag));
 
@@ -240,18 +922,104 @@ impl Database {
                 let has_note = self.get_note(path)?.is_some();
                 if has_note {
                     // Keep file in database with empty tags (equality model)
-                    self.insert(path, tags)?;
+                    self.insert_pair_unlocked(&Pair::new(path.to_path_buf(), tags))?;
                 } else {
                     // No note - safe to remove completely
-                    self.remove(path)?;
+                    self.remove_unlocked(path)?;
                 }
             } else {
-                self.insert(path, tags)?;
+                self.insert_pair_unlocked(&Pair::new(path.to_path_buf(), tags))?;
             }
         }
         Ok(())
     }
 
+    /// Move a file's tags and note from `old` to `new`
+    ///
+    /// For files moved or renamed outside of tagr. If `new` is already in the
+    /// database, the tags of both paths are merged; use
+    /// [`Database::rename_file_replace`] to overwrite them instead. The note
+    /// moves along unless `new` already has one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError::FileNotFound` if `new` does not exist on the filesystem,
+    /// `DbError::InvalidInput` if `old` is not in the database, or `DbError` if
+    /// database operations fail.
+    pub fn rename_file(&self, old: &Path, new: &Path) -> Result<(), DbError> {
+        self.rename_file_with(old, new, false)
+    }
+
+    /// Like [`Database::rename_file`], but `new` ends up with exactly the tags
+    /// and note of `old`
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Database::rename_file`].
+    pub fn rename_file_replace(&self, old: &Path, new: &Path) -> Result<(), DbError> {
+        self.rename_file_with(old, new, true)
+    }
+
+    fn rename_file_with(&self, old: &Path, new: &Path, replace: bool) -> Result<(), DbError> {
+        if !new.exists() {
+            return Err(DbError::FileNotFound(new.display().to_string()));
+        }
+
+        let _guard = self.write_guard();
+        let Some(old_tags) = self.get_tags(old)? else {
+            return Err(DbError::InvalidInput(format!(
+                "{} is not in the database",
+                old.display()
+            )));
+        };
+        if old == new {
+            return Ok(());
+        }
+
+        let note = self.get_note(old)?;
+        let mut tags = if replace {
+            Vec::new()
+        } else {
+            self.get_tags(new)?.unwrap_or_default()
+        };
+        for tag in old_tags {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        let keep_note = !replace && self.get_note(new)?.is_some();
+        let old_times = self.get_timestamps(old)?;
+
+        // Check the merged tags before `old` is gone; the move itself never
+        // adds a file
+        let pair = Pair::new(new.to_path_buf(), tags);
+        self.check_limits(&pair, false, 0)?;
+
+        self.remove_unlocked(old)?;
+        if replace {
+            self.remove_unlocked(new)?;
+        }
+        self.insert_pair_unlocked(&pair)?;
+
+        // The file keeps the date it was first tagged under its old path
+        if let Some(old_times) = old_times
+            && let Some(mut times) = self.get_timestamps(new)?
+        {
+            times.first_tagged = times.first_tagged.min(old_times.first_tagged);
+            let key = bincode::encode_to_vec(new, bincode::config::standard())?;
+            let value = bincode::encode_to_vec(times, bincode::config::standard())?;
+            self.timestamps.insert(key, value)?;
+        }
+
+        if let Some(note) = note.filter(|_| !keep_note) {
+            let key = bincode::encode_to_vec(new, bincode::config::standard())?;
+            let value = bincode::encode_to_vec(&note, bincode::config::standard())?;
+            self.notes.insert(key, value)?;
+        }
+
+        Ok(())
+    }
+
     /// List all file-tag pairings in the database
     ///
     /// # Returns
@@ -261,16 +1029,124 @@ impl Database {
     ///
     /// Returns `DbError` if database iteration fails or deserialization errors occur.
     pub fn list_all(&self) -> Result<Vec<Pair>, DbError> {
+        self.iter_pairs().collect()
+    }
+
+    /// Iterate over all file-tag pairings, decoding each entry as it is read
+    ///
+    /// Unlike [`Database::list_all`], nothing is buffered, so callers can
+    /// start producing output before the whole tree has been read.
+    pub fn iter_pairs(&self) -> impl Iterator<Item = Result<Pair, DbError>> + use<> {
+        self.files.iter().map(|result| {
+            let (key, value) = result?;
+            decode_pair(&key, &value)
+        })
+    }
+
+    /// Iterate over the pairs stored after `cursor` in `files` tree order
+    ///
+    /// The order is that of the encoded keys, which is stable but is not
+    /// path order. With no cursor, iteration starts at the first entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if the cursor cannot be encoded.
+    pub fn iter_pairs_after(
+        &self,
+        cursor: Option<&Path>,
+    ) -> Result<impl Iterator<Item = Result<Pair, DbError>> + use<>, DbError> {
+        let start = match cursor {
+            Some(cursor) => Bound::Excluded(Vec::<u8>::try_from(PathKey::new(cursor))?),
+            None => Bound::Unbounded,
+        };
+        Ok(self.files.range((start, Bound::Unbounded)).map(|result| {
+            let (key, value) = result?;
+            decode_pair(&key, &value)
+        }))
+    }
+
+    /// Get the file where the last incremental cleanup stopped
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database operations fail.
+    pub fn cleanup_cursor(&self) -> Result<Option<PathBuf>, DbError> {
+        match self.cleanup_state.get(CLEANUP_CURSOR_KEY)? {
+            Some(value) => {
+                let (cursor, _): (PathBuf, usize) =
+                    bincode::decode_from_slice(&value, bincode::config::standard())?;
+                Ok(Some(cursor))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Set where the next incremental cleanup resumes; `None` starts over
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database operations fail.
+    pub fn set_cleanup_cursor(&self, cursor: Option<&Path>) -> Result<(), DbError> {
+        match cursor {
+            Some(cursor) => {
+                let value: Vec<u8> = PathKey::new(cursor).try_into()?;
+                self.cleanup_state.insert(CLEANUP_CURSOR_KEY, value)?;
+            }
+            None => {
+                self.cleanup_state.remove(CLEANUP_CURSOR_KEY)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get when cleanup last verified that a file exists (unix timestamp)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database operations fail.
+    pub fn last_checked<P: AsRef<Path>>(&self, file: P) -> Result<Option<i64>, DbError> {
+        let key: Vec<u8> = PathKey::new(file).try_into()?;
+        match self.last_checked.get(key)? {
+            Some(value) => {
+                let (at, _): (i64, usize) =
+                    bincode::decode_from_slice(&value, bincode::config::standard())?;
+                Ok(Some(at))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Record that cleanup verified a file exists at `at` (unix timestamp)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database operations fail.
+    pub fn set_last_checked<P: AsRef<Path>>(&self, file: P, at: i64) -> Result<(), DbError> {
+        let key: Vec<u8> = PathKey::new(file).try_into()?;
+        let value = bincode::encode_to_vec(at, bincode::config::standard())?;
+        self.last_checked.insert(key, value)?;
+        Ok(())
+    }
+
+    /// List all file-tags pairs, skipping entries that cannot be decoded
+    ///
+    /// # Returns
+    /// The decodable pairs, and a `DbError::CorruptEntry` for each skipped entry
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database iteration fails.
+    pub fn list_all_lenient(&self) -> Result<(Vec<Pair>, Vec<DbError>), DbError> {
         let mut pairs = Vec::new();
+        let mut corrupt = Vec::new();
         for result in &self.files {
             let (key, value) = result?;
-            let (file, _): (PathBuf, usize) =
-                bincode::decode_from_slice(&key, bincode::config::standard())?;
-            let (tags, _): (Vec<String>, usize) =
-                bincode::decode_from_slice(&value, bincode::config::standard())?;
-            pairs.push(Pair::new(file, tags));
+            match decode_pair(&key, &value) {
+                Ok(pair) => pairs.push(pair),
+                Err(e) => corrupt.push(e),
+            }
         }
-        Ok(pairs)
+        Ok((pairs, corrupt))
     }
 
     /// Find all files that have a specific tag (optimized with reverse index)
@@ -279,7 +1155,9 @@ impl Database {
     /// * `tag` - The tag to search for
     ///
     /// # Returns
-    /// Vector of file paths that contain the specified tag
+    /// Vector of file paths that contain the specified tag, in the order they
+    /// were added to the reverse index. Use [`Database::find_by_tag_sorted`]
+    /// when the order is visible to users.
     ///
     /// # Performance
     /// O(1) lookup using the reverse tag index instead of O(n) full scan
@@ -288,15 +1166,79 @@ impl Database {
     ///
     /// Returns `DbError` if database operations fail or deserialization errors occur.
     pub fn find_by_tag(&self, tag: &str) -> Result<Vec<PathBuf>, DbError> {
-        let key = tag.as_bytes();
+        Ok(self
+            .tag_file_names(tag)?
+            .into_iter()
+            .map(PathBuf::from)
+            .collect())
+    }
 
-        match self.tags.get(key)? {
-            Some(value) => {
-                let (files, _): (Vec<String>, usize) =
-                    bincode::decode_from_slice(&value, bincode::config::standard())?;
-                Ok(files.into_iter().map(PathBuf::from).collect())
-            }
-            None => Ok(Vec::new()),
+    /// Find all files that have a specific tag, sorted by path
+    ///
+    /// Paths are ordered with [`Path`]'s `Ord`, comparing components' raw
+    /// bytes rather than a lossy UTF-8 rendering. This is the same order
+    /// [`Database::find_by_all_tags`], [`Database::find_by_any_tag`] and
+    /// [`Database::find_by_tag_scan`] return.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database operations fail or deserialization errors occur.
+    pub fn find_by_tag_sorted(&self, tag: &str) -> Result<Vec<PathBuf>, DbError> {
+        let mut files = self.find_by_tag(tag)?;
+        files.sort();
+        Ok(files)
+    }
+
+    /// Find all files with a specific tag by scanning every entry
+    ///
+    /// Gives the same files as [`Database::find_by_tag`] without reading the
+    /// reverse index, so it stays correct if the index has drifted from the
+    /// `files` tree. Results are sorted.
+    ///
+    /// # Performance
+    /// O(n) full scan; meant for debugging and as a correctness oracle
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database operations fail or deserialization errors occur.
+    pub fn find_by_tag_scan(&self, tag: &str) -> Result<Vec<PathBuf>, DbError> {
+        let mut files: Vec<PathBuf> = self
+            .list_all()?
+            .into_iter()
+            .filter(|pair| pair.tags.iter().any(|t| t == tag))
+            .map(|pair| pair.file)
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    /// Number of files carrying a specific tag, read from the reverse index
+    ///
+    /// Same as [`Database::count_files_per_tag_fast`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database operations fail or deserialization errors occur.
+    pub fn tag_file_count(&self, tag: &str) -> Result<usize, DbError> {
+        self.count_files_per_tag_fast(tag)
+    }
+
+    /// Number of files carrying a specific tag, without decoding their paths
+    ///
+    /// An index entry is a bincode `BTreeSet<String>`, which starts with its
+    /// length as a varint `u64`. Only that prefix is decoded, so no path is allocated;
+    /// the tag tree calls this for every visible tag on each render.
+    ///
+    /// # Performance
+    /// O(1) in the number of files carrying the tag
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database operations fail or the length prefix cannot be decoded.
+    pub fn count_files_per_tag_fast(&self, tag: &str) -> Result<usize, DbError> {
+        match self.tags.get(tag.as_bytes())? {
+            Some(value) => decode_index_len(&value),
+            None => Ok(0),
         }
     }
 
@@ -306,38 +1248,43 @@ impl Database {
     /// * `tags` - The tags to search for (AND operation)
     ///
     /// # Returns
-    /// Vector of file paths that contain all specified tags
+    /// Vector of file paths that contain all specified tags, sorted by path
     ///
     /// # Performance
-    /// Uses reverse index to find intersection of file sets
+    /// Uses reverse index to find intersection of file sets. Tags are
+    /// intersected from the fewest files up, sized by their index length
+    /// prefix, and no further sets are read once the intersection is empty,
+    /// so a rare or unused tag makes the lookup cheap.
     ///
     /// # Errors
     ///
     /// Returns `DbError` if any tag lookup fails or database operations fail.
     pub fn find_by_all_tags(&self, tags: &[String]) -> Result<Vec<PathBuf>, DbError> {
-        if tags.is_empty() {
+        let mut by_size = tags
+            .iter()
+            .map(|tag| Ok((self.count_files_per_tag_fast(tag)?, tag)))
+            .collect::<Result<Vec<_>, DbError>>()?;
+        by_size.sort_by_key(|(count, _)| *count);
+
+        let Some(((smallest, first), rest)) = by_size.split_first() else {
+            return Ok(Vec::new());
+        };
+        if *smallest == 0 {
             return Ok(Vec::new());
         }
 
-        let mut file_sets: Vec<HashSet<String>> = tags
-            .iter()
-            .map(|tag| {
-                self.find_by_tag(tag).map(|files| {
-                    files
-                        .into_iter()
-                        .filter_map(|p| p.to_str().map(String::from))
-                        .collect()
-                })
-            })
-            .collect::<Result<_, _>>()?;
-
-        let first_set = file_sets.remove(0);
-        let result: HashSet<_> = first_set
-            .into_iter()
-            .filter(|file| file_sets.iter().all(|set| set.contains(file)))
-            .collect();
+        let mut result: HashSet<PathBuf> = self.find_by_tag(first)?.into_iter().collect();
+        for (_, tag) in rest {
+            if result.is_empty() {
+                return Ok(Vec::new());
+            }
+            let files: HashSet<PathBuf> = self.find_by_tag(tag)?.into_iter().collect();
+            result.retain(|file| files.contains(file));
+        }
 
-        Ok(result.into_iter().map(PathBuf::from).collect())
+        let mut result: Vec<_> = result.into_iter().collect();
+        result.sort();
+        Ok(result)
     }
 
     /// Find all files that have any of the specified tags (optimized)
@@ -346,7 +1293,8 @@ impl Database {
     /// * `tags` - The tags to search for (OR operation)
     ///
     /// # Returns
-    /// Vector of file paths that contain at least one of the specified tags
+    /// Vector of file paths that contain at least one of the specified tags,
+    /// sorted by path
     ///
     /// # Performance
     /// Uses reverse index to find union of file sets
@@ -358,15 +1306,100 @@ impl Database {
         let mut file_set = HashSet::new();
 
         for tag in tags {
-            let files = self.find_by_tag(tag)?;
-            for file in files {
-                if let Some(file_str) = file.to_str() {
-                    file_set.insert(file_str.to_string());
-                }
+            file_set.extend(self.find_by_tag(tag)?);
+        }
+
+        let mut files: Vec<_> = file_set.into_iter().collect();
+        files.sort();
+
+        Ok(files)
+    }
+
+    /// Count files matching tags without building the file list
+    ///
+    /// Gives the same number as searching with `mode` and `excludes` and
+    /// counting the results. Each tag's file list is decoded once, excluded
+    /// files are taken from the tag index rather than per-file lookups, and
+    /// `All` mode stops early when a tag has no files.
+    ///
+    /// # Arguments
+    /// * `tags` - Tags to match (exact names; expand aliases beforehand)
+    /// * `mode` - Whether files need any or all of `tags`
+    /// * `excludes` - Files with any of these tags are not counted
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if a tag lookup or deserialization fails.
+    pub fn file_count_for_tags(
+        &self,
+        tags: &[String],
+        mode: SearchMode,
+        excludes: &[String],
+    ) -> Result<usize, DbError> {
+        self.file_count_for_tags_where(tags, mode, excludes, |_| true)
+    }
+
+    /// Like [`Database::file_count_for_tags`], counting only files for which
+    /// `keep` returns true
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if a tag lookup or deserialization fails.
+    pub fn file_count_for_tags_where(
+        &self,
+        tags: &[String],
+        mode: SearchMode,
+        excludes: &[String],
+        keep: impl Fn(&Path) -> bool,
+    ) -> Result<usize, DbError> {
+        if tags.is_empty() {
+            return Ok(0);
+        }
+
+        let mut lists = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let files = self.tag_file_names(tag)?;
+            if files.is_empty() && mode == SearchMode::All {
+                return Ok(0);
             }
+            lists.push(files);
+        }
+
+        let mut excluded: HashSet<String> = HashSet::new();
+        for tag in excludes {
+            excluded.extend(self.tag_file_names(tag)?);
         }
+        let counts = |file: &String| !excluded.contains(file) && keep(Path::new(file));
+
+        let count = match mode {
+            SearchMode::Any => {
+                let unique: HashSet<&String> = lists.iter().flatten().collect();
+                unique.into_iter().filter(|file| counts(file)).count()
+            }
+            SearchMode::All => {
+                lists.sort_by_key(Vec::len);
+                let Some((smallest, rest)) = lists.split_first() else {
+                    return Ok(0);
+                };
+                let rest: Vec<HashSet<&String>> =
+                    rest.iter().map(|files| files.iter().collect()).collect();
+                smallest
+                    .iter()
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .filter(|file| rest.iter().all(|set| set.contains(file)) && counts(file))
+                    .count()
+            }
+        };
+        Ok(count)
+    }
 
-        Ok(file_set.into_iter().map(PathBuf::from).collect())
+    /// Decode a tag's file list as stored, without converting to paths
+    fn tag_file_names(&self, tag: &str) -> Result<Vec<String>, DbError> {
+        match self.tags.get(tag.as_bytes())? {
+            Some(value) => Ok(types::decode_file_set(&value)?.into_iter().collect()),
+            None => Ok(Vec::new()),
+        }
     }
 
     /// Get all unique tags in the database (optimized)
@@ -394,12 +1427,173 @@ impl Database {
         Ok(tag_vec)
     }
 
+    /// Get every tag with the number of files carrying it
+    ///
+    /// # Returns
+    /// `(tag, count)` pairs sorted by count (descending), then by tag
+    ///
+    /// # Performance
+    /// O(k) where k is number of unique tags, using the tags tree
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database iteration fails or an index entry cannot be decoded.
+    pub fn tag_counts(&self) -> Result<Vec<(String, usize)>, DbError> {
+        let mut counts = Vec::new();
+        for result in self.tags.iter() {
+            let (key, value) = result?;
+            let Ok(tag) = String::from_utf8(key.to_vec()) else {
+                continue;
+            };
+            counts.push((tag, decode_index_len(&value)?));
+        }
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(counts)
+    }
+
+    /// Find groups of tags that look like variants of each other
+    ///
+    /// Tags are compared case-insensitively with `-`, `_`, `.` and spaces
+    /// ignored, by normalized Levenshtein similarity (0.0 to 1.0). Two tags at
+    /// or above `threshold` share a group, and so do tags linked through a
+    /// chain of such pairs.
+    ///
+    /// # Returns
+    /// Groups of two or more `(tag, file_count)` entries, each sorted by count
+    /// (descending) then tag, so the most used tag comes first; groups are
+    /// ordered by their first tag
+    ///
+    /// # Performance
+    /// Compares every pair of tags: O(k²) where k is the number of unique tags
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database iteration fails or an index entry cannot be decoded.
+    pub fn detect_duplicate_tags(
+        &self,
+        threshold: f64,
+    ) -> Result<Vec<Vec<(String, usize)>>, DbError> {
+        let counts = self.tag_counts()?;
+        let keys: Vec<String> = counts
+            .iter()
+            .map(|(tag, _)| {
+                tag.chars()
+                    .filter(|c| !matches!(c, '-' | '_' | '.' | ' '))
+                    .flat_map(char::to_lowercase)
+                    .collect()
+            })
+            .collect();
+
+        // Union-find over tag indices
+        let mut parent: Vec<usize> = (0..counts.len()).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for i in 0..keys.len() {
+            for j in i + 1..keys.len() {
+                if strsim::normalized_levenshtein(&keys[i], &keys[j]) >= threshold {
+                    let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                    parent[a.max(b)] = a.min(b);
+                }
+            }
+        }
+
+        // `counts` is already most-used first, so each group keeps that order
+        let mut groups: Vec<Vec<(String, usize)>> = vec![Vec::new(); counts.len()];
+        for (i, entry) in counts.into_iter().enumerate() {
+            let group = root(&mut parent, i);
+            groups[group].push(entry);
+        }
+        groups.retain(|group| group.len() > 1);
+        groups.sort_by(|a, b| a[0].0.cmp(&b[0].0));
+        Ok(groups)
+    }
+
+    /// Get the `n` files with the most tags
+    ///
+    /// # Returns
+    /// Vector of (`file_path`, `tag_count`) tuples sorted by tag count (descending),
+    /// with ties broken by path (ascending)
+    ///
+    /// # Performance
+    /// Single pass over the `files` tree using a bounded heap, so memory stays O(n)
+    /// regardless of database size
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database iteration fails or deserialization errors occur.
+    pub fn most_tagged_files(&self, n: usize) -> Result<Vec<(PathBuf, usize)>, DbError> {
+        self.tag_count_extremes(n, true)
+    }
+
+    /// Get the `n` files with the fewest tags
+    ///
+    /// # Returns
+    /// Vector of (`file_path`, `tag_count`) tuples sorted by tag count (ascending),
+    /// with ties broken by path (ascending)
+    ///
+    /// # Performance
+    /// Single pass over the `files` tree using a bounded heap, so memory stays O(n)
+    /// regardless of database size
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if database iteration fails or deserialization errors occur.
+    pub fn least_tagged_files(&self, n: usize) -> Result<Vec<(PathBuf, usize)>, DbError> {
+        self.tag_count_extremes(n, false)
+    }
+
+    /// Select the `n` best-ranked files by tag count using a bounded min-heap
+    fn tag_count_extremes(&self, n: usize, most: bool) -> Result<Vec<(PathBuf, usize)>, DbError> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+
+        // The heap root is always the worst entry kept so far
+        let mut heap: BinaryHeap<Reverse<TagCountEntry>> = BinaryHeap::with_capacity(n + 1);
+        for result in &self.files {
+            let (key, value) = result?;
+            let (file, _): (PathBuf, usize) =
+                bincode::decode_from_slice(&key, bincode::config::standard())?;
+            let tags = types::decode_tags(&value)?;
+
+            heap.push(Reverse(TagCountEntry {
+                file,
+                count: tags.len(),
+                most,
+            }));
+            if heap.len() > n {
+                heap.pop();
+            }
+        }
+
+        // Ascending order of Reverse<_> is best-first
+        Ok(heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(entry)| (entry.file, entry.count))
+            .collect())
+    }
+
     /// Get the number of entries in the database
     #[must_use]
     pub fn count(&self) -> usize {
         self.files.len()
     }
 
+    /// Get the space the database currently occupies on disk, in bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if the database files cannot be inspected.
+    pub fn size_on_disk(&self) -> Result<u64, DbError> {
+        Ok(self.db.size_on_disk()?)
+    }
+
     /// Check if a file exists in the database
     ///
     /// # Errors
@@ -411,6 +1605,39 @@ impl Database {
         Ok(self.files.contains_key(key.as_slice())?)
     }
 
+    /// Watch every file entry for changes
+    ///
+    /// Shorthand for [`Self::watch_prefix`] with an empty prefix.
+    pub fn subscribe(&self) -> impl Iterator<Item = ChangeEvent> + use<> {
+        self.watch_prefix(&[])
+    }
+
+    /// Watch file entries whose raw key starts with `prefix`
+    ///
+    /// The iterator blocks until the next change and yields typed events with
+    /// the decoded path. Events cover writes made through any clone of this
+    /// database after the call; sled's directory lock keeps other processes
+    /// from opening the same database at the same time. Entries that cannot be
+    /// decoded are reported as [`ChangeEvent::Corrupt`] instead of skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tagr::db::{ChangeEvent, Database};
+    ///
+    /// let db = Database::open("my_db").unwrap();
+    /// for event in db.subscribe() {
+    ///     match event {
+    ///         ChangeEvent::Inserted { file, tags } => println!("{}: {tags:?}", file.display()),
+    ///         ChangeEvent::Removed { file } => println!("{} removed", file.display()),
+    ///         ChangeEvent::Corrupt(e) => eprintln!("{e}"),
+    ///     }
+    /// }
+    /// ```
+    pub fn watch_prefix(&self, prefix: &[u8]) -> impl Iterator<Item = ChangeEvent> + use<> {
+        self.files.watch_prefix(prefix).map(ChangeEvent::from)
+    }
+
     /// Flush all pending writes to disk
     ///
     /// This ensures data durability by forcing a write to disk
@@ -423,6 +1650,76 @@ impl Database {
         Ok(())
     }
 
+    /// Rewrite the database at `path` to reclaim space left behind by deletes
+    ///
+    /// sled has no in-place compaction, so the database is flushed, exported
+    /// into a fresh database in a sibling `<name>.compact-tmp` directory and
+    /// flushed again. The original is then moved to `<name>.compact-old`, the
+    /// rewrite renamed into place, and the old copy deleted. Each rename is
+    /// atomic; if the second fails the original is moved back.
+    ///
+    /// The database must not be open anywhere else, including in this process.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError::DatabaseNotFound` if no database exists at `path`,
+    /// `DbError::Locked` if it is in use, `DbError::InvalidInput` if a
+    /// `<name>.compact-old` directory is left over or the rewrite does not
+    /// contain every file entry, or `DbError` if reading, writing or renaming
+    /// fails.
+    pub fn compact<P: AsRef<Path>>(path: P) -> Result<CompactStats, DbError> {
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .ok_or_else(|| DbError::InvalidInput(format!("Invalid path: {}", path.display())))?
+            .to_string_lossy();
+        let tmp_path = path.with_file_name(format!("{name}.compact-tmp"));
+        let old_path = path.with_file_name(format!("{name}.compact-old"));
+        if old_path.exists() {
+            // Left by an interrupted compaction; it may be the only full copy
+            return Err(DbError::InvalidInput(format!(
+                "{} exists from an earlier compaction; inspect and remove it first",
+                old_path.display()
+            )));
+        }
+
+        let source = Self::open_existing(path)?;
+        source.flush()?;
+        let size_before = dir_size(path)?;
+
+        if tmp_path.exists() {
+            std::fs::remove_dir_all(&tmp_path)?;
+        }
+        {
+            let target = sled::open(&tmp_path)?;
+            target.import(source.db.export());
+            target.flush()?;
+
+            let copied = target.open_tree("files")?.len();
+            if copied != source.files.len() {
+                drop(target);
+                std::fs::remove_dir_all(&tmp_path)?;
+                return Err(DbError::InvalidInput(format!(
+                    "Compaction copied {copied} of {} files; database left unchanged",
+                    source.files.len()
+                )));
+            }
+        }
+        drop(source);
+
+        std::fs::rename(path, &old_path)?;
+        if let Err(e) = std::fs::rename(&tmp_path, path) {
+            std::fs::rename(&old_path, path)?;
+            return Err(e.into());
+        }
+        std::fs::remove_dir_all(&old_path)?;
+
+        Ok(CompactStats {
+            size_before,
+            size_after: dir_size(path)?,
+        })
+    }
+
     /// Remove a specific tag from all files in the database
     ///
     /// This method removes the tag from all files and then cleans up
@@ -463,8 +1760,14 @@ impl Database {
     ///
     /// Returns `DbError` if clearing either the files or tags tree fails.
     pub fn clear(&self) -> Result<(), DbError> {
+        let _guard = self.write_guard();
         self.files.clear()?;
+        self.file_count.store(0, atomic::Ordering::Relaxed);
         self.tags.clear()?;
+        self.timestamps.clear()?;
+        self.last_checked.clear()?;
+        self.cleanup_state.clear()?;
+        self.touch_last_mutation()?;
         Ok(())
     }
 
@@ -487,6 +1790,33 @@ impl Database {
         Ok(files)
     }
 
+    /// Find tagged files whose paths match glob patterns, regardless of tags
+    ///
+    /// # Arguments
+    /// * `patterns` - Glob patterns matched against the full path
+    /// * `match_all` - Require every pattern to match instead of any
+    ///
+    /// # Returns
+    /// Matching file paths, sorted
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if a glob pattern is invalid or database iteration fails.
+    pub fn find_by_file_glob(
+        &self,
+        patterns: &[String],
+        match_all: bool,
+    ) -> Result<Vec<PathBuf>, DbError> {
+        use crate::search::filter::PathFilterExt;
+
+        let mut files = self
+            .list_all_files()?
+            .into_iter()
+            .filter_patterns(patterns, false, match_all)?;
+        files.sort();
+        Ok(files)
+    }
+
     /// Find files matching a regex pattern for tags
     ///
     /// Searches for tags that match the regex pattern, then returns all files
@@ -571,23 +1901,9 @@ impl Database {
     /// Returns `DbError` if database operations fail or serialization errors occur.
     fn add_to_tag_index(&self, file_path: &str, tags: &[String]) -> Result<(), DbError> {
         for tag in tags {
-            let tag_key = tag.as_bytes();
-
-            let mut files: Vec<String> = match self.tags.get(tag_key)? {
-                Some(value) => {
-                    let (files, _): (Vec<String>, usize) =
-                        bincode::decode_from_slice(&value, bincode::config::standard())?;
-                    files
-                }
-                None => Vec::new(),
-            };
-
-            if !files.contains(&file_path.to_string()) {
-                files.push(file_path.to_string());
-            }
-
-            let encoded = bincode::encode_to_vec(&files, bincode::config::standard())?;
-            self.tags.insert(tag_key, encoded)?;
+            self.update_tag_files(tag, |files| {
+                !files.contains(file_path) && files.insert(file_path.to_string())
+            })?;
         }
         Ok(())
     }
@@ -606,22 +1922,79 @@ impl Database {
     /// Returns `DbError` if database operations fail or deserialization errors occur.
     fn remove_from_tag_index(&self, file_path: &str, tags: &[String]) -> Result<(), DbError> {
         for tag in tags {
-            let tag_key = tag.as_bytes();
-
-            if let Some(value) = self.tags.get(tag_key)? {
-                let (mut files, _): (Vec<String>, usize) =
-                    bincode::decode_from_slice(&value, bincode::config::standard())?;
-
-                files.retain(|f| f != file_path);
+            self.update_tag_files(tag, |files| files.remove(file_path))?;
+        }
+        Ok(())
+    }
 
-                if files.is_empty() {
-                    self.tags.remove(tag_key)?;
-                } else {
-                    let encoded = bincode::encode_to_vec(&files, bincode::config::standard())?;
-                    self.tags.insert(tag_key, encoded)?;
+    /// Atomically apply `update` to the set of files carrying `tag`
+    ///
+    /// Runs as a sled compare-and-swap, so concurrent updates to the same tag
+    /// are never lost; `update` may be called again if the entry changed
+    /// underneath it. It returns whether it changed the set, and unchanged
+    /// entries are not rewritten. An entry left empty is removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if the entry cannot be decoded or encoded, or the
+    /// update fails; the entry is left as it was.
+    fn update_tag_files(
+        &self,
+        tag: &str,
+        mut update: impl FnMut(&mut BTreeSet<String>) -> bool,
+    ) -> Result<(), DbError> {
+        let mut error = None;
+        self.tags.fetch_and_update(tag.as_bytes(), |old| {
+            error = None;
+            let mut files = match old.map(types::decode_file_set).transpose() {
+                Ok(files) => files.unwrap_or_default(),
+                Err(e) => {
+                    error = Some(e);
+                    return old.map(<[u8]>::to_vec);
                 }
+            };
+            if !update(&mut files) {
+                return old.map(<[u8]>::to_vec);
+            }
+            if files.is_empty() {
+                return None;
             }
+            types::encode_file_set(&files).map_or_else(
+                |e| {
+                    error = Some(e);
+                    old.map(<[u8]>::to_vec)
+                },
+                Some,
+            )
+        })?;
+        error.map_or(Ok(()), Err)
+    }
+
+    /// Rewrite `tags` tree entries stored as unsorted lists as sorted sets
+    ///
+    /// Runs once per database, on its first writable open; the layout
+    /// version is then recorded in the `meta` tree and later opens skip the
+    /// scan. A [`ReadOnlyDatabase`] never migrates and reads unmigrated
+    /// entries as they are.
+    fn migrate_tag_index(&self) -> Result<(), DbError> {
+        if self.meta.get(TAG_INDEX_VERSION_KEY)?.as_deref() == Some(&[TAG_INDEX_VERSION]) {
+            return Ok(());
+        }
+        let _guard = self.write_guard();
+        let mut batch = sled::Batch::default();
+        for result in self.tags.iter() {
+            let (key, value) = result?;
+            let files = types::decode_file_set(&value).map_err(|e| {
+                DbError::SerializeError(format!(
+                    "Cannot migrate index entry for tag '{}': {e}",
+                    String::from_utf8_lossy(&key)
+                ))
+            })?;
+            batch.insert(key, types::encode_file_set(&files)?);
         }
+        self.tags.apply_batch(batch)?;
+        self.meta
+            .insert(TAG_INDEX_VERSION_KEY, &[TAG_INDEX_VERSION])?;
         Ok(())
     }
 
@@ -647,6 +2020,7 @@ impl Database {
     /// Returns `DbError` if path contains invalid UTF-8 or serialization fails.
     pub fn set_note<P: AsRef<Path>>(&self, file: P, note: NoteRecord) -> Result<(), DbError> {
         let file_path = file.as_ref();
+        let _guard = self.write_guard();
         let key = bincode::encode_to_vec(file_path, bincode::config::standard())?;
         let value = bincode::encode_to_vec(&note, bincode::config::standard())?;
         self.notes.insert(key, value)?;
@@ -655,7 +2029,7 @@ impl Database {
         // This maintains the equality model: files with notes are "tracked" even without tags
         if self.get_tags(file_path)?.is_none() {
             // File not in database - add it with empty tags
-            self.insert(file_path, vec![])?;
+            self.insert_pair_unlocked(&Pair::new(file_path.to_path_buf(), vec![]))?;
         }
 
         Ok(())
@@ -701,19 +2075,27 @@ impl Database {
     ///
     /// Returns `DbError` if database operation fails.
     pub fn delete_note<P: AsRef<Path>>(&self, file: P) -> Result<bool, DbError> {
-        let file_path = file.as_ref();
+        let _guard = self.write_guard();
+        self.delete_note_unlocked(file.as_ref())
+    }
+
+    fn delete_note_unlocked(&self, file_path: &Path) -> Result<bool, DbError> {
         let key = bincode::encode_to_vec(file_path, bincode::config::standard())?;
         let was_deleted = self.notes.remove(key.clone())?.is_some();
 
         if was_deleted {
             // Check if file has any tags - if not, remove from files tree
             if let Some(tags_value) = self.files.get(key.clone())? {
-                let (tags, _): (Vec<String>, usize) =
-                    bincode::decode_from_slice(&tags_value, bincode::config::standard())?;
+                let tags = types::decode_tags(&tags_value)?;
 
                 if tags.is_empty() {
                     // No tags and no note - remove from files tree
-                    self.files.remove(key)?;
+                    self.timestamps.remove(key.as_slice())?;
+                    self.last_checked.remove(key.as_slice())?;
+                    if self.files.remove(key)?.is_some() {
+                        self.adjust_file_count(0, 1);
+                    }
+                    self.touch_last_mutation()?;
                 }
             }
         }
@@ -779,6 +2161,128 @@ impl Database {
     }
 }
 
+/// Heap entry for tag count ranking; "greater" means better ranked
+#[derive(PartialEq, Eq)]
+struct TagCountEntry {
+    file: PathBuf,
+    count: usize,
+    most: bool,
+}
+
+impl Ord for TagCountEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let by_count = if self.most {
+            self.count.cmp(&other.count)
+        } else {
+            other.count.cmp(&self.count)
+        };
+        // Smaller paths rank higher so ties resolve deterministically
+        by_count.then_with(|| other.file.cmp(&self.file))
+    }
+}
+
+impl PartialOrd for TagCountEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// On-disk size of a database before and after [`Database::compact`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactStats {
+    /// Size in bytes before compaction
+    pub size_before: u64,
+    /// Size in bytes after compaction
+    pub size_after: u64,
+}
+
+/// A change to the `files` tree, as seen by [`Database::watch_prefix`]
+#[derive(Debug)]
+pub enum ChangeEvent {
+    /// A file was added or its tags were replaced
+    Inserted {
+        /// The file whose entry changed
+        file: PathBuf,
+        /// The file's tags after the change
+        tags: Vec<String>,
+    },
+    /// A file was removed from the database
+    Removed {
+        /// The removed file
+        file: PathBuf,
+    },
+    /// The changed entry could not be decoded (`DbError::CorruptEntry`)
+    Corrupt(DbError),
+}
+
+impl From<sled::Event> for ChangeEvent {
+    fn from(event: sled::Event) -> Self {
+        match event {
+            sled::Event::Insert { key, value } => match decode_pair(&key, &value) {
+                Ok(pair) => Self::Inserted {
+                    file: pair.file,
+                    tags: pair.tags,
+                },
+                Err(e) => Self::Corrupt(e),
+            },
+            sled::Event::Remove { key } => match PathKey::from_bytes(&key) {
+                Ok(path) => Self::Removed {
+                    file: path.into_inner(),
+                },
+                Err(e) => Self::Corrupt(DbError::corrupt_entry(&key, e)),
+            },
+        }
+    }
+}
+
+/// Decode a `files` tree entry, reporting failures as `DbError::CorruptEntry`
+fn decode_pair(key: &[u8], value: &[u8]) -> Result<Pair, DbError> {
+    let decode = || -> Result<Pair, DbError> {
+        let (file, _): (PathBuf, usize) =
+            bincode::decode_from_slice(key, bincode::config::standard())?;
+        let tags = types::decode_tags(value)?;
+        Ok(Pair::new(file, tags))
+    };
+    decode().map_err(|e| DbError::corrupt_entry(key, e))
+}
+
+/// Fail with `DbError::DatabaseNotFound` unless `path` holds a sled database
+fn ensure_exists(path: &Path) -> Result<(), DbError> {
+    // sled writes its `conf` file when a database is first created
+    if path.join("conf").is_file() {
+        Ok(())
+    } else {
+        Err(DbError::DatabaseNotFound(path.display().to_string()))
+    }
+}
+
+/// The mutation counter stored in the `meta` tree
+fn decode_counter(value: &[u8]) -> Option<u64> {
+    <[u8; 8]>::try_from(value).ok().map(u64::from_be_bytes)
+}
+
+/// Number of files in a `tags` tree entry, read from its length prefix
+fn decode_index_len(value: &[u8]) -> Result<usize, DbError> {
+    let (len, _): (u64, usize) = bincode::decode_from_slice(value, bincode::config::standard())?;
+    usize::try_from(len)
+        .map_err(|_| DbError::SerializeError(format!("Tag index length {len} is too large")))
+}
+
+/// Total size of all files under `path`
+fn dir_size(path: &Path) -> std::io::Result<u64> {
+    let mut total = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        total += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(total)
+}
+
 impl Drop for Database {
     fn drop(&mut self) {
         // Best-effort flush on drop. Errors are ignored since we can't
@@ -788,6 +2292,10 @@ impl Drop for Database {
     }
 }
 
+#[cfg(test)]
+#[path = "tests/concurrent.rs"]
+mod concurrent_tests;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -805,21 +2313,82 @@ mod tests {
     }
 
     #[test]
-    fn test_create_database_with_data() {
-        let test_db = TestDb::new("test_db_with_data");
+    fn test_create_database_with_data() {
+        let test_db = TestDb::new("test_db_with_data");
+        let db = test_db.db();
+
+        let file1 = TempFile::create("file1.txt").unwrap();
+        let file2 = TempFile::create("file2.txt").unwrap();
+
+        db.insert(file1.path(), vec!["tag1".into(), "tag2".into()])
+            .unwrap();
+        db.insert(file2.path(), vec!["tag3".into()]).unwrap();
+
+        assert_eq!(db.count(), 2);
+        assert!(db.contains(file1.path()).unwrap());
+        assert!(db.contains(file2.path()).unwrap());
+        // TestDb and TempFiles automatically cleaned up
+    }
+
+    #[test]
+    fn test_get_tags_many_keeps_input_order() {
+        let test_db = TestDb::new("test_db_get_tags_many");
+        let db = test_db.db();
+
+        let file1 = TempFile::create("many1.txt").unwrap();
+        let file2 = TempFile::create("many2.txt").unwrap();
+        let missing = PathBuf::from("/nonexistent/many.txt");
+
+        db.insert(file1.path(), vec!["tag1".into()]).unwrap();
+        db.insert(file2.path(), vec!["tag2".into()]).unwrap();
+
+        let files = [
+            file2.path().to_path_buf(),
+            missing.clone(),
+            file1.path().to_path_buf(),
+        ];
+        let results = db.get_tags_many(&files).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                (file2.path().to_path_buf(), Some(vec!["tag2".to_string()])),
+                (missing, None),
+                (file1.path().to_path_buf(), Some(vec!["tag1".to_string()])),
+            ]
+        );
+        assert!(db.get_tags_many::<&Path>(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_detect_duplicate_tags() {
+        let test_db = TestDb::new("test_db_detect_duplicate_tags");
         let db = test_db.db();
 
-        let file1 = TempFile::create("file1.txt").unwrap();
-        let file2 = TempFile::create("file2.txt").unwrap();
+        let file1 = TempFile::create("dup1.js").unwrap();
+        let file2 = TempFile::create("dup2.js").unwrap();
+        let file3 = TempFile::create("dup3.js").unwrap();
 
-        db.insert(file1.path(), vec!["tag1".into(), "tag2".into()])
+        db.insert(file1.path(), vec!["javascript".into(), "rust".into()])
+            .unwrap();
+        db.insert(file2.path(), vec!["javascript".into(), "JavaScript".into()])
+            .unwrap();
+        db.insert(file3.path(), vec!["java-script".into(), "python".into()])
             .unwrap();
-        db.insert(file2.path(), vec!["tag3".into()]).unwrap();
 
-        assert_eq!(db.count(), 2);
-        assert!(db.contains(file1.path()).unwrap());
-        assert!(db.contains(file2.path()).unwrap());
-        // TestDb and TempFiles automatically cleaned up
+        let groups = db.detect_duplicate_tags(0.8).unwrap();
+        assert_eq!(
+            groups,
+            vec![vec![
+                ("javascript".to_string(), 2),
+                ("JavaScript".to_string(), 1),
+                ("java-script".to_string(), 1),
+            ]]
+        );
+
+        // At 1.0 only tags equal after folding case and separators group
+        let groups = db.detect_duplicate_tags(1.0).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].len(), 3);
     }
 
     #[test]
@@ -891,6 +2460,325 @@ mod tests {
         let _ = fs::remove_dir_all(test_db_path);
     }
 
+    #[test]
+    fn test_open_existing_nonexistent_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let missing = temp_dir.path().join("typo_db");
+
+        let result = Database::open_existing(&missing);
+        assert!(matches!(result, Err(DbError::DatabaseNotFound(_))));
+        assert!(!missing.exists());
+
+        // An empty directory is not a database either
+        fs::create_dir(&missing).unwrap();
+        assert!(matches!(
+            Database::open_existing(&missing),
+            Err(DbError::DatabaseNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_open_existing_after_create() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("existing_db");
+
+        {
+            let db = Database::open(&db_path).unwrap();
+            db.flush().unwrap();
+        }
+
+        let db = Database::open_existing(&db_path).unwrap();
+        assert_eq!(db.count(), 0);
+    }
+
+    #[test]
+    fn test_insert_respects_limits() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let options = DbOptions {
+            cache_mb: 8,
+            max_files: Some(2),
+            max_tags_per_file: Some(3),
+        };
+        let db = Database::open_with(temp_dir.path().join("limited_db"), options).unwrap();
+        assert_eq!(db.options(), options);
+        let files: Vec<TempFile> = (0..3)
+            .map(|i| TempFile::create(format!("limited{i}.txt")).unwrap())
+            .collect();
+
+        db.insert(files[0].path(), vec!["a".into()]).unwrap();
+        db.insert(files[1].path(), vec!["a".into()]).unwrap();
+        assert!(matches!(
+            db.insert(files[2].path(), vec!["a".into()]),
+            Err(DbError::LimitExceeded {
+                limit: 2,
+                current: 2
+            })
+        ));
+
+        // Updating an existing file is fine at the file limit
+        db.add_tags(files[0].path(), vec!["b".into(), "c".into()])
+            .unwrap();
+        let err = db.add_tags(files[0].path(), vec!["d".into()]).unwrap_err();
+        assert!(matches!(
+            err,
+            DbError::LimitExceeded {
+                limit: 3,
+                current: 4
+            }
+        ));
+        assert!(err.to_string().contains("tagr cleanup"));
+        assert_eq!(db.get_tags(files[0].path()).unwrap().unwrap().len(), 3);
+        assert_eq!(db.find_by_tag("a").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_file_limit_tracks_writes_without_recounting() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let options = DbOptions {
+            max_files: Some(2),
+            ..DbOptions::default()
+        };
+        let db = Database::open_with(temp_dir.path().join("counted_db"), options).unwrap();
+        let files: Vec<TempFile> = (0..3)
+            .map(|i| TempFile::create(format!("counted{i}.txt")).unwrap())
+            .collect();
+        let path = |i: usize| files[i].path().to_path_buf();
+        let at_limit = |result: Result<(), DbError>| {
+            matches!(result, Err(DbError::LimitExceeded { limit: 2, .. }))
+        };
+
+        db.insert(path(0), vec!["a".into()]).unwrap();
+        db.insert(path(1), vec!["a".into()]).unwrap();
+        assert!(at_limit(db.insert(path(2), vec!["a".into()])));
+
+        db.remove(path(0)).unwrap();
+        db.insert(path(2), vec!["a".into()]).unwrap();
+        assert!(at_limit(db.insert(path(0), vec!["a".into()])));
+
+        let removed = db.remove_tags_many(&[path(1)], Some(&["a".into()]));
+        assert!(removed.unwrap()[0].is_ok());
+        let added = db
+            .add_tags_many(&[(path(0), vec!["a".into()]), (path(1), vec!["a".into()])])
+            .unwrap();
+        assert!(added[0].is_ok());
+        assert!(at_limit(added.into_iter().nth(1).unwrap()));
+        assert_eq!(db.file_count(), db.files.len());
+
+        db.clear().unwrap();
+        db.insert(path(0), vec!["a".into()]).unwrap();
+        db.insert(path(1), vec!["a".into()]).unwrap();
+        assert_eq!(db.file_count(), 2);
+    }
+
+    #[test]
+    fn test_add_tags_appends_in_order() {
+        let test_db = TestDb::new("test_add_tags_appends_in_order");
+        let db = test_db.db();
+        let file = TempFile::create("ordered.txt").unwrap();
+
+        db.insert(file.path(), vec!["zeta".into(), "alpha".into()])
+            .unwrap();
+        db.add_tags(
+            file.path(),
+            vec!["rust".into(), "alpha".into(), "cli".into(), "rust".into()],
+        )
+        .unwrap();
+
+        assert_eq!(
+            db.get_tags(file.path()).unwrap().unwrap(),
+            ["zeta", "alpha", "rust", "cli"]
+        );
+    }
+
+    #[test]
+    fn test_add_tags_runs_hook_and_keeps_tags_on_failure() {
+        use crate::hooks::tests::MockExecutor;
+
+        let test_db = TestDb::new("test_add_tags_runs_hook");
+        let executor = Arc::new(MockExecutor {
+            fail: true,
+            ..MockExecutor::default()
+        });
+        let hook = TagHook::new("index {file} {tags}").with_executor(executor.clone());
+        let db = test_db.db().clone().with_tag_hook(Some(hook));
+        let file = TempFile::create("hooked.txt").unwrap();
+
+        db.add_tags(file.path(), vec!["rust".into()]).unwrap();
+
+        assert_eq!(
+            *executor.commands.lock().unwrap(),
+            [format!("index {} rust", file.path().display())]
+        );
+        assert_eq!(db.get_tags(file.path()).unwrap().unwrap(), ["rust"]);
+    }
+
+    #[test]
+    fn test_tag_hook_runs_with_appended_tags_only() {
+        use crate::hooks::tests::MockExecutor;
+
+        let test_db = TestDb::new("test_tag_hook_appended_only");
+        let executor = Arc::new(MockExecutor::default());
+        let hook = TagHook::new("index {file} {tags}").with_executor(executor.clone());
+        let db = test_db.db().clone().with_tag_hook(Some(hook));
+        let a = TempFile::create("hook_appended_a.txt").unwrap();
+        let b = TempFile::create("hook_appended_b.txt").unwrap();
+
+        db.add_tags(a.path(), vec!["rust".into()]).unwrap();
+        db.add_tags(a.path(), vec!["rust".into(), "cli".into()])
+            .unwrap();
+        db.add_tags(a.path(), vec!["cli".into()]).unwrap();
+        let results = db
+            .add_tags_many(&[
+                (a.path().to_path_buf(), vec!["rust".into()]),
+                (b.path().to_path_buf(), vec!["rust".into(), "cli".into()]),
+                (b.path().to_path_buf(), vec!["cli".into(), "web".into()]),
+            ])
+            .unwrap();
+        assert!(results.iter().all(Result::is_ok));
+
+        let (a, b) = (a.path().display(), b.path().display());
+        assert_eq!(
+            *executor.commands.lock().unwrap(),
+            [
+                format!("index {a} rust"),
+                format!("index {a} cli"),
+                format!("index {b} rust cli"),
+                format!("index {b} web"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_open_locked_database() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("locked_db");
+
+        let _first = Database::open(&db_path).unwrap();
+        match Database::open(&db_path) {
+            Err(DbError::Locked { path }) => assert_eq!(path, db_path),
+            Err(e) => panic!("Expected Locked error, got {e:?}"),
+            Ok(_) => panic!("Expected Locked error, database opened twice"),
+        }
+    }
+
+    #[test]
+    fn test_compact_preserves_pairs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("compact_db");
+        let files: Vec<TempFile> = (0..20)
+            .map(|i| TempFile::create(format!("file{i}.txt")).unwrap())
+            .collect();
+
+        let mut expected = {
+            let db = Database::open(&db_path).unwrap();
+            for (i, file) in files.iter().enumerate() {
+                db.insert(file.path(), vec!["common".into(), format!("tag{i}")])
+                    .unwrap();
+            }
+            for file in &files[10..] {
+                db.remove(file.path()).unwrap();
+            }
+            db.set_note(files[0].path(), NoteRecord::new("kept".into()))
+                .unwrap();
+            db.list_all().unwrap()
+        };
+
+        let stats = Database::compact(&db_path).unwrap();
+        assert!(stats.size_after > 0);
+        assert!(!temp_dir.path().join("compact_db.compact-tmp").exists());
+        assert!(!temp_dir.path().join("compact_db.compact-old").exists());
+
+        let db = Database::open_existing(&db_path).unwrap();
+        let mut pairs = db.list_all().unwrap();
+        pairs.sort_by(|a, b| a.file.cmp(&b.file));
+        expected.sort_by(|a, b| a.file.cmp(&b.file));
+        assert_eq!(pairs, expected);
+        assert_eq!(db.find_by_tag("common").unwrap().len(), 10);
+        assert_eq!(
+            db.get_note(files[0].path()).unwrap().unwrap().content,
+            "kept"
+        );
+    }
+
+    #[test]
+    fn test_list_all_lenient_skips_corrupt_entry() {
+        let test_db = TestDb::new("test_list_all_lenient");
+        let db = test_db.db();
+        let good = TempFile::create("lenient_good.txt").unwrap();
+        db.insert(good.path(), vec!["ok".into()]).unwrap();
+
+        let bad_path = PathBuf::from("/tmp/lenient_bad.txt");
+        let key: Vec<u8> = PathKey::new(&bad_path).try_into().unwrap();
+        db.files
+            .insert(key, vec![types::TAGS_VALUE_MARKER, 99])
+            .unwrap();
+
+        match db.list_all() {
+            Err(DbError::CorruptEntry { key, .. }) => {
+                assert_eq!(key, bad_path.display().to_string());
+            }
+            other => panic!("Expected CorruptEntry, got {other:?}"),
+        }
+        assert!(matches!(
+            db.get_tags(&bad_path),
+            Err(DbError::CorruptEntry { .. })
+        ));
+
+        let (pairs, corrupt) = db.list_all_lenient().unwrap();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].file, good.path());
+        assert_eq!(corrupt.len(), 1);
+        assert!(corrupt[0].to_string().contains("lenient_bad.txt"));
+    }
+
+    #[test]
+    fn test_subscribe_yields_typed_events() {
+        let test_db = TestDb::new("test_subscribe_events");
+        let db = test_db.db();
+        let file = TempFile::create("subscribe_file.txt").unwrap();
+        let mut events = db.subscribe();
+
+        db.insert(file.path(), vec!["watched".into()]).unwrap();
+        db.remove(file.path()).unwrap();
+        db.files.insert(vec![0xFF, 0xFF], vec![0]).unwrap();
+
+        match events.next() {
+            Some(ChangeEvent::Inserted { file: path, tags }) => {
+                assert_eq!(path, file.path());
+                assert_eq!(tags, vec!["watched"]);
+            }
+            other => panic!("Expected Inserted, got {other:?}"),
+        }
+        match events.next() {
+            Some(ChangeEvent::Removed { file: path }) => assert_eq!(path, file.path()),
+            other => panic!("Expected Removed, got {other:?}"),
+        }
+        match events.next() {
+            Some(ChangeEvent::Corrupt(DbError::CorruptEntry { key, .. })) => {
+                assert_eq!(key, "0xffff");
+            }
+            other => panic!("Expected Corrupt, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_corrupt_entry_undecodable_key_is_hex() {
+        let err = DbError::corrupt_entry(&[0xFF, 0xFF], DbError::SerializeError("bad".into()));
+        assert!(err.to_string().starts_with("Corrupt entry 0xffff"));
+    }
+
+    #[test]
+    fn test_compact_locked_database() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("compact_locked_db");
+
+        let _open = Database::open(&db_path).unwrap();
+        assert!(matches!(
+            Database::compact(&db_path),
+            Err(DbError::Locked { .. })
+        ));
+    }
+
     #[test]
     fn test_create_multiple_databases() {
         let db_paths = ["test_db_multi_1", "test_db_multi_2", "test_db_multi_3"];
@@ -986,6 +2874,65 @@ mod tests {
         assert_eq!(files.len(), 1);
     }
 
+    #[test]
+    fn test_most_and_least_tagged_files() {
+        let test_db = TestDb::new("test_most_least_tagged");
+        let db = test_db.db();
+
+        let a = TempFile::create("a.txt").unwrap();
+        let b = TempFile::create("b.txt").unwrap();
+        let c = TempFile::create("c.txt").unwrap();
+
+        db.insert(a.path(), vec!["t1".into()]).unwrap();
+        db.insert(b.path(), vec!["t1".into(), "t2".into(), "t3".into()])
+            .unwrap();
+        db.insert(c.path(), vec!["t1".into(), "t2".into()]).unwrap();
+
+        let most = db.most_tagged_files(2).unwrap();
+        assert_eq!(
+            most,
+            vec![(b.path().to_path_buf(), 3), (c.path().to_path_buf(), 2)]
+        );
+
+        let least = db.least_tagged_files(1).unwrap();
+        assert_eq!(least, vec![(a.path().to_path_buf(), 1)]);
+
+        assert!(db.most_tagged_files(0).unwrap().is_empty());
+        assert_eq!(db.least_tagged_files(10).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_tag_count_ties_broken_by_path() {
+        let test_db = TestDb::new("test_tag_count_ties");
+        let db = test_db.db();
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut paths = Vec::new();
+        for name in ["c.txt", "a.txt", "b.txt"] {
+            let path = dir.path().join(name);
+            fs::write(&path, "x").unwrap();
+            db.insert(&path, vec!["same".into()]).unwrap();
+            paths.push(path);
+        }
+        paths.sort();
+
+        let most: Vec<_> = db
+            .most_tagged_files(2)
+            .unwrap()
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(most, paths[..2]);
+
+        let least: Vec<_> = db
+            .least_tagged_files(2)
+            .unwrap()
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect();
+        assert_eq!(least, paths[..2]);
+    }
+
     // ==================== Note Tests ====================
 
     #[test]
@@ -1194,4 +3141,459 @@ mod tests {
         assert!(note.metadata.updated_at >= original_updated);
         // Note: >= instead of > because system time might not advance on all platforms
     }
+
+    #[test]
+    fn test_find_by_file_glob() {
+        let test_db = TestDb::new("test_find_by_file_glob");
+        let db = test_db.db();
+
+        let lib = TempFile::create("glob_lib.rs").unwrap();
+        let test = TempFile::create("glob_test.rs").unwrap();
+        let readme = TempFile::create("glob_readme.md").unwrap();
+        db.insert(lib.path(), vec!["a".into()]).unwrap();
+        db.insert(test.path(), vec!["b".into()]).unwrap();
+        db.insert(readme.path(), vec!["c".into()]).unwrap();
+
+        let mut rust = vec![lib.path().to_path_buf(), test.path().to_path_buf()];
+        rust.sort();
+        assert_eq!(db.find_by_file_glob(&["*.rs".into()], false).unwrap(), rust);
+
+        let any = db
+            .find_by_file_glob(&["*_test.rs".into(), "*.md".into()], false)
+            .unwrap();
+        assert_eq!(any.len(), 2);
+        assert!(!any.contains(&lib.path().to_path_buf()));
+
+        let all = db
+            .find_by_file_glob(&["*.rs".into(), "*_test*".into()], true)
+            .unwrap();
+        assert_eq!(all, vec![test.path().to_path_buf()]);
+    }
+
+    #[test]
+    fn test_rename_file_moves_tags_and_note() {
+        let test_db = TestDb::new("test_rename_file");
+        let db = test_db.db();
+
+        let old = TempFile::create("old.txt").unwrap();
+        let new = TempFile::create("new.txt").unwrap();
+        db.insert(old.path(), vec!["rust".into(), "notes".into()])
+            .unwrap();
+        db.set_note(old.path(), NoteRecord::new("moved".into()))
+            .unwrap();
+
+        db.rename_file(old.path(), new.path()).unwrap();
+
+        assert!(!db.contains(old.path()).unwrap());
+        assert!(db.get_note(old.path()).unwrap().is_none());
+        assert_eq!(
+            db.get_tags(new.path()).unwrap(),
+            Some(vec!["rust".to_string(), "notes".to_string()])
+        );
+        assert_eq!(db.get_note(new.path()).unwrap().unwrap().content, "moved");
+        assert_eq!(
+            db.find_by_tag("rust").unwrap(),
+            vec![new.path().to_path_buf()]
+        );
+    }
+
+    #[test]
+    fn test_migrate_tag_index_to_sorted_sets() {
+        let test_db = TestDb::new("test_migrate_tag_index_to_sorted_sets");
+        let db = test_db.db();
+        let a = TempFile::create("migrate_a.txt").unwrap();
+        let b = TempFile::create("migrate_b.txt").unwrap();
+        let (a_path, b_path) = (
+            a.path().display().to_string(),
+            b.path().display().to_string(),
+        );
+        db.insert(a.path(), vec!["rust".into()]).unwrap();
+        db.insert(b.path(), vec!["rust".into()]).unwrap();
+        // Write the entry as an older version would: unsorted, unversioned
+        let legacy = vec![b_path.clone(), a_path.clone(), b_path.clone()];
+        db.tags
+            .insert(
+                "rust",
+                bincode::encode_to_vec(&legacy, bincode::config::standard()).unwrap(),
+            )
+            .unwrap();
+        db.meta.remove(TAG_INDEX_VERSION_KEY).unwrap();
+
+        db.migrate_tag_index().unwrap();
+
+        let expected: BTreeSet<String> = [a_path, b_path].into_iter().collect();
+        assert_eq!(
+            db.tags.get("rust").unwrap().unwrap().to_vec(),
+            types::encode_file_set(&expected).unwrap()
+        );
+        assert_eq!(
+            db.meta
+                .get(TAG_INDEX_VERSION_KEY)
+                .unwrap()
+                .unwrap()
+                .to_vec(),
+            [TAG_INDEX_VERSION]
+        );
+        assert_eq!(db.count_files_per_tag_fast("rust").unwrap(), 2);
+        assert_eq!(db.find_by_tag("rust").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_add_tags_many_writes_all_and_reports_per_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let options = DbOptions {
+            max_files: Some(3),
+            ..DbOptions::default()
+        };
+        let db = Database::open_with(temp_dir.path().join("many_db"), options).unwrap();
+        let files: Vec<TempFile> = (0..4)
+            .map(|i| TempFile::create(format!("many{i}.txt")).unwrap())
+            .collect();
+        db.insert(files[0].path(), vec!["old".into(), "a".into()])
+            .unwrap();
+
+        let path = |i: usize| files[i].path().to_path_buf();
+        let results = db
+            .add_tags_many(&[
+                (path(0), vec!["a".into(), "b".into()]),
+                (PathBuf::from("/nonexistent/many.txt"), vec!["a".into()]),
+                (path(1), vec!["a".into()]),
+                (path(1), vec!["c".into()]),
+                (path(2), vec!["a".into()]),
+                // The file limit is reached by the staged files above
+                (path(3), vec!["a".into()]),
+            ])
+            .unwrap();
+
+        assert!(results[0].is_ok() && results[2].is_ok() && results[3].is_ok());
+        assert!(results[4].is_ok());
+        assert!(matches!(results[1], Err(DbError::FileNotFound(_))));
+        assert!(matches!(
+            results[5],
+            Err(DbError::LimitExceeded {
+                limit: 3,
+                current: 3
+            })
+        ));
+        assert_eq!(
+            db.get_tags(files[0].path()).unwrap().unwrap(),
+            vec!["old", "a", "b"]
+        );
+        assert_eq!(
+            db.get_tags(files[1].path()).unwrap().unwrap(),
+            vec!["a", "c"]
+        );
+        assert!(db.get_tags(files[3].path()).unwrap().is_none());
+        assert_eq!(db.find_by_tag("a").unwrap().len(), 3);
+        assert_eq!(db.count_files_per_tag_fast("b").unwrap(), 1);
+        assert_eq!(db.count_files_per_tag_fast("c").unwrap(), 1);
+        assert!(db.get_timestamps(files[2].path()).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_remove_tags_many_matches_single_removals() {
+        let test_db = TestDb::new("test_remove_tags_many");
+        let db = test_db.db();
+        let files: Vec<TempFile> = (0..4)
+            .map(|i| TempFile::create(format!("remove_many{i}.txt")).unwrap())
+            .collect();
+        let path = |i: usize| files[i].path().to_path_buf();
+        db.insert(path(0), vec!["x".into(), "y".into(), "z".into()])
+            .unwrap();
+        db.insert(path(1), vec!["y".into()]).unwrap();
+        db.set_note(path(1), NoteRecord::new("keep me".into()))
+            .unwrap();
+        db.insert(path(2), vec!["y".into()]).unwrap();
+        db.insert(path(3), vec!["y".into()]).unwrap();
+        db.set_note(path(3), NoteRecord::new("drop me".into()))
+            .unwrap();
+
+        let results = db
+            .remove_tags_many(
+                &[
+                    path(0),
+                    path(1),
+                    path(2),
+                    PathBuf::from("/nonexistent/untracked.txt"),
+                ],
+                Some(&["y".to_string(), "z".to_string()]),
+            )
+            .unwrap();
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(db.get_tags(path(0)).unwrap().unwrap(), ["x"]);
+        // A note keeps the file tracked with no tags, like remove_tags
+        assert_eq!(db.get_tags(path(1)).unwrap().unwrap(), Vec::<String>::new());
+        assert!(db.get_tags(path(2)).unwrap().is_none());
+        assert!(db.get_timestamps(path(2)).unwrap().is_none());
+        assert_eq!(db.find_by_tag("y").unwrap(), [path(3)]);
+        assert!(db.find_by_tag("z").unwrap().is_empty());
+
+        // Without tags the whole entry goes, note included, like remove
+        let results = db.remove_tags_many(&[path(3)], None).unwrap();
+        assert!(results[0].is_ok());
+        assert!(db.get_tags(path(3)).unwrap().is_none());
+        assert!(db.get_note(path(3)).unwrap().is_none());
+        assert!(db.find_by_tag("y").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tag_index_updates_are_atomic() {
+        let test_db = TestDb::new("test_tag_index_atomic");
+        let db = test_db.db();
+
+        // Bypass the write lock: concurrent updates of one tag must not be lost
+        std::thread::scope(|scope| {
+            for t in 0..4 {
+                scope.spawn(move || {
+                    for i in 0..50 {
+                        db.add_to_tag_index(&format!("/t{t}/f{i}"), &["shared".into()])
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(db.count_files_per_tag_fast("shared").unwrap(), 200);
+
+        std::thread::scope(|scope| {
+            for t in 0..4 {
+                scope.spawn(move || {
+                    for i in 0..50 {
+                        db.remove_from_tag_index(&format!("/t{t}/f{i}"), &["shared".into()])
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        assert!(db.tags.get("shared").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_count_files_per_tag_fast_matches_full_decode() {
+        let test_db = TestDb::new("test_count_files_per_tag_fast");
+        let db = test_db.db();
+        let temp_dir = tempfile::tempdir().unwrap();
+        // Over 250 entries the varint length takes more than one byte
+        for i in 0..300 {
+            let file = temp_dir.path().join(format!("file_{i}.txt"));
+            std::fs::write(&file, "x").unwrap();
+            let mut tags = vec!["many".to_string()];
+            if i % 100 == 0 {
+                tags.push("few".into());
+            }
+            db.insert(&file, tags).unwrap();
+        }
+
+        for tag in ["many", "few", "missing"] {
+            assert_eq!(
+                db.count_files_per_tag_fast(tag).unwrap(),
+                db.find_by_tag(tag).unwrap().len(),
+                "tag '{tag}'"
+            );
+        }
+        assert_eq!(db.count_files_per_tag_fast("many").unwrap(), 300);
+    }
+
+    #[test]
+    fn test_find_by_tag_sorted_uses_path_order() {
+        let test_db = TestDb::new("test_find_by_tag_sorted");
+        let db = test_db.db();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let nested = temp_dir.path().join("a").join("b.txt");
+        let dashed = temp_dir.path().join("a-b.txt");
+        let first = temp_dir.path().join("0.txt");
+        std::fs::create_dir(nested.parent().unwrap()).unwrap();
+        for file in [&dashed, &nested, &first] {
+            std::fs::write(file, "x").unwrap();
+            db.insert(file, vec!["tag".into()]).unwrap();
+        }
+
+        // Component order puts "a/b.txt" before "a-b.txt", unlike string order
+        let expected = vec![first, nested, dashed];
+        assert_eq!(db.find_by_tag_sorted("tag").unwrap(), expected);
+        assert_eq!(db.find_by_any_tag(&["tag".into()]).unwrap(), expected);
+        assert_eq!(db.find_by_all_tags(&["tag".into()]).unwrap(), expected);
+        assert_eq!(db.find_by_tag_scan("tag").unwrap(), expected);
+    }
+
+    #[test]
+    fn test_timestamps_track_first_and_last_tagged() {
+        let test_db = TestDb::new("test_timestamps");
+        let db = test_db.db();
+        let old = TempFile::create("times_old.txt").unwrap();
+        let new = TempFile::create("times_new.txt").unwrap();
+
+        db.insert(old.path(), vec!["a".into()]).unwrap();
+        let times = db.get_timestamps(old.path()).unwrap().unwrap();
+        assert_eq!(times.first_tagged, times.last_tagged);
+
+        // Backdate the first tagging, then tag again
+        let key = bincode::encode_to_vec(old.path(), bincode::config::standard()).unwrap();
+        let backdated = TagTimes {
+            first_tagged: 100,
+            last_tagged: 100,
+        };
+        db.timestamps
+            .insert(
+                key,
+                bincode::encode_to_vec(backdated, bincode::config::standard()).unwrap(),
+            )
+            .unwrap();
+        db.add_tags(old.path(), vec!["b".into()]).unwrap();
+        let times = db.get_timestamps(old.path()).unwrap().unwrap();
+        assert_eq!(times.first_tagged, 100);
+        assert!(times.last_tagged > 100);
+
+        db.rename_file(old.path(), new.path()).unwrap();
+        assert!(db.get_timestamps(old.path()).unwrap().is_none());
+        assert_eq!(
+            db.get_timestamps(new.path()).unwrap().unwrap().first_tagged,
+            100
+        );
+
+        db.remove(new.path()).unwrap();
+        assert!(db.get_timestamps(new.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rename_file_merges_or_replaces_existing_tags() {
+        let test_db = TestDb::new("test_rename_file_merge");
+        let db = test_db.db();
+
+        let old = TempFile::create("old.txt").unwrap();
+        let new = TempFile::create("new.txt").unwrap();
+        db.insert(old.path(), vec!["rust".into()]).unwrap();
+        db.insert(new.path(), vec!["draft".into()]).unwrap();
+        db.rename_file(old.path(), new.path()).unwrap();
+        assert_eq!(
+            db.get_tags(new.path()).unwrap(),
+            Some(vec!["draft".to_string(), "rust".to_string()])
+        );
+
+        db.insert(old.path(), vec!["final".into()]).unwrap();
+        db.rename_file_replace(old.path(), new.path()).unwrap();
+        assert_eq!(
+            db.get_tags(new.path()).unwrap(),
+            Some(vec!["final".to_string()])
+        );
+        assert!(db.find_by_tag("draft").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rename_file_over_tag_limit_keeps_old_entry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let options = DbOptions {
+            max_tags_per_file: Some(2),
+            ..DbOptions::default()
+        };
+        let db = Database::open_with(temp_dir.path().join("rename_limit_db"), options).unwrap();
+        let old = TempFile::create("rename_limit_old.txt").unwrap();
+        let new = TempFile::create("rename_limit_new.txt").unwrap();
+        db.insert(old.path(), vec!["rust".into(), "cli".into()])
+            .unwrap();
+        db.insert(new.path(), vec!["draft".into()]).unwrap();
+
+        assert!(matches!(
+            db.rename_file(old.path(), new.path()),
+            Err(DbError::LimitExceeded {
+                limit: 2,
+                current: 3
+            })
+        ));
+        assert_eq!(db.get_tags(old.path()).unwrap().unwrap(), ["rust", "cli"]);
+        assert_eq!(db.get_tags(new.path()).unwrap().unwrap(), ["draft"]);
+        assert_eq!(db.find_by_tag("rust").unwrap().len(), 1);
+
+        db.rename_file_replace(old.path(), new.path()).unwrap();
+        assert_eq!(db.get_tags(new.path()).unwrap().unwrap(), ["rust", "cli"]);
+    }
+
+    #[test]
+    fn test_rename_file_errors() {
+        let test_db = TestDb::new("test_rename_file_errors");
+        let db = test_db.db();
+
+        let tagged = TempFile::create("tagged.txt").unwrap();
+        let untagged = TempFile::create("untagged.txt").unwrap();
+        db.insert(tagged.path(), vec!["rust".into()]).unwrap();
+
+        assert!(matches!(
+            db.rename_file(untagged.path(), tagged.path()),
+            Err(DbError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            db.rename_file(tagged.path(), Path::new("/nonexistent/moved.txt")),
+            Err(DbError::FileNotFound(_))
+        ));
+        assert!(db.contains(tagged.path()).unwrap());
+    }
+
+    #[test]
+    fn test_file_count_for_tags_matches_search() {
+        let test_db = TestDb::new("test_file_count_for_tags");
+        let db = test_db.db();
+
+        let files: Vec<TempFile> = (0..6)
+            .map(|i| TempFile::create(format!("count_{i}.txt")).unwrap())
+            .collect();
+        let tag_sets: [&[&str]; 6] = [
+            &["rust"],
+            &["rust", "web"],
+            &["rust", "web", "old"],
+            &["web"],
+            &["python", "old"],
+            &["python"],
+        ];
+        for (file, tags) in files.iter().zip(tag_sets) {
+            db.insert(file.path(), tags.iter().map(ToString::to_string).collect())
+                .unwrap();
+        }
+
+        let strings =
+            |tags: &[&str]| -> Vec<String> { tags.iter().map(ToString::to_string).collect() };
+        let expected = |found: Vec<PathBuf>, excludes: &[String]| {
+            found
+                .into_iter()
+                .filter(|file| {
+                    let tags = db.get_tags(file).unwrap().unwrap();
+                    !tags.iter().any(|tag| excludes.contains(tag))
+                })
+                .count()
+        };
+
+        for (tags, excludes) in [
+            (&["rust", "web"][..], &[][..]),
+            (&["rust", "web"], &["old"]),
+            (&["rust", "python"], &["web"]),
+            (&["missing"], &[]),
+            (&["rust", "missing"], &[]),
+        ] {
+            let tags = strings(tags);
+            let excludes = strings(excludes);
+            assert_eq!(
+                db.file_count_for_tags(&tags, SearchMode::Any, &excludes)
+                    .unwrap(),
+                expected(db.find_by_any_tag(&tags).unwrap(), &excludes),
+                "any {tags:?} -{excludes:?}"
+            );
+            assert_eq!(
+                db.file_count_for_tags(&tags, SearchMode::All, &excludes)
+                    .unwrap(),
+                expected(db.find_by_all_tags(&tags).unwrap(), &excludes),
+                "all {tags:?} -{excludes:?}"
+            );
+        }
+
+        assert_eq!(
+            db.file_count_for_tags(&[], SearchMode::Any, &[]).unwrap(),
+            0
+        );
+        let only_first = files[0].path().to_path_buf();
+        assert_eq!(
+            db.file_count_for_tags_where(&strings(&["rust"]), SearchMode::Any, &[], |path| {
+                path == only_first
+            })
+            .unwrap(),
+            1
+        );
+    }
 }
@@ -3,14 +3,163 @@
 //! This module provides shared query building functionality used by both
 //! search and browse commands to construct file lists based on search parameters.
 
+use crate::Pair;
 use crate::cli::{SearchMode, SearchParams};
 use crate::db::{Database, DbError};
-use crate::search::filter::{PathFilterExt, PathTagFilterExt};
+use crate::search::filter::{GlobMatcher, PathFilterExt, PathTagFilterExt};
 use crate::search::hierarchy;
-use crate::vtags::{VirtualTag, VirtualTagConfig, VirtualTagEvaluator};
+use crate::search::query_lang::Expr;
+use crate::vtags::{VirtualTag, VirtualTagConfig, VirtualTagEvaluator, split_negated};
 use std::collections::HashSet;
-use std::path::PathBuf;
-use std::time::Duration;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Kind of work performed by a [`PlanStep`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanOp {
+    /// Schema expansion of tags (synonyms and hierarchy levels)
+    ExpandTags,
+    /// Reverse-index lookup of a single tag
+    IndexLookup,
+    /// Regex match against all tag names
+    TagRegex,
+    /// Pass over every entry in the database
+    Scan,
+    /// Hierarchical tag matching over scanned entries
+    TagMatch,
+    /// Intersection of candidate sets (AND)
+    Intersect,
+    /// Union of candidate sets (OR)
+    Union,
+    /// Keep files whose paths match file patterns
+    FileFilter,
+    /// Drop files matching exclude patterns or excluded directories
+    ExcludeFiles,
+    /// Drop files carrying excluded tags
+    ExcludeTags,
+    /// Keep files matching virtual tags
+    VirtualTags,
+    /// Keep files satisfying a boolean tag expression
+    Expression,
+}
+
+impl fmt::Display for PlanOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::ExpandTags => "expand tags",
+            Self::IndexLookup => "index lookup",
+            Self::TagRegex => "tag regex",
+            Self::Scan => "scan",
+            Self::TagMatch => "tag match",
+            Self::Intersect => "intersect",
+            Self::Union => "union",
+            Self::FileFilter => "file filter",
+            Self::ExcludeFiles => "exclude files",
+            Self::ExcludeTags => "exclude tags",
+            Self::VirtualTags => "virtual tags",
+            Self::Expression => "expression",
+        })
+    }
+}
+
+/// One step of an executed search
+#[derive(Debug, Clone)]
+pub struct PlanStep {
+    /// What the step did
+    pub op: PlanOp,
+    /// Human-readable details (tag, pattern, ...)
+    pub detail: String,
+    /// Number of candidates produced (tags for [`PlanOp::ExpandTags`], files otherwise)
+    pub candidates: usize,
+    /// Time spent in the step
+    pub elapsed: Duration,
+}
+
+/// Record of how a search was executed, in execution order
+///
+/// Filled in by [`apply_search_params_traced`] for `search --explain`.
+#[derive(Debug, Clone, Default)]
+pub struct QueryPlan {
+    pub steps: Vec<PlanStep>,
+}
+
+impl QueryPlan {
+    /// Append a step that started at `started` and produced `candidates`
+    pub fn record(
+        &mut self,
+        op: PlanOp,
+        detail: impl Into<String>,
+        candidates: usize,
+        started: Instant,
+    ) {
+        self.steps.push(PlanStep {
+            op,
+            detail: detail.into(),
+            candidates,
+            elapsed: started.elapsed(),
+        });
+    }
+
+    /// Operations in execution order
+    #[must_use]
+    pub fn ops(&self) -> Vec<PlanOp> {
+        self.steps.iter().map(|step| step.op).collect()
+    }
+
+    /// Total time spent across all steps
+    #[must_use]
+    pub fn total_elapsed(&self) -> Duration {
+        self.steps.iter().map(|step| step.elapsed).sum()
+    }
+}
+
+impl fmt::Display for QueryPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>3}  {:<13} {:>8} {:>10}  detail",
+            "#", "step", "count", "time"
+        )?;
+        for (i, step) in self.steps.iter().enumerate() {
+            let elapsed = format!("{:.2?}", step.elapsed);
+            writeln!(
+                f,
+                "{:>3}. {:<13} {:>8} {:>10}  {}",
+                i + 1,
+                step.op,
+                step.candidates,
+                elapsed,
+                step.detail
+            )?;
+        }
+        write!(f, "Total: {:.2?}", self.total_elapsed())
+    }
+}
+
+/// Append a step to `plan` when a search is being traced
+///
+/// `detail` is only built when tracing, so untraced searches pay nothing for it.
+fn trace(
+    plan: &mut Option<&mut QueryPlan>,
+    op: PlanOp,
+    detail: impl FnOnce() -> String,
+    candidates: usize,
+    started: Instant,
+) {
+    if let Some(plan) = plan {
+        plan.record(op, detail(), candidates, started);
+    }
+}
+
+/// Describe glob patterns fused into a candidate step
+fn glob_note(patterns: &[String], applied: bool) -> String {
+    if applied {
+        format!(", filtered by glob {}", patterns.join(" "))
+    } else {
+        String::new()
+    }
+}
 
 /// Apply search parameters to build a filtered file list
 ///
@@ -24,7 +173,8 @@ use std::time::Duration;
 /// - Deeper tags override shallower ones within the same hierarchy
 /// - Excludes always win against includes from different hierarchies
 ///
-/// Then applies file pattern filters and tag exclusions.
+/// Then applies file pattern filters, tag exclusions and the grouped
+/// `expression`, if any.
 ///
 /// If params contains tags and regex mode is disabled, tags will be expanded
 /// using the schema to include synonyms and (if `no_hierarchy` is false) parent levels.
@@ -49,19 +199,45 @@ use std::time::Duration;
 /// };
 /// let files = apply_search_params(&db, &params)?;
 /// ```
-#[allow(clippy::too_many_lines)]
 pub fn apply_search_params(db: &Database, params: &SearchParams) -> Result<Vec<PathBuf>, DbError> {
+    apply_search_params_traced(db, params, None)
+}
+
+/// Like [`apply_search_params`], recording each step into `plan` when given
+///
+/// # Errors
+/// Returns `DbError` if database operations fail or pattern validation fails
+#[allow(clippy::too_many_lines)]
+pub fn apply_search_params_traced(
+    db: &Database,
+    params: &SearchParams,
+    mut plan: Option<&mut QueryPlan>,
+) -> Result<Vec<PathBuf>, DbError> {
     // Expand tags via schema if not in regex mode
     let mut expanded_params = params.clone();
     let original_tag_count = params.tags.len();
 
     if !params.tags.is_empty() && !params.regex_tag {
         // Load schema (gracefully handle missing schema)
+        let started = Instant::now();
         if let Ok(schema) = crate::schema::load_default_schema() {
             let include_hierarchy = !params.no_hierarchy;
             let expanded =
                 crate::search::expand_tags(&params.tags, &schema, db, include_hierarchy)?;
             expanded_params.tags = expanded;
+            trace(
+                &mut plan,
+                PlanOp::ExpandTags,
+                || {
+                    format!(
+                        "{} → {}",
+                        params.tags.join(", "),
+                        expanded_params.tags.join(", ")
+                    )
+                },
+                expanded_params.tags.len(),
+                started,
+            );
 
             // If tags were expanded from synonyms/hierarchy and user specified only 1 tag originally,
             // switch to ANY mode (OR logic) instead of ALL (AND logic) for intuitive behavior
@@ -71,17 +247,54 @@ pub fn apply_search_params(db: &Database, params: &SearchParams) -> Result<Vec<P
         }
     }
 
+    // Glob file patterns are compiled once and applied as a predicate while
+    // candidates are produced, instead of as a separate pass over the results
+    let file_globs = if expanded_params.file_patterns.is_empty() || expanded_params.regex_file {
+        None
+    } else {
+        Some(GlobMatcher::new(
+            &expanded_params.file_patterns,
+            expanded_params.file_mode == SearchMode::All,
+        )?)
+    };
+    let keep = |file: &Path| file_globs.as_ref().is_none_or(|globs| globs.matches(file));
+    let globs_note = || glob_note(&expanded_params.file_patterns, file_globs.is_some());
+
     let mut files = if let Some(query) = &expanded_params.query {
-        let files_by_tag = db.find_by_tag_regex(query)?;
+        let started = Instant::now();
+        let files_by_tag = find_by_tag_regex(db, query, !params.no_index)?;
+        trace(
+            &mut plan,
+            PlanOp::TagRegex,
+            || format!("query '{query}' against tag names"),
+            files_by_tag.len(),
+            started,
+        );
 
+        let started = Instant::now();
         let all_files = db.list_all_files()?;
         let filename_pattern = format!("*{query}*");
         let files_by_name = all_files.into_iter().filter_glob_any(&[filename_pattern])?;
+        trace(
+            &mut plan,
+            PlanOp::Scan,
+            || format!("filenames matching '*{query}*'"),
+            files_by_name.len(),
+            started,
+        );
 
+        let started = Instant::now();
         let mut file_set: HashSet<_> = files_by_tag.into_iter().collect();
         file_set.extend(files_by_name);
-        let mut files: Vec<_> = file_set.into_iter().collect();
+        let mut files: Vec<_> = file_set.into_iter().filter(|f| keep(f)).collect();
         files.sort();
+        trace(
+            &mut plan,
+            PlanOp::Union,
+            || format!("tag and filename matches{}", globs_note()),
+            files.len(),
+            started,
+        );
         files
     } else if !expanded_params.tags.is_empty() {
         if expanded_params.regex_tag {
@@ -92,19 +305,45 @@ pub fn apply_search_params(db: &Database, params: &SearchParams) -> Result<Vec<P
                     if expanded_params.tags.is_empty() {
                         Vec::new()
                     } else {
-                        // Get files matching each regex pattern
-                        let mut file_sets: Vec<HashSet<PathBuf>> = Vec::new();
+                        // Intersect the files matching each regex pattern,
+                        // stopping once no file can match them all
+                        let mut matched: Option<HashSet<PathBuf>> = None;
                         for tag_pattern in &expanded_params.tags {
-                            let matching_files = db.find_by_tag_regex(tag_pattern)?;
-                            file_sets.push(matching_files.into_iter().collect());
+                            if matched.as_ref().is_some_and(HashSet::is_empty) {
+                                break;
+                            }
+                            let started = Instant::now();
+                            let matching_files =
+                                find_by_tag_regex(db, tag_pattern, !params.no_index)?;
+                            trace(
+                                &mut plan,
+                                PlanOp::TagRegex,
+                                || format!("tag regex '{tag_pattern}'"),
+                                matching_files.len(),
+                                started,
+                            );
+                            matched = Some(match matched {
+                                None => matching_files.into_iter().collect(),
+                                Some(matched) => matching_files
+                                    .into_iter()
+                                    .filter(|file| matched.contains(file))
+                                    .collect(),
+                            });
                         }
 
-                        // Find intersection of all sets
-                        let first_set = file_sets.remove(0);
-                        let result: Vec<PathBuf> = first_set
+                        let started = Instant::now();
+                        let result: Vec<PathBuf> = matched
+                            .unwrap_or_default()
                             .into_iter()
-                            .filter(|file| file_sets.iter().all(|set| set.contains(file)))
+                            .filter(|file| keep(file))
                             .collect();
+                        trace(
+                            &mut plan,
+                            PlanOp::Intersect,
+                            || format!("regex matches{}", globs_note()),
+                            result.len(),
+                            started,
+                        );
                         result
                     }
                 }
@@ -112,11 +351,27 @@ pub fn apply_search_params(db: &Database, params: &SearchParams) -> Result<Vec<P
                     // For ANY mode with regex, collect all files matching any pattern
                     let mut file_set = HashSet::new();
                     for tag_pattern in &expanded_params.tags {
-                        let matching_files = db.find_by_tag_regex(tag_pattern)?;
-                        file_set.extend(matching_files);
+                        let started = Instant::now();
+                        let matching_files = find_by_tag_regex(db, tag_pattern, !params.no_index)?;
+                        trace(
+                            &mut plan,
+                            PlanOp::TagRegex,
+                            || format!("tag regex '{tag_pattern}'"),
+                            matching_files.len(),
+                            started,
+                        );
+                        file_set.extend(matching_files.into_iter().filter(|f| keep(f)));
                     }
+                    let started = Instant::now();
                     let mut files: Vec<_> = file_set.into_iter().collect();
                     files.sort();
+                    trace(
+                        &mut plan,
+                        PlanOp::Union,
+                        || format!("regex matches{}", globs_note()),
+                        files.len(),
+                        started,
+                    );
                     files
                 }
             }
@@ -125,21 +380,35 @@ pub fn apply_search_params(db: &Database, params: &SearchParams) -> Result<Vec<P
             // When no_hierarchy is false, use hierarchical prefix matching
             if params.no_hierarchy {
                 // Traditional exact matching
-                match expanded_params.tag_mode {
-                    SearchMode::All => db.find_by_all_tags(&expanded_params.tags)?,
-                    SearchMode::Any => db.find_by_any_tag(&expanded_params.tags)?,
-                }
+                find_by_exact_tags(
+                    db,
+                    &expanded_params.tags,
+                    expanded_params.tag_mode,
+                    file_globs.as_ref(),
+                    !params.no_index,
+                    plan.as_deref_mut(),
+                )?
             } else {
                 // Hierarchical matching with specificity rules
                 // Note: tag_mode (Any/All) is handled by the hierarchical filter
                 // For now, we treat it as ANY since hierarchical matching is more nuanced
                 // Get all files from database and filter using hierarchical logic
+                let started = Instant::now();
                 let all_files = db.list_all()?;
 
                 let files_with_tags: Vec<(String, Vec<String>)> = all_files
                     .into_iter()
+                    .filter(|pair| keep(&pair.file))
                     .filter_map(|pair| pair.file.to_str().map(|s| (s.to_string(), pair.tags)))
                     .collect();
+                trace(
+                    &mut plan,
+                    PlanOp::Scan,
+                    || format!("all entries{}", globs_note()),
+                    files_with_tags.len(),
+                    started,
+                );
+                let started = Instant::now();
 
                 let files_refs: Vec<(&str, &[String])> = files_with_tags
                     .iter()
@@ -175,24 +444,74 @@ pub fn apply_search_params(db: &Database, params: &SearchParams) -> Result<Vec<P
                         .collect()
                     }
                 };
+                trace(
+                    &mut plan,
+                    PlanOp::TagMatch,
+                    || {
+                        let mode = match expanded_params.tag_mode {
+                            SearchMode::All => "all of",
+                            SearchMode::Any => "any of",
+                        };
+                        format!("{mode} {} (hierarchical)", expanded_params.tags.join(", "))
+                    },
+                    filtered_paths.len(),
+                    started,
+                );
 
                 filtered_paths
             }
         }
     } else {
-        db.list_all_files()?
+        let started = Instant::now();
+        let files: Vec<PathBuf> = db
+            .list_all_files()?
+            .into_iter()
+            .filter(|f| keep(f))
+            .collect();
+        trace(
+            &mut plan,
+            PlanOp::Scan,
+            || format!("all files{}", globs_note()),
+            files.len(),
+            started,
+        );
+        files
     };
 
-    if !expanded_params.file_patterns.is_empty() {
+    if !expanded_params.file_patterns.is_empty() && file_globs.is_none() {
+        let started = Instant::now();
         let match_all = expanded_params.file_mode == SearchMode::All;
         files = files.into_iter().filter_patterns(
             &expanded_params.file_patterns,
             expanded_params.regex_file,
             match_all,
         )?;
+        trace(
+            &mut plan,
+            PlanOp::FileFilter,
+            || format!("regex {}", expanded_params.file_patterns.join(" ")),
+            files.len(),
+            started,
+        );
+    }
+
+    if !expanded_params.exclude_file_patterns.is_empty() {
+        let started = Instant::now();
+        files = files.into_iter().exclude_patterns(
+            &expanded_params.exclude_file_patterns,
+            expanded_params.regex_file,
+        )?;
+        trace(
+            &mut plan,
+            PlanOp::ExcludeFiles,
+            || expanded_params.exclude_file_patterns.join(" "),
+            files.len(),
+            started,
+        );
     }
 
     if !expanded_params.exclude_tags.is_empty() {
+        let started = Instant::now();
         if params.no_hierarchy {
             // Traditional exclude logic (simple contains check)
             files = files.exclude_tags(db, &expanded_params.exclude_tags)?;
@@ -218,33 +537,329 @@ pub fn apply_search_params(db: &Database, params: &SearchParams) -> Result<Vec<P
             }
             files = filtered_files;
         }
+        trace(
+            &mut plan,
+            PlanOp::ExcludeTags,
+            || expanded_params.exclude_tags.join(", "),
+            files.len(),
+            started,
+        );
+    }
+
+    if let Some(expr) = &expanded_params.expression {
+        let started = Instant::now();
+        let mut matched = Vec::with_capacity(files.len());
+        for file in files {
+            let tags = db.get_tags(&file)?.unwrap_or_default();
+            if expr.matches(&file, &tags, !params.no_hierarchy) {
+                matched.push(file);
+            }
+        }
+        files = matched;
+        trace(
+            &mut plan,
+            PlanOp::Expression,
+            || expr.to_string(),
+            files.len(),
+            started,
+        );
     }
 
-    if !expanded_params.virtual_tags.is_empty() {
+    let (virtual_tags, mut exclude_virtual_tags) = split_negated(&expanded_params.virtual_tags);
+    exclude_virtual_tags.extend(expanded_params.exclude_virtual_tags.iter().cloned());
+    if !virtual_tags.is_empty() || !exclude_virtual_tags.is_empty() {
+        let started = Instant::now();
         files = apply_virtual_tags(
             files,
-            &expanded_params.virtual_tags,
+            &virtual_tags,
             expanded_params.virtual_mode,
+            &exclude_virtual_tags,
         )?;
+        trace(
+            &mut plan,
+            PlanOp::VirtualTags,
+            || {
+                virtual_tags
+                    .iter()
+                    .cloned()
+                    .chain(exclude_virtual_tags.iter().map(|vtag| format!("!{vtag}")))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            },
+            files.len(),
+            started,
+        );
     }
 
     Ok(files)
 }
 
+/// Find files by exact tags, evaluating the cheaper side of a tag+glob search first
+///
+/// Without globs this is a plain reverse-index lookup. With globs, the number
+/// of index entries to decode is compared with the number of tagged files: if
+/// the tags are more selective their files are streamed through the glob
+/// predicate, otherwise every entry is pruned by glob first and its stored
+/// tags are checked directly. Index lookups are recorded when tracing.
+///
+/// Without `use_index` (`search --no-index`) each tag is found with
+/// [`Database::find_by_tag_scan`] instead, bypassing the reverse index.
+fn find_by_exact_tags(
+    db: &Database,
+    tags: &[String],
+    mode: SearchMode,
+    globs: Option<&GlobMatcher>,
+    use_index: bool,
+    mut plan: Option<&mut QueryPlan>,
+) -> Result<Vec<PathBuf>, DbError> {
+    if !use_index {
+        let started = Instant::now();
+        let mut sets = Vec::with_capacity(tags.len());
+        for tag in tags {
+            sets.push(
+                db.find_by_tag_scan(tag)?
+                    .into_iter()
+                    .collect::<HashSet<_>>(),
+            );
+        }
+        let mut files: Vec<PathBuf> = match mode {
+            SearchMode::All => {
+                let first = sets.pop().unwrap_or_default();
+                first
+                    .into_iter()
+                    .filter(|file| sets.iter().all(|set| set.contains(file)))
+                    .collect()
+            }
+            SearchMode::Any => sets
+                .into_iter()
+                .flatten()
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect(),
+        };
+        files.retain(|file| globs.is_none_or(|globs| globs.matches(file)));
+        files.sort();
+        trace(
+            &mut plan,
+            PlanOp::Scan,
+            || {
+                format!(
+                    "{} tag(s) checked against every entry (index bypassed)",
+                    tags.len()
+                )
+            },
+            files.len(),
+            started,
+        );
+        return Ok(files);
+    }
+
+    // Index sizes are only needed to choose an order, or to report them
+    let mut indexed = 0;
+    if globs.is_some() || plan.is_some() {
+        for tag in tags {
+            let started = Instant::now();
+            let count = db.tag_file_count(tag)?;
+            trace(
+                &mut plan,
+                PlanOp::IndexLookup,
+                || format!("tag '{tag}'"),
+                count,
+                started,
+            );
+            indexed += count;
+        }
+    }
+    let total = db.count();
+
+    if let Some(globs) = globs.filter(|_| indexed > total) {
+        let started = Instant::now();
+        let mut files: Vec<PathBuf> = db
+            .list_all()?
+            .into_iter()
+            .filter(|pair| globs.matches(&pair.file))
+            .filter(|pair| match mode {
+                SearchMode::All => tags.iter().all(|tag| pair.tags.contains(tag)),
+                SearchMode::Any => tags.iter().any(|tag| pair.tags.contains(tag)),
+            })
+            .map(|pair| pair.file)
+            .collect();
+        files.sort();
+        trace(
+            &mut plan,
+            PlanOp::Scan,
+            || {
+                format!(
+                    "entries pruned by glob, then tags checked ({indexed} index entries > {total} files)"
+                )
+            },
+            files.len(),
+            started,
+        );
+        return Ok(files);
+    }
+
+    let started = Instant::now();
+    let files = match mode {
+        SearchMode::All => db.find_by_all_tags(tags)?,
+        SearchMode::Any => db.find_by_any_tag(tags)?,
+    };
+    if tags.len() > 1 {
+        let op = match mode {
+            SearchMode::All => PlanOp::Intersect,
+            SearchMode::Any => PlanOp::Union,
+        };
+        trace(
+            &mut plan,
+            op,
+            || format!("{} tag sets", tags.len()),
+            files.len(),
+            started,
+        );
+    }
+
+    let Some(globs) = globs else {
+        return Ok(files);
+    };
+    let started = Instant::now();
+    let files: Vec<PathBuf> = files
+        .into_iter()
+        .filter(|file| globs.matches(file))
+        .collect();
+    trace(
+        &mut plan,
+        PlanOp::FileFilter,
+        || "glob, pruned by literal prefix".to_string(),
+        files.len(),
+        started,
+    );
+    Ok(files)
+}
+
+/// Find files with a tag matching `pattern`, via the reverse index or a full scan
+fn find_by_tag_regex(
+    db: &Database,
+    pattern: &str,
+    use_index: bool,
+) -> Result<Vec<PathBuf>, DbError> {
+    if use_index {
+        return db.find_by_tag_regex(pattern);
+    }
+    let regex = regex::Regex::new(pattern)
+        .map_err(|e| DbError::InvalidInput(format!("Invalid regex pattern: {e}")))?;
+    let mut files: Vec<PathBuf> = db
+        .list_all()?
+        .into_iter()
+        .filter(|pair| pair.tags.iter().any(|tag| regex.is_match(tag)))
+        .map(|pair| pair.file)
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Filter files by a boolean tag expression
+///
+/// Starts from the files selected by the remaining criteria in `params`
+/// (every file when there are none) and keeps those whose tags satisfy
+/// `expr`. Expression tags match descendants unless `no_hierarchy` is set.
+///
+/// # Errors
+/// Returns `DbError` if database operations fail
+pub fn apply_expr(
+    db: &Database,
+    params: &SearchParams,
+    expr: &Expr,
+) -> Result<Vec<PathBuf>, DbError> {
+    apply_expr_traced(db, params, expr, None)
+}
+
+/// Like [`apply_expr`], recording each step into `plan` when given
+///
+/// # Errors
+/// Returns `DbError` if database operations fail
+pub fn apply_expr_traced(
+    db: &Database,
+    params: &SearchParams,
+    expr: &Expr,
+    mut plan: Option<&mut QueryPlan>,
+) -> Result<Vec<PathBuf>, DbError> {
+    let candidates = apply_search_params_traced(db, params, plan.as_deref_mut())?;
+    let started = Instant::now();
+    let mut files = Vec::with_capacity(candidates.len());
+    for file in candidates {
+        let tags = db.get_tags(&file)?.unwrap_or_default();
+        if expr.matches(&tags, !params.no_hierarchy) {
+            files.push(file);
+        }
+    }
+    trace(
+        &mut plan,
+        PlanOp::Expression,
+        || expr.to_string(),
+        files.len(),
+        started,
+    );
+    Ok(files)
+}
+
+/// Group matched files under each requested tag
+///
+/// For every tag in `params.tags`, applies the same tag matching as
+/// [`apply_search_params`] (synonyms, hierarchy, regex) to that tag alone and
+/// keeps the files that are in `files`. Groups follow the order of
+/// `params.tags`, files keep their order from `files`, and a file appears
+/// under every tag it matches.
+///
+/// # Errors
+/// Returns `DbError` if database operations fail
+pub fn group_by_tag(
+    db: &Database,
+    params: &SearchParams,
+    files: &[PathBuf],
+) -> Result<Vec<(String, Vec<Pair>)>, DbError> {
+    let mut groups = Vec::with_capacity(params.tags.len());
+    for tag in &params.tags {
+        let single = SearchParams {
+            tags: vec![tag.clone()],
+            tag_mode: SearchMode::Any,
+            regex_tag: params.regex_tag,
+            no_hierarchy: params.no_hierarchy,
+            ..Default::default()
+        };
+        let matching: HashSet<PathBuf> = apply_search_params(db, &single)?.into_iter().collect();
+
+        let mut pairs = Vec::new();
+        for file in files.iter().filter(|file| matching.contains(*file)) {
+            let tags = db.get_tags(file)?.unwrap_or_default();
+            pairs.push(Pair::new(file.clone(), tags));
+        }
+        groups.push((tag.clone(), pairs));
+    }
+    Ok(groups)
+}
+
+/// Keep files matching `virtual_tags` (combined by `mode`) and none of `exclude`
+///
+/// A file whose metadata cannot be read matches no virtual tag and is never
+/// known not to match one, so it is dropped whenever any virtual tag, included
+/// or excluded, is given.
 fn apply_virtual_tags(
     files: Vec<PathBuf>,
     virtual_tags: &[String],
     mode: SearchMode,
+    exclude: &[String],
 ) -> Result<Vec<PathBuf>, DbError> {
     use rayon::prelude::*;
 
     let config = VirtualTagConfig::default();
-
-    let parsed_tags: Vec<VirtualTag> = virtual_tags
-        .iter()
-        .map(|s| VirtualTag::parse_with_config(s, &config))
-        .collect::<Result<_, _>>()
-        .map_err(|e| DbError::InvalidInput(format!("Invalid virtual tag: {e}")))?;
+    let parse = |tags: &[String]| -> Result<Vec<VirtualTag>, DbError> {
+        tags.iter()
+            .map(|s| VirtualTag::parse_with_config(s, &config))
+            .collect::<Result<_, _>>()
+            .map_err(|e| DbError::InvalidInput(format!("Invalid virtual tag: {e}")))
+    };
+    let parsed_tags = parse(virtual_tags)?;
+    let parsed_excludes = parse(exclude)?;
 
     let cache_ttl = Duration::from_secs(config.cache_ttl_seconds);
 
@@ -252,14 +867,19 @@ fn apply_virtual_tags(
         .into_par_iter()
         .filter(|path| {
             let mut evaluator = VirtualTagEvaluator::new(cache_ttl, config.clone());
-            match mode {
-                SearchMode::All => parsed_tags
-                    .iter()
-                    .all(|vtag| evaluator.matches(path, vtag).unwrap_or(false)),
-                SearchMode::Any => parsed_tags
+            let included = parsed_tags.is_empty()
+                || match mode {
+                    SearchMode::All => parsed_tags
+                        .iter()
+                        .all(|vtag| evaluator.matches(path, vtag).unwrap_or(false)),
+                    SearchMode::Any => parsed_tags
+                        .iter()
+                        .any(|vtag| evaluator.matches(path, vtag).unwrap_or(false)),
+                };
+            included
+                && parsed_excludes
                     .iter()
-                    .any(|vtag| evaluator.matches(path, vtag).unwrap_or(false)),
-            }
+                    .all(|vtag| evaluator.matches(path, vtag).is_ok_and(|matched| !matched))
         })
         .collect();
 
@@ -302,14 +922,19 @@ mod tests {
             tags: vec!["mark.*".to_string()],
             tag_mode: SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: true,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let results = apply_search_params(db, &params).unwrap();
@@ -338,14 +963,19 @@ mod tests {
             tags: vec!["mark.*".to_string(), ".*note".to_string()],
             tag_mode: SearchMode::All,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: true,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let results = apply_search_params(db, &params).unwrap();
@@ -370,14 +1000,19 @@ mod tests {
             tags: vec![".*".to_string()],
             tag_mode: SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: true,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let results = apply_search_params(db, &params).unwrap();
@@ -405,14 +1040,19 @@ mod tests {
             tags: vec![],
             tag_mode: SearchMode::All,
             file_patterns: vec![".*\\.rs".to_string()],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: true,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let results = apply_search_params(db, &params).unwrap();
@@ -441,14 +1081,19 @@ mod tests {
             tags: vec!["rust.*".to_string()],
             tag_mode: SearchMode::Any,
             file_patterns: vec![".*\\.rs".to_string()],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: true,
             regex_file: true,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let results = apply_search_params(db, &params).unwrap();
@@ -456,6 +1101,232 @@ mod tests {
         assert!(results.contains(&file1.path().to_path_buf()));
     }
 
+    #[test]
+    fn test_tag_and_glob_search_intersects() {
+        let test_db = TestDb::new("test_tag_and_glob_intersect");
+        let db = test_db.db();
+
+        let wanted = TempFile::create("lib.rs").unwrap();
+        let other_dir = TempFile::create("main.rs").unwrap();
+        let other_ext = TempFile::create_with_content("notes.txt", b"notes").unwrap();
+        let docs_only = TempFile::create("mod.rs").unwrap();
+
+        for file in [&wanted, &other_dir] {
+            db.add_tags(file.path(), vec!["rust".into(), "code".into()])
+                .unwrap();
+        }
+        db.add_tags(other_ext.path(), vec!["rust".into(), "code".into()])
+            .unwrap();
+        db.add_tags(docs_only.path(), vec!["docs".into()]).unwrap();
+
+        // Literal directory prefix limits candidates to `wanted`'s directory
+        let dir = wanted.path().parent().unwrap().display();
+        let mut params = SearchParams {
+            query: None,
+            tags: vec!["rust".to_string()],
+            tag_mode: SearchMode::All,
+            file_patterns: vec![format!("{dir}/*.rs")],
+            file_only_patterns: vec![],
+            file_mode: SearchMode::All,
+            exclude_tags: vec![],
+            exclude_file_patterns: vec![],
+            regex_tag: false,
+            regex_file: false,
+            glob_files: false,
+            virtual_tags: vec![],
+            virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
+            no_hierarchy: false,
+            expression: None,
+            no_index: false,
+        };
+        let expected = vec![wanted.path().to_path_buf()];
+
+        // Hierarchical matching scans entries pruned by glob
+        assert_eq!(apply_search_params(db, &params).unwrap(), expected);
+
+        // Exact matching, tag index more selective than a scan
+        params.no_hierarchy = true;
+        assert_eq!(apply_search_params(db, &params).unwrap(), expected);
+
+        // Exact matching, tags less selective than a scan
+        params.tags = vec!["rust".to_string(), "code".to_string()];
+        params.tag_mode = SearchMode::Any;
+        assert_eq!(apply_search_params(db, &params).unwrap(), expected);
+
+        // Glob without a literal prefix still intersects by extension
+        params.file_patterns = vec!["*.rs".to_string()];
+        let mut expected = vec![wanted.path().to_path_buf(), other_dir.path().to_path_buf()];
+        expected.sort();
+        assert_eq!(apply_search_params(db, &params).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_no_index_scan_agrees_with_index() {
+        let test_db = TestDb::new("test_no_index_scan_agrees");
+        let db = test_db.db();
+        let files: Vec<TempFile> = (0..12)
+            .map(|i| TempFile::create(format!("scan{i}.rs")).unwrap())
+            .collect();
+        let tag_sets: [&[&str]; 4] = [&["rust"], &["rust", "code"], &["docs"], &["code", "wip"]];
+        for (i, file) in files.iter().enumerate() {
+            let tags = tag_sets[i % tag_sets.len()]
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            db.insert(file.path(), tags).unwrap();
+        }
+
+        for tag in db.list_all_tags().unwrap() {
+            assert_eq!(
+                db.find_by_tag_sorted(&tag).unwrap(),
+                db.find_by_tag_scan(&tag).unwrap(),
+                "tag '{tag}'"
+            );
+        }
+
+        let searches = [
+            (vec!["rust", "code"], SearchMode::All, false),
+            (vec!["rust", "wip"], SearchMode::Any, false),
+            (vec!["^(co|do)"], SearchMode::Any, true),
+            (vec!["^c", "e$"], SearchMode::All, true),
+        ];
+        for (tags, tag_mode, regex_tag) in searches {
+            let mut params = SearchParams {
+                tags: tags.iter().map(ToString::to_string).collect(),
+                tag_mode,
+                regex_tag,
+                no_hierarchy: true,
+                ..Default::default()
+            };
+            let mut indexed = apply_search_params(db, &params).unwrap();
+            params.no_index = true;
+            let mut scanned = apply_search_params(db, &params).unwrap();
+            indexed.sort();
+            scanned.sort();
+            assert!(!indexed.is_empty());
+            assert_eq!(indexed, scanned, "{tags:?} {tag_mode:?}");
+        }
+
+        // A drifted index is exposed by the scan
+        db.tags.remove("docs").unwrap();
+        let mut params = SearchParams {
+            tags: vec!["docs".to_string()],
+            no_hierarchy: true,
+            ..Default::default()
+        };
+        assert!(apply_search_params(db, &params).unwrap().is_empty());
+        params.no_index = true;
+        assert_eq!(apply_search_params(db, &params).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_all_tags_stops_once_intersection_is_empty() {
+        let test_db = TestDb::new("test_all_tags_short_circuit");
+        let db = test_db.db();
+        let files: Vec<TempFile> = (0..8)
+            .map(|i| TempFile::create(format!("and{i}.rs")).unwrap())
+            .collect();
+        for (i, file) in files.iter().enumerate() {
+            let mut tags = vec!["common".to_string()];
+            if i == 3 {
+                tags.push("rare".into());
+            }
+            db.insert(file.path(), tags).unwrap();
+        }
+
+        let oracle = |tags: &[&str]| {
+            let mut files: Vec<PathBuf> = db
+                .list_all()
+                .unwrap()
+                .into_iter()
+                .filter(|pair| tags.iter().all(|tag| pair.tags.iter().any(|t| t == tag)))
+                .map(|pair| pair.file)
+                .collect();
+            files.sort();
+            files
+        };
+        for tags in [
+            &["common", "rare"][..],
+            &["rare", "common"],
+            &["common"],
+            &["common", "missing"],
+            &["missing", "common", "rare"],
+        ] {
+            let owned: Vec<String> = tags.iter().map(ToString::to_string).collect();
+            assert_eq!(
+                db.find_by_all_tags(&owned).unwrap(),
+                oracle(tags),
+                "{tags:?}"
+            );
+        }
+        assert_eq!(
+            db.find_by_all_tags(&["rare".into(), "common".into()])
+                .unwrap(),
+            vec![files[3].path().to_path_buf()]
+        );
+
+        // Later regex patterns are never evaluated once nothing can match
+        let params = SearchParams {
+            tags: vec!["^missing$".into(), "^common$".into(), "^rare$".into()],
+            tag_mode: SearchMode::All,
+            regex_tag: true,
+            ..Default::default()
+        };
+        let mut plan = QueryPlan::default();
+        let found = apply_search_params_traced(db, &params, Some(&mut plan)).unwrap();
+        assert!(found.is_empty());
+        assert_eq!(plan.ops(), vec![PlanOp::TagRegex, PlanOp::Intersect]);
+    }
+
+    #[test]
+    fn test_query_plan_records_mixed_search_steps() {
+        let test_db = TestDb::new("test_query_plan_mixed");
+        let db = test_db.db();
+
+        let done = TempFile::create("done.rs").unwrap();
+        let wip = TempFile::create("wip.rs").unwrap();
+        let notes = TempFile::create_with_content("notes.txt", b"notes").unwrap();
+        let docs = TempFile::create("docs.rs").unwrap();
+        db.add_tags(done.path(), vec!["rust".into()]).unwrap();
+        db.add_tags(wip.path(), vec!["rust".into(), "wip".into()])
+            .unwrap();
+        db.add_tags(notes.path(), vec!["rust".into()]).unwrap();
+        db.add_tags(docs.path(), vec!["docs".into()]).unwrap();
+
+        // search -t rust -f '*.rs' -e wip --no-hierarchy
+        let params = SearchParams {
+            tags: vec!["rust".to_string()],
+            file_patterns: vec!["*.rs".to_string()],
+            glob_files: true,
+            exclude_tags: vec!["wip".to_string()],
+            no_hierarchy: true,
+            ..Default::default()
+        };
+
+        let mut plan = QueryPlan::default();
+        let files = apply_search_params_traced(db, &params, Some(&mut plan)).unwrap();
+        assert_eq!(files, vec![done.path().to_path_buf()]);
+        assert_eq!(files, apply_search_params(db, &params).unwrap());
+
+        // Schema expansion depends on the user's schema; the rest is fixed
+        let steps: Vec<_> = plan
+            .steps
+            .iter()
+            .filter(|step| step.op != PlanOp::ExpandTags)
+            .map(|step| (step.op, step.candidates))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (PlanOp::IndexLookup, 3),
+                (PlanOp::FileFilter, 2),
+                (PlanOp::ExcludeTags, 1),
+            ]
+        );
+        assert!(plan.to_string().contains("index lookup"));
+    }
+
     #[test]
     fn test_regex_tag_no_matches() {
         let test_db = TestDb::new("test_regex_no_match");
@@ -471,14 +1342,19 @@ mod tests {
             tags: vec!["rust.*".to_string()],
             tag_mode: SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: true,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let results = apply_search_params(db, &params).unwrap();
@@ -505,14 +1381,19 @@ mod tests {
             tags: vec!["py.*".to_string(), "ru.*".to_string()],
             tag_mode: SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: true,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let results = apply_search_params(db, &params).unwrap();
@@ -521,4 +1402,196 @@ mod tests {
         assert!(results.contains(&file2.path().to_path_buf()));
         assert!(!results.contains(&file3.path().to_path_buf()));
     }
+
+    #[test]
+    fn test_exclude_file_patterns_with_tag_excludes() {
+        let test_db = TestDb::new("test_exclude_file_patterns");
+        let db = test_db.db();
+
+        let lock = TempFile::create("Cargo.lock").unwrap();
+        let toml = TempFile::create("Cargo.toml").unwrap();
+        let draft = TempFile::create("draft.toml").unwrap();
+
+        db.add_tags(lock.path(), vec!["config".into()]).unwrap();
+        db.add_tags(toml.path(), vec!["config".into()]).unwrap();
+        db.add_tags(draft.path(), vec!["config".into(), "wip".into()])
+            .unwrap();
+
+        let mut params = SearchParams {
+            tags: vec!["config".to_string()],
+            exclude_file_patterns: vec!["*.lock".to_string()],
+            ..Default::default()
+        };
+
+        let results = apply_search_params(db, &params).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(!results.contains(&lock.path().to_path_buf()));
+        assert!(results.contains(&toml.path().to_path_buf()));
+
+        params.exclude_tags = vec!["wip".to_string()];
+        let results = apply_search_params(db, &params).unwrap();
+        assert_eq!(results, vec![toml.path().to_path_buf()]);
+
+        params.exclude_tags.clear();
+        params.exclude_file_patterns = vec![r"\.lock$".to_string()];
+        params.regex_file = true;
+        let results = apply_search_params(db, &params).unwrap();
+        assert!(!results.contains(&lock.path().to_path_buf()));
+        assert!(results.contains(&toml.path().to_path_buf()));
+    }
+
+    #[test]
+    fn test_group_by_tag_lists_shared_files_under_each_tag() {
+        let test_db = TestDb::new("test_group_by_tag");
+        let db = test_db.db();
+        let both = TempFile::create("group_both.txt").unwrap();
+        let rust = TempFile::create("group_rust.txt").unwrap();
+        let python = TempFile::create("group_python.txt").unwrap();
+        db.add_tags(both.path(), vec!["rust".into(), "python".into()])
+            .unwrap();
+        db.add_tags(rust.path(), vec!["rust".into()]).unwrap();
+        db.add_tags(python.path(), vec!["python".into()]).unwrap();
+
+        let params = SearchParams {
+            tags: vec!["rust".into(), "python".into()],
+            tag_mode: SearchMode::Any,
+            ..Default::default()
+        };
+        let files = apply_search_params(db, &params).unwrap();
+        let groups = group_by_tag(db, &params, &files).unwrap();
+
+        assert_eq!(groups.len(), 2);
+        let group_files = |i: usize| -> HashSet<PathBuf> {
+            groups[i].1.iter().map(|pair| pair.file.clone()).collect()
+        };
+        assert_eq!(groups[0].0, "rust");
+        assert_eq!(
+            group_files(0),
+            HashSet::from([both.path().to_path_buf(), rust.path().to_path_buf()])
+        );
+        assert_eq!(groups[1].0, "python");
+        assert_eq!(
+            group_files(1),
+            HashSet::from([both.path().to_path_buf(), python.path().to_path_buf()])
+        );
+    }
+
+    #[test]
+    fn test_apply_expr() {
+        let test_db = TestDb::new("test_apply_expr");
+        let db = test_db.db();
+        let tested = TempFile::create("expr_tested.rs").unwrap();
+        let bench = TempFile::create("expr_bench.rs").unwrap();
+        let old = TempFile::create("expr_old.rs").unwrap();
+        let plain = TempFile::create("expr_plain.rs").unwrap();
+        db.add_tags(tested.path(), vec!["rust".into(), "test".into()])
+            .unwrap();
+        db.add_tags(bench.path(), vec!["rust".into(), "bench".into()])
+            .unwrap();
+        db.add_tags(
+            old.path(),
+            vec!["rust".into(), "test".into(), "deprecated".into()],
+        )
+        .unwrap();
+        db.add_tags(plain.path(), vec!["rust".into()]).unwrap();
+
+        let expr =
+            crate::search::query_lang::parse("rust AND (test OR bench) AND -deprecated").unwrap();
+        let files: HashSet<PathBuf> = apply_expr(db, &SearchParams::default(), &expr)
+            .unwrap()
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            files,
+            HashSet::from([tested.path().to_path_buf(), bench.path().to_path_buf()])
+        );
+    }
+
+    #[test]
+    fn test_search_params_expression() {
+        let test_db = TestDb::new("test_search_params_expression");
+        let db = test_db.db();
+        let rust = TempFile::create("sp_expr_rust.rs").unwrap();
+        let python = TempFile::create("sp_expr_python.py").unwrap();
+        let old = TempFile::create("sp_expr_old.rs").unwrap();
+        let notes = TempFile::create("sp_expr_notes.md").unwrap();
+        db.add_tags(rust.path(), vec!["rust".into()]).unwrap();
+        db.add_tags(python.path(), vec!["python".into()]).unwrap();
+        db.add_tags(old.path(), vec!["rust".into(), "deprecated".into()])
+            .unwrap();
+        db.add_tags(notes.path(), vec!["notes".into()]).unwrap();
+
+        let params = SearchParams {
+            expression: Some(
+                crate::filters::FilterExpression::parse(
+                    "(rust OR python OR file:*.md) AND NOT deprecated",
+                )
+                .unwrap(),
+            ),
+            ..Default::default()
+        };
+        let files: HashSet<PathBuf> = apply_search_params(db, &params)
+            .unwrap()
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            files,
+            HashSet::from([
+                rust.path().to_path_buf(),
+                python.path().to_path_buf(),
+                notes.path().to_path_buf()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_negated_virtual_tags() {
+        let test_db = TestDb::new("test_negated_virtual_tags");
+        let db = test_db.db();
+        let code = TempFile::create_with_content("neg_vtag_code.rs", b"fn main() {}").unwrap();
+        let empty = TempFile::create_with_content("neg_vtag_empty.rs", b"").unwrap();
+        let notes = TempFile::create_with_content("neg_vtag_notes.md", b"# Notes").unwrap();
+        let gone = TempFile::create("neg_vtag_gone.rs").unwrap();
+        for file in [&code, &empty, &notes, &gone] {
+            db.add_tags(file.path(), vec!["project".into()]).unwrap();
+        }
+        let gone_path = gone.path().to_path_buf();
+        drop(gone);
+
+        let search = |virtual_tags: &[&str], mode, exclude: &[&str]| -> HashSet<PathBuf> {
+            let params = SearchParams {
+                tags: vec!["project".into()],
+                virtual_tags: virtual_tags.iter().map(ToString::to_string).collect(),
+                virtual_mode: mode,
+                exclude_virtual_tags: exclude.iter().map(ToString::to_string).collect(),
+                ..Default::default()
+            };
+            apply_search_params(db, &params)
+                .unwrap()
+                .into_iter()
+                .collect()
+        };
+
+        // `!` in the virtual tag list and exclude_virtual_tags are equivalent
+        let expected = HashSet::from([
+            code.path().to_path_buf(),
+            empty.path().to_path_buf(),
+            gone_path.clone(),
+        ]);
+        assert_eq!(search(&["!ext:.md"], SearchMode::All, &[]), expected);
+        assert_eq!(search(&[], SearchMode::All, &["ext:.md"]), expected);
+
+        // Exclusions apply after included tags are combined by mode; the
+        // missing file cannot be shown not to be empty, so it is dropped
+        assert_eq!(
+            search(&["ext:.rs", "ext:.md"], SearchMode::Any, &["size:empty"]),
+            HashSet::from([code.path().to_path_buf(), notes.path().to_path_buf()])
+        );
+        assert_eq!(
+            search(&["ext:.rs", "!size:empty"], SearchMode::All, &[]),
+            HashSet::from([code.path().to_path_buf()])
+        );
+    }
 }
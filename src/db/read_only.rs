@@ -0,0 +1,395 @@
+//! Read-only database handle
+//!
+//! [`ReadOnlyDatabase`] wraps a [`Database`] and exposes only its query
+//! methods, so code holding one cannot modify tags or notes. Commands that
+//! only inspect the database (`search`, `list`) use it.
+//!
+//! sled has no native read-only mode: the handle still takes sled's exclusive
+//! process lock while open. The guarantees are that opening never creates a
+//! database, that opening writes nothing to its trees, and that no write API
+//! is reachable through the handle. In particular, a database from an older
+//! version is not migrated; its tag index is read in the old layout until a
+//! writable command opens it.
+//!
+//! ```compile_fail
+//! use tagr::db::ReadOnlyDatabase;
+//!
+//! let db = ReadOnlyDatabase::open("my_db").unwrap();
+//! db.insert("file.txt", vec!["tag".into()]).unwrap(); // no such method
+//! ```
+
+use super::{Database, DbError, DbOptions, FileTags, NoteRecord, TagTimes};
+use crate::Pair;
+use crate::cli::SearchMode;
+use std::path::{Path, PathBuf};
+
+/// Database handle that only exposes query methods
+#[derive(Debug, Clone)]
+pub struct ReadOnlyDatabase {
+    db: Database,
+}
+
+/// Generate methods forwarding to the wrapped [`Database`]
+macro_rules! delegate_reads {
+    ($(
+        $(#[$meta:meta])*
+        fn $name:ident $(<$generic:ident: $bound:path>)? (&self $(, $arg:ident: $ty:ty)* $(,)?) -> $ret:ty;
+    )*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name $(<$generic: $bound>)? (&self $(, $arg: $ty)*) -> $ret {
+                self.db.$name($($arg),*)
+            }
+        )*
+    };
+}
+
+impl ReadOnlyDatabase {
+    /// Open an existing database for reading
+    ///
+    /// Never creates a database; see [`Database::open_existing`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError::DatabaseNotFound` if no database exists at `path`,
+    /// `DbError::Locked` if another process has it open, or `DbError` if the
+    /// database cannot be opened.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, DbError> {
+        Self::open_with(path, DbOptions::default())
+    }
+
+    /// Open an existing database for reading with a custom cache size
+    ///
+    /// # Errors
+    ///
+    /// Same as [`ReadOnlyDatabase::open`].
+    pub fn open_with<P: AsRef<Path>>(path: P, options: DbOptions) -> Result<Self, DbError> {
+        Database::open_existing_read_only(path.as_ref(), options).map(Self::from)
+    }
+
+    /// Access the wrapped database for crate-internal query helpers
+    ///
+    /// Callers must only use read methods.
+    pub(crate) const fn inner(&self) -> &Database {
+        &self.db
+    }
+
+    delegate_reads! {
+        /// Counter of changes to files or tags; see [`Database::last_mutation`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if the counter cannot be read.
+        fn last_mutation(&self) -> Result<Option<u64>, DbError>;
+
+        /// Get tags for a file; see [`Database::get_tags`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn get_tags<P: AsRef<Path>>(&self, file: P) -> Result<Option<Vec<String>>, DbError>;
+
+        /// Get tags for several files in input order; see [`Database::get_tags_many`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if a lookup fails or an entry cannot be decoded.
+        fn get_tags_many<P: AsRef<Path>>(&self, files: &[P]) -> Result<Vec<FileTags>, DbError>;
+
+        /// Get a file-tags pair; see [`Database::get_pair`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn get_pair<P: AsRef<Path>>(&self, file: P) -> Result<Option<Pair>, DbError>;
+
+        /// List all file-tags pairs; see [`Database::list_all`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn list_all(&self) -> Result<Vec<Pair>, DbError>;
+
+        /// Write all pairs to an SQLite file; see [`Database::export_to_sqlite`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if reading the database or writing the file fails.
+        #[cfg(feature = "sqlite")]
+        fn export_to_sqlite<P: AsRef<Path>>(&self, path: P) -> Result<(), DbError>;
+
+        /// List all decodable pairs; see [`Database::list_all_lenient`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database iteration fails.
+        fn list_all_lenient(&self) -> Result<(Vec<Pair>, Vec<DbError>), DbError>;
+
+        /// List all files; see [`Database::list_all_files`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn list_all_files(&self) -> Result<Vec<PathBuf>, DbError>;
+
+        /// List all tags; see [`Database::list_all_tags`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn list_all_tags(&self) -> Result<Vec<String>, DbError>;
+
+        /// Count files per tag; see [`Database::tag_counts`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn tag_counts(&self) -> Result<Vec<(String, usize)>, DbError>;
+
+        /// Find files with a tag; see [`Database::find_by_tag`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn find_by_tag(&self, tag: &str) -> Result<Vec<PathBuf>, DbError>;
+
+        /// Count files with a tag; see [`Database::count_files_per_tag_fast`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn count_files_per_tag_fast(&self, tag: &str) -> Result<usize, DbError>;
+
+        /// Find files with a tag, sorted by path; see [`Database::find_by_tag_sorted`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn find_by_tag_sorted(&self, tag: &str) -> Result<Vec<PathBuf>, DbError>;
+
+        /// Find files with all tags; see [`Database::find_by_all_tags`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn find_by_all_tags(&self, tags: &[String]) -> Result<Vec<PathBuf>, DbError>;
+
+        /// Find files with any tag; see [`Database::find_by_any_tag`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn find_by_any_tag(&self, tags: &[String]) -> Result<Vec<PathBuf>, DbError>;
+
+        /// Find files by path glob; see [`Database::find_by_file_glob`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if a pattern is invalid or database operations fail.
+        fn find_by_file_glob(
+            &self,
+            patterns: &[String],
+            match_all: bool,
+        ) -> Result<Vec<PathBuf>, DbError>;
+
+        /// Count files matching tags; see [`Database::file_count_for_tags`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn file_count_for_tags(
+            &self,
+            tags: &[String],
+            mode: SearchMode,
+            excludes: &[String],
+        ) -> Result<usize, DbError>;
+
+        /// Find files with a tag matching a regex; see [`Database::find_by_tag_regex`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if the pattern is invalid or database operations fail.
+        fn find_by_tag_regex(&self, pattern: &str) -> Result<Vec<PathBuf>, DbError>;
+
+        /// Find files by included and excluded tags; see [`Database::find_excluding_tags`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn find_excluding_tags(
+            &self,
+            include_tags: &[String],
+            exclude_tags: &[String]
+        ) -> Result<Vec<PathBuf>, DbError>;
+
+        /// Files with the most tags; see [`Database::most_tagged_files`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn most_tagged_files(&self, n: usize) -> Result<Vec<(PathBuf, usize)>, DbError>;
+
+        /// Files with the fewest tags; see [`Database::least_tagged_files`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn least_tagged_files(&self, n: usize) -> Result<Vec<(PathBuf, usize)>, DbError>;
+
+        /// Check if a file is in the database; see [`Database::contains`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn contains<P: AsRef<Path>>(&self, file: P) -> Result<bool, DbError>;
+
+        /// Get the note for a file; see [`Database::get_note`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn get_note<P: AsRef<Path>>(&self, file: P) -> Result<Option<NoteRecord>, DbError>;
+
+        /// Get when a file was first and last tagged; see [`Database::get_timestamps`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn get_timestamps<P: AsRef<Path>>(&self, file: P) -> Result<Option<TagTimes>, DbError>;
+
+        /// List all notes; see [`Database::list_all_notes`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn list_all_notes(&self) -> Result<Vec<(PathBuf, NoteRecord)>, DbError>;
+
+        /// Search note contents; see [`Database::search_notes`]
+        ///
+        /// # Errors
+        ///
+        /// Returns `DbError` if database operations fail.
+        fn search_notes(&self, query: &str) -> Result<Vec<(PathBuf, NoteRecord)>, DbError>;
+    }
+
+    /// Get the number of files in the database
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.db.count()
+    }
+
+    /// Iterate over all pairs as they are read; see [`Database::iter_pairs`]
+    pub fn iter_pairs(&self) -> impl Iterator<Item = Result<Pair, DbError>> + use<> {
+        self.db.iter_pairs()
+    }
+
+    /// Get the database's size on disk; see [`Database::size_on_disk`]
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if the database files cannot be inspected.
+    pub fn size_on_disk(&self) -> Result<u64, DbError> {
+        self.db.size_on_disk()
+    }
+
+    /// The cache size and limits this database was opened with
+    #[must_use]
+    pub const fn options(&self) -> DbOptions {
+        self.db.options()
+    }
+
+    /// Path of the database directory
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.db.path()
+    }
+}
+
+impl From<Database> for ReadOnlyDatabase {
+    fn from(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_read_only_missing_database() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("missing_db");
+
+        let result = ReadOnlyDatabase::open(&db_path);
+        assert!(matches!(result, Err(DbError::DatabaseNotFound(_))));
+        assert!(!db_path.exists());
+    }
+
+    #[test]
+    fn test_read_only_queries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("ro_db");
+        let file = temp_dir.path().join("file.txt");
+        std::fs::write(&file, "content").unwrap();
+
+        {
+            let db = Database::open(&db_path).unwrap();
+            db.insert(&file, vec!["rust".into()]).unwrap();
+            // As left by a version without the mutation counter
+            db.meta.remove(super::super::LAST_MUTATION_KEY).unwrap();
+            db.flush().unwrap();
+        }
+
+        let db = Database::open_read_only(&db_path).unwrap();
+        assert_eq!(db.path(), db_path);
+        // Opening read-only writes nothing, not even the counter
+        assert_eq!(db.last_mutation().unwrap(), None);
+        assert_eq!(db.count(), 1);
+        assert_eq!(db.find_by_tag("rust").unwrap(), vec![file.clone()]);
+        assert_eq!(db.get_tags(&file).unwrap(), Some(vec!["rust".to_string()]));
+    }
+
+    #[test]
+    fn test_read_only_open_skips_tag_index_migration() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("legacy_db");
+        let files: Vec<PathBuf> = ["b.txt", "a.txt"]
+            .iter()
+            .map(|name| temp_dir.path().join(name))
+            .collect();
+        // As written by a version that kept each tag's files as an unsorted list
+        let legacy: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+        let legacy = bincode::encode_to_vec(&legacy, bincode::config::standard()).unwrap();
+
+        {
+            let db = Database::open(&db_path).unwrap();
+            for file in &files {
+                std::fs::write(file, "content").unwrap();
+                db.insert(file, vec!["rust".into(), "cli".into()]).unwrap();
+            }
+            db.tags.insert("rust", legacy.clone()).unwrap();
+            db.meta.remove(super::super::TAG_INDEX_VERSION_KEY).unwrap();
+            db.flush().unwrap();
+        }
+
+        let db = Database::open_read_only(&db_path).unwrap();
+        let mut sorted = files.clone();
+        sorted.sort();
+        assert_eq!(db.find_by_tag("rust").unwrap(), sorted);
+        assert_eq!(
+            db.find_by_all_tags(&["rust".into(), "cli".into()]).unwrap(),
+            sorted
+        );
+        assert_eq!(db.count_files_per_tag_fast("rust").unwrap(), 2);
+
+        let inner = db.inner();
+        assert_eq!(inner.tags.get("rust").unwrap().unwrap().to_vec(), legacy);
+        assert!(
+            inner
+                .meta
+                .get(super::super::TAG_INDEX_VERSION_KEY)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Portable SQLite export and import (`sqlite` feature)
+//!
+//! The sled database is private to tagr; an SQLite file gives other tools a
+//! stable, queryable copy. The schema is two tables:
+//!
+//! ```sql
+//! CREATE TABLE files (id INTEGER PRIMARY KEY, path TEXT UNIQUE NOT NULL);
+//! CREATE TABLE tags (id INTEGER PRIMARY KEY, file_id INTEGER REFERENCES files(id), tag TEXT NOT NULL);
+//! ```
+
+use super::{Database, DbError, PathString};
+use crate::Pair;
+use rusqlite::{Connection, params};
+use std::path::{Path, PathBuf};
+
+const SCHEMA: &str = "
+    DROP TABLE IF EXISTS tags;
+    DROP TABLE IF EXISTS files;
+    CREATE TABLE files (id INTEGER PRIMARY KEY, path TEXT UNIQUE NOT NULL);
+    CREATE TABLE tags (
+        id INTEGER PRIMARY KEY,
+        file_id INTEGER REFERENCES files(id),
+        tag TEXT NOT NULL
+    );
+    CREATE INDEX tags_file_id ON tags(file_id);
+";
+
+/// How [`Database::import_from_sqlite`] treats files that already have tags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportConflict {
+    /// Keep the existing tags and ignore the imported ones
+    #[default]
+    Skip,
+    /// Replace the existing tags with the imported ones
+    Replace,
+    /// Add the imported tags to the existing ones
+    Merge,
+}
+
+/// Outcome of [`Database::import_from_sqlite`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Files whose tags were written
+    pub imported: usize,
+    /// Files left unchanged because they already had tags
+    pub skipped: usize,
+    /// Files in the export that no longer exist on disk
+    pub missing: Vec<PathBuf>,
+}
+
+impl Database {
+    /// Write every file-tag pair to a new SQLite database at `path`
+    ///
+    /// Existing `files` and `tags` tables at `path` are replaced; other
+    /// tables are left alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError` if reading the database fails, or
+    /// `DbError::Sqlite` if the SQLite file cannot be written.
+    pub fn export_to_sqlite<P: AsRef<Path>>(&self, path: P) -> Result<(), DbError> {
+        let mut pairs = self.list_all()?;
+        pairs.sort_by(|a, b| a.file.cmp(&b.file));
+
+        let mut conn = Connection::open(path)?;
+        let tx = conn.transaction()?;
+        tx.execute_batch(SCHEMA)?;
+        {
+            let mut insert_file = tx.prepare("INSERT INTO files (path) VALUES (?1)")?;
+            let mut insert_tag = tx.prepare("INSERT INTO tags (file_id, tag) VALUES (?1, ?2)")?;
+            for pair in &pairs {
+                let path = PathString::new(&pair.file)?;
+                let file_id = insert_file.insert(params![path.as_str()])?;
+                for tag in &pair.tags {
+                    insert_tag.execute(params![file_id, tag])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Read file-tag pairs from an SQLite export and insert them
+    ///
+    /// Files are written with [`Database::insert_pair`]; `conflict` decides
+    /// what happens to files that already have tags. Files that no longer
+    /// exist on disk are listed in [`ImportReport::missing`] instead of
+    /// failing the import.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DbError::Sqlite` if the SQLite file cannot be read or lacks
+    /// the export schema, or `DbError` if a database write fails.
+    pub fn import_from_sqlite<P: AsRef<Path>>(
+        &self,
+        path: P,
+        conflict: ImportConflict,
+    ) -> Result<ImportReport, DbError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(DbError::FileNotFound(path.display().to_string()));
+        }
+        let conn = Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let mut stmt = conn.prepare(
+            "SELECT files.path, tags.tag FROM files
+             JOIN tags ON tags.file_id = files.id
+             ORDER BY files.id, tags.id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut pairs: Vec<Pair> = Vec::new();
+        for row in rows {
+            let (file, tag) = row?;
+            let file = PathBuf::from(file);
+            match pairs.last_mut() {
+                Some(pair) if pair.file == file => {
+                    if !pair.tags.contains(&tag) {
+                        pair.tags.push(tag);
+                    }
+                }
+                _ => pairs.push(Pair::new(file, vec![tag])),
+            }
+        }
+
+        let mut report = ImportReport::default();
+        for mut pair in pairs {
+            if !pair.file.exists() {
+                report.missing.push(pair.file);
+                continue;
+            }
+            if let Some(existing) = self.get_tags(&pair.file)?.filter(|tags| !tags.is_empty()) {
+                match conflict {
+                    ImportConflict::Skip => {
+                        report.skipped += 1;
+                        continue;
+                    }
+                    ImportConflict::Replace => {}
+                    ImportConflict::Merge => {
+                        let mut tags = existing;
+                        for tag in pair.tags {
+                            if !tags.contains(&tag) {
+                                tags.push(tag);
+                            }
+                        }
+                        pair.tags = tags;
+                    }
+                }
+            }
+            self.insert_pair(&pair)?;
+            report.imported += 1;
+        }
+        self.flush()?;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TempFile, TestDb};
+
+    #[test]
+    fn test_sqlite_round_trip() {
+        let source = TestDb::new("test_sqlite_export");
+        let a = TempFile::create("sqlite_a.txt").unwrap();
+        let b = TempFile::create("sqlite_b.txt").unwrap();
+        source
+            .db()
+            .insert(a.path(), vec!["rust".into(), "cli".into()])
+            .unwrap();
+        source.db().insert(b.path(), vec!["docs".into()]).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let export = dir.path().join("tags.sqlite");
+        source.db().export_to_sqlite(&export).unwrap();
+
+        let conn = Connection::open(&export).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM tags", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 3);
+        drop(conn);
+
+        let target = TestDb::new("test_sqlite_import");
+        let report = target
+            .db()
+            .import_from_sqlite(&export, ImportConflict::Skip)
+            .unwrap();
+        assert_eq!(report.imported, 2);
+        assert!(report.missing.is_empty());
+        assert_eq!(
+            target.db().get_tags(a.path()).unwrap().unwrap(),
+            ["rust", "cli"]
+        );
+        assert_eq!(target.db().get_tags(b.path()).unwrap().unwrap(), ["docs"]);
+
+        // Exporting again overwrites the previous tables
+        source.db().export_to_sqlite(&export).unwrap();
+        let report = target
+            .db()
+            .import_from_sqlite(&export, ImportConflict::Skip)
+            .unwrap();
+        assert_eq!(report.skipped, 2);
+    }
+
+    #[test]
+    fn test_sqlite_import_conflicts() {
+        let source = TestDb::new("test_sqlite_conflict_src");
+        let file = TempFile::create("sqlite_conflict.txt").unwrap();
+        source.db().insert(file.path(), vec!["new".into()]).unwrap();
+        let gone = TempFile::create("sqlite_gone.txt").unwrap();
+        source.db().insert(gone.path(), vec!["x".into()]).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let export = dir.path().join("tags.sqlite");
+        source.db().export_to_sqlite(&export).unwrap();
+        let gone_path = gone.path().to_path_buf();
+        drop(gone);
+
+        let target = TestDb::new("test_sqlite_conflict_dst");
+        target.db().insert(file.path(), vec!["old".into()]).unwrap();
+        let tags = || target.db().get_tags(file.path()).unwrap().unwrap();
+
+        let report = target
+            .db()
+            .import_from_sqlite(&export, ImportConflict::Skip)
+            .unwrap();
+        assert_eq!((report.imported, report.skipped), (0, 1));
+        assert_eq!(report.missing, [gone_path]);
+        assert_eq!(tags(), ["old"]);
+
+        target
+            .db()
+            .import_from_sqlite(&export, ImportConflict::Merge)
+            .unwrap();
+        assert_eq!(tags(), ["old", "new"]);
+
+        target
+            .db()
+            .import_from_sqlite(&export, ImportConflict::Replace)
+            .unwrap();
+        assert_eq!(tags(), ["new"]);
+    }
+
+    #[test]
+    fn test_sqlite_import_rejects_other_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("other.sqlite");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch("CREATE TABLE notes (body TEXT)")
+            .unwrap();
+
+        let db = TestDb::new("test_sqlite_bad_schema");
+        let err = db
+            .db()
+            .import_from_sqlite(&path, ImportConflict::Skip)
+            .unwrap_err();
+        assert!(matches!(err, DbError::Sqlite(_)));
+    }
+}
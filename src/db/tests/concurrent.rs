@@ -0,0 +1,134 @@
+//! Concurrent access tests for `Database`
+//!
+//! Each test drives the same database from several threads and then checks
+//! that the `files` tree and the `tags` reverse index still agree.
+
+use crate::Pair;
+use crate::cli::{ConditionalArgs, SearchParams};
+use crate::commands::bulk::bulk_tag;
+use crate::db::Database;
+use crate::testing::{TempFile, TestDb};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::thread;
+
+const FILES_PER_THREAD: usize = 50;
+
+fn temp_files(prefix: &str) -> Vec<TempFile> {
+    (0..FILES_PER_THREAD)
+        .map(|i| TempFile::create(format!("{prefix}_{i}.txt")).unwrap())
+        .collect()
+}
+
+/// Every tag on every file must be indexed, and the index must hold nothing else
+fn assert_index_consistent(db: &Database) {
+    let pairs = db.list_all().unwrap();
+    let mut indexed = 0;
+    for tag in db.list_all_tags().unwrap() {
+        let files: HashSet<PathBuf> = db.find_by_tag(&tag).unwrap().into_iter().collect();
+        let expected: HashSet<PathBuf> = pairs
+            .iter()
+            .filter(|pair| pair.tags.contains(&tag))
+            .map(|pair| pair.file.clone())
+            .collect();
+        assert_eq!(files, expected, "index out of sync for tag '{tag}'");
+        indexed += files.len();
+    }
+    let stored: usize = pairs.iter().map(|pair| pair.tags.len()).sum();
+    assert_eq!(indexed, stored);
+}
+
+#[test]
+fn test_concurrent_insert_pair() {
+    let test_db = TestDb::new("test_concurrent_insert_pair");
+    let db = test_db.db();
+    let batches = [temp_files("a"), temp_files("b")];
+
+    thread::scope(|s| {
+        for (n, batch) in batches.iter().enumerate() {
+            s.spawn(move || {
+                for file in batch {
+                    let pair = Pair::new(
+                        file.path().to_path_buf(),
+                        vec!["shared".into(), format!("thread{n}")],
+                    );
+                    db.insert_pair(&pair).unwrap();
+                }
+            });
+        }
+    });
+
+    assert_eq!(db.count(), 2 * FILES_PER_THREAD);
+    assert_eq!(
+        db.find_by_tag("shared").unwrap().len(),
+        2 * FILES_PER_THREAD
+    );
+    assert_index_consistent(db);
+}
+
+#[test]
+fn test_concurrent_insert_and_find() {
+    let test_db = TestDb::new("test_concurrent_insert_and_find");
+    let db = test_db.db();
+    let files = temp_files("f");
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            for file in &files {
+                db.insert(file.path(), vec!["shared".into()]).unwrap();
+            }
+        });
+        s.spawn(|| {
+            let mut seen = 0;
+            while seen < FILES_PER_THREAD {
+                let found = db.find_by_tag("shared").unwrap();
+                // The index only grows while the writer runs
+                assert!(found.len() >= seen);
+                seen = found.len();
+            }
+        });
+    });
+
+    assert_index_consistent(db);
+}
+
+#[test]
+fn test_bulk_tag_during_list_all() {
+    let test_db = TestDb::new("test_bulk_tag_during_list_all");
+    let db = test_db.db();
+    let files = temp_files("bulk");
+    for file in &files {
+        db.insert(file.path(), vec!["src".into()]).unwrap();
+    }
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            let params = SearchParams {
+                tags: vec!["src".into()],
+                ..Default::default()
+            };
+            bulk_tag(
+                db,
+                params,
+                &["reviewed".into()],
+                crate::config::TagNormalization::default(),
+                &ConditionalArgs::default(),
+                false,
+                false,
+                true,
+                true,
+            )
+            .unwrap();
+        });
+        s.spawn(|| {
+            for _ in 0..20 {
+                let pairs = db.list_all().unwrap();
+                assert_eq!(pairs.len(), FILES_PER_THREAD);
+                assert!(pairs.iter().all(|pair| pair.tags.contains(&"src".into())));
+            }
+        });
+    });
+
+    assert_eq!(db.find_by_tag("reviewed").unwrap().len(), FILES_PER_THREAD);
+    assert_index_consistent(db);
+}
@@ -9,6 +9,16 @@
 //! - **`PathKey`**: Wrapper for `PathBuf` that can be serialized to `Vec<u8>` for database keys
 //! - **`PathString`**: Wrapper that guarantees a path can be represented as valid UTF-8
 //!
+//! # Tag Values
+//!
+//! Tag vectors stored in the `files` tree are wrapped in a versioned envelope:
+//! a [`TAGS_VALUE_MARKER`] byte, a version byte, then the bincode payload.
+//! [`encode_tags`] and [`decode_tags`] are the only places that know the layout;
+//! values written before versioning (a bare bincode `Vec<String>`) still decode.
+//!
+//! Entries in the `tags` tree are a bincode `BTreeSet<String>` of file paths,
+//! read and written with [`encode_file_set`] and [`decode_file_set`].
+//!
 //! # Design Rationale
 //!
 //! These types ensure type safety and proper error handling when working with paths
@@ -32,6 +42,7 @@
 
 use super::error::DbError;
 use bincode;
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 
 /// Wrapper for `PathBuf` that can be converted to `Vec<u8>` for database keys
@@ -77,6 +88,76 @@ impl AsRef<Path> for PathKey {
     }
 }
 
+/// First byte of a versioned tag value
+///
+/// Unversioned values start with bincode's varint length prefix, which is never
+/// `0xFF`, so the marker cannot be confused with legacy data.
+pub const TAGS_VALUE_MARKER: u8 = 0xFF;
+
+/// Layout version written by [`encode_tags`]
+pub const TAGS_VALUE_VERSION: u8 = 1;
+
+/// Encode a file's tags for storage in the `files` tree
+///
+/// # Errors
+///
+/// Returns `DbError` if the tags cannot be serialized.
+pub fn encode_tags(tags: &[String]) -> Result<Vec<u8>, DbError> {
+    let mut bytes = vec![TAGS_VALUE_MARKER, TAGS_VALUE_VERSION];
+    bytes.extend(bincode::encode_to_vec(tags, bincode::config::standard())?);
+    Ok(bytes)
+}
+
+/// Decode a file's tags as stored in the `files` tree
+///
+/// Dispatches on the version byte; unversioned values are read with the
+/// original layout.
+///
+/// # Errors
+///
+/// Returns `DbError::SerializeError` for an unknown version, or `DbError` if
+/// the payload cannot be deserialized.
+pub fn decode_tags(bytes: &[u8]) -> Result<Vec<String>, DbError> {
+    let payload = match bytes {
+        [TAGS_VALUE_MARKER, TAGS_VALUE_VERSION, payload @ ..] => payload,
+        [TAGS_VALUE_MARKER, version, ..] => {
+            return Err(DbError::SerializeError(format!(
+                "Unsupported tag value version {version}"
+            )));
+        }
+        legacy => legacy,
+    };
+    let (tags, _): (Vec<String>, usize) =
+        bincode::decode_from_slice(payload, bincode::config::standard())?;
+    Ok(tags)
+}
+
+/// Encode the file paths carrying a tag for storage in the `tags` tree
+///
+/// A set encodes like a sorted `Vec<String>`: a varint length prefix, then
+/// the paths in order.
+///
+/// # Errors
+///
+/// Returns `DbError` if the paths cannot be serialized.
+pub fn encode_file_set(files: &BTreeSet<String>) -> Result<Vec<u8>, DbError> {
+    Ok(bincode::encode_to_vec(files, bincode::config::standard())?)
+}
+
+/// Decode the file paths carrying a tag as stored in the `tags` tree
+///
+/// Unsorted `Vec<String>` entries written before the index was kept as a
+/// set decode too, with duplicates dropped.
+///
+/// # Errors
+///
+/// Returns `DbError` if the entry cannot be deserialized.
+pub fn decode_file_set(bytes: &[u8]) -> Result<BTreeSet<String>, DbError> {
+    let (files, _): (BTreeSet<String>, usize) =
+        bincode::decode_from_slice(bytes, bincode::config::standard())?;
+    Ok(files)
+}
+
 /// Wrapper for a path that guarantees valid UTF-8 string representation
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PathString(String);
@@ -175,6 +256,15 @@ impl Default for NoteMeta {
     }
 }
 
+/// When a file was first and most recently tagged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct TagTimes {
+    /// Unix timestamp when the file was added to the database
+    pub first_tagged: i64,
+    /// Unix timestamp of the latest change to the file's tags
+    pub last_tagged: i64,
+}
+
 /// A note attached to a file
 #[derive(
     Debug,
@@ -215,4 +215,40 @@ mod tests {
         assert_ne!(bytes_vec[1], bytes_vec[2]);
         assert_ne!(bytes_vec[0], bytes_vec[2]);
     }
+
+    #[test]
+    fn test_encode_tags_v1_roundtrip() {
+        use crate::db::types::{TAGS_VALUE_MARKER, TAGS_VALUE_VERSION, decode_tags, encode_tags};
+
+        let tags = vec!["rust".to_string(), "lang:rust".to_string()];
+        let bytes = encode_tags(&tags).unwrap();
+
+        assert_eq!(&bytes[..2], &[TAGS_VALUE_MARKER, TAGS_VALUE_VERSION]);
+        assert_eq!(decode_tags(&bytes).unwrap(), tags);
+        assert_eq!(
+            decode_tags(&encode_tags(&[]).unwrap()).unwrap(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_decode_tags_unversioned() {
+        use crate::db::types::decode_tags;
+
+        let tags = vec!["legacy".to_string()];
+        let bytes = bincode::encode_to_vec(&tags, bincode::config::standard()).unwrap();
+        assert_eq!(decode_tags(&bytes).unwrap(), tags);
+
+        let empty: Vec<String> = Vec::new();
+        let bytes = bincode::encode_to_vec(&empty, bincode::config::standard()).unwrap();
+        assert_eq!(decode_tags(&bytes).unwrap(), empty);
+    }
+
+    #[test]
+    fn test_decode_tags_unknown_version() {
+        use crate::db::types::{TAGS_VALUE_MARKER, decode_tags};
+
+        let result = decode_tags(&[TAGS_VALUE_MARKER, 99, 0]);
+        assert!(matches!(result, Err(DbError::SerializeError(_))));
+    }
 }
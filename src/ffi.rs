@@ -0,0 +1,324 @@
+//! C API for embedding tagr in non-Rust applications (`ffi` feature)
+//!
+//! A database is opened with [`tagr_open`], which returns an opaque handle
+//! that every other call takes and [`tagr_close`] releases. Strings cross the
+//! boundary as NUL-terminated UTF-8; tag and file lists are JSON arrays of
+//! strings, so `["rust", "cli"]` rather than a C array.
+//!
+//! # Memory ownership
+//!
+//! - Strings passed in are borrowed for the duration of the call only.
+//! - Strings returned by [`tagr_get_tags`] and [`tagr_find_by_tag`] are owned
+//!   by the caller and must be released with [`tagr_string_free`], never with
+//!   `free()`.
+//! - [`tagr_last_error`] returns a string owned by tagr. It stays valid until
+//!   the next tagr call on the same thread and must not be freed.
+//! - A handle must not be used after [`tagr_close`]. A handle may be shared
+//!   between threads, since database operations are thread-safe.
+//!
+//! # Errors
+//!
+//! Functions returning a pointer return NULL on failure and functions
+//! returning `int` return -1. [`tagr_last_error`] then describes the failure.
+//!
+//! # Building
+//!
+//! The crate builds as a Rust library; produce a C library with
+//! `cargo rustc --lib --release --features ffi --crate-type cdylib`.
+
+use crate::db::Database;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::ptr;
+
+/// An open database, as seen from C
+pub struct TagrHandle {
+    db: Database,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// Run `f`, recording its error for [`tagr_last_error`] and returning `failed`
+fn ffi_call<T>(failed: T, f: impl FnOnce() -> Result<T, String>) -> T {
+    clear_last_error();
+    f().unwrap_or_else(|e| {
+        set_last_error(&e);
+        failed
+    })
+}
+
+/// Borrow a C string argument as UTF-8
+///
+/// # Safety
+/// `ptr` must be NULL or point to a NUL-terminated string that outlives `'a`.
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err(format!("{name} is NULL"));
+    }
+    // SAFETY: non-null and NUL-terminated per the caller's contract
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| format!("{name} is not valid UTF-8"))
+}
+
+/// Borrow the database behind a handle
+///
+/// # Safety
+/// `handle` must be NULL or a live pointer returned by [`tagr_open`].
+unsafe fn handle_db<'a>(handle: *const TagrHandle) -> Result<&'a Database, String> {
+    // SAFETY: live handle per the caller's contract
+    unsafe { handle.as_ref() }
+        .map(|handle| &handle.db)
+        .ok_or_else(|| "handle is NULL".to_string())
+}
+
+/// Return `value` as a caller-owned JSON string
+fn json_string(value: &impl serde::Serialize) -> Result<*mut c_char, String> {
+    let json = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    CString::new(json)
+        .map(CString::into_raw)
+        .map_err(|e| e.to_string())
+}
+
+/// Open (or create) the database at `path`
+///
+/// Returns NULL on failure, e.g. when another process has it open.
+///
+/// # Safety
+/// `path` must be NULL or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tagr_open(path: *const c_char) -> *mut TagrHandle {
+    ffi_call(ptr::null_mut(), || {
+        // SAFETY: forwarded from this function's contract
+        let path = unsafe { str_arg(path, "path") }?;
+        let db = Database::open(path).map_err(|e| e.to_string())?;
+        Ok(Box::into_raw(Box::new(TagrHandle { db })))
+    })
+}
+
+/// Flush and close a database; NULL is ignored
+///
+/// # Safety
+/// `handle` must be NULL or a pointer returned by [`tagr_open`] that has not
+/// been closed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tagr_close(handle: *mut TagrHandle) {
+    if handle.is_null() {
+        return;
+    }
+    // SAFETY: handle came from Box::into_raw in tagr_open and is closed once
+    let handle = unsafe { Box::from_raw(handle) };
+    if let Err(e) = handle.db.flush() {
+        set_last_error(&e.to_string());
+    }
+}
+
+/// Add tags to a file, given as a JSON array of strings
+///
+/// The file must exist. Returns 0 on success and -1 on failure.
+///
+/// # Safety
+/// `handle` must be a live handle from [`tagr_open`]; `file` and `tags_json`
+/// must be NULL or NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tagr_add_tags(
+    handle: *const TagrHandle,
+    file: *const c_char,
+    tags_json: *const c_char,
+) -> c_int {
+    ffi_call(-1, || {
+        // SAFETY: forwarded from this function's contract
+        let (db, file, tags_json) = unsafe {
+            (
+                handle_db(handle)?,
+                str_arg(file, "file")?,
+                str_arg(tags_json, "tags_json")?,
+            )
+        };
+        let tags: Vec<String> = serde_json::from_str(tags_json)
+            .map_err(|e| format!("tags_json is not a JSON array of strings: {e}"))?;
+        db.add_tags(file, tags).map_err(|e| e.to_string())?;
+        Ok(0)
+    })
+}
+
+/// A file's tags as a JSON array; `[]` when the file is not in the database
+///
+/// Returns NULL on failure. Free the result with [`tagr_string_free`].
+///
+/// # Safety
+/// `handle` must be a live handle from [`tagr_open`]; `file` must be NULL or
+/// a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tagr_get_tags(
+    handle: *const TagrHandle,
+    file: *const c_char,
+) -> *mut c_char {
+    ffi_call(ptr::null_mut(), || {
+        // SAFETY: forwarded from this function's contract
+        let (db, file) = unsafe { (handle_db(handle)?, str_arg(file, "file")?) };
+        let tags = db
+            .get_tags(file)
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default();
+        json_string(&tags)
+    })
+}
+
+/// Paths of files carrying `tag`, as a JSON array
+///
+/// Returns NULL on failure. Free the result with [`tagr_string_free`].
+///
+/// # Safety
+/// `handle` must be a live handle from [`tagr_open`]; `tag` must be NULL or a
+/// NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tagr_find_by_tag(
+    handle: *const TagrHandle,
+    tag: *const c_char,
+) -> *mut c_char {
+    ffi_call(ptr::null_mut(), || {
+        // SAFETY: forwarded from this function's contract
+        let (db, tag) = unsafe { (handle_db(handle)?, str_arg(tag, "tag")?) };
+        let mut files = db.find_by_tag(tag).map_err(|e| e.to_string())?;
+        files.sort();
+        json_string(&files)
+    })
+}
+
+/// Free a string returned by tagr; NULL is ignored
+///
+/// # Safety
+/// `s` must be NULL or a string returned by [`tagr_get_tags`] or
+/// [`tagr_find_by_tag`] that has not been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tagr_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: s came from CString::into_raw and is freed once
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// The error from the last failed call on this thread, or NULL
+///
+/// The string is owned by tagr and valid until the next tagr call on this
+/// thread; do not free it.
+#[unsafe(no_mangle)]
+pub extern "C" fn tagr_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempFile;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    /// Take ownership of a returned string
+    fn take(s: *mut c_char) -> String {
+        assert!(!s.is_null(), "call failed: {}", last_error());
+        let value = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        unsafe { tagr_string_free(s) };
+        value
+    }
+
+    fn last_error() -> String {
+        let err = tagr_last_error();
+        if err.is_null() {
+            String::new()
+        } else {
+            unsafe { CStr::from_ptr(err) }
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    #[test]
+    fn test_ffi_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = TempFile::create("ffi_main.rs").unwrap();
+        let file_path = c(file.path().to_str().unwrap());
+
+        let handle = unsafe { tagr_open(c(dir.path().join("db").to_str().unwrap()).as_ptr()) };
+        assert!(!handle.is_null(), "{}", last_error());
+
+        let status =
+            unsafe { tagr_add_tags(handle, file_path.as_ptr(), c(r#"["rust","cli"]"#).as_ptr()) };
+        assert_eq!(status, 0, "{}", last_error());
+
+        let tags: Vec<String> =
+            serde_json::from_str(&take(unsafe { tagr_get_tags(handle, file_path.as_ptr()) }))
+                .unwrap();
+        assert_eq!(tags, ["rust", "cli"]);
+
+        // Tags are appended in order, skipping ones the file already has
+        let status =
+            unsafe { tagr_add_tags(handle, file_path.as_ptr(), c(r#"["cli","ffi"]"#).as_ptr()) };
+        assert_eq!(status, 0, "{}", last_error());
+        let tags: Vec<String> =
+            serde_json::from_str(&take(unsafe { tagr_get_tags(handle, file_path.as_ptr()) }))
+                .unwrap();
+        assert_eq!(tags, ["rust", "cli", "ffi"]);
+
+        let files: Vec<String> = serde_json::from_str(&take(unsafe {
+            tagr_find_by_tag(handle, c("rust").as_ptr())
+        }))
+        .unwrap();
+        assert_eq!(files, [file.path().to_str().unwrap()]);
+
+        let untagged = c(dir.path().join("other.txt").to_str().unwrap());
+        assert_eq!(
+            take(unsafe { tagr_get_tags(handle, untagged.as_ptr()) }),
+            "[]"
+        );
+
+        unsafe { tagr_close(handle) };
+    }
+
+    #[test]
+    fn test_handle_is_thread_safe() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<TagrHandle>();
+    }
+
+    #[test]
+    fn test_ffi_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let handle = unsafe { tagr_open(c(dir.path().join("db").to_str().unwrap()).as_ptr()) };
+        assert!(!handle.is_null());
+        assert!(tagr_last_error().is_null());
+
+        let missing = c(dir.path().join("missing.txt").to_str().unwrap());
+        let status = unsafe { tagr_add_tags(handle, missing.as_ptr(), c(r#"["x"]"#).as_ptr()) };
+        assert_eq!(status, -1);
+        assert!(!last_error().is_empty());
+
+        let status = unsafe { tagr_add_tags(handle, missing.as_ptr(), c("not json").as_ptr()) };
+        assert_eq!(status, -1);
+        assert!(last_error().contains("tags_json"));
+
+        assert!(unsafe { tagr_find_by_tag(handle, ptr::null()) }.is_null());
+        assert!(last_error().contains("tag is NULL"));
+        assert!(unsafe { tagr_get_tags(ptr::null(), missing.as_ptr()) }.is_null());
+
+        unsafe {
+            tagr_close(handle);
+            tagr_close(ptr::null_mut());
+            tagr_string_free(ptr::null_mut());
+        }
+    }
+}
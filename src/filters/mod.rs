@@ -47,8 +47,11 @@ pub mod operations;
 pub mod types;
 
 pub use error::FilterError;
-pub use operations::FilterManager;
-pub use types::{FileMode, Filter, FilterCriteria, FilterStorage, TagMode, validate_filter_name};
+pub use operations::{FilterManager, ImportResolution};
+pub use types::{
+    FileMode, Filter, FilterCriteria, FilterExpression, FilterStorage, TagMode,
+    validate_filter_name,
+};
 
 use std::path::PathBuf;
 
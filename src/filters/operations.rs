@@ -4,10 +4,21 @@
 //! idiomatic Rust APIs.
 
 use super::error::FilterError;
-use super::types::{Filter, FilterCriteria, FilterStorage};
+use super::types::{Filter, FilterCriteria, FilterStorage, validate_filter_name};
 use std::fs;
 use std::path::PathBuf;
 
+/// How to handle an imported filter whose name already exists
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportResolution {
+    /// Replace the existing filter with the imported one
+    Overwrite,
+    /// Keep the existing filter and drop the imported one
+    Skip,
+    /// Import the filter under a new name
+    Rename(String),
+}
+
 /// Manager for filter operations
 ///
 /// Encapsulates the storage path and provides methods for filter CRUD operations.
@@ -209,6 +220,30 @@ impl FilterManager {
         Ok(storage.filters)
     }
 
+    /// Rename a tag in every saved filter that references it
+    ///
+    /// Returns the names of the updated filters. The storage file is only
+    /// written when at least one filter changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FilterError` if the storage file cannot be loaded or saved.
+    pub fn rename_tag(&self, old: &str, new: &str) -> Result<Vec<String>, FilterError> {
+        let mut storage = self.load()?;
+
+        let updated: Vec<String> = storage
+            .filters
+            .iter_mut()
+            .filter_map(|f| f.criteria.rename_tag(old, new).then(|| f.name.clone()))
+            .collect();
+
+        if !updated.is_empty() {
+            self.save(&storage)?;
+        }
+
+        Ok(updated)
+    }
+
     /// Record filter usage (increment use count, update `last_used` timestamp)
     ///
     /// # Errors
@@ -280,6 +315,8 @@ impl FilterManager {
     /// * `import_path` - Path to the file to import from
     /// * `overwrite` - If true, overwrite existing filters with the same name
     /// * `skip_existing` - If true, skip filters that already exist (only if overwrite is false)
+    /// * `resolve` - Called for each name conflict when neither flag is set;
+    ///   a renamed filter that still conflicts is passed to it again
     ///
     /// # Returns
     /// A tuple of (`imported_count`, `skipped_count`)
@@ -288,12 +325,15 @@ impl FilterManager {
     ///
     /// Returns `FilterError` if:
     /// - The import file cannot be read
+    /// - A filter conflicts and there is no flag or resolver to settle it
+    /// - A resolver renames a filter to an invalid name
     /// - The storage file cannot be saved
     pub fn import(
         &self,
         import_path: &PathBuf,
         overwrite: bool,
         skip_existing: bool,
+        mut resolve: Option<&mut dyn FnMut(&Filter) -> ImportResolution>,
     ) -> Result<(usize, usize), FilterError> {
         let mut storage = self.load()?;
 
@@ -303,24 +343,40 @@ impl FilterManager {
         let mut imported = 0;
         let mut skipped = 0;
 
-        for filter in import_storage.filters {
-            if storage.contains(&filter.name) {
-                if overwrite {
-                    storage
-                        .update(filter)
-                        .map_err(FilterError::InvalidCriteria)?;
-                    imported += 1;
+        'filters: for mut filter in import_storage.filters {
+            while storage.contains(&filter.name) {
+                let resolution = if overwrite {
+                    ImportResolution::Overwrite
                 } else if skip_existing {
-                    skipped += 1;
+                    ImportResolution::Skip
+                } else if let Some(resolve) = resolve.as_mut() {
+                    resolve(&filter)
                 } else {
                     return Err(FilterError::AlreadyExists(filter.name));
+                };
+                match resolution {
+                    ImportResolution::Overwrite => {
+                        storage
+                            .update(filter)
+                            .map_err(FilterError::InvalidCriteria)?;
+                        imported += 1;
+                        continue 'filters;
+                    }
+                    ImportResolution::Skip => {
+                        skipped += 1;
+                        continue 'filters;
+                    }
+                    ImportResolution::Rename(name) => {
+                        validate_filter_name(&name)
+                            .map_err(|e| FilterError::InvalidName(name.clone(), e))?;
+                        filter.name = name;
+                    }
                 }
-            } else {
-                storage
-                    .add(filter.clone())
-                    .map_err(|_e| FilterError::AlreadyExists(filter.name.clone()))?;
-                imported += 1;
             }
+            storage
+                .add(filter.clone())
+                .map_err(|_e| FilterError::AlreadyExists(filter.name.clone()))?;
+            imported += 1;
         }
 
         self.save(&storage)?;
@@ -432,7 +488,9 @@ mod tests {
 
         manager.export(&export_path, &[]).unwrap();
 
-        let (imported, skipped) = import_manager.import(&export_path, false, false).unwrap();
+        let (imported, skipped) = import_manager
+            .import(&export_path, false, false, None)
+            .unwrap();
         assert_eq!(imported, 2);
         assert_eq!(skipped, 0);
 
@@ -443,4 +501,63 @@ mod tests {
         let _ = fs::remove_file(&export_path);
         let _ = fs::remove_file(&import_path);
     }
+
+    #[test]
+    fn test_import_resolves_conflicts_with_resolver() {
+        let source_path = temp_path("resolver_source");
+        let export_path = temp_path("resolver_export");
+        let target_path = temp_path("resolver_target");
+        for path in [&source_path, &export_path, &target_path] {
+            let _ = fs::remove_file(path);
+        }
+
+        let source = FilterManager::without_backup(source_path.clone());
+        let target = FilterManager::without_backup(target_path.clone());
+        let criteria = FilterCriteria {
+            tags: vec!["test".to_string()],
+            ..Default::default()
+        };
+        for name in ["keep", "replace", "copy"] {
+            source
+                .create(name, "imported".to_string(), criteria.clone())
+                .unwrap();
+            target
+                .create(name, "original".to_string(), criteria.clone())
+                .unwrap();
+        }
+        target
+            .create("copy-2", "original".to_string(), criteria)
+            .unwrap();
+        source.export(&export_path, &[]).unwrap();
+
+        let mut asked = Vec::new();
+        let mut resolver = |filter: &Filter| {
+            asked.push(filter.name.clone());
+            match filter.name.as_str() {
+                "keep" => ImportResolution::Skip,
+                "replace" => ImportResolution::Overwrite,
+                "copy" => ImportResolution::Rename("copy-2".to_string()),
+                _ => ImportResolution::Rename("copy-3".to_string()),
+            }
+        };
+        let (imported, skipped) = target
+            .import(&export_path, false, false, Some(&mut resolver))
+            .unwrap();
+        assert_eq!((imported, skipped), (2, 1));
+        assert_eq!(asked, vec!["keep", "replace", "copy", "copy-2"]);
+
+        assert_eq!(target.get("keep").unwrap().description, "original");
+        assert_eq!(target.get("replace").unwrap().description, "imported");
+        assert_eq!(target.get("copy").unwrap().description, "original");
+        assert_eq!(target.get("copy-2").unwrap().description, "original");
+        assert_eq!(target.get("copy-3").unwrap().description, "imported");
+
+        let mut invalid = |_: &Filter| ImportResolution::Rename(String::new());
+        let result = target.import(&export_path, false, false, Some(&mut invalid));
+        assert!(matches!(result, Err(FilterError::InvalidName(..))));
+
+        for path in [&source_path, &export_path, &target_path] {
+            let _ = fs::remove_file(path);
+        }
+    }
 }
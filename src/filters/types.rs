@@ -2,34 +2,23 @@
 //!
 //! This module defines the core data structures for saved filters:
 //! - `FilterCriteria`: The search criteria (tags, file patterns, exclusions, etc.)
+//! - `FilterExpression`: Grouped boolean expressions over tags and file patterns
 //! - `FilterMetadata`: Metadata about filter usage and creation
 //! - `Filter`: Complete filter with criteria and metadata
 //! - `FilterStorage`: Container for all filters
 
 use crate::cli::SearchMode;
+use crate::search::query_lang::{self, Expr, ParseError};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
 
 /// Filter criteria representing search parameters
 ///
 /// This matches the search/browse command parameters and can be serialized to TOML.
-///
-/// # Future Enhancement
-///
-/// TODO: Consider refactoring to structured criteria for composable queries:
-/// - `TagCriterion { text: String, exclude: bool, regex: bool }`
-/// - `FileCriterion { pattern: String, exclude: bool, regex: bool }`
-/// - Support expression trees: `(tag1 AND tag2) OR (file1 AND NOT tag3)`
-///
-/// This would enable complex filter expressions like:
-/// ```text
-/// (tag matching regex ".*doc.*" excluding "doctor") AND
-/// (tag matching either "text" OR "code") OR
-/// (file name "*.md")
-/// ```
-///
-/// The current flat structure handles most use cases well. Consider this evolution
-/// when users request complex query expressions or when building a filter DSL.
+/// Queries the flat lists cannot express, such as
+/// `(rust OR python) AND NOT deprecated`, go in `expression`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FilterCriteria {
     /// Tags to search for
@@ -40,6 +29,11 @@ pub struct FilterCriteria {
     #[serde(default)]
     pub tag_mode: TagMode,
 
+    /// Grouped expression over tags and file patterns; supersedes `tags` and
+    /// `tag_mode` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<FilterExpression>,
+
     /// File patterns to filter by (glob or regex)
     #[serde(default)]
     pub file_patterns: Vec<String>,
@@ -52,6 +46,10 @@ pub struct FilterCriteria {
     #[serde(default)]
     pub excludes: Vec<String>,
 
+    /// File patterns to exclude (glob or regex, following `regex_file`)
+    #[serde(default)]
+    pub exclude_file_patterns: Vec<String>,
+
     /// Use regex for tag matching
     #[serde(default)]
     pub regex_tag: bool,
@@ -71,6 +69,10 @@ pub struct FilterCriteria {
     /// How to combine multiple virtual tags ("all" = AND, "any" = OR)
     #[serde(default)]
     pub virtual_mode: TagMode,
+
+    /// Virtual tags whose matching files are dropped (e.g., "modified:today")
+    #[serde(default)]
+    pub exclude_virtual_tags: Vec<String>,
 }
 
 impl FilterCriteria {
@@ -111,6 +113,16 @@ impl FilterCriteria {
             }
         }
 
+        for pattern in &other.exclude_file_patterns {
+            if !self.exclude_file_patterns.contains(pattern) {
+                self.exclude_file_patterns.push(pattern.clone());
+            }
+        }
+
+        if other.expression.is_some() {
+            self.expression.clone_from(&other.expression);
+        }
+
         self.regex_tag = self.regex_tag || other.regex_tag;
         self.regex_file = self.regex_file || other.regex_file;
         self.glob_files = self.glob_files || other.glob_files;
@@ -119,6 +131,37 @@ impl FilterCriteria {
         // unless the user explicitly provides mode flags in the CLI
     }
 
+    /// Check whether `tag` appears in the included or excluded tags or the expression
+    #[must_use]
+    pub fn references_tag(&self, tag: &str) -> bool {
+        self.tags.iter().chain(&self.excludes).any(|t| t == tag)
+            || self
+                .expression
+                .as_ref()
+                .is_some_and(|expr| expr.references_tag(tag))
+    }
+
+    /// Replace `old` with `new` in the included and excluded tags and the expression
+    ///
+    /// Duplicates created by the rename are dropped. Returns `true` if the
+    /// criteria referenced `old`.
+    pub fn rename_tag(&mut self, old: &str, new: &str) -> bool {
+        let renamed = self.references_tag(old);
+        if let Some(expr) = &mut self.expression {
+            expr.rename_tag(old, new);
+        }
+        for tags in [&mut self.tags, &mut self.excludes] {
+            let mut seen = std::collections::HashSet::new();
+            tags.retain_mut(|t| {
+                if t == old {
+                    *t = new.to_string();
+                }
+                seen.insert(t.clone())
+            });
+        }
+        renamed
+    }
+
     /// Validate the criteria
     ///
     /// # Errors
@@ -127,8 +170,14 @@ impl FilterCriteria {
     /// - No tags or file patterns are specified
     /// - Regex patterns are invalid when regex mode is enabled
     pub fn validate(&self) -> Result<(), String> {
-        if self.tags.is_empty() && self.file_patterns.is_empty() {
-            return Err("Filter must specify at least one tag or file pattern".to_string());
+        if self.tags.is_empty() && self.file_patterns.is_empty() && self.expression.is_none() {
+            return Err(
+                "Filter must specify at least one tag, file pattern or expression".to_string(),
+            );
+        }
+
+        if let Some(expr) = &self.expression {
+            expr.validate()?;
         }
 
         if self.regex_tag {
@@ -156,13 +205,16 @@ impl FilterCriteria {
 pub struct FilterCriteriaBuilder {
     tags: Vec<String>,
     tag_mode: Option<TagMode>,
+    expression: Option<FilterExpression>,
     file_patterns: Vec<String>,
     file_mode: Option<FileMode>,
     excludes: Vec<String>,
+    exclude_file_patterns: Vec<String>,
     regex_tag: bool,
     regex_file: bool,
     virtual_tags: Vec<String>,
     virtual_mode: Option<TagMode>,
+    exclude_virtual_tags: Vec<String>,
 }
 
 impl FilterCriteriaBuilder {
@@ -187,6 +239,13 @@ impl FilterCriteriaBuilder {
         self
     }
 
+    /// Set a grouped expression, superseding tags and tag mode
+    #[must_use]
+    pub fn expression(mut self, expression: FilterExpression) -> Self {
+        self.expression = Some(expression);
+        self
+    }
+
     /// Add file patterns to filter by
     #[must_use]
     pub fn file_patterns(mut self, patterns: Vec<String>) -> Self {
@@ -222,6 +281,13 @@ impl FilterCriteriaBuilder {
         self
     }
 
+    /// Add file patterns to exclude
+    #[must_use]
+    pub fn exclude_file_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.exclude_file_patterns = patterns;
+        self
+    }
+
     /// Enable regex matching for tags
     #[must_use]
     pub const fn regex_tag(mut self, enabled: bool) -> Self {
@@ -257,20 +323,30 @@ impl FilterCriteriaBuilder {
         self
     }
 
+    /// Add virtual tags whose matching files are dropped
+    #[must_use]
+    pub fn exclude_virtual_tags(mut self, tags: Vec<String>) -> Self {
+        self.exclude_virtual_tags = tags;
+        self
+    }
+
     /// Build the `FilterCriteria`
     #[must_use]
     pub fn build(self) -> FilterCriteria {
         FilterCriteria {
             tags: self.tags,
             tag_mode: self.tag_mode.unwrap_or(TagMode::All),
+            expression: self.expression,
             file_patterns: self.file_patterns,
             file_mode: self.file_mode.unwrap_or(FileMode::Any),
             excludes: self.excludes,
+            exclude_file_patterns: self.exclude_file_patterns,
             regex_tag: self.regex_tag,
             regex_file: self.regex_file,
             glob_files: false,
             virtual_tags: self.virtual_tags,
             virtual_mode: self.virtual_mode.unwrap_or(TagMode::All),
+            exclude_virtual_tags: self.exclude_virtual_tags,
         }
     }
 }
@@ -280,14 +356,17 @@ impl Default for FilterCriteria {
         Self {
             tags: Vec::new(),
             tag_mode: TagMode::All,
+            expression: None,
             file_patterns: Vec::new(),
             file_mode: FileMode::Any,
             excludes: Vec::new(),
+            exclude_file_patterns: Vec::new(),
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: Vec::new(),
             virtual_mode: TagMode::All,
+            exclude_virtual_tags: Vec::new(),
         }
     }
 }
@@ -350,6 +429,172 @@ impl From<FileMode> for SearchMode {
     }
 }
 
+/// Prefix marking a file pattern term in a [`FilterExpression`]
+pub const FILE_PATTERN_PREFIX: &str = "file:";
+
+/// Boolean expression over tags and file patterns
+///
+/// Uses the `search --expr` syntax, where `file:GLOB` terms match the full
+/// file path:
+///
+/// ```text
+/// (rust OR python) AND NOT deprecated AND (tutorial OR file:*.md)
+/// ```
+///
+/// Serialized to TOML as its string form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum FilterExpression {
+    /// All sub-expressions must match
+    And(Vec<Self>),
+    /// Any sub-expression must match
+    Or(Vec<Self>),
+    /// The sub-expression must not match
+    Not(Box<Self>),
+    /// A tag (also matches its descendants unless hierarchy is disabled)
+    Tag(String),
+    /// A glob matched against the full file path
+    FilePattern(String),
+}
+
+impl FilterExpression {
+    /// Parse an expression such as `(rust OR python) AND NOT deprecated`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tagr::filters::FilterExpression;
+    ///
+    /// let expr = FilterExpression::parse("(rust OR python) AND NOT file:*.bak").unwrap();
+    /// assert_eq!(expr.to_string(), "(rust OR python) AND NOT file:*.bak");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] pointing at the first token that does not fit
+    /// the grammar.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        query_lang::parse(input).map(Self::from)
+    }
+
+    /// Evaluate the expression against a file's path and tags
+    ///
+    /// With `hierarchy`, a tag also matches its descendants (`lang` matches
+    /// `lang:rust`). Invalid globs never match; see [`Self::validate`].
+    #[must_use]
+    pub fn matches(&self, file: &Path, file_tags: &[String], hierarchy: bool) -> bool {
+        match self {
+            Self::And(terms) => terms
+                .iter()
+                .all(|term| term.matches(file, file_tags, hierarchy)),
+            Self::Or(terms) => terms
+                .iter()
+                .any(|term| term.matches(file, file_tags, hierarchy)),
+            Self::Not(inner) => !inner.matches(file, file_tags, hierarchy),
+            Self::Tag(tag) => query_lang::tag_matches(tag, file_tags, hierarchy),
+            Self::FilePattern(pattern) => glob::Pattern::new(pattern)
+                .is_ok_and(|glob| file.to_str().is_some_and(|path| glob.matches(path))),
+        }
+    }
+
+    /// Check whether the expression contains `tag` as a term
+    #[must_use]
+    pub fn references_tag(&self, tag: &str) -> bool {
+        match self {
+            Self::And(terms) | Self::Or(terms) => terms.iter().any(|term| term.references_tag(tag)),
+            Self::Not(inner) => inner.references_tag(tag),
+            Self::Tag(t) => t == tag,
+            Self::FilePattern(_) => false,
+        }
+    }
+
+    /// Replace every `old` tag term with `new`
+    pub fn rename_tag(&mut self, old: &str, new: &str) {
+        match self {
+            Self::And(terms) | Self::Or(terms) => {
+                for term in terms {
+                    term.rename_tag(old, new);
+                }
+            }
+            Self::Not(inner) => inner.rename_tag(old, new),
+            Self::Tag(t) if t == old => *t = new.to_string(),
+            Self::Tag(_) | Self::FilePattern(_) => {}
+        }
+    }
+
+    /// Check that every file pattern is a valid glob
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first invalid pattern.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::And(terms) | Self::Or(terms) => terms.iter().try_for_each(Self::validate),
+            Self::Not(inner) => inner.validate(),
+            Self::Tag(_) => Ok(()),
+            Self::FilePattern(pattern) => glob::Pattern::new(pattern)
+                .map(|_| ())
+                .map_err(|e| format!("Invalid file pattern in expression: {pattern}: {e}")),
+        }
+    }
+}
+
+impl From<Expr> for FilterExpression {
+    /// Convert a tag expression, turning `file:GLOB` tags into file patterns
+    fn from(expr: Expr) -> Self {
+        match expr {
+            Expr::Tag(tag) => match tag.strip_prefix(FILE_PATTERN_PREFIX) {
+                Some(pattern) if !pattern.is_empty() => Self::FilePattern(pattern.to_string()),
+                _ => Self::Tag(tag),
+            },
+            Expr::Not(inner) => Self::Not(Box::new(Self::from(*inner))),
+            Expr::And(terms) => Self::And(terms.into_iter().map(Self::from).collect()),
+            Expr::Or(terms) => Self::Or(terms.into_iter().map(Self::from).collect()),
+        }
+    }
+}
+
+impl fmt::Display for FilterExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, terms: &[Self], op: &str| {
+            for (i, term) in terms.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " {op} ")?;
+                }
+                match term {
+                    Self::And(_) | Self::Or(_) => write!(f, "({term})")?,
+                    _ => write!(f, "{term}")?,
+                }
+            }
+            Ok(())
+        };
+        match self {
+            Self::And(terms) => join(f, terms, "AND"),
+            Self::Or(terms) => join(f, terms, "OR"),
+            Self::Not(inner) => match inner.as_ref() {
+                Self::And(_) | Self::Or(_) => write!(f, "NOT ({inner})"),
+                _ => write!(f, "NOT {inner}"),
+            },
+            Self::Tag(tag) => write!(f, "{tag}"),
+            Self::FilePattern(pattern) => write!(f, "{FILE_PATTERN_PREFIX}{pattern}"),
+        }
+    }
+}
+
+impl TryFrom<String> for FilterExpression {
+    type Error = ParseError;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        Self::parse(&input)
+    }
+}
+
+impl From<FilterExpression> for String {
+    fn from(expr: FilterExpression) -> Self {
+        expr.to_string()
+    }
+}
+
 /// Filter metadata (usage statistics and timestamps)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FilterMetadata {
@@ -454,7 +699,7 @@ impl Filter {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct FilterStorage {
     /// All saved filters
-    #[serde(rename = "filter")]
+    #[serde(rename = "filter", default)]
     pub filters: Vec<Filter>,
 }
 
@@ -536,7 +781,7 @@ impl FilterStorage {
     #[must_use]
     pub fn most_used(&self) -> Vec<&Filter> {
         let mut sorted: Vec<&Filter> = self.filters.iter().collect();
-        sorted.sort_by(|a, b| b.use_count.cmp(&a.use_count));
+        sorted.sort_by_key(|f| std::cmp::Reverse(f.use_count));
         sorted
     }
 
@@ -544,7 +789,7 @@ impl FilterStorage {
     #[must_use]
     pub fn recently_used(&self) -> Vec<&Filter> {
         let mut sorted: Vec<&Filter> = self.filters.iter().collect();
-        sorted.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+        sorted.sort_by_key(|f| std::cmp::Reverse(f.last_used));
         sorted
     }
 }
@@ -628,6 +873,11 @@ impl std::fmt::Display for FilterCriteria {
             )?;
         }
 
+        // Expression
+        if let Some(expr) = &self.expression {
+            writeln!(f, "Expression: {expr}")?;
+        }
+
         // File patterns
         if self.file_patterns.is_empty() {
             writeln!(f, "File Patterns: (none)")?;
@@ -648,6 +898,14 @@ impl std::fmt::Display for FilterCriteria {
             writeln!(f, "Excludes: {}", self.excludes.join(", "))?;
         }
 
+        if !self.exclude_file_patterns.is_empty() {
+            writeln!(
+                f,
+                "Excluded File Patterns: {}",
+                self.exclude_file_patterns.join(", ")
+            )?;
+        }
+
         // Virtual tags
         if !self.virtual_tags.is_empty() {
             writeln!(
@@ -661,6 +919,14 @@ impl std::fmt::Display for FilterCriteria {
             )?;
         }
 
+        if !self.exclude_virtual_tags.is_empty() {
+            writeln!(
+                f,
+                "Excluded Virtual Tags: {}",
+                self.exclude_virtual_tags.join(", ")
+            )?;
+        }
+
         // Regex modes
         if self.regex_tag || self.regex_file {
             let mut regex_modes = Vec::new();
@@ -710,11 +976,14 @@ mod tests {
             file_patterns: vec!["*.rs".to_string()],
             file_mode: FileMode::Any,
             excludes: vec!["test".to_string()],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: Vec::new(),
             virtual_mode: TagMode::All,
+            exclude_virtual_tags: Vec::new(),
+            expression: None,
         };
 
         let additional = FilterCriteria {
@@ -723,11 +992,14 @@ mod tests {
             file_patterns: vec!["*.md".to_string()],
             file_mode: FileMode::All, // This should be ignored
             excludes: vec!["deprecated".to_string()],
+            exclude_file_patterns: vec![],
             regex_tag: true,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec!["size:>1MB".to_string()],
             virtual_mode: TagMode::All,
+            exclude_virtual_tags: vec![],
+            expression: None,
         };
 
         base.merge(&additional);
@@ -779,11 +1051,14 @@ mod tests {
                 file_patterns: vec!["*.rs".to_string()],
                 file_mode: FileMode::Any,
                 excludes: vec![],
+                exclude_file_patterns: vec![],
                 regex_tag: false,
                 regex_file: false,
                 glob_files: false,
                 virtual_tags: Vec::new(),
                 virtual_mode: TagMode::All,
+                exclude_virtual_tags: Vec::new(),
+                expression: None,
             },
         );
 
@@ -801,4 +1076,54 @@ mod tests {
         assert_eq!(deserialized.filters.len(), 1);
         assert_eq!(deserialized.filters[0].name, "rust-tutorials");
     }
+
+    #[test]
+    fn test_filter_expression_matches_groups_and_file_patterns() {
+        let expr = FilterExpression::parse("(rust OR python) AND NOT deprecated AND file:*/src/*")
+            .unwrap();
+        let tags = |t: &[&str]| t.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert!(expr.matches(Path::new("/p/src/main.rs"), &tags(&["rust"]), true));
+        assert!(expr.matches(Path::new("/p/src/app.py"), &tags(&["python"]), true));
+        assert!(!expr.matches(
+            Path::new("/p/src/old.rs"),
+            &tags(&["rust", "deprecated"]),
+            true
+        ));
+        assert!(!expr.matches(Path::new("/p/docs/guide.md"), &tags(&["rust"]), true));
+        assert!(!expr.matches(Path::new("/p/src/main.go"), &tags(&["go"]), true));
+
+        let hierarchical = FilterExpression::parse("lang AND NOT lang:go").unwrap();
+        assert!(hierarchical.matches(Path::new("/a"), &tags(&["lang:rust"]), true));
+        assert!(!hierarchical.matches(Path::new("/a"), &tags(&["lang:rust"]), false));
+        assert!(!hierarchical.matches(Path::new("/a"), &tags(&["lang:go"]), true));
+    }
+
+    #[test]
+    fn test_filter_expression_round_trips_through_toml() {
+        let criteria = FilterCriteria::builder()
+            .expression(FilterExpression::parse("a AND (b OR NOT file:*.bak)").unwrap())
+            .build();
+        assert!(criteria.validate().is_ok());
+
+        let toml = toml::to_string(&criteria).unwrap();
+        assert!(toml.contains("expression = \"a AND (b OR NOT file:*.bak)\""));
+        let back: FilterCriteria = toml::from_str(&toml).unwrap();
+        assert_eq!(back.expression, criteria.expression);
+
+        assert!(toml::from_str::<FilterCriteria>("expression = \"a AND\"").is_err());
+    }
+
+    #[test]
+    fn test_filter_expression_rename_tag() {
+        let mut criteria = FilterCriteria::builder()
+            .expression(FilterExpression::parse("old OR NOT (other AND old)").unwrap())
+            .build();
+        assert!(criteria.references_tag("old"));
+        assert!(criteria.rename_tag("old", "new"));
+        assert_eq!(
+            criteria.expression.unwrap().to_string(),
+            "new OR NOT (other AND new)"
+        );
+    }
 }
@@ -0,0 +1,162 @@
+//! User-defined commands run after tagging
+//!
+//! A hook is a shell command template set in `config.toml`:
+//!
+//! ```toml
+//! on_tag = "my-indexer update {file} {tags}"
+//! ```
+//!
+//! After tags are added to a file, `{file}` is replaced with the shell-quoted
+//! path and `{tags}` with the shell-quoted tags that were added, separated by
+//! spaces; tags the file already had are left out, and a file that gained
+//! none runs no hook. The command runs through `sh -c`, like `--exec`. A failing hook
+//! prints a warning; the tags stay added.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Runs a hook's command line
+pub trait HookExecutor: Send + Sync {
+    /// Run `command`
+    ///
+    /// # Errors
+    /// Returns a description of the failure if the command could not be run
+    /// or exited unsuccessfully.
+    fn execute(&self, command: &str) -> Result<(), String>;
+}
+
+/// Runs hook commands with `sh -c`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShellExecutor;
+
+impl HookExecutor for ShellExecutor {
+    fn execute(&self, command: &str) -> Result<(), String> {
+        match std::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .status()
+        {
+            Ok(status) if status.success() => Ok(()),
+            Ok(status) => Err(format!("command failed with status: {status}")),
+            Err(e) => Err(format!("failed to execute command: {e}")),
+        }
+    }
+}
+
+/// Command run after tags are added to a file (`on_tag` in the config)
+#[derive(Clone)]
+pub struct TagHook {
+    template: String,
+    executor: Arc<dyn HookExecutor>,
+}
+
+impl fmt::Debug for TagHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TagHook")
+            .field("template", &self.template)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TagHook {
+    /// Create a hook that runs `template` with [`ShellExecutor`]
+    #[must_use]
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            executor: Arc::new(ShellExecutor),
+        }
+    }
+
+    /// Run the hook's commands with `executor` instead of the shell
+    #[must_use]
+    pub fn with_executor(mut self, executor: Arc<dyn HookExecutor>) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    /// Build the command line for `file` and the `tags` added to it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use tagr::hooks::TagHook;
+    ///
+    /// let hook = TagHook::new("index {file} {tags}");
+    /// let command = hook
+    ///     .command_line(Path::new("/notes/to do.md"), &["work".into(), "q&a".into()])
+    ///     .unwrap();
+    /// assert_eq!(command, "index '/notes/to do.md' work 'q&a'");
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the path or a tag cannot be shell-quoted (it
+    /// contains a NUL byte).
+    pub fn command_line(&self, file: &Path, tags: &[String]) -> Result<String, shlex::QuoteError> {
+        let file = shlex::try_quote(&file.to_string_lossy())?.into_owned();
+        let tags = tags
+            .iter()
+            .map(|tag| shlex::try_quote(tag).map(std::borrow::Cow::into_owned))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(" ");
+        Ok(self
+            .template
+            .replace("{file}", &file)
+            .replace("{tags}", &tags))
+    }
+
+    /// Run the hook for `file`, warning on stderr if it fails
+    pub fn run(&self, file: &Path, tags: &[String]) {
+        let result = self
+            .command_line(file, tags)
+            .map_err(|e| e.to_string())
+            .and_then(|command| self.executor.execute(&command));
+        if let Err(e) = result {
+            eprintln!("Warning: on_tag hook failed for {}: {e}", file.display());
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records commands instead of running them, optionally failing each one
+    #[derive(Default)]
+    pub(crate) struct MockExecutor {
+        pub(crate) commands: Mutex<Vec<String>>,
+        pub(crate) fail: bool,
+    }
+
+    impl HookExecutor for MockExecutor {
+        fn execute(&self, command: &str) -> Result<(), String> {
+            self.commands.lock().unwrap().push(command.to_string());
+            if self.fail {
+                Err("mock failure".into())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn test_tag_hook_runs_quoted_command() {
+        let executor = Arc::new(MockExecutor::default());
+        let hook =
+            TagHook::new("sync --file={file} -- {tags} # {file}").with_executor(executor.clone());
+
+        hook.run(
+            Path::new("/tmp/it's here.txt"),
+            &["rust".into(), "two words".into()],
+        );
+
+        let commands = executor.commands.lock().unwrap();
+        assert_eq!(
+            *commands,
+            ["sync --file=\"/tmp/it's here.txt\" -- rust 'two words' # \"/tmp/it's here.txt\""]
+        );
+    }
+}
@@ -35,6 +35,10 @@ pub enum BrowseAction {
 
     /// Refine search criteria - Ctrl+/
     RefineSearch,
+    /// Copy the equivalent CLI command to clipboard - Alt+Y
+    CopyCommand,
+    /// Load more files into a capped file pane - Alt+M
+    ShowMoreFiles,
 
     /// Show help screen - Ctrl+? or F1
     ShowHelp,
@@ -81,6 +85,8 @@ impl FromStr for BrowseAction {
             "edit_note" => Ok(Self::EditNote),
             "toggle_note_preview" => Ok(Self::ToggleNotePreview),
             "refine_search" => Ok(Self::RefineSearch),
+            "copy_command" => Ok(Self::CopyCommand),
+            "show_more_files" => Ok(Self::ShowMoreFiles),
             "show_help" => Ok(Self::ShowHelp),
             _ => Err(ParseActionError::new(s)),
         }
@@ -129,6 +135,8 @@ impl BrowseAction {
                 | Self::EditNote
                 | Self::ToggleNotePreview
                 | Self::ShowDetails
+                | Self::CopyCommand
+                | Self::ShowMoreFiles
         )
     }
 
@@ -158,6 +166,8 @@ impl BrowseAction {
             Self::EditNote => "Edit note for selected file",
             Self::ToggleNotePreview => "Toggle file/note preview",
             Self::RefineSearch => "Refine search criteria",
+            Self::CopyCommand => "Copy CLI command to clipboard",
+            Self::ShowMoreFiles => "Load more files into the file pane",
             Self::ShowHelp => "Show help",
             Self::Cancel => "Cancel",
         }
@@ -238,6 +248,8 @@ impl BrowseAction {
             Self::EditNote => "edit_note",
             Self::ToggleNotePreview => "toggle_note_preview",
             Self::RefineSearch => "refine_search",
+            Self::CopyCommand => "copy_command",
+            Self::ShowMoreFiles => "show_more_files",
             Self::ShowHelp => "show_help",
             Self::Cancel => "cancel",
         }
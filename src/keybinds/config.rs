@@ -211,6 +211,14 @@ fn default_keybinds() -> HashMap<String, KeybindDef> {
         "refine_search".to_string(),
         KeybindDef::Multiple(vec!["ctrl-/".to_string(), "f2".to_string()]),
     );
+    keybinds.insert(
+        "copy_command".to_string(),
+        KeybindDef::Single("alt-y".to_string()),
+    );
+    keybinds.insert(
+        "show_more_files".to_string(),
+        KeybindDef::Single("alt-m".to_string()),
+    );
 
     // Note: F1/? for help is handled internally by the TUI, not as a custom keybind
 
@@ -263,6 +263,26 @@ static ALL_ACTIONS: &[ActionMetadata] = &[
         available_in_tag_phase: true,
         available_in_file_phase: true,
     },
+    ActionMetadata {
+        action: BrowseAction::CopyCommand,
+        id: "copy_command",
+        default_keys: &["alt-y"],
+        short_name: "Copy Command",
+        description: "Copy the equivalent tagr search command to clipboard",
+        category: ActionCategory::Search,
+        available_in_tag_phase: true,
+        available_in_file_phase: true,
+    },
+    ActionMetadata {
+        action: BrowseAction::ShowMoreFiles,
+        id: "show_more_files",
+        default_keys: &["alt-m"],
+        short_name: "More Files",
+        description: "Double the number of files loaded into a capped file pane",
+        category: ActionCategory::Search,
+        available_in_tag_phase: true,
+        available_in_file_phase: true,
+    },
     // System
     ActionMetadata {
         action: BrowseAction::ShowHelp,
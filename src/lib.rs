@@ -5,7 +5,7 @@
 
 use bincode::{self, Decode, Encode};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 pub mod browse;
@@ -14,7 +14,10 @@ pub mod commands;
 pub mod config;
 pub mod db;
 pub mod discovery;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod filters;
+pub mod hooks;
 pub mod keybinds;
 pub mod output;
 pub mod patterns;
@@ -24,7 +27,7 @@ pub mod search;
 pub mod ui;
 pub mod vtags;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "testing"))]
 pub mod testing;
 
 /// Error enum, contains all failure states of the program
@@ -66,6 +69,46 @@ pub enum TagrError {
     /// Invalid input error
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+    /// An error annotated with the operation and file it occurred in
+    #[error("{operation}{}: {source}", context_file(file.as_deref()))]
+    WithContext {
+        /// The underlying failure
+        #[source]
+        source: Box<TagrError>,
+        /// The file being processed, if any
+        file: Option<PathBuf>,
+        /// Name of the operation, e.g. `bulk_tag`
+        operation: String,
+    },
+}
+
+/// Render the file part of a [`TagrError::WithContext`] message
+fn context_file(file: Option<&Path>) -> String {
+    file.map(|file| format!(" on '{}'", file.display()))
+        .unwrap_or_default()
+}
+
+impl TagrError {
+    /// Annotate the error with the operation and file it occurred in
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use tagr::TagrError;
+    ///
+    /// let err = TagrError::InvalidInput("no tags".into())
+    ///     .with_context(Path::new("/notes/a.md"), "bulk_tag");
+    /// assert_eq!(err.to_string(), "bulk_tag on '/notes/a.md': Invalid input: no tags");
+    /// ```
+    #[must_use]
+    pub fn with_context(self, file: &Path, operation: &str) -> Self {
+        Self::WithContext {
+            source: Box::new(self),
+            file: Some(file.to_path_buf()),
+            operation: operation.to_string(),
+        }
+    }
 }
 
 /// Data struct containing the pairings of file and tags
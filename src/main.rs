@@ -44,9 +44,13 @@
 
 use tagr::{
     TagrError,
-    cli::{AliasCommands, Cli, Commands, ConfigCommands, DbCommands, SearchParams},
+    cli::{
+        AliasCommands, Cli, Commands, ConfigCommands, ConfigFormat, DbCommands, SearchParams,
+        TableFormat,
+    },
     commands, config,
-    db::Database,
+    db::{Database, DbError, ReadOnlyDatabase},
+    output::TableFormatter,
 };
 
 type Result<T> = std::result::Result<T, TagrError>;
@@ -95,8 +99,11 @@ fn handle_db_command(
                 }
             }
         }
-        DbCommands::List => {
-            if config.databases.is_empty() {
+        DbCommands::List { json: true, .. } => {
+            print_json(&config.database_entries())?;
+        }
+        DbCommands::List { json: false, table } => {
+            if config.databases.is_empty() && table.format == TableFormat::Text {
                 if !quiet {
                     println!("No databases configured.");
                     println!("Add one with: tagr db add <name> <path>");
@@ -104,26 +111,20 @@ fn handle_db_command(
                 return Ok(());
             }
 
-            if !quiet {
-                println!("Configured databases:");
+            if quiet && table.format == TableFormat::Text {
+                for entry in config.database_entries() {
+                    println!("{}", entry.name);
+                }
+                return Ok(());
             }
 
-            let default_db = config.get_default_database();
-            let mut db_names: Vec<_> = config.list_databases();
-            db_names.sort();
-
-            for name in db_names {
-                if let Some(path) = config.get_database(name) {
-                    let is_default = default_db == Some(name);
-                    let marker = if is_default { " (default)" } else { "" };
-
-                    if quiet {
-                        println!("{name}");
-                    } else {
-                        println!("  {} -> {}{}", name, path.display(), marker);
-                    }
-                }
+            let mut rows =
+                TableFormatter::new(&["NAME", "PATH", "DEFAULT"]).with_max_width(table.max_width);
+            for entry in config.database_entries() {
+                let path = entry.path.display().to_string();
+                rows.add_row(&[&entry.name, &path, if entry.default { "yes" } else { "" }]);
             }
+            rows.print(table.format);
         }
         DbCommands::Remove { name, delete_files } => {
             if config.get_database(name).is_none() {
@@ -198,10 +199,73 @@ fn handle_db_command(
                 println!("Set '{name}' as default database");
             }
         }
+        DbCommands::Compact { name } => {
+            let path = config.get_database(name).ok_or_else(|| {
+                TagrError::InvalidInput(format!("Database '{name}' does not exist"))
+            })?;
+
+            let stats = Database::compact(path)?;
+
+            if !quiet {
+                println!(
+                    "Compacted '{name}': {} -> {}",
+                    format_size(stats.size_before),
+                    format_size(stats.size_after)
+                );
+            }
+        }
+        DbCommands::Info { name } => {
+            let name = name
+                .as_deref()
+                .or(config.default_database.as_deref())
+                .ok_or_else(|| {
+                    TagrError::InvalidInput(
+                        "No database specified and no default database set".into(),
+                    )
+                })?;
+            let path = config.get_database(name).ok_or_else(|| {
+                TagrError::InvalidInput(format!("Database '{name}' does not exist"))
+            })?;
+
+            let options = config.db_options();
+            let db = ReadOnlyDatabase::open_with(path, options)?;
+            let most_tags = db
+                .list_all()?
+                .iter()
+                .map(|pair| pair.tags.len())
+                .max()
+                .unwrap_or(0);
+            let against = |current: usize, limit: Option<usize>| match limit {
+                Some(limit) => format!("{current} / {limit}"),
+                None => format!("{current} (no limit)"),
+            };
+
+            println!("Database '{name}' at {}", path.display());
+            println!(
+                "  Files:          {}",
+                against(db.count(), options.max_files)
+            );
+            println!("  Tags:           {}", db.list_all_tags()?.len());
+            println!(
+                "  Tags per file:  {}",
+                against(most_tags, options.max_tags_per_file)
+            );
+            println!("  Cache size:     {} MiB", options.cache_mb);
+            println!("  Disk usage:     {}", format_size(db.size_on_disk()?));
+        }
     }
     Ok(())
 }
 
+/// Format a byte count with a binary unit, e.g. `1.5 MiB`
+fn format_size(bytes: u64) -> String {
+    use byte_unit::{Byte, UnitType};
+    format!(
+        "{:.1}",
+        Byte::from_u64(bytes).get_appropriate_unit(UnitType::Binary)
+    )
+}
+
 /// Handle the config command - manage application settings
 ///
 /// Performs configuration operations including setting and getting config values.
@@ -246,63 +310,648 @@ fn handle_config_command(
                     }
                 }
                 "path_format" | "path-format" => {
+                    let new_value = value
+                        .parse::<config::PathFormat>()
+                        .map_err(TagrError::InvalidInput)?;
+                    config.path_format = new_value;
+                    config.save()?;
+                    if !quiet {
+                        println!("Set path_format = {new_value}");
+                    }
+                }
+                "path_context_max" | "path-context-max" => {
+                    let new_value = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|max| *max > 0)
+                        .ok_or_else(|| {
+                            TagrError::InvalidInput(format!(
+                                "Invalid value for path_context_max: '{value}'. Use a positive number"
+                            ))
+                        })?;
+                    config.path_context_max = new_value;
+                    config.save()?;
+                    if !quiet {
+                        println!("Set path_context_max = {new_value}");
+                    }
+                }
+                "symlinks" => {
                     let new_value = match value.to_lowercase().as_str() {
-                        "absolute" | "abs" => config::PathFormat::Absolute,
-                        "relative" | "rel" => config::PathFormat::Relative,
+                        "follow" => config::SymlinkMode::Follow,
+                        "store-target" => config::SymlinkMode::StoreTarget,
+                        "store-link" => config::SymlinkMode::StoreLink,
                         _ => {
                             return Err(TagrError::InvalidInput(format!(
-                                "Invalid value for path_format: '{value}'. Use 'absolute' or 'relative'"
+                                "Invalid value for symlinks: '{value}'. Use 'follow', 'store-target' or 'store-link'"
                             )));
                         }
                     };
-                    config.path_format = new_value;
+                    config.symlinks = new_value;
+                    config.save()?;
+                    if !quiet {
+                        println!("Set symlinks = {value}");
+                    }
+                }
+                "normalize_tags" | "normalize-tags" => {
+                    let new_value = match value.to_lowercase().as_str() {
+                        "off" => config::TagNormalization::Off,
+                        "trim" => config::TagNormalization::Trim,
+                        "slugify" => config::TagNormalization::Slugify,
+                        _ => {
+                            return Err(TagrError::InvalidInput(format!(
+                                "Invalid value for normalize_tags: '{value}'. Use 'off', 'trim' or 'slugify'"
+                            )));
+                        }
+                    };
+                    config.normalize_tags = new_value;
                     config.save()?;
                     if !quiet {
-                        println!("Set path_format = {new_value:?}");
+                        println!("Set normalize_tags = {value}");
+                    }
+                }
+                "default_tag_mode" | "default-tag-mode" => {
+                    let new_value = match value.to_lowercase().as_str() {
+                        "any" => Some(tagr::filters::TagMode::Any),
+                        "all" => Some(tagr::filters::TagMode::All),
+                        "none" | "" => None,
+                        _ => {
+                            return Err(TagrError::InvalidInput(format!(
+                                "Invalid value for default_tag_mode: '{value}'. Use 'any', 'all' or 'none'"
+                            )));
+                        }
+                    };
+                    config.default_tag_mode = new_value;
+                    config.save()?;
+                    if !quiet {
+                        println!("Set default_tag_mode = {value}");
+                    }
+                }
+                "max_files" | "max-files" | "max_tags_per_file" | "max-tags-per-file" => {
+                    let new_value = if value.eq_ignore_ascii_case("none") {
+                        None
+                    } else {
+                        Some(value.parse::<usize>().map_err(|_| {
+                            TagrError::InvalidInput(format!(
+                                "Invalid value for {key}: '{value}'. Use a number or 'none'"
+                            ))
+                        })?)
+                    };
+                    if matches!(key, "max_files" | "max-files") {
+                        config.max_files = new_value;
+                    } else {
+                        config.max_tags_per_file = new_value;
+                    }
+                    config.save()?;
+                    if !quiet {
+                        println!("Set {key} = {value}");
+                    }
+                }
+                "on_tag" | "on-tag" => {
+                    config.on_tag = if value.is_empty() || value.eq_ignore_ascii_case("none") {
+                        None
+                    } else {
+                        Some(value.to_string())
+                    };
+                    config.save()?;
+                    if !quiet {
+                        println!("Set on_tag = {value}");
+                    }
+                }
+                "browse_max_items" | "browse-max-items" => {
+                    let new_value = value.parse::<usize>().map_err(|_| {
+                        TagrError::InvalidInput(format!(
+                            "Invalid value for browse_max_items: '{value}'. Use a number (0 for no limit)"
+                        ))
+                    })?;
+                    config.browse_max_items = new_value;
+                    config.save()?;
+                    if !quiet {
+                        println!("Set browse_max_items = {new_value}");
+                    }
+                }
+                "search_cache_ttl" | "search-cache-ttl" => {
+                    let new_value = value.parse::<u64>().map_err(|_| {
+                        TagrError::InvalidInput(format!(
+                            "Invalid value for search_cache_ttl: '{value}'. Use a number of seconds (0 to disable)"
+                        ))
+                    })?;
+                    config.search_cache_ttl = new_value;
+                    config.save()?;
+                    if !quiet {
+                        println!("Set search_cache_ttl = {new_value}");
+                    }
+                }
+                "db_cache_mb" | "db-cache-mb" => {
+                    let new_value = value.parse::<usize>().map_err(|_| {
+                        TagrError::InvalidInput(format!(
+                            "Invalid value for db_cache_mb: '{value}'. Use a size in megabytes"
+                        ))
+                    })?;
+                    config.db_cache_mb = new_value;
+                    config.save()?;
+                    if !quiet {
+                        println!("Set db_cache_mb = {new_value}");
                     }
                 }
                 _ => {
                     return Err(TagrError::InvalidInput(format!(
-                        "Unknown configuration key: '{key}'. Available keys: quiet, path_format"
+                        "Unknown configuration key: '{key}'. Available keys: quiet, path_format, path_context_max, symlinks, normalize_tags, default_tag_mode, max_files, max_tags_per_file, browse_max_items, search_cache_ttl, db_cache_mb, on_tag"
                     )));
                 }
             }
         }
-        ConfigCommands::Get { key } => match key.as_str() {
+        ConfigCommands::List { json: true } => {
+            print_json(&config.to_display_table())?;
+        }
+        ConfigCommands::List { json: false } => {
+            print_config_table(&config, ConfigFormat::Text)?;
+        }
+        ConfigCommands::Get { key, format } if matches!(key.as_str(), "all" | "*") => {
+            print_config_table(&config, *format)?;
+        }
+        ConfigCommands::Get {
+            format: ConfigFormat::Json,
+            ..
+        } => {
+            return Err(TagrError::InvalidInput(
+                "--format json is only supported with 'tagr config get all'".into(),
+            ));
+        }
+        ConfigCommands::Get { key, .. } => match key.as_str() {
             "quiet" => {
                 println!("{}", config.quiet);
             }
             "path_format" | "path-format" => {
-                let value = match config.path_format {
-                    config::PathFormat::Absolute => "absolute",
-                    config::PathFormat::Relative => "relative",
+                println!("{}", config.path_format);
+            }
+            "path_context_max" | "path-context-max" => {
+                println!("{}", config.path_context_max);
+            }
+            "symlinks" => {
+                let value = match config.symlinks {
+                    config::SymlinkMode::Follow => "follow",
+                    config::SymlinkMode::StoreTarget => "store-target",
+                    config::SymlinkMode::StoreLink => "store-link",
                 };
                 println!("{value}");
             }
+            "normalize_tags" | "normalize-tags" => {
+                let value = match config.normalize_tags {
+                    config::TagNormalization::Off => "off",
+                    config::TagNormalization::Trim => "trim",
+                    config::TagNormalization::Slugify => "slugify",
+                };
+                println!("{value}");
+            }
+            "default_tag_mode" | "default-tag-mode" => match config.default_tag_mode {
+                Some(tagr::filters::TagMode::Any) => println!("any"),
+                Some(tagr::filters::TagMode::All) => println!("all"),
+                None => println!("none"),
+            },
+            "max_files" | "max-files" => match config.max_files {
+                Some(limit) => println!("{limit}"),
+                None => println!("none"),
+            },
+            "max_tags_per_file" | "max-tags-per-file" => match config.max_tags_per_file {
+                Some(limit) => println!("{limit}"),
+                None => println!("none"),
+            },
+            "browse_max_items" | "browse-max-items" => {
+                println!("{}", config.browse_max_items);
+            }
+            "search_cache_ttl" | "search-cache-ttl" => {
+                println!("{}", config.search_cache_ttl);
+            }
+            "db_cache_mb" | "db-cache-mb" => {
+                println!("{}", config.db_cache_mb);
+            }
+            "on_tag" | "on-tag" => {
+                println!("{}", config.on_tag.as_deref().unwrap_or("none"));
+            }
             _ => {
                 return Err(TagrError::InvalidInput(format!(
-                    "Unknown configuration key: '{key}'. Available keys: quiet, path_format"
+                    "Unknown configuration key: '{key}'. Available keys: quiet, path_format, path_context_max, symlinks, normalize_tags, default_tag_mode, max_files, max_tags_per_file, browse_max_items, search_cache_ttl, db_cache_mb, on_tag"
                 )));
             }
         },
+        ConfigCommands::Validate => validate_config(quiet)?,
+    }
+    Ok(())
+}
+
+/// Print every configuration field with its default and source
+///
+/// # Errors
+///
+/// Returns `TagrError` if JSON output cannot be written.
+fn print_config_table(config: &config::TagrConfig, format: ConfigFormat) -> Result<()> {
+    let rows = config.to_display_table();
+    match format {
+        ConfigFormat::Json => {
+            let object: serde_json::Map<String, serde_json::Value> = rows
+                .into_iter()
+                .map(|entry| {
+                    (
+                        entry.key,
+                        serde_json::json!({
+                            "value": entry.value,
+                            "default": entry.default,
+                            "source": entry.source,
+                        }),
+                    )
+                })
+                .collect();
+            print_json(&object)?;
+        }
+        ConfigFormat::Text => {
+            let width = |column: fn(&config::ConfigEntry) -> &String, header: &str| {
+                rows.iter()
+                    .map(|row| column(row).chars().count())
+                    .chain(std::iter::once(header.len()))
+                    .max()
+                    .unwrap_or(0)
+            };
+            let field_width = width(|row| &row.key, "FIELD");
+            let value_width = width(|row| &row.value, "VALUE");
+            let default_width = width(|row| &row.default, "DEFAULT");
+            println!(
+                "{:field_width$}  {:value_width$}  {:default_width$}  SOURCE",
+                "FIELD", "VALUE", "DEFAULT"
+            );
+            for row in &rows {
+                println!(
+                    "{:field_width$}  {:value_width$}  {:default_width$}  {}",
+                    row.key, row.value, row.default, row.source
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print `value` as pretty JSON, with nothing else on stdout
+fn print_json(value: &impl serde::Serialize) -> Result<()> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| TagrError::InvalidInput(format!("Failed to serialize JSON: {e}")))?;
+    println!("{json}");
+    Ok(())
+}
+
+/// Validate the config file and tag schema, printing a report
+///
+/// Runs without loading the configuration first so that broken config files
+/// can still be diagnosed.
+///
+/// # Errors
+///
+/// Returns `TagrError::InvalidInput` if any check reports an error, so the
+/// process exits with a non-zero status.
+fn validate_config(quiet: bool) -> Result<()> {
+    let config_path = config::TagrConfig::config_path()?;
+
+    let mut results = config::validate_file(&config_path);
+    results.extend(config::validate_schema_file(
+        &tagr::schema::default_schema_path(),
+    ));
+
+    let error_count = results
+        .iter()
+        .filter(|r| r.status == config::CheckStatus::Error)
+        .count();
+    let warning_count = results
+        .iter()
+        .filter(|r| r.status == config::CheckStatus::Warning)
+        .count();
+
+    for result in &results {
+        let marker = match result.status {
+            config::CheckStatus::Ok if quiet => continue,
+            config::CheckStatus::Ok => "✓",
+            config::CheckStatus::Warning => "⚠",
+            config::CheckStatus::Error => "✗",
+        };
+        println!("{marker} {}: {}", result.field, result.message);
+    }
+
+    if !quiet {
+        println!("\n{error_count} error(s), {warning_count} warning(s)");
+        if error_count + warning_count > 0 {
+            println!(
+                "Run 'tagr config get all' to see every effective value and where it comes from."
+            );
+        }
+    }
+
+    if error_count > 0 {
+        return Err(TagrError::InvalidInput(format!(
+            "Configuration has {error_count} error(s)"
+        )));
+    }
+    Ok(())
+}
+
+/// The daemon request for a search it can answer, or `None` to search directly
+///
+/// Only plain tag searches are forwarded: `-t` tags with their mode and
+/// `--no-hierarchy`, printed as text. Other searches open the database after
+/// taking a lease from the daemon, like every other command.
+#[cfg(unix)]
+fn daemon_search_request(
+    command: &Commands,
+    config: &config::TagrConfig,
+) -> Option<commands::daemon::Request> {
+    let Commands::Search {
+        expr,
+        criteria,
+        table,
+        top,
+        bottom,
+        format,
+        group_by_tag,
+        sort,
+        limit,
+        offset,
+        explain,
+        count,
+        clear_cache,
+        filter_args,
+        ..
+    } = command
+    else {
+        return None;
+    };
+    let params = command.get_search_params(config.tag_mode_or(tagr::cli::SearchMode::All))?;
+    let plain = !params.tags.is_empty()
+        && params.query.is_none()
+        && params.file_patterns.is_empty()
+        && params.file_only_patterns.is_empty()
+        && params.exclude_tags.is_empty()
+        && params.exclude_file_patterns.is_empty()
+        && params.virtual_tags.is_empty()
+        && params.exclude_virtual_tags.is_empty()
+        && !params.regex_tag
+        && !params.regex_file
+        && !params.no_index
+        && expr.is_none()
+        && filter_args.filter.is_none()
+        && filter_args.save_filter.is_none()
+        && !table
+        && top.is_none()
+        && bottom.is_none()
+        && *format == tagr::cli::ListFormat::Text
+        && !group_by_tag
+        && sort.is_none()
+        && limit.is_none()
+        && *offset == 0
+        && !explain
+        && !count
+        && !clear_cache;
+    plain.then(|| commands::daemon::Request::Search {
+        tags: params.tags,
+        any: params.tag_mode == tagr::cli::SearchMode::Any,
+        no_hierarchy: params.no_hierarchy,
+        exclude_dirs: criteria.exclude_dirs(&config.default_exclude_dirs),
+    })
+}
+
+/// Open the database at `db_path` with `open`
+///
+/// While a daemon runs, the caller holds a lease (see
+/// [`commands::daemon::Lease`]) and the daemon's lock may still be on its way
+/// out, so a locked database is retried briefly.
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn open_db<T>(
+    db_path: &std::path::Path,
+    mut open: impl FnMut() -> std::result::Result<T, DbError>,
+) -> Result<T> {
+    #[cfg(unix)]
+    if commands::daemon::socket_path(db_path).exists() {
+        return Ok(commands::daemon::retry_while_locked(open)?);
+    }
+    Ok(open()?)
+}
+
+/// Handle commands that only query the database
+///
+/// These receive a `ReadOnlyDatabase`, so they cannot modify tags or notes.
+fn handle_query_command(
+    db: &ReadOnlyDatabase,
+    command: &Commands,
+    config: &config::TagrConfig,
+    path_format: config::PathFormat,
+    dry_run: bool,
+    quiet: bool,
+) -> Result<()> {
+    let default_exclude_dirs = config.default_exclude_dirs.as_slice();
+    match command {
+        Commands::Search {
+            filter_args,
+            criteria,
+            table,
+            top,
+            bottom,
+            format,
+            group_by_tag,
+            sort,
+            limit,
+            offset,
+            expr,
+            explain,
+            count,
+            cache_ttl,
+            clear_cache,
+            ..
+        } => {
+            use tagr::commands::search::{ExplicitFlags, FilterConfig, OutputConfig, TagRank};
+            use tagr::search::cache::SearchCache;
+
+            let params = command
+                .get_search_params(config.tag_mode_or(tagr::cli::SearchMode::All))
+                .ok_or_else(|| {
+                    TagrError::InvalidInput("Failed to parse search parameters".into())
+                })?;
+            if *clear_cache {
+                if let Some(path) = SearchCache::default_path() {
+                    SearchCache::clear(&path)?;
+                }
+                if !quiet {
+                    println!("Cleared search cache");
+                }
+                if !params.has_criteria() && expr.is_none() && filter_args.filter.is_none() {
+                    return Ok(());
+                }
+            }
+            let expr = match expr
+                .as_deref()
+                .map(tagr::search::query_lang::parse)
+                .transpose()
+            {
+                Ok(expr) => expr,
+                Err(err) => {
+                    if !quiet {
+                        eprintln!("Error: {err}");
+                    }
+                    return Err(tagr::search::SearchError::from(err).into());
+                }
+            };
+
+            let save_filter = filter_args
+                .save_filter
+                .as_ref()
+                .map(|name| (name.as_str(), filter_args.filter_desc.as_deref()));
+
+            // Determine if user explicitly provided mode flags
+            let has_explicit_tag_mode = criteria.any_tag || criteria.all_tags;
+            let has_explicit_file_mode = criteria.any_file || criteria.all_files;
+            let has_explicit_virtual_mode = criteria.any_virtual || criteria.all_virtual;
+
+            commands::search(
+                db,
+                params,
+                expr.as_ref(),
+                &criteria.exclude_dirs(default_exclude_dirs),
+                FilterConfig {
+                    apply: filter_args.filter.as_deref(),
+                    save: save_filter,
+                },
+                ExplicitFlags {
+                    tag_mode: has_explicit_tag_mode,
+                    file_mode: has_explicit_file_mode,
+                    virtual_mode: has_explicit_virtual_mode,
+                },
+                OutputConfig {
+                    format: path_format,
+                    path_context_max: config.path_context_max,
+                    quiet,
+                    table: *table,
+                    rank: top.map(TagRank::Top).or(bottom.map(TagRank::Bottom)),
+                    output: *format,
+                    group_by_tag: *group_by_tag,
+                    sort: *sort,
+                    page: tagr::output::Page {
+                        offset: *offset,
+                        limit: *limit,
+                    },
+                    explain: *explain,
+                    count: *count,
+                    cache_ttl: cache_ttl.unwrap_or(config.search_cache_ttl),
+                },
+            )?;
+        }
+        Commands::List {
+            variant,
+            most_tagged,
+            least_tagged,
+            table,
+            sort,
+            reverse,
+            limit,
+            offset,
+            by_tag,
+            only_tags,
+            once_per_file,
+            format,
+            max_width,
+            skip_corrupt,
+            ..
+        } => {
+            let options = commands::list::ListOptions {
+                most_tagged: *most_tagged,
+                least_tagged: *least_tagged,
+                table: *table,
+                sort: *sort,
+                reverse: *reverse,
+                limit: *limit,
+                offset: *offset,
+                by_tag: *by_tag,
+                only_tags: only_tags.clone(),
+                once_per_file: *once_per_file,
+                format: *format,
+                max_width: *max_width,
+                skip_corrupt: *skip_corrupt,
+            };
+            commands::list(db, *variant, &options, path_format, quiet)?;
+        }
+        Commands::Dedup { by, execute, .. } => {
+            commands::dedup(db, *by, execute.as_deref(), path_format, quiet)?;
+        }
+        Commands::Get { file, json, .. } => {
+            commands::get(
+                db,
+                file,
+                *json,
+                config.symlinks,
+                default_exclude_dirs,
+                path_format,
+            )?;
+        }
+        Commands::Has {
+            file,
+            tags,
+            any,
+            verbose,
+            ..
+        } => {
+            if !commands::has(db, file, tags, *any, config.symlinks, *verbose)? {
+                std::process::exit(1);
+            }
+        }
+        Commands::Export { command, .. } => {
+            commands::export(db, command, dry_run, quiet)?;
+        }
+        _ => unreachable!(),
     }
     Ok(())
 }
 
 /// Main entry point for the tagr application
 ///
-/// Loads configuration, parses command-line arguments, and dispatches to the
-/// appropriate command handler.
+/// Parses command-line arguments and runs the command.
+///
+/// # Errors
+///
+/// Returns `TagrError` if the command fails. `tagr has` instead exits with
+/// status 2, since status 1 means the file lacks the tags.
+fn main() -> Result<()> {
+    let cli = Cli::parse_args();
+    let result = run(&cli);
+    if let (Err(e), Some(Commands::Has { .. })) = (&result, &cli.command) {
+        eprintln!("Error: {e}");
+        std::process::exit(2);
+    }
+    result
+}
+
+/// Load configuration and dispatch to the appropriate command handler
 ///
 /// # Errors
 ///
 /// Returns `TagrError` if configuration loading fails, database initialization fails,
 /// or any command handler returns an error.
 #[allow(clippy::too_many_lines)]
-fn main() -> Result<()> {
-    let config = config::TagrConfig::load_or_setup()?;
+fn run(cli: &Cli) -> Result<()> {
+    if cli.dry_run
+        && let Some(name) = cli.get_command().dry_run_unsupported()
+    {
+        return Err(TagrError::InvalidInput(format!(
+            "--dry-run is not supported by '{name}'"
+        )));
+    }
 
-    let cli = Cli::parse_args();
+    // Validation must work even when the config file cannot be loaded
+    if let Some(Commands::Config {
+        command: ConfigCommands::Validate,
+    }) = &cli.command
+    {
+        return validate_config(cli.quiet);
+    }
+
+    // Completions and shell hooks need neither config nor database
+    if let Some(Commands::Completions { shell, command }) = &cli.command {
+        return commands::completions::execute(*shell, command.as_ref(), cli.quiet);
+    }
+
+    let config = config::TagrConfig::load_or_setup()?;
 
     let quiet = cli.quiet || config.quiet;
 
@@ -312,6 +961,8 @@ fn main() -> Result<()> {
         handle_db_command(config, command, quiet)?;
     } else if let Commands::Config { command } = &command {
         handle_config_command(config, command, quiet)?;
+    } else if let Commands::Preview { file, plain } = &command {
+        commands::preview::execute(file, &config.preview, *plain)?;
     } else {
         let db_name = command.get_db().or_else(|| {
             config.get_default_database().cloned()
@@ -323,87 +974,203 @@ fn main() -> Result<()> {
             TagrError::InvalidInput(format!("Database '{db_name}' not found in configuration"))
         })?;
 
-        let db = Database::open(db_path)?;
-
         // Determine path format: CLI override > config default
         let path_format = if let Some(cli_format) = cli.get_path_format() {
             match cli_format {
                 tagr::cli::PathFormat::Absolute => config::PathFormat::Absolute,
                 tagr::cli::PathFormat::Relative => config::PathFormat::Relative,
+                tagr::cli::PathFormat::LastN(n) => config::PathFormat::LastN(n),
             }
         } else {
             config.path_format
         };
 
+        #[cfg(unix)]
+        if let Commands::Daemon { stop, .. } = &command {
+            let db = if *stop {
+                None
+            } else {
+                Some(ReadOnlyDatabase::open_with(db_path, config.db_options())?)
+            };
+            return commands::daemon(db_path, db, *stop, quiet);
+        }
+
+        #[cfg(unix)]
+        if let Some(request) = daemon_search_request(&command, &config)
+            && let Some(response) = commands::daemon::forward(db_path, &request)
+        {
+            use commands::daemon::{Request, Response};
+            match (request, response) {
+                (
+                    Request::Search {
+                        tags,
+                        any,
+                        no_hierarchy,
+                        ..
+                    },
+                    Response::Files { pairs },
+                ) => {
+                    let params = Request::search_params(&tags, any, no_hierarchy);
+                    commands::search::print_tagged(&params, &pairs, path_format, quiet);
+                    return Ok(());
+                }
+                (_, Response::Error { message }) => {
+                    return Err(TagrError::InvalidInput(message));
+                }
+                _ => {}
+            }
+        }
+
+        // A running daemon closes the database until the lease is dropped,
+        // after the database handle below
+        #[cfg(unix)]
+        let _lease = commands::daemon::Lease::take(db_path);
+
+        // Query-only commands never create or modify the database
+        if matches!(
+            command,
+            Commands::Search { .. }
+                | Commands::List { .. }
+                | Commands::Dedup { .. }
+                | Commands::Get { .. }
+                | Commands::Has { .. }
+                | Commands::Export { .. }
+        ) {
+            let db = open_db(db_path, || {
+                ReadOnlyDatabase::open_with(db_path, config.db_options())
+            })?;
+            return handle_query_command(&db, &command, &config, path_format, cli.dry_run, quiet);
+        }
+
+        let db = open_db(db_path, || {
+            if cli.no_create {
+                Database::open_existing_with(db_path, config.db_options())
+            } else {
+                Database::open_with(db_path, config.db_options())
+            }
+        })?
+        .with_tag_hook(config.tag_hook());
+
         match &command {
-            Commands::Browse { filter_args, .. } => {
-                let ctx = command.get_browse_context().unwrap();
+            Commands::Browse {
+                filter_args,
+                criteria,
+                ..
+            } => {
+                let ctx = command
+                    .get_browse_context(config.tag_mode_or(tagr::cli::SearchMode::Any))
+                    .unwrap();
 
                 let save_filter = filter_args
                     .save_filter
                     .as_ref()
                     .map(|name| (name.as_str(), filter_args.filter_desc.as_deref()));
 
+                let tui_state_file = if config.persist_tui_state {
+                    tagr::ui::ratatui_adapter::PersistedState::default_path(&db_name)
+                } else {
+                    None
+                };
+
                 commands::browse(
                     &db,
                     ctx.search_params,
                     filter_args.filter.as_deref(),
                     save_filter,
                     ctx.execute_cmd,
+                    ctx.execute_mode,
+                    criteria.exclude_dirs(&config.default_exclude_dirs),
                     Some(&ctx.preview_overrides),
+                    ctx.start_tags,
+                    config.normalize_tags,
+                    config.image_preview,
+                    config.preview.max_preview_bytes,
+                    config.browse_max_items,
+                    tui_state_file,
+                    !ctx.fresh,
                     path_format,
                     quiet,
                 )?;
             }
             Commands::Tag { .. } => {
                 let ctx = command.get_tag_context().unwrap();
-                commands::tag(&db, ctx.file, &ctx.tags, ctx.no_canonicalize, quiet)?;
-            }
-            Commands::Search {
-                filter_args,
-                criteria,
-                ..
-            } => {
-                use tagr::commands::search::{ExplicitFlags, FilterConfig, OutputConfig};
-
-                let params = command.get_search_params().ok_or_else(|| {
-                    TagrError::InvalidInput("Failed to parse search parameters".into())
-                })?;
-
-                let save_filter = filter_args
-                    .save_filter
-                    .as_ref()
-                    .map(|name| (name.as_str(), filter_args.filter_desc.as_deref()));
-
-                // Determine if user explicitly provided mode flags
-                let has_explicit_tag_mode = criteria.any_tag || criteria.all_tags;
-                let has_explicit_file_mode = criteria.any_file || criteria.all_files;
-                let has_explicit_virtual_mode = criteria.any_virtual || criteria.all_virtual;
-
-                commands::search(
-                    &db,
-                    params,
-                    FilterConfig {
-                        apply: filter_args.filter.as_deref(),
-                        save: save_filter,
-                    },
-                    ExplicitFlags {
-                        tag_mode: has_explicit_tag_mode,
-                        file_mode: has_explicit_file_mode,
-                        virtual_mode: has_explicit_virtual_mode,
-                    },
-                    OutputConfig {
-                        format: path_format,
+                let filename_options =
+                    ctx.from_filename
+                        .then(|| commands::tag::FilenameTagOptions {
+                            separators: ctx.separators.clone(),
+                            skip_numbers: ctx.skip_numbers,
+                            min_len: ctx.min_len,
+                        });
+                if ctx.recursive {
+                    let dir = ctx
+                        .file
+                        .ok_or_else(|| TagrError::InvalidInput("No directory provided".into()))?;
+                    let options = commands::tag::WalkOptions {
+                        include: ctx.include,
+                        exclude: ctx.exclude,
+                        gitignore: ctx.gitignore,
+                        follow_symlinks: ctx.follow_symlinks,
+                    };
+                    commands::tag::tag_recursive(
+                        &db,
+                        &dir,
+                        config.symlinks,
+                        &ctx.tags,
+                        config.normalize_tags,
+                        ctx.no_canonicalize || config.no_canonicalize,
+                        filename_options.as_ref(),
+                        &options,
+                        ctx.dry_run,
                         quiet,
-                    },
-                )?;
+                    )?;
+                } else {
+                    commands::tag(
+                        &db,
+                        ctx.file,
+                        config.symlinks,
+                        &ctx.tags,
+                        config.normalize_tags,
+                        ctx.no_canonicalize || config.no_canonicalize,
+                        filename_options.as_ref(),
+                        ctx.dry_run,
+                        quiet,
+                    )?;
+                }
             }
             Commands::Untag { .. } => {
                 let ctx = command.get_untag_context().unwrap();
-                commands::tag::untag(&db, ctx.file, &ctx.tags, ctx.all, quiet)?;
+                commands::tag::untag(
+                    &db,
+                    ctx.file,
+                    config.symlinks,
+                    &ctx.tags,
+                    ctx.all,
+                    ctx.dry_run,
+                    quiet,
+                )?;
             }
-            Commands::Tags { command, .. } => {
-                commands::tags(&db, command, quiet)?;
+            Commands::MoveFile {
+                from, to, replace, ..
+            } => {
+                commands::tag::move_file(
+                    &db,
+                    from,
+                    to,
+                    config.symlinks,
+                    *replace,
+                    cli.dry_run,
+                    quiet,
+                )?;
+            }
+            Commands::Tags { command, query, .. } => {
+                commands::tags(
+                    &db,
+                    command.as_ref(),
+                    query,
+                    config.symlinks,
+                    cli.dry_run,
+                    quiet,
+                )?;
             }
             Commands::Bulk { command, .. } => {
                 use tagr::cli::BulkCommands;
@@ -413,12 +1180,24 @@ fn main() -> Result<()> {
                         criteria,
                         add_tags,
                         conditions,
+                        parallel,
                         dry_run,
                         yes,
                     } => {
-                        let params = SearchParams::from(criteria);
+                        let params = SearchParams::from_criteria(
+                            criteria,
+                            config.tag_mode_or(tagr::cli::SearchMode::All),
+                        );
                         commands::bulk::bulk_tag(
-                            &db, params, add_tags, conditions, *dry_run, *yes, quiet,
+                            &db,
+                            params,
+                            add_tags,
+                            config.normalize_tags,
+                            conditions,
+                            *parallel,
+                            *dry_run,
+                            *yes,
+                            quiet,
                         )?;
                     }
                     BulkCommands::Untag {
@@ -426,16 +1205,21 @@ fn main() -> Result<()> {
                         remove_tags,
                         all,
                         conditions,
+                        parallel,
                         dry_run,
                         yes,
                     } => {
-                        let params = SearchParams::from(criteria);
+                        let params = SearchParams::from_criteria(
+                            criteria,
+                            config.tag_mode_or(tagr::cli::SearchMode::All),
+                        );
                         commands::bulk::bulk_untag(
                             &db,
                             params,
                             remove_tags,
                             *all,
                             conditions,
+                            *parallel,
                             *dry_run,
                             *yes,
                             quiet,
@@ -444,10 +1228,26 @@ fn main() -> Result<()> {
                     BulkCommands::RenameTag {
                         old_tag,
                         new_tag,
+                        update_refs,
+                        merge,
                         dry_run,
                         yes,
                     } => {
-                        commands::bulk::rename_tag(&db, old_tag, new_tag, *dry_run, *yes, quiet)?;
+                        let mut refs = if *update_refs {
+                            Some(commands::bulk::TagRefs::load_default()?)
+                        } else {
+                            None
+                        };
+                        commands::bulk::rename_tag(
+                            &db,
+                            old_tag,
+                            new_tag,
+                            refs.as_mut(),
+                            *merge,
+                            *dry_run,
+                            *yes,
+                            quiet,
+                        )?;
                     }
                     BulkCommands::MergeTags {
                         source_tags,
@@ -466,24 +1266,36 @@ fn main() -> Result<()> {
                     }
                     BulkCommands::CopyTags {
                         source,
+                        smart,
+                        threshold,
                         criteria,
                         specific_tags,
                         exclude,
                         dry_run,
                         yes,
                     } => {
-                        use tagr::commands::bulk::CopyTagsConfig;
+                        use tagr::commands::bulk::{CopyTagsConfig, CopyTagsSource};
 
-                        let params = SearchParams::from(criteria);
+                        let params = SearchParams::from_criteria(
+                            criteria,
+                            config.tag_mode_or(tagr::cli::SearchMode::All),
+                        );
                         let specific = if specific_tags.is_empty() {
                             None
                         } else {
                             Some(specific_tags.as_slice())
                         };
 
+                        let source = match source {
+                            Some(file) if !*smart => CopyTagsSource::Explicit(file.clone()),
+                            _ => CopyTagsSource::Smart {
+                                similarity_threshold: *threshold,
+                            },
+                        };
+
                         commands::bulk::copy_tags(
                             &db,
-                            source,
+                            &source,
                             params,
                             CopyTagsConfig {
                                 specific_tags: specific,
@@ -572,41 +1384,105 @@ fn main() -> Result<()> {
                             quiet,
                         )?;
                     }
+                    BulkCommands::PropagateByFilename {
+                        root,
+                        separators,
+                        skip_numbers,
+                        min_len,
+                        dry_run,
+                        yes,
+                    } => {
+                        let options = commands::tag::FilenameTagOptions {
+                            separators: separators.clone(),
+                            skip_numbers: *skip_numbers,
+                            min_len: *min_len,
+                        };
+                        commands::bulk::propagate_by_filename(
+                            &db,
+                            root.as_deref(),
+                            &options,
+                            *dry_run,
+                            *yes,
+                            quiet,
+                        )?;
+                    }
+                    BulkCommands::Propagate {
+                        dir,
+                        rules,
+                        max_depth,
+                        follow_symlinks,
+                        dry_run,
+                        yes,
+                    } => {
+                        let rules = commands::bulk::PropagateRules::load(rules)?;
+                        commands::bulk::propagate_by_rules(
+                            &db,
+                            dir,
+                            &rules,
+                            *max_depth,
+                            *follow_symlinks,
+                            *dry_run,
+                            *yes,
+                            quiet,
+                        )?;
+                    }
+                    BulkCommands::PropagateFromManifest {
+                        manifest,
+                        target_dir,
+                        dry_run,
+                        yes,
+                    } => {
+                        commands::bulk::propagate_from_manifest(
+                            &db,
+                            manifest,
+                            target_dir.as_deref(),
+                            *dry_run,
+                            *yes,
+                            quiet,
+                        )?;
+                    }
                     BulkCommands::Transform {
-                        transformation,
+                        kind,
                         param,
                         replacement,
+                        transformation,
                         filter,
+                        tag_filter,
                         dry_run,
                         yes,
                     } => {
                         use commands::bulk::TagTransformation;
                         use tagr::cli::TransformationType;
 
-                        let trans = match transformation {
+                        let base = kind.map(|kind| match kind {
                             TransformationType::Lowercase => TagTransformation::Lowercase,
                             TransformationType::Uppercase => TagTransformation::Uppercase,
                             TransformationType::KebabCase => TagTransformation::KebabCase,
                             TransformationType::SnakeCase => TagTransformation::SnakeCase,
                             TransformationType::CamelCase => TagTransformation::CamelCase,
                             TransformationType::PascalCase => TagTransformation::PascalCase,
-                            TransformationType::AddPrefix => {
-                                TagTransformation::AddPrefix(param.clone().unwrap())
-                            }
-                            TransformationType::AddSuffix => {
-                                TagTransformation::AddSuffix(param.clone().unwrap())
-                            }
-                            TransformationType::RemovePrefix => {
-                                TagTransformation::RemovePrefix(param.clone().unwrap())
-                            }
-                            TransformationType::RemoveSuffix => {
-                                TagTransformation::RemoveSuffix(param.clone().unwrap())
-                            }
+                            TransformationType::AddPrefix => TagTransformation::AddPrefix {
+                                prefix: param.clone().unwrap(),
+                            },
+                            TransformationType::AddSuffix => TagTransformation::AddSuffix {
+                                suffix: param.clone().unwrap(),
+                            },
+                            TransformationType::RemovePrefix => TagTransformation::StripPrefix {
+                                prefix: param.clone().unwrap(),
+                            },
+                            TransformationType::RemoveSuffix => TagTransformation::StripSuffix {
+                                suffix: param.clone().unwrap(),
+                            },
                             TransformationType::RegexReplace => TagTransformation::RegexReplace {
                                 pattern: param.clone().unwrap(),
                                 replacement: replacement.clone().unwrap(),
                             },
-                        };
+                        });
+
+                        let chain: Vec<TagTransformation> = base
+                            .into_iter()
+                            .chain(transformation.transformations())
+                            .collect();
 
                         let filter_tags = if filter.is_empty() {
                             None
@@ -616,8 +1492,9 @@ fn main() -> Result<()> {
 
                         commands::bulk::transform_tags(
                             &db,
-                            &trans,
+                            &chain,
                             filter_tags,
+                            tag_filter.as_deref(),
                             *dry_run,
                             *yes,
                             quiet,
@@ -625,11 +1502,30 @@ fn main() -> Result<()> {
                     }
                 }
             }
-            Commands::Cleanup { .. } => {
-                commands::cleanup(&db, path_format, quiet)?;
+            Commands::Cleanup {
+                skip_corrupt,
+                incremental,
+                resume,
+                max_age_check,
+                ..
+            } => {
+                let limits = commands::cleanup::ScanLimits {
+                    incremental: *incremental,
+                    resume: *resume,
+                    max_age_check: *max_age_check,
+                };
+                commands::cleanup(
+                    &db,
+                    config.symlinks,
+                    *skip_corrupt,
+                    limits,
+                    path_format,
+                    cli.dry_run,
+                    quiet,
+                )?;
             }
-            Commands::List { variant, .. } => {
-                commands::list(&db, *variant, path_format, quiet)?;
+            Commands::Import { command, .. } => {
+                commands::import(&db, command, quiet)?;
             }
             Commands::Note { command, .. } => {
                 command.execute(&db, &config, path_format)?;
@@ -647,7 +1543,47 @@ fn main() -> Result<()> {
                 commands::alias(command, db_ref)
                     .map_err(|e| TagrError::InvalidInput(e.to_string()))?;
             }
-            Commands::Db { .. } | Commands::Config { .. } => unreachable!(),
+            Commands::Replay {
+                journal,
+                continue_on_error,
+                dry_run,
+                ..
+            } => {
+                commands::replay(&db, journal, *continue_on_error, *dry_run, quiet)?;
+            }
+            #[cfg(feature = "server")]
+            Commands::Serve {
+                port,
+                bind,
+                allowed_hosts,
+                allow_write,
+                ..
+            } => {
+                commands::serve(
+                    db.clone(),
+                    bind,
+                    *port,
+                    allowed_hosts.clone(),
+                    config.normalize_tags,
+                    *allow_write,
+                    config.default_exclude_dirs.clone(),
+                    quiet,
+                )?;
+            }
+            Commands::Search { .. }
+            | Commands::List { .. }
+            | Commands::Dedup { .. }
+            | Commands::Get { .. }
+            | Commands::Has { .. }
+            | Commands::Export { .. } => {
+                unreachable!()
+            }
+            #[cfg(unix)]
+            Commands::Daemon { .. } => unreachable!(),
+            Commands::Db { .. }
+            | Commands::Config { .. }
+            | Commands::Completions { .. }
+            | Commands::Preview { .. } => unreachable!(),
         }
     }
 
@@ -0,0 +1,64 @@
+//! Minimal CSV writing for exports
+//!
+//! Fields are quoted only when needed: when they contain the delimiter, a
+//! double quote, or a line break. Embedded quotes are doubled (RFC 4180).
+
+use std::borrow::Cow;
+
+/// Quote a single field if it contains a comma, quote, or line break
+///
+/// # Examples
+///
+/// ```
+/// use tagr::output::csv::quote_field;
+///
+/// assert_eq!(quote_field("plain"), "plain");
+/// assert_eq!(quote_field("a,b"), "\"a,b\"");
+/// assert_eq!(quote_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+/// ```
+#[must_use]
+pub fn quote_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Format one CSV record, terminated by a newline
+#[must_use]
+pub fn format_row<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut row = fields
+        .iter()
+        .map(|field| quote_field(field.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push('\n');
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_row_quotes_special_fields() {
+        assert_eq!(format_row(&["/a/b.txt", "rust"]), "/a/b.txt,rust\n");
+        assert_eq!(
+            format_row(&["/a/x,y.txt", "line\nbreak", "q\"t"]),
+            "\"/a/x,y.txt\",\"line\nbreak\",\"q\"\"t\"\n"
+        );
+        assert_eq!(format_row(&["", "carriage\r"]), ",\"carriage\r\"\n");
+    }
+
+    #[test]
+    fn test_format_row_round_trips_through_csv_reader() {
+        let fields = ["/tmp/a \"b\", c\n.txt", "tag;other"];
+        let row = format_row(&fields);
+        let mut reader = ::csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(row.as_bytes());
+        let record = reader.records().next().unwrap().unwrap();
+        assert_eq!(record.iter().collect::<Vec<_>>(), fields);
+    }
+}
@@ -1,11 +1,22 @@
 //! Output formatting for CLI display
 //!
 //! This module provides utilities for formatting output in the CLI,
-//! including path display formatting and file/tag formatting.
+//! including path display formatting, file/tag formatting, aligned tables and
+//! CSV records.
 
+pub mod csv;
+pub mod table;
+
+pub use table::TableFormatter;
+
+use crate::Pair;
+use crate::cli::ListFormat;
 use crate::config::PathFormat;
 use colored::Colorize;
-use std::path::Path;
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Format a path according to the display mode
 #[must_use]
@@ -21,16 +32,93 @@ pub fn format_path(path: &Path, format: PathFormat) -> String {
             // Fallback to absolute if relative path cannot be computed
             path.display().to_string()
         }
+        PathFormat::LastN(n) => last_components(path, n),
     }
 }
 
+/// Format paths for display together, keeping `last-N` paths distinguishable
+///
+/// With [`PathFormat::LastN`], paths that would display the same show one
+/// more component, repeatedly, until every path is unique or shows
+/// `max_components`. Other formats format each path on its own.
+///
+/// # Examples
+/// ```
+/// use tagr::config::PathFormat;
+/// use tagr::output::format_paths;
+///
+/// let paths = ["/src/a/mod.rs", "/src/b/mod.rs", "/src/b/lib.rs"];
+/// assert_eq!(
+///     format_paths(&paths, PathFormat::LastN(1), 8),
+///     ["a/mod.rs", "b/mod.rs", "lib.rs"]
+/// );
+/// ```
+#[must_use]
+pub fn format_paths<P: AsRef<Path>>(
+    paths: &[P],
+    format: PathFormat,
+    max_components: usize,
+) -> Vec<String> {
+    let PathFormat::LastN(n) = format else {
+        return paths
+            .iter()
+            .map(|path| format_path(path.as_ref(), format))
+            .collect();
+    };
+
+    let mut shown = vec![n; paths.len()];
+    loop {
+        let labels: Vec<String> = paths
+            .iter()
+            .zip(&shown)
+            .map(|(path, &n)| last_components(path.as_ref(), n))
+            .collect();
+
+        let mut by_label: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, label) in labels.iter().enumerate() {
+            by_label.entry(label).or_default().push(i);
+        }
+        let mut grew = false;
+        for i in by_label
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .flatten()
+        {
+            if shown[i] < max_components && shown[i] < paths[i].as_ref().components().count() {
+                shown[i] += 1;
+                grew = true;
+            }
+        }
+        if !grew {
+            return labels;
+        }
+    }
+}
+
+/// The last `n` components of `path`, or all of it if it has no more
+fn last_components(path: &Path, n: usize) -> String {
+    let components: Vec<_> = path.components().collect();
+    if n >= components.len() {
+        return path.display().to_string();
+    }
+    components[components.len() - n..]
+        .iter()
+        .collect::<PathBuf>()
+        .display()
+        .to_string()
+}
+
 /// Format a file with its tags for display
 #[must_use]
 pub fn file_with_tags(path: &Path, tags: &[String], format: PathFormat, quiet: bool) -> String {
-    let path_str = format_path(path, format);
+    labeled_file_with_tags(&format_path(path, format), tags, quiet)
+}
 
+/// Format an already formatted path with its tags for display
+#[must_use]
+pub fn labeled_file_with_tags(path_str: &str, tags: &[String], quiet: bool) -> String {
     if quiet {
-        path_str
+        path_str.to_string()
     } else if tags.is_empty() {
         format!("  {path_str} (no tags)")
     } else {
@@ -48,6 +136,198 @@ pub fn tag_with_count(tag: &str, count: usize, quiet: bool) -> String {
     }
 }
 
+/// Number of cells in a bar for `count` scaled against `max` over `width` cells
+///
+/// Rounds to the nearest cell, but any non-zero count gets at least one cell
+/// so rare tags stay visible.
+#[must_use]
+pub const fn bar_width(count: usize, max: usize, width: usize) -> usize {
+    if count == 0 || max == 0 {
+        return 0;
+    }
+    let cells = (count * width + max / 2) / max;
+    if cells == 0 {
+        1
+    } else if cells > width {
+        width
+    } else {
+        cells
+    }
+}
+
+/// Render `(tag, count)` pairs as a bar chart scaled so `max` fills `width` cells
+///
+/// Tags are padded to a common display width and each bar is followed by its
+/// count: `rust   ████████████ 12`.
+#[must_use]
+pub fn format_tag_bars(counts: &[(String, usize)], max: usize, width: usize) -> String {
+    let tag_width = counts.iter().map(|(tag, _)| tag.width()).max().unwrap_or(0);
+    let mut chart = String::new();
+    for (tag, count) in counts {
+        let bar = "█".repeat(bar_width(*count, max, width));
+        chart.push_str(&format!(
+            "{tag}{}  {} {count}\n",
+            " ".repeat(tag_width - tag.width()),
+            bar.cyan()
+        ));
+    }
+    chart
+}
+
+/// Render files as a markdown index under a `# Tagged Files` header
+///
+/// Each file becomes a list item linking its name to its path relative to the
+/// current directory, followed by its tags as code spans:
+/// ``- [main.rs](src/main.rs) `rust` `cli` ``
+#[must_use]
+pub fn format_pairs_as_markdown(pairs: &[Pair]) -> String {
+    let mut out = String::from("# Tagged Files\n\n");
+    for pair in pairs {
+        out.push_str(&markdown_item(pair));
+    }
+    out
+}
+
+/// Render grouped files as a markdown index with a `## tag` section per group
+#[must_use]
+pub fn format_groups_as_markdown(groups: &[(String, Vec<Pair>)]) -> String {
+    let mut out = String::from("# Tagged Files\n");
+    for (tag, pairs) in groups {
+        out.push_str(&format!("\n## {tag}\n\n"));
+        for pair in pairs {
+            out.push_str(&markdown_item(pair));
+        }
+    }
+    out
+}
+
+fn markdown_item(pair: &Pair) -> String {
+    let href = format_path(&pair.file, PathFormat::Relative);
+    // Angle brackets let the link target contain spaces and parentheses
+    let href = if href.contains([' ', '(', ')']) {
+        format!("<{href}>")
+    } else {
+        href
+    };
+    let name = pair
+        .file
+        .file_name()
+        .map_or_else(
+            || pair.file.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+        .replace('[', "\\[")
+        .replace(']', "\\]");
+    let mut item = format!("- [{name}]({href})");
+    for tag in &pair.tags {
+        item.push_str(&format!(" `{tag}`"));
+    }
+    item.push('\n');
+    item
+}
+
+/// Writes one compact JSON object per line (JSON Lines)
+///
+/// Each line is flushed as soon as it is written, so a consumer reading from
+/// a pipe (e.g. `tagr search --format jsonl | jq`) sees results while the rest
+/// are still being produced.
+///
+/// # Examples
+///
+/// ```
+/// use tagr::output::JsonLines;
+///
+/// let mut lines = JsonLines::new(Vec::new());
+/// lines.write(&serde_json::json!({"file": "/a.txt", "tags": ["x"]})).unwrap();
+/// assert_eq!(lines.into_inner(), b"{\"file\":\"/a.txt\",\"tags\":[\"x\"]}\n");
+/// ```
+pub struct JsonLines<W: Write> {
+    writer: W,
+}
+
+impl JsonLines<std::io::Stdout> {
+    /// Write lines to stdout
+    #[must_use]
+    pub fn stdout() -> Self {
+        Self::new(std::io::stdout())
+    }
+}
+
+impl<W: Write> JsonLines<W> {
+    /// Write lines to `writer`
+    pub const fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Write `value` as one line and flush it
+    ///
+    /// # Errors
+    /// Returns an I/O error if writing or flushing fails, including
+    /// `BrokenPipe` when the reader has gone away.
+    pub fn write(&mut self, value: &serde_json::Value) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, value)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+
+    /// Write a file and its tags as `{"file": ..., "tags": [...]}`
+    ///
+    /// # Errors
+    /// See [`JsonLines::write`].
+    pub fn write_pair(&mut self, pair: &Pair, format: PathFormat) -> std::io::Result<()> {
+        self.write(&serde_json::json!({
+            "file": format_path(&pair.file, format),
+            "tags": pair.tags,
+        }))
+    }
+
+    /// Consume the writer, returning the underlying output
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Treat a reader closing the pipe (e.g. `| head`) as the normal end of output
+///
+/// # Errors
+/// Returns `error` unless it is `BrokenPipe`.
+pub fn end_on_broken_pipe(error: std::io::Error) -> std::io::Result<()> {
+    if error.kind() == std::io::ErrorKind::BrokenPipe {
+        Ok(())
+    } else {
+        Err(error)
+    }
+}
+
+/// Print JSON entries as a pretty array, or one per line for [`ListFormat::Jsonl`]
+///
+/// # Errors
+/// Returns an I/O error if writing fails for a reason other than a closed pipe.
+pub fn print_json(entries: &[serde_json::Value], format: ListFormat) -> std::io::Result<()> {
+    if format == ListFormat::Jsonl {
+        let mut lines = JsonLines::stdout();
+        for entry in entries {
+            if let Err(e) = lines.write(entry) {
+                return end_on_broken_pipe(e);
+            }
+        }
+        return Ok(());
+    }
+    println!("{}", serde_json::to_string_pretty(entries)?);
+    Ok(())
+}
+
+/// Warn on stderr about entries skipped with `--skip-corrupt`
+pub fn warn_corrupt_entries(corrupt: &[crate::db::DbError]) {
+    if corrupt.is_empty() {
+        return;
+    }
+    eprintln!("Warning: skipped {} corrupt entry(ies):", corrupt.len());
+    for err in corrupt {
+        eprintln!("  - {err}");
+    }
+}
+
 /// Color a path based on file existence (green if exists, red if missing)
 #[must_use]
 pub fn colorize_path(path: &Path, format: PathFormat) -> String {
@@ -58,3 +338,348 @@ pub fn colorize_path(path: &Path, format: PathFormat) -> String {
         formatted.red().to_string()
     }
 }
+
+/// Format files and their tags as an aligned table
+///
+/// Columns are file path, tag count and tags. Widths are measured in terminal
+/// display columns, so CJK and other wide characters line up correctly. The
+/// tags column is truncated with an ellipsis so each row fits in `width`;
+/// paths are never truncated.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use tagr::{Pair, config::PathFormat, output::format_table};
+///
+/// let pairs = vec![Pair::new(
+///     PathBuf::from("/docs/a.txt"),
+///     vec!["rust".into(), "notes".into()],
+/// )];
+/// let table = format_table(&pairs, PathFormat::Absolute, 80);
+/// assert!(table.contains("/docs/a.txt  2  rust, notes"));
+/// ```
+#[must_use]
+pub fn format_table(pairs: &[Pair], format: PathFormat, width: usize) -> String {
+    let labels: Vec<String> = pairs
+        .iter()
+        .map(|pair| format_path(&pair.file, format))
+        .collect();
+    format_labeled_table(pairs, &labels, width)
+}
+
+/// Files and their tags as a [`TableFormatter`] with the columns of
+/// [`format_table`]
+#[must_use]
+pub fn pairs_table(pairs: &[Pair], format: PathFormat) -> TableFormatter {
+    let mut table = TableFormatter::new(&["PATH", "#", "TAGS"]);
+    for pair in pairs {
+        table.add_row(&[
+            &format_path(&pair.file, format),
+            &pair.tags.len().to_string(),
+            &pair.tags.join(", "),
+        ]);
+    }
+    table
+}
+
+/// Format files and their tags as an aligned table, showing `labels[i]` as
+/// the path of `pairs[i]`
+///
+/// Used when the displayed paths depend on each other, as with
+/// [`format_paths`]. See [`format_table`] for the layout.
+#[must_use]
+pub fn format_labeled_table(pairs: &[Pair], labels: &[String], width: usize) -> String {
+    const PATH_HEADER: &str = "PATH";
+    const COUNT_HEADER: &str = "#";
+    const TAGS_HEADER: &str = "TAGS";
+    const GAP: &str = "  ";
+
+    let rows: Vec<(String, String, String)> = pairs
+        .iter()
+        .zip(labels)
+        .map(|(pair, label)| {
+            (
+                label.clone(),
+                pair.tags.len().to_string(),
+                pair.tags.join(", "),
+            )
+        })
+        .collect();
+
+    let path_width = rows
+        .iter()
+        .map(|(path, _, _)| path.width())
+        .chain(std::iter::once(PATH_HEADER.width()))
+        .max()
+        .unwrap_or(0);
+    let count_width = rows
+        .iter()
+        .map(|(_, count, _)| count.width())
+        .chain(std::iter::once(COUNT_HEADER.width()))
+        .max()
+        .unwrap_or(0);
+    let tags_width = width
+        .saturating_sub(path_width + count_width + 2 * GAP.len())
+        .max(1);
+
+    let mut table = String::new();
+    let header = (PATH_HEADER, COUNT_HEADER, TAGS_HEADER);
+    for (path, count, tags) in std::iter::once(header).chain(
+        rows.iter()
+            .map(|(p, c, t)| (p.as_str(), c.as_str(), t.as_str())),
+    ) {
+        let line = format!(
+            "{path}{}{GAP}{}{count}{GAP}{}",
+            " ".repeat(path_width - path.width()),
+            " ".repeat(count_width - count.width()),
+            truncate_to_width(tags, tags_width),
+        );
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+/// Width of the terminal attached to stdout, if stdout is a terminal
+///
+/// Returns `None` when output is piped or redirected, so callers can fall back
+/// to line-oriented output.
+#[must_use]
+pub fn terminal_width() -> Option<usize> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| usize::from(columns))
+}
+
+/// A window of results selected with `--offset` and `--limit`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Page {
+    /// Number of leading results to skip
+    pub offset: usize,
+    /// Maximum number of results to keep (None = all remaining)
+    pub limit: Option<usize>,
+}
+
+impl Page {
+    /// Keep only the items inside this page
+    ///
+    /// Callers must sort `items` first so pages stay consistent between runs.
+    pub fn apply<T>(self, items: &mut Vec<T>) {
+        items.drain(..self.offset.min(items.len()));
+        if let Some(limit) = self.limit {
+            items.truncate(limit);
+        }
+    }
+
+    /// Footer like "showing 1-50 of 3200", or `None` when not paginating
+    #[must_use]
+    pub fn footer(self, shown: usize, total: usize) -> Option<String> {
+        if self.offset == 0 && self.limit.is_none() {
+            return None;
+        }
+        if shown == 0 {
+            return Some(format!("showing 0 of {total}"));
+        }
+        Some(format!(
+            "showing {}-{} of {total}",
+            self.offset + 1,
+            self.offset + shown
+        ))
+    }
+}
+
+/// Truncate `text` to at most `max_width` display columns, ending with `…`
+fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+
+    let budget = max_width.saturating_sub(1);
+    let mut used = 0;
+    let mut truncated = String::new();
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if used + char_width > budget {
+            break;
+        }
+        used += char_width;
+        truncated.push(c);
+    }
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn pair(path: &str, tags: &[&str]) -> Pair {
+        Pair::new(
+            PathBuf::from(path),
+            tags.iter().map(|t| (*t).to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_format_pairs_as_markdown() {
+        let pairs = [
+            pair("/notes/todo list.md", &["todo", "docs"]),
+            pair("/src/[draft].rs", &[]),
+        ];
+        assert_eq!(
+            format_pairs_as_markdown(&pairs),
+            "# Tagged Files\n\n\
+             - [todo list.md](</notes/todo list.md>) `todo` `docs`\n\
+             - [\\[draft\\].rs](/src/[draft].rs)\n"
+        );
+
+        let groups = vec![
+            ("docs".to_string(), vec![pair("/a.md", &["docs"])]),
+            ("rust".to_string(), vec![pair("/b.rs", &["rust"])]),
+        ];
+        assert_eq!(
+            format_groups_as_markdown(&groups),
+            "# Tagged Files\n\n## docs\n\n- [a.md](/a.md) `docs`\n\n## rust\n\n- [b.rs](/b.rs) `rust`\n"
+        );
+    }
+
+    #[test]
+    fn test_json_lines_writes_one_object_per_line() {
+        let mut lines = JsonLines::new(Vec::new());
+        lines
+            .write_pair(&pair("/a.txt", &["rust"]), PathFormat::Absolute)
+            .unwrap();
+        lines
+            .write_pair(&pair("/b.md", &["docs", "notes"]), PathFormat::Absolute)
+            .unwrap();
+        let output = String::from_utf8(lines.into_inner()).unwrap();
+
+        let values: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0]["file"], "/a.txt");
+        assert_eq!(values[1]["tags"], serde_json::json!(["docs", "notes"]));
+    }
+
+    #[test]
+    fn test_format_table_aligns_by_display_width() {
+        let pairs = vec![
+            pair("/docs/日本語.txt", &["cjk"]),
+            pair("/docs/abcdefgh.txt", &[]),
+        ];
+        let table = format_table(&pairs, PathFormat::Absolute, 80);
+        let lines: Vec<&str> = table.lines().collect();
+
+        // "日本語" is three double-width characters, as wide as "abcdef"
+        assert_eq!(lines[1], "/docs/日本語.txt    1  cjk");
+        assert_eq!(lines[2], "/docs/abcdefgh.txt  0");
+    }
+
+    #[test]
+    fn test_format_table_truncates_tags_to_width() {
+        let pairs = vec![pair("/a", &["alpha", "beta", "gamma", "delta"])];
+        let table = format_table(&pairs, PathFormat::Absolute, 20);
+
+        for line in table.lines() {
+            assert!(line.width() <= 20, "line too wide: {line:?}");
+        }
+        assert!(table.lines().nth(1).unwrap().ends_with('…'));
+    }
+
+    #[test]
+    fn test_page_boundaries() {
+        let page = |offset, limit| Page { offset, limit };
+        let paged = |p: Page| {
+            let mut items: Vec<u32> = (1..=5).collect();
+            p.apply(&mut items);
+            items
+        };
+
+        assert_eq!(paged(page(0, Some(2))), vec![1, 2]);
+        assert_eq!(paged(page(3, Some(10))), vec![4, 5]);
+        assert_eq!(paged(page(4, None)), vec![5]);
+        assert_eq!(paged(page(5, Some(1))), Vec::<u32>::new());
+        assert_eq!(paged(page(9, None)), Vec::<u32>::new());
+        assert_eq!(paged(page(0, Some(0))), Vec::<u32>::new());
+
+        assert_eq!(page(0, None).footer(5, 5), None);
+        assert_eq!(
+            page(0, Some(2)).footer(2, 5).as_deref(),
+            Some("showing 1-2 of 5")
+        );
+        assert_eq!(
+            page(3, Some(10)).footer(2, 5).as_deref(),
+            Some("showing 4-5 of 5")
+        );
+        assert_eq!(
+            page(9, None).footer(0, 5).as_deref(),
+            Some("showing 0 of 5")
+        );
+    }
+
+    #[test]
+    fn test_truncate_to_width_wide_chars() {
+        assert_eq!(truncate_to_width("日本語", 6), "日本語");
+        assert_eq!(truncate_to_width("日本語", 5), "日本…");
+        assert_eq!(truncate_to_width("日本語", 4), "日…");
+    }
+
+    #[test]
+    fn test_bar_width() {
+        assert_eq!(bar_width(10, 10, 40), 40);
+        assert_eq!(bar_width(5, 10, 40), 20);
+        assert_eq!(bar_width(1, 3, 40), 13);
+        assert_eq!(bar_width(2, 3, 40), 27);
+        // Tiny counts still get a cell; zero gets none
+        assert_eq!(bar_width(1, 1000, 40), 1);
+        assert_eq!(bar_width(0, 10, 40), 0);
+        assert_eq!(bar_width(3, 0, 40), 0);
+    }
+
+    #[test]
+    fn test_format_path_last_n() {
+        let path = Path::new("/home/me/project/src/components/Button.tsx");
+        assert_eq!(
+            format_path(path, PathFormat::LastN(2)),
+            Path::new("components/Button.tsx").display().to_string()
+        );
+        assert_eq!(
+            format_path(path, PathFormat::LastN(20)),
+            path.display().to_string()
+        );
+    }
+
+    #[test]
+    fn test_format_paths_disambiguates() {
+        let paths = [
+            "/web/admin/components/Button.tsx",
+            "/web/shop/components/Button.tsx",
+            "/web/shop/components/Card.tsx",
+        ];
+        let shown = |max| format_paths(&paths, PathFormat::LastN(2), max);
+        assert_eq!(
+            shown(8),
+            [
+                "admin/components/Button.tsx",
+                "shop/components/Button.tsx",
+                "components/Card.tsx",
+            ]
+        );
+        // The maximum wins over uniqueness
+        assert_eq!(
+            shown(2),
+            [
+                "components/Button.tsx",
+                "components/Button.tsx",
+                "components/Card.tsx",
+            ]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Aligned tables for list-style commands
+//!
+//! [`TableFormatter`] collects a header and rows of cells, then renders them
+//! padded to the widest cell of each column, or as tab-separated values for
+//! scripts.
+
+use super::truncate_to_width;
+use crate::cli::TableFormat;
+use colored::Colorize;
+use std::io::IsTerminal;
+use unicode_width::UnicodeWidthStr;
+
+/// Space between columns
+const GAP: &str = "  ";
+
+/// Table with a header row and aligned columns
+///
+/// # Examples
+///
+/// ```
+/// use tagr::output::TableFormatter;
+///
+/// let mut table = TableFormatter::new(&["TAG", "FILES"]);
+/// table.add_row(&["rust", "12"]);
+/// table.add_row(&["documentation", "3"]);
+/// assert_eq!(
+///     table.render(false),
+///     "TAG            FILES\nrust           12\ndocumentation  3\n"
+/// );
+/// assert_eq!(
+///     table.render_tsv(),
+///     "TAG\tFILES\nrust\t12\ndocumentation\t3\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TableFormatter {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    max_width: Option<usize>,
+}
+
+impl TableFormatter {
+    /// Empty table with the given column headers
+    #[must_use]
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(ToString::to_string).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Truncate cells wider than `max_width` display columns with `…`
+    #[must_use]
+    pub const fn with_max_width(mut self, max_width: Option<usize>) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    /// Append a row; missing cells are left blank and extra cells dropped
+    pub fn add_row(&mut self, cells: &[&str]) {
+        let mut row: Vec<String> = cells
+            .iter()
+            .take(self.headers.len())
+            .map(ToString::to_string)
+            .collect();
+        row.resize(self.headers.len(), String::new());
+        self.rows.push(row);
+    }
+
+    /// Whether no rows were added
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Render with every column padded to its widest cell
+    ///
+    /// Widths are measured in terminal display columns. With `color`, the
+    /// header is bold and every other row is shaded.
+    #[must_use]
+    pub fn render(&self, color: bool) -> String {
+        let cells = |row: &[String]| -> Vec<String> {
+            row.iter()
+                .map(|cell| match self.max_width {
+                    Some(max) => truncate_to_width(cell, max.max(1)),
+                    None => cell.clone(),
+                })
+                .collect()
+        };
+        let header = cells(&self.headers);
+        let rows: Vec<Vec<String>> = self.rows.iter().map(|row| cells(row)).collect();
+
+        let widths: Vec<usize> = (0..header.len())
+            .map(|column| {
+                std::iter::once(&header)
+                    .chain(&rows)
+                    .map(|row| row[column].width())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let mut table = String::new();
+        for (index, row) in std::iter::once(&header).chain(&rows).enumerate() {
+            let mut line = String::new();
+            for (column, cell) in row.iter().enumerate() {
+                if column > 0 {
+                    line.push_str(GAP);
+                }
+                line.push_str(cell);
+                line.push_str(&" ".repeat(widths[column] - cell.width()));
+            }
+            let line = line.trim_end();
+
+            if !color {
+                table.push_str(line);
+            } else if index == 0 {
+                table.push_str(&line.bold().to_string());
+            } else if index % 2 == 0 {
+                let padded = format!("{line}{}", " ".repeat(row_width(&widths) - line.width()));
+                table.push_str(&padded.on_bright_black().to_string());
+            } else {
+                table.push_str(line);
+            }
+            table.push('\n');
+        }
+        table
+    }
+
+    /// Render as tab-separated values without padding or truncation
+    ///
+    /// Tabs and newlines inside cells become spaces so every row stays one
+    /// line, ready for `column -t -s $'\t'` or `cut`.
+    #[must_use]
+    pub fn render_tsv(&self) -> String {
+        let mut tsv = String::new();
+        for row in std::iter::once(&self.headers).chain(&self.rows) {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|cell| cell.replace(['\t', '\n', '\r'], " "))
+                .collect();
+            tsv.push_str(&cells.join("\t"));
+            tsv.push('\n');
+        }
+        tsv
+    }
+
+    /// Print to stdout as `format`, shading text output on a terminal
+    pub fn print(&self, format: TableFormat) {
+        match format {
+            TableFormat::Text => print!("{}", self.render(std::io::stdout().is_terminal())),
+            TableFormat::Tsv => print!("{}", self.render_tsv()),
+        }
+    }
+}
+
+/// Display width of a full row with the given column widths
+fn row_width(widths: &[usize]) -> usize {
+    widths.iter().sum::<usize>() + GAP.len() * widths.len().saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_pads_columns_and_truncates() {
+        let mut table = TableFormatter::new(&["NAME", "PATH"]).with_max_width(Some(8));
+        table.add_row(&["work", "/home/user/work/db"]);
+        table.add_row(&["日本語", "/tmp"]);
+
+        assert_eq!(
+            table.render(false),
+            "NAME    PATH\nwork    /home/u…\n日本語  /tmp\n"
+        );
+    }
+
+    #[test]
+    fn test_add_row_fills_missing_cells() {
+        let mut table = TableFormatter::new(&["A", "B", "C"]);
+        table.add_row(&["1"]);
+        table.add_row(&["1", "2", "3", "4"]);
+
+        assert_eq!(table.render_tsv(), "A\tB\tC\n1\t\t\n1\t2\t3\n");
+        assert_eq!(table.render(false), "A  B  C\n1\n1  2  3\n");
+    }
+
+    #[test]
+    fn test_render_tsv_keeps_rows_on_one_line() {
+        let mut table = TableFormatter::new(&["TAG", "NOTE"]).with_max_width(Some(2));
+        table.add_row(&["rust", "a\tb\nc"]);
+
+        assert_eq!(table.render_tsv(), "TAG\tNOTE\nrust\ta b c\n");
+    }
+}
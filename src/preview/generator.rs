@@ -1,4 +1,6 @@
 use super::error::{PreviewError, Result};
+use super::image;
+use super::prefix::{read_text_prefix, truncation_notice};
 use super::types::{FileMetadata, ImageMetadata, PreviewContent};
 use crate::ui::PreviewConfig;
 use std::fs;
@@ -53,7 +55,6 @@ impl PreviewGenerator {
     /// - The file cannot be read (I/O error)
     /// - The file path is invalid or contains non-UTF-8 characters
     /// - The file exceeds the maximum size limit
-    /// - Image metadata cannot be extracted
     pub fn generate(&self, path: &Path) -> Result<PreviewContent> {
         if !path.exists() {
             return Ok(PreviewContent::Error(format!(
@@ -83,25 +84,22 @@ impl PreviewGenerator {
         }
     }
 
-    fn generate_text_preview(&self, path: &Path, _file_size: u64) -> Result<PreviewContent> {
-        // Try bat first if available and syntax highlighting is enabled
+    fn generate_text_preview(&self, path: &Path, file_size: u64) -> Result<PreviewContent> {
+        // Try bat first if available and syntax highlighting is enabled; bat
+        // reads the whole file, so it is skipped for files over the byte cap
         if self.config.syntax_highlighting
             && self.bat_available
+            && file_size <= self.config.max_preview_bytes
             && let Ok(highlighted) = self.generate_bat_preview(path)
         {
             return Ok(highlighted);
         }
 
-        // Fallback to syntect or plain text
-        let content = fs::read_to_string(path).map_err(|e| {
-            if e.kind() == std::io::ErrorKind::InvalidData {
-                PreviewError::InvalidUtf8(path.display().to_string())
-            } else {
-                PreviewError::IoError(e)
-            }
-        })?;
+        // Fallback to syntect or plain text, reading no more than the byte cap
+        let prefix = read_text_prefix(path, self.config.max_preview_bytes)?
+            .ok_or_else(|| PreviewError::InvalidUtf8(path.display().to_string()))?;
 
-        let all_lines: Vec<String> = content.lines().map(String::from).collect();
+        let all_lines: Vec<String> = prefix.text.lines().map(String::from).collect();
         let total_lines = all_lines.len();
         let max_lines = self.config.max_lines;
 
@@ -122,7 +120,11 @@ impl PreviewGenerator {
         #[cfg(not(feature = "syntax-highlighting"))]
         let has_ansi = false;
 
-        let truncated = total_lines > max_lines;
+        let mut lines = lines;
+        if prefix.truncated {
+            lines.push(truncation_notice(file_size));
+        }
+        let truncated = total_lines > max_lines || prefix.truncated;
 
         Ok(PreviewContent::Text {
             lines,
@@ -201,11 +203,18 @@ impl PreviewGenerator {
             file_type: Self::detect_file_type(path),
         };
 
-        if Self::is_image(path)
-            && let Some(image_meta) = Self::extract_image_metadata(path, file_metadata.clone())
+        if image::is_image(path)
+            && let Some(header) = image::read_header(path)
         {
             return PreviewContent::Image {
-                metadata: image_meta,
+                path: path.to_path_buf(),
+                width: header.width,
+                height: header.height,
+                metadata: ImageMetadata {
+                    file_metadata,
+                    format: header.format,
+                    color_mode: Some(header.color_mode),
+                },
             };
         }
 
@@ -246,38 +255,6 @@ impl PreviewGenerator {
             .and_then(|e| e.to_str())
             .map(str::to_uppercase)
     }
-
-    fn is_image(path: &Path) -> bool {
-        path.extension()
-            .and_then(|e| e.to_str())
-            .is_some_and(|ext| {
-                matches!(
-                    ext.to_lowercase().as_str(),
-                    "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "svg" | "ico"
-                )
-            })
-    }
-
-    /// Extract image-specific metadata.
-    ///
-    /// **Note**: This is a stub implementation. Currently returns placeholder
-    /// metadata without actual image dimensions. Returns `Option` for API
-    /// consistency with future implementation using the `image` crate.
-    ///
-    /// # TODO
-    /// Use image crate to extract actual dimensions and format information.
-    #[allow(clippy::unnecessary_wraps)]
-    const fn extract_image_metadata(
-        _path: &Path,
-        file_metadata: FileMetadata,
-    ) -> Option<ImageMetadata> {
-        Some(ImageMetadata {
-            file_metadata,
-            width: None,
-            height: None,
-            format: None,
-        })
-    }
 }
 
 #[cfg(test)]
@@ -346,6 +323,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_caps_bytes_read() {
+        let temp = TempFile::create("big.txt").unwrap();
+        let mut content = "Line 1\nLine 2\n".as_bytes().to_vec();
+        // Bytes past the cap would make the file look binary
+        content.extend_from_slice(&[0xff; 64]);
+        fs::write(temp.path(), content).unwrap();
+
+        let config = PreviewConfig {
+            max_preview_bytes: 10,
+            syntax_highlighting: false,
+            ..Default::default()
+        };
+        let generator = PreviewGenerator::new(config);
+        let preview = generator.generate(temp.path()).unwrap();
+
+        match preview {
+            PreviewContent::Text {
+                lines, truncated, ..
+            } => {
+                assert_eq!(lines, ["Line 1", "Lin", "… truncated (file is 78 B)"]);
+                assert!(truncated);
+            }
+            _ => panic!("Expected Text preview, got {preview:?}"),
+        }
+    }
+
     #[test]
     fn test_generate_empty_file_preview() {
         let temp = TempFile::create("empty.txt").unwrap();
@@ -0,0 +1,98 @@
+//! Image detection and header probing
+//!
+//! Shared by the plain-text preview and the TUI preview pane. Only the image
+//! header is decoded, so probing stays cheap even for large files.
+
+use image::{ColorType, ImageDecoder, ImageReader};
+use std::path::Path;
+
+/// Extensions treated as images by the preview system
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp", "svg", "ico"];
+
+/// Dimensions, format and color mode read from an image header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageHeader {
+    /// Image width in pixels
+    pub width: u32,
+    /// Image height in pixels
+    pub height: u32,
+    /// Image format (PNG, JPEG, etc.)
+    pub format: Option<String>,
+    /// Color mode (e.g. "RGBA 8-bit")
+    pub color_mode: String,
+}
+
+/// Check whether a path looks like an image based on its extension
+#[must_use]
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Read the header of a raster image
+///
+/// Returns `None` for vector formats (SVG), unsupported formats and files
+/// that cannot be decoded.
+#[must_use]
+pub fn read_header(path: &Path) -> Option<ImageHeader> {
+    let reader = ImageReader::open(path).ok()?.with_guessed_format().ok()?;
+    let format = reader
+        .format()
+        .map(|format| format!("{format:?}").to_uppercase());
+    let decoder = reader.into_decoder().ok()?;
+    let (width, height) = decoder.dimensions();
+
+    Some(ImageHeader {
+        width,
+        height,
+        format,
+        color_mode: describe_color_type(decoder.color_type()),
+    })
+}
+
+fn describe_color_type(color: ColorType) -> String {
+    match color {
+        ColorType::L8 => "Grayscale 8-bit".to_string(),
+        ColorType::La8 => "Grayscale + alpha 8-bit".to_string(),
+        ColorType::Rgb8 => "RGB 8-bit".to_string(),
+        ColorType::Rgba8 => "RGBA 8-bit".to_string(),
+        ColorType::L16 => "Grayscale 16-bit".to_string(),
+        ColorType::La16 => "Grayscale + alpha 16-bit".to_string(),
+        ColorType::Rgb16 => "RGB 16-bit".to_string(),
+        ColorType::Rgba16 => "RGBA 16-bit".to_string(),
+        ColorType::Rgb32F => "RGB 32-bit float".to_string(),
+        ColorType::Rgba32F => "RGBA 32-bit float".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempFile;
+
+    #[test]
+    fn test_read_header_png() {
+        let temp = TempFile::create("pixel.png").unwrap();
+        image::RgbaImage::new(3, 2).save(temp.path()).unwrap();
+
+        assert!(is_image(temp.path()));
+        let header = read_header(temp.path()).unwrap();
+        assert_eq!((header.width, header.height), (3, 2));
+        assert_eq!(header.format.as_deref(), Some("PNG"));
+        assert_eq!(header.color_mode, "RGBA 8-bit");
+    }
+
+    #[test]
+    fn test_read_header_svg_is_none() {
+        let temp = TempFile::create_with_content(
+            "icon.svg",
+            b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>",
+        )
+        .unwrap();
+
+        assert!(is_image(temp.path()));
+        assert!(read_header(temp.path()).is_none());
+    }
+}
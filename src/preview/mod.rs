@@ -2,10 +2,14 @@
 
 mod error;
 mod generator;
+pub mod image;
+mod prefix;
 mod provider;
 mod types;
 
 pub use error::{PreviewError, Result};
 pub use generator::PreviewGenerator;
+pub use image::ImageHeader;
+pub use prefix::{DEFAULT_MAX_PREVIEW_BYTES, TextPrefix, read_text_prefix, truncation_notice};
 pub use provider::FilePreviewProvider;
 pub use types::{FileMetadata, ImageMetadata, PreviewContent};
@@ -0,0 +1,91 @@
+//! Bounded reads so previewing a huge file never loads all of it
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Default number of bytes read for a preview (5 MiB)
+pub const DEFAULT_MAX_PREVIEW_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Text read from the start of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextPrefix {
+    /// The decoded text, at most the byte cap long
+    pub text: String,
+    /// Whether the file continues past the cap
+    pub truncated: bool,
+}
+
+/// Read at most `max_bytes` from the start of `path` as UTF-8 text
+///
+/// A multi-byte character split by the cap is dropped rather than treated as
+/// invalid, so binary detection only considers bytes that were fully read.
+///
+/// # Returns
+/// `None` if the prefix is not valid UTF-8 (a binary file)
+///
+/// # Errors
+/// Returns an I/O error if the file cannot be opened or read.
+pub fn read_text_prefix(path: &Path, max_bytes: u64) -> io::Result<Option<TextPrefix>> {
+    let mut bytes = Vec::new();
+    File::open(path)?
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut bytes)?;
+    let truncated = bytes.len() as u64 > max_bytes;
+    if truncated {
+        bytes.truncate(usize::try_from(max_bytes).unwrap_or(usize::MAX));
+    }
+
+    let text = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) if truncated && e.utf8_error().error_len().is_none() => {
+            let valid = e.utf8_error().valid_up_to();
+            let mut bytes = e.into_bytes();
+            bytes.truncate(valid);
+            match String::from_utf8(bytes) {
+                Ok(text) => text,
+                Err(_) => return Ok(None),
+            }
+        }
+        Err(_) => return Ok(None),
+    };
+    Ok(Some(TextPrefix { text, truncated }))
+}
+
+/// Line appended to a preview cut off by the byte cap
+#[must_use]
+pub fn truncation_notice(file_size: u64) -> String {
+    use byte_unit::{Byte, UnitType};
+    format!(
+        "… truncated (file is {:.1})",
+        Byte::from_u64(file_size).get_appropriate_unit(UnitType::Binary)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempFile;
+    use std::fs;
+
+    #[test]
+    fn test_read_text_prefix_caps_and_detects_binary() {
+        let temp = TempFile::create("prefix.txt").unwrap();
+        fs::write(temp.path(), "héllo world").unwrap();
+
+        let whole = read_text_prefix(temp.path(), 100).unwrap().unwrap();
+        assert_eq!(whole.text, "héllo world");
+        assert!(!whole.truncated);
+
+        // The cap falls inside the two-byte 'é', which is dropped
+        let split = read_text_prefix(temp.path(), 2).unwrap().unwrap();
+        assert_eq!(split.text, "h");
+        assert!(split.truncated);
+
+        // Binary bytes past the cap are never read
+        fs::write(temp.path(), b"text\0\xff\xfe").unwrap();
+        let text = read_text_prefix(temp.path(), 4).unwrap().unwrap();
+        assert_eq!(text.text, "text");
+        assert!(read_text_prefix(temp.path(), 6).unwrap().is_none());
+    }
+}
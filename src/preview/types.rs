@@ -23,8 +23,14 @@ pub enum PreviewContent {
         metadata: FileMetadata,
     },
 
-    /// Image file with metadata
+    /// Raster image with known dimensions
     Image {
+        /// Image file path
+        path: PathBuf,
+        /// Image width in pixels
+        width: u32,
+        /// Image height in pixels
+        height: u32,
         /// Image-specific metadata
         metadata: ImageMetadata,
     },
@@ -76,12 +82,10 @@ pub struct FileMetadata {
 pub struct ImageMetadata {
     /// Base file metadata
     pub file_metadata: FileMetadata,
-    /// Image width in pixels
-    pub width: Option<u32>,
-    /// Image height in pixels
-    pub height: Option<u32>,
     /// Image format (PNG, JPEG, etc.)
     pub format: Option<String>,
+    /// Color mode (e.g. "RGBA 8-bit")
+    pub color_mode: Option<String>,
 }
 
 impl PreviewContent {
@@ -94,6 +98,18 @@ impl PreviewContent {
         }
     }
 
+    /// Render the content as plain text, without ANSI escape codes
+    ///
+    /// Identical to the `Display` output apart from the stripped escapes, so
+    /// truncation footers and metadata layouts are preserved.
+    #[must_use]
+    pub fn to_plain_string(&self) -> String {
+        match self {
+            Self::Text { has_ansi: true, .. } => strip_ansi(&self.to_string()),
+            _ => self.to_string(),
+        }
+    }
+
     /// Get a display string for the content
     ///
     /// # Note
@@ -135,7 +151,12 @@ impl std::fmt::Display for PreviewContent {
                 Ok(())
             }
             Self::Binary { metadata } => write!(f, "{}", format_file_metadata(metadata)),
-            Self::Image { metadata } => write!(f, "{}", format_image_metadata(metadata)),
+            Self::Image {
+                width,
+                height,
+                metadata,
+                ..
+            } => write!(f, "{}", format_image_metadata(*width, *height, metadata)),
             Self::Archive {
                 contents,
                 truncated,
@@ -166,6 +187,44 @@ impl std::fmt::Display for PreviewContent {
     }
 }
 
+/// Remove ANSI escape sequences (CSI and OSC) from text
+fn strip_ansi(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters and intermediates, terminated by a final byte
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    output
+}
+
 /// Format file metadata for display
 fn format_file_metadata(metadata: &FileMetadata) -> String {
     use byte_unit::{Byte, UnitType};
@@ -198,7 +257,7 @@ fn format_file_metadata(metadata: &FileMetadata) -> String {
 }
 
 /// Format image metadata for display
-fn format_image_metadata(metadata: &ImageMetadata) -> String {
+fn format_image_metadata(width: u32, height: u32, metadata: &ImageMetadata) -> String {
     use std::fmt::Write;
 
     let mut output = format_file_metadata(&metadata.file_metadata);
@@ -208,8 +267,10 @@ fn format_image_metadata(metadata: &ImageMetadata) -> String {
         let _ = writeln!(output, "Format: {format}");
     }
 
-    if let (Some(width), Some(height)) = (metadata.width, metadata.height) {
-        let _ = writeln!(output, "Dimensions: {width} x {height} pixels");
+    let _ = writeln!(output, "Dimensions: {width} x {height} pixels");
+
+    if let Some(color_mode) = &metadata.color_mode {
+        let _ = writeln!(output, "Color mode: {color_mode}");
     }
 
     output
@@ -224,3 +285,49 @@ fn format_timestamp(timestamp: i64) -> String {
         |dt: DateTime<Local>| dt.format("%Y-%m-%d %H:%M:%S").to_string(),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(
+            strip_ansi("\x1b[38;2;192;197;206mfn\x1b[0m main"),
+            "fn main"
+        );
+        assert_eq!(
+            strip_ansi("\x1b]8;;file:///a\x1b\\link\x1b]8;;\x07"),
+            "link"
+        );
+        assert_eq!(strip_ansi("plain text"), "plain text");
+    }
+
+    #[test]
+    fn test_to_plain_string_keeps_truncation_footer() {
+        let content = PreviewContent::Text {
+            lines: vec!["\x1b[1mone\x1b[0m".into(), "\x1b[1mtwo\x1b[0m".into()],
+            truncated: true,
+            total_lines: 10,
+            has_ansi: true,
+        };
+        assert_eq!(
+            content.to_plain_string(),
+            "one\ntwo\n\n[... truncated, showing 2 of 10 lines ...]"
+        );
+    }
+
+    #[test]
+    fn test_to_plain_string_matches_display_for_binary() {
+        let content = PreviewContent::Binary {
+            metadata: FileMetadata {
+                path: PathBuf::from("/tmp/data.bin"),
+                size: 2048,
+                modified: None,
+                permissions: Some("rw-r--r--".into()),
+                file_type: Some("BIN".into()),
+            },
+        };
+        assert_eq!(content.to_plain_string(), content.to_string());
+    }
+}
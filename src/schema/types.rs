@@ -118,13 +118,15 @@ impl TagSchema {
 
     /// Remove an alias
     ///
+    /// Returns `false` if `alias` was not defined.
+    ///
     /// # Errors
-    /// Returns error if alias doesn't exist
-    pub fn remove_alias(&mut self, alias: &str) -> Result<()> {
-        let canonical = self
-            .aliases
-            .remove(alias)
-            .ok_or_else(|| SchemaError::TagNotFound(alias.to_string()))?;
+    /// Currently infallible; returns `Result` for consistency with the other
+    /// alias operations.
+    pub fn remove_alias(&mut self, alias: &str) -> Result<bool> {
+        let Some(canonical) = self.aliases.remove(alias) else {
+            return Ok(false);
+        };
 
         // Remove from reverse index
         if let Some(aliases) = self.reverse_aliases.get_mut(&canonical) {
@@ -134,6 +136,36 @@ impl TagSchema {
             }
         }
 
+        Ok(true)
+    }
+
+    /// Rename an alias, keeping its canonical tag (e.g., "js" → "ecmascript")
+    ///
+    /// # Errors
+    /// Returns error if:
+    /// - `old_source` is not an alias
+    /// - `new_source` is the canonical tag itself
+    /// - `new_source` is rejected by [`Self::add_alias`]; the old alias is kept
+    pub fn rename_alias(&mut self, old_source: &str, new_source: &str) -> Result<()> {
+        let canonical = self
+            .aliases
+            .get(old_source)
+            .cloned()
+            .ok_or_else(|| SchemaError::TagNotFound(old_source.to_string()))?;
+        if old_source == new_source {
+            return Ok(());
+        }
+        if new_source == canonical {
+            return Err(SchemaError::CircularAlias(format!(
+                "Alias '{new_source}' cannot point to itself"
+            )));
+        }
+
+        self.remove_alias(old_source)?;
+        if let Err(e) = self.add_alias(new_source, &canonical) {
+            self.add_alias(old_source, &canonical)?;
+            return Err(e);
+        }
         Ok(())
     }
 
@@ -242,6 +274,27 @@ impl TagSchema {
         aliases
     }
 
+    /// Point every alias that targets `old` at `new` instead
+    ///
+    /// An alias equal to `new` is removed, since a tag cannot alias itself.
+    /// Returns the affected alias names, sorted.
+    pub fn retarget_aliases(&mut self, old: &str, new: &str) -> Vec<String> {
+        let mut affected = self.get_aliases(old);
+        affected.sort();
+
+        for alias in &affected {
+            self.aliases.remove(alias);
+            if alias != new {
+                self.aliases.insert(alias.clone(), new.to_string());
+            }
+        }
+        if !affected.is_empty() {
+            self.build_reverse_index();
+        }
+
+        affected
+    }
+
     /// Build reverse index from aliases map (used after deserialization)
     fn build_reverse_index(&mut self) {
         self.reverse_aliases.clear();
@@ -381,6 +434,47 @@ mod tests {
         assert_eq!(schema.canonicalize("es"), "javascript");
     }
 
+    #[test]
+    fn test_remove_missing_alias() {
+        let mut schema = TagSchema::new();
+        schema.add_alias("js", "javascript").unwrap();
+
+        assert!(!schema.remove_alias("py").unwrap());
+        assert!(schema.remove_alias("js").unwrap());
+        assert!(!schema.remove_alias("js").unwrap());
+    }
+
+    #[test]
+    fn test_rename_alias() {
+        let mut schema = TagSchema::new();
+        schema.add_alias("js", "javascript").unwrap();
+        schema.add_alias("py", "python").unwrap();
+
+        schema.rename_alias("js", "ecmascript").unwrap();
+        assert_eq!(schema.canonicalize("ecmascript"), "javascript");
+        assert_eq!(schema.canonicalize("js"), "js");
+        assert_eq!(schema.get_aliases("javascript"), ["ecmascript"]);
+
+        assert!(matches!(
+            schema.rename_alias("js", "es"),
+            Err(SchemaError::TagNotFound(_))
+        ));
+        assert!(matches!(
+            schema.rename_alias("ecmascript", "javascript"),
+            Err(SchemaError::CircularAlias(_))
+        ));
+        // A rejected rename keeps the original alias
+        assert!(matches!(
+            schema.rename_alias("ecmascript", "py"),
+            Err(SchemaError::AliasExists(..))
+        ));
+        assert!(matches!(
+            schema.rename_alias("ecmascript", "lang:js"),
+            Err(SchemaError::InvalidTag(_))
+        ));
+        assert_eq!(schema.canonicalize("ecmascript"), "javascript");
+    }
+
     #[test]
     fn test_get_aliases() {
         let mut schema = TagSchema::new();
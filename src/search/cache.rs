@@ -0,0 +1,225 @@
+//! On-disk cache of search results
+//!
+//! With `tagr search --cache-ttl SECS` (or `search_cache_ttl` in the config),
+//! the files each search matched are kept in `<cache dir>/tagr/search-cache.json`
+//! (`~/.cache/tagr/` on Linux) and repeated searches are answered from it.
+//!
+//! Entries expire after the TTL. Any change to the database's files or tags
+//! invalidates them sooner, since the key covers the database's path and its
+//! [`Database::last_mutation`] counter.
+//! Changes outside the database, such as edits to the tag schema or to the
+//! file sizes and times virtual tags read, only show once an entry expires.
+//!
+//! [`Database::last_mutation`]: crate::db::Database::last_mutation
+
+use crate::cli::SearchParams;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One cached search, as stored in the cache file's JSON array
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// Hash of the search, see [`SearchCache::key`]
+    pub key: String,
+    /// When the entry was written, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Matching files, before sorting and paging
+    pub results: Vec<PathBuf>,
+}
+
+/// Search results cache backed by a JSON file
+#[derive(Debug, Clone)]
+pub struct SearchCache {
+    path: PathBuf,
+    ttl: u64,
+}
+
+impl SearchCache {
+    /// Cache file at `path` whose entries live for `ttl` seconds
+    #[must_use]
+    pub const fn new(path: PathBuf, ttl: u64) -> Self {
+        Self { path, ttl }
+    }
+
+    /// Default cache file
+    ///
+    /// Resolves to `<cache dir>/tagr/search-cache.json`
+    /// (`~/.cache/tagr/` on Linux).
+    #[must_use]
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("tagr").join("search-cache.json"))
+    }
+
+    /// Key identifying a search against a database state
+    ///
+    /// Hashes every search parameter (virtual tags included), the excluded
+    /// directories, the database's path and its mutation counter.
+    #[must_use]
+    pub fn key(
+        params: &SearchParams,
+        exclude_dirs: &[String],
+        db_path: &Path,
+        last_mutation: Option<u64>,
+    ) -> String {
+        let input = format!("{params:?}\0{exclude_dirs:?}\0{db_path:?}\0{last_mutation:?}");
+        blake3::hash(input.as_bytes()).to_hex().to_string()
+    }
+
+    /// Cached results for `key`, unless missing or expired
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Vec<PathBuf>> {
+        let now = unix_now();
+        load(&self.path)
+            .into_iter()
+            .find(|entry| entry.key == key && !self.is_expired(entry, now))
+            .map(|entry| entry.results)
+    }
+
+    /// Store `results` under `key`, dropping expired entries
+    ///
+    /// # Errors
+    /// Returns an error if the cache directory or file cannot be written
+    pub fn put(&self, key: &str, results: &[PathBuf]) -> io::Result<()> {
+        let now = unix_now();
+        let mut entries = load(&self.path);
+        entries.retain(|entry| entry.key != key && !self.is_expired(entry, now));
+        entries.push(CacheEntry {
+            key: key.to_string(),
+            timestamp: now,
+            results: results.to_vec(),
+        });
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string(&entries).map_err(io::Error::other)?;
+        fs::write(&self.path, content)
+    }
+
+    /// Delete the cache file at `path`
+    ///
+    /// Returns whether there was a file to delete.
+    ///
+    /// # Errors
+    /// Returns an error if the file exists but cannot be removed
+    pub fn clear(path: &Path) -> io::Result<bool> {
+        match fs::remove_file(path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    const fn is_expired(&self, entry: &CacheEntry, now: u64) -> bool {
+        now.saturating_sub(entry.timestamp) >= self.ttl
+    }
+}
+
+/// Entries in the cache file; empty if it is missing or cannot be parsed
+fn load(path: &Path) -> Vec<CacheEntry> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{TempFile, TestDb};
+
+    fn params(tag: &str) -> SearchParams {
+        SearchParams {
+            tags: vec![tag.to_string()],
+            ..SearchParams::default()
+        }
+    }
+
+    #[test]
+    fn test_cache_roundtrip_and_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tagr").join("search-cache.json");
+        let cache = SearchCache::new(path.clone(), 60);
+        let key = SearchCache::key(&params("rust"), &[], Path::new("db"), Some(1));
+
+        assert_eq!(cache.get(&key), None);
+        cache.put(&key, &[PathBuf::from("/a.txt")]).unwrap();
+        assert_eq!(cache.get(&key), Some(vec![PathBuf::from("/a.txt")]));
+
+        // A zero TTL treats every entry as expired
+        assert_eq!(SearchCache::new(path.clone(), 0).get(&key), None);
+
+        assert!(SearchCache::clear(&path).unwrap());
+        assert!(!SearchCache::clear(&path).unwrap());
+        assert_eq!(cache.get(&key), None);
+    }
+
+    #[test]
+    fn test_cache_file_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("search-cache.json");
+        let cache = SearchCache::new(path.clone(), 60);
+        cache.put("abc", &[PathBuf::from("/a.txt")]).unwrap();
+        cache.put("abc", &[PathBuf::from("/b.txt")]).unwrap();
+
+        let value: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let entries = value.as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["key"], "abc");
+        assert!(entries[0]["timestamp"].as_u64().unwrap() > 0);
+        assert_eq!(entries[0]["results"], serde_json::json!(["/b.txt"]));
+    }
+
+    #[test]
+    fn test_key_changes_with_search_database_and_mutation() {
+        let db = Path::new("db");
+        let key = SearchCache::key(&params("rust"), &[], db, Some(1));
+        assert_eq!(key, SearchCache::key(&params("rust"), &[], db, Some(1)));
+        assert_ne!(key, SearchCache::key(&params("go"), &[], db, Some(1)));
+        assert_ne!(
+            key,
+            SearchCache::key(&params("rust"), &["target".into()], db, Some(1))
+        );
+        assert_ne!(
+            key,
+            SearchCache::key(&params("rust"), &[], Path::new("other_db"), Some(1))
+        );
+        assert_ne!(key, SearchCache::key(&params("rust"), &[], db, Some(2)));
+    }
+
+    #[test]
+    fn test_mutations_advance_last_mutation() {
+        let test_db = TestDb::new("test_search_cache_mutation");
+        let db = test_db.db();
+        let file = TempFile::create("search_cache_mutation.txt").unwrap();
+
+        let opened = db.last_mutation().unwrap();
+        assert!(opened.is_some());
+
+        db.insert(file.path(), vec!["rust".into()]).unwrap();
+        let inserted = db.last_mutation().unwrap();
+        assert!(inserted > opened);
+
+        db.add_tags(file.path(), vec!["cli".into()]).unwrap();
+        let tagged = db.last_mutation().unwrap();
+        assert!(tagged > inserted);
+
+        db.remove(file.path()).unwrap();
+        let removed = db.last_mutation().unwrap();
+        assert!(removed > tagged);
+
+        // Removing a file that is not there changes nothing
+        assert!(!db.remove(file.path()).unwrap());
+        assert_eq!(db.last_mutation().unwrap(), removed);
+    }
+}
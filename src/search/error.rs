@@ -9,6 +9,7 @@
 //! - **`DatabaseError`**: Errors from database queries during search (wraps `DbError`)
 //! - **`InterruptedError`**: User cancelled the interactive fuzzy finder (Ctrl+C or ESC)
 //! - **`BuildError`**: Failed to construct skim fuzzy finder options
+//! - **`QueryParse`**: A `--expr` tag expression could not be parsed
 //!
 //! All errors implement proper error chaining and provide context for debugging.
 
@@ -32,6 +33,10 @@ pub enum SearchError {
     /// Failed to build UI options
     #[error("Failed to build UI options: {0}")]
     BuildError(String),
+
+    /// Invalid `--expr` tag expression
+    #[error("Invalid expression: {0}")]
+    QueryParse(#[from] super::query_lang::ParseError),
 }
 
 #[cfg(test)]
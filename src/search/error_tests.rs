@@ -104,7 +104,8 @@ mod tests {
                 }
                 SearchError::InterruptedError
                 | SearchError::DatabaseError(_)
-                | SearchError::UiError(_) => {
+                | SearchError::UiError(_)
+                | SearchError::QueryParse(_) => {
                     // Expected
                 }
             }
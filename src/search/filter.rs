@@ -24,7 +24,8 @@
 use crate::db::{Database, DbError};
 use glob::Pattern as GlobPattern;
 use regex::Regex;
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 /// Filter files by patterns (glob or regex) with AND/OR logic
 ///
@@ -72,30 +73,107 @@ pub fn by_patterns(
             })
             .collect())
     } else {
-        let matchers: Result<Vec<GlobPattern>, _> = patterns
+        let matcher = GlobMatcher::new(patterns, match_all)?;
+        Ok(files.into_iter().filter(|f| matcher.matches(f)).collect())
+    }
+}
+
+/// Compiled glob patterns with literal directory prefixes for cheap pruning
+///
+/// Each path is first checked against the wildcard-free directory prefix of a
+/// pattern (see [`literal_dir_prefix`]); the full glob only runs for paths that
+/// pass, so candidates outside the pattern's directory are rejected with a
+/// single `starts_with`.
+#[derive(Debug, Clone)]
+pub struct GlobMatcher {
+    patterns: Vec<(String, GlobPattern)>,
+    match_all: bool,
+}
+
+impl GlobMatcher {
+    /// Compile glob patterns with AND (`match_all`) or OR logic
+    ///
+    /// # Errors
+    /// Returns `DbError::InvalidInput` if any pattern is invalid
+    pub fn new(patterns: &[String], match_all: bool) -> Result<Self, DbError> {
+        let patterns = patterns
             .iter()
             .map(|p| {
                 GlobPattern::new(p)
+                    .map(|pattern| (literal_dir_prefix(p).to_string(), pattern))
                     .map_err(|e| DbError::InvalidInput(format!("Invalid glob pattern '{p}': {e}")))
             })
-            .collect();
-        let matchers = matchers?;
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            patterns,
+            match_all,
+        })
+    }
 
-        Ok(files
-            .into_iter()
-            .filter(|f| {
-                f.to_str().is_some_and(|s| {
-                    if match_all {
-                        matchers.iter().all(|m| m.matches(s))
-                    } else {
-                        matchers.iter().any(|m| m.matches(s))
-                    }
-                })
-            })
-            .collect())
+    /// Check whether a path satisfies the patterns
+    #[must_use]
+    pub fn matches(&self, path: &Path) -> bool {
+        let Some(path) = path.to_str() else {
+            return false;
+        };
+        let matches = |(prefix, pattern): &(String, GlobPattern)| {
+            path.starts_with(prefix.as_str()) && pattern.matches(path)
+        };
+        if self.match_all {
+            self.patterns.iter().all(matches)
+        } else {
+            self.patterns.iter().any(matches)
+        }
     }
 }
 
+/// Longest leading directory of a glob pattern that contains no wildcards
+///
+/// `src/**/*.rs` yields `src/`, `/home/me/*.md` yields `/home/me/`, and a
+/// pattern starting with a wildcard yields an empty prefix.
+#[must_use]
+pub fn literal_dir_prefix(pattern: &str) -> &str {
+    let literal = pattern
+        .find(['*', '?', '['])
+        .map_or(pattern, |end| &pattern[..end]);
+    literal.rfind('/').map_or("", |slash| &pattern[..=slash])
+}
+
+/// Compile directory exclusion globs
+///
+/// # Errors
+/// Returns `DbError::InvalidInput` if any pattern is invalid
+pub fn dir_patterns(patterns: &[String]) -> Result<Vec<GlobPattern>, DbError> {
+    patterns
+        .iter()
+        .map(|p| {
+            GlobPattern::new(p)
+                .map_err(|e| DbError::InvalidInput(format!("Invalid directory pattern '{p}': {e}")))
+        })
+        .collect()
+}
+
+/// Check whether a file lives under a directory matching any pattern
+///
+/// Patterns without a `/` are matched against each directory name in the
+/// file's parent path, so `target` excludes `/proj/target/debug/app`.
+/// Patterns containing a `/` are matched against the whole parent path.
+#[must_use]
+pub fn in_excluded_dir(path: &Path, patterns: &[GlobPattern]) -> bool {
+    let Some(parent) = path.parent() else {
+        return false;
+    };
+    patterns.iter().any(|pattern| {
+        if pattern.as_str().contains('/') {
+            parent.to_str().is_some_and(|s| pattern.matches(s))
+        } else {
+            parent
+                .iter()
+                .any(|component| component.to_str().is_some_and(|s| pattern.matches(s)))
+        }
+    })
+}
+
 /// Extension trait for filtering iterators of `PathBuf` by patterns
 ///
 /// This trait adds pattern filtering capabilities directly to iterators,
@@ -171,6 +249,38 @@ pub trait PathFilterExt: IntoIterator<Item = PathBuf> + Sized {
     fn filter_regex_all(self, patterns: &[String]) -> Result<Vec<PathBuf>, DbError> {
         by_patterns(self, patterns, true, true)
     }
+
+    /// Drop paths matching any of the given glob or regex patterns
+    ///
+    /// The inverse of [`filter_patterns`](Self::filter_patterns) with ANY logic.
+    ///
+    /// # Errors
+    /// Returns `DbError::InvalidInput` if any pattern is invalid
+    fn exclude_patterns(
+        self,
+        patterns: &[String],
+        use_regex: bool,
+    ) -> Result<Vec<PathBuf>, DbError> {
+        let files: Vec<PathBuf> = self.into_iter().collect();
+        let matched: HashSet<PathBuf> = by_patterns(files.clone(), patterns, use_regex, false)?
+            .into_iter()
+            .collect();
+        Ok(files.into_iter().filter(|f| !matched.contains(f)).collect())
+    }
+
+    /// Drop paths inside directories matching any of the given globs
+    ///
+    /// See [`in_excluded_dir`] for how patterns are matched.
+    ///
+    /// # Errors
+    /// Returns `DbError::InvalidInput` if any pattern is invalid
+    fn exclude_dirs(self, patterns: &[String]) -> Result<Vec<PathBuf>, DbError> {
+        let patterns = dir_patterns(patterns)?;
+        Ok(self
+            .into_iter()
+            .filter(|f| !in_excluded_dir(f, &patterns))
+            .collect())
+    }
 }
 
 // Implement for any iterator that yields PathBuf
@@ -248,6 +358,23 @@ mod tests {
         assert_eq!(result, files);
     }
 
+    #[test]
+    fn test_literal_dir_prefix() {
+        assert_eq!(literal_dir_prefix("src/**/*.rs"), "src/");
+        assert_eq!(literal_dir_prefix("/home/me/notes/*.md"), "/home/me/notes/");
+        assert_eq!(literal_dir_prefix("/srv/a?c/*.rs"), "/srv/");
+        assert_eq!(literal_dir_prefix("*.rs"), "");
+        assert_eq!(literal_dir_prefix("main.rs"), "");
+    }
+
+    #[test]
+    fn test_glob_matcher_prunes_by_prefix() {
+        let matcher = GlobMatcher::new(&["/src/**/*.rs".to_string()], false).unwrap();
+        assert!(matcher.matches(Path::new("/src/db/query.rs")));
+        assert!(!matcher.matches(Path::new("/tests/src/query.rs")));
+        assert!(!matcher.matches(Path::new("/src/README.md")));
+    }
+
     #[test]
     fn test_filter_glob_any() {
         let files = vec![
@@ -308,6 +435,34 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_exclude_dirs_matches_parent_components() {
+        let files = vec![
+            PathBuf::from("/proj/src/main.rs"),
+            PathBuf::from("/proj/target/debug/app"),
+            PathBuf::from("/proj/.git/config"),
+            PathBuf::from("/proj/targets.txt"),
+            PathBuf::from("/proj/vendor/build/out.o"),
+        ];
+
+        let result = files
+            .into_iter()
+            .exclude_dirs(&[
+                "target".to_string(),
+                ".git".to_string(),
+                "*/vendor/*".to_string(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                PathBuf::from("/proj/src/main.rs"),
+                PathBuf::from("/proj/targets.txt"),
+            ]
+        );
+    }
+
     // Extension trait tests
     #[test]
     fn test_path_filter_ext_glob_any() {
@@ -25,9 +25,11 @@
 //! # }
 //! ```
 
+pub mod cache;
 pub mod error;
 pub mod filter;
 pub mod hierarchy;
+pub mod query_lang;
 pub mod traits;
 
 pub use error::SearchError;
@@ -0,0 +1,546 @@
+//! Boolean tag expressions for `tagr search --expr`
+//!
+//! A small query language combining tags with `AND`, `OR`, parentheses and
+//! `-tag` (or `NOT tag`) exclusion:
+//!
+//! ```text
+//! rust AND (test OR bench) AND -deprecated
+//! (rust OR python) AND NOT deprecated
+//! ```
+//!
+//! `AND` binds tighter than `OR`; keywords are case-insensitive. Expressions
+//! that only AND or only OR plain tags (plus exclusions) convert to
+//! [`SearchParams`] and go through the regular search path; anything else is
+//! evaluated per file with [`Expr::matches`].
+
+use crate::cli::{SearchMode, SearchParams};
+use crate::schema::HIERARCHY_DELIMITER;
+use std::fmt;
+use thiserror::Error;
+
+/// Parsed tag expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// A single tag
+    Tag(String),
+    /// Exclusion (`-tag` or `-(...)`)
+    Not(Box<Self>),
+    /// All sub-expressions must match
+    And(Vec<Self>),
+    /// Any sub-expression must match
+    Or(Vec<Self>),
+}
+
+impl Expr {
+    /// Evaluate the expression against a file's tags
+    ///
+    /// With `hierarchy`, a tag also matches its descendants (`lang` matches
+    /// `lang:rust`), mirroring the default `-t` behaviour.
+    #[must_use]
+    pub fn matches(&self, file_tags: &[String], hierarchy: bool) -> bool {
+        match self {
+            Self::Tag(tag) => tag_matches(tag, file_tags, hierarchy),
+            Self::Not(inner) => !inner.matches(file_tags, hierarchy),
+            Self::And(terms) => terms.iter().all(|term| term.matches(file_tags, hierarchy)),
+            Self::Or(terms) => terms.iter().any(|term| term.matches(file_tags, hierarchy)),
+        }
+    }
+
+    /// Convert to flag-style search parameters when the expression allows it
+    ///
+    /// Succeeds for a single tag, an `AND` of tags and `-tag` exclusions, or an
+    /// `OR` of tags. Returns `None` for nested groups, which have no flat
+    /// equivalent.
+    #[must_use]
+    pub fn to_search_params(&self) -> Option<SearchParams> {
+        let mut params = SearchParams {
+            tag_mode: SearchMode::All,
+            ..SearchParams::default()
+        };
+        match self {
+            Self::Tag(tag) => params.tags.push(tag.clone()),
+            Self::And(terms) => {
+                for term in terms {
+                    match term {
+                        Self::Tag(tag) => params.tags.push(tag.clone()),
+                        Self::Not(inner) => match inner.as_ref() {
+                            Self::Tag(tag) => params.exclude_tags.push(tag.clone()),
+                            _ => return None,
+                        },
+                        _ => return None,
+                    }
+                }
+                // Exclusions alone select from every file; leave that to `matches`
+                if params.tags.is_empty() {
+                    return None;
+                }
+            }
+            Self::Or(terms) => {
+                params.tag_mode = SearchMode::Any;
+                for term in terms {
+                    match term {
+                        Self::Tag(tag) => params.tags.push(tag.clone()),
+                        _ => return None,
+                    }
+                }
+            }
+            Self::Not(_) => return None,
+        }
+        Some(params)
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, terms: &[Self], op: &str| {
+            for (i, term) in terms.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " {op} ")?;
+                }
+                match term {
+                    Self::And(_) | Self::Or(_) => write!(f, "({term})")?,
+                    _ => write!(f, "{term}")?,
+                }
+            }
+            Ok(())
+        };
+        match self {
+            Self::Tag(tag) => write!(f, "{tag}"),
+            Self::Not(inner) => match inner.as_ref() {
+                Self::Tag(tag) => write!(f, "-{tag}"),
+                other => write!(f, "-({other})"),
+            },
+            Self::And(terms) => join(f, terms, "AND"),
+            Self::Or(terms) => join(f, terms, "OR"),
+        }
+    }
+}
+
+/// Check whether a file with `file_tags` has `tag`
+///
+/// With `hierarchy`, descendants count too (`lang` matches `lang:rust`).
+#[must_use]
+pub fn tag_matches(tag: &str, file_tags: &[String], hierarchy: bool) -> bool {
+    file_tags.iter().any(|file_tag| {
+        file_tag == tag
+            || (hierarchy
+                && file_tag
+                    .strip_prefix(tag)
+                    .is_some_and(|rest| rest.starts_with(HIERARCHY_DELIMITER)))
+    })
+}
+
+/// Expression syntax error with the location of the offending token
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{}", self.render())]
+pub struct ParseError {
+    /// What went wrong
+    pub message: String,
+    /// Character offset of the offending token
+    pub position: usize,
+    /// Width of the offending token in characters (at least 1)
+    pub len: usize,
+    /// The expression being parsed
+    pub input: String,
+}
+
+impl ParseError {
+    fn new(message: impl Into<String>, token: &Token, input: &str) -> Self {
+        Self {
+            message: message.into(),
+            position: token.position,
+            len: token.len.max(1),
+            input: input.to_string(),
+        }
+    }
+
+    /// Render the message with the expression and a caret under the token
+    fn render(&self) -> String {
+        format!(
+            "{} at column {}\n  {}\n  {}{}",
+            self.message,
+            self.position + 1,
+            self.input,
+            " ".repeat(self.position),
+            "^".repeat(self.len)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    Tag(String),
+    And,
+    Or,
+    Not,
+    Minus,
+    Open,
+    Close,
+    End,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    position: usize,
+    len: usize,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match &self.kind {
+            TokenKind::Tag(tag) => format!("'{tag}'"),
+            TokenKind::And => "'AND'".to_string(),
+            TokenKind::Or => "'OR'".to_string(),
+            TokenKind::Not => "'NOT'".to_string(),
+            TokenKind::Minus => "'-'".to_string(),
+            TokenKind::Open => "'('".to_string(),
+            TokenKind::Close => "')'".to_string(),
+            TokenKind::End => "end of expression".to_string(),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Vec<Token> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let single = |kind| Token {
+            kind,
+            position: i,
+            len: 1,
+        };
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => tokens.push(single(TokenKind::Open)),
+            ')' => tokens.push(single(TokenKind::Close)),
+            '-' => tokens.push(single(TokenKind::Minus)),
+            _ => {
+                let start = i;
+                while i + 1 < chars.len()
+                    && !chars[i + 1].is_whitespace()
+                    && !matches!(chars[i + 1], '(' | ')')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..=i].iter().collect();
+                let kind = if word.eq_ignore_ascii_case("and") {
+                    TokenKind::And
+                } else if word.eq_ignore_ascii_case("or") {
+                    TokenKind::Or
+                } else if word.eq_ignore_ascii_case("not") {
+                    TokenKind::Not
+                } else {
+                    TokenKind::Tag(word)
+                };
+                tokens.push(Token {
+                    kind,
+                    position: start,
+                    len: i + 1 - start,
+                });
+            }
+        }
+        i += 1;
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::End,
+        position: chars.len(),
+        len: 1,
+    });
+    tokens
+}
+
+/// Parse a tag expression
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] pointing at the first token that does not fit the
+/// grammar: empty input, a missing operand, an unbalanced parenthesis, or two
+/// operands without `AND`/`OR` between them.
+pub fn parse(input: &str) -> Result<Expr, ParseError> {
+    let mut parser = Parser {
+        input,
+        tokens: tokenize(input),
+        pos: 0,
+    };
+    if parser.peek().kind == TokenKind::End {
+        return Err(ParseError::new("empty expression", parser.peek(), input));
+    }
+    let expr = parser.or_expr()?;
+    let next = parser.peek();
+    match next.kind {
+        TokenKind::End => Ok(expr),
+        TokenKind::Close => Err(ParseError::new("unmatched ')'", next, input)),
+        _ => Err(ParseError::new(
+            format!("expected AND or OR but found {}", next.describe()),
+            next,
+            input,
+        )),
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if token.kind != TokenKind::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn or_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut terms = vec![self.and_expr()?];
+        while self.peek().kind == TokenKind::Or {
+            self.advance();
+            terms.push(self.and_expr()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            Expr::Or(terms)
+        })
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut terms = vec![self.unary()?];
+        while self.peek().kind == TokenKind::And {
+            self.advance();
+            terms.push(self.unary()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            Expr::And(terms)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.peek().kind == TokenKind::Minus {
+            let minus = self.advance();
+            let next = self.peek();
+            // "- tag" is almost certainly a typo; require the tag to follow directly
+            if next.kind == TokenKind::End || next.position != minus.position + 1 {
+                return Err(ParseError::new(
+                    "expected tag or '(' directly after '-'",
+                    &minus,
+                    self.input,
+                ));
+            }
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.peek().kind == TokenKind::Not {
+            self.advance();
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let token = self.advance();
+        match token.kind {
+            TokenKind::Tag(tag) => Ok(Expr::Tag(tag)),
+            TokenKind::Open => {
+                let inner = self.or_expr()?;
+                let close = self.peek();
+                if close.kind == TokenKind::Close {
+                    self.advance();
+                    Ok(inner)
+                } else if close.kind == TokenKind::End {
+                    Err(ParseError::new("unclosed '('", &token, self.input))
+                } else {
+                    Err(ParseError::new(
+                        format!("expected ')' but found {}", close.describe()),
+                        close,
+                        self.input,
+                    ))
+                }
+            }
+            _ => Err(ParseError::new(
+                format!("expected tag or '(' but found {}", token.describe()),
+                &token,
+                self.input,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(name: &str) -> Expr {
+        Expr::Tag(name.to_string())
+    }
+
+    fn not(expr: Expr) -> Expr {
+        Expr::Not(Box::new(expr))
+    }
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_valid_expressions() {
+        let cases = [
+            ("rust", tag("rust")),
+            ("  rust  ", tag("rust")),
+            ("rust AND test", Expr::And(vec![tag("rust"), tag("test")])),
+            ("rust and test", Expr::And(vec![tag("rust"), tag("test")])),
+            (
+                "rust OR go OR c",
+                Expr::Or(vec![tag("rust"), tag("go"), tag("c")]),
+            ),
+            ("-deprecated", not(tag("deprecated"))),
+            ("--x", not(not(tag("x")))),
+            ("(rust)", tag("rust")),
+            ("((rust))", tag("rust")),
+            (
+                "a OR b AND c",
+                Expr::Or(vec![tag("a"), Expr::And(vec![tag("b"), tag("c")])]),
+            ),
+            (
+                "rust AND (test OR bench) AND -deprecated",
+                Expr::And(vec![
+                    tag("rust"),
+                    Expr::Or(vec![tag("test"), tag("bench")]),
+                    not(tag("deprecated")),
+                ]),
+            ),
+            ("-(a OR b)", not(Expr::Or(vec![tag("a"), tag("b")]))),
+            ("NOT deprecated", not(tag("deprecated"))),
+            (
+                "(rust OR python) and not deprecated",
+                Expr::And(vec![
+                    Expr::Or(vec![tag("rust"), tag("python")]),
+                    not(tag("deprecated")),
+                ]),
+            ),
+            ("NOT (a OR b)", not(Expr::Or(vec![tag("a"), tag("b")]))),
+            (
+                "lang:rust AND my-tag",
+                Expr::And(vec![tag("lang:rust"), tag("my-tag")]),
+            ),
+            ("(a)AND(b)", Expr::And(vec![tag("a"), tag("b")])),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(parse(input).unwrap(), expected, "parsing {input:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_expressions() {
+        let cases = [
+            ("", "empty expression", 0),
+            ("   ", "empty expression", 3),
+            ("AND", "expected tag or '(' but found 'AND'", 0),
+            (
+                "rust AND",
+                "expected tag or '(' but found end of expression",
+                8,
+            ),
+            ("rust OR OR go", "expected tag or '(' but found 'OR'", 8),
+            ("rust test", "expected AND or OR but found 'test'", 5),
+            ("(rust", "unclosed '('", 0),
+            ("rust)", "unmatched ')'", 4),
+            ("()", "expected tag or '(' but found ')'", 1),
+            ("(a b)", "expected ')' but found 'b'", 3),
+            ("- rust", "expected tag or '(' directly after '-'", 0),
+            ("rust AND -", "expected tag or '(' directly after '-'", 9),
+            (
+                "rust AND NOT",
+                "expected tag or '(' but found end of expression",
+                12,
+            ),
+            ("NOT AND rust", "expected tag or '(' but found 'AND'", 4),
+        ];
+
+        for (input, message, position) in cases {
+            let err = parse(input).expect_err(input);
+            assert_eq!(err.message, message, "parsing {input:?}");
+            assert_eq!(err.position, position, "parsing {input:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_error_caret() {
+        let err = parse("rust AND (test OR )").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected tag or '(' but found ')' at column 19\n  rust AND (test OR )\n                    ^"
+        );
+
+        let err = parse("rust bench").unwrap_err();
+        assert!(err.to_string().ends_with("\n       ^^^^^"));
+    }
+
+    #[test]
+    fn test_matches() {
+        let expr = parse("rust AND (test OR bench) AND -deprecated").unwrap();
+        assert!(expr.matches(&tags(&["rust", "test"]), true));
+        assert!(expr.matches(&tags(&["rust", "bench", "other"]), true));
+        assert!(!expr.matches(&tags(&["rust", "test", "deprecated"]), true));
+        assert!(!expr.matches(&tags(&["rust"]), true));
+        assert!(!expr.matches(&tags(&["test", "bench"]), true));
+    }
+
+    #[test]
+    fn test_matches_hierarchy() {
+        let expr = parse("lang AND -lang:go").unwrap();
+        assert!(expr.matches(&tags(&["lang:rust"]), true));
+        assert!(!expr.matches(&tags(&["lang:go"]), true));
+        assert!(!expr.matches(&tags(&["language"]), true));
+        assert!(!expr.matches(&tags(&["lang:rust"]), false));
+    }
+
+    #[test]
+    fn test_to_search_params() {
+        let params = parse("rust AND test AND -deprecated")
+            .unwrap()
+            .to_search_params()
+            .unwrap();
+        assert_eq!(params.tags, tags(&["rust", "test"]));
+        assert_eq!(params.tag_mode, SearchMode::All);
+        assert_eq!(params.exclude_tags, tags(&["deprecated"]));
+
+        let params = parse("rust OR go").unwrap().to_search_params().unwrap();
+        assert_eq!(params.tags, tags(&["rust", "go"]));
+        assert_eq!(params.tag_mode, SearchMode::Any);
+
+        assert!(
+            parse("rust AND (test OR bench)")
+                .unwrap()
+                .to_search_params()
+                .is_none()
+        );
+        assert!(parse("rust OR -go").unwrap().to_search_params().is_none());
+        assert!(parse("-deprecated").unwrap().to_search_params().is_none());
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for input in [
+            "rust AND (test OR bench) AND -deprecated",
+            "a OR b AND c",
+            "-(a OR b)",
+        ] {
+            let expr = parse(input).unwrap();
+            assert_eq!(parse(&expr.to_string()).unwrap(), expr);
+        }
+    }
+}
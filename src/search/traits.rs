@@ -43,6 +43,7 @@
 
 use crate::cli::SearchParams;
 use crate::search::hierarchy;
+use std::path::Path;
 
 /// Represents a file-tag pair as borrowed data
 ///
@@ -198,6 +199,12 @@ impl<T: AsFileTagPair> FilterExt<T> for [T] {
                 }
             }
 
+            if let Some(expr) = &params.expression
+                && !expr.matches(Path::new(pair.file), pair.tags, !params.no_hierarchy)
+            {
+                return false;
+            }
+
             true
         })
     }
@@ -259,14 +266,19 @@ mod tests {
             tags: vec!["rust".to_string()],
             tag_mode: SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: true, // Exact matching
+            expression: None,
+            no_index: false,
         };
 
         let results: Vec<_> = files.apply_filter(&params).collect();
@@ -287,14 +299,19 @@ mod tests {
             tags: vec!["rust".to_string()],
             tag_mode: SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec!["tests".to_string()],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: true,
+            expression: None,
+            no_index: false,
         };
 
         let results: Vec<_> = files.apply_filter(&params).collect();
@@ -315,14 +332,19 @@ mod tests {
             tags: vec!["lang".to_string()],
             tag_mode: SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false, // Hierarchical matching
+            expression: None,
+            no_index: false,
         };
 
         let results: Vec<_> = files.apply_filter(&params).collect();
@@ -343,14 +365,19 @@ mod tests {
             tags: vec!["lang".to_string()],
             tag_mode: SearchMode::Any,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec!["lang:rust".to_string()],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let results: Vec<_> = files.apply_filter(&params).collect();
@@ -371,14 +398,19 @@ mod tests {
             tags: vec!["lang".to_string(), "project".to_string()],
             tag_mode: SearchMode::All,
             file_patterns: vec![],
+            file_only_patterns: vec![],
             file_mode: SearchMode::All,
             exclude_tags: vec![],
+            exclude_file_patterns: vec![],
             regex_tag: false,
             regex_file: false,
             glob_files: false,
             virtual_tags: vec![],
             virtual_mode: SearchMode::All,
+            exclude_virtual_tags: vec![],
             no_hierarchy: false,
+            expression: None,
+            no_index: false,
         };
 
         let results: Vec<_> = files.apply_filter(&params).collect();
@@ -4,6 +4,12 @@
 //! including a `TestDb` wrapper for temporary database management.
 //!
 //! Uses the standard `tempfile` crate for automatic cleanup.
+//!
+//! Available to downstream crates with the `testing` feature. The scripted
+//! [`MockFinder`] and [`MockInput`] let integration tests drive browse flows
+//! without a terminal.
+
+pub use crate::ui::mock::{MockFinder, MockInput};
 
 use crate::db::Database;
 use std::io::Write;
@@ -16,11 +22,12 @@ use std::path::{Path, PathBuf};
 ///
 /// # Examples
 /// ```
-/// # use tagr::testing::TestDb;
+/// # use tagr::testing::{TempFile, TestDb};
 /// let test_db = TestDb::new("my_test_db");
 /// let db = test_db.db();
 ///
-/// db.insert("file.txt", vec!["tag1".into()]).unwrap();
+/// let file = TempFile::create("file.txt").unwrap();
+/// db.insert(file.path(), vec!["tag1".into()]).unwrap();
 /// assert_eq!(db.count(), 1);
 /// // Database automatically cleaned up when test_db is dropped
 /// ```
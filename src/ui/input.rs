@@ -96,6 +96,83 @@ pub trait UserInput: Send + Sync {
         items: &[String],
         default: Option<usize>,
     ) -> Result<Option<usize>>;
+
+    /// Prompt user for text input with completion against known candidates
+    ///
+    /// Empty input is always allowed. Backends without completion support fall
+    /// back to a plain text prompt.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The prompt message to display
+    /// * `candidates` - Values offered as completions (e.g., existing tags)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(String))` - User entered text (possibly empty)
+    /// * `Ok(None)` - User cancelled (ESC)
+    /// * `Err(_)` - Input operation failed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying input mechanism fails (e.g., I/O error,
+    /// terminal not available).
+    fn prompt_text_with_completion(
+        &self,
+        prompt: &str,
+        candidates: &[String],
+    ) -> Result<Option<String>> {
+        let _ = candidates;
+        self.prompt_text(prompt, None, true)
+    }
+}
+
+/// Complete `input` against `candidates` by prefix
+///
+/// Returns the single matching candidate, or the longest common prefix of all
+/// matches when it extends the input. Returns `None` when nothing can be added.
+///
+/// # Examples
+///
+/// ```
+/// use tagr::ui::input::complete_prefix;
+///
+/// let tags = vec!["rust".to_string(), "ruby".to_string(), "python".to_string()];
+/// assert_eq!(complete_prefix("py", &tags), Some("python".to_string()));
+/// assert_eq!(complete_prefix("ru", &tags), None);
+/// assert_eq!(complete_prefix("rus", &tags), Some("rust".to_string()));
+/// ```
+#[must_use]
+pub fn complete_prefix(input: &str, candidates: &[String]) -> Option<String> {
+    if input.is_empty() {
+        return None;
+    }
+
+    let mut matches = candidates.iter().filter(|c| c.starts_with(input));
+    let first = matches.next()?;
+
+    let mut common = first.as_str();
+    for candidate in matches {
+        let shared = common
+            .char_indices()
+            .zip(candidate.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or_else(|| common.len().min(candidate.len()), |((i, _), _)| i);
+        common = &common[..shared];
+    }
+
+    (common.len() > input.len()).then(|| common.to_string())
+}
+
+/// Adapter exposing a candidate list to dialoguer's completion hook
+struct PrefixCompletion<'a> {
+    candidates: &'a [String],
+}
+
+impl dialoguer::Completion for PrefixCompletion<'_> {
+    fn get(&self, input: &str) -> Option<String> {
+        complete_prefix(input, self.candidates)
+    }
 }
 
 /// Result type for user input operations
@@ -205,6 +282,24 @@ impl UserInput for DialoguerInput {
             .map(Some)
             .map_err(|e| InputError::Io(io::Error::other(e)))
     }
+
+    fn prompt_text_with_completion(
+        &self,
+        prompt: &str,
+        candidates: &[String],
+    ) -> Result<Option<String>> {
+        use dialoguer::Input;
+
+        let completion = PrefixCompletion { candidates };
+
+        Input::<String>::with_theme(&self.theme)
+            .with_prompt(prompt)
+            .allow_empty(true)
+            .completion_with(&completion)
+            .interact_text()
+            .map(Some)
+            .map_err(|e| InputError::Io(io::Error::other(e)))
+    }
 }
 
 #[cfg(test)]
@@ -218,6 +313,22 @@ mod tests {
         assert!(matches!(input_err, InputError::Io(_)));
     }
 
+    #[test]
+    fn test_complete_prefix() {
+        let tags = vec![
+            "lang:rust".to_string(),
+            "lang:python".to_string(),
+            "notes".to_string(),
+        ];
+
+        assert_eq!(complete_prefix("no", &tags), Some("notes".to_string()));
+        assert_eq!(complete_prefix("la", &tags), Some("lang:".to_string()));
+        assert_eq!(complete_prefix("lang:", &tags), None);
+        assert_eq!(complete_prefix("x", &tags), None);
+        assert_eq!(complete_prefix("", &tags), None);
+        assert_eq!(complete_prefix("notes", &tags), None);
+    }
+
     #[test]
     fn test_dialoguer_input_creation() {
         let _input = DialoguerInput::new();
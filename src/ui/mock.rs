@@ -1,38 +1,64 @@
-//! Mock fuzzy finder for testing
+//! Mock fuzzy finder and user input for testing
 
-use super::error::Result;
+use super::error::{Result, UiError};
+use super::input::{self, UserInput};
 use super::traits::{FinderConfig, FuzzyFinder};
 use super::types::FinderResult;
+use std::collections::VecDeque;
+use std::sync::Mutex;
 
-/// Mock fuzzy finder that returns predetermined selections
+/// Mock fuzzy finder that replays scripted results
 ///
-/// Useful for testing without requiring user interaction
-#[derive(Debug, Clone)]
+/// Each call to [`FuzzyFinder::run`] returns the next scripted
+/// [`FinderResult`], so tests can drive multi-step flows (keybinds via
+/// `final_key`, refine search, input actions) without user interaction.
+/// Running past the end of the script returns a [`UiError::BuildError`].
+#[derive(Debug)]
 pub struct MockFinder {
-    /// Predetermined items to return as selected
-    pub predetermined_selection: Vec<String>,
-    /// Whether to simulate user abort
-    pub should_abort: bool,
+    script: Mutex<VecDeque<FinderResult>>,
+    prompts: Mutex<Vec<String>>,
 }
 
 impl MockFinder {
-    /// Create a new mock finder with predetermined selections
+    /// Create a mock finder that selects `selections` once
     #[must_use]
     pub fn new(selections: Vec<String>) -> Self {
-        Self {
-            predetermined_selection: selections,
-            should_abort: false,
-        }
+        Self::scripted([FinderResult::selected(selections)])
     }
 
     /// Create a mock finder that simulates user abort
     #[must_use]
     pub fn aborted() -> Self {
+        Self::scripted([FinderResult::aborted()])
+    }
+
+    /// Create a mock finder that returns `results` in order, one per run
+    #[must_use]
+    pub fn scripted(results: impl IntoIterator<Item = FinderResult>) -> Self {
         Self {
-            predetermined_selection: Vec::new(),
-            should_abort: true,
+            script: Mutex::new(results.into_iter().collect()),
+            prompts: Mutex::new(Vec::new()),
         }
     }
+
+    /// Append another result to the script
+    #[must_use]
+    pub fn then(self, result: FinderResult) -> Self {
+        lock(&self.script).push_back(result);
+        self
+    }
+
+    /// Number of scripted results not yet returned
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        lock(&self.script).len()
+    }
+
+    /// Prompts of every finder run so far, in call order
+    #[must_use]
+    pub fn prompts(&self) -> Vec<String> {
+        lock(&self.prompts).clone()
+    }
 }
 
 impl Default for MockFinder {
@@ -42,13 +68,69 @@ impl Default for MockFinder {
 }
 
 impl FuzzyFinder for MockFinder {
-    fn run(&self, _config: FinderConfig) -> Result<FinderResult> {
-        if self.should_abort {
-            Ok(FinderResult::aborted())
-        } else {
-            Ok(FinderResult::selected(self.predetermined_selection.clone()))
+    fn run(&self, config: FinderConfig) -> Result<FinderResult> {
+        lock(&self.prompts).push(config.prompt);
+        lock(&self.script)
+            .pop_front()
+            .ok_or_else(|| UiError::BuildError("No more mock results".into()))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Mock user input that replays scripted text responses
+///
+/// Each text prompt consumes the next response; once the script is exhausted
+/// prompts behave as if the user cancelled. Confirmations and selections
+/// return their defaults.
+#[derive(Debug, Default)]
+pub struct MockInput {
+    responses: Mutex<VecDeque<String>>,
+}
+
+impl MockInput {
+    /// Create a mock input that answers text prompts with `responses` in order
+    #[must_use]
+    pub fn new<S: Into<String>>(responses: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            responses: Mutex::new(responses.into_iter().map(Into::into).collect()),
         }
     }
+
+    fn next_response(&self) -> Option<String> {
+        lock(&self.responses).pop_front()
+    }
+}
+
+impl UserInput for MockInput {
+    fn prompt_text(
+        &self,
+        _prompt: &str,
+        default: Option<&str>,
+        _allow_empty: bool,
+    ) -> input::Result<Option<String>> {
+        Ok(self.next_response().map(|r| match default {
+            Some(def) if r.is_empty() => def.to_string(),
+            _ => r,
+        }))
+    }
+
+    fn prompt_confirm(&self, _prompt: &str, default: bool) -> input::Result<Option<bool>> {
+        Ok(Some(default))
+    }
+
+    fn prompt_select(
+        &self,
+        _prompt: &str,
+        _items: &[String],
+        default: Option<usize>,
+    ) -> input::Result<Option<usize>> {
+        Ok(default)
+    }
 }
 
 #[cfg(test)]
@@ -56,6 +138,21 @@ mod tests {
     use super::*;
     use crate::ui::DisplayItem;
 
+    #[test]
+    fn test_mock_input_replays_responses() {
+        let input = MockInput::new(["first", ""]);
+        assert_eq!(
+            input.prompt_text("p", None, true).unwrap(),
+            Some("first".to_string())
+        );
+        assert_eq!(
+            input.prompt_text("p", Some("def"), true).unwrap(),
+            Some("def".to_string())
+        );
+        assert_eq!(input.prompt_text("p", None, true).unwrap(), None);
+        assert_eq!(input.prompt_confirm("p", true).unwrap(), Some(true));
+    }
+
     #[test]
     fn test_mock_finder_with_selections() {
         let finder = MockFinder::new(vec!["file1.txt".to_string(), "file2.txt".to_string()]);
@@ -94,4 +191,40 @@ mod tests {
         assert!(!result.aborted);
         assert!(result.selected.is_empty());
     }
+
+    #[test]
+    fn test_mock_finder_replays_script() {
+        let finder = MockFinder::scripted([FinderResult::with_key(
+            vec!["rust".to_string()],
+            Some("enter".to_string()),
+        )])
+        .then(FinderResult::with_refine_search(
+            vec!["python".to_string()],
+            vec![],
+            vec![],
+            vec![],
+            None,
+        ));
+        assert_eq!(finder.remaining(), 2);
+
+        let first = finder
+            .run(FinderConfig::new(vec![], "tags: ".to_string()))
+            .unwrap();
+        assert_eq!(first.final_key.as_deref(), Some("enter"));
+
+        let second = finder
+            .run(FinderConfig::new(vec![], "files: ".to_string()))
+            .unwrap();
+        assert_eq!(
+            second.refine_search.unwrap().include_tags,
+            vec!["python".to_string()]
+        );
+
+        assert!(
+            finder
+                .run(FinderConfig::new(vec![], "extra: ".to_string()))
+                .is_err()
+        );
+        assert_eq!(finder.prompts(), vec!["tags: ", "files: ", "extra: "]);
+    }
 }
@@ -60,6 +60,11 @@
 //!     search_criteria: None,
 //!     tag_schema: None,
 //!     database: None,
+//!     state_file: None,
+//!     restore_state: false,
+//!     exclude_dirs: vec![],
+//!     start_tags: vec![],
+//!     max_items: 0,
 //! };
 //!
 //! let finder = RatatuiFinder::new();
@@ -159,7 +164,7 @@ pub mod input;
 pub mod output;
 pub mod ratatui_adapter;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "testing"))]
 pub mod mock;
 
 pub use error::{Result, UiError};
@@ -5,6 +5,7 @@
 use super::state::{AppState, Mode};
 use crate::filters::TagMode;
 use crate::keybinds::actions::BrowseAction;
+use crate::ui::output::MessageLevel;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
 use std::collections::HashMap;
 use std::time::Duration;
@@ -130,6 +131,22 @@ fn handle_normal_mode(
             return EventResult::PreviewChanged;
         }
 
+        // Special case: ShowMoreFiles - raise the file pane cap for this session
+        if action == BrowseAction::ShowMoreFiles {
+            state.raise_file_preview_limit();
+            return EventResult::Continue;
+        }
+
+        // Special case: CopyCommand - copy CLI preview and report in status bar
+        if action == BrowseAction::CopyCommand {
+            let command = state.cli_command();
+            match crate::browse::copy_to_clipboard(&command) {
+                Ok(()) => state.add_message(MessageLevel::Success, format!("Copied: {command}")),
+                Err(e) => state.add_message(MessageLevel::Error, e),
+            }
+            return EventResult::Continue;
+        }
+
         // Special case: ShowDetails - display modal inline
         if action == BrowseAction::ShowDetails {
             // Get current file based on phase and focus
@@ -163,9 +180,15 @@ fn handle_normal_mode(
                     .and_then(|db| db.get_note(&path).ok())
                     .flatten();
 
+                let times = state
+                    .database
+                    .as_ref()
+                    .and_then(|db| db.get_timestamps(&path).ok())
+                    .flatten();
+
                 // Create FileDetails and enter details mode
                 use crate::ui::ratatui_adapter::widgets::FileDetails;
-                if let Ok(details) = FileDetails::from_path(&path, tags, note) {
+                if let Ok(details) = FileDetails::from_path(&path, tags, note, times) {
                     state.enter_details(details);
                 }
             }
@@ -583,7 +606,32 @@ fn handle_refine_search_mode(state: &mut AppState, key: KeyEvent) -> EventResult
         return EventResult::Continue;
     };
 
-    if refine_state.in_selection {
+    if refine_state.in_selection && refine_state.selected_field.is_text() {
+        // Editing a free-text field; every printable key is input
+        match (key.code, key.modifiers) {
+            (KeyCode::Enter, _) => {
+                if refine_state.commit_expression() {
+                    refine_state.exit_selection();
+                }
+                EventResult::Continue
+            }
+            (KeyCode::Esc, _) => {
+                refine_state.exit_selection();
+                EventResult::Continue
+            }
+            (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                refine_state.selection_query.push(c);
+                refine_state.expression_error = None;
+                EventResult::Continue
+            }
+            (KeyCode::Backspace, _) => {
+                refine_state.selection_query.pop();
+                refine_state.expression_error = None;
+                EventResult::Continue
+            }
+            _ => EventResult::Continue,
+        }
+    } else if refine_state.in_selection {
         // In sub-selection mode (selecting items from list)
         match (key.code, key.modifiers) {
             // Exit sub-selection and apply changes
@@ -806,10 +854,22 @@ fn handle_confirm_mode(state: &mut AppState, key: KeyEvent) -> EventResult {
 }
 
 /// Handle events in details mode
-fn handle_details_mode(state: &mut AppState, _key: KeyEvent) -> EventResult {
-    // Any key closes details modal
-    state.exit_details();
-    EventResult::Continue
+fn handle_details_mode(
+    state: &mut AppState,
+    key: KeyEvent,
+    custom_binds: &KeybindMap,
+) -> EventResult {
+    // Esc, i, or the key that opened it closes the details modal
+    let closes = matches!(key.code, KeyCode::Esc | KeyCode::Char('i'))
+        || custom_binds
+            .get(&key)
+            .is_some_and(|action| action == BrowseAction::ShowDetails.as_str());
+    if closes {
+        state.exit_details();
+        EventResult::Continue
+    } else {
+        EventResult::Ignored
+    }
 }
 
 /// Poll for events and handle them
@@ -833,7 +893,7 @@ pub fn poll_and_handle(
             Mode::RefineSearch => handle_refine_search_mode(state, key),
             Mode::Input => handle_input_mode(state, key),
             Mode::Confirm => handle_confirm_mode(state, key),
-            Mode::Details => handle_details_mode(state, key),
+            Mode::Details => handle_details_mode(state, key, custom_binds),
         },
         Event::Mouse(mouse) => handle_mouse(state, mouse),
         Event::Resize(_, _) => EventResult::Continue,
@@ -944,6 +1004,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_details_mode_closes_on_esc_or_i() {
+        use crate::ui::ratatui_adapter::widgets::FileDetails;
+
+        let mut state = make_state();
+        let binds = KeybindMap::new();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let details = FileDetails::from_path(file.path(), vec![], None, None).unwrap();
+        assert_eq!(details.encoding, "empty");
+
+        state.enter_details(details.clone());
+        let result = handle_details_mode(
+            &mut state,
+            KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE),
+            &binds,
+        );
+        assert_eq!(result, EventResult::Ignored);
+        assert_eq!(state.mode, Mode::Details);
+
+        for code in [KeyCode::Char('i'), KeyCode::Esc] {
+            state.enter_details(details.clone());
+            handle_details_mode(&mut state, KeyEvent::new(code, KeyModifiers::NONE), &binds);
+            assert_eq!(state.mode, Mode::Normal);
+            assert!(state.file_details().is_none());
+        }
+    }
+
+    #[test]
+    fn test_copy_command_reports_in_status_bar() {
+        let mut state = make_state();
+        let mut binds = KeybindMap::new();
+        binds.insert(
+            KeyEvent::new(KeyCode::Char('y'), KeyModifiers::ALT),
+            "copy_command".to_string(),
+        );
+
+        let result = handle_normal_mode(
+            &mut state,
+            KeyEvent::new(KeyCode::Char('y'), KeyModifiers::ALT),
+            &binds,
+        );
+        // Stays in the TUI whether or not a clipboard is available
+        assert_eq!(result, EventResult::Continue);
+        assert_eq!(state.messages.len(), 1);
+    }
+
     #[test]
     fn test_query_input() {
         let mut state = make_state();
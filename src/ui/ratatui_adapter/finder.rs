@@ -3,6 +3,7 @@
 //! Implements the `FuzzyFinder` trait using ratatui for UI and nucleo for matching.
 
 use super::events::{EventResult, KeybindMap, poll_and_handle};
+use super::persist::PersistedState;
 use super::state::{AppState, Mode};
 use super::styled_preview::{StyledPreview, StyledPreviewGenerator};
 use super::theme::Theme;
@@ -29,9 +30,14 @@ use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
 };
+use ratatui_image::{
+    StatefulImage,
+    picker::{Picker, ProtocolType},
+    protocol::StatefulProtocol,
+};
 use std::io::{self, Stdout};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
 /// Ratatui-based fuzzy finder implementation
@@ -42,6 +48,12 @@ pub struct RatatuiFinder {
     /// Native styled preview generator (preferred)
     styled_generator: Option<StyledPreviewGenerator>,
     theme: Theme,
+    /// Draw images inline when the terminal supports it
+    image_preview: bool,
+    /// Graphics protocol picker, detected once on first run
+    ///
+    /// `None` inside the cell means inline images are unavailable.
+    picker: OnceLock<Option<Picker>>,
 }
 
 impl RatatuiFinder {
@@ -52,16 +64,24 @@ impl RatatuiFinder {
             preview_provider: None,
             styled_generator: None,
             theme: Theme::default(),
+            image_preview: true,
+            picker: OnceLock::new(),
         }
     }
 
     /// Create a ratatui finder with native styled preview generator
+    ///
+    /// At most `max_bytes` of each file are read for its preview.
     #[must_use]
-    pub fn with_styled_preview(max_lines: usize) -> Self {
+    pub fn with_styled_preview(max_lines: usize, max_bytes: u64) -> Self {
         Self {
             preview_provider: None,
-            styled_generator: Some(StyledPreviewGenerator::new(max_lines)),
+            styled_generator: Some(
+                StyledPreviewGenerator::new(max_lines).with_max_bytes(max_bytes),
+            ),
             theme: Theme::default(),
+            image_preview: true,
+            picker: OnceLock::new(),
         }
     }
 
@@ -72,6 +92,8 @@ impl RatatuiFinder {
             preview_provider: Some(Arc::new(preview_provider)),
             styled_generator: None,
             theme: Theme::default(),
+            image_preview: true,
+            picker: OnceLock::new(),
         }
     }
 
@@ -82,6 +104,40 @@ impl RatatuiFinder {
         self
     }
 
+    /// Enable or disable inline image previews
+    #[must_use]
+    pub const fn with_image_preview(mut self, enabled: bool) -> Self {
+        self.image_preview = enabled;
+        self
+    }
+
+    /// Query the terminal for a graphics protocol
+    ///
+    /// Only Kitty and Sixel are used; other terminals fall back to the image
+    /// metadata preview.
+    fn detect_picker(&self) -> Option<Picker> {
+        if !self.image_preview {
+            return None;
+        }
+        Picker::from_query_stdio().ok().filter(|picker| {
+            matches!(
+                picker.protocol_type(),
+                ProtocolType::Kitty | ProtocolType::Sixel
+            )
+        })
+    }
+
+    /// Decode an image into a protocol that is resized to the preview pane
+    fn load_image(picker: &Picker, path: &Path) -> Option<StatefulProtocol> {
+        let image = image::ImageReader::open(path)
+            .ok()?
+            .with_guessed_format()
+            .ok()?
+            .decode()
+            .ok()?;
+        Some(picker.new_resize_protocol(image))
+    }
+
     /// Setup terminal for TUI
     fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
         enable_raw_mode()?;
@@ -247,6 +303,7 @@ impl RatatuiFinder {
         state: &mut AppState,
         theme: &Theme,
         preview_content: Option<&StyledPreview>,
+        preview_image: Option<&mut StatefulProtocol>,
     ) {
         let area = frame.area();
 
@@ -268,13 +325,22 @@ impl RatatuiFinder {
 
         // Content area: items list and optional preview
         let content_area = main_layout[1];
-        self.render_content(frame, state, theme, content_area, preview_content);
+        self.render_content(
+            frame,
+            state,
+            theme,
+            content_area,
+            preview_content,
+            preview_image,
+        );
 
         // Render status bar with optional CLI preview
         let messages: Vec<_> = state.active_messages();
         let cli_preview = state.build_cli_preview();
+        let truncation = state.file_preview_truncation();
         let status_bar = StatusBar::new(&messages, theme, state.preview_mode)
-            .with_cli_preview(cli_preview.as_deref());
+            .with_cli_preview(cli_preview.as_deref())
+            .with_notice(truncation.as_deref());
         frame.render_widget(status_bar, main_layout[2]);
 
         // Render help bar
@@ -332,6 +398,7 @@ impl RatatuiFinder {
         theme: &Theme,
         area: Rect,
         preview_content: Option<&StyledPreview>,
+        preview_image: Option<&mut StatefulProtocol>,
     ) {
         // Always render 3-pane layout: tag tree | files | preview
         // Split horizontally: tag tree (left 30%) | files (middle 35%) | preview (right 35%)
@@ -392,10 +459,25 @@ impl RatatuiFinder {
         frame.render_widget(preview_block, chunks[2]);
 
         // Show preview if we have content and files to preview
-        if !state.file_preview_items.is_empty() && preview_content.is_some() {
-            let preview_pane =
-                PreviewPane::new(preview_content, theme).scroll(state.preview_scroll);
-            frame.render_widget(preview_pane, preview_inner);
+        if state.file_preview_items.is_empty() {
+            return;
+        }
+        match (preview_content, preview_image) {
+            (Some(preview), Some(protocol)) => {
+                let image_block = ratatui::widgets::Block::default()
+                    .borders(ratatui::widgets::Borders::ALL)
+                    .border_style(theme.border_style())
+                    .title(preview.title.clone());
+                let image_area = image_block.inner(preview_inner);
+                frame.render_widget(image_block, preview_inner);
+                frame.render_stateful_widget(StatefulImage::default(), image_area, protocol);
+            }
+            (Some(_), None) => {
+                let preview_pane =
+                    PreviewPane::new(preview_content, theme).scroll(state.preview_scroll);
+                frame.render_widget(preview_pane, preview_inner);
+            }
+            (None, _) => {}
         }
     }
 
@@ -472,6 +554,84 @@ impl RatatuiFinder {
         frame.render_widget(list, area);
     }
 
+    /// Re-filter the items list, tag tree and file preview for the current query
+    ///
+    /// In the `TagSelection` phase both panes are filtered simultaneously.
+    fn apply_query(
+        state: &mut AppState,
+        nucleo: &mut Nucleo<u32>,
+        prev_query: &mut String,
+        prev_file_query: &mut String,
+    ) {
+        let indices = Self::update_filter(nucleo, &state.query, prev_query);
+        prev_query.clone_from(&state.query);
+        state.update_filtered(indices);
+
+        // In TagSelection phase, filter BOTH panes simultaneously
+        if state.is_tag_selection_phase() {
+            // Filter tag tree (left pane) based on query
+            if let Some(ref mut tag_tree_state) = state.tag_tree_state {
+                if state.query.is_empty() {
+                    // No query - show all tags
+                    tag_tree_state.rebuild_visible_cache();
+                } else {
+                    // Filter tags by fuzzy matching on name
+                    let matching_tags: Vec<String> = tag_tree_state
+                        .all_tag_paths()
+                        .into_iter()
+                        .filter(|tag| {
+                            // Simple substring match (case-insensitive)
+                            tag.to_lowercase().contains(&state.query.to_lowercase())
+                        })
+                        .collect();
+                    tag_tree_state.filter_visible_tags(&matching_tags);
+                }
+            }
+
+            // Filter file preview items (right pane) from every matching file,
+            // not just the capped window, so a query can reach past the cap
+            if !state.file_preview_paths.is_empty() {
+                let mut temp_file_nucleo: Nucleo<u32> =
+                    Nucleo::new(Config::DEFAULT.match_paths(), Arc::new(|| {}), None, 1);
+
+                let file_injector = temp_file_nucleo.injector();
+                for (idx, path) in state.file_preview_paths.iter().enumerate() {
+                    #[allow(clippy::cast_possible_truncation)]
+                    let _ = file_injector.push(idx as u32, |_, cols| {
+                        cols[0] = path.as_str().into();
+                    });
+                }
+
+                let file_indices =
+                    Self::update_filter(&mut temp_file_nucleo, &state.query, prev_file_query);
+                prev_file_query.clone_from(&state.query);
+
+                state.set_file_preview_matches(file_indices);
+                state.file_preview_scroll = 0;
+            }
+        }
+    }
+
+    /// Build the result returned when the finder exits normally
+    fn exit_result(state: &AppState) -> FinderResult {
+        if state.aborted {
+            FinderResult::aborted()
+        } else {
+            let direct_file_selection = state.is_direct_file_selection();
+            let selected_tags = if direct_file_selection {
+                state.get_filtering_tags()
+            } else {
+                Vec::new()
+            };
+            FinderResult::with_key_and_direct_selection(
+                state.selected_keys(),
+                state.final_key.clone(),
+                direct_file_selection,
+                selected_tags,
+            )
+        }
+    }
+
     /// Run the finder event loop
     #[allow(clippy::too_many_lines)]
     fn run_loop(
@@ -491,6 +651,8 @@ impl RatatuiFinder {
         );
         // Set available tags for autocomplete in text input modals
         state.available_tags.clone_from(&config.available_tags);
+        state.exclude_dirs.clone_from(&config.exclude_dirs);
+        state.file_preview_limit = config.max_items;
 
         // Always initialize tag tree (3-pane layout)
         use super::widgets::TagTreeState;
@@ -504,9 +666,9 @@ impl RatatuiFinder {
                 .into_iter()
                 .filter_map(|tag| {
                     database
-                        .find_by_tag(&tag)
+                        .count_files_per_tag_fast(&tag)
                         .ok()
-                        .map(|files| (tag, files.len()))
+                        .map(|count| (tag, count))
                 })
                 .collect();
 
@@ -543,6 +705,16 @@ impl RatatuiFinder {
         }
         state.tag_tree_state = Some(tag_tree_state);
 
+        // Load saved state from a previous session (if enabled)
+        let persisted = config
+            .state_file
+            .as_deref()
+            .filter(|_| config.restore_state)
+            .and_then(PersistedState::load);
+        if let Some(saved) = &persisted {
+            state.restore_selected_tags(saved);
+        }
+
         // Sync active_filter from tag tree selections (for CLI preview)
         state.sync_filter_from_tag_tree();
 
@@ -563,6 +735,9 @@ impl RatatuiFinder {
             state.focused_pane = FocusPane::FilePreview;
         }
 
+        // Pre-select --start-tag tags, focusing the file pane when any apply
+        state.select_start_tags(&config.start_tags);
+
         let mut nucleo = Self::create_matcher(&config.items);
         let custom_binds = Self::parse_keybinds(&config.bind);
         let overlay_binds = Self::build_overlay_binds(&custom_binds);
@@ -572,11 +747,28 @@ impl RatatuiFinder {
         // Initial filter (show all)
         state.update_filtered(Self::update_filter(&mut nucleo, "", ""));
 
+        // Re-apply the saved query, then clamp saved cursors to what is now visible
+        if let Some(saved) = &persisted {
+            if !saved.query.is_empty() {
+                state.query.clone_from(&saved.query);
+                state.query_cursor = state.query.len();
+                Self::apply_query(
+                    &mut state,
+                    &mut nucleo,
+                    &mut prev_query,
+                    &mut prev_file_query,
+                );
+            }
+            state.restore_cursors(saved);
+        }
+
+        let picker = self.picker.get_or_init(|| self.detect_picker()).as_ref();
         let mut cached_preview: Option<StyledPreview> = None;
+        let mut cached_image: Option<StatefulProtocol> = None;
         let mut cached_preview_key: Option<String> = None;
         let mut cached_preview_mode: Option<crate::ui::ratatui_adapter::state::PreviewMode> = None;
 
-        loop {
+        let result = loop {
             // Update preview if needed - prefer styled_generator (native ratatui) over preview_provider (ANSI)
             if let Some(preview_config) = &config.preview_config
                 && preview_config.enabled
@@ -623,6 +815,9 @@ impl RatatuiFinder {
                                 Some(note_preview)
                             }
                         };
+                        cached_image = picker
+                            .zip(cached_preview.as_ref().and_then(|p| p.image.as_deref()))
+                            .and_then(|(picker, path)| Self::load_image(picker, path));
                         cached_preview_key = Some(current_key.to_string());
                         cached_preview_mode = Some(state.preview_mode);
                     }
@@ -631,7 +826,13 @@ impl RatatuiFinder {
 
             // Render
             terminal.draw(|frame| {
-                self.render(frame, &mut state, &self.theme, cached_preview.as_ref());
+                self.render(
+                    frame,
+                    &mut state,
+                    &self.theme,
+                    cached_preview.as_ref(),
+                    cached_image.as_mut(),
+                );
                 Self::render_overlays(frame, &state, &self.theme, &overlay_binds);
             })?;
 
@@ -695,15 +896,6 @@ impl RatatuiFinder {
                                                     {
                                                         item.metadata.has_note = false;
                                                     }
-                                                    if let Some(item) = state
-                                                        .file_preview_items_unfiltered
-                                                        .iter_mut()
-                                                        .find(|i| {
-                                                            i.key == file_path.to_string_lossy()
-                                                        })
-                                                    {
-                                                        item.metadata.has_note = false;
-                                                    }
                                                 } else if let Some(item) =
                                                     state.items.get_mut(state.cursor)
                                                 {
@@ -729,15 +921,6 @@ impl RatatuiFinder {
                                                     {
                                                         item.metadata.has_note = true;
                                                     }
-                                                    if let Some(item) = state
-                                                        .file_preview_items_unfiltered
-                                                        .iter_mut()
-                                                        .find(|i| {
-                                                            i.key == file_path.display().to_string()
-                                                        })
-                                                    {
-                                                        item.metadata.has_note = true;
-                                                    }
                                                 } else if let Some(current_idx) =
                                                     state.filtered_indices.get(state.cursor)
                                                     && let Some(item) =
@@ -773,6 +956,7 @@ impl RatatuiFinder {
                         criteria.map_or_else(Vec::new, |c| c.exclude_tags.clone()),
                         criteria.map_or_else(Vec::new, |c| c.file_patterns.clone()),
                         criteria.map_or_else(Vec::new, |c| c.virtual_tags.clone()),
+                        criteria.and_then(|c| c.expression.clone()),
                         config.available_tags.clone(),
                     );
                 }
@@ -780,21 +964,22 @@ impl RatatuiFinder {
                     // Apply the refined search criteria - return with special action
                     if let Some(refine_state) = state.exit_refine_search() {
                         // Build a result that signals refine search was applied
-                        return Ok(FinderResult::with_refine_search(
+                        break FinderResult::with_refine_search(
                             refine_state.include_tags,
                             refine_state.exclude_tags,
                             refine_state.file_patterns,
                             refine_state.virtual_tags,
-                        ));
+                            refine_state.expression,
+                        );
                     }
                 }
                 EventResult::Action { action, context } => {
                     // Generic action handling - return immediately with context
-                    return Ok(FinderResult::with_action(
+                    break FinderResult::with_action(
                         context,
                         action.as_str().to_string(),
                         Vec::new(),
-                    ));
+                    );
                 }
                 EventResult::Confirm => {
                     state.confirm(None);
@@ -803,74 +988,12 @@ impl RatatuiFinder {
                     state.abort();
                 }
                 EventResult::QueryChanged => {
-                    let indices = Self::update_filter(&mut nucleo, &state.query, &prev_query);
-                    prev_query.clone_from(&state.query);
-                    state.update_filtered(indices);
-
-                    // In TagSelection phase, filter BOTH panes simultaneously
-                    if state.is_tag_selection_phase() {
-                        // Filter tag tree (left pane) based on query
-                        if let Some(ref mut tag_tree_state) = state.tag_tree_state {
-                            if state.query.is_empty() {
-                                // No query - show all tags
-                                tag_tree_state.rebuild_visible_cache();
-                            } else {
-                                // Filter tags by fuzzy matching on name
-                                let matching_tags: Vec<String> = tag_tree_state
-                                    .all_tag_paths()
-                                    .into_iter()
-                                    .filter(|tag| {
-                                        // Simple substring match (case-insensitive)
-                                        tag.to_lowercase().contains(&state.query.to_lowercase())
-                                    })
-                                    .collect();
-                                tag_tree_state.filter_visible_tags(&matching_tags);
-                            }
-                        }
-
-                        // Filter file preview items (right pane) from the unfiltered list
-                        if !state.file_preview_items_unfiltered.is_empty() {
-                            let mut temp_file_nucleo: Nucleo<u32> = Nucleo::new(
-                                Config::DEFAULT.match_paths(),
-                                Arc::new(|| {}),
-                                None,
-                                1,
-                            );
-
-                            let file_injector = temp_file_nucleo.injector();
-                            for (idx, item) in
-                                state.file_preview_items_unfiltered.iter().enumerate()
-                            {
-                                #[allow(clippy::cast_possible_truncation)]
-                                let _ = file_injector.push(idx as u32, |_, cols| {
-                                    cols[0] = item.searchable.clone().into();
-                                });
-                            }
-
-                            let file_indices = Self::update_filter(
-                                &mut temp_file_nucleo,
-                                &state.query,
-                                &prev_file_query,
-                            );
-                            prev_file_query.clone_from(&state.query);
-
-                            state.file_preview_items = file_indices
-                                .iter()
-                                .filter_map(|&idx| {
-                                    state
-                                        .file_preview_items_unfiltered
-                                        .get(idx as usize)
-                                        .cloned()
-                                })
-                                .collect();
-
-                            if state.file_preview_cursor >= state.file_preview_items.len() {
-                                state.file_preview_cursor =
-                                    state.file_preview_items.len().saturating_sub(1);
-                            }
-                            state.file_preview_scroll = 0;
-                        }
-                    }
+                    Self::apply_query(
+                        &mut state,
+                        &mut nucleo,
+                        &mut prev_query,
+                        &mut prev_file_query,
+                    );
                     // Reset preview cache when query changes
                     cached_preview_key = None;
                 }
@@ -886,20 +1009,16 @@ impl RatatuiFinder {
                 } => {
                     // The input modal was submitted - return to caller with action info
                     // Use the stored context (selected files when modal was opened)
-                    return Ok(FinderResult::with_action(
-                        context,
-                        action.as_str().to_string(),
-                        values,
-                    ));
+                    break FinderResult::with_action(context, action.as_str().to_string(), values);
                 }
                 EventResult::ConfirmSubmitted { action, context } => {
                     // Confirmation dialog was confirmed - return to caller with action info
                     // The context contains the file paths that were selected for the action
-                    return Ok(FinderResult::with_action(
+                    break FinderResult::with_action(
                         context,
                         action.as_str().to_string(),
                         Vec::new(), // No additional values for confirmation-only actions
-                    ));
+                    );
                 }
                 EventResult::InputCancelled
                 | EventResult::ConfirmCancelled
@@ -910,28 +1029,20 @@ impl RatatuiFinder {
             }
 
             if state.should_exit {
-                break;
+                break Self::exit_result(&state);
             }
 
             state.cleanup_messages();
-        }
+        };
 
-        if state.aborted {
-            Ok(FinderResult::aborted())
-        } else {
-            let direct_file_selection = state.is_direct_file_selection();
-            let selected_tags = if direct_file_selection {
-                state.get_filtering_tags()
-            } else {
-                Vec::new()
-            };
-            Ok(FinderResult::with_key_and_direct_selection(
-                state.selected_keys(),
-                state.final_key.clone(),
-                direct_file_selection,
-                selected_tags,
-            ))
+        // Persist state on any non-abort exit; failing to save is not worth an error
+        if !result.aborted
+            && let Some(path) = &config.state_file
+        {
+            let _ = state.to_persisted().save(path);
         }
+
+        Ok(result)
     }
 }
 
@@ -29,9 +29,11 @@
 //! - **In-TUI dialogs** for prompts (no breaking out to dialoguer)
 //! - **Status bar** for messages
 //! - **Help overlay** (F1)
+//! - **Persistent state** (cursor, selections and query restored between sessions)
 
 mod events;
 mod finder;
+mod persist;
 mod state;
 mod styled_preview;
 mod theme;
@@ -39,6 +41,7 @@ pub mod widgets;
 
 pub use finder::RatatuiFinder;
 pub use finder::RatatuiPreviewProvider;
+pub use persist::PersistedState;
 pub use state::{AppState, Mode};
 pub use styled_preview::{StyledPreview, StyledPreviewGenerator};
 pub use theme::Theme;
@@ -0,0 +1,91 @@
+//! Persistent TUI state between browse invocations
+//!
+//! Captures the parts of [`AppState`](super::AppState) worth restoring the next
+//! time `tagr browse` is opened: tag tree cursor, selected tags, file preview
+//! cursor and search query. Stored as JSON in the user's data directory.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Serializable snapshot of the browse TUI state
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedState {
+    /// Cursor position in the tag tree (index into visible nodes)
+    #[serde(default)]
+    pub tag_tree_cursor: usize,
+    /// Tags selected in the tag tree
+    #[serde(default)]
+    pub selected_tags: Vec<String>,
+    /// Cursor position in the file preview pane
+    #[serde(default)]
+    pub file_preview_cursor: usize,
+    /// Search query
+    #[serde(default)]
+    pub query: String,
+}
+
+impl PersistedState {
+    /// Default state file for the database named `db_name`
+    ///
+    /// Resolves to `<data dir>/tagr/<db-name>-tui-state.json`
+    /// (`~/.local/share/tagr/` on Linux).
+    #[must_use]
+    pub fn default_path(db_name: &str) -> Option<PathBuf> {
+        dirs::data_local_dir().map(|dir| dir.join("tagr").join(format!("{db_name}-tui-state.json")))
+    }
+
+    /// Load state from `path`
+    ///
+    /// Returns `None` if the file is missing or cannot be parsed; stale or
+    /// corrupt state is never worth failing the browse session over.
+    #[must_use]
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Save state to `path`, creating parent directories as needed
+    ///
+    /// # Errors
+    /// Returns an error if the directory or file cannot be written
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("nested").join("db-tui-state.json");
+        let state = PersistedState {
+            tag_tree_cursor: 3,
+            selected_tags: vec!["rust".to_string()],
+            file_preview_cursor: 1,
+            query: "notes".to_string(),
+        };
+
+        state.save(&path).unwrap();
+        assert_eq!(PersistedState::load(&path), Some(state));
+    }
+
+    #[test]
+    fn test_load_missing_or_corrupt() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.json");
+        assert!(PersistedState::load(&path).is_none());
+
+        fs::write(&path, "{ not json").unwrap();
+        assert!(PersistedState::load(&path).is_none());
+    }
+}
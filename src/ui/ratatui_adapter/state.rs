@@ -5,6 +5,7 @@
 
 use crate::browse::ActiveFilter;
 use crate::ui::output::MessageLevel;
+use crate::ui::ratatui_adapter::persist::PersistedState;
 use crate::ui::ratatui_adapter::widgets::{
     ConfirmDialogState, FileDetails, KeyHint, RefineSearchState, TagTreeState, TextInputState,
 };
@@ -130,12 +131,18 @@ pub struct AppState {
     pub tag_schema: Option<std::sync::Arc<crate::schema::TagSchema>>,
     /// Database reference for live file count queries
     pub database: Option<std::sync::Arc<crate::db::Database>>,
+    /// Directory patterns excluded from file previews and counts (set by finder from config)
+    pub exclude_dirs: Vec<String>,
     /// Which pane has focus (during `TagSelection` phase)
     pub focused_pane: FocusPane,
-    /// File preview items (live query results)
+    /// File preview items (live query results, up to `file_preview_limit`)
     pub file_preview_items: Vec<DisplayItem>,
-    /// Original unfiltered file preview items (before search filtering)
-    pub file_preview_items_unfiltered: Vec<DisplayItem>,
+    /// All files matching the selected tags, sorted (before search filtering)
+    pub file_preview_paths: Vec<String>,
+    /// Indices into `file_preview_paths` of the files matching the search query
+    pub file_preview_matches: Vec<u32>,
+    /// Most files loaded into the file preview pane at once (0 = no limit)
+    pub file_preview_limit: usize,
     /// Cursor position in file preview pane
     pub file_preview_cursor: usize,
     /// Scroll offset for file preview pane
@@ -198,12 +205,15 @@ impl AppState {
             text_input_state: None,
             confirm_state: None,
             available_tags: Vec::new(),
+            exclude_dirs: Vec::new(),
             tag_tree_state: None,
             tag_schema,
             database,
             focused_pane: FocusPane::TagTree,
             file_preview_items: Vec::new(),
-            file_preview_items_unfiltered: Vec::new(),
+            file_preview_paths: Vec::new(),
+            file_preview_matches: Vec::new(),
+            file_preview_limit: 0,
             file_preview_cursor: 0,
             file_preview_scroll: 0,
             file_preview_selected: HashSet::new(),
@@ -500,6 +510,7 @@ impl AppState {
         exclude_tags: Vec<String>,
         file_patterns: Vec<String>,
         virtual_tags: Vec<String>,
+        expression: Option<String>,
         available_tags: Vec<String>,
     ) {
         self.refine_search_state = Some(RefineSearchState::new(
@@ -507,6 +518,7 @@ impl AppState {
             exclude_tags,
             file_patterns,
             virtual_tags,
+            expression,
             available_tags,
         ));
         self.mode = Mode::RefineSearch;
@@ -706,6 +718,17 @@ impl AppState {
         // CLI preview will be rebuilt automatically on next render via build_cli_preview()
     }
 
+    /// Remove files located under any of the excluded directory patterns
+    fn retain_outside_excluded_dirs(&self, file_set: &mut std::collections::HashSet<String>) {
+        if self.exclude_dirs.is_empty() {
+            return;
+        }
+        let patterns = crate::search::filter::dir_patterns(&self.exclude_dirs).unwrap_or_default();
+        file_set.retain(|file_path| {
+            !crate::search::filter::in_excluded_dir(std::path::Path::new(file_path), &patterns)
+        });
+    }
+
     /// Update file preview based on currently selected tags
     ///
     /// Queries database for files matching selected tags (with alias expansion)
@@ -715,6 +738,8 @@ impl AppState {
 
         if selected_tags.is_empty() {
             self.file_preview_items.clear();
+            self.file_preview_paths.clear();
+            self.file_preview_matches.clear();
             self.file_preview_cursor = 0;
             self.file_preview_scroll = 0;
             self.file_preview_selected.clear();
@@ -724,6 +749,8 @@ impl AppState {
         // Get database and schema
         let Some(db) = &self.database else {
             self.file_preview_items.clear();
+            self.file_preview_paths.clear();
+            self.file_preview_matches.clear();
             self.file_preview_selected.clear();
             return;
         };
@@ -781,27 +808,24 @@ impl AppState {
             }
         }
 
-        // Apply exclusion filter if any tags are excluded
-        if !self.active_filter.criteria.excludes.is_empty() {
-            file_set.retain(|file_path| {
-                // Get tags for this file
-                if let Ok(Some(file_tags)) = db.get_tags(std::path::Path::new(file_path)) {
-                    // Check if file has any excluded tags
-                    let has_excluded = file_tags
-                        .iter()
-                        .any(|tag| self.active_filter.criteria.excludes.contains(tag));
-                    !has_excluded
-                } else {
-                    // Files without tags pass through
-                    true
-                }
-            });
-        }
+        // Drop files under excluded directories
+        self.retain_outside_excluded_dirs(&mut file_set);
 
-        // Convert to DisplayItems
         let mut files: Vec<String> = file_set.into_iter().collect();
         files.sort();
 
+        // Apply exclusion filter if any tags are excluded
+        let excludes = &self.active_filter.criteria.excludes;
+        if !excludes.is_empty()
+            && let Ok(tagged) = db.get_tags_many(&files)
+        {
+            // Results line up with `files`; files without tags pass through
+            let mut keep = tagged.into_iter().map(|(_, tags)| {
+                !tags.is_some_and(|tags| tags.iter().any(|tag| excludes.contains(tag)))
+            });
+            files.retain(|_| keep.next().unwrap_or(true));
+        }
+
         // Build new file set for checking which selections to keep
         let new_file_set: std::collections::HashSet<&str> =
             files.iter().map(String::as_str).collect();
@@ -810,35 +834,82 @@ impl AppState {
         self.file_preview_selected
             .retain(|key| new_file_set.contains(key.as_str()));
 
-        self.file_preview_items = files
+        // Keep every match for search filtering, but only load the first
+        // `file_preview_limit` into the pane
+        #[allow(clippy::cast_possible_truncation)]
+        let matches = (0..files.len() as u32).collect();
+        self.file_preview_paths = files;
+        self.set_file_preview_matches(matches);
+        self.file_preview_scroll = 0;
+    }
+
+    /// Show the files at `matches` (indices into `file_preview_paths`) in the
+    /// file preview pane, loading at most `file_preview_limit` of them
+    pub fn set_file_preview_matches(&mut self, matches: Vec<u32>) {
+        self.file_preview_matches = matches;
+        self.load_file_preview_window();
+    }
+
+    /// Double the file preview limit for this session and load the extra files
+    pub fn raise_file_preview_limit(&mut self) {
+        if self.file_preview_limit == 0 {
+            return;
+        }
+        self.file_preview_limit = self.file_preview_limit.saturating_mul(2);
+        self.load_file_preview_window();
+        self.add_message(
+            MessageLevel::Info,
+            format!("Showing up to {} files", self.file_preview_limit),
+        );
+    }
+
+    /// Total number of files matching the selected tags and search query
+    #[must_use]
+    pub const fn file_preview_total(&self) -> usize {
+        self.file_preview_matches.len()
+    }
+
+    /// Status notice shown while the file preview pane is capped
+    #[must_use]
+    pub fn file_preview_truncation(&self) -> Option<String> {
+        let shown = self.file_preview_items.len();
+        let total = self.file_preview_total();
+        (shown < total).then(|| format!("showing {shown} of {total} — refine query"))
+    }
+
+    fn load_file_preview_window(&mut self) {
+        let shown = match self.file_preview_limit {
+            0 => self.file_preview_matches.len(),
+            limit => limit.min(self.file_preview_matches.len()),
+        };
+        self.file_preview_items = self.file_preview_matches[..shown]
             .iter()
-            .map(|path| {
-                // Check if file has a note
-                let has_note = self
-                    .database
-                    .as_ref()
-                    .and_then(|db| {
-                        std::path::Path::new(path)
-                            .canonicalize()
-                            .ok()
-                            .and_then(|canonical| db.get_note(&canonical).ok().flatten())
-                    })
-                    .is_some();
-
-                let mut item = DisplayItem::new(path.clone(), path.clone(), path.clone());
-                item.metadata.has_note = has_note;
-                item
-            })
+            .filter_map(|&idx| self.file_preview_paths.get(idx as usize))
+            .map(|path| self.file_preview_item(path))
             .collect();
 
-        // Save unfiltered list for search filtering
-        self.file_preview_items_unfiltered = self.file_preview_items.clone();
-
         // Reset cursor if out of bounds
         if self.file_preview_cursor >= self.file_preview_items.len() {
             self.file_preview_cursor = self.file_preview_items.len().saturating_sub(1);
         }
-        self.file_preview_scroll = 0;
+    }
+
+    fn file_preview_item(&self, path: &str) -> DisplayItem {
+        // Check if file has a note
+        let has_note = self
+            .database
+            .as_ref()
+            .and_then(|db| {
+                std::path::Path::new(path)
+                    .canonicalize()
+                    .ok()
+                    .and_then(|canonical| db.get_note(&canonical).ok().flatten())
+            })
+            .is_some();
+
+        let mut item = DisplayItem::new(path.to_string(), path.to_string(), path.to_string());
+        item.metadata.has_note = has_note;
+        item
     }
 
     /// Switch focus between tag tree and file preview panes
@@ -977,6 +1048,19 @@ impl AppState {
         }
     }
 
+    /// CLI command equivalent to the current active filter
+    ///
+    /// This is the command part of [`Self::build_cli_preview`], without the
+    /// file count, suitable for pasting into a shell.
+    #[must_use]
+    pub fn cli_command(&self) -> String {
+        if self.active_filter.is_empty() {
+            "tagr browse".to_string()
+        } else {
+            self.active_filter.to_string()
+        }
+    }
+
     /// Build CLI preview command from current active filter (for educational display)
     ///
     /// Shows canonical tag names to educate users on what actually gets stored.
@@ -987,11 +1071,10 @@ impl AppState {
 
         // If no filters are active, show default browse command
         if self.active_filter.is_empty() {
-            return Some("tagr browse".to_string());
+            return Some(self.cli_command());
         }
 
-        // Use active_filter's Display impl to generate the base command
-        let mut cmd = format!("{}", self.active_filter);
+        let mut cmd = self.cli_command();
 
         // Canonicalize tags for file count calculation
         let canonical_tags: Vec<String> = self
@@ -1036,34 +1119,91 @@ impl AppState {
             },
         );
 
-        // Use ANY mode (union) - count unique files across all expanded tags
-        let mut file_set = std::collections::HashSet::new();
+        // Count only: ANY mode (union), skipping files under excluded directories
+        let dir_patterns =
+            crate::search::filter::dir_patterns(&self.exclude_dirs).unwrap_or_default();
+        db.file_count_for_tags_where(
+            &expanded_tags,
+            crate::cli::SearchMode::Any,
+            &self.active_filter.criteria.excludes,
+            |path| !crate::search::filter::in_excluded_dir(path, &dir_patterns),
+        )
+        .ok()
+    }
 
-        for tag in &expanded_tags {
-            if let Ok(files) = db.find_by_tag(tag) {
-                for file in files {
-                    if let Some(file_str) = file.to_str() {
-                        file_set.insert(file_str.to_string());
-                    }
-                }
-            }
+    /// Capture the state worth restoring on the next browse invocation
+    #[must_use]
+    pub fn to_persisted(&self) -> PersistedState {
+        let (tag_tree_cursor, mut selected_tags) = self.tag_tree_state.as_ref().map_or_else(
+            || (0, Vec::new()),
+            |tree| (tree.selected, tree.selected_tag_paths()),
+        );
+        selected_tags.sort();
+
+        PersistedState {
+            tag_tree_cursor,
+            selected_tags,
+            file_preview_cursor: self.file_preview_cursor,
+            query: self.query.clone(),
         }
+    }
 
-        // Apply exclusion filter if any tags are excluded
-        if !self.active_filter.criteria.excludes.is_empty() {
-            file_set.retain(|file_path| {
-                if let Ok(Some(file_tags)) = db.get_tags(std::path::Path::new(file_path)) {
-                    let has_excluded = file_tags
-                        .iter()
-                        .any(|tag| self.active_filter.criteria.excludes.contains(tag));
-                    !has_excluded
-                } else {
-                    true
-                }
-            });
+    /// Restore tag tree selections from saved state
+    ///
+    /// Tags that no longer exist in the tag tree are ignored.
+    pub fn restore_selected_tags(&mut self, saved: &PersistedState) {
+        if let Some(ref mut tree) = self.tag_tree_state {
+            let known: HashSet<String> = tree.all_tag_paths().into_iter().collect();
+            tree.selected_tags.extend(
+                saved
+                    .selected_tags
+                    .iter()
+                    .filter(|tag| known.contains(*tag))
+                    .cloned(),
+            );
+        }
+    }
+
+    /// Pre-select tags in the tag tree on launch
+    ///
+    /// Tags missing from the tree are ignored. When any tag is selected, the
+    /// active filter and file preview are updated and the file pane gets focus.
+    pub fn select_start_tags(&mut self, tags: &[String]) {
+        let Some(ref mut tree) = self.tag_tree_state else {
+            return;
+        };
+        let known: HashSet<String> = tree.all_tag_paths().into_iter().collect();
+        let matched: Vec<String> = tags
+            .iter()
+            .filter(|tag| known.contains(*tag))
+            .cloned()
+            .collect();
+        if matched.is_empty() {
+            return;
+        }
+        tree.selected_tags.extend(matched);
+
+        self.sync_filter_from_tag_tree();
+        self.update_file_preview();
+        self.focused_pane = FocusPane::FilePreview;
+    }
+
+    /// Restore cursor positions from saved state
+    ///
+    /// Saved positions may be stale (tags or files removed since the last
+    /// session), so they are clamped to the current item counts.
+    pub fn restore_cursors(&mut self, saved: &PersistedState) {
+        if let Some(ref mut tree) = self.tag_tree_state {
+            tree.selected = saved
+                .tag_tree_cursor
+                .min(tree.visible_count().saturating_sub(1));
         }
+        self.sync_cursor_with_tag_tree();
 
-        Some(file_set.len())
+        self.file_preview_cursor = saved
+            .file_preview_cursor
+            .min(self.file_preview_items.len().saturating_sub(1));
+        self.adjust_file_preview_scroll();
     }
 
     /// Synchronize items list cursor with tag tree cursor
@@ -1241,4 +1381,110 @@ mod tests {
         keys.sort();
         assert_eq!(keys, vec!["item0", "item2"]);
     }
+
+    #[test]
+    fn test_restore_persisted_state_clamps_stale_cursors() {
+        let mut state = AppState::new(
+            make_items(3),
+            true,
+            None,
+            None,
+            "> ".to_string(),
+            vec![],
+            None,
+        );
+        let mut tree = TagTreeState::new();
+        tree.build_from_tags(&[("rust".to_string(), 1), ("notes".to_string(), 2)]);
+        state.tag_tree_state = Some(tree);
+
+        let saved = PersistedState {
+            tag_tree_cursor: 50,
+            selected_tags: vec!["rust".to_string(), "deleted".to_string()],
+            file_preview_cursor: 10,
+            query: String::new(),
+        };
+        state.restore_selected_tags(&saved);
+        state.restore_cursors(&saved);
+
+        let tree = state.tag_tree_state.as_ref().unwrap();
+        assert_eq!(tree.selected, tree.visible_count() - 1);
+        assert_eq!(tree.selected_tag_paths(), vec!["rust".to_string()]);
+        assert_eq!(state.file_preview_cursor, 0);
+
+        let persisted = state.to_persisted();
+        assert_eq!(persisted.tag_tree_cursor, tree.selected);
+        assert_eq!(persisted.selected_tags, vec!["rust".to_string()]);
+    }
+
+    #[test]
+    fn test_select_start_tags_filters_and_focuses_files() {
+        let test_db = crate::testing::TestDb::new("test_select_start_tags");
+        let file = crate::testing::TempFile::create("main.rs").unwrap();
+        test_db
+            .db()
+            .add_tags(file.path(), vec!["rust".to_string()])
+            .unwrap();
+
+        let mut state = AppState::new(
+            make_items(2),
+            true,
+            None,
+            Some(std::sync::Arc::new(test_db.db().clone())),
+            "> ".to_string(),
+            vec![],
+            None,
+        );
+        let mut tree = TagTreeState::new();
+        tree.build_from_tags(&[("rust".to_string(), 1), ("notes".to_string(), 0)]);
+        state.tag_tree_state = Some(tree);
+
+        state.select_start_tags(&["missing".to_string()]);
+        assert_eq!(state.focused_pane, FocusPane::TagTree);
+
+        state.select_start_tags(&["rust".to_string(), "missing".to_string()]);
+        assert_eq!(state.focused_pane, FocusPane::FilePreview);
+        assert_eq!(state.active_filter.criteria.tags, vec!["rust".to_string()]);
+        assert_eq!(state.file_preview_items.len(), 1);
+    }
+
+    #[test]
+    fn test_file_preview_limit() {
+        let mut state = AppState::new(vec![], true, None, None, "> ".to_string(), vec![], None);
+        state.file_preview_limit = 2;
+        state.file_preview_paths = (0..5).map(|i| format!("/files/{i}.txt")).collect();
+        state.set_file_preview_matches((0..5).collect());
+
+        let keys = |state: &AppState| -> Vec<String> {
+            state
+                .file_preview_items
+                .iter()
+                .map(|item| item.key.clone())
+                .collect()
+        };
+        assert_eq!(keys(&state), ["/files/0.txt", "/files/1.txt"]);
+        assert_eq!(state.file_preview_total(), 5);
+        assert_eq!(
+            state.file_preview_truncation().as_deref(),
+            Some("showing 2 of 5 — refine query")
+        );
+
+        // Selection works within the loaded window
+        state.file_preview_cursor_down();
+        state.file_preview_toggle_selection();
+        assert!(state.is_file_preview_selected_key("/files/1.txt"));
+
+        // A narrowed query reaches files beyond the cap
+        state.set_file_preview_matches(vec![4]);
+        assert_eq!(keys(&state), ["/files/4.txt"]);
+        assert_eq!(state.file_preview_cursor, 0);
+        assert_eq!(state.file_preview_truncation(), None);
+
+        state.set_file_preview_matches((0..5).collect());
+        state.raise_file_preview_limit();
+        assert_eq!(state.file_preview_limit, 4);
+        assert_eq!(state.file_preview_items.len(), 4);
+        state.raise_file_preview_limit();
+        assert_eq!(state.file_preview_items.len(), 5);
+        assert_eq!(state.file_preview_truncation(), None);
+    }
 }
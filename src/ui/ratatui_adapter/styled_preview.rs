@@ -7,7 +7,7 @@ use ratatui::{
     style::{Color, Modifier, Style},
     text::{Line, Span},
 };
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "syntax-highlighting")]
 use syntect::easy::HighlightLines;
@@ -27,6 +27,9 @@ pub struct StyledPreview {
     pub total_lines: usize,
     /// Title for the preview (filename, etc.)
     pub title: String,
+    /// Raster image to draw inline in place of `lines`, when the terminal
+    /// supports a graphics protocol
+    pub image: Option<PathBuf>,
 }
 
 impl StyledPreview {
@@ -39,6 +42,7 @@ impl StyledPreview {
             truncated: false,
             total_lines: 1,
             title: String::from(" Error "),
+            image: None,
         }
     }
 
@@ -51,6 +55,7 @@ impl StyledPreview {
             truncated: false,
             total_lines: 0,
             title: String::from(" Preview "),
+            image: None,
         }
     }
 
@@ -67,6 +72,46 @@ impl StyledPreview {
             truncated: false,
             total_lines: 0,
             title: String::from(" Binary File "),
+            image: None,
+        }
+    }
+
+    /// Create a preview for an image file
+    ///
+    /// The lines hold the image metadata, used as a fallback when the image
+    /// cannot be drawn inline.
+    #[must_use]
+    pub fn image(path: &Path, size: u64) -> Self {
+        let dim_style = Style::default().fg(Color::DarkGray);
+        let header = crate::preview::image::read_header(path);
+
+        let mut text = match &header {
+            Some(header) => format!(
+                "{} image\n\nDimensions: {} x {} pixels\nColor mode: {}\n",
+                header.format.as_deref().unwrap_or("Unknown"),
+                header.width,
+                header.height,
+                header.color_mode
+            ),
+            None => String::from("Image file - cannot decode\n\n"),
+        };
+        text.push_str(&format!("Size: {size} bytes"));
+
+        let lines: Vec<Line<'static>> = text
+            .lines()
+            .map(|line| Line::styled(line.to_string(), dim_style))
+            .collect();
+        let title = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map_or_else(|| String::from(" Image "), |n| format!(" {n} "));
+
+        Self {
+            total_lines: lines.len(),
+            lines,
+            truncated: false,
+            title,
+            image: header.map(|_| path.to_path_buf()),
         }
     }
 
@@ -127,6 +172,7 @@ impl StyledPreview {
             truncated: false,
             total_lines: total_lines + 8, // +8 for header lines
             title: String::from(" Note "),
+            image: None,
         }
     }
 
@@ -202,6 +248,7 @@ impl StyledPreview {
             truncated: false,
             total_lines: 3,
             title: String::from(" No Note "),
+            image: None,
         }
     }
 }
@@ -212,6 +259,7 @@ pub struct StyledPreviewGenerator {
     syntax_set: SyntaxSet,
     theme_set: ThemeSet,
     max_lines: usize,
+    max_bytes: u64,
 }
 
 #[cfg(feature = "syntax-highlighting")]
@@ -223,9 +271,17 @@ impl StyledPreviewGenerator {
             syntax_set: SyntaxSet::load_defaults_newlines(),
             theme_set: ThemeSet::load_defaults(),
             max_lines,
+            max_bytes: crate::preview::DEFAULT_MAX_PREVIEW_BYTES,
         }
     }
 
+    /// Read at most `max_bytes` of each file, truncating longer files
+    #[must_use]
+    pub const fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
     /// Generate a styled preview for a file
     ///
     /// # Errors
@@ -244,26 +300,29 @@ impl StyledPreviewGenerator {
             return Ok(StyledPreview::empty());
         }
 
-        // Try to read as text
-        let content = match std::fs::read_to_string(path) {
-            Ok(c) => c,
-            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
-                // Binary file
-                return Ok(StyledPreview::binary(&format!(
-                    "Binary file - cannot preview\n\nSize: {} bytes",
-                    metadata.len()
-                )));
-            }
-            Err(e) => return Err(e),
+        if crate::preview::image::is_image(path) {
+            return Ok(StyledPreview::image(path, metadata.len()));
+        }
+
+        // Try to read as text, up to the byte cap
+        let Some(prefix) = crate::preview::read_text_prefix(path, self.max_bytes)? else {
+            // Binary file
+            return Ok(StyledPreview::binary(&format!(
+                "Binary file - cannot preview\n\nSize: {} bytes",
+                metadata.len()
+            )));
         };
 
-        let all_lines: Vec<&str> = content.lines().collect();
+        let all_lines: Vec<&str> = prefix.text.lines().collect();
         let total_lines = all_lines.len();
-        let truncated = total_lines > self.max_lines;
+        let truncated = total_lines > self.max_lines || prefix.truncated;
         let lines_to_render: Vec<&str> = all_lines.into_iter().take(self.max_lines).collect();
 
         // Apply syntax highlighting
-        let styled_lines = self.highlight_lines(path, &lines_to_render);
+        let mut styled_lines = self.highlight_lines(path, &lines_to_render);
+        if prefix.truncated {
+            styled_lines.push(truncation_line(metadata.len()));
+        }
 
         let title = path
             .file_name()
@@ -275,6 +334,7 @@ impl StyledPreviewGenerator {
             truncated,
             total_lines,
             title,
+            image: None,
         })
     }
 
@@ -339,17 +399,35 @@ fn syntect_to_ratatui(style: &syntect::highlighting::Style) -> Style {
     ratatui_style
 }
 
+/// Dimmed notice shown after a preview cut off by the byte cap
+fn truncation_line(file_size: u64) -> Line<'static> {
+    Line::styled(
+        crate::preview::truncation_notice(file_size),
+        Style::default().fg(Color::DarkGray),
+    )
+}
+
 /// Fallback generator when syntax-highlighting feature is disabled
 #[cfg(not(feature = "syntax-highlighting"))]
 pub struct StyledPreviewGenerator {
     max_lines: usize,
+    max_bytes: u64,
 }
 
 #[cfg(not(feature = "syntax-highlighting"))]
 impl StyledPreviewGenerator {
     #[must_use]
     pub fn new(max_lines: usize) -> Self {
-        Self { max_lines }
+        Self {
+            max_lines,
+            max_bytes: crate::preview::DEFAULT_MAX_PREVIEW_BYTES,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
     }
 
     pub fn generate(&self, path: &Path) -> Result<StyledPreview, std::io::Error> {
@@ -365,26 +443,29 @@ impl StyledPreviewGenerator {
             return Ok(StyledPreview::empty());
         }
 
-        let content = match std::fs::read_to_string(path) {
-            Ok(c) => c,
-            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
-                return Ok(StyledPreview::binary(&format!(
-                    "Binary file - cannot preview\n\nSize: {} bytes",
-                    metadata.len()
-                )));
-            }
-            Err(e) => return Err(e),
+        if crate::preview::image::is_image(path) {
+            return Ok(StyledPreview::image(path, metadata.len()));
+        }
+
+        let Some(prefix) = crate::preview::read_text_prefix(path, self.max_bytes)? else {
+            return Ok(StyledPreview::binary(&format!(
+                "Binary file - cannot preview\n\nSize: {} bytes",
+                metadata.len()
+            )));
         };
 
-        let all_lines: Vec<&str> = content.lines().collect();
+        let all_lines: Vec<&str> = prefix.text.lines().collect();
         let total_lines = all_lines.len();
-        let truncated = total_lines > self.max_lines;
+        let truncated = total_lines > self.max_lines || prefix.truncated;
 
-        let lines: Vec<Line<'static>> = all_lines
+        let mut lines: Vec<Line<'static>> = all_lines
             .into_iter()
             .take(self.max_lines)
             .map(|line| Line::raw(line.to_string()))
             .collect();
+        if prefix.truncated {
+            lines.push(truncation_line(metadata.len()));
+        }
 
         let title = path
             .file_name()
@@ -396,6 +477,7 @@ impl StyledPreviewGenerator {
             truncated,
             total_lines,
             title,
+            image: None,
         })
     }
 }
@@ -444,6 +526,23 @@ mod tests {
         assert_eq!(preview.total_lines, 3);
     }
 
+    #[test]
+    fn test_generator_image_metadata() {
+        let temp = tempfile::Builder::new().suffix(".png").tempfile().unwrap();
+        image::RgbImage::new(4, 3).save(temp.path()).unwrap();
+
+        let generator = StyledPreviewGenerator::new(100);
+        let preview = generator.generate(temp.path()).unwrap();
+
+        assert_eq!(preview.image.as_deref(), Some(temp.path()));
+        assert!(
+            preview
+                .lines
+                .iter()
+                .any(|line| line.to_string() == "Dimensions: 4 x 3 pixels")
+        );
+    }
+
     #[test]
     fn test_generator_truncation() {
         let temp = NamedTempFile::new().unwrap();
@@ -459,4 +558,18 @@ mod tests {
         assert!(preview.truncated);
         assert_eq!(preview.total_lines, 100);
     }
+
+    #[test]
+    fn test_generator_byte_cap() {
+        let temp = NamedTempFile::new().unwrap();
+        let content: String = (0..100).map(|i| format!("Line {i}\n")).collect();
+        fs::write(temp.path(), content).unwrap();
+
+        let generator = StyledPreviewGenerator::new(100).with_max_bytes(14);
+        let preview = generator.generate(temp.path()).unwrap();
+
+        assert!(preview.truncated);
+        let text: Vec<String> = preview.lines.iter().map(ToString::to_string).collect();
+        assert_eq!(text, ["Line 0", "Line 1", "… truncated (file is 790 B)"]);
+    }
 }
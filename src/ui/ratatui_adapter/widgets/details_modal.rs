@@ -1,6 +1,9 @@
 //! Details modal widget for displaying file information
 
-use crate::{db::NoteRecord, ui::ratatui_adapter::theme::Theme};
+use crate::{
+    db::{NoteRecord, TagTimes},
+    ui::ratatui_adapter::theme::Theme,
+};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Layout, Rect},
@@ -19,6 +22,12 @@ pub struct FileDetails {
     pub size: u64,
     /// Last modified timestamp (formatted)
     pub modified: String,
+    /// Creation timestamp (formatted), if the filesystem records it
+    pub created: Option<String>,
+    /// When the file was first tagged (formatted), if recorded
+    pub first_tagged: Option<String>,
+    /// Text encoding guessed from the start of the file
+    pub encoding: &'static str,
     /// Tags associated with the file
     pub tags: Vec<String>,
     /// Unix file permissions (if available)
@@ -29,7 +38,7 @@ pub struct FileDetails {
 }
 
 impl FileDetails {
-    /// Create file details from metadata
+    /// Create file details from metadata and what the database stores for it
     ///
     /// # Errors
     /// Returns error if file metadata cannot be read
@@ -37,6 +46,7 @@ impl FileDetails {
         path: &Path,
         tags: Vec<String>,
         note: Option<NoteRecord>,
+        times: Option<TagTimes>,
     ) -> std::io::Result<Self> {
         let metadata = std::fs::metadata(path)?;
 
@@ -46,23 +56,21 @@ impl FileDetails {
             Some(metadata.permissions().mode() & 0o777)
         };
 
-        let modified = if let Ok(time) = metadata.modified() {
-            if let Ok(duration) = time.duration_since(std::time::UNIX_EPOCH) {
-                let timestamp = duration.as_secs() as i64;
-                let dt =
-                    chrono::DateTime::from_timestamp(timestamp, 0).unwrap_or_else(chrono::Utc::now);
-                dt.format("%Y-%m-%d %H:%M:%S").to_string()
-            } else {
-                "Unknown".to_string()
-            }
-        } else {
-            "Unknown".to_string()
-        };
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(format_system_time)
+            .unwrap_or_else(|| "Unknown".to_string());
+        let created = metadata.created().ok().and_then(format_system_time);
+        let first_tagged = times.and_then(|times| format_timestamp(times.first_tagged));
 
         Ok(Self {
             path: path.to_path_buf(),
             size: metadata.len(),
             modified,
+            created,
+            first_tagged,
+            encoding: detect_encoding(path, metadata.len()),
             tags,
             #[cfg(unix)]
             permissions,
@@ -71,6 +79,29 @@ impl FileDetails {
     }
 }
 
+fn format_timestamp(secs: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(secs, 0).map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+fn format_system_time(time: std::time::SystemTime) -> Option<String> {
+    let duration = time.duration_since(std::time::UNIX_EPOCH).ok()?;
+    format_timestamp(i64::try_from(duration.as_secs()).ok()?)
+}
+
+/// Guess the encoding from the first few kilobytes of the file
+fn detect_encoding(path: &Path, size: u64) -> &'static str {
+    const SAMPLE_BYTES: u64 = 8192;
+    if size == 0 {
+        return "empty";
+    }
+    match crate::preview::read_text_prefix(path, SAMPLE_BYTES) {
+        Ok(Some(prefix)) if prefix.text.is_ascii() => "ASCII",
+        Ok(Some(_)) => "UTF-8",
+        Ok(None) => "binary",
+        Err(_) => "unknown",
+    }
+}
+
 /// Details modal widget that displays file information
 pub struct DetailsModal<'a> {
     /// File details to display
@@ -143,6 +174,28 @@ impl<'a> DetailsModal<'a> {
             Span::raw(self.details.modified.clone()),
         ]));
 
+        if let Some(created) = &self.details.created {
+            lines.push(Line::from(vec![
+                Span::styled("Created:  ", Style::default().fg(Color::DarkGray)),
+                Span::raw(created.clone()),
+            ]));
+        }
+
+        lines.push(Line::from(vec![
+            Span::styled("Tagged:   ", Style::default().fg(Color::DarkGray)),
+            Span::raw(
+                self.details
+                    .first_tagged
+                    .clone()
+                    .unwrap_or_else(|| "Unknown".to_string()),
+            ),
+        ]));
+
+        lines.push(Line::from(vec![
+            Span::styled("Encoding: ", Style::default().fg(Color::DarkGray)),
+            Span::raw(self.details.encoding),
+        ]));
+
         #[cfg(unix)]
         if let Some(perms) = self.details.permissions {
             lines.push(Line::from(vec![
@@ -227,7 +280,7 @@ impl<'a> DetailsModal<'a> {
         lines.push(Line::from("─".repeat(70)));
         lines.push(Line::default());
         lines.push(Line::from(Span::styled(
-            "Press any key to close",
+            "Press Esc or i to close",
             Style::default()
                 .fg(Color::DarkGray)
                 .add_modifier(Modifier::ITALIC),
@@ -5,7 +5,9 @@
 //! - Exclude tags (with fuzzy selection)
 //! - File patterns
 //! - Virtual tags (with selection from defined vtag patterns)
+//! - Advanced expression (free text, validated on confirm)
 
+use crate::filters::FilterExpression;
 use crate::ui::ratatui_adapter::theme::Theme;
 use ratatui::{
     buffer::Buffer,
@@ -23,6 +25,7 @@ pub enum RefineField {
     ExcludeTags,
     FilePatterns,
     VirtualTags,
+    Expression,
 }
 
 impl RefineField {
@@ -34,17 +37,19 @@ impl RefineField {
             Self::ExcludeTags => "Exclude Tags",
             Self::FilePatterns => "File Patterns",
             Self::VirtualTags => "Virtual Tags",
+            Self::Expression => "Advanced Expression",
         }
     }
 
     /// Get all fields in order
     #[must_use]
-    pub const fn all() -> [Self; 4] {
+    pub const fn all() -> [Self; 5] {
         [
             Self::IncludeTags,
             Self::ExcludeTags,
             Self::FilePatterns,
             Self::VirtualTags,
+            Self::Expression,
         ]
     }
 
@@ -55,7 +60,8 @@ impl RefineField {
             Self::IncludeTags => Self::ExcludeTags,
             Self::ExcludeTags => Self::FilePatterns,
             Self::FilePatterns => Self::VirtualTags,
-            Self::VirtualTags => Self::IncludeTags,
+            Self::VirtualTags => Self::Expression,
+            Self::Expression => Self::IncludeTags,
         }
     }
 
@@ -63,12 +69,19 @@ impl RefineField {
     #[must_use]
     pub const fn prev(self) -> Self {
         match self {
-            Self::IncludeTags => Self::VirtualTags,
+            Self::IncludeTags => Self::Expression,
             Self::ExcludeTags => Self::IncludeTags,
             Self::FilePatterns => Self::ExcludeTags,
             Self::VirtualTags => Self::FilePatterns,
+            Self::Expression => Self::VirtualTags,
         }
     }
+
+    /// Whether this field is edited as free text rather than picked from a list
+    #[must_use]
+    pub const fn is_text(self) -> bool {
+        matches!(self, Self::Expression)
+    }
 }
 
 /// State for the refine search overlay
@@ -86,6 +99,10 @@ pub struct RefineSearchState {
     pub file_patterns: Vec<String>,
     /// Current virtual tags
     pub virtual_tags: Vec<String>,
+    /// Current grouped expression, in query syntax
+    pub expression: Option<String>,
+    /// Parse error from the last attempt to confirm the expression
+    pub expression_error: Option<String>,
     /// Available tags from database (for selection)
     pub available_tags: Vec<String>,
     /// Available virtual tag patterns
@@ -106,6 +123,7 @@ impl RefineSearchState {
         exclude_tags: Vec<String>,
         file_patterns: Vec<String>,
         virtual_tags: Vec<String>,
+        expression: Option<String>,
         available_tags: Vec<String>,
     ) -> Self {
         Self {
@@ -115,6 +133,8 @@ impl RefineSearchState {
             exclude_tags,
             file_patterns,
             virtual_tags,
+            expression,
+            expression_error: None,
             available_tags,
             available_vtags: Self::default_vtag_patterns(),
             selection_cursor: 0,
@@ -174,18 +194,49 @@ impl RefineSearchState {
         self.in_selection = true;
         self.selection_cursor = 0;
         self.selection_query.clear();
-        self.update_selection_items();
+        self.expression_error = None;
+        if self.selected_field.is_text() {
+            self.selection_query = self.expression.clone().unwrap_or_default();
+            self.selection_items.clear();
+        } else {
+            self.update_selection_items();
+        }
     }
 
     /// Exit selection mode
     pub fn exit_selection(&mut self) {
         self.in_selection = false;
         self.selection_query.clear();
+        self.expression_error = None;
+    }
+
+    /// Validate the typed expression and store it
+    ///
+    /// An empty query clears the expression. Returns `false` and records the
+    /// parse error if the query is not a valid expression, leaving the text
+    /// in place for the user to fix.
+    pub fn commit_expression(&mut self) -> bool {
+        let input = self.selection_query.trim();
+        if input.is_empty() {
+            self.expression = None;
+            return true;
+        }
+        match FilterExpression::parse(input) {
+            Ok(expr) => {
+                self.expression = Some(expr.to_string());
+                true
+            }
+            Err(e) => {
+                self.expression_error = Some(e.to_string());
+                false
+            }
+        }
     }
 
     /// Update the filtered selection items based on query
     pub fn update_selection_items(&mut self) {
         let source = match self.selected_field {
+            RefineField::Expression => return,
             RefineField::IncludeTags | RefineField::ExcludeTags => &self.available_tags,
             RefineField::VirtualTags => &self.available_vtags,
             RefineField::FilePatterns => {
@@ -229,6 +280,7 @@ impl RefineSearchState {
                 RefineField::ExcludeTags => &mut self.exclude_tags,
                 RefineField::FilePatterns => &mut self.file_patterns,
                 RefineField::VirtualTags => &mut self.virtual_tags,
+                RefineField::Expression => return,
             };
 
             if let Some(pos) = target.iter().position(|x| x == &item) {
@@ -247,6 +299,7 @@ impl RefineSearchState {
             RefineField::ExcludeTags => &self.exclude_tags,
             RefineField::FilePatterns => &self.file_patterns,
             RefineField::VirtualTags => &self.virtual_tags,
+            RefineField::Expression => return false,
         };
         target.contains(&item.to_string())
     }
@@ -285,6 +338,7 @@ impl RefineSearchState {
             RefineField::ExcludeTags => &self.exclude_tags,
             RefineField::FilePatterns => &self.file_patterns,
             RefineField::VirtualTags => &self.virtual_tags,
+            RefineField::Expression => self.expression.as_slice(),
         }
     }
 }
@@ -335,10 +389,11 @@ impl<'a> RefineSearchOverlay<'a> {
             .map(|field| {
                 let is_selected = *field == self.state.selected_field;
                 let values = match field {
-                    RefineField::IncludeTags => &self.state.include_tags,
+                    RefineField::IncludeTags => self.state.include_tags.as_slice(),
                     RefineField::ExcludeTags => &self.state.exclude_tags,
                     RefineField::FilePatterns => &self.state.file_patterns,
                     RefineField::VirtualTags => &self.state.virtual_tags,
+                    RefineField::Expression => self.state.expression.as_slice(),
                 };
 
                 let label = field.label();
@@ -413,7 +468,12 @@ impl Widget for RefineSearchOverlay<'_> {
         // Clear the background
         Clear.render(popup_area, buf);
 
-        let title = if self.state.in_selection {
+        let title = if self.state.in_selection && self.state.selected_field.is_text() {
+            format!(
+                " Edit {} (Enter to confirm, Esc to cancel) ",
+                self.state.selected_field.label()
+            )
+        } else if self.state.in_selection {
             format!(
                 " Select {} (TAB to toggle, Enter to confirm) ",
                 self.state.selected_field.label()
@@ -431,7 +491,34 @@ impl Widget for RefineSearchOverlay<'_> {
         let inner = block.inner(popup_area);
         block.render(popup_area, buf);
 
-        if self.state.in_selection {
+        if self.state.in_selection && self.state.selected_field.is_text() {
+            // Show free-text editor with syntax hint and parse error
+            let chunks = Layout::vertical([
+                Constraint::Length(3), // Input
+                Constraint::Min(3),    // Hint or error
+                Constraint::Length(1), // Help
+            ])
+            .split(inner);
+
+            let input_block = Block::default().borders(Borders::ALL).title(" Expression ");
+            Paragraph::new(self.state.selection_query.clone())
+                .block(input_block)
+                .render(chunks[0], buf);
+
+            let message = self.state.expression_error.as_ref().map_or_else(
+                || {
+                    Paragraph::new("e.g. (rust OR python) AND NOT deprecated AND file:src/**")
+                        .style(Style::default().fg(Color::DarkGray))
+                },
+                |err| Paragraph::new(err.clone()).style(Style::default().fg(Color::Red)),
+            );
+            message.render(chunks[1], buf);
+
+            let help = Paragraph::new("Enter: validate & save | Esc: cancel | Empty clears")
+                .style(Style::default().fg(Color::DarkGray))
+                .alignment(Alignment::Center);
+            help.render(chunks[2], buf);
+        } else if self.state.in_selection {
             // Show selection list with search
             let chunks = Layout::vertical([
                 Constraint::Length(3), // Search bar
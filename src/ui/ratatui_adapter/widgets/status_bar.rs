@@ -20,6 +20,8 @@ pub struct StatusBar<'a> {
     cli_preview: Option<&'a str>,
     /// Current preview mode (file or note)
     preview_mode: PreviewMode,
+    /// Warning shown after the CLI preview, e.g. when results are capped
+    notice: Option<&'a str>,
 }
 
 impl<'a> StatusBar<'a> {
@@ -35,6 +37,7 @@ impl<'a> StatusBar<'a> {
             theme,
             cli_preview: None,
             preview_mode,
+            notice: None,
         }
     }
 
@@ -45,6 +48,13 @@ impl<'a> StatusBar<'a> {
         self
     }
 
+    /// Set a warning notice shown after the CLI preview
+    #[must_use]
+    pub const fn with_notice(mut self, notice: Option<&'a str>) -> Self {
+        self.notice = notice;
+        self
+    }
+
     /// Get style for a message level
     fn style_for_level(&self, level: MessageLevel) -> ratatui::style::Style {
         match level {
@@ -131,7 +141,15 @@ impl Widget for StatusBar<'_> {
         // Left side: CLI preview or messages
         // Priority 1: Show CLI preview if available (educational feature)
         if let Some(cmd) = self.cli_preview {
-            let line = Self::build_cli_preview_line(cmd);
+            let mut line = Self::build_cli_preview_line(cmd);
+            if let Some(notice) = self.notice {
+                let style = self.style_for_level(MessageLevel::Warning);
+                line.spans.push(Span::styled(
+                    format!("  {}", Self::prefix_for_level(MessageLevel::Warning)),
+                    style,
+                ));
+                line.spans.push(Span::styled(notice.to_string(), style));
+            }
             Paragraph::new(line).render(chunks[0], buf);
         } else if !self.messages.is_empty() {
             // Priority 2: Show messages if any
@@ -87,8 +87,7 @@ impl TagTreeNode {
     pub fn add_child(&mut self, child: Self) {
         self.children.push(child);
         // Keep children sorted
-        self.children
-            .sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        self.children.sort_by_key(|node| node.name.to_lowercase());
     }
 
     /// Toggle expansion state
@@ -334,7 +333,7 @@ impl TagTreeState {
         }
 
         // Sort nodes alphabetically
-        nodes.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        nodes.sort_by_key(|node| node.name.to_lowercase());
         nodes
     }
 
@@ -14,6 +14,8 @@ pub struct RefineSearchCriteria {
     pub file_patterns: Vec<String>,
     /// Virtual tag patterns
     pub virtual_tags: Vec<String>,
+    /// Grouped filter expression, in query syntax
+    pub expression: Option<String>,
 }
 
 impl RefineSearchCriteria {
@@ -24,12 +26,14 @@ impl RefineSearchCriteria {
         exclude_tags: Vec<String>,
         file_patterns: Vec<String>,
         virtual_tags: Vec<String>,
+        expression: Option<String>,
     ) -> Self {
         Self {
             include_tags,
             exclude_tags,
             file_patterns,
             virtual_tags,
+            expression,
         }
     }
 }
@@ -57,6 +61,16 @@ pub struct FinderConfig {
     pub tag_schema: Option<std::sync::Arc<crate::schema::TagSchema>>,
     /// Database reference for live file count queries (used in tag selection phase)
     pub database: Option<std::sync::Arc<crate::db::Database>>,
+    /// File to save TUI state to on exit (None = don't persist)
+    pub state_file: Option<std::path::PathBuf>,
+    /// Restore TUI state from `state_file` on startup
+    pub restore_state: bool,
+    /// Directory patterns excluded from live file previews
+    pub exclude_dirs: Vec<String>,
+    /// Tags pre-selected in the tag tree on launch
+    pub start_tags: Vec<String>,
+    /// Most files loaded into the file pane at once (0 = no limit)
+    pub max_items: usize,
 }
 
 impl FinderConfig {
@@ -74,6 +88,11 @@ impl FinderConfig {
             search_criteria: None,
             tag_schema: None,
             database: None,
+            state_file: None,
+            restore_state: false,
+            exclude_dirs: Vec::new(),
+            start_tags: Vec::new(),
+            max_items: 0,
         }
     }
 
@@ -132,6 +151,40 @@ impl FinderConfig {
         self.database = db;
         self
     }
+
+    /// Set the file used to persist TUI state between sessions
+    ///
+    /// State is always saved on exit; `restore` controls whether it is loaded
+    /// on startup.
+    #[must_use]
+    pub fn with_state_file(mut self, path: Option<std::path::PathBuf>, restore: bool) -> Self {
+        self.state_file = path;
+        self.restore_state = restore;
+        self
+    }
+
+    /// Set directory patterns excluded from live file previews
+    #[must_use]
+    pub fn with_exclude_dirs(mut self, dirs: Vec<String>) -> Self {
+        self.exclude_dirs = dirs;
+        self
+    }
+
+    /// Set tags pre-selected in the tag tree on launch
+    #[must_use]
+    pub fn with_start_tags(mut self, tags: Vec<String>) -> Self {
+        self.start_tags = tags;
+        self
+    }
+
+    /// Set the most files loaded into the file pane at once (0 = no limit)
+    ///
+    /// Typing a query still searches every matching file.
+    #[must_use]
+    pub const fn with_max_items(mut self, max_items: usize) -> Self {
+        self.max_items = max_items;
+        self
+    }
 }
 
 /// Configuration for preview pane
@@ -141,6 +194,8 @@ pub struct PreviewConfig {
     pub enabled: bool,
     /// Maximum file size to preview (bytes)
     pub max_file_size: u64,
+    /// Maximum bytes read from a file for its preview
+    pub max_preview_bytes: u64,
     /// Maximum lines to display
     pub max_lines: usize,
     /// Enable syntax highlighting
@@ -158,6 +213,7 @@ impl Default for PreviewConfig {
         Self {
             enabled: true,
             max_file_size: 5_242_880, // 5MB
+            max_preview_bytes: crate::preview::DEFAULT_MAX_PREVIEW_BYTES,
             max_lines: 50,
             syntax_highlighting: true,
             show_line_numbers: true,
@@ -172,6 +228,7 @@ impl From<crate::config::PreviewConfig> for PreviewConfig {
         Self {
             enabled: cfg.enabled,
             max_file_size: cfg.max_file_size,
+            max_preview_bytes: cfg.max_preview_bytes,
             max_lines: cfg.max_lines,
             syntax_highlighting: cfg.syntax_highlighting,
             show_line_numbers: cfg.show_line_numbers,
@@ -91,7 +91,7 @@ pub struct ItemMetadata {
 }
 
 /// Result from fuzzy finder
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FinderResult {
     /// Selected items (keys from `DisplayItem`)
     pub selected: Vec<String>,
@@ -134,6 +134,8 @@ pub struct RefinedSearchCriteria {
     pub file_patterns: Vec<String>,
     /// Virtual tag patterns
     pub virtual_tags: Vec<String>,
+    /// Grouped filter expression, in query syntax
+    pub expression: Option<String>,
 }
 
 impl FinderResult {
@@ -205,6 +207,7 @@ impl FinderResult {
         exclude_tags: Vec<String>,
         file_patterns: Vec<String>,
         virtual_tags: Vec<String>,
+        expression: Option<String>,
     ) -> Self {
         Self {
             selected: Vec::new(),
@@ -215,6 +218,7 @@ impl FinderResult {
                 exclude_tags,
                 file_patterns,
                 virtual_tags,
+                expression,
             }),
             input_action: None,
             direct_file_selection: false,
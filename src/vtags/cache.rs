@@ -6,6 +6,10 @@ use std::time::{Duration, SystemTime};
 
 pub struct MetadataCache {
     cache: Cache<PathBuf, FileMetadata>,
+    #[cfg(feature = "exif")]
+    exif: Cache<PathBuf, Option<ExifData>>,
+    #[cfg(feature = "media")]
+    media: Cache<PathBuf, Option<MediaData>>,
 }
 
 #[derive(Debug, Clone)]
@@ -20,11 +24,48 @@ pub struct FileMetadata {
     pub permissions: Permissions,
 }
 
+/// EXIF fields used by `exif:` virtual tags
+#[cfg(feature = "exif")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExifData {
+    pub make: Option<String>,
+    pub model: Option<String>,
+    pub lens: Option<String>,
+    pub iso: Option<u64>,
+    /// Focal length rounded to whole millimetres
+    pub focal_length: Option<u64>,
+    /// Capture date as `YYYY-MM-DD HH:MM:SS`, from `DateTimeOriginal` or `DateTime`
+    pub date: Option<String>,
+}
+
+/// Stream details used by `duration:`, `bitrate:` and `codec:` virtual tags
+#[cfg(feature = "media")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MediaData {
+    /// Duration of the default track in milliseconds
+    pub duration_ms: Option<u64>,
+    /// Average bitrate over the whole file in bits per second
+    pub bitrate: Option<u64>,
+    /// Codec short name of the default track, e.g. `flac` or `pcm_s16le`
+    pub codec: Option<String>,
+}
+
+/// Extensions probed for `media` virtual tags; other files never match
+#[cfg(feature = "media")]
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "aac", "caf", "flac", "m4a", "m4b", "m4v", "mka", "mkv", "mov", "mp3", "mp4", "oga", "ogg",
+    "opus", "wav", "webm",
+];
+
 impl MetadataCache {
     #[must_use]
     pub fn new(ttl: Duration) -> Self {
         Self {
             cache: Cache::builder().time_to_idle(ttl).build(),
+            #[cfg(feature = "exif")]
+            exif: Cache::builder().time_to_idle(ttl).build(),
+            #[cfg(feature = "media")]
+            media: Cache::builder().time_to_idle(ttl).build(),
         }
     }
 
@@ -56,12 +97,162 @@ impl MetadataCache {
         })
     }
 
+    /// Read a file's EXIF metadata, or `None` if it has none
+    ///
+    /// Files that aren't images or whose EXIF block can't be parsed yield
+    /// `None` rather than an error.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened.
+    #[cfg(feature = "exif")]
+    pub fn get_exif(&mut self, path: &Path) -> io::Result<Option<ExifData>> {
+        if let Some(exif) = self.exif.get(&path.to_path_buf()) {
+            return Ok(exif);
+        }
+
+        let exif = Self::fetch_exif(path)?;
+        self.exif.insert(path.to_path_buf(), exif.clone());
+
+        Ok(exif)
+    }
+
+    #[cfg(feature = "exif")]
+    fn fetch_exif(path: &Path) -> io::Result<Option<ExifData>> {
+        use exif::{In, Tag, Value};
+
+        let file = std::fs::File::open(path)?;
+        let Ok(exif) = exif::Reader::new().read_from_container(&mut io::BufReader::new(file))
+        else {
+            return Ok(None);
+        };
+
+        let text = |tag| match exif.get_field(tag, In::PRIMARY).map(|field| &field.value) {
+            Some(Value::Ascii(values)) => values
+                .first()
+                .map(|value| String::from_utf8_lossy(value).trim().to_string())
+                .filter(|value| !value.is_empty()),
+            _ => None,
+        };
+        let number = |tag| {
+            exif.get_field(tag, In::PRIMARY)
+                .and_then(|field| match &field.value {
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    Value::Rational(values) => {
+                        values.first().map(|value| value.to_f64().round() as u64)
+                    }
+                    value => value.get_uint(0).map(u64::from),
+                })
+        };
+        let date = text(Tag::DateTimeOriginal)
+            .or_else(|| text(Tag::DateTime))
+            .and_then(|value| exif::DateTime::from_ascii(value.as_bytes()).ok())
+            .map(|dt| {
+                format!(
+                    "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                    dt.year, dt.month, dt.day, dt.hour, dt.minute, dt.second
+                )
+            });
+
+        Ok(Some(ExifData {
+            make: text(Tag::Make),
+            model: text(Tag::Model),
+            lens: text(Tag::LensModel),
+            iso: number(Tag::PhotographicSensitivity),
+            focal_length: number(Tag::FocalLength),
+            date,
+        }))
+    }
+
+    /// Read an audio or video file's stream details, or `None` if it isn't media
+    ///
+    /// Only files with a known media extension are probed; files that can't be
+    /// probed yield `None` rather than an error.
+    ///
+    /// # Errors
+    /// Returns an error if a media file cannot be opened.
+    #[cfg(feature = "media")]
+    pub fn get_media(&mut self, path: &Path) -> io::Result<Option<MediaData>> {
+        if let Some(media) = self.media.get(&path.to_path_buf()) {
+            return Ok(media);
+        }
+
+        let media = Self::fetch_media(path)?;
+        self.media.insert(path.to_path_buf(), media.clone());
+
+        Ok(media)
+    }
+
+    #[cfg(feature = "media")]
+    fn fetch_media(path: &Path) -> io::Result<Option<MediaData>> {
+        use symphonia::core::{
+            formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+            units::TimeBase,
+        };
+
+        let Some(ext) = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase)
+            .filter(|ext| MEDIA_EXTENSIONS.contains(&ext.as_str()))
+        else {
+            return Ok(None);
+        };
+        let file = std::fs::File::open(path)?;
+        let size = file.metadata()?.len();
+
+        let mut hint = Hint::new();
+        hint.with_extension(&ext);
+        let stream = MediaSourceStream::new(Box::new(file), Default::default());
+        let Ok(probed) = symphonia::default::get_probe().format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        ) else {
+            return Ok(None);
+        };
+        let Some(track) = probed.format.default_track() else {
+            return Ok(None);
+        };
+
+        let params = &track.codec_params;
+        let time_base = params
+            .time_base
+            .or_else(|| params.sample_rate.map(|rate| TimeBase::new(1, rate)));
+        let duration_ms = params.n_frames.zip(time_base).map(|(frames, time_base)| {
+            let time = time_base.calc_time(frames);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let frac_ms = (time.frac * 1000.0).round() as u64;
+            time.seconds * 1000 + frac_ms
+        });
+        let bitrate = duration_ms
+            .filter(|ms| *ms > 0)
+            .map(|ms| size.saturating_mul(8_000) / ms);
+        let codec = symphonia::default::get_codecs()
+            .get_codec(params.codec)
+            .map(|codec| codec.short_name.to_string());
+
+        Ok(Some(MediaData {
+            duration_ms,
+            bitrate,
+            codec,
+        }))
+    }
+
     pub fn cleanup(&mut self) {
         // Moka automatically handles TTL-based eviction
         self.cache.run_pending_tasks();
+        #[cfg(feature = "exif")]
+        self.exif.run_pending_tasks();
+        #[cfg(feature = "media")]
+        self.media.run_pending_tasks();
     }
 
     pub fn clear(&mut self) {
         self.cache.invalidate_all();
+        #[cfg(feature = "exif")]
+        self.exif.invalidate_all();
+        #[cfg(feature = "media")]
+        self.media.invalidate_all();
     }
 }
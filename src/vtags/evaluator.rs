@@ -1,5 +1,9 @@
 use crate::vtags::cache::MetadataCache;
 use crate::vtags::config::VirtualTagConfig;
+#[cfg(feature = "media")]
+use crate::vtags::types::MediaCondition;
+#[cfg(feature = "exif")]
+use crate::vtags::types::{ExifCondition, ExifNumberField, ExifTextField};
 use crate::vtags::types::{
     ExtTypeCategory, PermissionCondition, RangeCondition, SizeCategory, SizeCondition,
     TimeCondition, VirtualTag,
@@ -41,9 +45,69 @@ impl VirtualTagEvaluator {
             VirtualTag::Permission(perm) => self.check_permission(path, perm),
             VirtualTag::Lines(range) => self.check_lines(path, range),
             VirtualTag::Git(_cond) => Ok(false),
+            #[cfg(feature = "exif")]
+            VirtualTag::Exif(cond) => self.check_exif(path, cond),
+            #[cfg(not(feature = "exif"))]
+            VirtualTag::Exif(_cond) => Ok(false),
+            #[cfg(feature = "media")]
+            VirtualTag::Media(cond) => self.check_media(path, cond),
+            #[cfg(not(feature = "media"))]
+            VirtualTag::Media(_cond) => Ok(false),
         }
     }
 
+    /// Files that aren't audio or video, or lack the detail, don't match
+    #[cfg(feature = "media")]
+    fn check_media(&mut self, path: &Path, cond: &MediaCondition) -> io::Result<bool> {
+        let Some(media) = self.cache.get_media(path)? else {
+            return Ok(false);
+        };
+
+        Ok(match cond {
+            MediaCondition::Duration(range) => media
+                .duration_ms
+                .is_some_and(|ms| evaluate_range_condition(ms, range)),
+            MediaCondition::Bitrate(range) => media
+                .bitrate
+                .is_some_and(|bitrate| evaluate_range_condition(bitrate, range)),
+            MediaCondition::Codec(name) => media.codec.is_some_and(|codec| {
+                codec == *name
+                    || codec
+                        .strip_prefix(name.as_str())
+                        .is_some_and(|rest| rest.starts_with('_'))
+            }),
+        })
+    }
+
+    /// Files without EXIF metadata, or without the field, don't match
+    #[cfg(feature = "exif")]
+    fn check_exif(&mut self, path: &Path, cond: &ExifCondition) -> io::Result<bool> {
+        let Some(exif) = self.cache.get_exif(path)? else {
+            return Ok(false);
+        };
+
+        Ok(match cond {
+            ExifCondition::Text(field, value) => {
+                let text = match field {
+                    ExifTextField::Camera => match (&exif.make, &exif.model) {
+                        (Some(make), Some(model)) => Some(format!("{make} {model}")),
+                        (make, model) => make.clone().or_else(|| model.clone()),
+                    },
+                    ExifTextField::Make => exif.make,
+                    ExifTextField::Model => exif.model,
+                    ExifTextField::Lens => exif.lens,
+                };
+                text.is_some_and(|text| text.to_lowercase().contains(value.as_str()))
+            }
+            ExifCondition::Number(field, range) => match field {
+                ExifNumberField::Iso => exif.iso,
+                ExifNumberField::FocalLength => exif.focal_length,
+            }
+            .is_some_and(|number| evaluate_range_condition(number, range)),
+            ExifCondition::Date(prefix) => exif.date.is_some_and(|date| date.starts_with(prefix)),
+        })
+    }
+
     fn check_time(
         &mut self,
         path: &Path,
@@ -307,3 +371,137 @@ const fn evaluate_range_condition(value: u64, cond: &RangeCondition) -> bool {
         RangeCondition::Range(min, max) => value >= *min && value <= *max,
     }
 }
+
+#[cfg(all(test, feature = "exif"))]
+mod tests {
+    use super::*;
+    use crate::testing::TempFile;
+    use std::path::PathBuf;
+
+    /// JPEG with Make "Canon", Model "Canon EOS 5D Mark IV", ISO 1600,
+    /// 50mm focal length, lens "EF50mm f/1.8 STM", taken 2023-06-15 14:30:00
+    fn fixture() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/exif.jpg")
+    }
+
+    fn matches(path: &Path, vtag: &str) -> bool {
+        let mut evaluator =
+            VirtualTagEvaluator::new(Duration::from_secs(60), VirtualTagConfig::default());
+        evaluator
+            .matches(path, &VirtualTag::try_from(vtag).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_exif_fields() {
+        let photo = fixture();
+        for vtag in [
+            "exif:camera=Canon",
+            "exif:camera=eos 5d",
+            "exif:make=canon",
+            "exif:lens=50mm",
+            "exif:iso>800",
+            "exif:iso=1600",
+            "exif:iso=100-3200",
+            "exif:focal<85",
+            "exif:date:2023",
+            "exif:date:2023-06-15",
+        ] {
+            assert!(matches(&photo, vtag), "{vtag} should match");
+        }
+        for vtag in [
+            "exif:camera=Nikon",
+            "exif:model=Nikon",
+            "exif:iso<800",
+            "exif:focal>85",
+            "exif:date:2022",
+            "exif:date:2023-07",
+        ] {
+            assert!(!matches(&photo, vtag), "{vtag} should not match");
+        }
+    }
+
+    #[test]
+    fn test_exif_non_image_does_not_match() {
+        let text = TempFile::create("exif_notes.txt").unwrap();
+        assert!(!matches(text.path(), "exif:camera=Canon"));
+        assert!(!matches(text.path(), "exif:iso>0"));
+    }
+
+    #[test]
+    fn test_exif_cached_per_file() {
+        let mut cache = MetadataCache::new(Duration::from_secs(60));
+        let exif = cache.get_exif(&fixture()).unwrap().unwrap();
+        assert_eq!(exif.make.as_deref(), Some("Canon"));
+        assert_eq!(exif.iso, Some(1600));
+        assert_eq!(exif.focal_length, Some(50));
+        assert_eq!(exif.date.as_deref(), Some("2023-06-15 14:30:00"));
+        assert_eq!(cache.get_exif(&fixture()).unwrap(), Some(exif));
+
+        assert!(cache.get_exif(Path::new("/nonexistent/photo.jpg")).is_err());
+    }
+}
+
+#[cfg(all(test, feature = "media"))]
+mod media_tests {
+    use super::*;
+    use crate::testing::TempFile;
+    use std::path::PathBuf;
+
+    /// Two seconds of 8-bit mono PCM at 4 kHz (32 kbps)
+    fn fixture() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tone.wav")
+    }
+
+    fn matches(path: &Path, vtag: &str) -> bool {
+        let mut evaluator =
+            VirtualTagEvaluator::new(Duration::from_secs(60), VirtualTagConfig::default());
+        evaluator
+            .matches(path, &VirtualTag::try_from(vtag).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_media_fields() {
+        let audio = fixture();
+        for vtag in [
+            "duration:>1s",
+            "duration:<1m",
+            "duration:1s-3s",
+            "bitrate:>30k",
+            "bitrate:<64kbps",
+            "codec:pcm",
+            "codec:pcm_u8",
+        ] {
+            assert!(matches(&audio, vtag), "{vtag} should match");
+        }
+        for vtag in [
+            "duration:>5m",
+            "duration:<2",
+            "bitrate:>320k",
+            "codec:flac",
+            "codec:pc",
+        ] {
+            assert!(!matches(&audio, vtag), "{vtag} should not match");
+        }
+    }
+
+    #[test]
+    fn test_media_non_media_does_not_match() {
+        let text = TempFile::create("media_notes.txt").unwrap();
+        assert!(!matches(text.path(), "duration:<1h"));
+        let fake = TempFile::create("media_fake.mp3").unwrap();
+        assert!(!matches(fake.path(), "codec:mp3"));
+    }
+
+    #[test]
+    fn test_media_cached_per_file() {
+        let mut cache = MetadataCache::new(Duration::from_secs(60));
+        let media = cache.get_media(&fixture()).unwrap().unwrap();
+        assert_eq!(media.duration_ms, Some(2000));
+        assert_eq!(media.codec.as_deref(), Some("pcm_u8"));
+        assert_eq!(cache.get_media(&fixture()).unwrap(), Some(media));
+
+        assert!(cache.get_media(Path::new("/nonexistent/song.mp3")).is_err());
+    }
+}
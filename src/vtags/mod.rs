@@ -13,6 +13,8 @@
 //! - **Permission queries**: Filter by file permissions (executable, readable, etc.)
 //! - **Content queries**: Filter by line count or other content properties
 //! - **Git queries**: Filter by Git status (tracked, modified, staged, etc.)
+//! - **EXIF queries**: Filter photos by camera, ISO, focal length or capture date
+//!   (`exif` feature)
 //! - **Metadata caching**: TTL-based caching for performance
 //! - **Parallel evaluation**: Uses rayon for efficient multi-threaded processing
 //!
@@ -50,11 +52,13 @@ pub mod evaluator;
 pub mod parser;
 pub mod types;
 
+#[cfg(feature = "exif")]
+pub use cache::ExifData;
 pub use cache::{FileMetadata, MetadataCache};
 pub use config::VirtualTagConfig;
 pub use evaluator::VirtualTagEvaluator;
-pub use parser::ParseError;
+pub use parser::{ParseError, split_negated};
 pub use types::{
-    ExtTypeCategory, GitCondition, PermissionCondition, RangeCondition, SizeCategory,
-    SizeCondition, TimeCondition, VirtualTag,
+    ExifCondition, ExifNumberField, ExifTextField, ExtTypeCategory, GitCondition,
+    PermissionCondition, RangeCondition, SizeCategory, SizeCondition, TimeCondition, VirtualTag,
 };
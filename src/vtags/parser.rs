@@ -1,4 +1,6 @@
 use crate::vtags::config::VirtualTagConfig;
+#[cfg(feature = "media")]
+use crate::vtags::types::{MediaCondition, RangeCondition};
 use crate::vtags::types::{SizeCategory, SizeCondition, VirtualTag};
 use std::path::PathBuf;
 use thiserror::Error;
@@ -19,6 +21,8 @@ pub enum ParseError {
     InvalidRange(String),
     #[error("Invalid pattern: {0}")]
     InvalidPattern(String),
+    #[error("Unsupported virtual tag: {0}")]
+    Unsupported(String),
 }
 
 impl TryFrom<&str> for VirtualTag {
@@ -67,11 +71,53 @@ impl VirtualTag {
             "perm" => Ok(Self::Permission(value.try_into()?)),
             "lines" => Ok(Self::Lines(value.try_into()?)),
             "git" => Ok(Self::Git(value.try_into()?)),
+            #[cfg(feature = "exif")]
+            "exif" => Ok(Self::Exif(value.try_into()?)),
+            #[cfg(not(feature = "exif"))]
+            "exif" => Err(ParseError::Unsupported(
+                "exif tags require building tagr with `--features exif`".to_string(),
+            )),
+            #[cfg(feature = "media")]
+            "duration" => Ok(Self::Media(MediaCondition::Duration(parse_scaled_range(
+                value,
+                parse_duration_ms,
+            )?))),
+            #[cfg(feature = "media")]
+            "bitrate" => Ok(Self::Media(MediaCondition::Bitrate(parse_scaled_range(
+                value,
+                parse_bitrate,
+            )?))),
+            #[cfg(feature = "media")]
+            "codec" if !value.is_empty() => {
+                Ok(Self::Media(MediaCondition::Codec(value.to_lowercase())))
+            }
+            #[cfg(feature = "media")]
+            "codec" => Err(ParseError::InvalidValue(input.to_string())),
+            #[cfg(not(feature = "media"))]
+            "duration" | "bitrate" | "codec" => Err(ParseError::Unsupported(format!(
+                "{prefix} tags require building tagr with `--features media`"
+            ))),
             _ => Err(ParseError::UnknownPrefix(prefix.to_string())),
         }
     }
 }
 
+/// Split virtual tag arguments into tags to match and tags to exclude
+///
+/// A leading `!` negates a tag: `!modified:today` drops files modified today.
+#[must_use]
+pub fn split_negated(vtags: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+    for vtag in vtags {
+        match vtag.strip_prefix('!') {
+            Some(negated) => exclude.push(negated.to_string()),
+            None => include.push(vtag.clone()),
+        }
+    }
+    (include, exclude)
+}
+
 fn parse_size(value: &str, config: &VirtualTagConfig) -> Result<SizeCondition, ParseError> {
     match value {
         "empty" => Ok(SizeCondition::Empty),
@@ -118,6 +164,85 @@ fn parse_size(value: &str, config: &VirtualTagConfig) -> Result<SizeCondition, P
     }
 }
 
+/// Parse `>N`, `<N`, `=N`, `N` or `MIN-MAX`, reading each amount with `parse`
+#[cfg(feature = "media")]
+fn parse_scaled_range(
+    value: &str,
+    parse: fn(&str) -> Option<u64>,
+) -> Result<RangeCondition, ParseError> {
+    let amount =
+        |amount: &str| parse(amount).ok_or_else(|| ParseError::InvalidRange(value.to_string()));
+
+    if let Some(amount_str) = value.strip_prefix('>') {
+        Ok(RangeCondition::GreaterThan(amount(amount_str)?))
+    } else if let Some(amount_str) = value.strip_prefix('<') {
+        Ok(RangeCondition::LessThan(amount(amount_str)?))
+    } else if let Some((min, max)) = value.split_once('-') {
+        Ok(RangeCondition::Range(amount(min)?, amount(max)?))
+    } else {
+        Ok(RangeCondition::Equals(amount(
+            value.strip_prefix('=').unwrap_or(value),
+        )?))
+    }
+}
+
+/// Parse a duration like `90`, `45s`, `5m`, `1.5h` or `1h30m` into milliseconds
+///
+/// A bare number is seconds.
+#[cfg(feature = "media")]
+fn parse_duration_ms(value: &str) -> Option<u64> {
+    if let Ok(secs) = value.parse::<f64>() {
+        return seconds_to_ms(secs);
+    }
+
+    if value.is_empty() {
+        return None;
+    }
+    let mut secs = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let unit_start = rest.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+        let (number, tail) = rest.split_at(unit_start);
+        let number = number.parse::<f64>().ok()?;
+        let unit_end = tail
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_end);
+        secs += number
+            * match unit {
+                "h" => 3600.0,
+                "m" | "min" => 60.0,
+                "s" => 1.0,
+                _ => return None,
+            };
+        rest = tail;
+    }
+    seconds_to_ms(secs)
+}
+
+#[cfg(feature = "media")]
+fn seconds_to_ms(secs: f64) -> Option<u64> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    (secs.is_finite() && secs >= 0.0).then(|| (secs * 1000.0).round() as u64)
+}
+
+/// Parse a bitrate like `320k`, `320kbps`, `1.4m` or `128000` into bits per second
+#[cfg(feature = "media")]
+fn parse_bitrate(value: &str) -> Option<u64> {
+    let value = value.to_lowercase();
+    let value = value.strip_suffix("bps").unwrap_or(&value);
+    let (number, scale) = if let Some(number) = value.strip_suffix('k') {
+        (number, 1_000.0)
+    } else if let Some(number) = value.strip_suffix('m') {
+        (number, 1_000_000.0)
+    } else {
+        (value, 1.0)
+    };
+    let bits = number.parse::<f64>().ok()? * scale;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    (bits.is_finite() && bits >= 0.0).then(|| bits.round() as u64)
+}
+
 fn parse_path_pattern(value: &str) -> Result<glob::Pattern, ParseError> {
     glob::Pattern::new(value).map_err(|_| ParseError::InvalidPattern(value.to_string()))
 }
@@ -131,6 +256,18 @@ mod tests {
     };
     use std::path::Path;
 
+    #[test]
+    fn test_split_negated() {
+        let vtags = vec![
+            "ext:.rs".to_string(),
+            "!modified:today".to_string(),
+            "size:>1MB".to_string(),
+        ];
+        let (include, exclude) = split_negated(&vtags);
+        assert_eq!(include, ["ext:.rs", "size:>1MB"]);
+        assert_eq!(exclude, ["modified:today"]);
+    }
+
     #[test]
     fn test_parse_modified_today() {
         let tag: VirtualTag = "modified:today".try_into().unwrap();
@@ -230,6 +367,117 @@ mod tests {
         assert!(matches!(tag, VirtualTag::Git(GitCondition::Modified)));
     }
 
+    #[cfg(feature = "exif")]
+    #[test]
+    fn test_parse_exif() {
+        use crate::vtags::types::{ExifCondition, ExifNumberField, ExifTextField};
+
+        let tag: VirtualTag = "exif:camera=Canon".try_into().unwrap();
+        assert_eq!(
+            tag,
+            VirtualTag::Exif(ExifCondition::Text(
+                ExifTextField::Camera,
+                "canon".to_string()
+            ))
+        );
+        let tag: VirtualTag = "exif:iso>800".try_into().unwrap();
+        assert_eq!(
+            tag,
+            VirtualTag::Exif(ExifCondition::Number(
+                ExifNumberField::Iso,
+                RangeCondition::GreaterThan(800)
+            ))
+        );
+        let tag: VirtualTag = "exif:focal=24-70".try_into().unwrap();
+        assert_eq!(
+            tag,
+            VirtualTag::Exif(ExifCondition::Number(
+                ExifNumberField::FocalLength,
+                RangeCondition::Range(24, 70)
+            ))
+        );
+        let tag: VirtualTag = "exif:date:2023-06".try_into().unwrap();
+        assert_eq!(tag, VirtualTag::Exif(ExifCondition::Date("2023-06".into())));
+
+        for invalid in [
+            "exif:camera",
+            "exif:camera=",
+            "exif:iso>fast",
+            "exif:date:23",
+            "exif:date:2023-6",
+            "exif:shutter=1",
+        ] {
+            assert!(VirtualTag::try_from(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[cfg(not(feature = "exif"))]
+    #[test]
+    fn test_parse_exif_requires_feature() {
+        let result: Result<VirtualTag, _> = "exif:iso>800".try_into();
+        assert!(matches!(result, Err(ParseError::Unsupported(_))));
+    }
+
+    #[cfg(feature = "media")]
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration_ms("90"), Some(90_000));
+        assert_eq!(parse_duration_ms("45s"), Some(45_000));
+        assert_eq!(parse_duration_ms("5m"), Some(300_000));
+        assert_eq!(parse_duration_ms("1.5h"), Some(5_400_000));
+        assert_eq!(parse_duration_ms("1h30m"), Some(5_400_000));
+        assert_eq!(parse_duration_ms("2m30s"), Some(150_000));
+        for invalid in ["", "m", "5x", "1h30", "-5m", "inf"] {
+            assert_eq!(parse_duration_ms(invalid), None, "{invalid}");
+        }
+    }
+
+    #[cfg(feature = "media")]
+    #[test]
+    fn test_parse_media() {
+        use crate::vtags::types::MediaCondition;
+
+        let tag: VirtualTag = "duration:>5m".try_into().unwrap();
+        assert_eq!(
+            tag,
+            VirtualTag::Media(MediaCondition::Duration(RangeCondition::GreaterThan(
+                300_000
+            )))
+        );
+        let tag: VirtualTag = "duration:30s-2m".try_into().unwrap();
+        assert_eq!(
+            tag,
+            VirtualTag::Media(MediaCondition::Duration(RangeCondition::Range(
+                30_000, 120_000
+            )))
+        );
+        let tag: VirtualTag = "bitrate:>320k".try_into().unwrap();
+        assert_eq!(
+            tag,
+            VirtualTag::Media(MediaCondition::Bitrate(RangeCondition::GreaterThan(
+                320_000
+            )))
+        );
+        let tag: VirtualTag = "bitrate:<1.4Mbps".try_into().unwrap();
+        assert_eq!(
+            tag,
+            VirtualTag::Media(MediaCondition::Bitrate(RangeCondition::LessThan(1_400_000)))
+        );
+        let tag: VirtualTag = "codec:FLAC".try_into().unwrap();
+        assert_eq!(tag, VirtualTag::Media(MediaCondition::Codec("flac".into())));
+
+        for invalid in ["duration:>long", "duration:5m-", "bitrate:>fast", "codec:"] {
+            assert!(VirtualTag::try_from(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[cfg(not(feature = "media"))]
+    #[test]
+    fn test_parse_media_requires_feature() {
+        let result: Result<VirtualTag, _> = "duration:>5m".try_into();
+        assert!(matches!(result, Err(ParseError::Unsupported(_))));
+    }
+
     // Error cases
     #[test]
     fn test_parse_missing_colon() {
@@ -17,6 +17,8 @@ pub enum VirtualTag {
     Permission(PermissionCondition),
     Lines(RangeCondition),
     Git(GitCondition),
+    Exif(ExifCondition),
+    Media(MediaCondition),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -219,6 +221,99 @@ impl TryFrom<&str> for PermissionCondition {
     }
 }
 
+/// Condition on a photo's EXIF metadata (`exif:` virtual tags)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExifCondition {
+    /// Text field containing the value, compared case-insensitively
+    ///
+    /// The value is stored lowercased.
+    Text(ExifTextField, String),
+    /// Numeric field compared like other range conditions
+    Number(ExifNumberField, RangeCondition),
+    /// Capture date starting with `YYYY`, `YYYY-MM` or `YYYY-MM-DD`
+    Date(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExifTextField {
+    /// Make and model together, e.g. "Canon Canon EOS 5D"
+    Camera,
+    Make,
+    Model,
+    Lens,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExifNumberField {
+    Iso,
+    /// Focal length in whole millimetres
+    FocalLength,
+}
+
+impl TryFrom<&str> for ExifCondition {
+    type Error = ParseError;
+
+    /// Parse the part after `exif:`, e.g. `camera=Canon`, `iso>800` or `date:2023`
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let split = value
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .ok_or_else(|| ParseError::InvalidValue(value.to_string()))?;
+        let (field, condition) = value.split_at(split);
+
+        let text = |field| {
+            condition
+                .strip_prefix('=')
+                .filter(|text| !text.is_empty())
+                .map(|text| Self::Text(field, text.to_lowercase()))
+                .ok_or_else(|| ParseError::InvalidValue(value.to_string()))
+        };
+        let number = |field| {
+            let range = condition.strip_prefix('=').unwrap_or(condition);
+            Ok(Self::Number(field, range.try_into()?))
+        };
+
+        match field {
+            "camera" => text(ExifTextField::Camera),
+            "make" => text(ExifTextField::Make),
+            "model" => text(ExifTextField::Model),
+            "lens" => text(ExifTextField::Lens),
+            "iso" => number(ExifNumberField::Iso),
+            "focal" => number(ExifNumberField::FocalLength),
+            "date" => {
+                let date = condition
+                    .strip_prefix([':', '='])
+                    .filter(|date| is_date_prefix(date))
+                    .ok_or_else(|| ParseError::InvalidDate(value.to_string()))?;
+                Ok(Self::Date(date.to_string()))
+            }
+            _ => Err(ParseError::InvalidValue(value.to_string())),
+        }
+    }
+}
+
+/// Whether `value` is `YYYY`, `YYYY-MM` or `YYYY-MM-DD`
+fn is_date_prefix(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    parts.len() <= 3
+        && parts.iter().enumerate().all(|(i, part)| {
+            part.len() == if i == 0 { 4 } else { 2 } && part.bytes().all(|b| b.is_ascii_digit())
+        })
+}
+
+/// Condition on an audio or video file's stream (`duration:`, `bitrate:` and
+/// `codec:` virtual tags)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MediaCondition {
+    /// Duration in milliseconds
+    Duration(RangeCondition),
+    /// Average bitrate in bits per second
+    Bitrate(RangeCondition),
+    /// Codec short name, e.g. `flac`; `pcm` also matches `pcm_s16le`
+    ///
+    /// The value is stored lowercased.
+    Codec(String),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum GitCondition {
     Tracked,
@@ -10,7 +10,11 @@ use tagr::cli::{SearchMode, SearchParams};
 use tagr::commands::bulk::{bulk_tag, bulk_untag};
 use tagr::commands::search as search_cmd;
 use tagr::config;
-use tagr::{Pair, cli::execute_command_on_files, db::Database};
+use tagr::{
+    Pair,
+    cli::{execute_on_all_files, execute_per_file},
+    db::{Database, ReadOnlyDatabase},
+};
 
 /// Test database wrapper that cleans up on drop
 struct TestDb {
@@ -95,14 +99,19 @@ fn test_e2e_bulk_tag_with_glob_file_patterns() {
         tags: vec![],
         tag_mode: SearchMode::All,
         file_patterns: vec!["*.rs".to_string()],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
 
     // Execute bulk tag (normalize should enable glob and match only .rs files)
@@ -110,7 +119,9 @@ fn test_e2e_bulk_tag_with_glob_file_patterns() {
         test_db.db(),
         params,
         &["added".into()],
+        tagr::config::TagNormalization::default(),
         &tagr::cli::ConditionalArgs::default(),
+        /*parallel*/ false,
         /*dry_run*/ false,
         /*yes*/ true,
         /*quiet*/ true,
@@ -152,14 +163,19 @@ fn test_e2e_bulk_untag_with_regex_file_patterns() {
         tags: vec![],
         tag_mode: SearchMode::All,
         file_patterns: vec![".*\\.txt".to_string()],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: true,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
 
     bulk_untag(
@@ -168,6 +184,7 @@ fn test_e2e_bulk_untag_with_regex_file_patterns() {
         &["remove".into()],
         /*remove_all*/ false,
         &tagr::cli::ConditionalArgs::default(),
+        /*parallel*/ false,
         /*dry_run*/ false,
         /*yes*/ true,
         /*quiet*/ true,
@@ -198,21 +215,28 @@ fn test_e2e_search_execute_with_glob_flag() {
         tags: vec![],
         tag_mode: SearchMode::All,
         file_patterns: vec!["*.rs".to_string()],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: true,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
 
     use tagr::commands::search::{ExplicitFlags, FilterConfig, OutputConfig};
 
     let res = search_cmd::execute(
-        db,
+        &ReadOnlyDatabase::from(db.clone()),
         params,
+        None,
+        &[],
         FilterConfig {
             apply: None,
             save: None,
@@ -224,7 +248,17 @@ fn test_e2e_search_execute_with_glob_flag() {
         },
         OutputConfig {
             format: config::PathFormat::Absolute,
+            path_context_max: 8,
             quiet: true,
+            table: false,
+            rank: None,
+            output: tagr::cli::ListFormat::Text,
+            group_by_tag: false,
+            sort: None,
+            page: tagr::output::Page::default(),
+            explain: false,
+            count: false,
+            cache_ttl: 0,
         },
     );
     assert!(res.is_ok());
@@ -513,7 +547,7 @@ fn test_execute_command_on_files() {
         PathBuf::from("exec_test2.txt"),
     ];
 
-    let success_count = execute_command_on_files(&files, "test -f {}", true);
+    let success_count = execute_per_file(&files, "test -f {}", true);
 
     assert_eq!(success_count, 2);
 
@@ -527,13 +561,29 @@ fn test_execute_command_on_files_with_failure() {
 
     let files = vec![PathBuf::from("exec_fail_test.txt")];
 
-    let success_count = execute_command_on_files(&files, "false", true);
+    let success_count = execute_per_file(&files, "false", true);
 
     assert_eq!(success_count, 0);
 
     let _ = fs::remove_file("exec_fail_test.txt");
 }
 
+#[test]
+fn test_execute_on_all_files_runs_once_with_quoted_paths() {
+    let _first = TestFile::create("exec multi 1.txt", "a").unwrap();
+    let _second = TestFile::create("exec_multi_2.txt", "b").unwrap();
+
+    let files = vec![
+        PathBuf::from("exec multi 1.txt"),
+        PathBuf::from("exec_multi_2.txt"),
+    ];
+
+    // Succeeds only if both quoted paths arrive as exactly two arguments
+    assert!(execute_on_all_files(&files, "test $(ls {} | wc -l) -eq 2", true).is_ok());
+    assert!(execute_on_all_files(&files, "test -f {} 2>/dev/null", true).is_err());
+    assert!(execute_on_all_files(&[], "false {}", true).is_ok());
+}
+
 #[test]
 fn test_find_by_all_tags() {
     let test_db = TestDb::new("find_all_tags");
@@ -966,7 +1016,7 @@ fn test_filter_export_and_import() {
     let test_mgr2 = TestFilterManager::new("import_dest");
     let manager2 = test_mgr2.manager();
 
-    let result = manager2.import(export_path, false, false);
+    let result = manager2.import(export_path, false, false, None);
     assert!(result.is_ok());
 
     let filters = manager2.list().unwrap();
@@ -1000,7 +1050,7 @@ fn test_filter_export_selective() {
     // Import to new manager
     let test_mgr2 = TestFilterManager::new("import_selective");
     let manager2 = test_mgr2.manager();
-    manager2.import(export_path, false, false).unwrap();
+    manager2.import(export_path, false, false, None).unwrap();
 
     let filters = manager2.list().unwrap();
     assert_eq!(filters.len(), 2);
@@ -1037,7 +1087,7 @@ fn test_filter_import_conflict_skip() {
     manager2.export(export_path, &[]).unwrap();
 
     // Import with skip-existing
-    let result = manager.import(export_path, false, true);
+    let result = manager.import(export_path, false, true, None);
     assert!(result.is_ok());
 
     // Original should remain unchanged
@@ -1073,7 +1123,7 @@ fn test_filter_import_conflict_overwrite() {
     manager2.export(export_path, &[]).unwrap();
 
     // Import with overwrite
-    let result = manager.import(export_path, true, false);
+    let result = manager.import(export_path, true, false, None);
     assert!(result.is_ok());
 
     // Should be updated
@@ -1170,14 +1220,19 @@ fn test_hierarchy_prefix_matching() {
         tags: vec!["lang".to_string()],
         tag_mode: SearchMode::Any,
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
 
     let results = tagr::db::query::apply_search_params(db, &params).unwrap();
@@ -1210,14 +1265,19 @@ fn test_hierarchy_specificity_exclude_wins() {
         tags: vec!["lang".to_string()],
         tag_mode: SearchMode::Any,
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec!["lang:rust".to_string()],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
 
     let results = tagr::db::query::apply_search_params(db, &params).unwrap();
@@ -1251,14 +1311,19 @@ fn test_hierarchy_cross_hierarchy_exclude() {
         tags: vec!["lang".to_string()],
         tag_mode: SearchMode::Any,
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec!["tests".to_string()],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
 
     let results = tagr::db::query::apply_search_params(db, &params).unwrap();
@@ -1286,14 +1351,19 @@ fn test_hierarchy_deeper_include_overrides_exclude() {
         tags: vec!["lang".to_string(), "lang:rust:async".to_string()],
         tag_mode: SearchMode::Any,
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec!["lang:rust".to_string()],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
 
     let results = tagr::db::query::apply_search_params(db, &params).unwrap();
@@ -1332,14 +1402,19 @@ fn test_hierarchy_all_mode_requires_all_patterns() {
         tags: vec!["lang".to_string(), "project".to_string()],
         tag_mode: SearchMode::All,
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: false,
+        expression: None,
+        no_index: false,
     };
 
     let results = tagr::db::query::apply_search_params(db, &params).unwrap();
@@ -1370,14 +1445,19 @@ fn test_hierarchy_no_hierarchy_flag_disables_prefix_matching() {
         tags: vec!["lang".to_string()],
         tag_mode: SearchMode::Any,
         file_patterns: vec![],
+        file_only_patterns: vec![],
         file_mode: SearchMode::All,
         exclude_tags: vec![],
+        exclude_file_patterns: vec![],
         regex_tag: false,
         regex_file: false,
         glob_files: false,
         virtual_tags: vec![],
         virtual_mode: SearchMode::All,
+        exclude_virtual_tags: vec![],
         no_hierarchy: true,
+        expression: None,
+        no_index: false,
     };
 
     let results = tagr::db::query::apply_search_params(db, &params).unwrap();
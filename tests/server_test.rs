@@ -0,0 +1,271 @@
+//! Integration tests for `tagr serve`, talking HTTP to a server on a free port
+
+#![cfg(feature = "server")]
+
+use serde_json::{Value, json};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use tagr::commands::serve::TagServer;
+use tagr::config::TagNormalization;
+use tagr::db::Database;
+
+/// Headers a local client such as an editor plugin sends
+const LOCAL_HEADERS: &str = "Host: localhost\r\nContent-Type: application/json\r\n";
+
+/// Send one request and return the status code and JSON body
+fn request(addr: SocketAddr, method: &str, target: &str, body: Option<&Value>) -> (u16, Value) {
+    request_with_headers(addr, method, target, LOCAL_HEADERS, body)
+}
+
+/// Like [`request`], with `headers` (each ending in CRLF) instead of the local ones
+fn request_with_headers(
+    addr: SocketAddr,
+    method: &str,
+    target: &str,
+    headers: &str,
+    body: Option<&Value>,
+) -> (u16, Value) {
+    let body = body.map(Value::to_string).unwrap_or_default();
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{method} {target} HTTP/1.1\r\n{headers}Connection: close\r\n\
+         Content-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+/// Percent-encode a path for a query string
+fn encode(path: &std::path::Path) -> String {
+    path.to_str()
+        .unwrap()
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_serve_endpoints() {
+    let dir = tempfile::tempdir().unwrap();
+    let files = dir.path().join("my files");
+    std::fs::create_dir(&files).unwrap();
+    let main = files.join("main.rs");
+    let readme = files.join("README.md");
+    std::fs::write(&main, "").unwrap();
+    std::fs::write(&readme, "").unwrap();
+
+    let db = Database::open(dir.path().join("db")).unwrap();
+    db.insert(&main, vec!["rust".into(), "cli".into()]).unwrap();
+    db.insert(&readme, vec!["docs".into()]).unwrap();
+
+    let server = TagServer::bind(db, "127.0.0.1:0", TagNormalization::default())
+        .unwrap()
+        .with_allow_write(true);
+    let addr = server.local_addr().unwrap();
+    std::thread::spawn(move || server.run());
+
+    let (status, body) = request(addr, "GET", "/files?tag=rust&tag=cli", None);
+    assert_eq!(status, 200);
+    assert_eq!(body, json!([main]));
+
+    let (status, body) = request(addr, "GET", "/files", None);
+    assert_eq!(status, 200);
+    assert_eq!(body.as_array().unwrap().len(), 2);
+
+    let target = format!("/file?path={}", encode(&main));
+    let (status, body) = request(addr, "GET", &target, None);
+    assert_eq!(status, 200);
+    let mut tags: Vec<String> = serde_json::from_value(body["tags"].clone()).unwrap();
+    tags.sort();
+    assert_eq!(tags, ["cli", "rust"]);
+
+    let change = json!({ "path": readme, "add": ["guide"], "remove": ["docs"] });
+    let (status, body) = request(addr, "POST", "/file", Some(&change));
+    assert_eq!(status, 200);
+    assert_eq!(body, json!({ "path": readme, "tags": ["guide"] }));
+    let (_, body) = request(addr, "GET", "/files?tag=guide", None);
+    assert_eq!(body, json!([readme]));
+
+    let missing = json!({ "path": files.join("gone.txt"), "add": ["x"] });
+    assert_eq!(request(addr, "POST", "/file", Some(&missing)).0, 404);
+    let (status, body) = request(addr, "GET", "/file?path=relative.txt", None);
+    assert_eq!(status, 400);
+    assert!(body["error"].as_str().unwrap().contains("absolute"));
+    assert_eq!(request(addr, "DELETE", "/files", None).0, 405);
+    assert_eq!(request(addr, "GET", "/nope", None).0, 404);
+}
+
+#[test]
+fn test_serve_search_and_tags() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = dir.path().join("main.rs");
+    let lib = dir.path().join("lib.go");
+    let notes = dir.path().join("notes.md");
+    for file in [&main, &lib, &notes] {
+        std::fs::write(file, "").unwrap();
+    }
+
+    let db = Database::open(dir.path().join("db")).unwrap();
+    db.insert(&main, vec!["rust".into(), "cli".into()]).unwrap();
+    db.insert(&lib, vec!["go".into(), "cli".into()]).unwrap();
+    db.insert(&notes, vec!["docs".into()]).unwrap();
+
+    let server = TagServer::bind(db, "127.0.0.1:0", TagNormalization::default()).unwrap();
+    let addr = server.local_addr().unwrap();
+    std::thread::spawn(move || server.run());
+
+    let (status, body) = request(addr, "GET", "/search?tag=rust", None);
+    assert_eq!(status, 200);
+    assert_eq!(body[0]["path"], json!(main));
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    // Multiple tags default to AND like the CLI; tag_mode=any switches to OR
+    let (_, body) = request(addr, "GET", "/search?tag=rust&tag=go", None);
+    assert_eq!(body, json!([]));
+    let (_, body) = request(addr, "GET", "/search?tag=rust&tag=go&tag_mode=any", None);
+    assert_eq!(body.as_array().unwrap().len(), 2);
+
+    let (_, body) = request(addr, "GET", "/search?tag=cli&exclude=go", None);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["path"], json!(main));
+
+    // Glob file patterns need glob_files, as with --glob-files
+    let (status, _) = request(addr, "GET", "/search?tag=cli&file=*.go", None);
+    assert_eq!(status, 400);
+    let (_, body) = request(
+        addr,
+        "GET",
+        "/search?tag=cli&file=*.go&glob_files=true",
+        None,
+    );
+    assert_eq!(body[0]["path"], json!(lib));
+
+    assert_eq!(request(addr, "GET", "/search", None).0, 400);
+    assert_eq!(request(addr, "GET", "/search?colour=red", None).0, 400);
+    assert_eq!(
+        request(addr, "GET", "/search?tag=x&tag_mode=some", None).0,
+        400
+    );
+
+    let (status, body) = request(addr, "GET", "/tags", None);
+    assert_eq!(status, 200);
+    assert_eq!(body[0], json!({ "tag": "cli", "count": 2 }));
+    assert_eq!(body.as_array().unwrap().len(), 4);
+
+    // Read-only unless started with --allow-write
+    let change = json!({ "path": notes, "add": ["guide"] });
+    assert_eq!(request(addr, "POST", "/file", Some(&change)).0, 403);
+}
+
+#[test]
+fn test_serve_rejects_cross_site_requests() {
+    let dir = tempfile::tempdir().unwrap();
+    let notes = dir.path().join("notes.md");
+    std::fs::write(&notes, "").unwrap();
+
+    let db = Database::open(dir.path().join("db")).unwrap();
+    db.insert(&notes, vec!["docs".into()]).unwrap();
+
+    let server = TagServer::bind(db, "127.0.0.1:0", TagNormalization::default())
+        .unwrap()
+        .with_allow_write(true);
+    let addr = server.local_addr().unwrap();
+    std::thread::spawn(move || server.run());
+
+    // A rebound DNS name reaches the server with a foreign Host
+    for host in ["Host: evil.example\r\n", "Host: evil.example:7890\r\n", ""] {
+        let (status, body) = request_with_headers(addr, "GET", "/tags", host, None);
+        assert_eq!(status, 403, "{host}");
+        assert!(body["error"].as_str().unwrap().contains("Host"));
+    }
+    for host in [
+        "Host: 127.0.0.1:7890\r\n",
+        "Host: [::1]\r\n",
+        "Host: LOCALHOST\r\n",
+    ] {
+        assert_eq!(
+            request_with_headers(addr, "GET", "/tags", host, None).0,
+            200
+        );
+    }
+
+    // A page's text/plain form post needs no preflight, so it must be refused
+    let change = json!({ "path": notes, "add": ["pwned"] });
+    let plain = "Host: localhost\r\nContent-Type: text/plain\r\n";
+    assert_eq!(
+        request_with_headers(addr, "POST", "/file", plain, Some(&change)).0,
+        415
+    );
+    let foreign = format!("{LOCAL_HEADERS}Origin: https://evil.example\r\n");
+    assert_eq!(
+        request_with_headers(addr, "POST", "/file", &foreign, Some(&change)).0,
+        403
+    );
+    let null = format!("{LOCAL_HEADERS}Origin: null\r\n");
+    assert_eq!(
+        request_with_headers(addr, "GET", "/tags", &null, None).0,
+        403
+    );
+    let (_, body) = request(addr, "GET", "/files?tag=pwned", None);
+    assert_eq!(body, json!([]));
+
+    let local = format!("{LOCAL_HEADERS}Origin: http://localhost:7890\r\n");
+    let json_change = json!({ "path": notes, "add": ["guide"] });
+    let (status, body) = request_with_headers(addr, "POST", "/file", &local, Some(&json_change));
+    assert_eq!(status, 200);
+    assert_eq!(body["tags"], json!(["docs", "guide"]));
+}
+
+#[test]
+fn test_serve_on_all_interfaces_accepts_allowed_hosts() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = Database::open(dir.path().join("db")).unwrap();
+
+    let server = TagServer::bind(db, "0.0.0.0:0", TagNormalization::default())
+        .unwrap()
+        .with_allowed_hosts(vec![
+            "tags.lan".into(),
+            "192.168.1.20".into(),
+            "fd00::20".into(),
+        ]);
+    let port = server.local_addr().unwrap().port();
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    std::thread::spawn(move || server.run());
+
+    // Remote clients send the machine's own name or address as Host
+    for host in [
+        "Host: tags.lan\r\n",
+        "Host: 192.168.1.20:7890\r\n",
+        "Host: [fd00::20]:7890\r\n",
+        "Host: localhost\r\nOrigin: http://tags.lan:7890\r\n",
+    ] {
+        assert_eq!(
+            request_with_headers(addr, "GET", "/tags", host, None).0,
+            200,
+            "{host}"
+        );
+    }
+    for host in [
+        "Host: 192.168.1.21\r\n",
+        "Host: 0.0.0.0\r\n",
+        "Host: localhost\r\nOrigin: http://evil.example\r\n",
+    ] {
+        assert_eq!(
+            request_with_headers(addr, "GET", "/tags", host, None).0,
+            403,
+            "{host}"
+        );
+    }
+}